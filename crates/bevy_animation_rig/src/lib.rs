@@ -4,6 +4,7 @@ use bevy_ecs::{
     schedule::{ParallelSystemDescriptorCoercion, SystemLabel},
     system::IntoSystem,
 };
+use bevy_render::RenderStage;
 use bevy_transform::TransformSystem;
 
 mod skinned_mesh;
@@ -34,6 +35,10 @@ impl Plugin for AnimationRigPlugin {
                     .system()
                     .label(AnimationRigSystem::SkinnedMeshUpdate)
                     .after(TransformSystem::TransformPropagate),
+            )
+            .add_system_to_stage(
+                RenderStage::RenderResource,
+                skinned_mesh_motion_vectors.system(),
             );
     }
 }
@@ -2,6 +2,7 @@ use bevy_asset::{Assets, Handle, HandleUntyped};
 use bevy_ecs::{
     component::Component,
     entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    query::{Added, With},
     reflect::{ReflectComponent, ReflectMapEntities},
     system::{Query, Res, ResMut},
 };
@@ -11,6 +12,7 @@ use bevy_reflect::{
     serde, DynamicStruct, FieldIter, Reflect, ReflectMut, ReflectRef, Struct, TypeUuid,
 };
 use bevy_render::{
+    motion_vectors::{build_motion_vectors_pipeline, MotionVectors},
     pipeline::PipelineDescriptor,
     render_graph::{RenderGraph, RenderResourcesNode},
     renderer::{
@@ -20,6 +22,7 @@ use bevy_render::{
     shader::{Shader, ShaderStage},
     texture::Texture,
 };
+use bevy_tasks::ComputeTaskPool;
 use bevy_transform::components::GlobalTransform;
 
 /// The name of skinned mesh node
@@ -30,12 +33,18 @@ pub mod node {
 /// The name of skinned mesh buffer
 pub mod buffer {
     pub const JOINT_TRANSFORMS: &str = "JointTransforms";
+    pub const PREVIOUS_JOINT_TRANSFORMS: &str = "PreviousJointTransforms";
 }
 
 /// Specify RenderPipelines with this handle to render the skinned mesh.
 pub const SKINNED_MESH_PIPELINE_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 0x14db1922328e7fcc);
 
+/// The pipeline used to draw skinned meshes into the motion vector texture of
+/// [`MotionVectorsPlugin`](bevy_render::motion_vectors::MotionVectorsPlugin).
+pub const SKINNED_MESH_MOTION_VECTORS_PIPELINE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 0x7b0e5a93d24c61f8);
+
 /// Used to update and bind joint transforms to the skinned mesh render pipeline specified with [`SKINNED_MESH_PIPELINE_HANDLE`].
 ///
 /// The length of entities vector passed to [`SkinnedMesh::new()`] should equal to the number of matrices inside [`SkinnedMeshInverseBindposes`].
@@ -68,6 +77,9 @@ pub const SKINNED_MESH_PIPELINE_HANDLE: HandleUntyped =
 pub struct SkinnedMesh {
     pub inverse_bindposes: Handle<SkinnedMeshInverseBindposes>,
     pub joints: Vec<SkinnedMeshJoint>,
+    /// Joint transforms of the previous frame, used to compute the motion of skinned vertices.
+    #[reflect(ignore)]
+    previous_joint_transforms: PreviousJointTransforms,
 }
 
 impl SkinnedMesh {
@@ -84,6 +96,7 @@ impl SkinnedMesh {
                     transform: Mat4::IDENTITY,
                 })
                 .collect(),
+            previous_joint_transforms: Default::default(),
         }
     }
 
@@ -96,10 +109,23 @@ impl SkinnedMesh {
             .get(self.inverse_bindposes.clone())
             .unwrap();
 
+        let previous_joint_transforms = self
+            .joints
+            .iter()
+            .map(|joint| joint.transform)
+            .collect::<Vec<_>>();
         for (joint, &inverse_bindpose) in self.joints.iter_mut().zip(inverse_bindposes.0.iter()) {
             let global_transform = global_transform_query.get(joint.entity).unwrap();
             joint.transform = global_transform.compute_matrix() * inverse_bindpose;
         }
+
+        // the joints haven't moved yet on their first update
+        self.previous_joint_transforms.0 =
+            if self.previous_joint_transforms.0.len() == self.joints.len() {
+                previous_joint_transforms
+            } else {
+                self.joints.iter().map(|joint| joint.transform).collect()
+            };
     }
 }
 
@@ -139,20 +165,28 @@ impl RenderResource for SkinnedMesh {
 
 impl RenderResources for SkinnedMesh {
     fn render_resources_len(&self) -> usize {
-        1
+        2
     }
 
     fn get_render_resource(&self, index: usize) -> Option<&dyn RenderResource> {
-        (index == 0).then(|| self as &dyn RenderResource)
+        match index {
+            0 => Some(self as &dyn RenderResource),
+            1 => Some(&self.previous_joint_transforms as &dyn RenderResource),
+            _ => None,
+        }
     }
 
     fn get_render_resource_name(&self, index: usize) -> Option<&str> {
-        (index == 0).then(|| buffer::JOINT_TRANSFORMS)
+        match index {
+            0 => Some(buffer::JOINT_TRANSFORMS),
+            1 => Some(buffer::PREVIOUS_JOINT_TRANSFORMS),
+            _ => None,
+        }
     }
 
     // Used to tell GLSL to use storage buffer instead of uniform buffer
     fn get_render_resource_hints(&self, index: usize) -> Option<RenderResourceHints> {
-        (index == 0).then(|| RenderResourceHints::BUFFER)
+        (index < 2).then(|| RenderResourceHints::BUFFER)
     }
 
     fn iter(&self) -> RenderResourceIterator {
@@ -160,6 +194,33 @@ impl RenderResources for SkinnedMesh {
     }
 }
 
+#[derive(Debug, Clone, Default)]
+struct PreviousJointTransforms(Vec<Mat4>);
+
+impl RenderResource for PreviousJointTransforms {
+    fn resource_type(&self) -> Option<RenderResourceType> {
+        Some(RenderResourceType::Buffer)
+    }
+
+    fn write_buffer_bytes(&self, buffer: &mut [u8]) {
+        let transform_size = std::mem::size_of::<[f32; 16]>();
+
+        for (index, transform) in self.0.iter().enumerate() {
+            transform.write_buffer_bytes(
+                &mut buffer[index * transform_size..(index + 1) * transform_size],
+            );
+        }
+    }
+
+    fn buffer_byte_len(&self) -> Option<usize> {
+        Some(self.0.len() * std::mem::size_of::<[f32; 16]>())
+    }
+
+    fn texture(&self) -> Option<&Handle<Texture>> {
+        None
+    }
+}
+
 /// Store data for each joint belongs to the [`SkinnedMesh`]
 #[derive(Debug, Clone)]
 pub struct SkinnedMeshJoint {
@@ -346,6 +407,10 @@ pub fn skinned_mesh_setup(
         include_str!("skinned_mesh.vert"),
     ));
     pipelines.set_untracked(SKINNED_MESH_PIPELINE_HANDLE, skinned_mesh_pipeline);
+    pipelines.set_untracked(
+        SKINNED_MESH_MOTION_VECTORS_PIPELINE_HANDLE,
+        build_motion_vectors_pipeline(&mut shaders, include_str!("skinned_motion_vectors.vert")),
+    );
 
     render_graph.add_system_node(
         node::SKINNED_MESH,
@@ -359,15 +424,33 @@ pub fn skinned_mesh_setup(
         .unwrap();
 }
 
+/// The number of skinned meshes updated by each task of [skinned_mesh_update].
+const SKINNED_MESH_UPDATE_BATCH_SIZE: usize = 8;
+
 pub fn skinned_mesh_update(
+    pool: Res<ComputeTaskPool>,
     skinned_mesh_inverse_bindposes_assets: Res<Assets<SkinnedMeshInverseBindposes>>,
     global_transform_query: Query<&GlobalTransform>,
     mut skinned_mesh_query: Query<&mut SkinnedMesh>,
 ) {
-    skinned_mesh_query.for_each_mut(|mut skinned_mesh| {
-        skinned_mesh.update_joint_transforms(
-            &skinned_mesh_inverse_bindposes_assets,
-            &global_transform_query,
-        );
-    });
+    // every skinned mesh reads the transforms of all of its joints, so spread them over the pool
+    skinned_mesh_query.par_for_each_mut(
+        &pool,
+        SKINNED_MESH_UPDATE_BATCH_SIZE,
+        |mut skinned_mesh| {
+            skinned_mesh.update_joint_transforms(
+                &skinned_mesh_inverse_bindposes_assets,
+                &global_transform_query,
+            );
+        },
+    );
+}
+
+/// Draws skinned meshes into the motion vector texture with the skinned motion vector pipeline.
+pub fn skinned_mesh_motion_vectors(
+    mut query: Query<&mut MotionVectors, (With<SkinnedMesh>, Added<MotionVectors>)>,
+) {
+    for mut motion_vectors in query.iter_mut() {
+        motion_vectors.pipeline = SKINNED_MESH_MOTION_VECTORS_PIPELINE_HANDLE.typed();
+    }
 }
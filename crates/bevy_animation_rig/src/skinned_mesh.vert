@@ -10,6 +10,10 @@ layout(location = 4) in uvec4 Vertex_JointIndex;
 layout(location = 5) in vec4 Vertex_Tangent;
 #endif
 
+#ifdef STANDARDMATERIAL_VERTEX_COLORS
+layout(location = 6) in vec4 Vertex_Color;
+#endif
+
 layout(location = 0) out vec3 v_WorldPosition;
 layout(location = 1) out vec3 v_WorldNormal;
 layout(location = 2) out vec2 v_Uv;
@@ -22,6 +26,10 @@ layout(set = 0, binding = 0) uniform CameraViewProj {
 layout(location = 3) out vec4 v_WorldTangent;
 #endif
 
+#ifdef STANDARDMATERIAL_VERTEX_COLORS
+layout(location = 4) out vec4 v_Color;
+#endif
+
 layout(set = 2, binding = 0) buffer JointTransforms {
     mat4[] Joints;
 };
@@ -39,6 +47,9 @@ void main() {
     v_Uv = Vertex_Uv;
 #ifdef STANDARDMATERIAL_NORMAL_MAP
     v_WorldTangent = vec4(mat3(Model) * Vertex_Tangent.xyz, Vertex_Tangent.w);
+#endif
+#ifdef STANDARDMATERIAL_VERTEX_COLORS
+    v_Color = Vertex_Color;
 #endif
     gl_Position = ViewProj * world_position;
 }
@@ -0,0 +1,39 @@
+#version 450
+
+layout(location = 0) in vec3 Vertex_Position;
+layout(location = 3) in vec4 Vertex_JointWeight;
+layout(location = 4) in uvec4 Vertex_JointIndex;
+
+layout(location = 0) out vec4 v_ClipPosition;
+layout(location = 1) out vec4 v_PreviousClipPosition;
+
+layout(set = 0, binding = 0) uniform CameraViewProj {
+    mat4 ViewProj;
+};
+layout(set = 0, binding = 1) uniform CameraPreviousViewProj {
+    mat4 PreviousViewProj;
+};
+
+layout(set = 1, binding = 0) buffer JointTransforms {
+    mat4[] Joints;
+};
+layout(set = 1, binding = 1) buffer PreviousJointTransforms {
+    mat4[] PreviousJoints;
+};
+
+void main() {
+    mat4 Model =
+        Vertex_JointWeight.x * Joints[Vertex_JointIndex.x] +
+        Vertex_JointWeight.y * Joints[Vertex_JointIndex.y] +
+        Vertex_JointWeight.z * Joints[Vertex_JointIndex.z] +
+        Vertex_JointWeight.w * Joints[Vertex_JointIndex.w];
+    mat4 PreviousModel =
+        Vertex_JointWeight.x * PreviousJoints[Vertex_JointIndex.x] +
+        Vertex_JointWeight.y * PreviousJoints[Vertex_JointIndex.y] +
+        Vertex_JointWeight.z * PreviousJoints[Vertex_JointIndex.z] +
+        Vertex_JointWeight.w * PreviousJoints[Vertex_JointIndex.w];
+
+    v_ClipPosition = ViewProj * Model * vec4(Vertex_Position, 1.0);
+    v_PreviousClipPosition = PreviousViewProj * PreviousModel * vec4(Vertex_Position, 1.0);
+    gl_Position = v_ClipPosition;
+}
@@ -1,14 +1,20 @@
-use crate::{CoreStage, Events, Plugin, PluginGroup, PluginGroupBuilder, StartupStage};
+use crate::{
+    AppLabel, CoreStage, EventRetention, Events, Plugin, PluginGroup, PluginGroupBuilder,
+    StartupStage,
+};
 use bevy_ecs::{
+    component::Component,
+    index::{index_maintenance_system, Index},
     prelude::{FromWorld, IntoExclusiveSystem},
+    relation::{relation_maintenance_system, Relation},
     schedule::{
-        IntoSystemDescriptor, RunOnce, Schedule, Stage, StageLabel, State, StateData, SystemSet,
-        SystemStage,
+        IntoSystemDescriptor, RunOnce, Schedule, Stage, StageLabel, State, StateData, SystemLabel,
+        SystemSet, SystemStage,
     },
-    system::Resource,
-    world::World,
+    system::{CommandError, Resource},
+    world::{RemovedValue, World},
 };
-use bevy_utils::tracing::debug;
+use bevy_utils::{tracing::debug, HashMap};
 use std::fmt::Debug;
 
 #[cfg(feature = "trace")]
@@ -42,6 +48,17 @@ pub struct App {
     pub world: World,
     pub runner: Box<dyn Fn(App)>,
     pub schedule: Schedule,
+    sub_apps: HashMap<Box<dyn AppLabel>, SubApp>,
+}
+
+/// Updates a sub-app, given the [`World`] of its parent app.
+type SubAppRunner = Box<dyn Fn(&mut World, &mut App)>;
+
+/// An [`App`] with its own [`World`] and [`Schedule`], updated after its parent app, see
+/// [`App::add_sub_app`].
+struct SubApp {
+    app: App,
+    runner: SubAppRunner,
 }
 
 impl Default for App {
@@ -52,6 +69,7 @@ impl Default for App {
 
         app.add_default_stages()
             .add_event::<AppExit>()
+            .add_event::<CommandError>()
             .add_system_to_stage(CoreStage::Last, World::clear_trackers.exclusive_system());
 
         #[cfg(feature = "bevy_ci_testing")]
@@ -73,6 +91,7 @@ impl App {
             world: Default::default(),
             schedule: Default::default(),
             runner: Box::new(run_once),
+            sub_apps: HashMap::default(),
         }
     }
 
@@ -85,6 +104,9 @@ impl App {
         #[cfg(feature = "trace")]
         let _bevy_frame_update_guard = bevy_frame_update_span.enter();
         self.schedule.run(&mut self.world);
+        for sub_app in self.sub_apps.values_mut() {
+            (sub_app.runner)(&mut self.world, &mut sub_app.app);
+        }
     }
 
     /// Starts the application by calling the app's [runner function](Self::set_runner).
@@ -102,6 +124,17 @@ impl App {
         (runner)(app);
     }
 
+    /// Makes the app run its systems one at a time, in the same order on every run, so that
+    /// lockstep multiplayer games and replays get identical results from identical inputs. See
+    /// [`SystemStage::set_deterministic`].
+    ///
+    /// This only affects the stages that were added before, so it should be called after adding
+    /// plugins.
+    pub fn set_deterministic(&mut self, seed: u64) -> &mut Self {
+        self.schedule.set_deterministic(seed);
+        self
+    }
+
     /// Adds a [`Stage`] with the given `label` to the last position of the app's
     /// [`Schedule`].
     ///
@@ -375,6 +408,34 @@ impl App {
         self
     }
 
+    /// Adds a point labelled `label` to the [`Stage`] identified by `stage_label`, where the
+    /// [`Commands`](bevy_ecs::system::Commands) of the systems ordered before it are applied.
+    /// Systems ordered after it see their effects in the same frame, without needing a new
+    /// stage.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// # let mut app = App::new();
+    /// # fn spawn_enemies() {}
+    /// # fn configure_enemies() {}
+    /// #
+    /// app.add_flush_point_to_stage(CoreStage::Update, "enemies_spawned")
+    ///     .add_system(spawn_enemies.before("enemies_spawned"))
+    ///     .add_system(configure_enemies.after("enemies_spawned"));
+    /// ```
+    pub fn add_flush_point_to_stage(
+        &mut self,
+        stage_label: impl StageLabel,
+        label: impl SystemLabel,
+    ) -> &mut Self {
+        self.schedule.add_flush_point_to_stage(stage_label, label);
+        self
+    }
+
     /// Adds a system to the [startup stage](Self::add_default_stages) of the app's [`Schedule`].
     ///
     /// * For adding a system that runs for every frame, see [`add_system`](Self::add_system).
@@ -532,6 +593,8 @@ impl App {
     ///     - **Post-startup:** Intended for systems that need to run after other startup systems.
     /// - **Pre-update:** Often used by plugins to prepare their internal state before the
     ///   update stage begins.
+    /// - **Fixed update:** Runs at a fixed rate instead of once per update. Intended for
+    ///   simulation logic that needs to be deterministic, like physics.
     /// - **Update:** Intended for user defined logic. Systems are added here by default.
     /// - **Post-update:** Often used by plugins to finalize their internal state after the
     ///   world changes that happened during the update stage.
@@ -558,6 +621,7 @@ impl App {
                     .with_stage(StartupStage::PostStartup, SystemStage::parallel()),
             )
             .add_stage(CoreStage::PreUpdate, SystemStage::parallel())
+            .add_stage(CoreStage::FixedUpdate, SystemStage::parallel())
             .add_stage(CoreStage::Update, SystemStage::parallel())
             .add_stage(CoreStage::PostUpdate, SystemStage::parallel())
             .add_stage(CoreStage::Last, SystemStage::parallel())
@@ -589,6 +653,117 @@ impl App {
             .add_system_to_stage(CoreStage::First, Events::<T>::update_system)
     }
 
+    /// Setup the application to manage events of type `T`, keeping them according to `retention`
+    /// instead of the default of two frames.
+    ///
+    /// See [`App::add_event`] and [`EventRetention`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_app::{prelude::*, EventRetention};
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// # struct MyEvent;
+    /// # let mut app = App::new();
+    /// #
+    /// // events are kept until they are consumed
+    /// app.add_event_with_retention::<MyEvent>(EventRetention::Manual);
+    /// ```
+    pub fn add_event_with_retention<T>(&mut self, retention: EventRetention) -> &mut Self
+    where
+        T: Resource,
+    {
+        self.insert_resource(Events::<T>::with_retention(retention))
+            .add_system_to_stage(CoreStage::First, Events::<T>::update_system)
+    }
+
+    /// Setup the application to maintain the relation `R`.
+    ///
+    /// This inserts a [`relation_maintenance_system`] for `R` into `CoreStage::PostUpdate`, which
+    /// cleans up relations whose target was despawned and keeps the
+    /// [`RelatedBy`](bevy_ecs::relation::RelatedBy) components of the targets up to date.
+    ///
+    /// See [`Relation`] for defining relations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// # #[derive(Component)]
+    /// # struct Targets(Entity);
+    /// # impl Relation for Targets {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.0
+    /// #     }
+    /// # }
+    /// # let mut app = App::new();
+    /// #
+    /// app.add_relation::<Targets>();
+    /// ```
+    pub fn add_relation<R>(&mut self) -> &mut Self
+    where
+        R: Relation,
+    {
+        self.add_system_to_stage(CoreStage::PostUpdate, relation_maintenance_system::<R>)
+    }
+
+    /// Setup the application to maintain an [`Index`] of the values of the component `T`, to look
+    /// up entities by the value of their `T`.
+    ///
+    /// This inserts the [`Index<T>`] resource, and an [`index_maintenance_system`] for `T` into
+    /// `CoreStage::PostUpdate`, which updates the index with the components that changed during
+    /// the frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// # #[derive(Component, Clone, PartialEq, Eq, Hash)]
+    /// # struct NetworkId(u64);
+    /// # let mut app = App::new();
+    /// #
+    /// app.add_index::<NetworkId>();
+    /// ```
+    pub fn add_index<T>(&mut self) -> &mut Self
+    where
+        T: Component + Eq + std::hash::Hash + Clone,
+    {
+        self.init_resource::<Index<T>>()
+            .add_system_to_stage(CoreStage::PostUpdate, index_maintenance_system::<T>)
+    }
+
+    /// Inserts the default value of `R` along with `T`, when `T` is added to an entity without
+    /// an `R`.
+    ///
+    /// See [`World::register_required_components`].
+    pub fn register_required_components<T, R>(&mut self) -> &mut Self
+    where
+        T: Component,
+        R: Component + Default,
+    {
+        self.world.register_required_components::<T, R>();
+        self
+    }
+
+    /// Sends the values of the removed `T` components as [`RemovedValue<T>`] events, which can be
+    /// read with an [`EventReader`](bevy_ecs::event::EventReader), for example to free resources
+    /// owned by the component.
+    ///
+    /// See [`World::store_removed_values`].
+    pub fn store_removed_values<T>(&mut self) -> &mut Self
+    where
+        T: Component + Clone,
+    {
+        self.add_event::<RemovedValue<T>>();
+        self.world.store_removed_values::<T>();
+        self
+    }
+
     /// Inserts a resource to the current [App] and overwrites any resource previously added of the same type.
     ///
     /// A resource in Bevy represents globally unique data. Resources must be added to Bevy Apps
@@ -685,11 +860,7 @@ impl App {
     where
         R: FromWorld + 'static,
     {
-        // See perf comment in init_resource
-        if self.world.get_non_send_resource::<R>().is_none() {
-            let resource = R::from_world(&mut self.world);
-            self.world.insert_non_send(resource);
-        }
+        self.world.init_non_send_resource::<R>();
         self
     }
 
@@ -811,6 +982,70 @@ impl App {
         self
     }
 
+    /// Adds an [`App`] with its own [`World`] and [`Schedule`], for example a simulation running
+    /// at a fixed rate, or a world dedicated to rendering.
+    ///
+    /// The sub-app is updated after each update of this app by calling `runner` with the world
+    /// of this app. The runner is responsible for extracting the data the sub-app needs from that
+    /// world, and for running the sub-app, usually with [`App::update`]. Data can be written back
+    /// to the main world the same way.
+    ///
+    /// The runner of the sub-app itself, set with [`App::set_runner`], isn't used.
+    ///
+    /// ```
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Clone, Copy)]
+    /// struct Score(u32);
+    ///
+    /// let mut simulation = App::empty();
+    /// simulation.add_stage("simulate", SystemStage::single_threaded());
+    ///
+    /// App::new()
+    ///     .insert_resource(Score(0))
+    ///     .add_sub_app("simulation", simulation, |main_world, simulation| {
+    ///         let score = *main_world.get_resource::<Score>().unwrap();
+    ///         simulation.world.insert_resource(score);
+    ///         simulation.update();
+    ///     });
+    /// ```
+    pub fn add_sub_app(
+        &mut self,
+        label: impl AppLabel,
+        app: App,
+        runner: impl Fn(&mut World, &mut App) + 'static,
+    ) -> &mut Self {
+        self.sub_apps.insert(
+            Box::new(label),
+            SubApp {
+                app,
+                runner: Box::new(runner),
+            },
+        );
+        self
+    }
+
+    /// Retrieves a sub-app added with [`App::add_sub_app`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if no sub-app was added with `label`.
+    pub fn sub_app(&mut self, label: impl AppLabel) -> &mut App {
+        match self.get_sub_app(label) {
+            Ok(app) => app,
+            Err(label) => panic!("Sub-App with label '{:?}' does not exist", label),
+        }
+    }
+
+    /// Retrieves a sub-app added with [`App::add_sub_app`], or returns the label back if no
+    /// sub-app was added with it.
+    pub fn get_sub_app(&mut self, label: impl AppLabel) -> Result<&mut App, impl AppLabel> {
+        self.sub_apps
+            .get_mut((&label) as &dyn AppLabel)
+            .map(|sub_app| &mut sub_app.app)
+            .ok_or(label)
+    }
+
     /// Adds the type `T` to the type registry resource.
     #[cfg(feature = "bevy_reflect")]
     pub fn register_type<T: bevy_reflect::GetTypeRegistration>(&mut self) -> &mut Self {
@@ -832,3 +1067,53 @@ fn run_once(mut app: App) {
 /// An event that indicates the app should exit. This will fully exit the app process.
 #[derive(Debug, Clone)]
 pub struct AppExit;
+
+#[cfg(test)]
+mod tests {
+    use crate::App;
+    use bevy_ecs::{
+        schedule::SystemStage,
+        system::{IntoSystem, Res, ResMut},
+    };
+
+    #[derive(Clone, Copy)]
+    struct Score(u32);
+
+    struct Simulated(Vec<u32>);
+
+    #[test]
+    fn update_sub_app() {
+        fn increase_score(mut score: ResMut<Score>) {
+            score.0 += 1;
+        }
+
+        fn simulate(score: Res<Score>, mut simulated: ResMut<Simulated>) {
+            simulated.0.push(score.0);
+        }
+
+        let mut simulation = App::empty();
+        simulation
+            .insert_resource(Simulated(Vec::new()))
+            .add_stage("simulate", SystemStage::single_threaded())
+            .add_system_to_stage("simulate", simulate.system());
+
+        let mut app = App::empty();
+        app.insert_resource(Score(0))
+            .add_stage("update", SystemStage::single_threaded())
+            .add_system_to_stage("update", increase_score.system())
+            .add_sub_app("simulation", simulation, |main_world, simulation| {
+                let score = *main_world.get_resource::<Score>().unwrap();
+                simulation.world.insert_resource(score);
+                simulation.update();
+            });
+
+        app.update();
+        app.update();
+        // the sub-app runs after the main app, with the score extracted from its world
+        let simulation = app.sub_app("simulation");
+        assert_eq!(
+            simulation.world.get_resource::<Simulated>().unwrap().0,
+            vec![1, 2]
+        );
+    }
+}
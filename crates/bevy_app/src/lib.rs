@@ -10,7 +10,7 @@ mod schedule_runner;
 mod ci_testing;
 
 pub use app::*;
-pub use bevy_derive::DynamicPlugin;
+pub use bevy_derive::{AppLabel, DynamicPlugin};
 pub use bevy_ecs::event::*;
 pub use plugin::*;
 pub use plugin_group::*;
@@ -21,7 +21,43 @@ pub mod prelude {
     pub use crate::{app::App, CoreStage, DynamicPlugin, Plugin, PluginGroup, StartupStage};
 }
 
-use bevy_ecs::schedule::StageLabel;
+use bevy_ecs::schedule::{DynHash, StageLabel};
+use std::{
+    fmt::Debug,
+    hash::{Hash, Hasher},
+};
+
+/// A label identifying a sub-app, see [`App::add_sub_app`].
+pub trait AppLabel: DynHash + Debug + Send + Sync + 'static {
+    #[doc(hidden)]
+    fn dyn_clone(&self) -> Box<dyn AppLabel>;
+}
+
+impl PartialEq for dyn AppLabel {
+    fn eq(&self, other: &Self) -> bool {
+        self.dyn_eq(other.as_dyn_eq())
+    }
+}
+
+impl Eq for dyn AppLabel {}
+
+impl Hash for dyn AppLabel {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.dyn_hash(state);
+    }
+}
+
+impl Clone for Box<dyn AppLabel> {
+    fn clone(&self) -> Self {
+        self.dyn_clone()
+    }
+}
+
+impl AppLabel for &'static str {
+    fn dyn_clone(&self) -> Box<dyn AppLabel> {
+        Box::new(<&str>::clone(self))
+    }
+}
 
 /// The names of the default App stages
 ///
@@ -37,6 +73,12 @@ pub enum CoreStage {
     First,
     /// Name of app stage responsible for performing setup before an update. Runs before UPDATE.
     PreUpdate,
+    /// Name of app stage that runs at a fixed rate, independent of the frame rate. Depending on
+    /// the length of a frame it runs zero, one or several times per update. Runs before UPDATE.
+    ///
+    /// The rate is configured by the `FixedUpdateSettings` of `bevy_core`, the stage runs every
+    /// update if the `CorePlugin` isn't added.
+    FixedUpdate,
     /// Name of app stage responsible for doing most app logic. Systems should be registered here
     /// by default.
     Update,
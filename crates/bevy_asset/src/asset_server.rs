@@ -1,17 +1,29 @@
 use crate::{
-    path::{AssetPath, AssetPathId, SourcePathId},
+    import_settings_path,
+    path::{get_hasher, AssetPath, AssetPathId, SourcePathId},
     Asset, AssetIo, AssetIoError, AssetLifecycle, AssetLifecycleChannel, AssetLifecycleEvent,
-    AssetLoader, Assets, Handle, HandleId, HandleUntyped, LabelId, LoadContext, LoadState,
-    RefChange, RefChangeChannel, SourceInfo, SourceMeta,
+    AssetLoader, AssetPathPattern, AssetProcessor, AssetSaver, Assets, Handle, HandleId,
+    HandleUntyped, LabelId, LabeledAsset, LoadContext, LoadFolderSettings, LoadState, LoadedFolder,
+    RefChange, RefChangeChannel, SourceInfo, SourceMeta, WatchSettings,
 };
 use anyhow::Result;
-use bevy_ecs::system::{Res, ResMut};
+use bevy_ecs::{
+    event::EventWriter,
+    system::{Res, ResMut},
+};
 use bevy_log::warn;
+use bevy_reflect::TypeUuid;
 use bevy_tasks::TaskPool;
-use bevy_utils::{HashMap, Uuid};
+use bevy_utils::{HashMap, HashSet, Uuid};
 use crossbeam_channel::TryRecvError;
 use parking_lot::{Mutex, RwLock};
-use std::{collections::hash_map::Entry, path::Path, sync::Arc};
+use std::{
+    any::Any,
+    collections::hash_map::Entry,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use thiserror::Error;
 
 /// Errors that occur while loading assets with an AssetServer
@@ -25,8 +37,24 @@ pub enum AssetServerError {
     IncorrectHandleType,
     #[error("encountered an error while loading an asset: {0}")]
     AssetLoaderError(anyhow::Error),
+    #[error("encountered an error while processing an asset: {0}")]
+    AssetProcessorError(anyhow::Error),
+    #[error("no `AssetSaver` found{}", format_missing_asset_ext(.extensions))]
+    MissingAssetSaver { extensions: Vec<String> },
+    #[error("encountered an error while saving an asset: {0}")]
+    AssetSaverError(anyhow::Error),
     #[error("encountered an error while reading an asset: {0}")]
     AssetIoError(#[from] AssetIoError),
+    #[error("the asset was not loaded from a path")]
+    AssetNotLoadedFromPath,
+}
+
+/// Sent when an asset fails to load. If the asset type has a fallback asset, see
+/// [`AssetServer::set_fallback_asset`], the fallback is used in its place.
+#[derive(Debug)]
+pub struct AssetLoadError {
+    pub path: AssetPath<'static>,
+    pub error: AssetServerError,
 }
 
 fn format_missing_asset_ext(exts: &[String]) -> String {
@@ -41,6 +69,23 @@ fn format_missing_asset_ext(exts: &[String]) -> String {
     }
 }
 
+/// When the assets of a type are freed once no strong [`Handle`] to them is left, see
+/// [`AssetServer::set_free_policy`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FreePolicy {
+    /// Assets are freed on the frame after their last strong handle is dropped
+    #[default]
+    Immediate,
+    /// Assets are freed once they stayed unused for this many frames, so that assets that are
+    /// used again shortly after, like the sounds of a game, stay loaded
+    Delayed(u32),
+    /// Assets are never freed, they stay loaded even without strong handles
+    Never,
+}
+
+/// Creates the fallback asset of a type
+struct FallbackAsset<T>(Box<dyn Fn() -> T + Send + Sync>);
+
 #[derive(Default)]
 pub(crate) struct AssetRefCounter {
     pub(crate) channel: Arc<RefChangeChannel>,
@@ -55,6 +100,22 @@ pub struct AssetServerInternal {
     pub(crate) asset_lifecycles: Arc<RwLock<HashMap<Uuid, Box<dyn AssetLifecycle>>>>,
     loaders: RwLock<Vec<Arc<dyn AssetLoader>>>,
     extension_to_loader_index: RwLock<HashMap<String, usize>>,
+    extension_to_processor: RwLock<HashMap<String, Arc<dyn AssetProcessor>>>,
+    processed_asset_cache: RwLock<Option<PathBuf>>,
+    pub(crate) watch_settings: RwLock<WatchSettings>,
+    free_policies: RwLock<HashMap<Uuid, FreePolicy>>,
+    /// The unused assets of types with a [`FreePolicy::Delayed`], with the frames left before they
+    /// are freed
+    delayed_frees: Mutex<Vec<(HandleId, Uuid, u32)>>,
+    /// The `FallbackAsset<T>`s by asset type
+    fallback_assets: RwLock<HashMap<Uuid, Box<dyn Any + Send + Sync>>>,
+    /// The types of the assets that have a typed handle, for the types with a fallback asset
+    fallback_types: RwLock<HashMap<HandleId, Uuid>>,
+    /// The assets that failed to load, with their type, to replace with their fallback asset
+    failed_assets: Mutex<Vec<(HandleId, Uuid)>>,
+    load_errors: Mutex<Vec<AssetLoadError>>,
+    /// The `Arc<dyn AssetSaver<Asset = T>>`s by asset type and extension
+    savers: RwLock<HashMap<(Uuid, String), Box<dyn Any + Send + Sync>>>,
     handle_to_path: Arc<RwLock<HashMap<HandleId, AssetPath<'static>>>>,
     task_pool: TaskPool,
 }
@@ -82,6 +143,16 @@ impl AssetServer {
             server: Arc::new(AssetServerInternal {
                 loaders: Default::default(),
                 extension_to_loader_index: Default::default(),
+                extension_to_processor: Default::default(),
+                processed_asset_cache: Default::default(),
+                watch_settings: Default::default(),
+                free_policies: Default::default(),
+                delayed_frees: Default::default(),
+                fallback_assets: Default::default(),
+                fallback_types: Default::default(),
+                failed_assets: Default::default(),
+                load_errors: Default::default(),
+                savers: Default::default(),
                 asset_sources: Default::default(),
                 asset_ref_counter: Default::default(),
                 handle_to_path: Default::default(),
@@ -115,14 +186,117 @@ impl AssetServer {
         loaders.push(Arc::new(loader));
     }
 
+    /// Adds a processor for the sources with one of its extensions, replacing the processor
+    /// previously added for the extension.
+    pub fn add_processor<T>(&self, processor: T)
+    where
+        T: AssetProcessor,
+    {
+        let processor = Arc::new(processor);
+        let mut extension_to_processor = self.server.extension_to_processor.write();
+        for extension in processor.extensions().iter() {
+            extension_to_processor.insert(extension.to_string(), processor.clone());
+        }
+    }
+
+    /// Sets the folder where the outputs of [`AssetProcessor`]s are cached. Sources are
+    /// processed every time they're loaded if there is no cache.
+    pub fn set_processed_asset_cache(&self, path: Option<PathBuf>) {
+        *self.server.processed_asset_cache.write() = path;
+    }
+
     pub fn watch_for_changes(&self) -> Result<(), AssetServerError> {
         self.server.asset_io.watch_for_changes()?;
         Ok(())
     }
 
+    /// Watches the assets loaded from now on for changes, limited to the folders and extensions of
+    /// the settings.
+    pub fn watch_for_changes_with(&self, settings: WatchSettings) -> Result<(), AssetServerError> {
+        *self.server.watch_settings.write() = settings;
+        self.watch_for_changes()
+    }
+
+    /// The [`AssetIo`] assets are loaded with. It can be downcast to its concrete type, like a
+    /// [`LayeredAssetIo`](crate::LayeredAssetIo) to mount layers at runtime.
+    pub fn asset_io(&self) -> &dyn AssetIo {
+        &*self.server.asset_io
+    }
+
+    pub fn watch_settings(&self) -> WatchSettings {
+        self.server.watch_settings.read().clone()
+    }
+
+    fn watch_path_for_changes(&self, path: &Path) {
+        if self.server.watch_settings.read().matches(path) {
+            self.server.asset_io.watch_path_for_changes(path).unwrap();
+        }
+    }
+
+    /// Reloads the source of the asset, like hot reloading does when the source changes.
+    pub fn reload_asset<H: Into<HandleId>>(&self, handle: H) -> Result<(), AssetServerError> {
+        let path = match handle.into() {
+            HandleId::AssetPathId(id) => self
+                .server
+                .asset_sources
+                .read()
+                .get(&id.source_path_id())
+                .map(|info| info.path.clone()),
+            HandleId::Id(..) => None,
+        };
+        let path = path.ok_or(AssetServerError::AssetNotLoadedFromPath)?;
+        let _ = self.load_untracked(path.as_path().into(), true);
+        Ok(())
+    }
+
     pub fn get_handle<T: Asset, I: Into<HandleId>>(&self, id: I) -> Handle<T> {
+        let id = id.into();
+        self.track_fallback_type::<T>(id);
         let sender = self.server.asset_ref_counter.channel.sender.clone();
-        Handle::strong(id.into(), sender)
+        Handle::strong(id, sender)
+    }
+
+    /// Sets the asset used in place of the assets of type `T` that fail to load, like a magenta
+    /// texture, so that a bad file doesn't leave invisible meshes. Only the assets loaded with a
+    /// typed handle, like with [`AssetServer::load`], are replaced. An asset that fails to reload
+    /// keeps its previous version.
+    pub fn set_fallback_asset<T: Asset + Clone>(&self, asset: T) {
+        self.server.fallback_assets.write().insert(
+            T::TYPE_UUID,
+            Box::new(FallbackAsset::<T>(Box::new(move || asset.clone()))),
+        );
+    }
+
+    pub fn remove_fallback_asset<T: Asset>(&self) {
+        self.server.fallback_assets.write().remove(&T::TYPE_UUID);
+    }
+
+    pub fn has_fallback_asset<T: Asset>(&self) -> bool {
+        self.server
+            .fallback_assets
+            .read()
+            .contains_key(&T::TYPE_UUID)
+    }
+
+    fn track_fallback_type<T: Asset>(&self, id: HandleId) {
+        if matches!(id, HandleId::AssetPathId(_)) && self.has_fallback_asset::<T>() {
+            self.server.fallback_types.write().insert(id, T::TYPE_UUID);
+        }
+    }
+
+    /// Queues the assets of a source that failed to load to be replaced by their fallback asset.
+    fn queue_fallback_assets(&self, source_path_id: SourcePathId) {
+        let failed = self
+            .server
+            .fallback_types
+            .read()
+            .iter()
+            .filter(|(id, _)| {
+                matches!(id, HandleId::AssetPathId(id) if id.source_path_id() == source_path_id)
+            })
+            .map(|(id, type_uuid)| (*id, *type_uuid))
+            .collect::<Vec<_>>();
+        self.server.failed_assets.lock().extend(failed);
     }
 
     pub fn get_handle_untyped<I: Into<HandleId>>(&self, id: I) -> HandleUntyped {
@@ -173,6 +347,177 @@ impl AssetServer {
         })
     }
 
+    /// Adds a saver for the assets of its type, replacing the saver previously added for the type
+    /// and one of its extensions.
+    pub fn add_saver<T>(&self, saver: T)
+    where
+        T: AssetSaver,
+    {
+        let saver: Arc<dyn AssetSaver<Asset = T::Asset>> = Arc::new(saver);
+        let mut savers = self.server.savers.write();
+        for extension in saver.extensions().iter() {
+            savers.insert(
+                (<T::Asset as TypeUuid>::TYPE_UUID, extension.to_string()),
+                Box::new(saver.clone()),
+            );
+        }
+    }
+
+    fn get_path_asset_saver<T: Asset>(
+        &self,
+        path: &Path,
+    ) -> Result<Arc<dyn AssetSaver<Asset = T>>, AssetServerError> {
+        let file_name = path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .map(|file_name| file_name.to_lowercase())
+            .unwrap_or_default();
+        let savers = self.server.savers.read();
+        let mut exts = Vec::new();
+        let mut ext = file_name.as_str();
+        while let Some(idx) = ext.find('.') {
+            ext = &ext[idx + 1..];
+            exts.push(ext.to_string());
+            if let Some(saver) = savers
+                .get(&(T::TYPE_UUID, ext.to_string()))
+                .and_then(|saver| saver.downcast_ref::<Arc<dyn AssetSaver<Asset = T>>>())
+            {
+                return Ok(saver.clone());
+            }
+        }
+        Err(AssetServerError::MissingAssetSaver { extensions: exts })
+    }
+
+    fn serialize_asset<T: Asset>(
+        &self,
+        asset: &T,
+        path: &Path,
+    ) -> Result<Vec<u8>, AssetServerError> {
+        self.get_path_asset_saver::<T>(path)?
+            .save(asset)
+            .map_err(AssetServerError::AssetSaverError)
+    }
+
+    /// Saves the asset of the handle to `path`, with the [`AssetSaver`] for its type and the
+    /// extension of the path. The asset is saved in the background once the [`Assets`] collection
+    /// of its type is updated, errors while saving it are logged.
+    pub fn save<T: Asset, P: AsRef<Path>>(
+        &self,
+        handle: &Handle<T>,
+        path: P,
+    ) -> Result<(), AssetServerError> {
+        let path = path.as_ref();
+        self.get_path_asset_saver::<T>(path)?;
+        let asset_lifecycles = self.server.asset_lifecycles.read();
+        let channel = asset_lifecycles
+            .get(&T::TYPE_UUID)
+            .and_then(|asset_lifecycle| asset_lifecycle.downcast_ref::<AssetLifecycleChannel<T>>())
+            .ok_or(AssetServerError::IncorrectHandleType)?;
+        channel
+            .sender
+            .send(AssetLifecycleEvent::Save(handle.id, path.to_owned()))
+            .unwrap();
+        Ok(())
+    }
+
+    /// Saves `asset` to `path`, with the [`AssetSaver`] for its type and the extension of the
+    /// path, for tools that have the asset at hand.
+    pub async fn save_async<T: Asset>(
+        &self,
+        asset: &T,
+        path: &Path,
+    ) -> Result<(), AssetServerError> {
+        let bytes = self.serialize_asset(asset, path)?;
+        self.server.asset_io.save_path(path, &bytes).await?;
+        Ok(())
+    }
+
+    fn save_in_background<T: Asset>(&self, asset: Option<&T>, path: PathBuf) {
+        let bytes = match asset {
+            Some(asset) => self.serialize_asset(asset, &path),
+            None => {
+                warn!("failed to save {}: the asset doesn't exist", path.display());
+                return;
+            }
+        };
+        let bytes = match bytes {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                warn!("failed to save {}: {}", path.display(), err);
+                return;
+            }
+        };
+        let server = self.clone();
+        self.server
+            .task_pool
+            .spawn(async move {
+                if let Err(err) = server.server.asset_io.save_path(&path, &bytes).await {
+                    warn!("failed to save {}: {}", path.display(), err);
+                }
+            })
+            .detach();
+    }
+
+    fn get_path_asset_processor(&self, path: &Path) -> Option<Arc<dyn AssetProcessor>> {
+        let extension_to_processor = self.server.extension_to_processor.read();
+        if extension_to_processor.is_empty() {
+            return None;
+        }
+        let file_name = path.file_name()?.to_str()?.to_lowercase();
+        let mut ext = file_name.as_str();
+        while let Some(idx) = ext.find('.') {
+            ext = &ext[idx + 1..];
+            if let Some(processor) = extension_to_processor.get(ext) {
+                return Some(processor.clone());
+            }
+        }
+        None
+    }
+
+    /// Runs the [`AssetProcessor`] of the source at `path` on its bytes, if there is one. The
+    /// cached output is used if the source was already processed by the same processor.
+    async fn process_asset(
+        &self,
+        path: &Path,
+        bytes: Vec<u8>,
+    ) -> Result<Vec<u8>, AssetServerError> {
+        let processor = match self.get_path_asset_processor(path) {
+            Some(processor) => processor,
+            None => return Ok(bytes),
+        };
+        let cache_path = self
+            .server
+            .processed_asset_cache
+            .read()
+            .as_ref()
+            .map(|cache| {
+                let mut hasher = get_hasher();
+                processor.name().hash(&mut hasher);
+                processor.version().hash(&mut hasher);
+                bytes.hash(&mut hasher);
+                cache.join(format!("{:016x}", hasher.finish()))
+            });
+        if let Some(processed) = cache_path
+            .as_ref()
+            .and_then(|cache_path| std::fs::read(cache_path).ok())
+        {
+            return Ok(processed);
+        }
+
+        let processed = processor
+            .process(&bytes, path)
+            .await
+            .map_err(AssetServerError::AssetProcessorError)?;
+        if let Some(cache_path) = cache_path {
+            let result = std::fs::create_dir_all(cache_path.parent().unwrap())
+                .and_then(|_| std::fs::write(&cache_path, &processed));
+            if let Err(err) = result {
+                warn!("failed to cache the processed asset {:?}: {}", path, err);
+            }
+        }
+        Ok(processed)
+    }
+
     pub fn get_handle_path<H: Into<HandleId>>(&self, handle: H) -> Option<AssetPath<'_>> {
         self.server
             .handle_to_path
@@ -181,6 +526,75 @@ impl AssetServer {
             .cloned()
     }
 
+    /// The paths of the sources whose loader read `path`, directly or through other sources. They
+    /// are reloaded when `path` changes on disk, see [`LoadContext::read_asset_bytes`].
+    pub fn get_source_dependants<P: AsRef<Path>>(&self, path: P) -> Vec<PathBuf> {
+        let asset_sources = self.server.asset_sources.read();
+        let mut visited = HashSet::default();
+        visited.insert(path.as_ref().to_owned());
+        let mut queue = vec![path.as_ref().to_owned()];
+        let mut dependants = Vec::new();
+        while let Some(path) = queue.pop() {
+            for info in asset_sources.values() {
+                if info.source_dependencies.contains(&path) && visited.insert(info.path.clone()) {
+                    dependants.push(info.path.clone());
+                    queue.push(info.path.clone());
+                }
+            }
+        }
+        dependants
+    }
+
+    /// The assets the source at `path` produced when it was loaded, like the meshes, materials and
+    /// scenes of a glTF file, sorted by label with the default asset first. This is empty until
+    /// the source is loaded.
+    /// The path of the asset. Unlike [`AssetServer::get_handle_path`], it also finds the paths of
+    /// the labeled assets of loaded sources that weren't loaded by path.
+    pub fn get_asset_path<H: Into<HandleId>>(&self, handle: H) -> Option<AssetPath<'static>> {
+        let handle_id = handle.into();
+        if let Some(path) = self.server.handle_to_path.read().get(&handle_id) {
+            return Some(path.clone());
+        }
+        let id = match handle_id {
+            HandleId::AssetPathId(id) => id,
+            HandleId::Id(..) => return None,
+        };
+        let asset_sources = self.server.asset_sources.read();
+        let info = asset_sources.get(&id.source_path_id())?;
+        info.meta
+            .as_ref()?
+            .assets
+            .iter()
+            .find(|asset| LabelId::from(asset.label.as_deref()) == id.label_id())
+            .map(|asset| AssetPath::new(info.path.clone(), asset.label.clone()))
+    }
+
+    pub fn get_labeled_assets<P: AsRef<Path>>(&self, path: P) -> Vec<LabeledAsset> {
+        let path = path.as_ref();
+        let asset_sources = self.server.asset_sources.read();
+        let meta = match asset_sources
+            .get(&SourcePathId::from(path))
+            .and_then(|info| info.meta.as_ref())
+        {
+            Some(meta) => meta,
+            None => return Vec::new(),
+        };
+        let mut labeled_assets = meta
+            .assets
+            .iter()
+            .map(|asset| {
+                let asset_path = AssetPath::new(path.to_owned(), asset.label.clone());
+                LabeledAsset {
+                    handle: HandleUntyped::weak(asset_path.get_id().into()),
+                    path: asset_path,
+                    type_uuid: asset.type_uuid,
+                }
+            })
+            .collect::<Vec<_>>();
+        labeled_assets.sort_by(|a, b| a.label().cmp(&b.label()));
+        labeled_assets
+    }
+
     pub fn get_load_state<H: Into<HandleId>>(&self, handle: H) -> LoadState {
         match handle.into() {
             HandleId::AssetPathId(id) => {
@@ -228,6 +642,9 @@ impl AssetServer {
     /// `"assets"`.
     #[must_use = "not using the returned strong handle may result in the unexpected release of the asset"]
     pub fn load<'a, T: Asset, P: Into<AssetPath<'a>>>(&self, path: P) -> Handle<T> {
+        let path = path.into();
+        // tracked before loading, so that the type is known if the asset fails to load right away
+        self.track_fallback_type::<T>(path.get_id().into());
         self.load_untyped(path).typed()
     }
 
@@ -251,6 +668,7 @@ impl AssetServer {
                     meta: None,
                     path: asset_path.path().to_owned(),
                     version: 0,
+                    source_dependencies: Vec::new(),
                 }),
             };
 
@@ -277,6 +695,8 @@ impl AssetServer {
                 .get_mut(&asset_path_id.source_path_id())
                 .expect("`AssetSource` should exist at this point.");
             source_info.load_state = LoadState::Failed;
+            drop(asset_sources);
+            self.queue_fallback_assets(asset_path_id.source_path_id());
         };
 
         // get the according asset loader
@@ -288,12 +708,28 @@ impl AssetServer {
             }
         };
 
-        // load the asset bytes
-        let bytes = match self.server.asset_io.load_path(asset_path.path()).await {
-            Ok(bytes) => bytes,
-            Err(err) => {
-                set_asset_failed();
-                return Err(AssetServerError::AssetIoError(err));
+        // streaming loaders read the asset themselves, unless it has to be processed first
+        let stream = asset_loader.supports_streaming()
+            && self.get_path_asset_processor(asset_path.path()).is_none();
+        let bytes = if stream {
+            None
+        } else {
+            // load the asset bytes
+            let bytes = match self.server.asset_io.load_path(asset_path.path()).await {
+                Ok(bytes) => bytes,
+                Err(err) => {
+                    set_asset_failed();
+                    return Err(AssetServerError::AssetIoError(err));
+                }
+            };
+
+            // process the asset bytes, if the asset has a processor
+            match self.process_asset(asset_path.path(), bytes).await {
+                Ok(bytes) => Some(bytes),
+                Err(err) => {
+                    set_asset_failed();
+                    return Err(err);
+                }
             }
         };
 
@@ -302,15 +738,41 @@ impl AssetServer {
             asset_path.path(),
             &self.server.asset_ref_counter.channel,
             &*self.server.asset_io,
+            &self.server.asset_lifecycles,
             version,
             &self.server.task_pool,
         );
 
-        if let Err(err) = asset_loader
-            .load(&bytes, &mut load_context)
-            .await
-            .map_err(AssetServerError::AssetLoaderError)
-        {
+        // read the import settings of the asset, they reload the asset when they change
+        let import_settings_path = import_settings_path(asset_path.path());
+        match self.server.asset_io.load_path(&import_settings_path).await {
+            Ok(import_settings) => {
+                load_context.import_settings = Some(import_settings);
+                load_context.add_source_dependency(import_settings_path);
+            }
+            Err(AssetIoError::NotFound(_)) => {}
+            Err(err) => warn!(
+                "failed to read the import settings of {}: {}",
+                asset_path.path().display(),
+                err
+            ),
+        }
+
+        let result = match &bytes {
+            Some(bytes) => asset_loader.load(bytes, &mut load_context).await,
+            None => match self.server.asset_io.read_path(asset_path.path()).await {
+                Ok(mut reader) => {
+                    asset_loader
+                        .load_streaming(&mut *reader, &mut load_context)
+                        .await
+                }
+                Err(err) => {
+                    set_asset_failed();
+                    return Err(AssetServerError::AssetIoError(err));
+                }
+            },
+        };
+        if let Err(err) = result.map_err(AssetServerError::AssetLoaderError) {
             set_asset_failed();
             return Err(err);
         }
@@ -339,6 +801,14 @@ impl AssetServer {
             assets: load_context.get_asset_metas(),
         });
 
+        let mut source_dependencies = std::mem::take(load_context.source_dependencies.get_mut());
+        source_dependencies.sort();
+        source_dependencies.dedup();
+        for path in source_dependencies.iter() {
+            self.watch_path_for_changes(path);
+        }
+        source_info.source_dependencies = source_dependencies;
+
         // load asset dependencies and prepare asset type hashmap
         for (label, loaded_asset) in load_context.labeled_assets.iter_mut() {
             let label_id = LabelId::from(label.as_ref().map(|label| label.as_str()));
@@ -349,10 +819,7 @@ impl AssetServer {
             }
         }
 
-        self.server
-            .asset_io
-            .watch_path_for_changes(asset_path.path())
-            .unwrap();
+        self.watch_path_for_changes(asset_path.path());
         self.create_assets_in_load_context(&mut load_context);
         Ok(asset_path_id)
     }
@@ -369,8 +836,14 @@ impl AssetServer {
         self.server
             .task_pool
             .spawn(async move {
-                if let Err(err) = server.load_async(owned_path, force).await {
-                    warn!("{}", err);
+                let path = owned_path.clone();
+                if let Err(error) = server.load_async(owned_path, force).await {
+                    warn!("{}", error);
+                    server
+                        .server
+                        .load_errors
+                        .lock()
+                        .push(AssetLoadError { path, error });
                 }
             })
             .detach();
@@ -390,6 +863,19 @@ impl AssetServer {
         &self,
         path: P,
     ) -> Result<Vec<HandleUntyped>, AssetServerError> {
+        Ok(self
+            .load_folder_with(path, &LoadFolderSettings::default())?
+            .into_handles())
+    }
+
+    /// Loads the assets of a folder that have a loader, filtered by the settings, and returns
+    /// them with the structure of the folder.
+    #[must_use = "not using the returned strong handles may result in the unexpected release of the assets"]
+    pub fn load_folder_with<P: AsRef<Path>>(
+        &self,
+        path: P,
+        settings: &LoadFolderSettings,
+    ) -> Result<LoadedFolder, AssetServerError> {
         let path = path.as_ref();
         if !self.server.asset_io.is_directory(path) {
             return Err(AssetServerError::AssetFolderNotADirectory(
@@ -397,30 +883,110 @@ impl AssetServer {
             ));
         }
 
-        let mut handles = Vec::new();
-        for child_path in self.server.asset_io.read_directory(path.as_ref())? {
+        let mut child_paths = self
+            .server
+            .asset_io
+            .read_directory(path)?
+            .collect::<Vec<_>>();
+        child_paths.sort();
+        let mut folder = LoadedFolder {
+            path: path.to_owned(),
+            ..Default::default()
+        };
+        for child_path in child_paths {
             if self.server.asset_io.is_directory(&child_path) {
-                handles.extend(self.load_folder(&child_path)?);
+                if settings.recursive {
+                    folder
+                        .folders
+                        .push(self.load_folder_with(&child_path, settings)?);
+                }
             } else {
                 if self.get_path_asset_loader(&child_path).is_err() {
                     continue;
                 }
+                let matches = settings
+                    .pattern
+                    .as_ref()
+                    .is_none_or(|pattern| pattern.matches(&AssetPath::from(child_path.as_path())));
+                if !matches {
+                    continue;
+                }
                 let handle =
                     self.load_untyped(child_path.to_str().expect("Path should be a valid string."));
-                handles.push(handle);
+                folder.assets.push((child_path, handle));
             }
         }
 
-        Ok(handles)
+        Ok(folder)
+    }
+
+    /// Loads the assets matching a pattern like `textures/**/*.png`, see [`AssetPathPattern`].
+    #[must_use = "not using the returned strong handles may result in the unexpected release of the assets"]
+    pub fn load_glob<P: Into<AssetPathPattern>>(
+        &self,
+        pattern: P,
+    ) -> Result<LoadedFolder, AssetServerError> {
+        let pattern = pattern.into();
+        let settings = LoadFolderSettings {
+            recursive: true,
+            pattern: Some(pattern.clone()),
+        };
+        self.load_folder_with(pattern.base_folder(), &settings)
+    }
+
+    /// Sets when the assets of type `T` are freed once they are unused.
+    pub fn set_free_policy<T: Asset>(&self, policy: FreePolicy) {
+        self.server
+            .free_policies
+            .write()
+            .insert(T::TYPE_UUID, policy);
+    }
+
+    pub fn get_free_policy<T: Asset>(&self) -> FreePolicy {
+        self.server
+            .free_policies
+            .read()
+            .get(&T::TYPE_UUID)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The number of strong handles to the asset, as of the last time unused assets were marked.
+    pub fn get_strong_handle_count<H: Into<HandleId>>(&self, handle: H) -> usize {
+        self.server
+            .asset_ref_counter
+            .ref_counts
+            .read()
+            .get(&handle.into())
+            .copied()
+            .unwrap_or(0)
     }
 
     pub fn free_unused_assets(&self) {
         let mut potential_frees = self.server.asset_ref_counter.mark_unused_assets.lock();
+        let mut delayed_frees = self.server.delayed_frees.lock();
 
-        if !potential_frees.is_empty() {
+        if !potential_frees.is_empty() || !delayed_frees.is_empty() {
             let ref_counts = self.server.asset_ref_counter.ref_counts.read();
             let asset_sources = self.server.asset_sources.read();
             let asset_lifecycles = self.server.asset_lifecycles.read();
+            let free_policies = self.server.free_policies.read();
+            let free = |handle_id: HandleId, type_uuid: Uuid| {
+                if let Some(asset_lifecycle) = asset_lifecycles.get(&type_uuid) {
+                    asset_lifecycle.free_asset(handle_id);
+                }
+            };
+
+            // assets that were used again before their delay elapsed are kept
+            delayed_frees.retain(|(handle_id, _, _)| ref_counts.get(handle_id) == Some(&0));
+            for (handle_id, type_uuid, frames) in delayed_frees.iter_mut() {
+                *frames -= 1;
+                if *frames == 0 {
+                    free(*handle_id, *type_uuid);
+                }
+            }
+            delayed_frees.retain(|(_, _, frames)| *frames > 0);
+
             for potential_free in potential_frees.drain(..) {
                 if let Some(&0) = ref_counts.get(&potential_free) {
                     let type_uuid = match potential_free {
@@ -431,8 +997,16 @@ impl AssetServer {
                     };
 
                     if let Some(type_uuid) = type_uuid {
-                        if let Some(asset_lifecycle) = asset_lifecycles.get(&type_uuid) {
-                            asset_lifecycle.free_asset(potential_free);
+                        match free_policies.get(&type_uuid).copied().unwrap_or_default() {
+                            FreePolicy::Immediate | FreePolicy::Delayed(0) => {
+                                free(potential_free, type_uuid)
+                            }
+                            FreePolicy::Delayed(frames) => {
+                                if !delayed_frees.iter().any(|(id, _, _)| *id == potential_free) {
+                                    delayed_frees.push((potential_free, type_uuid, frames));
+                                }
+                            }
+                            FreePolicy::Never => {}
                         }
                     }
                 }
@@ -500,6 +1074,31 @@ impl AssetServer {
             .downcast_ref::<AssetLifecycleChannel<T>>()
             .unwrap();
 
+        // fallbacks are set before the loaded assets, so that an asset that loads right after
+        // failing replaces its fallback
+        let mut failed_assets = self.server.failed_assets.lock();
+        if failed_assets
+            .iter()
+            .any(|(_, type_uuid)| *type_uuid == T::TYPE_UUID)
+        {
+            let fallback_assets = self.server.fallback_assets.read();
+            let fallback_asset = fallback_assets
+                .get(&T::TYPE_UUID)
+                .and_then(|fallback_asset| fallback_asset.downcast_ref::<FallbackAsset<T>>());
+            failed_assets.retain(|(id, type_uuid)| {
+                if *type_uuid != T::TYPE_UUID {
+                    return true;
+                }
+                if let Some(fallback_asset) = fallback_asset {
+                    if !assets.contains(*id) {
+                        assets.set_untracked(*id, (fallback_asset.0)());
+                    }
+                }
+                false
+            });
+        }
+        drop(failed_assets);
+
         loop {
             match channel.receiver.try_recv() {
                 Ok(AssetLifecycleEvent::Create(result)) => {
@@ -530,6 +1129,9 @@ impl AssetServer {
                     }
                     assets.remove(handle_id);
                 }
+                Ok(AssetLifecycleEvent::Save(handle_id, path)) => {
+                    self.save_in_background(assets.get(handle_id), path);
+                }
                 Err(TryRecvError::Empty) => {
                     break;
                 }
@@ -548,15 +1150,28 @@ pub fn free_unused_assets_system(asset_server: Res<AssetServer>) {
     free_unused_assets_system_impl(&asset_server);
 }
 
+/// Sends the [`AssetLoadError`]s of the assets that failed to load since the last run.
+pub fn asset_load_error_system(
+    asset_server: Res<AssetServer>,
+    mut load_errors: EventWriter<AssetLoadError>,
+) {
+    for load_error in std::mem::take(&mut *asset_server.server.load_errors.lock()) {
+        load_errors.send(load_error);
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{loader::LoadedAsset, update_asset_storage_system};
+    use crate::{
+        loader::LoadedAsset, update_asset_storage_system, AssetReader, BytesAssetReader,
+        LoadingGroup, LoadingProgress,
+    };
     use bevy_ecs::prelude::*;
     use bevy_reflect::TypeUuid;
     use bevy_utils::BoxedFuture;
 
-    #[derive(Debug, TypeUuid)]
+    #[derive(Debug, Clone, TypeUuid)]
     #[uuid = "a5189b72-0572-4290-a2e0-96f73a491c44"]
     struct PngAsset;
 
@@ -591,6 +1206,43 @@ mod test {
         }
     }
 
+    struct BufferLoader;
+    impl AssetLoader for BufferLoader {
+        fn load<'a>(
+            &'a self,
+            _: &'a [u8],
+            ctx: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+            Box::pin(async move {
+                ctx.read_asset_bytes("buffer.bin").await?;
+                ctx.set_default_asset(LoadedAsset::new(PngAsset));
+                Ok(())
+            })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["buffered"]
+        }
+    }
+
+    struct CountingProcessor {
+        runs: Arc<std::sync::atomic::AtomicUsize>,
+    }
+    impl AssetProcessor for CountingProcessor {
+        fn process<'a>(
+            &'a self,
+            bytes: &'a [u8],
+            _: &'a Path,
+        ) -> BoxedFuture<'a, Result<Vec<u8>, anyhow::Error>> {
+            self.runs.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            Box::pin(async move { Ok(bytes.iter().rev().copied().collect()) })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["raw"]
+        }
+    }
+
     struct FakeMultipleDotLoader;
     impl AssetLoader for FakeMultipleDotLoader {
         fn load<'a>(
@@ -613,6 +1265,16 @@ mod test {
             server: Arc::new(AssetServerInternal {
                 loaders: Default::default(),
                 extension_to_loader_index: Default::default(),
+                extension_to_processor: Default::default(),
+                processed_asset_cache: Default::default(),
+                watch_settings: Default::default(),
+                free_policies: Default::default(),
+                delayed_frees: Default::default(),
+                fallback_assets: Default::default(),
+                fallback_types: Default::default(),
+                failed_assets: Default::default(),
+                load_errors: Default::default(),
+                savers: Default::default(),
                 asset_sources: Default::default(),
                 asset_ref_counter: Default::default(),
                 handle_to_path: Default::default(),
@@ -840,6 +1502,741 @@ mod test {
         assert!(get_asset(&handle, &world).is_some());
     }
 
+    #[test]
+    fn test_free_policy() {
+        let dir = create_dir_and_file("fake.png");
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(FakePngLoader);
+        let assets = asset_server.register_asset_type::<PngAsset>();
+        assert_eq!(
+            FreePolicy::Immediate,
+            asset_server.get_free_policy::<PngAsset>()
+        );
+        asset_server.set_free_policy::<PngAsset>(FreePolicy::Delayed(2));
+
+        let mut world = World::new();
+        world.insert_resource(assets);
+        world.insert_resource(asset_server);
+
+        let mut tick = {
+            let mut free_unused_assets_system = free_unused_assets_system.system();
+            free_unused_assets_system.initialize(&mut world);
+            let mut update_asset_storage_system = update_asset_storage_system::<PngAsset>.system();
+            update_asset_storage_system.initialize(&mut world);
+
+            move |world: &mut World| {
+                free_unused_assets_system.run((), world);
+                update_asset_storage_system.run((), world);
+            }
+        };
+
+        fn is_loaded(id: HandleId, world: &World) -> bool {
+            world
+                .get_resource::<Assets<PngAsset>>()
+                .unwrap()
+                .contains(id)
+        }
+
+        let asset_server = world.get_resource::<AssetServer>().unwrap().clone();
+        let load = || {
+            let id =
+                futures_lite::future::block_on(asset_server.load_async("fake.png".into(), true))
+                    .unwrap();
+            asset_server.get_handle::<PngAsset, _>(id)
+        };
+        let handle = load();
+        let id = handle.id;
+        tick(&mut world);
+        assert!(is_loaded(id, &world));
+        assert_eq!(1, asset_server.get_strong_handle_count(id));
+
+        // the asset stays loaded for 2 frames after it would have been freed immediately
+        drop(handle);
+        tick(&mut world);
+        assert_eq!(0, asset_server.get_strong_handle_count(id));
+        tick(&mut world);
+        tick(&mut world);
+        assert!(is_loaded(id, &world));
+        tick(&mut world);
+        assert!(!is_loaded(id, &world));
+
+        // an asset used again before its delay elapsed isn't freed
+        let handle = load();
+        tick(&mut world);
+        drop(handle);
+        tick(&mut world);
+        tick(&mut world);
+        let handle = asset_server.get_handle::<PngAsset, _>(id);
+        for _ in 0..4 {
+            tick(&mut world);
+        }
+        assert!(is_loaded(id, &world));
+        drop(handle);
+
+        asset_server.set_free_policy::<PngAsset>(FreePolicy::Never);
+        for _ in 0..4 {
+            tick(&mut world);
+        }
+        assert!(is_loaded(id, &world));
+    }
+
+    #[test]
+    fn test_fallback_asset() {
+        let dir = create_dir_and_file("fake.png");
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(FakePngLoader);
+        let assets = asset_server.register_asset_type::<PngAsset>();
+        asset_server.set_fallback_asset(PngAsset);
+        assert!(asset_server.has_fallback_asset::<PngAsset>());
+
+        let mut world = World::new();
+        world.insert_resource(assets);
+        world.insert_resource(asset_server.clone());
+        let mut update_asset_storage_system = update_asset_storage_system::<PngAsset>.system();
+        update_asset_storage_system.initialize(&mut world);
+
+        // a typed asset that fails to load is replaced by the fallback
+        let missing: AssetPath = "missing.png".into();
+        let missing_handle = asset_server.get_handle::<PngAsset, _>(missing.get_id());
+        assert!(futures_lite::future::block_on(asset_server.load_async(missing, true)).is_err());
+        update_asset_storage_system.run((), &mut world);
+        let assets = world.get_resource::<Assets<PngAsset>>().unwrap();
+        assert!(assets.contains(missing_handle.id));
+        assert_eq!(
+            LoadState::Failed,
+            asset_server.get_load_state(missing_handle.id)
+        );
+
+        // the type of untyped handles isn't known
+        let untyped: AssetPath = "untyped.png".into();
+        let untyped_handle = asset_server.get_handle_untyped(untyped.get_id());
+        assert!(futures_lite::future::block_on(asset_server.load_async(untyped, true)).is_err());
+        update_asset_storage_system.run((), &mut world);
+        let assets = world.get_resource::<Assets<PngAsset>>().unwrap();
+        assert!(!assets.contains(untyped_handle.id));
+    }
+
+    #[test]
+    fn test_asset_changes() {
+        use crate::{AssetChangeKind, AssetChanges, AssetEvent, AssetPathPattern};
+        use bevy_ecs::event::Events;
+
+        let pattern = AssetPathPattern::new("config/*.png");
+        assert!(pattern.matches(&"config/b.png".into()));
+        assert!(pattern.matches(&"config/b.png#label".into()));
+        assert!(!pattern.matches(&"config/nested/b.png".into()));
+        assert!(!pattern.matches(&"b.png".into()));
+        let pattern = AssetPathPattern::new("**/?.png");
+        assert!(pattern.matches(&"b.png".into()));
+        assert!(pattern.matches(&"config/nested/b.png".into()));
+        assert!(!pattern.matches(&"config/bb.png".into()));
+
+        let dir = create_dir_and_file("a.png");
+        std::fs::create_dir(dir.path().join("config")).unwrap();
+        std::fs::write(dir.path().join("config/b.png"), &[]).unwrap();
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(FakePngLoader);
+        let _assets = asset_server.register_asset_type::<PngAsset>();
+        let load = |path: &str| {
+            let id =
+                futures_lite::future::block_on(asset_server.load_async(path.into(), true)).unwrap();
+            HandleId::from(id)
+        };
+        let a = load("a.png");
+        let b = load("config/b.png");
+
+        let mut events = Events::<AssetEvent<PngAsset>>::default();
+        events.send(AssetEvent::Created {
+            handle: Handle::weak(a),
+        });
+        events.send(AssetEvent::Created {
+            handle: Handle::weak(b),
+        });
+        events.send(AssetEvent::Modified {
+            handle: Handle::weak(b),
+        });
+
+        #[derive(Default)]
+        struct Changes(Vec<(AssetChangeKind, Option<AssetPath<'static>>)>);
+
+        fn for_handle(mut changes: AssetChanges<PngAsset>, mut found: ResMut<Changes>) {
+            let handle_id = HandleId::from("a.png");
+            found.0 = changes
+                .for_handle(handle_id)
+                .map(|change| (change.kind, change.path))
+                .collect();
+        }
+
+        fn matching(mut changes: AssetChanges<PngAsset>, mut found: ResMut<Changes>) {
+            found.0 = changes
+                .matching("config/*")
+                .map(|change| (change.kind, change.path))
+                .collect();
+        }
+
+        let mut world = World::new();
+        world.insert_resource(asset_server.clone());
+        world.insert_resource(events);
+
+        world.insert_resource(Changes::default());
+        let mut system = for_handle.system();
+        system.initialize(&mut world);
+        system.run((), &mut world);
+        let found = &world.get_resource::<Changes>().unwrap().0;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, AssetChangeKind::Created);
+        assert_eq!(found[0].1.as_ref().unwrap().path(), Path::new("a.png"));
+
+        let mut system = matching.system();
+        system.initialize(&mut world);
+        system.run((), &mut world);
+        let found = &world.get_resource::<Changes>().unwrap().0;
+        let kinds = found.iter().map(|(kind, _)| *kind).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![AssetChangeKind::Created, AssetChangeKind::Modified]
+        );
+        assert_eq!(
+            found[0].1.as_ref().unwrap().path(),
+            Path::new("config/b.png")
+        );
+    }
+
+    #[test]
+    fn test_load_folder_with() {
+        let dir = create_dir_and_file("root.png");
+        std::fs::create_dir_all(dir.path().join("textures/ui")).unwrap();
+        for file in [
+            "textures/b.png",
+            "textures/a.png",
+            "textures/ui/c.png",
+            "textures/d.txt",
+        ]
+        .iter()
+        {
+            std::fs::write(dir.path().join(file), &[]).unwrap();
+        }
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(FakePngLoader);
+
+        let folder = asset_server
+            .load_folder_with("textures", &LoadFolderSettings::default())
+            .unwrap();
+        // files without a loader are skipped, and the listing is sorted
+        assert_eq!(
+            folder.paths(),
+            vec![
+                Path::new("textures/a.png"),
+                Path::new("textures/b.png"),
+                Path::new("textures/ui/c.png")
+            ]
+        );
+        assert_eq!(folder.folders[0].path, Path::new("textures/ui"));
+        assert_eq!(folder.typed::<PngAsset>().len(), 3);
+
+        let settings = LoadFolderSettings {
+            recursive: false,
+            pattern: Some("textures/a*".into()),
+        };
+        let folder = asset_server
+            .load_folder_with("textures", &settings)
+            .unwrap();
+        assert_eq!(folder.paths(), vec![Path::new("textures/a.png")]);
+        assert!(folder.folders.is_empty());
+
+        let folder = asset_server.load_glob("textures/**/c.png").unwrap();
+        assert_eq!(folder.path, Path::new("textures"));
+        assert_eq!(folder.paths(), vec![Path::new("textures/ui/c.png")]);
+        assert_eq!(asset_server.load_glob("*.png").unwrap().len(), 1);
+        assert_eq!(asset_server.load_glob("**/*.png").unwrap().len(), 4);
+
+        assert!(matches!(
+            asset_server.load_folder_with("root.png", &LoadFolderSettings::default()),
+            Err(AssetServerError::AssetFolderNotADirectory(_))
+        ));
+    }
+
+    #[test]
+    fn test_loading_group() {
+        let dir = create_dir_and_file("fake.png");
+        std::fs::write(dir.path().join("fake.fail"), &[]).unwrap();
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(FakePngLoader);
+        asset_server.add_loader(FailingLoader);
+        let assets = asset_server.register_asset_type::<PngAsset>();
+
+        let mut world = World::new();
+        world.insert_resource(assets);
+        world.insert_resource(asset_server.clone());
+
+        let mut group = LoadingGroup::new();
+        for path in ["fake.png", "fake.fail"].iter() {
+            let path: AssetPath = (*path).into();
+            let _ = futures_lite::future::block_on(asset_server.load_async(path.clone(), true));
+            group.add_untyped(asset_server.get_handle_untyped(path.get_id()));
+        }
+        let progress = group.progress(&asset_server);
+        assert_eq!(
+            progress,
+            LoadingProgress {
+                loaded: 0,
+                failed: 1,
+                total: 2
+            }
+        );
+        assert!(!progress.is_finished());
+
+        let mut update_asset_storage_system = update_asset_storage_system::<PngAsset>.system();
+        update_asset_storage_system.initialize(&mut world);
+        update_asset_storage_system.run((), &mut world);
+        let progress = group.progress(&asset_server);
+        assert_eq!(progress.loaded, 1);
+        assert!(progress.is_finished());
+        assert!(!progress.is_ready());
+        assert_eq!(progress.fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_source_dependants() {
+        let dir = create_dir_and_file("buffer.bin");
+        std::fs::write(dir.path().join("model.buffered"), &[]).unwrap();
+        std::fs::write(dir.path().join("scene.buffered"), &[]).unwrap();
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(BufferLoader);
+        let _assets = asset_server.register_asset_type::<PngAsset>();
+
+        for path in ["model.buffered", "scene.buffered"].iter() {
+            futures_lite::future::block_on(asset_server.load_async((*path).into(), true)).unwrap();
+        }
+
+        let mut dependants = asset_server.get_source_dependants("buffer.bin");
+        dependants.sort();
+        assert_eq!(
+            dependants,
+            vec![
+                PathBuf::from("model.buffered"),
+                PathBuf::from("scene.buffered")
+            ]
+        );
+        assert!(asset_server
+            .get_source_dependants("model.buffered")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_processed_asset_cache() {
+        let cache = tempfile::tempdir().unwrap();
+        let asset_server = setup(".");
+        let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        asset_server.add_processor(CountingProcessor { runs: runs.clone() });
+        asset_server.set_processed_asset_cache(Some(cache.path().to_owned()));
+        let runs = || runs.load(std::sync::atomic::Ordering::Relaxed);
+        let process = |path: &str, bytes: &[u8]| {
+            futures_lite::future::block_on(
+                asset_server.process_asset(Path::new(path), bytes.to_vec()),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(process("a.raw", &[1, 2, 3]), vec![3, 2, 1]);
+        assert_eq!(runs(), 1);
+        // the cached output is reused, even for another path with the same content
+        assert_eq!(process("b.raw", &[1, 2, 3]), vec![3, 2, 1]);
+        assert_eq!(runs(), 1);
+        assert_eq!(process("a.raw", &[4, 5]), vec![5, 4]);
+        assert_eq!(runs(), 2);
+        // sources without a processor are loaded as they are
+        assert_eq!(process("a.png", &[1, 2, 3]), vec![1, 2, 3]);
+        assert_eq!(runs(), 2);
+    }
+
+    #[test]
+    fn test_archive_asset_io() {
+        use crate::{Archive, ArchiveAssetIo, ArchiveBuilder, FileAssetIo};
+
+        let dir = create_dir_and_file("loose.png");
+        let mut builder = ArchiveBuilder::new();
+        builder.add_file("textures/a.png", vec![1]);
+        builder.add_file("b.png", vec![2]);
+        let base = Archive::from_bytes(builder.to_bytes().unwrap()).unwrap();
+        let mut builder = ArchiveBuilder::new();
+        builder.add_file("b.png", vec![3]);
+        let patch = Archive::from_bytes(builder.to_bytes().unwrap()).unwrap();
+        assert_eq!(base.len(), 2);
+
+        let asset_io = ArchiveAssetIo::new()
+            .with_archive(base)
+            .with_archive(patch)
+            .with_fallback(Box::new(FileAssetIo::new(dir.path())));
+        let load = |path: &str| futures_lite::future::block_on(asset_io.load_path(Path::new(path)));
+        assert_eq!(load("textures/a.png").unwrap(), vec![1]);
+        // the last added archive takes precedence
+        assert_eq!(load("b.png").unwrap(), vec![3]);
+        assert_eq!(load("loose.png").unwrap(), Vec::<u8>::new());
+        assert!(matches!(
+            load("missing.png"),
+            Err(AssetIoError::NotFound(_))
+        ));
+
+        assert!(asset_io.is_directory(Path::new("textures")));
+        assert!(!asset_io.is_directory(Path::new("b.png")));
+        let mut children = asset_io
+            .read_directory(Path::new(""))
+            .unwrap()
+            .collect::<Vec<_>>();
+        children.sort();
+        assert_eq!(
+            children,
+            vec![
+                PathBuf::from("b.png"),
+                PathBuf::from("loose.png"),
+                PathBuf::from("textures")
+            ]
+        );
+
+        assert!(matches!(
+            Archive::from_bytes(vec![0; 16]),
+            Err(AssetIoError::InvalidArchive(_))
+        ));
+    }
+
+    #[test]
+    fn test_layered_asset_io() {
+        use crate::{Archive, ArchiveAssetIo, ArchiveBuilder, AssetLayerEvent, LayeredAssetIo};
+
+        fn layer(files: &[(&str, u8)]) -> ArchiveAssetIo {
+            let mut builder = ArchiveBuilder::new();
+            for (path, byte) in files.iter() {
+                builder.add_file(*path, vec![*byte]);
+            }
+            ArchiveAssetIo::new()
+                .with_archive(Archive::from_bytes(builder.to_bytes().unwrap()).unwrap())
+        }
+
+        let asset_io = LayeredAssetIo::new()
+            .with_layer("base", 0, layer(&[("a.png", 1), ("b.png", 1)]))
+            .with_layer("mod", 10, layer(&[("b.png", 3)]));
+        asset_io.drain_events();
+        let load = |path: &str| futures_lite::future::block_on(asset_io.load_path(Path::new(path)));
+        assert_eq!(load("a.png").unwrap(), vec![1]);
+        assert_eq!(load("b.png").unwrap(), vec![3]);
+        assert_eq!(
+            asset_io.layers(),
+            vec![("mod".to_string(), 10), ("base".to_string(), 0)]
+        );
+
+        // a layer mounted below the mod doesn't change which assets are live
+        asset_io.mount("dlc", 5, layer(&[("b.png", 2), ("c.png", 2)]));
+        assert_eq!(load("b.png").unwrap(), vec![3]);
+        assert_eq!(load("c.png").unwrap(), vec![2]);
+        assert_eq!(
+            asset_io.drain_events(),
+            vec![AssetLayerEvent {
+                path: PathBuf::from("c.png"),
+                previous_layer: None,
+                layer: Some("dlc".to_string()),
+            }]
+        );
+
+        assert!(asset_io.unmount("mod"));
+        assert!(!asset_io.unmount("mod"));
+        assert_eq!(load("b.png").unwrap(), vec![2]);
+        assert_eq!(
+            asset_io.get_live_layer(Path::new("b.png")),
+            Some("dlc".to_string())
+        );
+        assert_eq!(
+            asset_io.drain_events(),
+            vec![AssetLayerEvent {
+                path: PathBuf::from("b.png"),
+                previous_layer: Some("mod".to_string()),
+                layer: Some("dlc".to_string()),
+            }]
+        );
+
+        let mut children = asset_io
+            .read_directory(Path::new(""))
+            .unwrap()
+            .collect::<Vec<_>>();
+        children.sort();
+        assert_eq!(
+            children,
+            vec![
+                PathBuf::from("a.png"),
+                PathBuf::from("b.png"),
+                PathBuf::from("c.png")
+            ]
+        );
+    }
+
+    #[cfg(feature = "http_asset_io")]
+    #[test]
+    fn test_http_asset_io_url() {
+        use crate::HttpAssetIo;
+
+        let asset_io = HttpAssetIo::new("https://cdn.example.com/assets/");
+        assert_eq!(
+            asset_io.url(Path::new("textures/a.png")),
+            "https://cdn.example.com/assets/textures/a.png"
+        );
+        assert_eq!(
+            asset_io.url(Path::new("./b.png")),
+            "https://cdn.example.com/assets/b.png"
+        );
+        assert!(!asset_io.is_directory(Path::new("textures")));
+    }
+
+    struct PngSaver;
+    impl AssetSaver for PngSaver {
+        type Asset = PngAsset;
+
+        fn save(&self, _: &PngAsset) -> Result<Vec<u8>, anyhow::Error> {
+            Ok(b"png".to_vec())
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["png"]
+        }
+    }
+
+    #[test]
+    fn test_save_asset() {
+        let dir = tempfile::tempdir().unwrap();
+        let asset_server = setup(dir.path());
+        let mut assets = asset_server.register_asset_type::<PngAsset>();
+        let handle = assets.add(PngAsset);
+
+        assert!(matches!(
+            asset_server.save(&handle, "saved.png"),
+            Err(AssetServerError::MissingAssetSaver { .. })
+        ));
+        asset_server.add_saver(PngSaver);
+        assert!(matches!(
+            asset_server.save(&handle, "saved.txt"),
+            Err(AssetServerError::MissingAssetSaver { .. })
+        ));
+        asset_server.save(&handle, "saved.png").unwrap();
+
+        futures_lite::future::block_on(
+            asset_server.save_async(&PngAsset, Path::new("nested/saved.png")),
+        )
+        .unwrap();
+        assert_eq!(
+            std::fs::read(dir.path().join("nested/saved.png")).unwrap(),
+            b"png"
+        );
+    }
+
+    struct LabeledLoader;
+    impl AssetLoader for LabeledLoader {
+        fn load<'a>(
+            &'a self,
+            _: &'a [u8],
+            ctx: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+            ctx.set_labeled_asset("b", LoadedAsset::new(PngAsset));
+            ctx.set_labeled_asset("a", LoadedAsset::new(PngAsset));
+            ctx.set_default_asset(LoadedAsset::new(PngAsset));
+            Box::pin(async move { Ok(()) })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["labeled"]
+        }
+    }
+
+    #[test]
+    fn test_get_labeled_assets() {
+        let dir = create_dir_and_file("model.labeled");
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(LabeledLoader);
+        let _assets = asset_server.register_asset_type::<PngAsset>();
+        assert!(asset_server.get_labeled_assets("model.labeled").is_empty());
+
+        futures_lite::future::block_on(asset_server.load_async("model.labeled".into(), true))
+            .unwrap();
+        let labeled_assets = asset_server.get_labeled_assets("model.labeled");
+        let labels = labeled_assets
+            .iter()
+            .map(|asset| asset.label())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, vec![None, Some("a"), Some("b")]);
+        assert!(labeled_assets.iter().all(|asset| asset.is::<PngAsset>()));
+        assert!(labeled_assets[1].handle.is_weak());
+        assert_eq!(
+            labeled_assets[1].handle.id,
+            HandleId::from(AssetPath::new_ref(Path::new("model.labeled"), Some("a")).get_id())
+        );
+    }
+
+    #[derive(Default, serde::Deserialize)]
+    #[serde(default)]
+    struct TestSettings {
+        value: u32,
+    }
+
+    /// Fails to load unless the import settings set `value` to 2
+    struct SettingsLoader;
+    impl AssetLoader for SettingsLoader {
+        fn load<'a>(
+            &'a self,
+            _: &'a [u8],
+            ctx: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+            Box::pin(async move {
+                let settings: TestSettings = ctx.import_settings()?;
+                anyhow::ensure!(settings.value == 2, "value is {}", settings.value);
+                ctx.set_default_asset(LoadedAsset::new(PngAsset));
+                Ok(())
+            })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["settings"]
+        }
+    }
+
+    #[test]
+    fn test_import_settings() {
+        let dir = create_dir_and_file("a.settings");
+        std::fs::write(dir.path().join("b.settings"), &[]).unwrap();
+        std::fs::write(dir.path().join("b.settings.meta"), "(value: 2)").unwrap();
+        std::fs::write(dir.path().join("c.settings"), &[]).unwrap();
+        std::fs::write(dir.path().join("c.settings.meta"), "(value: \"2\")").unwrap();
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(SettingsLoader);
+        let _assets = asset_server.register_asset_type::<PngAsset>();
+        let load =
+            |path: &str| futures_lite::future::block_on(asset_server.load_async(path.into(), true));
+
+        // without a meta file, the default settings are used
+        assert!(matches!(
+            load("a.settings"),
+            Err(AssetServerError::AssetLoaderError(_))
+        ));
+        load("b.settings").unwrap();
+        assert!(matches!(
+            load("c.settings"),
+            Err(AssetServerError::AssetLoaderError(_))
+        ));
+        // changing the meta file reloads the asset
+        assert_eq!(
+            asset_server.get_source_dependants("b.settings.meta"),
+            vec![PathBuf::from("b.settings")]
+        );
+        assert_eq!(
+            import_settings_path(Path::new("textures/a.png")),
+            Path::new("textures/a.png.meta")
+        );
+    }
+
+    #[test]
+    fn test_watch_settings() {
+        let settings = WatchSettings {
+            folders: vec![PathBuf::from("textures"), PathBuf::from("models/props")],
+            extensions: vec!["png".to_string(), "gltf".to_string()],
+            ..Default::default()
+        };
+        assert!(settings.matches(Path::new("textures/grass.PNG")));
+        assert!(settings.matches(Path::new("models/props/crate.gltf")));
+        assert!(!settings.matches(Path::new("models/tree.gltf")));
+        assert!(!settings.matches(Path::new("textures/grass.png.meta")));
+        assert!(WatchSettings::default().matches(Path::new("any/file.ext")));
+    }
+
+    #[test]
+    fn test_reload_asset() {
+        let dir = create_dir_and_file("fake.png");
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(FakePngLoader);
+        let mut assets = asset_server.register_asset_type::<PngAsset>();
+
+        let path: AssetPath = "fake.png".into();
+        assert!(matches!(
+            asset_server.reload_asset(path.get_id()),
+            Err(AssetServerError::AssetNotLoadedFromPath)
+        ));
+        futures_lite::future::block_on(asset_server.load_async(path.clone(), true)).unwrap();
+        asset_server.reload_asset(path.get_id()).unwrap();
+
+        let handle = assets.add(PngAsset);
+        assert!(matches!(
+            asset_server.reload_asset(&handle),
+            Err(AssetServerError::AssetNotLoadedFromPath)
+        ));
+    }
+
+    /// Reads its sources two bytes at a time, making a partial asset available after each chunk
+    struct StreamingLoader;
+    impl AssetLoader for StreamingLoader {
+        fn load<'a>(
+            &'a self,
+            _: &'a [u8],
+            _: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+            Box::pin(async { anyhow::bail!("not streamed") })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["stream"]
+        }
+
+        fn supports_streaming(&self) -> bool {
+            true
+        }
+
+        fn load_streaming<'a>(
+            &'a self,
+            reader: &'a mut dyn AssetReader,
+            ctx: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+            Box::pin(async move {
+                let mut bytes = Vec::new();
+                let mut chunk = [0; 2];
+                loop {
+                    let read = reader.read(&mut chunk).await?;
+                    if read == 0 {
+                        break;
+                    }
+                    bytes.extend_from_slice(&chunk[..read]);
+                    ctx.set_partial_asset(None, PngAsset);
+                }
+                anyhow::ensure!(bytes == [1, 2, 3, 4, 5]);
+                ctx.set_default_asset(LoadedAsset::new(PngAsset));
+                Ok(())
+            })
+        }
+    }
+
+    #[test]
+    fn test_streaming_loader() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("data.stream"), &[1, 2, 3, 4, 5]).unwrap();
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(StreamingLoader);
+        let _assets = asset_server.register_asset_type::<PngAsset>();
+
+        futures_lite::future::block_on(asset_server.load_async("data.stream".into(), true))
+            .unwrap();
+        let asset_lifecycles = asset_server.server.asset_lifecycles.read();
+        let channel = asset_lifecycles
+            .get(&PngAsset::TYPE_UUID)
+            .unwrap()
+            .downcast_ref::<AssetLifecycleChannel<PngAsset>>()
+            .unwrap();
+        // three partial assets, then the loaded asset
+        assert_eq!(channel.receiver.len(), 4);
+
+        let mut reader = BytesAssetReader::new(vec![1, 2, 3]);
+        assert_eq!(reader.size_hint(), Some(3));
+        let mut bytes = vec![0];
+        assert_eq!(
+            futures_lite::future::block_on(reader.read_to_end(&mut bytes)).unwrap(),
+            3
+        );
+        assert_eq!(bytes, vec![0, 1, 2, 3]);
+    }
+
     #[test]
     fn test_get_handle_path() {
         const PATH: &str = "path/file.png";
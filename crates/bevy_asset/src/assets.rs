@@ -1,9 +1,12 @@
 use crate::{
-    update_asset_storage_system, Asset, AssetLoader, AssetServer, AssetStage, Handle, HandleId,
-    RefChange,
+    update_asset_storage_system, Asset, AssetLoader, AssetPath, AssetPathPattern, AssetProcessor,
+    AssetSaver, AssetServer, AssetStage, Handle, HandleId, RefChange,
+};
+use bevy_app::{App, EventReader, EventWriter, Events};
+use bevy_ecs::{
+    system::{Res, ResMut, SystemParam},
+    world::FromWorld,
 };
-use bevy_app::{App, EventWriter, Events};
-use bevy_ecs::{system::ResMut, world::FromWorld};
 use bevy_utils::HashMap;
 use crossbeam_channel::Sender;
 use std::fmt::Debug;
@@ -43,6 +46,116 @@ impl<T: Asset> Debug for AssetEvent<T> {
     }
 }
 
+impl<T: Asset> AssetEvent<T> {
+    /// The weak handle of the asset the event happened on.
+    pub fn handle(&self) -> &Handle<T> {
+        match self {
+            AssetEvent::Created { handle }
+            | AssetEvent::Modified { handle }
+            | AssetEvent::Removed { handle } => handle,
+        }
+    }
+}
+
+/// The kind of an [`AssetEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+/// An [`AssetEvent`] with the path of the asset, see [`AssetChanges`].
+#[derive(Debug)]
+pub struct AssetChange<T: Asset> {
+    pub kind: AssetChangeKind,
+    /// A weak handle to the asset
+    pub handle: Handle<T>,
+    /// The path of the asset, if it was loaded by the [`AssetServer`]
+    pub path: Option<AssetPath<'static>>,
+}
+
+impl<T: Asset> AssetChange<T> {
+    fn new(event: &AssetEvent<T>, asset_server: &AssetServer) -> Self {
+        let kind = match event {
+            AssetEvent::Created { .. } => AssetChangeKind::Created,
+            AssetEvent::Modified { .. } => AssetChangeKind::Modified,
+            AssetEvent::Removed { .. } => AssetChangeKind::Removed,
+        };
+        let handle = event.handle().clone_weak();
+        AssetChange {
+            kind,
+            path: asset_server.get_asset_path(&handle),
+            handle,
+        }
+    }
+}
+
+/// Reads the [`AssetEvent`]s of assets of type `T` with their paths, only for the assets a system
+/// is interested in.
+///
+/// ```
+/// # use bevy_asset::{AssetChanges, Handle};
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_reflect::TypeUuid;
+/// # #[derive(TypeUuid)]
+/// # #[uuid = "1b7d3f5a-8e41-4d2c-9a63-0f2e5b8c7d14"]
+/// # struct Config;
+/// # struct Settings { config: Handle<Config> }
+/// fn reload_config(mut changes: AssetChanges<Config>, settings: Res<Settings>) {
+///     for change in changes.for_handle(&settings.config) {
+///         println!("{:?} was {:?}", change.path, change.kind);
+///     }
+/// }
+/// # reload_config.system();
+/// ```
+#[derive(SystemParam)]
+pub struct AssetChanges<'w, 's, T: Asset> {
+    events: EventReader<'w, 's, AssetEvent<T>>,
+    asset_server: Res<'w, AssetServer>,
+}
+
+impl<'w, 's, T: Asset> AssetChanges<'w, 's, T> {
+    /// The changes of every asset of type `T` since the system last ran.
+    pub fn iter(&mut self) -> impl Iterator<Item = AssetChange<T>> + '_ {
+        let asset_server = &*self.asset_server;
+        self.events
+            .iter()
+            .map(move |event| AssetChange::new(event, asset_server))
+    }
+
+    /// The changes of the asset of `handle` since the system last ran.
+    pub fn for_handle<H: Into<HandleId>>(
+        &mut self,
+        handle: H,
+    ) -> impl Iterator<Item = AssetChange<T>> + '_ {
+        let id = handle.into();
+        let asset_server = &*self.asset_server;
+        self.events
+            .iter()
+            .filter(move |event| event.handle().id == id)
+            .map(move |event| AssetChange::new(event, asset_server))
+    }
+
+    /// The changes of the assets whose path matches `pattern` since the system last ran.
+    pub fn matching<P: Into<AssetPathPattern>>(
+        &mut self,
+        pattern: P,
+    ) -> impl Iterator<Item = AssetChange<T>> + '_ {
+        let pattern = pattern.into();
+        let asset_server = &*self.asset_server;
+        self.events
+            .iter()
+            .map(move |event| AssetChange::new(event, asset_server))
+            .filter(move |change| {
+                change
+                    .path
+                    .as_ref()
+                    .is_some_and(|path| pattern.matches(path))
+            })
+    }
+}
+
 /// Stores Assets of a given type and tracks changes to them.
 #[derive(Debug)]
 pub struct Assets<T: Asset> {
@@ -201,6 +314,12 @@ pub trait AddAsset {
     fn add_asset_loader<T>(&mut self, loader: T) -> &mut Self
     where
         T: AssetLoader;
+    fn add_asset_processor<T>(&mut self, processor: T) -> &mut Self
+    where
+        T: AssetProcessor;
+    fn add_asset_saver<T>(&mut self, saver: T) -> &mut Self
+    where
+        T: AssetSaver;
 }
 
 impl AddAsset for App {
@@ -238,4 +357,26 @@ impl AddAsset for App {
             .add_loader(loader);
         self
     }
+
+    fn add_asset_processor<T>(&mut self, processor: T) -> &mut Self
+    where
+        T: AssetProcessor,
+    {
+        self.world
+            .get_resource_mut::<AssetServer>()
+            .expect("AssetServer does not exist. Consider adding it as a resource.")
+            .add_processor(processor);
+        self
+    }
+
+    fn add_asset_saver<T>(&mut self, saver: T) -> &mut Self
+    where
+        T: AssetSaver,
+    {
+        self.world
+            .get_resource_mut::<AssetServer>()
+            .expect("AssetServer does not exist. Consider adding it as a resource.")
+            .add_saver(saver);
+        self
+    }
 }
@@ -0,0 +1,112 @@
+use crate::{Asset, AssetPath, AssetServer, Assets, HandleId};
+use bevy_app::prelude::*;
+use bevy_ecs::system::{Res, ResMut};
+use bevy_utils::{HashMap, Uuid};
+use std::cmp::Reverse;
+
+/// The memory used by an asset, for the [`AssetUsageReport`].
+pub trait AssetMemoryUsage {
+    /// The number of bytes used by the asset's data. It doesn't need to be exact, but should
+    /// account for the large buffers of the asset, like the pixels of a texture.
+    fn memory_usage(&self) -> usize;
+}
+
+/// Adds the assets of type `T` to the [`AssetUsageReport`] resource, which is updated every
+/// frame.
+pub struct AssetUsageDiagnosticsPlugin<T: Asset + AssetMemoryUsage> {
+    marker: std::marker::PhantomData<T>,
+}
+
+impl<T: Asset + AssetMemoryUsage> Default for AssetUsageDiagnosticsPlugin<T> {
+    fn default() -> Self {
+        Self {
+            marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T: Asset + AssetMemoryUsage> Plugin for AssetUsageDiagnosticsPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AssetUsageReport>()
+            .add_system(Self::diagnostic_system);
+    }
+}
+
+impl<T: Asset + AssetMemoryUsage> AssetUsageDiagnosticsPlugin<T> {
+    pub fn diagnostic_system(
+        mut report: ResMut<AssetUsageReport>,
+        assets: Res<Assets<T>>,
+        asset_server: Res<AssetServer>,
+    ) {
+        let mut usages = assets
+            .iter()
+            .map(|(id, asset)| AssetUsage {
+                id,
+                path: asset_server.get_handle_path(id).map(|path| path.to_owned()),
+                strong_handles: asset_server.get_strong_handle_count(id),
+                memory: asset.memory_usage(),
+            })
+            .collect::<Vec<_>>();
+        usages.sort_by_key(|usage| Reverse(usage.memory));
+        report.types.insert(
+            T::TYPE_UUID,
+            AssetTypeUsage {
+                type_name: std::any::type_name::<T>(),
+                assets: usages,
+            },
+        );
+    }
+}
+
+/// The assets currently loaded and the memory they use, by asset type. Only the types added with
+/// an [`AssetUsageDiagnosticsPlugin`] are reported.
+#[derive(Debug, Default)]
+pub struct AssetUsageReport {
+    types: HashMap<Uuid, AssetTypeUsage>,
+}
+
+impl AssetUsageReport {
+    pub fn get<T: Asset>(&self) -> Option<&AssetTypeUsage> {
+        self.types.get(&T::TYPE_UUID)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &AssetTypeUsage> {
+        self.types.values()
+    }
+
+    /// The memory used by the assets of every reported type, in bytes.
+    pub fn total_memory(&self) -> usize {
+        self.iter().map(|usage| usage.memory()).sum()
+    }
+}
+
+/// The assets of a type, in the [`AssetUsageReport`].
+#[derive(Debug)]
+pub struct AssetTypeUsage {
+    pub type_name: &'static str,
+    /// The assets of the type, using the most memory first
+    pub assets: Vec<AssetUsage>,
+}
+
+impl AssetTypeUsage {
+    pub fn count(&self) -> usize {
+        self.assets.len()
+    }
+
+    /// The memory used by the assets of the type, in bytes.
+    pub fn memory(&self) -> usize {
+        self.assets.iter().map(|asset| asset.memory).sum()
+    }
+}
+
+/// An asset, in the [`AssetUsageReport`].
+#[derive(Debug)]
+pub struct AssetUsage {
+    pub id: HandleId,
+    /// The path the asset was loaded from, if it was loaded by the [`AssetServer`]
+    pub path: Option<AssetPath<'static>>,
+    /// The number of strong handles keeping the asset loaded
+    pub strong_handles: usize,
+    /// The memory used by the asset, in bytes, see [`AssetMemoryUsage`]
+    pub memory: usize,
+}
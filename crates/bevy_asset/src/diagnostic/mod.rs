@@ -1,2 +1,6 @@
 mod asset_count_diagnostics_plugin;
+mod asset_usage_diagnostics_plugin;
 pub use asset_count_diagnostics_plugin::AssetCountDiagnosticsPlugin;
+pub use asset_usage_diagnostics_plugin::{
+    AssetMemoryUsage, AssetTypeUsage, AssetUsage, AssetUsageDiagnosticsPlugin, AssetUsageReport,
+};
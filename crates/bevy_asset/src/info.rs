@@ -1,4 +1,4 @@
-use crate::{path::AssetPath, LabelId};
+use crate::{path::AssetPath, Asset, HandleUntyped, LabelId};
 use bevy_utils::{HashMap, HashSet, Uuid};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -15,6 +15,28 @@ pub struct AssetMeta {
     pub type_uuid: Uuid,
 }
 
+/// An asset a source produced when it was loaded, see
+/// [`AssetServer::get_labeled_assets`](crate::AssetServer::get_labeled_assets).
+#[derive(Debug, Clone)]
+pub struct LabeledAsset {
+    /// The path of the asset, with its label, or without a label for the default asset
+    pub path: AssetPath<'static>,
+    pub type_uuid: Uuid,
+    /// A weak handle to the asset
+    pub handle: HandleUntyped,
+}
+
+impl LabeledAsset {
+    pub fn label(&self) -> Option<&str> {
+        self.path.label()
+    }
+
+    /// Whether the asset is of type `T`
+    pub fn is<T: Asset>(&self) -> bool {
+        self.type_uuid == T::TYPE_UUID
+    }
+}
+
 /// Info about a specific asset, such as its path and its current load state
 #[derive(Clone, Debug)]
 pub struct SourceInfo {
@@ -24,6 +46,9 @@ pub struct SourceInfo {
     pub load_state: LoadState,
     pub committed_assets: HashSet<LabelId>,
     pub version: usize,
+    /// The files the loader read besides the source itself, which reload the source when they
+    /// change
+    pub source_dependencies: Vec<PathBuf>,
 }
 
 impl SourceInfo {
@@ -0,0 +1,507 @@
+use crate::{AssetIo, AssetIoError, AssetReader, BytesAssetReader};
+use anyhow::Result;
+use bevy_utils::{BoxedFuture, HashMap, HashSet};
+use parking_lot::Mutex;
+use std::{
+    fs::{self, File},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Component, Path, PathBuf},
+};
+
+const ARCHIVE_MAGIC: &[u8; 4] = b"BPAK";
+const ARCHIVE_VERSION: u32 = 1;
+
+/// How the files of an [`Archive`] are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveCompression {
+    None,
+    /// Compressed with deflate. Archives with compressed files can only be written and read with
+    /// the `archive_compression` feature.
+    Deflate,
+}
+
+impl ArchiveCompression {
+    fn to_byte(self) -> u8 {
+        match self {
+            ArchiveCompression::None => 0,
+            ArchiveCompression::Deflate => 1,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Result<Self, AssetIoError> {
+        match byte {
+            0 => Ok(ArchiveCompression::None),
+            1 => Ok(ArchiveCompression::Deflate),
+            _ => Err(AssetIoError::InvalidArchive(format!(
+                "unknown compression {}",
+                byte
+            ))),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ArchiveEntry {
+    offset: u64,
+    size: u64,
+    compression: ArchiveCompression,
+}
+
+enum ArchiveData {
+    File(Mutex<File>),
+    Bytes(Vec<u8>),
+}
+
+/// A packed archive of asset files, written with an [`ArchiveBuilder`].
+///
+/// An archive starts with an index of its files, so opening an archive only reads the index, and
+/// the files are read when they are loaded.
+pub struct Archive {
+    entries: HashMap<String, ArchiveEntry>,
+    data_offset: u64,
+    data: ArchiveData,
+}
+
+impl Archive {
+    /// Opens the archive file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, AssetIoError> {
+        let path = path.as_ref();
+        let mut file = File::open(path).map_err(|e| {
+            if e.kind() == io::ErrorKind::NotFound {
+                AssetIoError::NotFound(path.to_owned())
+            } else {
+                e.into()
+            }
+        })?;
+        let (entries, data_offset) = read_index(&mut file)?;
+        Ok(Archive {
+            entries,
+            data_offset,
+            data: ArchiveData::File(Mutex::new(file)),
+        })
+    }
+
+    /// Reads an archive from memory, like an archive embedded in the executable with
+    /// `include_bytes!`, or downloaded on platforms without a filesystem.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, AssetIoError> {
+        let (entries, data_offset) = read_index(&mut &bytes[..])?;
+        Ok(Archive {
+            entries,
+            data_offset,
+            data: ArchiveData::Bytes(bytes),
+        })
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.entries.contains_key(&archive_key(path))
+    }
+
+    /// The paths of the files in the archive.
+    pub fn paths(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        self.entries.keys().map(PathBuf::from)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Reads the file at `path` from the archive, decompressing it if needed.
+    pub fn read(&self, path: &Path) -> Result<Vec<u8>, AssetIoError> {
+        let entry = self
+            .entries
+            .get(&archive_key(path))
+            .ok_or_else(|| AssetIoError::NotFound(path.to_owned()))?;
+        let start = self.data_offset + entry.offset;
+        let bytes = match &self.data {
+            ArchiveData::File(file) => {
+                let mut file = file.lock();
+                file.seek(SeekFrom::Start(start))?;
+                let mut bytes = vec![0; entry.size as usize];
+                file.read_exact(&mut bytes)?;
+                bytes
+            }
+            ArchiveData::Bytes(data) => data
+                .get(start as usize..(start + entry.size) as usize)
+                .ok_or_else(|| {
+                    AssetIoError::InvalidArchive(format!(
+                        "{} is out of the bounds of the archive",
+                        path.display()
+                    ))
+                })?
+                .to_vec(),
+        };
+        decompress(bytes, entry.compression)
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        let key = archive_key(path);
+        if key.is_empty() {
+            return !self.entries.is_empty();
+        }
+        let prefix = format!("{}/", key);
+        self.entries.keys().any(|entry| entry.starts_with(&prefix))
+    }
+
+    /// Adds the files and directories directly in the directory at `path` to `children`.
+    fn read_directory(&self, path: &Path, children: &mut HashSet<PathBuf>) {
+        let key = archive_key(path);
+        let prefix = if key.is_empty() {
+            key
+        } else {
+            format!("{}/", key)
+        };
+        for entry in self.entries.keys() {
+            if let Some(relative) = entry.strip_prefix(&prefix) {
+                let child = relative.split('/').next().unwrap();
+                children.insert(PathBuf::from(format!("{}{}", prefix, child)));
+            }
+        }
+    }
+}
+
+/// Writes [`Archive`]s, usually from the asset folder when packaging a game.
+///
+/// ```no_run
+/// # use bevy_asset::{ArchiveBuilder, ArchiveCompression};
+/// # use std::fs::File;
+/// let mut builder = ArchiveBuilder::new().with_compression(ArchiveCompression::None);
+/// builder.add_directory("assets").unwrap();
+/// builder.write(File::create("assets.pak").unwrap()).unwrap();
+/// ```
+pub struct ArchiveBuilder {
+    files: Vec<(String, Vec<u8>)>,
+    compression: ArchiveCompression,
+}
+
+impl Default for ArchiveBuilder {
+    fn default() -> Self {
+        ArchiveBuilder {
+            files: Vec::new(),
+            compression: ArchiveCompression::None,
+        }
+    }
+}
+
+impl ArchiveBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how the files are stored in the archive.
+    pub fn with_compression(mut self, compression: ArchiveCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Adds a file to the archive, replacing the file previously added at the same path.
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P, bytes: Vec<u8>) {
+        let key = archive_key(path.as_ref());
+        self.files.retain(|(file, _)| *file != key);
+        self.files.push((key, bytes));
+    }
+
+    /// Adds every file in the directory at `root` and its subdirectories, at their paths relative
+    /// to `root`.
+    pub fn add_directory<P: AsRef<Path>>(&mut self, root: P) -> Result<(), AssetIoError> {
+        let root = root.as_ref();
+        let mut directories = vec![root.to_owned()];
+        while let Some(directory) = directories.pop() {
+            for entry in fs::read_dir(&directory)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    directories.push(path);
+                } else {
+                    let bytes = fs::read(&path)?;
+                    self.add_file(path.strip_prefix(root).unwrap(), bytes);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the archive, with its index first.
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<(), AssetIoError> {
+        let mut data = Vec::with_capacity(self.files.len());
+        for (path, bytes) in &self.files {
+            data.push((path, compress(bytes, self.compression)?));
+        }
+
+        writer.write_all(ARCHIVE_MAGIC)?;
+        writer.write_all(&ARCHIVE_VERSION.to_le_bytes())?;
+        writer.write_all(&(data.len() as u32).to_le_bytes())?;
+        let mut offset = 0u64;
+        for (path, bytes) in &data {
+            writer.write_all(&(path.len() as u32).to_le_bytes())?;
+            writer.write_all(path.as_bytes())?;
+            writer.write_all(&offset.to_le_bytes())?;
+            writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+            writer.write_all(&[self.compression.to_byte()])?;
+            offset += bytes.len() as u64;
+        }
+        for (_, bytes) in &data {
+            writer.write_all(bytes)?;
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, AssetIoError> {
+        let mut bytes = Vec::new();
+        self.write(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+/// An [`AssetIo`] that loads assets from [`Archive`]s, so shipped games don't need a loose asset
+/// folder.
+///
+/// Archives are layered: an asset is loaded from the last added archive that contains it, so
+/// patches or mods can be shipped as archives overriding some of the assets. Assets that aren't in
+/// any archive are loaded from the fallback [`AssetIo`], if there is one. Changes are only watched
+/// in the fallback, and saved assets are written to it.
+///
+/// ```no_run
+/// # use bevy_app::App;
+/// # use bevy_asset::{create_platform_default_asset_io, Archive, ArchiveAssetIo, AssetServer};
+/// # use bevy_tasks::IoTaskPool;
+/// # let mut app = App::new();
+/// let task_pool = app.world.get_resource::<IoTaskPool>().unwrap().0.clone();
+/// let asset_io = ArchiveAssetIo::new()
+///     .with_archive(Archive::open("assets.pak").unwrap())
+///     .with_fallback(create_platform_default_asset_io(&mut app));
+/// // insert the server before adding the `AssetPlugin`, so it doesn't create its own
+/// app.insert_resource(AssetServer::new(asset_io, task_pool));
+/// ```
+#[derive(Default)]
+pub struct ArchiveAssetIo {
+    archives: Vec<Archive>,
+    fallback: Option<Box<dyn AssetIo>>,
+}
+
+impl ArchiveAssetIo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an archive, which takes precedence over the previously added archives.
+    pub fn with_archive(mut self, archive: Archive) -> Self {
+        self.add_archive(archive);
+        self
+    }
+
+    pub fn add_archive(&mut self, archive: Archive) {
+        self.archives.push(archive);
+    }
+
+    /// Sets the [`AssetIo`] assets are loaded from when no archive contains them.
+    pub fn with_fallback(mut self, fallback: Box<dyn AssetIo>) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    pub fn archives(&self) -> &[Archive] {
+        &self.archives
+    }
+
+    pub fn fallback(&self) -> Option<&dyn AssetIo> {
+        self.fallback.as_deref()
+    }
+}
+
+impl AssetIo for ArchiveAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            if let Some(archive) = self
+                .archives
+                .iter()
+                .rev()
+                .find(|archive| archive.contains(path))
+            {
+                return archive.read(path);
+            }
+            match &self.fallback {
+                Some(fallback) => fallback.load_path(path).await,
+                None => Err(AssetIoError::NotFound(path.to_owned())),
+            }
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        let mut children = HashSet::default();
+        for archive in &self.archives {
+            archive.read_directory(path, &mut children);
+        }
+        if let Some(fallback) = &self.fallback {
+            if fallback.is_directory(path) {
+                children.extend(fallback.read_directory(path)?);
+            }
+        }
+        if children.is_empty() && !self.is_directory(path) {
+            return Err(AssetIoError::NotFound(path.to_owned()));
+        }
+        Ok(Box::new(children.into_iter()))
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        self.archives
+            .iter()
+            .any(|archive| archive.is_directory(path))
+            || self
+                .fallback
+                .as_ref()
+                .is_some_and(|fallback| fallback.is_directory(path))
+    }
+
+    fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+        match &self.fallback {
+            Some(fallback) => fallback.watch_path_for_changes(path),
+            None => Ok(()),
+        }
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        match &self.fallback {
+            Some(fallback) => fallback.watch_for_changes(),
+            None => Ok(()),
+        }
+    }
+
+    fn read_path<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Box<dyn AssetReader>, AssetIoError>> {
+        Box::pin(async move {
+            if let Some(archive) = self
+                .archives
+                .iter()
+                .rev()
+                .find(|archive| archive.contains(path))
+            {
+                let bytes = archive.read(path)?;
+                return Ok(Box::new(BytesAssetReader::new(bytes)) as Box<dyn AssetReader>);
+            }
+            match &self.fallback {
+                Some(fallback) => fallback.read_path(path).await,
+                None => Err(AssetIoError::NotFound(path.to_owned())),
+            }
+        })
+    }
+
+    fn save_path<'a>(
+        &'a self,
+        path: &'a Path,
+        bytes: &'a [u8],
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        // archives are read-only, saved assets are written to the fallback
+        match &self.fallback {
+            Some(fallback) => fallback.save_path(path, bytes),
+            None => Box::pin(async move { Err(AssetIoError::SaveNotSupported(path.to_owned())) }),
+        }
+    }
+}
+
+/// The key of a path in an archive, with `/` separators on every platform.
+fn archive_key(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32, AssetIoError> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> Result<u64, AssetIoError> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Reads the index of an archive, returning its entries and the offset of the file data.
+fn read_index(
+    reader: &mut impl Read,
+) -> Result<(HashMap<String, ArchiveEntry>, u64), AssetIoError> {
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != ARCHIVE_MAGIC {
+        return Err(AssetIoError::InvalidArchive(
+            "not an asset archive".to_string(),
+        ));
+    }
+    let version = read_u32(reader)?;
+    if version != ARCHIVE_VERSION {
+        return Err(AssetIoError::InvalidArchive(format!(
+            "unsupported version {}",
+            version
+        )));
+    }
+    let count = read_u32(reader)?;
+    let mut data_offset = 12;
+    let mut entries = HashMap::default();
+    for _ in 0..count {
+        let path_len = read_u32(reader)?;
+        let mut path = vec![0; path_len as usize];
+        reader.read_exact(&mut path)?;
+        let path = String::from_utf8(path)
+            .map_err(|_| AssetIoError::InvalidArchive("invalid path".to_string()))?;
+        let offset = read_u64(reader)?;
+        let size = read_u64(reader)?;
+        let mut compression = [0; 1];
+        reader.read_exact(&mut compression)?;
+        entries.insert(
+            path,
+            ArchiveEntry {
+                offset,
+                size,
+                compression: ArchiveCompression::from_byte(compression[0])?,
+            },
+        );
+        data_offset += 4 + path_len as u64 + 8 + 8 + 1;
+    }
+    Ok((entries, data_offset))
+}
+
+fn compress(bytes: &[u8], compression: ArchiveCompression) -> Result<Vec<u8>, AssetIoError> {
+    match compression {
+        ArchiveCompression::None => Ok(bytes.to_vec()),
+        #[cfg(feature = "archive_compression")]
+        ArchiveCompression::Deflate => {
+            let mut encoder =
+                flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(bytes)?;
+            Ok(encoder.finish()?)
+        }
+        #[cfg(not(feature = "archive_compression"))]
+        ArchiveCompression::Deflate => Err(AssetIoError::InvalidArchive(
+            "compression requires the `archive_compression` feature".to_string(),
+        )),
+    }
+}
+
+fn decompress(bytes: Vec<u8>, compression: ArchiveCompression) -> Result<Vec<u8>, AssetIoError> {
+    match compression {
+        ArchiveCompression::None => Ok(bytes),
+        #[cfg(feature = "archive_compression")]
+        ArchiveCompression::Deflate => {
+            let mut decompressed = Vec::new();
+            flate2::read::DeflateDecoder::new(&bytes[..]).read_to_end(&mut decompressed)?;
+            Ok(decompressed)
+        }
+        #[cfg(not(feature = "archive_compression"))]
+        ArchiveCompression::Deflate => Err(AssetIoError::InvalidArchive(
+            "decompression requires the `archive_compression` feature".to_string(),
+        )),
+    }
+}
@@ -1,7 +1,10 @@
-use crate::{filesystem_watcher::FilesystemWatcher, AssetIo, AssetIoError, AssetServer};
+use crate::{
+    filesystem_watcher::FilesystemWatcher, AssetIo, AssetIoError, AssetReader, AssetServer,
+    SourcePathId,
+};
 use anyhow::Result;
-use bevy_ecs::system::Res;
-use bevy_utils::{BoxedFuture, HashSet};
+use bevy_ecs::system::{Local, Res};
+use bevy_utils::{BoxedFuture, HashMap, HashSet, Instant};
 use crossbeam_channel::TryRecvError;
 use fs::File;
 use io::Read;
@@ -102,14 +105,68 @@ impl AssetIo for FileAssetIo {
     fn is_directory(&self, path: &Path) -> bool {
         self.root_path.join(path).is_dir()
     }
+
+    fn save_path<'a>(
+        &'a self,
+        path: &'a Path,
+        bytes: &'a [u8],
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move {
+            let full_path = self.root_path.join(path);
+            if let Some(parent) = full_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(full_path, bytes)?;
+            Ok(())
+        })
+    }
+
+    fn read_path<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Box<dyn AssetReader>, AssetIoError>> {
+        Box::pin(async move {
+            let full_path = self.root_path.join(path);
+            match File::open(&full_path) {
+                Ok(file) => Ok(Box::new(FileAssetReader {
+                    size: file.metadata().ok().map(|metadata| metadata.len()),
+                    file,
+                }) as Box<dyn AssetReader>),
+                Err(e) => {
+                    if e.kind() == std::io::ErrorKind::NotFound {
+                        Err(AssetIoError::NotFound(full_path))
+                    } else {
+                        Err(e.into())
+                    }
+                }
+            }
+        })
+    }
+}
+
+struct FileAssetReader {
+    file: File,
+    size: Option<u64>,
+}
+
+impl AssetReader for FileAssetReader {
+    fn read<'a>(&'a mut self, buf: &'a mut [u8]) -> BoxedFuture<'a, Result<usize, AssetIoError>> {
+        Box::pin(async move { Ok(self.file.read(buf)?) })
+    }
+
+    fn size_hint(&self) -> Option<u64> {
+        self.size
+    }
 }
 
 #[cfg(all(
     feature = "filesystem_watcher",
     all(not(target_arch = "wasm32"), not(target_os = "android"))
 ))]
-pub fn filesystem_watcher_system(asset_server: Res<AssetServer>) {
-    let mut changed = HashSet::default();
+pub fn filesystem_watcher_system(
+    asset_server: Res<AssetServer>,
+    mut pending: Local<HashMap<PathBuf, Instant>>,
+) {
     let asset_io =
         if let Some(asset_io) = asset_server.server.asset_io.downcast_ref::<FileAssetIo>() {
             asset_io
@@ -118,6 +175,7 @@ pub fn filesystem_watcher_system(asset_server: Res<AssetServer>) {
         };
     let watcher = asset_io.filesystem_watcher.read();
     if let Some(ref watcher) = *watcher {
+        let now = Instant::now();
         loop {
             let event = match watcher.receiver.try_recv() {
                 Ok(result) => result.unwrap(),
@@ -130,13 +188,40 @@ pub fn filesystem_watcher_system(asset_server: Res<AssetServer>) {
                 ..
             } = event
             {
-                for path in paths.iter() {
-                    if !changed.contains(path) {
-                        let relative_path = path.strip_prefix(&asset_io.root_path).unwrap();
-                        let _ = asset_server.load_untracked(relative_path.into(), true);
-                    }
+                for path in paths {
+                    pending.insert(path, now);
+                }
+            }
+        }
+
+        // only reload the files that haven't changed for the debounce duration
+        let debounce = asset_server.server.watch_settings.read().debounce;
+        let ready = pending
+            .iter()
+            .filter(|(_, changed_at)| now.duration_since(**changed_at) >= debounce)
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        let mut changed = HashSet::default();
+        for path in ready.iter() {
+            pending.remove(path);
+            if !changed.insert(path.clone()) {
+                continue;
+            }
+            let relative_path = path.strip_prefix(&asset_io.root_path).unwrap();
+            // files that are only read by loaders, like glTF buffers, aren't sources
+            let is_source = asset_server
+                .server
+                .asset_sources
+                .read()
+                .contains_key(&SourcePathId::from(relative_path));
+            if is_source {
+                let _ = asset_server.load_untracked(relative_path.into(), true);
+            }
+            // reload the sources whose loader read the changed file
+            for dependant in asset_server.get_source_dependants(relative_path) {
+                if changed.insert(asset_io.root_path.join(&dependant)) {
+                    let _ = asset_server.load_untracked(dependant.as_path().into(), true);
                 }
-                changed.extend(paths);
             }
         }
     }
@@ -0,0 +1,265 @@
+use crate::{AssetIo, AssetIoError};
+use anyhow::Result;
+use bevy_log::warn;
+use bevy_utils::{BoxedFuture, Duration, HashMap};
+use parking_lot::RwLock;
+use std::{
+    collections::VecDeque,
+    path::{Component, Path, PathBuf},
+};
+
+/// An [`AssetIo`] that fetches assets over HTTP(S), so content can be streamed from a web server
+/// or a CDN. It works on wasm, where it uses the `fetch` API of the browser, and on native
+/// platforms.
+///
+/// Failed requests are retried with an exponential backoff, unless the server responded that the
+/// asset doesn't exist. Fetched assets are kept in an in-memory cache, so assets that are loaded
+/// again after being unloaded aren't fetched again.
+///
+/// Directories can't be listed over HTTP, so [`AssetServer::load_folder`] doesn't find any asset,
+/// and changes aren't watched.
+///
+/// [`AssetServer::load_folder`]: crate::AssetServer::load_folder
+pub struct HttpAssetIo {
+    base_url: String,
+    retries: u32,
+    retry_delay: Duration,
+    cache: RwLock<HttpCache>,
+}
+
+impl HttpAssetIo {
+    /// Fetches assets relative to `base_url`, like `https://cdn.example.com/assets`.
+    pub fn new<S: Into<String>>(base_url: S) -> Self {
+        HttpAssetIo {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            retries: 3,
+            retry_delay: Duration::from_millis(500),
+            cache: RwLock::new(HttpCache::new(32 * 1024 * 1024)),
+        }
+    }
+
+    /// Sets how many times a failed request is retried. Defaults to 3.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets the delay before the first retry of a failed request, which doubles with each retry.
+    /// Defaults to 500ms.
+    pub fn with_retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Sets the maximum number of bytes kept in the cache, the assets fetched first are evicted
+    /// first. A size of 0 disables the cache. Defaults to 32MiB.
+    pub fn with_cache_size(self, max_size: usize) -> Self {
+        self.cache.write().set_max_size(max_size);
+        self
+    }
+
+    pub fn clear_cache(&self) {
+        self.cache.write().clear();
+    }
+
+    /// The URL an asset is fetched from.
+    pub fn url(&self, path: &Path) -> String {
+        let mut url = self.base_url.clone();
+        for component in path.components() {
+            if let Component::Normal(name) = component {
+                url.push('/');
+                url.push_str(&name.to_string_lossy());
+            }
+        }
+        url
+    }
+}
+
+impl AssetIo for HttpAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            let url = self.url(path);
+            let cached = self.cache.read().get(&url).cloned();
+            if let Some(bytes) = cached {
+                return Ok(bytes);
+            }
+
+            let mut attempt = 0;
+            let bytes = loop {
+                match fetch(&url).await {
+                    Ok(bytes) => break bytes,
+                    Err(FetchError::NotFound) => {
+                        return Err(AssetIoError::NotFound(path.to_owned()))
+                    }
+                    Err(FetchError::Retryable(message)) if attempt < self.retries => {
+                        attempt += 1;
+                        warn!(
+                            "failed to fetch {} ({}), retrying ({}/{})",
+                            url, message, attempt, self.retries
+                        );
+                        sleep(self.retry_delay * (1 << (attempt - 1).min(16))).await;
+                    }
+                    Err(FetchError::Retryable(message)) | Err(FetchError::Fatal(message)) => {
+                        return Err(AssetIoError::HttpError(format!("{}: {}", url, message)))
+                    }
+                }
+            };
+            self.cache.write().insert(url, &bytes);
+            Ok(bytes)
+        })
+    }
+
+    fn read_directory(
+        &self,
+        _path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        Ok(Box::new(std::iter::empty::<PathBuf>()))
+    }
+
+    fn is_directory(&self, _path: &Path) -> bool {
+        false
+    }
+
+    fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+}
+
+/// The assets fetched by an [`HttpAssetIo`], by URL.
+struct HttpCache {
+    entries: HashMap<String, Vec<u8>>,
+    order: VecDeque<String>,
+    size: usize,
+    max_size: usize,
+}
+
+impl HttpCache {
+    fn new(max_size: usize) -> Self {
+        HttpCache {
+            entries: Default::default(),
+            order: Default::default(),
+            size: 0,
+            max_size,
+        }
+    }
+
+    fn get(&self, url: &str) -> Option<&Vec<u8>> {
+        self.entries.get(url)
+    }
+
+    fn insert(&mut self, url: String, bytes: &[u8]) {
+        if bytes.len() > self.max_size || self.entries.contains_key(&url) {
+            return;
+        }
+        self.size += bytes.len();
+        self.entries.insert(url.clone(), bytes.to_vec());
+        self.order.push_back(url);
+        self.evict();
+    }
+
+    fn set_max_size(&mut self, max_size: usize) {
+        self.max_size = max_size;
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        while self.size > self.max_size {
+            let url = match self.order.pop_front() {
+                Some(url) => url,
+                None => break,
+            };
+            if let Some(bytes) = self.entries.remove(&url) {
+                self.size -= bytes.len();
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+        self.size = 0;
+    }
+}
+
+enum FetchError {
+    NotFound,
+    /// Network errors, server errors and rate limiting, which may not happen again
+    Retryable(String),
+    Fatal(String),
+}
+
+impl FetchError {
+    fn from_status(status: u16) -> Self {
+        match status {
+            404 | 410 => FetchError::NotFound,
+            408 | 429 | 500..=599 => FetchError::Retryable(format!("status {}", status)),
+            _ => FetchError::Fatal(format!("status {}", status)),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn fetch(url: &str) -> Result<Vec<u8>, FetchError> {
+    use std::io::Read;
+
+    // the asset server loads assets on the io task pool, where blocking is fine
+    match ureq::get(url).call() {
+        Ok(response) => {
+            let mut bytes = Vec::new();
+            response
+                .into_reader()
+                .read_to_end(&mut bytes)
+                .map_err(|error| FetchError::Retryable(error.to_string()))?;
+            Ok(bytes)
+        }
+        Err(ureq::Error::Status(status, _)) => Err(FetchError::from_status(status)),
+        Err(error) => Err(FetchError::Retryable(error.to_string())),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn fetch(url: &str) -> Result<Vec<u8>, FetchError> {
+    use js_sys::Uint8Array;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::Response;
+
+    let window = web_sys::window().unwrap();
+    let response = JsFuture::from(window.fetch_with_str(url))
+        .await
+        .map_err(|error| FetchError::Retryable(format!("{:?}", error)))?;
+    let response: Response = response.dyn_into().unwrap();
+    if !response.ok() {
+        return Err(FetchError::from_status(response.status()));
+    }
+    let buffer = response
+        .array_buffer()
+        .map_err(|error| FetchError::Fatal(format!("{:?}", error)))?;
+    let data = JsFuture::from(buffer)
+        .await
+        .map_err(|error| FetchError::Retryable(format!("{:?}", error)))?;
+    Ok(Uint8Array::new(&data).to_vec())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn sleep(duration: Duration) {
+    std::thread::sleep(duration);
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn sleep(duration: Duration) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        web_sys::window()
+            .unwrap()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                &resolve,
+                duration.as_millis() as i32,
+            )
+            .unwrap();
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
@@ -0,0 +1,310 @@
+use crate::{AssetIo, AssetIoError, AssetReader, AssetServer, SourcePathId};
+use anyhow::Result;
+use bevy_ecs::{event::EventWriter, system::Res};
+use bevy_log::warn;
+use bevy_utils::{BoxedFuture, HashSet};
+use parking_lot::{Mutex, RwLock};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// An [`AssetIo`] made of layers of other [`AssetIo`]s, like the base game, DLC packs and user
+/// mods. Layers with a higher priority override the assets of lower layers path by path: an
+/// asset is loaded from the highest layer that has it.
+///
+/// Layers can be mounted and unmounted at runtime. The assets whose live layer changed are
+/// reloaded, and an [`AssetLayerEvent`] is sent for each of them.
+///
+/// ```
+/// # use bevy_asset::{AssetServer, FileAssetIo, LayeredAssetIo};
+/// # use bevy_ecs::prelude::*;
+/// fn enable_mod(asset_server: Res<AssetServer>) {
+///     if let Some(layers) = asset_server.asset_io().downcast_ref::<LayeredAssetIo>() {
+///         layers.mount("my_mod", 10, FileAssetIo::new("mods/my_mod"));
+///     }
+/// }
+/// # enable_mod.system();
+/// ```
+#[derive(Default)]
+pub struct LayeredAssetIo {
+    /// Sorted by priority, the highest last
+    layers: RwLock<Vec<AssetLayer>>,
+    events: Mutex<Vec<AssetLayerEvent>>,
+}
+
+struct AssetLayer {
+    name: String,
+    priority: i32,
+    io: Arc<dyn AssetIo>,
+    /// The files of the layer when it was mounted, used to find which assets it overrides
+    files: HashSet<PathBuf>,
+}
+
+/// Sent when mounting or unmounting a layer of a [`LayeredAssetIo`] changes the layer an asset
+/// is loaded from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetLayerEvent {
+    pub path: PathBuf,
+    /// The layer the asset was loaded from, if any
+    pub previous_layer: Option<String>,
+    /// The layer the asset is now loaded from, `None` if no layer has it anymore
+    pub layer: Option<String>,
+}
+
+impl LayeredAssetIo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_layer<S: Into<String>, I: AssetIo>(self, name: S, priority: i32, io: I) -> Self {
+        self.mount(name, priority, io);
+        self
+    }
+
+    /// Mounts a layer, replacing the layer with the same name. Of layers with the same priority,
+    /// the one mounted last overrides the others.
+    pub fn mount<S: Into<String>, I: AssetIo>(&self, name: S, priority: i32, io: I) {
+        self.mount_boxed(name, priority, Box::new(io));
+    }
+
+    pub fn mount_boxed<S: Into<String>>(&self, name: S, priority: i32, io: Box<dyn AssetIo>) {
+        let name = name.into();
+        let mut files = HashSet::default();
+        if let Err(err) = collect_files(&*io, Path::new(""), &mut files) {
+            warn!(
+                "failed to list the files of the asset layer {}: {:?}",
+                name, err
+            );
+        }
+        let mut layers = self.layers.write();
+        let mut changed_paths = files.clone();
+        if let Some(index) = layers.iter().position(|layer| layer.name == name) {
+            changed_paths.extend(layers[index].files.iter().cloned());
+        }
+        let previous = live_layers(&layers, &changed_paths);
+
+        layers.retain(|layer| layer.name != name);
+        let index = layers
+            .iter()
+            .position(|layer| layer.priority > priority)
+            .unwrap_or(layers.len());
+        layers.insert(
+            index,
+            AssetLayer {
+                name,
+                priority,
+                io: Arc::from(io),
+                files,
+            },
+        );
+        self.push_events(&layers, previous);
+    }
+
+    /// Unmounts the layer with the given name, returning whether it was mounted.
+    pub fn unmount(&self, name: &str) -> bool {
+        let mut layers = self.layers.write();
+        let index = match layers.iter().position(|layer| layer.name == name) {
+            Some(index) => index,
+            None => return false,
+        };
+        let previous = live_layers(&layers, &layers[index].files);
+        layers.remove(index);
+        self.push_events(&layers, previous);
+        true
+    }
+
+    /// The names and priorities of the mounted layers, the highest priority first.
+    pub fn layers(&self) -> Vec<(String, i32)> {
+        self.layers
+            .read()
+            .iter()
+            .rev()
+            .map(|layer| (layer.name.clone(), layer.priority))
+            .collect()
+    }
+
+    /// The name of the layer the asset at `path` is loaded from, among the files the layers had
+    /// when they were mounted.
+    pub fn get_live_layer(&self, path: &Path) -> Option<String> {
+        live_layer(&self.layers.read(), path).map(|layer| layer.name.clone())
+    }
+
+    /// Takes the events of the layers mounted and unmounted since the last call.
+    pub fn drain_events(&self) -> Vec<AssetLayerEvent> {
+        std::mem::take(&mut *self.events.lock())
+    }
+
+    fn push_events(&self, layers: &[AssetLayer], previous: Vec<(PathBuf, Option<String>)>) {
+        let mut events = self.events.lock();
+        for (path, previous_layer) in previous {
+            let layer = live_layer(layers, &path).map(|layer| layer.name.clone());
+            if layer != previous_layer {
+                events.push(AssetLayerEvent {
+                    path,
+                    previous_layer,
+                    layer,
+                });
+            }
+        }
+    }
+
+    /// The layers, the highest priority first. They are cloned so that the lock isn't held while
+    /// loading.
+    fn ordered_layers(&self) -> Vec<Arc<dyn AssetIo>> {
+        self.layers
+            .read()
+            .iter()
+            .rev()
+            .map(|layer| layer.io.clone())
+            .collect()
+    }
+}
+
+fn live_layer<'a>(layers: &'a [AssetLayer], path: &Path) -> Option<&'a AssetLayer> {
+    layers.iter().rev().find(|layer| layer.files.contains(path))
+}
+
+fn live_layers(layers: &[AssetLayer], paths: &HashSet<PathBuf>) -> Vec<(PathBuf, Option<String>)> {
+    paths
+        .iter()
+        .map(|path| {
+            let layer = live_layer(layers, path).map(|layer| layer.name.clone());
+            (path.clone(), layer)
+        })
+        .collect()
+}
+
+fn collect_files(
+    io: &dyn AssetIo,
+    path: &Path,
+    files: &mut HashSet<PathBuf>,
+) -> Result<(), AssetIoError> {
+    if !io.is_directory(path) {
+        return Ok(());
+    }
+    for child in io.read_directory(path)? {
+        if io.is_directory(&child) {
+            collect_files(io, &child, files)?;
+        } else {
+            files.insert(child);
+        }
+    }
+    Ok(())
+}
+
+impl AssetIo for LayeredAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        let layers = self.ordered_layers();
+        Box::pin(async move {
+            for io in layers.iter() {
+                match io.load_path(path).await {
+                    Err(AssetIoError::NotFound(_)) => continue,
+                    result => return result,
+                }
+            }
+            Err(AssetIoError::NotFound(path.to_owned()))
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        let mut children = HashSet::default();
+        for io in self.ordered_layers() {
+            if io.is_directory(path) {
+                children.extend(io.read_directory(path)?);
+            }
+        }
+        if children.is_empty() && !self.is_directory(path) {
+            return Err(AssetIoError::NotFound(path.to_owned()));
+        }
+        Ok(Box::new(children.into_iter()))
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        self.ordered_layers().iter().any(|io| io.is_directory(path))
+    }
+
+    fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+        for layer in self.layers.read().iter() {
+            if layer.files.contains(path) {
+                layer.io.watch_path_for_changes(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        for io in self.ordered_layers() {
+            io.watch_for_changes()?;
+        }
+        Ok(())
+    }
+
+    fn read_path<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Box<dyn AssetReader>, AssetIoError>> {
+        let layers = self.ordered_layers();
+        Box::pin(async move {
+            for io in layers.iter() {
+                match io.read_path(path).await {
+                    Err(AssetIoError::NotFound(_)) => continue,
+                    result => return result,
+                }
+            }
+            Err(AssetIoError::NotFound(path.to_owned()))
+        })
+    }
+
+    fn save_path<'a>(
+        &'a self,
+        path: &'a Path,
+        bytes: &'a [u8],
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        // assets are saved to the layer they are loaded from, new assets to the highest layer
+        let io = {
+            let layers = self.layers.read();
+            live_layer(&layers, path)
+                .or_else(|| layers.last())
+                .map(|layer| layer.io.clone())
+        };
+        Box::pin(async move {
+            match io {
+                Some(io) => io.save_path(path, bytes).await,
+                None => Err(AssetIoError::SaveNotSupported(path.to_owned())),
+            }
+        })
+    }
+}
+
+/// Reloads the assets whose live layer changed when layers of the [`LayeredAssetIo`] were mounted
+/// or unmounted, and sends their [`AssetLayerEvent`]s.
+pub fn layered_asset_io_system(
+    asset_server: Res<AssetServer>,
+    mut layer_events: EventWriter<AssetLayerEvent>,
+) {
+    let asset_io = match asset_server.asset_io().downcast_ref::<LayeredAssetIo>() {
+        Some(asset_io) => asset_io,
+        None => return,
+    };
+    for event in asset_io.drain_events() {
+        let is_source = asset_server
+            .server
+            .asset_sources
+            .read()
+            .contains_key(&SourcePathId::from(event.path.as_path()));
+        // assets that no layer has anymore keep their last loaded version
+        if event.layer.is_some() {
+            if is_source {
+                let _ = asset_server.load_untracked(event.path.as_path().into(), true);
+            }
+            for dependant in asset_server.get_source_dependants(&event.path) {
+                let _ = asset_server.load_untracked(dependant.as_path().into(), true);
+            }
+        }
+        layer_events.send(event);
+    }
+}
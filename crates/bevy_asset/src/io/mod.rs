@@ -1,19 +1,27 @@
 #[cfg(target_os = "android")]
 mod android_asset_io;
+mod archive_asset_io;
 #[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
 mod file_asset_io;
+#[cfg(feature = "http_asset_io")]
+mod http_asset_io;
+mod layered_asset_io;
 #[cfg(target_arch = "wasm32")]
 mod wasm_asset_io;
 
 #[cfg(target_os = "android")]
 pub use android_asset_io::*;
+pub use archive_asset_io::*;
 #[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
 pub use file_asset_io::*;
+#[cfg(feature = "http_asset_io")]
+pub use http_asset_io::*;
+pub use layered_asset_io::*;
 #[cfg(target_arch = "wasm32")]
 pub use wasm_asset_io::*;
 
 use anyhow::Result;
-use bevy_utils::BoxedFuture;
+use bevy_utils::{BoxedFuture, Duration};
 use downcast_rs::{impl_downcast, Downcast};
 use std::{
     io,
@@ -30,6 +38,12 @@ pub enum AssetIoError {
     Io(#[from] io::Error),
     #[error("failed to watch path: {0}")]
     PathWatchError(PathBuf),
+    #[error("invalid asset archive: {0}")]
+    InvalidArchive(String),
+    #[error("http request failed: {0}")]
+    HttpError(String),
+    #[error("saving assets is not supported by the asset io: {0}")]
+    SaveNotSupported(PathBuf),
 }
 
 /// Handles load requests from an AssetServer
@@ -42,6 +56,119 @@ pub trait AssetIo: Downcast + Send + Sync + 'static {
     fn is_directory(&self, path: &Path) -> bool;
     fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError>;
     fn watch_for_changes(&self) -> Result<(), AssetIoError>;
+
+    /// Writes the bytes of an asset saved with an [`AssetSaver`](crate::AssetSaver). Saving isn't
+    /// supported by default.
+    fn save_path<'a>(
+        &'a self,
+        path: &'a Path,
+        _bytes: &'a [u8],
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move { Err(AssetIoError::SaveNotSupported(path.to_owned())) })
+    }
+
+    /// Opens the file at `path` to read it incrementally, for streaming loaders, see
+    /// [`AssetLoader::load_streaming`](crate::AssetLoader::load_streaming). By default, the whole
+    /// file is loaded with [`AssetIo::load_path`] and read from memory.
+    fn read_path<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Box<dyn AssetReader>, AssetIoError>> {
+        Box::pin(async move {
+            let bytes = self.load_path(path).await?;
+            Ok(Box::new(BytesAssetReader::new(bytes)) as Box<dyn AssetReader>)
+        })
+    }
 }
 
 impl_downcast!(AssetIo);
+
+/// Reads the bytes of a file incrementally, see [`AssetIo::read_path`]
+pub trait AssetReader: Send {
+    /// Reads the next bytes of the file into `buf`, returning how many bytes were read. Returns 0
+    /// once the whole file was read.
+    fn read<'a>(&'a mut self, buf: &'a mut [u8]) -> BoxedFuture<'a, Result<usize, AssetIoError>>;
+
+    /// The size of the file in bytes, if it is known
+    fn size_hint(&self) -> Option<u64> {
+        None
+    }
+
+    /// Reads the rest of the file into `bytes`, returning how many bytes were read.
+    fn read_to_end<'a>(
+        &'a mut self,
+        bytes: &'a mut Vec<u8>,
+    ) -> BoxedFuture<'a, Result<usize, AssetIoError>> {
+        Box::pin(async move {
+            let start = bytes.len();
+            let mut chunk = vec![0; 64 * 1024];
+            loop {
+                let read = self.read(&mut chunk).await?;
+                if read == 0 {
+                    return Ok(bytes.len() - start);
+                }
+                bytes.extend_from_slice(&chunk[..read]);
+            }
+        })
+    }
+}
+
+/// An [`AssetReader`] reading a file that is already in memory
+pub struct BytesAssetReader {
+    bytes: Vec<u8>,
+    position: usize,
+}
+
+impl BytesAssetReader {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        BytesAssetReader { bytes, position: 0 }
+    }
+}
+
+impl AssetReader for BytesAssetReader {
+    fn read<'a>(&'a mut self, buf: &'a mut [u8]) -> BoxedFuture<'a, Result<usize, AssetIoError>> {
+        let remaining = &self.bytes[self.position..];
+        let read = buf.len().min(remaining.len());
+        buf[..read].copy_from_slice(&remaining[..read]);
+        self.position += read;
+        Box::pin(async move { Ok(read) })
+    }
+
+    fn size_hint(&self) -> Option<u64> {
+        Some(self.bytes.len() as u64)
+    }
+}
+
+/// Limits which assets are watched for changes by hot reloading, see
+/// [`AssetServer::watch_for_changes_with`](crate::AssetServer::watch_for_changes_with).
+#[derive(Debug, Clone, Default)]
+pub struct WatchSettings {
+    /// The folders, relative to the asset folder, in which assets are watched. Assets in every
+    /// folder are watched if empty.
+    pub folders: Vec<PathBuf>,
+    /// The extensions of the assets that are watched. Assets with any extension are watched if
+    /// empty.
+    pub extensions: Vec<String>,
+    /// How long a file must be left unchanged before it's reloaded, so that saving a file several
+    /// times in a row only reloads it once.
+    pub debounce: Duration,
+}
+
+impl WatchSettings {
+    /// Whether the asset at `path` should be watched.
+    pub fn matches(&self, path: &Path) -> bool {
+        let in_folder =
+            self.folders.is_empty() || self.folders.iter().any(|folder| path.starts_with(folder));
+        let has_extension = self.extensions.is_empty()
+            || path
+                .file_name()
+                .and_then(|file_name| file_name.to_str())
+                .is_some_and(|file_name| {
+                    let file_name = file_name.to_lowercase();
+                    self.extensions.iter().any(|extension| {
+                        file_name.ends_with(&format!(".{}", extension.to_lowercase()))
+                    })
+                });
+        in_folder && has_extension
+    }
+}
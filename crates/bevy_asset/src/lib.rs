@@ -9,8 +9,12 @@ mod filesystem_watcher;
 mod handle;
 mod info;
 mod io;
+mod loaded_folder;
 mod loader;
+mod loading_group;
 mod path;
+mod processor;
+mod saver;
 
 pub mod prelude {
     #[doc(hidden)]
@@ -23,8 +27,12 @@ pub use bevy_utils::BoxedFuture;
 pub use handle::*;
 pub use info::*;
 pub use io::*;
+pub use loaded_folder::*;
 pub use loader::*;
+pub use loading_group::*;
 pub use path::*;
+pub use processor::*;
+pub use saver::*;
 
 use bevy_app::{prelude::Plugin, App};
 use bevy_ecs::schedule::{StageLabel, SystemStage};
@@ -86,6 +94,10 @@ impl Plugin for AssetPlugin {
             let source = create_platform_default_asset_io(app);
 
             let asset_server = AssetServer::with_boxed_io(source, task_pool);
+            #[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
+            asset_server.set_processed_asset_cache(Some(
+                FileAssetIo::get_root_path().join(".processed_assets"),
+            ));
 
             app.insert_resource(asset_server);
         }
@@ -101,9 +113,16 @@ impl Plugin for AssetPlugin {
             SystemStage::parallel(),
         )
         .register_type::<HandleId>()
+        .add_event::<AssetLayerEvent>()
+        .add_event::<AssetLoadError>()
         .add_system_to_stage(
             bevy_app::CoreStage::PreUpdate,
             asset_server::free_unused_assets_system,
+        )
+        .add_system_to_stage(AssetStage::LoadAssets, io::layered_asset_io_system)
+        .add_system_to_stage(
+            AssetStage::LoadAssets,
+            asset_server::asset_load_error_system,
         );
 
         #[cfg(all(
@@ -0,0 +1,94 @@
+use crate::{Asset, AssetPathPattern, Handle, HandleUntyped};
+use std::path::{Path, PathBuf};
+
+/// How [`AssetServer::load_folder_with`](crate::AssetServer::load_folder_with) finds the assets of
+/// a folder.
+#[derive(Debug, Clone)]
+pub struct LoadFolderSettings {
+    /// Whether the assets of the sub folders are loaded
+    pub recursive: bool,
+    /// Only the assets whose path, from the asset folder, matches the pattern are loaded
+    pub pattern: Option<AssetPathPattern>,
+}
+
+impl Default for LoadFolderSettings {
+    fn default() -> Self {
+        LoadFolderSettings {
+            recursive: true,
+            pattern: None,
+        }
+    }
+}
+
+/// The assets loaded from a folder, see
+/// [`AssetServer::load_folder_with`](crate::AssetServer::load_folder_with). Assets and folders are
+/// sorted by path, so the listing doesn't depend on the platform.
+#[derive(Debug, Default)]
+pub struct LoadedFolder {
+    pub path: PathBuf,
+    /// The assets directly in the folder, with their path
+    pub assets: Vec<(PathBuf, HandleUntyped)>,
+    /// The sub folders, if they were loaded recursively
+    pub folders: Vec<LoadedFolder>,
+}
+
+impl LoadedFolder {
+    /// The paths of the assets of the folder and its sub folders.
+    pub fn paths(&self) -> Vec<&Path> {
+        let mut paths = Vec::new();
+        self.visit(&mut |path, _| paths.push(path));
+        paths
+    }
+
+    /// The handles of the assets of the folder and its sub folders.
+    pub fn handles(&self) -> Vec<HandleUntyped> {
+        let mut handles = Vec::new();
+        self.visit(&mut |_, handle| handles.push(handle.clone()));
+        handles
+    }
+
+    /// The typed handles of the assets of the folder and its sub folders. The handles aren't
+    /// checked to be of type `T`, so the folder should only have assets of type `T`, or be loaded
+    /// with a pattern that only matches them.
+    pub fn typed<T: Asset>(&self) -> Vec<Handle<T>> {
+        let mut handles = Vec::new();
+        self.visit(&mut |_, handle| handles.push(handle.clone().typed()));
+        handles
+    }
+
+    pub fn into_handles(self) -> Vec<HandleUntyped> {
+        let mut handles = Vec::new();
+        self.into_handles_inner(&mut handles);
+        handles
+    }
+
+    /// The number of assets of the folder and its sub folders.
+    pub fn len(&self) -> usize {
+        self.assets.len()
+            + self
+                .folders
+                .iter()
+                .map(|folder| folder.len())
+                .sum::<usize>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn visit<'a>(&'a self, visitor: &mut impl FnMut(&'a Path, &'a HandleUntyped)) {
+        for (path, handle) in self.assets.iter() {
+            visitor(path, handle);
+        }
+        for folder in self.folders.iter() {
+            folder.visit(visitor);
+        }
+    }
+
+    fn into_handles_inner(self, handles: &mut Vec<HandleUntyped>) {
+        handles.extend(self.assets.into_iter().map(|(_, handle)| handle));
+        for folder in self.folders {
+            folder.into_handles_inner(handles);
+        }
+    }
+}
@@ -1,15 +1,18 @@
 use crate::{
-    path::AssetPath, AssetIo, AssetIoError, AssetMeta, AssetServer, Assets, Handle, HandleId,
-    RefChangeChannel,
+    path::AssetPath, AssetIo, AssetIoError, AssetMeta, AssetReader, AssetServer, Assets, Handle,
+    HandleId, RefChangeChannel,
 };
 use anyhow::Result;
 use bevy_ecs::system::{Res, ResMut};
 use bevy_reflect::{TypeUuid, TypeUuidDynamic};
 use bevy_tasks::TaskPool;
-use bevy_utils::{BoxedFuture, HashMap};
+use bevy_utils::{BoxedFuture, HashMap, Uuid};
 use crossbeam_channel::{Receiver, Sender};
 use downcast_rs::{impl_downcast, Downcast};
-use std::path::Path;
+use parking_lot::{Mutex, RwLock};
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
 
 /// A loader for an asset source
 pub trait AssetLoader: Send + Sync + 'static {
@@ -19,6 +22,29 @@ pub trait AssetLoader: Send + Sync + 'static {
         load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<(), anyhow::Error>>;
     fn extensions(&self) -> &[&str];
+
+    /// Whether the loader loads sources incrementally with [`AssetLoader::load_streaming`],
+    /// instead of with [`AssetLoader::load`] once their bytes are all in memory.
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    /// Loads a source incrementally from a reader, for very large sources like terrain
+    /// heightfields or long audio files. It's used instead of [`AssetLoader::load`] if the loader
+    /// [supports streaming](AssetLoader::supports_streaming), unless the source has an
+    /// [`AssetProcessor`](crate::AssetProcessor). Parts of the asset can be made available before
+    /// the whole source is read with [`LoadContext::set_partial_asset`].
+    fn load_streaming<'a>(
+        &'a self,
+        reader: &'a mut dyn AssetReader,
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            self.load(&bytes, load_context).await
+        })
+    }
 }
 
 pub trait Asset: TypeUuid + AssetDynamic {}
@@ -73,10 +99,15 @@ impl<T: Asset> From<LoadedAsset<T>> for BoxedLoadedAsset {
 pub struct LoadContext<'a> {
     pub(crate) ref_change_channel: &'a RefChangeChannel,
     pub(crate) asset_io: &'a dyn AssetIo,
+    pub(crate) asset_lifecycles: &'a RwLock<HashMap<Uuid, Box<dyn AssetLifecycle>>>,
     pub(crate) labeled_assets: HashMap<Option<String>, BoxedLoadedAsset>,
     pub(crate) path: &'a Path,
     pub(crate) version: usize,
     pub(crate) task_pool: &'a TaskPool,
+    /// The files read by the loader besides the source itself
+    pub(crate) source_dependencies: Mutex<Vec<PathBuf>>,
+    /// The content of the `.meta` file of the source, if it has one
+    pub(crate) import_settings: Option<Vec<u8>>,
 }
 
 impl<'a> LoadContext<'a> {
@@ -84,16 +115,20 @@ impl<'a> LoadContext<'a> {
         path: &'a Path,
         ref_change_channel: &'a RefChangeChannel,
         asset_io: &'a dyn AssetIo,
+        asset_lifecycles: &'a RwLock<HashMap<Uuid, Box<dyn AssetLifecycle>>>,
         version: usize,
         task_pool: &'a TaskPool,
     ) -> Self {
         Self {
             ref_change_channel,
             asset_io,
+            asset_lifecycles,
             labeled_assets: Default::default(),
             version,
             path,
             task_pool,
+            source_dependencies: Default::default(),
+            import_settings: None,
         }
     }
 
@@ -116,6 +151,28 @@ impl<'a> LoadContext<'a> {
         self.get_handle(AssetPath::new_ref(self.path(), Some(label)))
     }
 
+    /// Makes a partially loaded asset available in its [`Assets`] collection before the loader
+    /// finishes, like the first chunks of a streamed terrain. `label` is `None` for the default
+    /// asset of the source. Each partial asset replaces the previous one, and they are all
+    /// replaced by the asset set with [`LoadContext::set_default_asset`] or
+    /// [`LoadContext::set_labeled_asset`] once the source is loaded, each sending an
+    /// [`AssetEvent`](crate::AssetEvent).
+    pub fn set_partial_asset<T: Asset>(&self, label: Option<&str>, asset: T) {
+        let asset_lifecycles = self.asset_lifecycles.read();
+        let asset_lifecycle = asset_lifecycles.get(&T::TYPE_UUID).unwrap_or_else(|| {
+            panic!(
+                "Failed to find AssetLifecycle for {}. Are you sure this asset type has been \
+                    added to your app builder?",
+                std::any::type_name::<T>()
+            )
+        });
+        asset_lifecycle.create_asset(
+            AssetPath::new_ref(self.path, label).into(),
+            Box::new(asset),
+            self.version,
+        );
+    }
+
     pub fn get_mut_labeled_asset<T: Asset>(&mut self, label: &str) -> Option<&mut T> {
         assert!(!label.is_empty());
 
@@ -129,10 +186,37 @@ impl<'a> LoadContext<'a> {
         Handle::strong(id.into(), self.ref_change_channel.sender.clone())
     }
 
+    /// Reads the bytes of another file, like the buffers of a glTF file. The file is a dependency
+    /// of the source being loaded: the source is reloaded when the file changes, see
+    /// [`LoadContext::add_source_dependency`].
     pub async fn read_asset_bytes<P: AsRef<Path>>(&self, path: P) -> Result<Vec<u8>, AssetIoError> {
+        self.source_dependencies
+            .lock()
+            .push(path.as_ref().to_owned());
         self.asset_io.load_path(path.as_ref()).await
     }
 
+    /// Declares that the source being loaded depends on the file at `path`, relative to the asset
+    /// folder, so that hot reloading reloads the source when the file changes. Files read with
+    /// [`LoadContext::read_asset_bytes`] are added automatically.
+    pub fn add_source_dependency<P: Into<PathBuf>>(&mut self, path: P) {
+        self.source_dependencies.get_mut().push(path.into());
+    }
+
+    /// The import settings of the source, read from the RON `.meta` file next to it, like
+    /// `texture.png.meta` for `texture.png`. Each loader defines its own settings type, which
+    /// should use `#[serde(default)]` so the file only needs the settings that aren't the default.
+    /// Sources without a `.meta` file use the default settings.
+    pub fn import_settings<S: DeserializeOwned + Default>(&self) -> Result<S, ImportSettingsError> {
+        match &self.import_settings {
+            Some(bytes) => ron::de::from_bytes(bytes).map_err(|error| ImportSettingsError {
+                path: import_settings_path(self.path),
+                error,
+            }),
+            None => Ok(S::default()),
+        }
+    }
+
     pub fn get_asset_metas(&self) -> Vec<AssetMeta> {
         let mut asset_metas = Vec::new();
         for (label, asset) in self.labeled_assets.iter() {
@@ -150,6 +234,22 @@ impl<'a> LoadContext<'a> {
     }
 }
 
+/// The `.meta` file of a source could not be deserialized into the import settings of its loader
+#[derive(Error, Debug)]
+#[error("invalid import settings in {path}: {error}")]
+pub struct ImportSettingsError {
+    pub path: PathBuf,
+    pub error: ron::Error,
+}
+
+/// The path of the `.meta` file holding the import settings of the source at `path`, see
+/// [`LoadContext::import_settings`].
+pub fn import_settings_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_owned();
+    file_name.push(".meta");
+    path.with_file_name(file_name)
+}
+
 /// The result of loading an asset of type `T`
 #[derive(Debug)]
 pub struct AssetResult<T> {
@@ -168,6 +268,8 @@ pub struct AssetLifecycleChannel<T> {
 pub enum AssetLifecycleEvent<T> {
     Create(AssetResult<T>),
     Free(HandleId),
+    /// Saves the asset to the path, see [`AssetServer::save`]
+    Save(HandleId, PathBuf),
 }
 
 pub trait AssetLifecycle: Downcast + Send + Sync + 'static {
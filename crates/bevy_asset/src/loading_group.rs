@@ -0,0 +1,148 @@
+use crate::{
+    Asset, AssetPath, AssetServer, Handle, HandleId, HandleUntyped, LoadState, SourcePathId,
+};
+use bevy_utils::HashSet;
+
+/// A set of assets that are loaded together, like everything a level needs, with the
+/// [`LoadingProgress`] of all of them for loading screens.
+///
+/// The progress includes the dependencies of the assets, like the textures of a glTF file. The
+/// dependencies of an asset are only known once it loaded, so the total can grow while loading.
+///
+/// ```
+/// # use bevy_asset::{AssetServer, LoadingGroup};
+/// # use bevy_ecs::prelude::*;
+/// # struct Level { group: LoadingGroup }
+/// fn loading_screen(asset_server: Res<AssetServer>, level: Res<Level>) {
+///     let progress = level.group.progress(&asset_server);
+///     println!("loaded {} of {} assets", progress.loaded, progress.total);
+///     if progress.is_ready() {
+///         // start the level
+///     }
+/// }
+/// # loading_screen.system();
+/// ```
+#[derive(Debug, Default)]
+pub struct LoadingGroup {
+    handles: Vec<HandleUntyped>,
+}
+
+/// How far the assets of a [`LoadingGroup`] are loaded. Assets are counted once per file, so
+/// several labeled assets of a file are counted as one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LoadingProgress {
+    pub loaded: usize,
+    pub failed: usize,
+    pub total: usize,
+}
+
+impl LoadingProgress {
+    /// Whether every asset loaded successfully.
+    pub fn is_ready(&self) -> bool {
+        self.loaded == self.total
+    }
+
+    /// Whether every asset either loaded or failed to load.
+    pub fn is_finished(&self) -> bool {
+        self.loaded + self.failed == self.total
+    }
+
+    /// The fraction of the assets that either loaded or failed to load, from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (self.loaded + self.failed) as f32 / self.total as f32
+        }
+    }
+}
+
+impl LoadingGroup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads an asset with the [`AssetServer`] and adds it to the group.
+    pub fn load<'a, T: Asset, P: Into<AssetPath<'a>>>(
+        &mut self,
+        asset_server: &AssetServer,
+        path: P,
+    ) -> Handle<T> {
+        self.add(asset_server.load(path))
+    }
+
+    /// Adds an asset to the group. The group keeps it loaded until the group is dropped.
+    pub fn add<T: Asset>(&mut self, handle: Handle<T>) -> Handle<T> {
+        self.handles.push(handle.clone_untyped());
+        handle
+    }
+
+    pub fn add_untyped(&mut self, handle: HandleUntyped) {
+        self.handles.push(handle);
+    }
+
+    pub fn handles(&self) -> &[HandleUntyped] {
+        &self.handles
+    }
+
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.handles.clear();
+    }
+
+    /// How far the assets of the group and their dependencies are loaded. Assets that weren't
+    /// loaded by the [`AssetServer`], but added directly to their [`Assets`](crate::Assets)
+    /// collection, count as loaded.
+    pub fn progress(&self, asset_server: &AssetServer) -> LoadingProgress {
+        let mut progress = LoadingProgress::default();
+        let mut visited = HashSet::<SourcePathId>::default();
+        let mut queue = Vec::new();
+        for handle in &self.handles {
+            match handle.id {
+                HandleId::AssetPathId(id) => queue.push(id.source_path_id()),
+                HandleId::Id(..) => {
+                    progress.loaded += 1;
+                    progress.total += 1;
+                }
+            }
+        }
+
+        let asset_sources = asset_server.server.asset_sources.read();
+        while let Some(source_path_id) = queue.pop() {
+            if !visited.insert(source_path_id) {
+                continue;
+            }
+            progress.total += 1;
+            let info = match asset_sources.get(&source_path_id) {
+                Some(info) => info,
+                None => continue,
+            };
+            match info.load_state {
+                LoadState::Loaded => progress.loaded += 1,
+                LoadState::Failed => progress.failed += 1,
+                LoadState::NotLoaded | LoadState::Loading | LoadState::Unloaded => {}
+            }
+            if let Some(meta) = &info.meta {
+                queue.extend(
+                    meta.assets
+                        .iter()
+                        .flat_map(|asset| asset.dependencies.iter())
+                        .map(|dependency| dependency.get_id().source_path_id()),
+                );
+            }
+        }
+        progress
+    }
+
+    /// Whether every asset of the group and their dependencies loaded successfully.
+    pub fn is_ready(&self, asset_server: &AssetServer) -> bool {
+        self.progress(asset_server).is_ready()
+    }
+}
@@ -169,3 +169,63 @@ impl<'a> From<PathBuf> for AssetPath<'a> {
         }
     }
 }
+
+/// A pattern matching asset paths, where `*` matches any characters but `/`, `**` matches any
+/// characters and `?` matches a single character but `/`. For example `config/*.ron` matches the
+/// `.ron` files of the `config` folder and `levels/**/*.scn` matches the scenes in every folder of
+/// the `levels` folder. Labeled assets match the pattern of their source path.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AssetPathPattern(String);
+
+impl AssetPathPattern {
+    pub fn new<S: Into<String>>(pattern: S) -> Self {
+        AssetPathPattern(pattern.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The folder all the paths matching the pattern are in, made of the segments of the pattern
+    /// before the first wildcard.
+    pub fn base_folder(&self) -> PathBuf {
+        let mut segments = self.0.split('/').collect::<Vec<_>>();
+        segments.pop();
+        segments
+            .into_iter()
+            .take_while(|segment| !segment.contains(['*', '?']))
+            .collect()
+    }
+
+    pub fn matches(&self, asset_path: &AssetPath) -> bool {
+        let path = asset_path.path().to_string_lossy().replace('\\', "/");
+        glob_matches(self.0.as_bytes(), path.as_bytes())
+    }
+}
+
+impl<'a> From<&'a str> for AssetPathPattern {
+    fn from(pattern: &'a str) -> Self {
+        AssetPathPattern::new(pattern)
+    }
+}
+
+fn glob_matches(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some(b'*') if pattern.get(1) == Some(&b'*') => {
+            let rest = &pattern[2..];
+            // `**/` also matches no folder at all
+            if rest.first() == Some(&b'/') && glob_matches(&rest[1..], text) {
+                return true;
+            }
+            (0..=text.len()).any(|start| glob_matches(rest, &text[start..]))
+        }
+        Some(b'*') => (0..=text.len())
+            .take_while(|&start| start == 0 || text[start - 1] != b'/')
+            .any(|start| glob_matches(&pattern[1..], &text[start..])),
+        Some(b'?') => {
+            text.first().is_some_and(|&c| c != b'/') && glob_matches(&pattern[1..], &text[1..])
+        }
+        Some(c) => text.first() == Some(c) && glob_matches(&pattern[1..], &text[1..]),
+    }
+}
@@ -0,0 +1,36 @@
+use anyhow::Result;
+use bevy_utils::BoxedFuture;
+use std::path::Path;
+
+/// Processes the bytes of asset sources before their [`AssetLoader`](crate::AssetLoader) loads
+/// them, for work that is too expensive to do every time an asset is loaded, like compressing
+/// textures, generating mesh tangents or reducing animation keyframes. The output must be in a
+/// format the loader of the source can load.
+///
+/// The outputs are cached on disk, see [`AssetServer::set_processed_asset_cache`], and are only
+/// processed again when the source, or the [`version`](AssetProcessor::version) of the
+/// processor, changes.
+///
+/// [`AssetServer::set_processed_asset_cache`]: crate::AssetServer::set_processed_asset_cache
+pub trait AssetProcessor: Send + Sync + 'static {
+    fn process<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Vec<u8>, anyhow::Error>>;
+
+    /// The extensions of the sources this processor applies to.
+    fn extensions(&self) -> &[&str];
+
+    /// Identifies the outputs of the processor. It should be changed whenever a change of the
+    /// processor's code or settings changes its outputs, so that outdated cached outputs aren't
+    /// used.
+    fn version(&self) -> u64 {
+        0
+    }
+
+    /// The name of the processor, which is part of the key of its cached outputs.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
@@ -0,0 +1,14 @@
+use crate::Asset;
+use anyhow::Result;
+
+/// A saver for assets of a type, the counterpart of an [`AssetLoader`](crate::AssetLoader). It
+/// writes assets in a format the loader of its extensions can load, so editors and tools can save
+/// modified or generated assets with [`AssetServer::save`](crate::AssetServer::save).
+pub trait AssetSaver: Send + Sync + 'static {
+    type Asset: Asset;
+
+    fn save(&self, asset: &Self::Asset) -> Result<Vec<u8>, anyhow::Error>;
+
+    /// The extensions of the paths this saver saves assets to.
+    fn extensions(&self) -> &[&str];
+}
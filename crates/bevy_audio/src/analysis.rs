@@ -0,0 +1,346 @@
+use crate::AudioSink;
+use bevy_asset::{Handle, HandleId};
+use bevy_utils::{Duration, HashMap};
+use parking_lot::Mutex;
+use rodio::Source;
+use std::{any::TypeId, collections::VecDeque, f32::consts::PI, sync::Arc};
+
+/// The spectrum and the amplitude of playing sounds and [`AudioMixer`](crate::AudioMixer)
+/// channels, updated every frame, for rhythm games and audio visualizers. Sounds and channels
+/// are only analyzed once requested.
+///
+/// ```
+/// # use bevy_audio::{AudioAnalysis, AudioMixer};
+/// # use bevy_ecs::prelude::*;
+/// fn setup(mut analysis: ResMut<AudioAnalysis>) {
+///     analysis.analyze_channel(AudioMixer::MUSIC);
+/// }
+///
+/// fn pulse(analysis: Res<AudioAnalysis>) {
+///     if let Some(spectrum) = analysis.channel(AudioMixer::MUSIC) {
+///         let bass = spectrum.band(20.0, 150.0);
+///         println!("rms: {}, bass: {}", spectrum.rms, bass);
+///     }
+/// }
+/// # setup.system();
+/// # pulse.system();
+/// ```
+#[derive(Debug)]
+pub struct AudioAnalysis {
+    fft_size: usize,
+    pub(crate) channels: HashMap<String, ChannelAnalysis>,
+    pub(crate) sounds: HashMap<HandleId, Option<AudioSpectrum>>,
+}
+
+impl Default for AudioAnalysis {
+    fn default() -> Self {
+        AudioAnalysis {
+            fft_size: 1024,
+            channels: Default::default(),
+            sounds: Default::default(),
+        }
+    }
+}
+
+impl AudioAnalysis {
+    pub const MAX_FFT_SIZE: usize = ANALYSIS_BUFFER_LEN;
+
+    /// The number of samples analyzed, which is also twice the number of frequency bins.
+    pub fn fft_size(&self) -> usize {
+        self.fft_size
+    }
+
+    /// Sets the number of samples analyzed, rounded up to a power of two and at most
+    /// [`AudioAnalysis::MAX_FFT_SIZE`]. Larger sizes have a finer frequency resolution, but react
+    /// slower. Defaults to 1024.
+    pub fn set_fft_size(&mut self, fft_size: usize) {
+        self.fft_size = fft_size.max(2).next_power_of_two().min(Self::MAX_FFT_SIZE);
+    }
+
+    /// Analyzes the sounds of a channel, including the sounds of the channels routed to it.
+    pub fn analyze_channel<S: Into<String>>(&mut self, name: S) {
+        self.channels.entry(name.into()).or_default();
+    }
+
+    pub fn stop_analyzing_channel(&mut self, name: &str) {
+        self.channels.remove(name);
+    }
+
+    pub fn analyze_sound(&mut self, sink: &Handle<AudioSink>) {
+        self.sounds.entry(sink.id).or_insert(None);
+    }
+
+    pub fn stop_analyzing_sound(&mut self, sink: &Handle<AudioSink>) {
+        self.sounds.remove(&sink.id);
+    }
+
+    /// The spectrum of an analyzed channel, `None` until it's first analyzed.
+    pub fn channel(&self, name: &str) -> Option<&AudioSpectrum> {
+        self.channels
+            .get(name)
+            .and_then(|channel| channel.spectrum.as_ref())
+    }
+
+    /// The spectrum of an analyzed sound, `None` until it starts playing.
+    pub fn sound(&self, sink: &Handle<AudioSink>) -> Option<&AudioSpectrum> {
+        self.sounds
+            .get(&sink.id)
+            .and_then(|spectrum| spectrum.as_ref())
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ChannelAnalysis {
+    spectrum: Option<AudioSpectrum>,
+    /// The latest samples of the sounds of the channel and their sample rate, by the type of
+    /// their source, as each type of source is played by its own system
+    windows: HashMap<TypeId, (Vec<f32>, u32)>,
+}
+
+impl ChannelAnalysis {
+    /// Sets the samples of the sounds with the source type `source`, and updates the spectrum
+    /// of the channel with the samples of the sounds of every type.
+    pub(crate) fn update(&mut self, source: TypeId, window: Vec<f32>, sample_rate: u32) {
+        let len = window.len();
+        self.windows.insert(source, (window, sample_rate));
+        self.windows.retain(|_, (window, _)| window.len() == len);
+        let mut mixed = vec![0.0; len];
+        let mut mixed_sample_rate = 0;
+        for (window, sample_rate) in self.windows.values() {
+            for (mixed, sample) in mixed.iter_mut().zip(window.iter()) {
+                *mixed += sample;
+            }
+            mixed_sample_rate = mixed_sample_rate.max(*sample_rate);
+        }
+        self.spectrum = Some(AudioSpectrum::from_samples(&mixed, mixed_sample_rate));
+    }
+}
+
+/// The amplitude and the frequency spectrum of the latest samples of a sound, mixed down to
+/// mono.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AudioSpectrum {
+    /// The root mean square of the samples
+    pub rms: f32,
+    /// The highest absolute value of the samples
+    pub peak: f32,
+    /// The amplitudes of the frequency bins, from 0Hz to half the sample rate
+    pub magnitudes: Vec<f32>,
+    pub sample_rate: u32,
+}
+
+impl AudioSpectrum {
+    /// Analyzes the samples, whose length must be a power of two.
+    pub(crate) fn from_samples(samples: &[f32], sample_rate: u32) -> Self {
+        let len = samples.len();
+        let mut sum_of_squares = 0.0;
+        let mut peak = 0f32;
+        for sample in samples {
+            sum_of_squares += sample * sample;
+            peak = peak.max(sample.abs());
+        }
+        let rms = if len == 0 {
+            0.0
+        } else {
+            (sum_of_squares / len as f32).sqrt()
+        };
+
+        // a Hann window, to reduce the leakage between bins
+        let window = |index: usize| 0.5 - 0.5 * (2.0 * PI * index as f32 / len as f32).cos();
+        let mut real: Vec<f32> = samples
+            .iter()
+            .enumerate()
+            .map(|(index, sample)| sample * window(index))
+            .collect();
+        let mut imaginary = vec![0.0; len];
+        fft(&mut real, &mut imaginary);
+        // scaled so that a full scale sine has a magnitude of about 1
+        let scale = 4.0 / len.max(1) as f32;
+        let magnitudes = real
+            .iter()
+            .zip(imaginary.iter())
+            .take(len / 2)
+            .map(|(real, imaginary)| (real * real + imaginary * imaginary).sqrt() * scale)
+            .collect();
+        AudioSpectrum {
+            rms,
+            peak,
+            magnitudes,
+            sample_rate,
+        }
+    }
+
+    /// The center frequency of a bin in Hz.
+    pub fn bin_frequency(&self, bin: usize) -> f32 {
+        bin as f32 * self.sample_rate as f32 / (self.magnitudes.len() * 2).max(1) as f32
+    }
+
+    /// The average magnitude of the bins between `low` and `high` Hz.
+    pub fn band(&self, low: f32, high: f32) -> f32 {
+        let mut sum = 0.0;
+        let mut count = 0;
+        for (bin, magnitude) in self.magnitudes.iter().enumerate() {
+            let frequency = self.bin_frequency(bin);
+            if (low..=high).contains(&frequency) {
+                sum += magnitude;
+                count += 1;
+            }
+        }
+        if count == 0 {
+            0.0
+        } else {
+            sum / count as f32
+        }
+    }
+}
+
+/// An in place radix-2 fast Fourier transform, of buffers whose length is a power of two.
+fn fft(real: &mut [f32], imaginary: &mut [f32]) {
+    let len = real.len();
+    if len < 2 {
+        return;
+    }
+    // bit reversal permutation
+    let mut j = 0;
+    for i in 1..len {
+        let mut bit = len >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            real.swap(i, j);
+            imaginary.swap(i, j);
+        }
+    }
+
+    let mut size = 2;
+    while size <= len {
+        let angle = -2.0 * PI / size as f32;
+        for start in (0..len).step_by(size) {
+            for k in 0..size / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let even = start + k;
+                let odd = even + size / 2;
+                let odd_real = real[odd] * cos - imaginary[odd] * sin;
+                let odd_imaginary = real[odd] * sin + imaginary[odd] * cos;
+                real[odd] = real[even] - odd_real;
+                imaginary[odd] = imaginary[even] - odd_imaginary;
+                real[even] += odd_real;
+                imaginary[even] += odd_imaginary;
+            }
+        }
+        size <<= 1;
+    }
+}
+
+/// The number of samples kept for analysis
+const ANALYSIS_BUFFER_LEN: usize = 8192;
+
+/// The latest samples of a playing sound, mixed down to mono
+#[derive(Default)]
+pub(crate) struct AnalysisBuffer {
+    samples: VecDeque<f32>,
+    pub(crate) sample_rate: u32,
+}
+
+impl AnalysisBuffer {
+    /// Adds the latest `window.len()` samples to the window, aligned to its end.
+    pub(crate) fn add_to_window(&self, window: &mut [f32]) {
+        let len = self.samples.len().min(window.len());
+        let window_start = window.len() - len;
+        let samples = self.samples.iter().skip(self.samples.len() - len);
+        for (sample, window_sample) in samples.zip(window[window_start..].iter_mut()) {
+            *window_sample += sample;
+        }
+    }
+}
+
+pub(crate) type SharedAnalysisBuffer = Arc<Mutex<AnalysisBuffer>>;
+
+/// Copies the samples of a source to an [`AnalysisBuffer`].
+pub(crate) struct AnalysisSource<I> {
+    input: I,
+    buffer: SharedAnalysisBuffer,
+    /// The samples mixed down since the buffer was last written to
+    pending: Vec<f32>,
+    frame_sum: f32,
+    channel: u16,
+}
+
+/// The buffer is written to every this many frames rather than for every frame
+const ANALYSIS_UPDATE_PERIOD: usize = 256;
+
+impl<I> AnalysisSource<I>
+where
+    I: Source<Item = f32>,
+{
+    pub(crate) fn new(input: I, buffer: SharedAnalysisBuffer) -> Self {
+        AnalysisSource {
+            input,
+            buffer,
+            pending: Vec::with_capacity(ANALYSIS_UPDATE_PERIOD),
+            frame_sum: 0.0,
+            channel: 0,
+        }
+    }
+
+    fn flush(&mut self) {
+        let mut buffer = self.buffer.lock();
+        buffer.sample_rate = self.input.sample_rate();
+        buffer.samples.extend(self.pending.drain(..));
+        let excess = buffer.samples.len().saturating_sub(ANALYSIS_BUFFER_LEN);
+        buffer.samples.drain(..excess);
+    }
+}
+
+impl<I> Iterator for AnalysisSource<I>
+where
+    I: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let channels = self.input.channels().max(1);
+        let sample = match self.input.next() {
+            Some(sample) => sample,
+            None => {
+                self.flush();
+                return None;
+            }
+        };
+        self.frame_sum += sample;
+        self.channel += 1;
+        if self.channel >= channels {
+            self.pending.push(self.frame_sum / channels as f32);
+            self.frame_sum = 0.0;
+            self.channel = 0;
+            if self.pending.len() >= ANALYSIS_UPDATE_PERIOD {
+                self.flush();
+            }
+        }
+        Some(sample)
+    }
+}
+
+impl<I> Source for AnalysisSource<I>
+where
+    I: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.input.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+}
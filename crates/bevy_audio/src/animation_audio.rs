@@ -0,0 +1,155 @@
+use crate::{Audio, AudioSource, PlaybackSettings};
+use bevy_asset::Handle;
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    query::Changed,
+    system::{Query, Res},
+};
+
+/// A sound played when an animation reaches a keyframe, see [`AnimationAudio`].
+#[derive(Debug, Clone)]
+pub struct AudioKeyframe {
+    /// The time of the keyframe in the animation, in seconds
+    pub time: f32,
+    pub source: Handle<AudioSource>,
+    pub settings: PlaybackSettings,
+}
+
+/// Plays sounds at keyframes of an animation of its entity, like the footsteps of a walk cycle
+/// or the swoosh of a sword swing, through the spatial audio path at the position of the
+/// entity. The entity needs a [`GlobalTransform`](bevy_transform::components::GlobalTransform).
+///
+/// The time of the animation, like the time of a `GltfAnimation` being played, is set by what
+/// plays the animation with [`set_time`](AnimationAudio::set_time), and the sounds of the
+/// keyframes it passed are played by [`animation_audio_system`].
+///
+/// ```
+/// # use bevy_asset::AssetServer;
+/// # use bevy_audio::AnimationAudio;
+/// # use bevy_ecs::prelude::*;
+/// # #[derive(Component)]
+/// # struct Walk;
+/// fn add_footsteps(
+///     mut commands: Commands,
+///     asset_server: Res<AssetServer>,
+///     query: Query<Entity, Added<Walk>>,
+/// ) {
+///     for entity in query.iter() {
+///         let footstep = asset_server.load("sounds/footstep.ogg");
+///         commands.entity(entity).insert(
+///             AnimationAudio::default()
+///                 .with_keyframe(0.1, footstep.clone())
+///                 .with_keyframe(0.6, footstep),
+///         );
+///     }
+/// }
+/// # add_footsteps.system();
+/// ```
+#[derive(Component, Debug, Clone, Default)]
+pub struct AnimationAudio {
+    /// Sorted by time
+    keyframes: Vec<AudioKeyframe>,
+    time: f32,
+    /// The indices of the keyframes passed since the sounds were last played
+    reached: Vec<usize>,
+}
+
+impl AnimationAudio {
+    pub fn new(mut keyframes: Vec<AudioKeyframe>) -> Self {
+        keyframes.sort_by(|a, b| {
+            a.time
+                .partial_cmp(&b.time)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        AnimationAudio {
+            keyframes,
+            ..Default::default()
+        }
+    }
+
+    /// Plays `source` when the animation reaches `time`.
+    pub fn with_keyframe(self, time: f32, source: Handle<AudioSource>) -> Self {
+        self.with_keyframe_settings(time, source, PlaybackSettings::default())
+    }
+
+    pub fn with_keyframe_settings(
+        mut self,
+        time: f32,
+        source: Handle<AudioSource>,
+        settings: PlaybackSettings,
+    ) -> Self {
+        self.add_keyframe(AudioKeyframe {
+            time,
+            source,
+            settings,
+        });
+        self
+    }
+
+    pub fn add_keyframe(&mut self, keyframe: AudioKeyframe) {
+        let index = self
+            .keyframes
+            .iter()
+            .position(|other| other.time > keyframe.time)
+            .unwrap_or(self.keyframes.len());
+        self.keyframes.insert(index, keyframe);
+        // the indices of the keyframes after it changed
+        self.reached.clear();
+    }
+
+    pub fn keyframes(&self) -> &[AudioKeyframe] {
+        &self.keyframes
+    }
+
+    /// The time of the animation, in seconds.
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Advances the animation to `time`, playing the sounds of the keyframes from the previous
+    /// time, included, to `time`, excluded. A `time` before the previous time is treated as the
+    /// animation looping: the sounds of the keyframes after the previous time and before `time`
+    /// are played.
+    pub fn set_time(&mut self, time: f32) {
+        let previous_time = self.time;
+        let looped = time < previous_time;
+        let reached = self.keyframes.iter().enumerate().filter(|(_, keyframe)| {
+            if looped {
+                keyframe.time >= previous_time || keyframe.time < time
+            } else {
+                keyframe.time >= previous_time && keyframe.time < time
+            }
+        });
+        self.reached.extend(reached.map(|(index, _)| index));
+        self.time = time;
+    }
+
+    /// Moves the animation to `time` without playing the sounds of the keyframes in between.
+    pub fn seek(&mut self, time: f32) {
+        self.time = time;
+    }
+}
+
+/// Plays the sounds of the keyframes reached by the animations of [`AnimationAudio`]s at the
+/// position of their entities.
+pub fn animation_audio_system(
+    audio: Res<Audio<AudioSource>>,
+    mut query: Query<(Entity, &mut AnimationAudio), Changed<AnimationAudio>>,
+) {
+    for (entity, mut animation_audio) in query.iter_mut() {
+        if animation_audio.reached.is_empty() {
+            continue;
+        }
+        let animation_audio = &mut *animation_audio;
+        for index in animation_audio.reached.drain(..) {
+            if let Some(keyframe) = animation_audio.keyframes.get(index) {
+                audio.play_spatial_with_settings(
+                    keyframe.source.clone(),
+                    entity,
+                    keyframe.settings.clone(),
+                );
+            }
+        }
+    }
+}
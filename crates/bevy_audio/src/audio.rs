@@ -1,5 +1,7 @@
-use crate::{AudioSource, Decodable};
-use bevy_asset::{Asset, Handle};
+use crate::{AudioSink, AudioSource, Decodable, LoopRegion};
+use bevy_asset::{Asset, Handle, HandleId};
+use bevy_ecs::entity::Entity;
+use bevy_utils::Duration;
 use parking_lot::RwLock;
 use std::{collections::VecDeque, fmt};
 
@@ -8,7 +10,96 @@ pub struct Audio<P = AudioSource>
 where
     P: Asset + Decodable,
 {
-    pub queue: RwLock<VecDeque<Handle<P>>>,
+    pub queue: RwLock<VecDeque<QueuedAudio<P>>>,
+}
+
+/// A sound waiting for its source to load before it's played
+pub struct QueuedAudio<P>
+where
+    P: Asset,
+{
+    pub source: Handle<P>,
+    pub sink: Handle<AudioSink>,
+    /// The entity the sound is played at, see [`Audio::play_spatial`]
+    pub emitter: Option<Entity>,
+    pub settings: PlaybackSettings,
+}
+
+/// How a sound is played, see [`Audio::play_with_settings`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaybackSettings {
+    /// The [`AudioMixer`](crate::AudioMixer) channel the sound is played in, the master channel
+    /// if `None`
+    pub channel: Option<String>,
+    /// Loops the sound over the region until it's stopped
+    pub looping: Option<LoopRegion>,
+    /// Decodes the sound on a background thread, a little ahead of the playback, rather than on
+    /// the audio thread. It's meant for long sounds like music. Sounds aren't streamed on wasm.
+    pub streaming: bool,
+    /// When a channel has as many sounds as its voice limit, sounds with a lower priority are
+    /// stopped first to play new sounds, and sounds with a higher priority than a new sound
+    /// aren't stopped, see [`AudioMixer::set_max_voices`](crate::AudioMixer::set_max_voices).
+    /// Defaults to 0.
+    pub priority: i32,
+    /// The volume the sound starts playing at, see [`AudioSink::set_volume`]. Defaults to 1.0.
+    pub volume: f32,
+}
+
+impl Default for PlaybackSettings {
+    fn default() -> Self {
+        PlaybackSettings {
+            channel: None,
+            looping: None,
+            streaming: false,
+            priority: 0,
+            volume: 1.0,
+        }
+    }
+}
+
+impl PlaybackSettings {
+    /// Loops the whole sound.
+    pub fn looped(mut self) -> Self {
+        self.looping = Some(LoopRegion::default());
+        self
+    }
+
+    /// Plays the sound until `end`, or its end if `None`, then loops it from `start`.
+    pub fn with_loop_points(mut self, start: Duration, end: Option<Duration>) -> Self {
+        self.looping = Some(LoopRegion { start, end });
+        self
+    }
+
+    pub fn streamed(mut self) -> Self {
+        self.streaming = true;
+        self
+    }
+
+    pub fn with_channel<S: Into<String>>(mut self, channel: S) -> Self {
+        self.channel = Some(channel.into());
+        self
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+}
+
+impl<P: Asset> fmt::Debug for QueuedAudio<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("QueuedAudio")
+            .field("source", &self.source)
+            .field("sink", &self.sink)
+            .field("emitter", &self.emitter)
+            .field("settings", &self.settings)
+            .finish()
+    }
 }
 
 impl<P: Asset> fmt::Debug for Audio<P>
@@ -37,7 +128,50 @@ where
     <P as Decodable>::Decoder: rodio::Source + Send + Sync,
     <<P as Decodable>::Decoder as Iterator>::Item: rodio::Sample + Send + Sync,
 {
-    pub fn play(&self, audio_source: Handle<P>) {
-        self.queue.write().push_front(audio_source);
+    /// Plays a sound, returning the handle of the [`AudioSink`] that controls it.
+    pub fn play(&self, audio_source: Handle<P>) -> Handle<AudioSink> {
+        self.play_with_settings(audio_source, PlaybackSettings::default())
+    }
+
+    pub fn play_with_settings(
+        &self,
+        audio_source: Handle<P>,
+        settings: PlaybackSettings,
+    ) -> Handle<AudioSink> {
+        self.queue_sound(audio_source, None, settings)
+    }
+
+    /// Plays a sound at the position of the `emitter` entity, which needs a
+    /// [`GlobalTransform`](bevy_transform::components::GlobalTransform). The sound is panned and
+    /// attenuated based on the [`AudioEmitter`](crate::AudioEmitter) of the entity and its
+    /// position relative to the [`AudioListener`](crate::AudioListener), and follows the entity
+    /// as it moves. It stops when the entity is despawned.
+    pub fn play_spatial(&self, audio_source: Handle<P>, emitter: Entity) -> Handle<AudioSink> {
+        self.play_spatial_with_settings(audio_source, emitter, PlaybackSettings::default())
+    }
+
+    pub fn play_spatial_with_settings(
+        &self,
+        audio_source: Handle<P>,
+        emitter: Entity,
+        settings: PlaybackSettings,
+    ) -> Handle<AudioSink> {
+        self.queue_sound(audio_source, Some(emitter), settings)
+    }
+
+    fn queue_sound(
+        &self,
+        audio_source: Handle<P>,
+        emitter: Option<Entity>,
+        settings: PlaybackSettings,
+    ) -> Handle<AudioSink> {
+        let sink = Handle::weak(HandleId::random::<AudioSink>());
+        self.queue.write().push_front(QueuedAudio {
+            source: audio_source,
+            sink: sink.clone_weak(),
+            emitter,
+            settings,
+        });
+        sink
     }
 }
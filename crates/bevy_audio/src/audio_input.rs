@@ -0,0 +1,243 @@
+use bevy_ecs::world::World;
+use bevy_utils::tracing::warn;
+use parking_lot::Mutex;
+use rodio::cpal::{
+    self,
+    traits::{DeviceTrait, HostTrait, StreamTrait},
+    SampleFormat,
+};
+use std::{collections::VecDeque, sync::Arc};
+
+/// Captures the samples of an audio input device like a microphone into a ring buffer, for
+/// voice chat and audio-reactive gameplay. Samples are mixed down to mono, and the oldest are
+/// dropped once the buffer is full.
+///
+/// ```
+/// # use bevy_audio::AudioInput;
+/// # use bevy_ecs::prelude::*;
+/// fn start_capture(mut input: ResMut<AudioInput>) {
+///     input.start();
+/// }
+///
+/// fn read_microphone(input: Res<AudioInput>) {
+///     let samples = input.drain();
+///     let loudest = samples.iter().fold(0f32, |loudest, sample| loudest.max(sample.abs()));
+///     println!("{} samples at {}Hz, peak {}", samples.len(), input.sample_rate(), loudest);
+/// }
+/// # start_capture.system();
+/// # read_microphone.system();
+/// ```
+#[derive(Debug)]
+pub struct AudioInput {
+    /// The name of the device to capture from, the default input device if `None`
+    device: Option<String>,
+    capturing: bool,
+    /// Incremented when capturing starts or the device changes, so the capture stream is
+    /// rebuilt, or retried after an error
+    generation: u64,
+    buffer: Arc<Mutex<CaptureBuffer>>,
+}
+
+#[derive(Debug)]
+struct CaptureBuffer {
+    samples: VecDeque<f32>,
+    capacity: usize,
+    sample_rate: u32,
+    error: Option<String>,
+}
+
+impl Default for AudioInput {
+    fn default() -> Self {
+        AudioInput {
+            device: None,
+            capturing: false,
+            generation: 0,
+            buffer: Arc::new(Mutex::new(CaptureBuffer {
+                samples: VecDeque::new(),
+                // two seconds at 48kHz
+                capacity: 96_000,
+                sample_rate: 0,
+                error: None,
+            })),
+        }
+    }
+}
+
+impl AudioInput {
+    /// The names of the available input devices.
+    pub fn input_devices() -> Vec<String> {
+        match cpal::default_host().input_devices() {
+            Ok(devices) => devices.filter_map(|device| device.name().ok()).collect(),
+            Err(error) => {
+                warn!("failed to list the audio input devices: {}", error);
+                Vec::new()
+            }
+        }
+    }
+
+    /// The name of the selected device, `None` for the default input device.
+    pub fn device(&self) -> Option<&str> {
+        self.device.as_deref()
+    }
+
+    /// Selects the device to capture from, by name, or the default input device if `None`.
+    pub fn set_device(&mut self, device: Option<String>) {
+        if self.device != device {
+            self.device = device;
+            self.generation += 1;
+        }
+    }
+
+    /// Starts capturing at the next update, or tries again if capturing failed.
+    pub fn start(&mut self) {
+        self.capturing = true;
+        self.generation += 1;
+        self.buffer.lock().error = None;
+    }
+
+    pub fn stop(&mut self) {
+        self.capturing = false;
+    }
+
+    pub fn is_capturing(&self) -> bool {
+        self.capturing
+    }
+
+    /// The error that stopped capturing, if any.
+    pub fn error(&self) -> Option<String> {
+        self.buffer.lock().error.clone()
+    }
+
+    /// The sample rate of the device, 0 until capturing starts.
+    pub fn sample_rate(&self) -> u32 {
+        self.buffer.lock().sample_rate
+    }
+
+    /// Sets the maximum number of samples kept in the buffer.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        let mut buffer = self.buffer.lock();
+        buffer.capacity = capacity;
+        let excess = buffer.samples.len().saturating_sub(capacity);
+        buffer.samples.drain(..excess);
+    }
+
+    /// The number of samples in the buffer.
+    pub fn len(&self) -> usize {
+        self.buffer.lock().samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Takes the samples captured since the last call, the oldest first.
+    pub fn drain(&self) -> Vec<f32> {
+        self.buffer.lock().samples.drain(..).collect()
+    }
+
+    /// Copies the latest `len` samples without removing them, the oldest first.
+    pub fn latest(&self, len: usize) -> Vec<f32> {
+        let buffer = self.buffer.lock();
+        let skip = buffer.samples.len().saturating_sub(len);
+        buffer.samples.iter().skip(skip).copied().collect()
+    }
+
+    pub fn clear(&self) {
+        self.buffer.lock().samples.clear();
+    }
+}
+
+/// The capture stream of the [`AudioInput`], which can't be sent between threads.
+#[derive(Default)]
+pub struct AudioInputStream {
+    stream: Option<cpal::Stream>,
+    /// The generation of the [`AudioInput`] the stream was built for
+    generation: Option<u64>,
+}
+
+/// Starts, stops and rebuilds the capture stream of the [`AudioInput`].
+pub fn audio_input_system(world: &mut World) {
+    let world = world.cell();
+    let input = match world.get_resource::<AudioInput>() {
+        Some(input) => input,
+        None => return,
+    };
+    let mut input_stream = world.get_non_send_mut::<AudioInputStream>().unwrap();
+
+    if !input.capturing {
+        input_stream.stream = None;
+        input_stream.generation = None;
+        return;
+    }
+    if input_stream.generation == Some(input.generation) {
+        return;
+    }
+    input_stream.generation = Some(input.generation);
+    input_stream.stream = None;
+    match build_input_stream(input.device.as_deref(), input.buffer.clone()) {
+        Ok(stream) => input_stream.stream = Some(stream),
+        Err(error) => {
+            warn!("failed to capture audio input: {}", error);
+            input.buffer.lock().error = Some(error.to_string());
+        }
+    }
+}
+
+fn build_input_stream(
+    device_name: Option<&str>,
+    buffer: Arc<Mutex<CaptureBuffer>>,
+) -> Result<cpal::Stream, anyhow::Error> {
+    let host = cpal::default_host();
+    let device = match device_name {
+        Some(name) => host
+            .input_devices()?
+            .find(|device| {
+                device
+                    .name()
+                    .is_ok_and(|device_name| device_name == name)
+            })
+            .ok_or_else(|| anyhow::anyhow!("no audio input device named {}", name))?,
+        None => host
+            .default_input_device()
+            .ok_or_else(|| anyhow::anyhow!("no audio input device found"))?,
+    };
+    let config = device.default_input_config()?;
+    let channels = config.channels().max(1) as usize;
+    buffer.lock().sample_rate = config.sample_rate().0;
+
+    let error_buffer = buffer.clone();
+    let on_error = move |error: cpal::StreamError| {
+        warn!("audio input error: {}", error);
+        error_buffer.lock().error = Some(error.to_string());
+    };
+    let stream = match config.sample_format() {
+        SampleFormat::F32 => device.build_input_stream(
+            &config.config(),
+            move |data: &[f32], _: &cpal::InputCallbackInfo| push_samples(&buffer, data, channels),
+            on_error,
+        )?,
+        SampleFormat::I16 => device.build_input_stream(
+            &config.config(),
+            move |data: &[i16], _: &cpal::InputCallbackInfo| push_samples(&buffer, data, channels),
+            on_error,
+        )?,
+        SampleFormat::U16 => device.build_input_stream(
+            &config.config(),
+            move |data: &[u16], _: &cpal::InputCallbackInfo| push_samples(&buffer, data, channels),
+            on_error,
+        )?,
+    };
+    stream.play()?;
+    Ok(stream)
+}
+
+/// Mixes the captured frames down to mono and adds them to the buffer.
+fn push_samples<T: cpal::Sample>(buffer: &Mutex<CaptureBuffer>, data: &[T], channels: usize) {
+    let mut buffer = buffer.lock();
+    for frame in data.chunks(channels) {
+        let sum: f32 = frame.iter().map(|sample| sample.to_f32()).sum();
+        buffer.samples.push_back(sum / frame.len() as f32);
+    }
+    let excess = buffer.samples.len().saturating_sub(buffer.capacity);
+    buffer.samples.drain(..excess);
+}
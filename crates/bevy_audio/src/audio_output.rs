@@ -1,9 +1,30 @@
-use crate::{Audio, AudioSource, Decodable};
-use bevy_asset::{Asset, Assets};
-use bevy_ecs::world::World;
-use bevy_utils::tracing::warn;
-use rodio::{OutputStream, OutputStreamHandle, Sink};
-use std::marker::PhantomData;
+use crate::{
+    set_shared_effects, AnalysisSource, Audio, AudioAnalysis, AudioEffect, AudioEffects,
+    AudioEmitter, AudioListener, AudioMixer, AudioSink, AudioSource, AudioSpectrum, AudioVelocity,
+    ChannelMix, ChannelSource, Decodable, DopplerSource, EffectsSource, LoopRegion, LoopingSource,
+    QueuedAudio, SharedAnalysisBuffer, SharedDoppler, SharedEffects, SpareDecoders, SpatialSource,
+    SpatialVolumes, Voice,
+};
+use bevy_asset::{Asset, Assets, HandleId, HandleUntyped};
+use bevy_ecs::{
+    entity::Entity,
+    query::With,
+    world::{Mut, World},
+};
+use bevy_math::Vec3;
+use bevy_transform::components::GlobalTransform;
+use bevy_utils::{tracing::warn, Duration, HashMap, Instant};
+use parking_lot::Mutex;
+use rodio::{OutputStream, OutputStreamHandle, Sample, Sink, Source};
+use std::{
+    any::TypeId,
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 /// Used internally to play audio on the current "audio device"
 pub struct AudioOutput<P = AudioSource>
@@ -12,9 +33,56 @@ where
 {
     _stream: Option<OutputStream>,
     stream_handle: Option<OutputStreamHandle>,
+    /// The playing sounds, by the id of their [`AudioSink`]
+    sounds: RefCell<HashMap<HandleId, PlayingSound<P>>>,
+    /// The time of the previous update and the position of the listener then, to compute
+    /// velocities
+    previous_update: Cell<Option<(Instant, Option<Vec3>)>>,
     phantom: PhantomData<P>,
 }
 
+struct PlayingSound<P>
+where
+    P: Decodable,
+{
+    /// Kept to play the sound again when it's seeked, and to create the decoders of its loops
+    source: HandleUntyped,
+    spatial: Option<SpatialPlayback>,
+    /// The volume and the effects of its [`AudioMixer`] channel
+    /// The name of its [`AudioMixer`] channel
+    channel: String,
+    channel_mix: ChannelMix,
+    /// The latest samples played, for the [`AudioAnalysis`]
+    analysis: SharedAnalysisBuffer,
+    looping: Option<LoopRegion>,
+    streaming: bool,
+    spare_decoders: Option<SpareDecoders<P::Decoder>>,
+}
+
+/// The emitter of a spatial sound, with its channel volumes, Doppler ratio and effects
+struct SpatialPlayback {
+    emitter: Entity,
+    /// The position of the emitter at the previous update
+    position: Vec3,
+    volumes: SpatialVolumes,
+    doppler: SharedDoppler,
+    effects: SharedEffects,
+}
+
+/// The listener of spatial sounds, with its velocity
+struct Listener {
+    transform: GlobalTransform,
+    velocity: Vec3,
+}
+
+/// How a spatial sound is heard by the listener
+struct SpatialParameters {
+    position: Vec3,
+    volumes: [f32; 2],
+    doppler: f32,
+    effects: Vec<AudioEffect>,
+}
+
 impl<P> Default for AudioOutput<P>
 where
     P: Decodable,
@@ -24,6 +92,8 @@ where
             Self {
                 _stream: Some(stream),
                 stream_handle: Some(stream_handle),
+                sounds: Default::default(),
+                previous_update: Cell::new(None),
                 phantom: PhantomData,
             }
         } else {
@@ -31,6 +101,8 @@ where
             Self {
                 _stream: None,
                 stream_handle: None,
+                sounds: Default::default(),
+                previous_update: Cell::new(None),
                 phantom: PhantomData,
             }
         }
@@ -43,29 +115,373 @@ where
     <P as Decodable>::Decoder: rodio::Source + Send + Sync,
     <<P as Decodable>::Decoder as Iterator>::Item: rodio::Sample + Send + Sync,
 {
-    fn play_source(&self, audio_source: &P) {
-        if let Some(stream_handle) = &self.stream_handle {
-            let sink = Sink::try_new(stream_handle).unwrap();
-            sink.append(audio_source.decoder());
-            sink.detach();
+    /// Starts an update of the playing sounds, returning the time since the previous update in
+    /// seconds, and the listener with its explicit velocity or the velocity of its movement.
+    fn begin_update(
+        &self,
+        listener: Option<(GlobalTransform, Option<Vec3>)>,
+    ) -> (f32, Option<Listener>) {
+        let now = Instant::now();
+        let (delta_seconds, previous_position) = match self.previous_update.get() {
+            Some((time, position)) => ((now - time).as_secs_f32(), position),
+            None => (0.0, None),
+        };
+        let listener = listener.map(|(transform, velocity)| Listener {
+            velocity: velocity.unwrap_or_else(|| {
+                movement_velocity(previous_position, transform.translation, delta_seconds)
+            }),
+            transform,
+        });
+        let listener_position = listener
+            .as_ref()
+            .map(|listener| listener.transform.translation);
+        self.previous_update.set(Some((now, listener_position)));
+        (delta_seconds, listener)
+    }
+
+    fn new_sink(&self) -> Option<Sink> {
+        self.stream_handle
+            .as_ref()
+            .map(|stream_handle| Sink::try_new(stream_handle).unwrap())
+    }
+
+    /// Appends the source to the sink from `start`, returning the playback position of the sink
+    /// and the spare decoders of a looping sound.
+    fn append_source(
+        &self,
+        sink: &Sink,
+        audio_source: &P,
+        start: Duration,
+        sound: &PlayingSound<P>,
+    ) -> (Arc<AtomicU64>, Option<SpareDecoders<P::Decoder>>) {
+        let position = Arc::new(AtomicU64::new(0));
+        let streaming = sound.streaming && cfg!(not(target_arch = "wasm32"));
+        // the position of a streamed sound is tracked as it's played, not as it's decoded
+        let decoded_position = if streaming {
+            Arc::new(AtomicU64::new(0))
+        } else {
+            position.clone()
+        };
+        let (source, spare_decoders) = LoopingSource::new(
+            audio_source.decoder(),
+            start,
+            sound.looping,
+            decoded_position.clone(),
+        );
+        #[cfg(not(target_arch = "wasm32"))]
+        if streaming {
+            let source = crate::StreamingSource::new(source, decoded_position, position.clone());
+            append_to_sink(sink, source, sound);
+            return (position, spare_decoders);
         }
+        append_to_sink(sink, source, sound);
+        (position, spare_decoders)
     }
 
-    fn try_play_queued(&self, audio_sources: &Assets<P>, audio: &mut Audio<P>) {
+    fn play_source(
+        &self,
+        audio_source: &P,
+        queued: QueuedAudio<P>,
+        spatial: Option<SpatialPlayback>,
+        channel_mix: ChannelMix,
+        audio_sinks: &mut Assets<AudioSink>,
+    ) {
+        let sink = match self.new_sink() {
+            Some(sink) => sink,
+            None => return,
+        };
+        sink.set_volume(queued.settings.volume);
+        let mut sound = PlayingSound {
+            source: queued.source.clone_untyped(),
+            spatial,
+            channel: queued
+                .settings
+                .channel
+                .clone()
+                .unwrap_or_else(|| AudioMixer::MASTER.to_string()),
+            channel_mix,
+            analysis: Default::default(),
+            looping: queued.settings.looping,
+            streaming: queued.settings.streaming,
+            spare_decoders: None,
+        };
+        let (position, spare_decoders) =
+            self.append_source(&sink, audio_source, Duration::default(), &sound);
+        sound.spare_decoders = spare_decoders;
+        let voice = Voice {
+            channel: sound.channel.clone(),
+            priority: queued.settings.priority,
+            started: Instant::now(),
+            spatial_volumes: sound
+                .spatial
+                .as_ref()
+                .map(|spatial| spatial.volumes.clone()),
+        };
+        audio_sinks.set_untracked(queued.sink.id, AudioSink::new(sink, position, voice));
+        self.sounds.borrow_mut().insert(queued.sink.id, sound);
+    }
+
+    fn try_play_queued(
+        &self,
+        world: &World,
+        audio_sources: &Assets<P>,
+        audio: &Audio<P>,
+        audio_sinks: &mut Assets<AudioSink>,
+        mixer: Option<&AudioMixer>,
+        listener: Option<&Listener>,
+    ) {
         let mut queue = audio.queue.write();
         let len = queue.len();
-        let mut i = 0;
-        while i < len {
-            let audio_source_handle = queue.pop_back().unwrap();
-            if let Some(audio_source) = audio_sources.get(&audio_source_handle) {
-                self.play_source(audio_source);
+        for _ in 0..len {
+            let queued = queue.pop_back().unwrap();
+            let spatial = match queued.emitter {
+                Some(emitter) => match spatial_parameters(world, emitter, None, listener, 0.0) {
+                    Some(parameters) => Some(SpatialPlayback {
+                        emitter,
+                        position: parameters.position,
+                        volumes: Arc::new(Mutex::new(parameters.volumes)),
+                        doppler: Arc::new(AtomicU32::new(parameters.doppler.to_bits())),
+                        effects: Arc::new(Mutex::new(parameters.effects)),
+                    }),
+                    // the emitter was despawned before the source loaded
+                    None => continue,
+                },
+                None => None,
+            };
+            if let Some(audio_source) = audio_sources.get(&queued.source) {
+                let channel = queued.settings.channel.as_deref();
+                if let Some(mixer) = mixer {
+                    let name = channel.unwrap_or(AudioMixer::MASTER);
+                    if !mixer.steal_voices(audio_sinks, name, queued.settings.priority) {
+                        // the voice limit is reached with sounds of a higher priority
+                        continue;
+                    }
+                }
+                let channel_mix = mixer
+                    .map(|mixer| mixer.channel_mix(channel))
+                    .unwrap_or_default();
+                self.play_source(audio_source, queued, spatial, channel_mix, audio_sinks);
             } else {
                 // audio source hasn't loaded yet. add it back to the queue
-                queue.push_front(audio_source_handle);
+                queue.push_front(queued);
             }
-            i += 1;
         }
     }
+
+    /// Updates the volumes, Doppler ratios and effects of the playing spatial sounds for the
+    /// current positions and velocities of their emitters and of the listener, restarts the
+    /// sounds that were seeked, and removes the sinks of finished sounds and of the sounds of
+    /// despawned emitters.
+    fn update_sounds(
+        &self,
+        world: &World,
+        audio_sources: Option<&Assets<P>>,
+        audio_sinks: &mut Assets<AudioSink>,
+        listener: Option<&Listener>,
+        delta_seconds: f32,
+    ) {
+        self.sounds.borrow_mut().retain(|&id, sound| {
+            let audio_sink = match audio_sinks.get(id) {
+                Some(audio_sink) => audio_sink,
+                None => return false,
+            };
+            if let Some(spatial) = &mut sound.spatial {
+                let previous_position = Some(spatial.position);
+                match spatial_parameters(
+                    world,
+                    spatial.emitter,
+                    previous_position,
+                    listener,
+                    delta_seconds,
+                ) {
+                    Some(parameters) => {
+                        spatial.position = parameters.position;
+                        *spatial.volumes.lock() = parameters.volumes;
+                        spatial
+                            .doppler
+                            .store(parameters.doppler.to_bits(), Ordering::Relaxed);
+                        set_shared_effects(&spatial.effects, &parameters.effects);
+                    }
+                    None => {
+                        audio_sink.stop();
+                        audio_sinks.remove(id);
+                        return false;
+                    }
+                }
+            }
+
+            let audio_source = audio_sources.and_then(|sources| sources.get(sound.source.id));
+            if let (Some(spare_decoders), Some(audio_source)) =
+                (&sound.spare_decoders, audio_source)
+            {
+                if spare_decoders.is_needed() {
+                    spare_decoders.send(audio_source.decoder());
+                }
+            }
+
+            if let Some(start) = audio_sink.seek {
+                if let (Some(audio_source), Some(sink)) = (audio_source, self.new_sink()) {
+                    sink.set_volume(audio_sink.volume());
+                    sink.set_speed(audio_sink.speed());
+                    if audio_sink.is_paused() {
+                        sink.pause();
+                    }
+                    let (position, spare_decoders) =
+                        self.append_source(&sink, audio_source, start, sound);
+                    sound.spare_decoders = spare_decoders;
+                    let voice = audio_sink.voice.clone();
+                    audio_sink.stop();
+                    audio_sinks.set_untracked(id, AudioSink::new(sink, position, voice));
+                } else if let Some(audio_sink) = audio_sinks.get_mut(id) {
+                    audio_sink.seek = None;
+                }
+            }
+
+            if audio_sinks
+                .get(id)
+                .is_none_or(|audio_sink| audio_sink.is_finished())
+            {
+                audio_sinks.remove(id);
+                return false;
+            }
+            true
+        });
+    }
+}
+
+impl<P> AudioOutput<P>
+where
+    P: Decodable,
+{
+    /// Updates the spectrums of the analyzed sounds and channels.
+    fn analyze(
+        &self,
+        audio_sinks: &Assets<AudioSink>,
+        mixer: Option<&AudioMixer>,
+        analysis: &mut AudioAnalysis,
+    ) {
+        let sounds = self.sounds.borrow();
+        let fft_size = analysis.fft_size();
+        // paused sounds are silent
+        let is_playing = |id: HandleId| {
+            audio_sinks
+                .get(id)
+                .is_some_and(|audio_sink| !audio_sink.is_paused())
+        };
+
+        for (id, spectrum) in analysis.sounds.iter_mut() {
+            if let Some(sound) = sounds.get(id) {
+                let mut window = vec![0.0; fft_size];
+                let buffer = sound.analysis.lock();
+                if is_playing(*id) {
+                    buffer.add_to_window(&mut window);
+                }
+                *spectrum = Some(AudioSpectrum::from_samples(&window, buffer.sample_rate));
+            }
+        }
+
+        for (name, channel) in analysis.channels.iter_mut() {
+            let mut window = vec![0.0; fft_size];
+            let mut sample_rate = 0;
+            for (id, sound) in sounds.iter() {
+                let is_routed = match mixer {
+                    Some(mixer) => mixer.is_routed_to(&sound.channel, name),
+                    None => sound.channel == *name || name == AudioMixer::MASTER,
+                };
+                if is_routed && is_playing(*id) {
+                    let buffer = sound.analysis.lock();
+                    buffer.add_to_window(&mut window);
+                    sample_rate = sample_rate.max(buffer.sample_rate);
+                }
+            }
+            channel.update(TypeId::of::<P>(), window, sample_rate);
+        }
+    }
+}
+
+/// Appends a source to a sink, in the [`AudioMixer`] channel and at the position of the sound.
+fn append_to_sink<P, S>(sink: &Sink, source: S, sound: &PlayingSound<P>)
+where
+    P: Decodable,
+    S: Source + Send + 'static,
+    S::Item: Sample + Send,
+{
+    let source =
+        ChannelSource::new(source, sound.channel_mix.gain.clone()).convert_samples::<f32>();
+    match &sound.spatial {
+        Some(spatial) => {
+            let source = EffectsSource::new(source, spatial.effects.clone());
+            let source = EffectsSource::new(source, sound.channel_mix.effects.clone());
+            let source = AnalysisSource::new(source, sound.analysis.clone());
+            let source = DopplerSource::new(source, spatial.doppler.clone());
+            sink.append(SpatialSource::new(source, spatial.volumes.clone()));
+        }
+        None => {
+            let source = EffectsSource::new(source, sound.channel_mix.effects.clone());
+            sink.append(AnalysisSource::new(source, sound.analysis.clone()));
+        }
+    }
+}
+
+/// The velocity of an entity that moved from `previous_position` to `position`.
+fn movement_velocity(previous_position: Option<Vec3>, position: Vec3, delta_seconds: f32) -> Vec3 {
+    match previous_position {
+        Some(previous_position) if delta_seconds > 0.0 => {
+            (position - previous_position) / delta_seconds
+        }
+        _ => Vec3::ZERO,
+    }
+}
+
+/// How a sound played by `emitter` is heard, `None` if the emitter doesn't exist anymore.
+/// Sounds are played at full volume and without the Doppler effect without a listener.
+fn spatial_parameters(
+    world: &World,
+    emitter: Entity,
+    previous_position: Option<Vec3>,
+    listener: Option<&Listener>,
+    delta_seconds: f32,
+) -> Option<SpatialParameters> {
+    let position = world.get::<GlobalTransform>(emitter)?.translation;
+    let mut effects = world
+        .get::<AudioEffects>(emitter)
+        .map_or_else(Vec::new, |effects| effects.0.clone());
+    let listener = match listener {
+        Some(listener) => listener,
+        None => {
+            return Some(SpatialParameters {
+                position,
+                volumes: [1.0; 2],
+                doppler: 1.0,
+                effects,
+            })
+        }
+    };
+
+    let default_emitter = AudioEmitter::default();
+    let audio_emitter = world
+        .get::<AudioEmitter>(emitter)
+        .unwrap_or(&default_emitter);
+    let velocity = match world.get::<AudioVelocity>(emitter) {
+        Some(velocity) => velocity.0,
+        None => movement_velocity(previous_position, position, delta_seconds),
+    };
+    let listener_position = listener.transform.translation;
+    let distance = position.distance(listener_position);
+    if let Some(cutoff) = audio_emitter.air_absorption_cutoff(distance) {
+        effects.push(AudioEffect::LowPass { cutoff });
+    }
+    Some(SpatialParameters {
+        position,
+        volumes: audio_emitter.channel_volumes(position, &listener.transform),
+        doppler: audio_emitter.doppler_ratio(
+            position,
+            velocity,
+            listener_position,
+            listener.velocity,
+        ),
+        effects,
+    })
 }
 
 /// Plays audio currently queued in the [Audio] resource through the [AudioOutput] resource
@@ -75,11 +491,47 @@ where
     <P as Decodable>::Decoder: rodio::Source + Send + Sync,
     <<P as Decodable>::Decoder as Iterator>::Item: rodio::Sample + Send + Sync,
 {
-    let world = world.cell();
-    let audio_output = world.get_non_send::<AudioOutput<P>>().unwrap();
-    let mut audio = world.get_resource_mut::<Audio<P>>().unwrap();
+    let listener = world
+        .query_filtered::<(&GlobalTransform, Option<&AudioVelocity>), With<AudioListener>>()
+        .iter(world)
+        .next()
+        .map(|(transform, velocity)| (*transform, velocity.map(|velocity| velocity.0)));
+    world.resource_scope(|world, mut audio_sinks: Mut<Assets<AudioSink>>| {
+        let world = &*world;
+        let audio_output = world.get_non_send_resource::<AudioOutput<P>>().unwrap();
+        let (delta_seconds, listener) = audio_output.begin_update(listener);
+        let audio = world.get_resource::<Audio<P>>().unwrap();
+        let audio_sources = world.get_resource::<Assets<P>>();
+        let mixer = world.get_resource::<AudioMixer>();
+        if let Some(mixer) = mixer {
+            mixer.update_channel_mixes();
+        }
 
-    if let Some(audio_sources) = world.get_resource::<Assets<P>>() {
-        audio_output.try_play_queued(&*audio_sources, &mut *audio);
-    };
+        if let Some(audio_sources) = audio_sources {
+            audio_output.try_play_queued(
+                world,
+                audio_sources,
+                audio,
+                &mut audio_sinks,
+                mixer,
+                listener.as_ref(),
+            );
+        };
+        audio_output.update_sounds(
+            world,
+            audio_sources,
+            &mut audio_sinks,
+            listener.as_ref(),
+            delta_seconds,
+        );
+    });
+
+    if world.contains_resource::<AudioAnalysis>() {
+        world.resource_scope(|world, mut analysis: Mut<AudioAnalysis>| {
+            let audio_output = world.get_non_send_resource::<AudioOutput<P>>().unwrap();
+            let audio_sinks = world.get_resource::<Assets<AudioSink>>().unwrap();
+            let mixer = world.get_resource::<AudioMixer>();
+            audio_output.analyze(audio_sinks, mixer, &mut analysis);
+        });
+    }
 }
@@ -0,0 +1,133 @@
+use crate::SpatialVolumes;
+use bevy_reflect::TypeUuid;
+use bevy_utils::{Duration, Instant};
+use rodio::Sink;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// Controls a sound played with [`Audio`](crate::Audio). The sink of a sound is returned by
+/// [`Audio::play`](crate::Audio::play) as a weak handle, and is added to the
+/// [`Assets<AudioSink>`](bevy_asset::Assets) once its source loaded and it started playing. It
+/// is removed once the sound finished playing or was stopped, including when it's stopped to
+/// play another sound because of the voice limits of the [`AudioMixer`](crate::AudioMixer).
+///
+/// ```
+/// # use bevy_asset::{Assets, Handle};
+/// # use bevy_audio::AudioSink;
+/// # use bevy_ecs::prelude::*;
+/// # struct Music(Handle<AudioSink>);
+/// fn pause_music(music: Res<Music>, audio_sinks: Res<Assets<AudioSink>>) {
+///     if let Some(sink) = audio_sinks.get(&music.0) {
+///         sink.pause();
+///     }
+/// }
+/// # pause_music.system();
+/// ```
+#[derive(TypeUuid)]
+#[uuid = "2f53f9b7-e5c2-457b-8398-5f8a434ec5fd"]
+pub struct AudioSink {
+    pub(crate) sink: Sink,
+    pub(crate) position: Arc<AtomicU64>,
+    pub(crate) seek: Option<Duration>,
+    pub(crate) voice: Voice,
+}
+
+/// What the voice limits of the [`AudioMixer`](crate::AudioMixer) need to know about a sound
+#[derive(Clone)]
+pub(crate) struct Voice {
+    pub(crate) channel: String,
+    pub(crate) priority: i32,
+    pub(crate) started: Instant,
+    /// The channel volumes of a spatial sound
+    pub(crate) spatial_volumes: Option<SpatialVolumes>,
+}
+
+impl AudioSink {
+    pub(crate) fn new(sink: Sink, position: Arc<AtomicU64>, voice: Voice) -> Self {
+        AudioSink {
+            sink,
+            position,
+            seek: None,
+            voice,
+        }
+    }
+
+    /// The [`AudioMixer`](crate::AudioMixer) channel the sound is played in.
+    pub fn channel(&self) -> &str {
+        &self.voice.channel
+    }
+
+    pub fn priority(&self) -> i32 {
+        self.voice.priority
+    }
+
+    /// How loud the sound is played, taking into account its volume, the volume of its channel
+    /// and its distance to the listener. Paused sounds are silent.
+    pub(crate) fn loudness(&self, channel_volume: f32) -> f32 {
+        if self.is_paused() {
+            return 0.0;
+        }
+        let spatial_volume = self.voice.spatial_volumes.as_ref().map_or(1.0, |volumes| {
+            let volumes = volumes.lock();
+            volumes[0].max(volumes[1])
+        });
+        self.volume() * channel_volume * spatial_volume
+    }
+
+    /// The volume of the sound, 1.0 being the volume of its source.
+    pub fn volume(&self) -> f32 {
+        self.sink.volume()
+    }
+
+    pub fn set_volume(&self, volume: f32) {
+        self.sink.set_volume(volume);
+    }
+
+    /// The playback speed of the sound, 1.0 being its normal speed. It also changes its pitch.
+    pub fn speed(&self) -> f32 {
+        self.sink.speed()
+    }
+
+    pub fn set_speed(&self, speed: f32) {
+        self.sink.set_speed(speed);
+    }
+
+    /// Resumes the sound if it's paused.
+    pub fn play(&self) {
+        self.sink.play();
+    }
+
+    pub fn pause(&self) {
+        self.sink.pause();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.sink.is_paused()
+    }
+
+    /// Stops the sound. It can't be resumed, and its sink is removed by the audio system at the
+    /// end of the frame.
+    pub fn stop(&self) {
+        self.sink.stop();
+    }
+
+    /// Whether the sound finished playing or was stopped.
+    pub fn is_finished(&self) -> bool {
+        self.seek.is_none() && self.sink.empty()
+    }
+
+    /// The position of the playback in the source, which doesn't depend on the speed. It goes
+    /// back to the start of the loop region when a looping sound loops.
+    pub fn position(&self) -> Duration {
+        self.seek
+            .unwrap_or_else(|| Duration::from_nanos(self.position.load(Ordering::Relaxed)))
+    }
+
+    /// Plays the sound from `position`, keeping its volume, speed and whether it's paused. The
+    /// sound is restarted by the audio system at the end of the frame.
+    pub fn seek(&mut self, position: Duration) {
+        self.seek = Some(position);
+    }
+}
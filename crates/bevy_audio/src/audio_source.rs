@@ -11,6 +11,35 @@ pub struct AudioSource {
     pub bytes: Arc<[u8]>,
 }
 
+impl AudioSource {
+    /// A short silent clip in the WAV format, used in place of the audio sources that fail to
+    /// load.
+    #[cfg(feature = "wav")]
+    pub fn silence() -> Self {
+        const SAMPLE_RATE: u32 = 44100;
+        const SAMPLES: u32 = 64;
+        // mono 16-bit PCM
+        let data_len = SAMPLES * 2;
+        let mut bytes = Vec::with_capacity(44 + data_len as usize);
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+        bytes.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        bytes.resize(bytes.len() + data_len as usize, 0);
+        AudioSource {
+            bytes: bytes.into(),
+        }
+    }
+}
+
 impl AsRef<[u8]> for AudioSource {
     fn as_ref(&self) -> &[u8] {
         &self.bytes
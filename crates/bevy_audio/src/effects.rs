@@ -0,0 +1,520 @@
+use bevy_ecs::component::Component;
+use bevy_utils::Duration;
+use parking_lot::Mutex;
+use rodio::Source;
+use std::{f32::consts::PI, sync::Arc};
+
+/// An effect applied to sounds, in the effect chain of an [`AudioMixer`](crate::AudioMixer)
+/// channel or of an emitter with [`AudioEffects`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AudioEffect {
+    /// Attenuates the frequencies above `cutoff` Hz, like sounds heard underwater or through a
+    /// wall
+    LowPass { cutoff: f32 },
+    /// Attenuates the frequencies below `cutoff` Hz, like sounds heard through a radio
+    HighPass { cutoff: f32 },
+    /// Adds the reflections of a room. `room_size` and `damping`, from 0 to 1, set how long the
+    /// reverb lasts and how fast its high frequencies fade, and `mix` is the level of the reverb
+    /// mixed with the sound.
+    Reverb {
+        room_size: f32,
+        damping: f32,
+        mix: f32,
+    },
+    /// Shifts the pitch by `semitones` without changing the speed
+    PitchShift { semitones: f32 },
+}
+
+impl AudioEffect {
+    fn same_kind(&self, other: &AudioEffect) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+/// The effects applied to the spatial sounds of an emitter, before the effects of their
+/// [`AudioMixer`](crate::AudioMixer) channel.
+///
+/// ```
+/// # use bevy_audio::{AudioEffect, AudioEffects};
+/// let underwater = AudioEffects(vec![AudioEffect::LowPass { cutoff: 800.0 }]);
+/// ```
+#[derive(Component, Debug, Clone, Default, PartialEq)]
+pub struct AudioEffects(pub Vec<AudioEffect>);
+
+/// An effect chain shared with the sounds it applies to, which is read by the audio thread.
+pub(crate) type SharedEffects = Arc<Mutex<Vec<AudioEffect>>>;
+
+/// Replaces the effects of a chain, if they changed.
+pub(crate) fn set_shared_effects(shared: &SharedEffects, effects: &[AudioEffect]) {
+    let mut shared = shared.lock();
+    if shared.as_slice() != effects {
+        shared.clear();
+        shared.extend_from_slice(effects);
+    }
+}
+
+/// Applies an effect chain to a source.
+pub(crate) struct EffectsSource<I> {
+    input: I,
+    effects: SharedEffects,
+    processors: Vec<EffectProcessor>,
+    channel: usize,
+    samples_until_update: usize,
+}
+
+/// The effect chain is read every this many samples rather than for every sample
+const EFFECTS_UPDATE_PERIOD: usize = 256;
+
+impl<I> EffectsSource<I>
+where
+    I: Source<Item = f32>,
+{
+    pub(crate) fn new(input: I, effects: SharedEffects) -> Self {
+        let mut source = EffectsSource {
+            input,
+            effects,
+            processors: Vec::new(),
+            channel: 0,
+            samples_until_update: EFFECTS_UPDATE_PERIOD,
+        };
+        source.update_processors();
+        source
+    }
+
+    /// Updates the effect processors for the current effect chain, keeping the state of the
+    /// effects that are still in the chain.
+    fn update_processors(&mut self) {
+        let channels = self.input.channels().max(1) as usize;
+        let sample_rate = self.input.sample_rate().max(1) as f32;
+        let effects = self.effects.lock();
+        self.processors.truncate(effects.len());
+        for (index, effect) in effects.iter().enumerate() {
+            match self.processors.get_mut(index) {
+                Some(processor)
+                    if processor.effect.same_kind(effect)
+                        && processor.channels == channels
+                        && processor.sample_rate == sample_rate =>
+                {
+                    processor.set_effect(*effect);
+                }
+                Some(processor) => {
+                    *processor = EffectProcessor::new(*effect, channels, sample_rate);
+                }
+                None => self
+                    .processors
+                    .push(EffectProcessor::new(*effect, channels, sample_rate)),
+            }
+        }
+    }
+}
+
+impl<I> Iterator for EffectsSource<I>
+where
+    I: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.samples_until_update == 0 && self.channel == 0 {
+            self.update_processors();
+            self.samples_until_update = EFFECTS_UPDATE_PERIOD;
+        }
+        self.samples_until_update = self.samples_until_update.saturating_sub(1);
+
+        let channels = self.input.channels().max(1) as usize;
+        let mut sample = self.input.next()?;
+        for processor in self.processors.iter_mut() {
+            let channel = self.channel.min(processor.channels - 1);
+            sample = processor.process(sample, channel);
+        }
+        self.channel = (self.channel + 1) % channels;
+        Some(sample)
+    }
+}
+
+impl<I> Source for EffectsSource<I>
+where
+    I: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.input.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+}
+
+/// An effect with its state for each channel of a source
+struct EffectProcessor {
+    effect: AudioEffect,
+    channels: usize,
+    sample_rate: f32,
+    state: EffectState,
+}
+
+enum EffectState {
+    Filter {
+        coefficients: BiquadCoefficients,
+        channels: Vec<BiquadState>,
+    },
+    Reverb(Vec<Reverb>),
+    PitchShift(Vec<PitchShifter>),
+}
+
+impl EffectProcessor {
+    fn new(effect: AudioEffect, channels: usize, sample_rate: f32) -> Self {
+        let state = match effect {
+            AudioEffect::LowPass { .. } | AudioEffect::HighPass { .. } => EffectState::Filter {
+                coefficients: BiquadCoefficients::new(effect, sample_rate),
+                channels: vec![BiquadState::default(); channels],
+            },
+            AudioEffect::Reverb { .. } => EffectState::Reverb(
+                (0..channels)
+                    .map(|channel| Reverb::new(sample_rate, channel))
+                    .collect(),
+            ),
+            AudioEffect::PitchShift { .. } => {
+                EffectState::PitchShift(vec![PitchShifter::new(sample_rate); channels])
+            }
+        };
+        EffectProcessor {
+            effect,
+            channels,
+            sample_rate,
+            state,
+        }
+    }
+
+    fn set_effect(&mut self, effect: AudioEffect) {
+        if effect == self.effect {
+            return;
+        }
+        self.effect = effect;
+        if let EffectState::Filter { coefficients, .. } = &mut self.state {
+            *coefficients = BiquadCoefficients::new(effect, self.sample_rate);
+        }
+    }
+
+    fn process(&mut self, sample: f32, channel: usize) -> f32 {
+        match (&mut self.state, self.effect) {
+            (
+                EffectState::Filter {
+                    coefficients,
+                    channels,
+                },
+                _,
+            ) => channels[channel].process(coefficients, sample),
+            (
+                EffectState::Reverb(reverbs),
+                AudioEffect::Reverb {
+                    room_size,
+                    damping,
+                    mix,
+                },
+            ) => {
+                let wet = reverbs[channel].process(sample, room_size, damping);
+                sample + wet * mix.max(0.0)
+            }
+            (EffectState::PitchShift(shifters), AudioEffect::PitchShift { semitones }) => {
+                shifters[channel].process(sample, 2f32.powf(semitones / 12.0))
+            }
+            _ => sample,
+        }
+    }
+}
+
+/// The coefficients of a second order low or high pass filter, normalized by `a0`
+#[derive(Clone, Copy)]
+struct BiquadCoefficients {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl BiquadCoefficients {
+    fn new(effect: AudioEffect, sample_rate: f32) -> Self {
+        let (cutoff, high_pass) = match effect {
+            AudioEffect::LowPass { cutoff } => (cutoff, false),
+            AudioEffect::HighPass { cutoff } => (cutoff, true),
+            _ => (sample_rate / 2.0, false),
+        };
+        let cutoff = cutoff.max(1.0).min(sample_rate * 0.49);
+        let w0 = 2.0 * PI * cutoff / sample_rate;
+        let cos = w0.cos();
+        // a Butterworth response, with a Q of 1/sqrt(2)
+        let alpha = w0.sin() / std::f32::consts::SQRT_2;
+        let a0 = 1.0 + alpha;
+        let (b0, b1) = if high_pass {
+            ((1.0 + cos) / 2.0, -(1.0 + cos))
+        } else {
+            ((1.0 - cos) / 2.0, 1.0 - cos)
+        };
+        BiquadCoefficients {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b0 / a0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct BiquadState {
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl BiquadState {
+    fn process(&mut self, c: &BiquadCoefficients, x: f32) -> f32 {
+        let y = c.b0 * x + c.b1 * self.x1 + c.b2 * self.x2 - c.a1 * self.y1 - c.a2 * self.y2;
+        self.x2 = self.x1;
+        self.x1 = x;
+        self.y2 = self.y1;
+        self.y1 = y;
+        y
+    }
+}
+
+/// The delays of the comb and allpass filters of the reverb at 44.1kHz, from Freeverb
+const COMB_DELAYS: [usize; 4] = [1116, 1188, 1277, 1356];
+const ALLPASS_DELAYS: [usize; 2] = [556, 441];
+/// Added to the delays of odd channels, so the reverb of each channel differs
+const STEREO_SPREAD: usize = 23;
+
+/// A Schroeder reverb of parallel comb filters followed by allpass filters
+struct Reverb {
+    combs: Vec<(Vec<f32>, usize, f32)>,
+    allpasses: Vec<(Vec<f32>, usize)>,
+}
+
+impl Reverb {
+    fn new(sample_rate: f32, channel: usize) -> Self {
+        let spread = if channel % 2 == 1 { STEREO_SPREAD } else { 0 };
+        let scale =
+            |delay: usize| ((delay + spread) as f32 * sample_rate / 44100.0).max(1.0) as usize;
+        Reverb {
+            combs: COMB_DELAYS
+                .iter()
+                .map(|delay| (vec![0.0; scale(*delay)], 0, 0.0))
+                .collect(),
+            allpasses: ALLPASS_DELAYS
+                .iter()
+                .map(|delay| (vec![0.0; scale(*delay)], 0))
+                .collect(),
+        }
+    }
+
+    fn process(&mut self, input: f32, room_size: f32, damping: f32) -> f32 {
+        let feedback = 0.7 + 0.28 * room_size.clamp(0.0, 1.0);
+        let damping = damping.clamp(0.0, 1.0) * 0.4;
+        let input = input * 0.015 * COMB_DELAYS.len() as f32;
+        let mut output = 0.0;
+        for (buffer, index, filtered) in self.combs.iter_mut() {
+            let delayed = buffer[*index];
+            *filtered = delayed * (1.0 - damping) + *filtered * damping;
+            buffer[*index] = input + *filtered * feedback;
+            *index = (*index + 1) % buffer.len();
+            output += delayed;
+        }
+        for (buffer, index) in self.allpasses.iter_mut() {
+            let delayed = buffer[*index];
+            buffer[*index] = output + delayed * 0.5;
+            *index = (*index + 1) % buffer.len();
+            output = delayed - output;
+        }
+        output / COMB_DELAYS.len() as f32
+    }
+}
+
+/// The length of the window of the pitch shifter
+const PITCH_SHIFT_WINDOW: f32 = 0.05;
+
+/// Shifts the pitch by reading a delay line at another rate than it's written, with two read
+/// heads half a window apart whose outputs are crossfaded to hide the jumps of the heads.
+#[derive(Clone)]
+struct PitchShifter {
+    buffer: Vec<f32>,
+    write: usize,
+    /// The delay of the first read head, in samples
+    delay: f32,
+}
+
+impl PitchShifter {
+    fn new(sample_rate: f32) -> Self {
+        PitchShifter {
+            buffer: vec![0.0; (sample_rate * PITCH_SHIFT_WINDOW).max(2.0) as usize],
+            write: 0,
+            delay: 0.0,
+        }
+    }
+
+    fn read(&self, delay: f32) -> f32 {
+        let len = self.buffer.len();
+        let position = (self.write as f32 - delay).rem_euclid(len as f32);
+        let index = position as usize % len;
+        let next = (index + 1) % len;
+        let fraction = position.fract();
+        self.buffer[index] * (1.0 - fraction) + self.buffer[next] * fraction
+    }
+
+    fn process(&mut self, input: f32, ratio: f32) -> f32 {
+        let len = self.buffer.len() as f32;
+        self.buffer[self.write] = input;
+        self.delay = (self.delay + 1.0 - ratio).rem_euclid(len);
+        let second_delay = (self.delay + len / 2.0) % len;
+        // triangular windows, silent where a head jumps and summing to 1
+        let gain = |delay: f32| 1.0 - (2.0 * delay / len - 1.0).abs();
+        let output =
+            self.read(self.delay) * gain(self.delay) + self.read(second_delay) * gain(second_delay);
+        self.write = (self.write + 1) % self.buffer.len();
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+
+    const SAMPLE_RATE: u32 = 44100;
+
+    fn process(effects: &[AudioEffect], channels: u16, samples: Vec<f32>) -> Vec<f32> {
+        let input = SamplesBuffer::new(channels, SAMPLE_RATE, samples);
+        EffectsSource::new(input, Arc::new(Mutex::new(effects.to_vec()))).collect()
+    }
+
+    fn sine(frequency: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * PI * frequency * i as f32 / SAMPLE_RATE as f32).sin())
+            .collect()
+    }
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples
+            .iter()
+            .fold(0.0, |peak, sample| sample.abs().max(peak))
+    }
+
+    fn zero_crossings(samples: &[f32]) -> usize {
+        samples
+            .windows(2)
+            .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+            .count()
+    }
+
+    #[test]
+    fn no_effects() {
+        let samples = sine(440.0, 1000);
+        assert_eq!(process(&[], 1, samples.clone()), samples);
+    }
+
+    #[test]
+    fn low_and_high_pass() {
+        let low = sine(100.0, 4410);
+        let high = sine(10000.0, 4410);
+        let low_pass = [AudioEffect::LowPass { cutoff: 1000.0 }];
+        let high_pass = [AudioEffect::HighPass { cutoff: 1000.0 }];
+
+        // skipping the start, while the filters settle
+        let filtered = process(&low_pass, 1, low.clone());
+        assert!(peak(&filtered[2205..]) > 0.95);
+        let filtered = process(&low_pass, 1, high.clone());
+        assert!(peak(&filtered[2205..]) < 0.05);
+        let filtered = process(&high_pass, 1, low);
+        assert!(peak(&filtered[2205..]) < 0.05);
+        let filtered = process(&high_pass, 1, high);
+        assert!(peak(&filtered[2205..]) > 0.95);
+    }
+
+    #[test]
+    fn filter_channels() {
+        // a low sound on the left and a high sound on the right, filtered separately
+        let low = sine(100.0, 4410);
+        let high = sine(10000.0, 4410);
+        let interleaved = low
+            .iter()
+            .zip(high.iter())
+            .flat_map(|(left, right)| vec![*left, *right])
+            .collect();
+        let filtered = process(&[AudioEffect::LowPass { cutoff: 1000.0 }], 2, interleaved);
+        let left = filtered.iter().step_by(2).copied().collect::<Vec<_>>();
+        let right = filtered
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .copied()
+            .collect::<Vec<_>>();
+        assert!(peak(&left[2205..]) > 0.95);
+        assert!(peak(&right[2205..]) < 0.05);
+    }
+
+    #[test]
+    fn reverb_tail() {
+        let mut impulse = vec![0.0; SAMPLE_RATE as usize];
+        impulse[0] = 1.0;
+        let reverb = |mix| AudioEffect::Reverb {
+            room_size: 0.5,
+            damping: 0.5,
+            mix,
+        };
+
+        let dry = process(&[reverb(0.0)], 1, impulse.clone());
+        assert_eq!(dry, impulse);
+
+        // silent until the shortest comb filter delay, then reflections long after the impulse
+        let wet = process(&[reverb(1.0)], 1, impulse);
+        assert_eq!(wet[0], 1.0);
+        assert_eq!(peak(&wet[1..COMB_DELAYS[0]]), 0.0);
+        assert!(peak(&wet[COMB_DELAYS[0]..]) > 0.0);
+        assert!(peak(&wet[SAMPLE_RATE as usize / 2..]) > 0.0);
+    }
+
+    #[test]
+    fn pitch_shift() {
+        let samples = sine(200.0, SAMPLE_RATE as usize);
+        let crossings = zero_crossings(&samples);
+
+        let octave_up = process(
+            &[AudioEffect::PitchShift { semitones: 12.0 }],
+            1,
+            samples.clone(),
+        );
+        let ratio = zero_crossings(&octave_up) as f32 / crossings as f32;
+        assert!((ratio - 2.0).abs() < 0.2, "{}", ratio);
+
+        let octave_down = process(&[AudioEffect::PitchShift { semitones: -12.0 }], 1, samples);
+        let ratio = zero_crossings(&octave_down) as f32 / crossings as f32;
+        assert!((ratio - 0.5).abs() < 0.1, "{}", ratio);
+    }
+
+    #[test]
+    fn update_effect_chain() {
+        let effects: SharedEffects = Default::default();
+        let input = SamplesBuffer::new(1, SAMPLE_RATE, sine(10000.0, 4 * EFFECTS_UPDATE_PERIOD));
+        let mut source = EffectsSource::new(input, effects.clone());
+        let unfiltered = source
+            .by_ref()
+            .take(EFFECTS_UPDATE_PERIOD)
+            .collect::<Vec<_>>();
+        assert!(peak(&unfiltered) > 0.95);
+
+        // the chain is read again once the current period of samples was played
+        set_shared_effects(&effects, &[AudioEffect::LowPass { cutoff: 100.0 }]);
+        let filtered = source.collect::<Vec<_>>();
+        assert!(peak(&filtered[EFFECTS_UPDATE_PERIOD..]) < 0.05);
+    }
+}
@@ -1,19 +1,51 @@
+mod analysis;
+mod animation_audio;
 mod audio;
+mod audio_input;
 mod audio_output;
+mod audio_sink;
 mod audio_source;
+mod effects;
+mod mixer;
+mod music;
+mod procedural;
+mod spatial;
+mod stream;
 
 pub mod prelude {
     #[doc(hidden)]
-    pub use crate::{Audio, AudioOutput, AudioSource, Decodable};
+    pub use crate::{
+        AnimationAudio, Audio, AudioAnalysis, AudioEffect, AudioEffects, AudioEmitter,
+        AudioGenerator, AudioInput, AudioListener, AudioMixer, AudioOutput, AudioSink, AudioSource,
+        AudioVelocity, Decodable, MusicController, MusicTrack, PlaybackSettings, ProceduralAudio,
+    };
 }
 
+pub(crate) use analysis::{AnalysisSource, SharedAnalysisBuffer};
+pub use analysis::{AudioAnalysis, AudioSpectrum};
+pub use animation_audio::*;
 pub use audio::*;
+pub use audio_input::*;
 pub use audio_output::*;
+pub use audio_sink::AudioSink;
+pub(crate) use audio_sink::Voice;
 pub use audio_source::*;
+pub(crate) use effects::{set_shared_effects, EffectsSource, SharedEffects};
+pub use effects::{AudioEffect, AudioEffects};
+pub use mixer::{AudioChannel, AudioMixer, VoiceStealing};
+pub(crate) use mixer::{ChannelMix, ChannelSource};
+pub use music::*;
+pub use procedural::{AudioGenerator, GeneratorSource, ProceduralAudio};
+pub use spatial::{AudioEmitter, AudioListener, AudioVelocity, SPEED_OF_SOUND};
+pub(crate) use spatial::{DopplerSource, SharedDoppler, SpatialSource, SpatialVolumes};
+pub use stream::LoopRegion;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use stream::StreamingSource;
+pub(crate) use stream::{LoopingSource, SpareDecoders};
 
 use bevy_app::prelude::*;
 use bevy_asset::AddAsset;
-use bevy_ecs::system::IntoExclusiveSystem;
+use bevy_ecs::system::{IntoExclusiveSystem, IntoSystem};
 
 /// Adds support for audio playback to an App
 #[derive(Default)]
@@ -23,13 +55,38 @@ impl Plugin for AudioPlugin {
     fn build(&self, app: &mut App) {
         app.init_non_send_resource::<AudioOutput<AudioSource>>()
             .add_asset::<AudioSource>()
+            .add_asset::<AudioSink>()
             .init_resource::<Audio<AudioSource>>()
+            .init_resource::<AudioMixer>()
+            .init_resource::<AudioAnalysis>()
+            .init_resource::<AudioInput>()
+            .init_non_send_resource::<AudioInputStream>()
+            .add_system_to_stage(CoreStage::PreUpdate, audio_input_system.exclusive_system())
+            .register_type::<AudioListener>()
+            .register_type::<AudioEmitter>()
+            .register_type::<AudioVelocity>()
+            .add_system_to_stage(CoreStage::PostUpdate, animation_audio_system.system())
+            .init_resource::<MusicController>()
+            .add_system_to_stage(CoreStage::PostUpdate, music_controller_system.system())
             .add_system_to_stage(
                 CoreStage::PostUpdate,
                 play_queued_audio_system::<AudioSource>.exclusive_system(),
+            )
+            .init_non_send_resource::<AudioOutput<ProceduralAudio>>()
+            .add_asset::<ProceduralAudio>()
+            .init_resource::<Audio<ProceduralAudio>>()
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                play_queued_audio_system::<ProceduralAudio>.exclusive_system(),
             );
 
         #[cfg(any(feature = "mp3", feature = "flac", feature = "wav", feature = "vorbis"))]
         app.init_asset_loader::<Mp3Loader>();
+
+        // the silent clip can only be decoded with WAV support
+        #[cfg(feature = "wav")]
+        if let Some(asset_server) = app.world.get_resource::<bevy_asset::AssetServer>() {
+            asset_server.set_fallback_asset(AudioSource::silence());
+        }
     }
 }
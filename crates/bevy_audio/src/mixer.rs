@@ -0,0 +1,618 @@
+use crate::{set_shared_effects, AudioEffect, AudioSink, SharedEffects};
+use bevy_asset::{Assets, HandleId};
+use bevy_utils::{Duration, HashMap, HashSet};
+use rodio::{Sample, Source};
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+/// The channels sounds are played in, like music, sound effects and voices, with their volumes,
+/// for the audio settings of games. The output of a channel is mixed into another channel, so
+/// the volume and the effects of a channel also apply to the channels routed to it.
+///
+/// Sounds are played in a channel with [`PlaybackSettings::channel`](crate::PlaybackSettings),
+/// or in the master channel. There are a master, a music, a sound effects and a voice channel by
+/// default, which are routed to the master channel, and channels can be added by name.
+///
+/// The number of sounds playing in a channel, including the channels routed to it, can be
+/// limited, so that many sounds played at once, like rapid gunfire, don't overload the mixer or
+/// drown out the other sounds. Limiting the master channel limits every sound.
+///
+/// ```
+/// # use bevy_audio::AudioMixer;
+/// # use bevy_ecs::prelude::*;
+/// fn mute_music(mut mixer: ResMut<AudioMixer>) {
+///     mixer.set_muted(AudioMixer::MUSIC, true);
+/// }
+/// # mute_music.system();
+/// ```
+#[derive(Debug)]
+pub struct AudioMixer {
+    channels: HashMap<String, AudioChannel>,
+}
+
+#[derive(Debug)]
+pub struct AudioChannel {
+    pub volume: f32,
+    pub muted: bool,
+    /// The channel the output of this channel is mixed into, `None` for the master channel
+    pub output: Option<String>,
+    /// The effects applied to the sounds of the channel, before the effects of the channel it's
+    /// routed to
+    pub effects: Vec<AudioEffect>,
+    /// The maximum number of sounds playing in the channel and the channels routed to it, `None`
+    /// for no limit
+    pub max_voices: Option<usize>,
+    pub voice_stealing: VoiceStealing,
+    mix: ChannelMix,
+}
+
+/// Which sound is stopped to play a new sound in a channel that has as many sounds as its voice
+/// limit. Sounds with a lower [`priority`](crate::PlaybackSettings::priority) are always stopped
+/// first.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VoiceStealing {
+    /// The sound that started playing first
+    #[default]
+    Oldest,
+    /// The sound played at the lowest volume, taking into account the volumes of its channels
+    /// and its distance to the listener. Paused sounds are the quietest.
+    Quietest,
+}
+
+/// The volume and the effect chain of the sounds of a channel, including those of the channels
+/// it's routed to, shared with its playing sounds.
+#[derive(Clone)]
+pub(crate) struct ChannelMix {
+    /// The bits of an `f32`
+    pub(crate) gain: Arc<AtomicU32>,
+    pub(crate) effects: SharedEffects,
+}
+
+impl Default for ChannelMix {
+    fn default() -> Self {
+        ChannelMix {
+            gain: Arc::new(AtomicU32::new(1f32.to_bits())),
+            effects: Default::default(),
+        }
+    }
+}
+
+impl std::fmt::Debug for ChannelMix {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ChannelMix")
+            .field("gain", &f32::from_bits(self.gain.load(Ordering::Relaxed)))
+            .field("effects", &*self.effects.lock())
+            .finish()
+    }
+}
+
+impl AudioChannel {
+    fn new(output: Option<String>) -> Self {
+        AudioChannel {
+            volume: 1.0,
+            muted: false,
+            output,
+            effects: Vec::new(),
+            max_voices: None,
+            voice_stealing: VoiceStealing::default(),
+            mix: Default::default(),
+        }
+    }
+}
+
+impl Default for AudioMixer {
+    fn default() -> Self {
+        let mut mixer = AudioMixer {
+            channels: Default::default(),
+        };
+        mixer
+            .channels
+            .insert(Self::MASTER.to_string(), AudioChannel::new(None));
+        mixer.add_channel(Self::MUSIC, Self::MASTER);
+        mixer.add_channel(Self::SFX, Self::MASTER);
+        mixer.add_channel(Self::VOICE, Self::MASTER);
+        mixer
+    }
+}
+
+impl AudioMixer {
+    pub const MASTER: &'static str = "master";
+    pub const MUSIC: &'static str = "music";
+    pub const SFX: &'static str = "sfx";
+    pub const VOICE: &'static str = "voice";
+
+    /// Adds a channel routed to the `output` channel, replacing the channel with the same name.
+    pub fn add_channel<S: Into<String>, O: Into<String>>(&mut self, name: S, output: O) {
+        self.channels
+            .insert(name.into(), AudioChannel::new(Some(output.into())));
+    }
+
+    /// Removes a channel, its sounds are then played in the master channel. The master channel
+    /// can't be removed.
+    pub fn remove_channel(&mut self, name: &str) -> Option<AudioChannel> {
+        if name == Self::MASTER {
+            return None;
+        }
+        self.channels.remove(name)
+    }
+
+    pub fn channel(&self, name: &str) -> Option<&AudioChannel> {
+        self.channels.get(name)
+    }
+
+    pub fn channel_mut(&mut self, name: &str) -> Option<&mut AudioChannel> {
+        self.channels.get_mut(name)
+    }
+
+    pub fn channel_names(&self) -> impl Iterator<Item = &str> {
+        self.channels.keys().map(|name| name.as_str())
+    }
+
+    /// The volume of a channel, 1.0 for unknown channels.
+    pub fn volume(&self, name: &str) -> f32 {
+        self.channel(name).map_or(1.0, |channel| channel.volume)
+    }
+
+    /// Sets the volume of a channel, adding it routed to the master channel if it doesn't exist.
+    pub fn set_volume(&mut self, name: &str, volume: f32) {
+        self.get_or_add_channel(name).volume = volume;
+    }
+
+    pub fn is_muted(&self, name: &str) -> bool {
+        self.channel(name).is_some_and(|channel| channel.muted)
+    }
+
+    /// Mutes or unmutes a channel, adding it routed to the master channel if it doesn't exist.
+    pub fn set_muted(&mut self, name: &str, muted: bool) {
+        self.get_or_add_channel(name).muted = muted;
+    }
+
+    /// Sets the effects of a channel, adding it routed to the master channel if it doesn't exist.
+    pub fn set_effects(&mut self, name: &str, effects: Vec<AudioEffect>) {
+        self.get_or_add_channel(name).effects = effects;
+    }
+
+    /// Limits the number of sounds playing in a channel and the channels routed to it, adding it
+    /// routed to the master channel if it doesn't exist. New sounds stop the sounds chosen by the
+    /// [`VoiceStealing`] of the channel, and aren't played if all of the sounds have a higher
+    /// priority.
+    pub fn set_max_voices(&mut self, name: &str, max_voices: Option<usize>) {
+        self.get_or_add_channel(name).max_voices = max_voices;
+    }
+
+    /// Sets which sounds are stopped first when the voice limit of a channel is reached, adding
+    /// it routed to the master channel if it doesn't exist.
+    pub fn set_voice_stealing(&mut self, name: &str, voice_stealing: VoiceStealing) {
+        self.get_or_add_channel(name).voice_stealing = voice_stealing;
+    }
+
+    fn get_or_add_channel(&mut self, name: &str) -> &mut AudioChannel {
+        if !self.channels.contains_key(name) {
+            self.add_channel(name, Self::MASTER);
+        }
+        self.channels.get_mut(name).unwrap()
+    }
+
+    /// The volume sounds of a channel are played at, after the volumes of the channels it's
+    /// routed to are applied. Unknown channels are treated like the master channel.
+    pub fn effective_volume(&self, name: &str) -> f32 {
+        let mut volume = 1.0;
+        for channel in self.routed_channels(name) {
+            if channel.muted {
+                return 0.0;
+            }
+            volume *= channel.volume;
+        }
+        volume
+    }
+
+    /// The effects applied to the sounds of a channel, followed by those of the channels it's
+    /// routed to. Unknown channels are treated like the master channel.
+    pub fn effective_effects(&self, name: &str) -> Vec<AudioEffect> {
+        self.routed_channels(name)
+            .flat_map(|channel| channel.effects.iter().copied())
+            .collect()
+    }
+
+    /// Whether the sounds of the channel `name` are mixed into the channel `target`, directly or
+    /// through other channels. Unknown channels are treated like the master channel.
+    pub fn is_routed_to(&self, name: &str, target: &str) -> bool {
+        match self.channels.get(target) {
+            Some(target) => self.is_routed_to_channel(name, target),
+            None => false,
+        }
+    }
+
+    fn is_routed_to_channel(&self, name: &str, target: &AudioChannel) -> bool {
+        self.routed_channels(name)
+            .any(|channel| std::ptr::eq(channel, target))
+    }
+
+    /// A channel followed by the channels it's routed to.
+    fn routed_channels<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a AudioChannel> {
+        let mut channel = self
+            .channels
+            .get(name)
+            .or_else(|| self.channels.get(Self::MASTER));
+        // channels routed in a cycle are only visited once each
+        let mut visited = Vec::new();
+        std::iter::from_fn(move || {
+            let current = channel.filter(|channel| {
+                !visited
+                    .iter()
+                    .any(|visited| std::ptr::eq(*visited, *channel))
+            })?;
+            visited.push(current);
+            channel = current
+                .output
+                .as_ref()
+                .and_then(|output| self.channels.get(output));
+            Some(current)
+        })
+    }
+
+    /// The mix of the sounds played in a channel, the master channel if `None` or unknown.
+    pub(crate) fn channel_mix(&self, name: Option<&str>) -> ChannelMix {
+        let name = name.unwrap_or(Self::MASTER);
+        self.channels
+            .get(name)
+            .or_else(|| self.channels.get(Self::MASTER))
+            .map(|channel| channel.mix.clone())
+            .unwrap_or_default()
+    }
+
+    /// Stops sounds to play a new sound with `priority` in the channel `name` within the voice
+    /// limits of the channels it's routed to, returning whether it can be played. Nothing is
+    /// stopped if it can't.
+    pub(crate) fn steal_voices(
+        &self,
+        audio_sinks: &mut Assets<AudioSink>,
+        name: &str,
+        priority: i32,
+    ) -> bool {
+        let mut stolen = HashSet::<HandleId>::default();
+        for channel in self.routed_channels(name) {
+            let max_voices = match channel.max_voices {
+                Some(max_voices) => max_voices,
+                None => continue,
+            };
+            let mut voices = audio_sinks
+                .iter()
+                .filter(|(id, audio_sink)| {
+                    !stolen.contains(id)
+                        && !audio_sink.is_finished()
+                        && self.is_routed_to_channel(audio_sink.channel(), channel)
+                })
+                .collect::<Vec<_>>();
+            let excess = (voices.len() + 1).saturating_sub(max_voices);
+            if excess == 0 {
+                continue;
+            }
+
+            voices.retain(|(_, audio_sink)| audio_sink.priority() <= priority);
+            if voices.len() < excess {
+                return false;
+            }
+            match channel.voice_stealing {
+                VoiceStealing::Oldest => voices.sort_by_key(|(_, audio_sink)| {
+                    (audio_sink.priority(), audio_sink.voice.started)
+                }),
+                VoiceStealing::Quietest => voices.sort_by(|(_, a), (_, b)| {
+                    let loudness = |audio_sink: &AudioSink| {
+                        audio_sink.loudness(self.effective_volume(audio_sink.channel()))
+                    };
+                    a.priority().cmp(&b.priority()).then_with(|| {
+                        loudness(a)
+                            .partial_cmp(&loudness(b))
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    })
+                }),
+            }
+            stolen.extend(voices.iter().take(excess).map(|(id, _)| *id));
+        }
+
+        for id in stolen {
+            if let Some(audio_sink) = audio_sinks.remove(id) {
+                audio_sink.stop();
+            }
+        }
+        true
+    }
+
+    /// Updates the volumes and the effects of the playing sounds for the current channel
+    /// settings.
+    pub(crate) fn update_channel_mixes(&self) {
+        for (name, channel) in self.channels.iter() {
+            channel
+                .mix
+                .gain
+                .store(self.effective_volume(name).to_bits(), Ordering::Relaxed);
+            set_shared_effects(&channel.mix.effects, &self.effective_effects(name));
+        }
+    }
+}
+
+/// Plays a source at the volume of its [`AudioMixer`] channel.
+pub(crate) struct ChannelSource<I> {
+    input: I,
+    gain: Arc<AtomicU32>,
+    current_gain: f32,
+    samples_until_update: usize,
+}
+
+/// The gain is read every this many samples rather than for every sample
+const CHANNEL_UPDATE_PERIOD: usize = 256;
+
+impl<I> ChannelSource<I> {
+    pub(crate) fn new(input: I, gain: Arc<AtomicU32>) -> Self {
+        let current_gain = f32::from_bits(gain.load(Ordering::Relaxed));
+        ChannelSource {
+            input,
+            gain,
+            current_gain,
+            samples_until_update: CHANNEL_UPDATE_PERIOD,
+        }
+    }
+}
+
+impl<I> Iterator for ChannelSource<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        if self.samples_until_update == 0 {
+            self.current_gain = f32::from_bits(self.gain.load(Ordering::Relaxed));
+            self.samples_until_update = CHANNEL_UPDATE_PERIOD;
+        }
+        self.samples_until_update -= 1;
+        self.input
+            .next()
+            .map(|sample| sample.amplify(self.current_gain))
+    }
+}
+
+impl<I> Source for ChannelSource<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.input.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Voice;
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin};
+    use bevy_tasks::{IoTaskPool, TaskPool};
+    use bevy_utils::Instant;
+    use rodio::{buffer::SamplesBuffer, source::Zero, Sink};
+
+    #[test]
+    fn default_channels() {
+        let mut mixer = AudioMixer::default();
+        let mut names = mixer.channel_names().collect::<Vec<_>>();
+        names.sort_unstable();
+        assert_eq!(names, vec!["master", "music", "sfx", "voice"]);
+        for name in [AudioMixer::MUSIC, AudioMixer::SFX, AudioMixer::VOICE] {
+            assert!(mixer.is_routed_to(name, AudioMixer::MASTER));
+        }
+        assert!(!mixer.is_routed_to(AudioMixer::MASTER, AudioMixer::MUSIC));
+        assert!(mixer.remove_channel(AudioMixer::MASTER).is_none());
+    }
+
+    #[test]
+    fn routing() {
+        let mut mixer = AudioMixer::default();
+        mixer.add_channel("footsteps", AudioMixer::SFX);
+        assert!(mixer.is_routed_to("footsteps", AudioMixer::SFX));
+        assert!(mixer.is_routed_to("footsteps", AudioMixer::MASTER));
+        assert!(mixer.is_routed_to("footsteps", "footsteps"));
+        assert!(!mixer.is_routed_to("footsteps", AudioMixer::MUSIC));
+        assert!(!mixer.is_routed_to("footsteps", "unknown"));
+        // unknown channels are played in the master channel
+        assert!(mixer.is_routed_to("unknown", AudioMixer::MASTER));
+        assert!(!mixer.is_routed_to("unknown", AudioMixer::SFX));
+
+        mixer.remove_channel(AudioMixer::SFX);
+        assert!(!mixer.is_routed_to("footsteps", AudioMixer::MASTER));
+    }
+
+    #[test]
+    fn routing_cycle() {
+        let mut mixer = AudioMixer::default();
+        mixer.add_channel("a", "b");
+        mixer.add_channel("b", "a");
+        mixer.set_volume("a", 0.5);
+        assert!(mixer.is_routed_to("a", "b"));
+        assert!(!mixer.is_routed_to("a", AudioMixer::MASTER));
+        assert_eq!(mixer.effective_volume("a"), 0.5);
+    }
+
+    #[test]
+    fn volume_products() {
+        let mut mixer = AudioMixer::default();
+        mixer.add_channel("footsteps", AudioMixer::SFX);
+        mixer.set_volume(AudioMixer::MASTER, 0.5);
+        mixer.set_volume(AudioMixer::SFX, 0.5);
+        mixer.set_volume("footsteps", 0.8);
+        assert_eq!(mixer.volume("footsteps"), 0.8);
+        assert_eq!(mixer.volume("unknown"), 1.0);
+        assert_eq!(mixer.effective_volume("footsteps"), 0.2);
+        assert_eq!(mixer.effective_volume(AudioMixer::SFX), 0.25);
+        assert_eq!(mixer.effective_volume(AudioMixer::MUSIC), 0.5);
+        assert_eq!(mixer.effective_volume("unknown"), 0.5);
+
+        // setting the volume of an unknown channel adds it
+        mixer.set_volume("ambience", 0.5);
+        assert!(mixer.is_routed_to("ambience", AudioMixer::MASTER));
+        assert_eq!(mixer.effective_volume("ambience"), 0.25);
+    }
+
+    #[test]
+    fn mute() {
+        let mut mixer = AudioMixer::default();
+        mixer.add_channel("footsteps", AudioMixer::SFX);
+        mixer.set_muted(AudioMixer::SFX, true);
+        assert!(mixer.is_muted(AudioMixer::SFX));
+        assert!(!mixer.is_muted("footsteps"));
+        assert_eq!(mixer.effective_volume("footsteps"), 0.0);
+        assert_eq!(mixer.effective_volume(AudioMixer::MUSIC), 1.0);
+
+        // muting keeps the volume
+        mixer.set_volume(AudioMixer::SFX, 0.5);
+        mixer.set_muted(AudioMixer::SFX, false);
+        assert_eq!(mixer.effective_volume("footsteps"), 0.5);
+    }
+
+    #[test]
+    fn channel_source_gain() {
+        let mut mixer = AudioMixer::default();
+        mixer.set_volume(AudioMixer::MUSIC, 0.5);
+        mixer.update_channel_mixes();
+        let mix = mixer.channel_mix(Some(AudioMixer::MUSIC));
+        let input = SamplesBuffer::new(1, 44100, vec![1.0f32; CHANNEL_UPDATE_PERIOD + 1]);
+        let mut source = ChannelSource::new(input, mix.gain);
+        assert_eq!(source.next(), Some(0.5));
+
+        // the new gain is read every CHANNEL_UPDATE_PERIOD samples
+        mixer.set_muted(AudioMixer::MASTER, true);
+        mixer.update_channel_mixes();
+        let samples = source.collect::<Vec<_>>();
+        assert_eq!(samples[CHANNEL_UPDATE_PERIOD - 2], 0.5);
+        assert_eq!(samples[CHANNEL_UPDATE_PERIOD - 1], 0.0);
+    }
+
+    fn sinks_app() -> App {
+        let mut app = App::new();
+        app.insert_resource(IoTaskPool(TaskPool::new()))
+            .add_plugin(AssetPlugin)
+            .add_asset::<AudioSink>();
+        app
+    }
+
+    /// Adds a sound that keeps playing, started `age` seconds ago
+    fn play(
+        audio_sinks: &mut Assets<AudioSink>,
+        channel: &str,
+        priority: i32,
+        age: u64,
+    ) -> HandleId {
+        // an idle sink isn't played on a device, so its sounds never finish
+        let (sink, _output) = Sink::new_idle();
+        sink.append(Zero::<f32>::new(1, 44100));
+        let voice = Voice {
+            channel: channel.to_string(),
+            priority,
+            started: Instant::now() - Duration::from_secs(age),
+            spatial_volumes: None,
+        };
+        audio_sinks
+            .add(AudioSink::new(sink, Default::default(), voice))
+            .id
+    }
+
+    fn playing(audio_sinks: &Assets<AudioSink>) -> Vec<HandleId> {
+        let mut ids = audio_sinks.ids().collect::<Vec<_>>();
+        ids.sort();
+        ids
+    }
+
+    fn sorted(mut ids: Vec<HandleId>) -> Vec<HandleId> {
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn steal_oldest_voice() {
+        let mut app = sinks_app();
+        let mut audio_sinks = app.world.get_resource_mut::<Assets<AudioSink>>().unwrap();
+        let mut mixer = AudioMixer::default();
+        let old = play(&mut audio_sinks, AudioMixer::SFX, 0, 2);
+        let new = play(&mut audio_sinks, AudioMixer::SFX, 0, 1);
+        let music = play(&mut audio_sinks, AudioMixer::MUSIC, 0, 3);
+
+        assert!(mixer.steal_voices(&mut audio_sinks, AudioMixer::SFX, 0));
+        assert_eq!(playing(&audio_sinks).len(), 3);
+
+        mixer.set_max_voices(AudioMixer::SFX, Some(2));
+        assert!(mixer.steal_voices(&mut audio_sinks, AudioMixer::SFX, 0));
+        assert_eq!(playing(&audio_sinks), sorted(vec![new, music]));
+        assert!(audio_sinks.get(old).is_none());
+    }
+
+    #[test]
+    fn steal_lower_priority_voices_first() {
+        let mut app = sinks_app();
+        let mut audio_sinks = app.world.get_resource_mut::<Assets<AudioSink>>().unwrap();
+        let mut mixer = AudioMixer::default();
+        mixer.set_max_voices(AudioMixer::SFX, Some(2));
+        let important = play(&mut audio_sinks, AudioMixer::SFX, 1, 2);
+        play(&mut audio_sinks, AudioMixer::SFX, 0, 1);
+
+        assert!(mixer.steal_voices(&mut audio_sinks, AudioMixer::SFX, 0));
+        assert_eq!(playing(&audio_sinks), vec![important]);
+
+        // sounds with a higher priority than the new sound are never stopped
+        let other = play(&mut audio_sinks, AudioMixer::SFX, 1, 0);
+        assert!(!mixer.steal_voices(&mut audio_sinks, AudioMixer::SFX, 0));
+        assert_eq!(playing(&audio_sinks), sorted(vec![important, other]));
+        assert!(mixer.steal_voices(&mut audio_sinks, AudioMixer::SFX, 1));
+        assert_eq!(playing(&audio_sinks), vec![other]);
+    }
+
+    #[test]
+    fn steal_voices_of_routed_channels() {
+        let mut app = sinks_app();
+        let mut audio_sinks = app.world.get_resource_mut::<Assets<AudioSink>>().unwrap();
+        let mut mixer = AudioMixer::default();
+        mixer.set_max_voices(AudioMixer::MASTER, Some(2));
+        play(&mut audio_sinks, AudioMixer::MUSIC, 0, 2);
+        let voice = play(&mut audio_sinks, AudioMixer::VOICE, 0, 1);
+
+        // the limit of the master channel applies to the sounds of every channel
+        assert!(mixer.steal_voices(&mut audio_sinks, AudioMixer::SFX, 0));
+        assert_eq!(playing(&audio_sinks), vec![voice]);
+    }
+
+    #[test]
+    fn steal_quietest_voice() {
+        let mut app = sinks_app();
+        let mut audio_sinks = app.world.get_resource_mut::<Assets<AudioSink>>().unwrap();
+        let mut mixer = AudioMixer::default();
+        mixer.set_max_voices(AudioMixer::MASTER, Some(3));
+        mixer.set_voice_stealing(AudioMixer::MASTER, VoiceStealing::Quietest);
+        mixer.set_volume(AudioMixer::MUSIC, 0.5);
+        let loud = play(&mut audio_sinks, AudioMixer::SFX, 0, 0);
+        let quiet = play(&mut audio_sinks, AudioMixer::SFX, 0, 1);
+        audio_sinks.get(quiet).unwrap().set_volume(0.8);
+        let music = play(&mut audio_sinks, AudioMixer::MUSIC, 0, 2);
+        let paused = play(&mut audio_sinks, AudioMixer::SFX, 0, 3);
+        audio_sinks.get(paused).unwrap().pause();
+
+        // the sound with the lowest volume, once multiplied by the volume of its channel
+        assert!(mixer.steal_voices(&mut audio_sinks, AudioMixer::SFX, 0));
+        assert_eq!(playing(&audio_sinks), sorted(vec![loud, quiet]));
+        assert!(audio_sinks.get(music).is_none());
+    }
+}
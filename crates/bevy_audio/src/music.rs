@@ -0,0 +1,606 @@
+use crate::{Audio, AudioMixer, AudioSink, AudioSource, Decodable, PlaybackSettings};
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::system::{Res, ResMut};
+use bevy_utils::{Duration, Instant};
+use rodio::Source;
+use std::collections::VecDeque;
+
+/// Plays music tracks one after the other, crossfading between them. A track can be made of
+/// layered stems, like drums and strings added over a base loop, which fade in and out with the
+/// [intensity](MusicController::set_intensity) of the gameplay.
+///
+/// The tracks are played in the [`AudioMixer::MUSIC`] channel by default. The stems of a track
+/// start playing together once all of their sources loaded, so they stay in sync.
+///
+/// ```
+/// # use bevy_asset::AssetServer;
+/// # use bevy_audio::{MusicController, MusicTrack};
+/// # use bevy_ecs::prelude::*;
+/// # #[derive(Component)]
+/// # struct Enemy;
+/// fn start_music(asset_server: Res<AssetServer>, mut music: ResMut<MusicController>) {
+///     music.play(
+///         MusicTrack::new(asset_server.load("music/explore.ogg"))
+///             .with_stem(asset_server.load("music/explore_drums.ogg"), 0.2, 0.5)
+///             .with_stem(asset_server.load("music/explore_brass.ogg"), 0.6, 1.0)
+///             .looped(),
+///     );
+/// }
+///
+/// fn follow_danger(mut music: ResMut<MusicController>, enemies: Query<&Enemy>) {
+///     music.set_intensity(enemies.iter().count() as f32 / 10.0);
+/// }
+/// # start_music.system();
+/// # follow_danger.system();
+/// ```
+#[derive(Debug)]
+pub struct MusicController {
+    queue: VecDeque<MusicTrack>,
+    current: Option<PlayingTrack>,
+    /// The tracks that were replaced and are fading out
+    fading_out: Vec<PlayingTrack>,
+    crossfade: Duration,
+    intensity: f32,
+    intensity_fade: Duration,
+    volume: f32,
+    channel: String,
+    last_update: Option<Instant>,
+}
+
+/// A music track played by the [`MusicController`], made of one or more stems played together.
+#[derive(Debug, Clone)]
+pub struct MusicTrack {
+    pub stems: Vec<MusicStem>,
+    /// Loops the track until it's skipped, rather than playing the next queued track once it
+    /// ends
+    pub looped: bool,
+}
+
+/// A layer of a [`MusicTrack`], heard depending on the intensity of the [`MusicController`]. It's
+/// silent at and below its minimum intensity, and fades in up to full volume at its maximum
+/// intensity.
+#[derive(Debug, Clone)]
+pub struct MusicStem {
+    pub source: Handle<AudioSource>,
+    pub min_intensity: f32,
+    pub max_intensity: f32,
+}
+
+impl MusicStem {
+    /// A stem that is always heard, whatever the intensity.
+    pub fn new(source: Handle<AudioSource>) -> Self {
+        MusicStem {
+            source,
+            min_intensity: f32::NEG_INFINITY,
+            max_intensity: f32::NEG_INFINITY,
+        }
+    }
+
+    /// The volume of the stem at `intensity`, from 0 to 1.
+    pub fn volume(&self, intensity: f32) -> f32 {
+        if intensity >= self.max_intensity {
+            1.0
+        } else if intensity <= self.min_intensity {
+            0.0
+        } else {
+            (intensity - self.min_intensity) / (self.max_intensity - self.min_intensity)
+        }
+    }
+}
+
+impl MusicTrack {
+    /// A track with a single stem, always heard.
+    pub fn new(source: Handle<AudioSource>) -> Self {
+        MusicTrack {
+            stems: vec![MusicStem::new(source)],
+            looped: false,
+        }
+    }
+
+    /// Adds a stem fading in as the intensity goes from `min_intensity` to `max_intensity`.
+    pub fn with_stem(
+        mut self,
+        source: Handle<AudioSource>,
+        min_intensity: f32,
+        max_intensity: f32,
+    ) -> Self {
+        self.stems.push(MusicStem {
+            source,
+            min_intensity,
+            max_intensity,
+        });
+        self
+    }
+
+    pub fn looped(mut self) -> Self {
+        self.looped = true;
+        self
+    }
+}
+
+/// A track of the [`MusicController`] that started playing, or is waiting for its sources to
+/// load
+#[derive(Debug)]
+struct PlayingTrack {
+    track: MusicTrack,
+    started: bool,
+    /// The sinks of the stems, once the track started
+    sinks: Vec<Handle<AudioSink>>,
+    /// The volumes of the stems, following the intensity
+    stem_volumes: Vec<f32>,
+    /// From 0 when the track is silent to 1 when it's faded in
+    fade: f32,
+    /// The duration of the track, if its source knows it
+    duration: Option<Duration>,
+}
+
+impl Default for MusicController {
+    fn default() -> Self {
+        MusicController {
+            queue: Default::default(),
+            current: None,
+            fading_out: Vec::new(),
+            crossfade: Duration::from_secs(2),
+            intensity: 0.0,
+            intensity_fade: Duration::from_secs(1),
+            volume: 1.0,
+            channel: AudioMixer::MUSIC.to_string(),
+            last_update: None,
+        }
+    }
+}
+
+impl MusicController {
+    /// Plays a track after the queued tracks.
+    pub fn queue(&mut self, track: MusicTrack) {
+        self.queue.push_back(track);
+    }
+
+    /// Crossfades to a track now, clearing the queued tracks.
+    pub fn play(&mut self, track: MusicTrack) {
+        self.queue.clear();
+        self.queue.push_back(track);
+        self.skip();
+    }
+
+    /// Crossfades from the current track to the next queued track, or fades it out if no track
+    /// is queued.
+    pub fn skip(&mut self) {
+        self.fading_out.extend(self.current.take());
+    }
+
+    /// Fades out the current track and clears the queued tracks.
+    pub fn stop(&mut self) {
+        self.queue.clear();
+        self.skip();
+    }
+
+    /// The track playing, or waiting for its sources to load.
+    pub fn current_track(&self) -> Option<&MusicTrack> {
+        self.current.as_ref().map(|current| &current.track)
+    }
+
+    pub fn queued_tracks(&self) -> impl Iterator<Item = &MusicTrack> {
+        self.queue.iter()
+    }
+
+    pub fn crossfade(&self) -> Duration {
+        self.crossfade
+    }
+
+    /// Sets how long tracks take to fade in and out. Defaults to 2 seconds.
+    pub fn set_crossfade(&mut self, crossfade: Duration) {
+        self.crossfade = crossfade;
+    }
+
+    pub fn intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    /// Sets the intensity the stems of the tracks are heard at, usually from 0 for calm
+    /// moments to 1 for the most intense ones.
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity;
+    }
+
+    pub fn intensity_fade(&self) -> Duration {
+        self.intensity_fade
+    }
+
+    /// Sets how long stems take to fade from silent to full volume when the intensity changes.
+    /// Defaults to 1 second.
+    pub fn set_intensity_fade(&mut self, intensity_fade: Duration) {
+        self.intensity_fade = intensity_fade;
+    }
+
+    pub fn volume(&self) -> f32 {
+        self.volume
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume;
+    }
+
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// Sets the [`AudioMixer`] channel the tracks that start playing from now on are played in.
+    pub fn set_channel<S: Into<String>>(&mut self, channel: S) {
+        self.channel = channel.into();
+    }
+
+    fn update(
+        &mut self,
+        audio: &Audio<AudioSource>,
+        audio_sources: &Assets<AudioSource>,
+        audio_sinks: &Assets<AudioSink>,
+    ) {
+        let now = Instant::now();
+        let delta_seconds = self
+            .last_update
+            .map_or(0.0, |last_update| (now - last_update).as_secs_f32());
+        self.last_update = Some(now);
+
+        // crossfade to the next track when the current track is about to end
+        if let Some(current) = &self.current {
+            if current.is_ending(audio_sinks, self.crossfade)
+                && (!self.queue.is_empty() || current.is_finished(audio_sinks))
+            {
+                self.skip();
+            }
+        }
+        if self.current.is_none() {
+            self.current = self.queue.pop_front().map(PlayingTrack::new);
+        }
+        if let Some(current) = &mut self.current {
+            let is_loaded = current
+                .track
+                .stems
+                .iter()
+                .all(|stem| audio_sources.contains(&stem.source));
+            if !current.started && is_loaded {
+                current.start(
+                    audio,
+                    audio_sources,
+                    &self.channel,
+                    self.volume,
+                    self.intensity,
+                    self.crossfade,
+                );
+            }
+        }
+
+        let fade_step = step(delta_seconds, self.crossfade);
+        let stem_step = step(delta_seconds, self.intensity_fade);
+        if let Some(current) = &mut self.current {
+            if current.started {
+                current.fade = (current.fade + fade_step).min(1.0);
+            }
+        }
+        for track in self.fading_out.iter_mut() {
+            track.fade -= fade_step;
+        }
+        for track in self.current.iter_mut().chain(self.fading_out.iter_mut()) {
+            track.update_stem_volumes(self.intensity, stem_step);
+            track.set_volumes(audio_sinks, self.volume);
+        }
+        self.fading_out.retain(|track| {
+            if track.fade > 0.0 {
+                return true;
+            }
+            track.stop(audio_sinks);
+            false
+        });
+    }
+}
+
+/// How much a fade progresses in `delta_seconds`, from 0 to 1.
+fn step(delta_seconds: f32, duration: Duration) -> f32 {
+    let duration = duration.as_secs_f32();
+    if duration > 0.0 {
+        delta_seconds / duration
+    } else {
+        1.0
+    }
+}
+
+impl PlayingTrack {
+    fn new(track: MusicTrack) -> Self {
+        PlayingTrack {
+            track,
+            started: false,
+            sinks: Vec::new(),
+            stem_volumes: Vec::new(),
+            fade: 0.0,
+            duration: None,
+        }
+    }
+
+    fn start(
+        &mut self,
+        audio: &Audio<AudioSource>,
+        audio_sources: &Assets<AudioSource>,
+        channel: &str,
+        volume: f32,
+        intensity: f32,
+        crossfade: Duration,
+    ) {
+        self.started = true;
+        self.fade = if crossfade > Duration::default() {
+            0.0
+        } else {
+            1.0
+        };
+        self.duration = self
+            .track
+            .stems
+            .first()
+            .and_then(|stem| audio_sources.get(&stem.source))
+            .and_then(|audio_source| audio_source.decoder().total_duration());
+        for stem in self.track.stems.iter() {
+            let stem_volume = stem.volume(intensity);
+            let mut settings = PlaybackSettings::default()
+                .with_channel(channel)
+                .with_volume(volume * self.fade * stem_volume);
+            if self.track.looped {
+                settings = settings.looped();
+            }
+            self.sinks
+                .push(audio.play_with_settings(stem.source.clone(), settings));
+            self.stem_volumes.push(stem_volume);
+        }
+    }
+
+    /// Whether the track started and all of its stems finished playing.
+    fn is_finished(&self, audio_sinks: &Assets<AudioSink>) -> bool {
+        self.started && self.sinks.iter().all(|sink| !audio_sinks.contains(sink))
+    }
+
+    /// Whether the track ends within `crossfade`.
+    fn is_ending(&self, audio_sinks: &Assets<AudioSink>, crossfade: Duration) -> bool {
+        if self.track.looped || !self.started {
+            return false;
+        }
+        let position = self
+            .sinks
+            .iter()
+            .find_map(|sink| audio_sinks.get(sink))
+            .map(|audio_sink| audio_sink.position());
+        match (position, self.duration) {
+            (Some(position), Some(duration)) => position + crossfade >= duration,
+            _ => self.is_finished(audio_sinks),
+        }
+    }
+
+    fn update_stem_volumes(&mut self, intensity: f32, step: f32) {
+        for (stem, volume) in self.track.stems.iter().zip(self.stem_volumes.iter_mut()) {
+            *volume += (stem.volume(intensity) - *volume).clamp(-step, step);
+        }
+    }
+
+    fn set_volumes(&self, audio_sinks: &Assets<AudioSink>, volume: f32) {
+        for (sink, stem_volume) in self.sinks.iter().zip(self.stem_volumes.iter()) {
+            if let Some(audio_sink) = audio_sinks.get(sink) {
+                audio_sink.set_volume(volume * self.fade.max(0.0) * stem_volume);
+            }
+        }
+    }
+
+    fn stop(&self, audio_sinks: &Assets<AudioSink>) {
+        for sink in self.sinks.iter() {
+            if let Some(audio_sink) = audio_sinks.get(sink) {
+                audio_sink.stop();
+            }
+        }
+    }
+}
+
+/// Starts, crossfades and mixes the stems of the tracks of the [`MusicController`].
+pub fn music_controller_system(
+    mut music: ResMut<MusicController>,
+    audio: Res<Audio<AudioSource>>,
+    audio_sources: Res<Assets<AudioSource>>,
+    audio_sinks: Res<Assets<AudioSink>>,
+) {
+    music.update(&audio, &audio_sources, &audio_sinks);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Voice;
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin, HandleId};
+    use bevy_tasks::{IoTaskPool, TaskPool};
+    use rodio::{source::Zero, Sink};
+    use std::sync::{atomic::AtomicU64, Arc};
+
+    fn music_app() -> App {
+        let mut app = App::new();
+        app.insert_resource(IoTaskPool(TaskPool::new()))
+            .add_plugin(AssetPlugin)
+            .add_asset::<AudioSource>()
+            .add_asset::<AudioSink>();
+        app
+    }
+
+    fn source() -> Handle<AudioSource> {
+        Handle::weak(HandleId::random::<AudioSource>())
+    }
+
+    /// A track that started playing `position` into its source, lasting `duration`
+    fn playing(
+        audio_sinks: &mut Assets<AudioSink>,
+        track: MusicTrack,
+        fade: f32,
+        position: Duration,
+        duration: Duration,
+    ) -> PlayingTrack {
+        let mut playing = PlayingTrack::new(track);
+        playing.started = true;
+        playing.fade = fade;
+        playing.duration = Some(duration);
+        for stem in playing.track.stems.iter() {
+            // an idle sink isn't played on a device, so its sounds never finish
+            let (sink, _output) = Sink::new_idle();
+            sink.append(Zero::<f32>::new(1, 44100));
+            let voice = Voice {
+                channel: AudioMixer::MUSIC.to_string(),
+                priority: 0,
+                started: Instant::now(),
+                spatial_volumes: None,
+            };
+            let position = Arc::new(AtomicU64::new(position.as_nanos() as u64));
+            playing
+                .sinks
+                .push(audio_sinks.add(AudioSink::new(sink, position, voice)));
+            playing.stem_volumes.push(stem.volume(0.0));
+        }
+        playing
+    }
+
+    /// Updates `music` as if `seconds` passed since its last update
+    fn update(music: &mut MusicController, app: &App, seconds: f32) {
+        music.last_update = Some(Instant::now() - Duration::from_secs_f32(seconds));
+        music.update(
+            &Audio::default(),
+            app.world.get_resource::<Assets<AudioSource>>().unwrap(),
+            app.world.get_resource::<Assets<AudioSink>>().unwrap(),
+        );
+    }
+
+    fn sink_volume(app: &App, sink: &Handle<AudioSink>) -> f32 {
+        let audio_sinks = app.world.get_resource::<Assets<AudioSink>>().unwrap();
+        audio_sinks.get(sink).unwrap().volume()
+    }
+
+    fn assert_approx_eq(a: f32, b: f32) {
+        assert!((a - b).abs() < 0.01, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn stem_volume() {
+        let stem = MusicStem::new(source());
+        assert_eq!(stem.volume(-10.0), 1.0);
+        assert_eq!(stem.volume(0.0), 1.0);
+
+        let track = MusicTrack::new(source()).with_stem(source(), 0.25, 0.75);
+        let stem = &track.stems[1];
+        assert_eq!(stem.volume(0.0), 0.0);
+        assert_eq!(stem.volume(0.25), 0.0);
+        assert_eq!(stem.volume(0.5), 0.5);
+        assert_eq!(stem.volume(0.75), 1.0);
+        assert_eq!(stem.volume(2.0), 1.0);
+    }
+
+    #[test]
+    fn fade_step() {
+        assert_eq!(step(0.5, Duration::from_secs(2)), 0.25);
+        assert_eq!(step(0.5, Duration::default()), 1.0);
+    }
+
+    #[test]
+    fn crossfade() {
+        let mut app = music_app();
+        let mut music = MusicController::default();
+        music.set_volume(0.5);
+        let mut audio_sinks = app.world.get_resource_mut::<Assets<AudioSink>>().unwrap();
+        let hour = Duration::from_secs(3600);
+        let old = playing(
+            &mut audio_sinks,
+            MusicTrack::new(source()),
+            1.0,
+            Duration::default(),
+            hour,
+        );
+        let new = playing(
+            &mut audio_sinks,
+            MusicTrack::new(source()),
+            0.0,
+            Duration::default(),
+            hour,
+        );
+        let old_sink = old.sinks[0].clone();
+        let new_sink = new.sinks[0].clone();
+        music.current = Some(old);
+        music.skip();
+        music.current = Some(new);
+
+        update(&mut music, &app, 0.5);
+        assert_approx_eq(music.current.as_ref().unwrap().fade, 0.25);
+        assert_approx_eq(music.fading_out[0].fade, 0.75);
+        assert_approx_eq(sink_volume(&app, &new_sink), 0.125);
+        assert_approx_eq(sink_volume(&app, &old_sink), 0.375);
+
+        update(&mut music, &app, 2.0);
+        assert_eq!(music.current.as_ref().unwrap().fade, 1.0);
+        assert!(music.fading_out.is_empty());
+        assert_eq!(sink_volume(&app, &new_sink), 0.5);
+        assert_eq!(sink_volume(&app, &old_sink), 0.0);
+    }
+
+    #[test]
+    fn crossfade_before_end() {
+        let mut app = music_app();
+        let mut music = MusicController::default();
+        let mut audio_sinks = app.world.get_resource_mut::<Assets<AudioSink>>().unwrap();
+        let track = MusicTrack::new(source());
+        music.current = Some(playing(
+            &mut audio_sinks,
+            track,
+            1.0,
+            Duration::from_secs(9),
+            Duration::from_secs(10),
+        ));
+
+        // the track keeps playing until it ends when no track is queued
+        update(&mut music, &app, 0.0);
+        assert!(music.fading_out.is_empty());
+
+        // and crossfades to the next track when it ends within the crossfade
+        let next = MusicTrack::new(source());
+        let next_source = next.stems[0].source.clone();
+        music.queue(next);
+        update(&mut music, &app, 0.0);
+        assert_eq!(music.fading_out.len(), 1);
+        let current = music.current.as_ref().unwrap();
+        assert_eq!(current.track.stems[0].source, next_source);
+        // waiting for its source to load
+        assert!(!current.started);
+        assert!(music.queued_tracks().next().is_none());
+    }
+
+    #[test]
+    fn stem_intensity_fade() {
+        let mut app = music_app();
+        let mut music = MusicController::default();
+        let mut audio_sinks = app.world.get_resource_mut::<Assets<AudioSink>>().unwrap();
+        let track = MusicTrack::new(source()).with_stem(source(), 0.0, 1.0);
+        let current = playing(
+            &mut audio_sinks,
+            track,
+            1.0,
+            Duration::default(),
+            Duration::from_secs(3600),
+        );
+        let sinks = current.sinks.clone();
+        music.current = Some(current);
+        music.set_intensity(1.0);
+
+        update(&mut music, &app, 0.5);
+        assert_eq!(sink_volume(&app, &sinks[0]), 1.0);
+        assert_approx_eq(sink_volume(&app, &sinks[1]), 0.5);
+
+        update(&mut music, &app, 1.0);
+        assert_eq!(sink_volume(&app, &sinks[1]), 1.0);
+
+        // back down, following the intensity fade
+        music.set_intensity(0.5);
+        music.set_intensity_fade(Duration::from_secs(2));
+        update(&mut music, &app, 0.5);
+        assert_approx_eq(sink_volume(&app, &sinks[1]), 0.75);
+        update(&mut music, &app, 2.0);
+        assert_eq!(sink_volume(&app, &sinks[1]), 0.5);
+    }
+}
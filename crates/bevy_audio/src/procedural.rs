@@ -0,0 +1,112 @@
+use crate::Decodable;
+use bevy_reflect::TypeUuid;
+use bevy_utils::Duration;
+use rodio::Source;
+use std::{fmt, sync::Arc};
+
+/// Produces the samples of a [`ProceduralAudio`] on demand, on the audio thread. Parameters
+/// changed while the sound plays, like the RPM of an engine, can be shared with atomics.
+pub trait AudioGenerator: Send + Sync + 'static {
+    /// The next sample, interleaved if there are several channels. `None` ends the sound.
+    fn next_sample(&mut self) -> Option<f32>;
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        44_100
+    }
+}
+
+/// A sound whose samples are produced by an [`AudioGenerator`], like a synthesizer or engine
+/// noise. It's played with the `Audio<ProceduralAudio>` resource, through the same
+/// [`AudioMixer`](crate::AudioMixer) channels and effects as sounds loaded from files.
+///
+/// ```
+/// # use bevy_audio::{Audio, AudioGenerator, ProceduralAudio};
+/// # use bevy_asset::Assets;
+/// # use bevy_ecs::prelude::*;
+/// struct Sine {
+///     frequency: f32,
+///     phase: f32,
+/// }
+///
+/// impl AudioGenerator for Sine {
+///     fn next_sample(&mut self) -> Option<f32> {
+///         self.phase = (self.phase + self.frequency / 44_100.0) % 1.0;
+///         Some((self.phase * std::f32::consts::TAU).sin() * 0.2)
+///     }
+/// }
+///
+/// fn play_tone(mut sounds: ResMut<Assets<ProceduralAudio>>, audio: Res<Audio<ProceduralAudio>>) {
+///     let tone = sounds.add(ProceduralAudio::new(|| Sine { frequency: 440.0, phase: 0.0 }));
+///     audio.play(tone);
+/// }
+/// # play_tone.system();
+/// ```
+#[derive(Clone, TypeUuid)]
+#[uuid = "c3bd2046-ad8c-429e-b41c-12f2f086256f"]
+pub struct ProceduralAudio {
+    new_generator: Arc<dyn Fn() -> Box<dyn AudioGenerator> + Send + Sync>,
+}
+
+impl fmt::Debug for ProceduralAudio {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ProceduralAudio").finish()
+    }
+}
+
+impl ProceduralAudio {
+    /// Each playback of the sound gets a new generator created by `new_generator`.
+    pub fn new<G, F>(new_generator: F) -> Self
+    where
+        G: AudioGenerator,
+        F: Fn() -> G + Send + Sync + 'static,
+    {
+        ProceduralAudio {
+            new_generator: Arc::new(move || Box::new(new_generator())),
+        }
+    }
+}
+
+impl Decodable for ProceduralAudio {
+    type Decoder = GeneratorSource;
+
+    fn decoder(&self) -> Self::Decoder {
+        GeneratorSource {
+            generator: (self.new_generator)(),
+        }
+    }
+}
+
+/// The [`Source`] of a playing [`ProceduralAudio`]
+pub struct GeneratorSource {
+    generator: Box<dyn AudioGenerator>,
+}
+
+impl Iterator for GeneratorSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        self.generator.next_sample()
+    }
+}
+
+impl Source for GeneratorSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.generator.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.generator.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
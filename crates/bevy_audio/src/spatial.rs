@@ -0,0 +1,529 @@
+use bevy_ecs::{component::Component, reflect::ReflectComponent};
+use bevy_math::Vec3;
+use bevy_reflect::Reflect;
+use bevy_transform::components::GlobalTransform;
+use parking_lot::Mutex;
+use rodio::Source;
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// The speed of sound in air, in units per second, assuming a unit is a meter
+pub const SPEED_OF_SOUND: f32 = 343.0;
+
+/// The entity spatial sounds are heard from, usually the camera. Sounds are panned based on its
+/// [`GlobalTransform`]. If there are several listeners, one of them is used.
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[reflect(Component)]
+pub struct AudioListener;
+
+/// Plays sounds positioned at its entity's [`GlobalTransform`], see
+/// [`Audio::play_spatial`](crate::Audio::play_spatial). Sounds attenuate with the distance to
+/// the [`AudioListener`] and follow the entity as it moves. They stop when the entity is
+/// despawned.
+///
+/// Sounds of emitters moving toward or away from the listener are pitched up or down by the
+/// Doppler effect, see [`AudioVelocity`].
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct AudioEmitter {
+    /// The distance within which sounds play at full volume
+    pub reference_distance: f32,
+    /// The distance beyond which sounds are silent
+    pub max_distance: f32,
+    /// How fast sounds get quieter past the reference distance
+    pub rolloff: f32,
+    /// Scales the velocities used for the Doppler effect, 0 disables it
+    pub doppler_factor: f32,
+    /// How fast high frequencies are absorbed by the air with the distance, 0 disables it
+    pub air_absorption: f32,
+}
+
+/// The velocity of an [`AudioEmitter`] or of the [`AudioListener`], for the Doppler effect.
+/// Without it, the velocity is computed from the movement of the entity's [`GlobalTransform`]
+/// between frames, which makes entities that teleport sound like they move very fast.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct AudioVelocity(pub Vec3);
+
+impl Default for AudioEmitter {
+    fn default() -> Self {
+        AudioEmitter {
+            reference_distance: 1.0,
+            max_distance: 100.0,
+            rolloff: 1.0,
+            doppler_factor: 1.0,
+            air_absorption: 0.0,
+        }
+    }
+}
+
+impl AudioEmitter {
+    /// The volume of a sound at `distance` from the listener, from 0 to 1.
+    pub fn attenuation(&self, distance: f32) -> f32 {
+        if distance >= self.max_distance {
+            return 0.0;
+        }
+        let reference_distance = self.reference_distance.max(f32::EPSILON);
+        let distance = distance.max(reference_distance);
+        reference_distance / (reference_distance + self.rolloff * (distance - reference_distance))
+    }
+
+    /// The volumes of the left and right channels of a sound played by an emitter at
+    /// `emitter_position` for the listener.
+    pub fn channel_volumes(&self, emitter_position: Vec3, listener: &GlobalTransform) -> [f32; 2] {
+        let offset = emitter_position - listener.translation;
+        let distance = offset.length();
+        let attenuation = self.attenuation(distance);
+        if distance <= f32::EPSILON {
+            return [attenuation; 2];
+        }
+        // equal power panning from the direction of the emitter in the listener's space
+        let pan = (listener.rotation.inverse() * offset).x / distance;
+        let angle = (pan + 1.0) * std::f32::consts::FRAC_PI_4;
+        [attenuation * angle.cos(), attenuation * angle.sin()]
+    }
+
+    /// How much faster a sound plays because of the Doppler effect, above 1 when the emitter
+    /// and the listener move toward each other. It's kept between 0.5 and 2.
+    pub fn doppler_ratio(
+        &self,
+        emitter_position: Vec3,
+        emitter_velocity: Vec3,
+        listener_position: Vec3,
+        listener_velocity: Vec3,
+    ) -> f32 {
+        let offset = emitter_position - listener_position;
+        let distance = offset.length();
+        if distance <= f32::EPSILON || self.doppler_factor == 0.0 {
+            return 1.0;
+        }
+        let direction = offset / distance;
+        // the speeds toward each other along the line between them, below the speed of sound
+        let max_speed = SPEED_OF_SOUND * 0.99;
+        let listener_speed =
+            (listener_velocity.dot(direction) * self.doppler_factor).clamp(-max_speed, max_speed);
+        let emitter_speed =
+            (-emitter_velocity.dot(direction) * self.doppler_factor).clamp(-max_speed, max_speed);
+        ((SPEED_OF_SOUND + listener_speed) / (SPEED_OF_SOUND - emitter_speed)).clamp(0.5, 2.0)
+    }
+
+    /// The cutoff frequency of the low pass filter absorbing the high frequencies of a sound at
+    /// `distance` from the listener, `None` without air absorption.
+    pub fn air_absorption_cutoff(&self, distance: f32) -> Option<f32> {
+        if self.air_absorption <= 0.0 {
+            return None;
+        }
+        Some(20000.0 / (1.0 + self.air_absorption * distance))
+    }
+}
+
+/// The channel volumes of a playing spatial sound, updated as its emitter and the listener move.
+pub(crate) type SpatialVolumes = Arc<Mutex<[f32; 2]>>;
+
+/// The Doppler ratio of a playing spatial sound, as the bits of an `f32`, updated as its emitter
+/// and the listener move.
+pub(crate) type SharedDoppler = Arc<AtomicU32>;
+
+/// Plays a source faster or slower by the Doppler ratio read from [`SharedDoppler`], resampling
+/// it with linear interpolation so that its sample rate doesn't change.
+pub(crate) struct DopplerSource<I> {
+    input: I,
+    doppler: SharedDoppler,
+    ratio: f32,
+    /// The frames of the input before and after the current position. The next frame is empty
+    /// once the input is over.
+    previous_frame: Vec<f32>,
+    next_frame: Vec<f32>,
+    /// The position between the previous and the next frames, from 0 to 1
+    fraction: f32,
+    channel: usize,
+    samples_until_update: usize,
+}
+
+impl<I> DopplerSource<I>
+where
+    I: Source<Item = f32>,
+{
+    pub(crate) fn new(input: I, doppler: SharedDoppler) -> Self {
+        let ratio = f32::from_bits(doppler.load(Ordering::Relaxed));
+        let mut source = DopplerSource {
+            input,
+            doppler,
+            ratio,
+            previous_frame: Vec::new(),
+            next_frame: Vec::new(),
+            fraction: 0.0,
+            channel: 0,
+            samples_until_update: SPATIAL_UPDATE_PERIOD,
+        };
+        source.read_frame();
+        source.read_frame();
+        source
+    }
+
+    /// Moves to the next frame of the input.
+    fn read_frame(&mut self) {
+        std::mem::swap(&mut self.previous_frame, &mut self.next_frame);
+        self.next_frame.clear();
+        let channels = self.input.channels() as usize;
+        for _ in 0..channels {
+            match self.input.next() {
+                Some(sample) => self.next_frame.push(sample),
+                None => break,
+            }
+        }
+        if !self.next_frame.is_empty() {
+            // a frame cut short by the end of the input
+            self.next_frame.resize(channels, 0.0);
+        }
+    }
+}
+
+impl<I> Iterator for DopplerSource<I>
+where
+    I: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.channel == 0 {
+            while self.fraction >= 1.0 {
+                if self.next_frame.is_empty() {
+                    return None;
+                }
+                self.fraction -= 1.0;
+                self.read_frame();
+            }
+            // the last frame is played, but nothing after it
+            if self.previous_frame.is_empty() || (self.next_frame.is_empty() && self.fraction > 0.0)
+            {
+                return None;
+            }
+        }
+
+        let previous = self.previous_frame[self.channel];
+        let next = self
+            .next_frame
+            .get(self.channel)
+            .copied()
+            .unwrap_or(previous);
+        let sample = previous + (next - previous) * self.fraction;
+        self.channel += 1;
+        if self.channel == self.previous_frame.len() {
+            self.channel = 0;
+            self.fraction += self.ratio;
+            self.samples_until_update = self
+                .samples_until_update
+                .saturating_sub(self.previous_frame.len());
+            if self.samples_until_update == 0 {
+                self.ratio = f32::from_bits(self.doppler.load(Ordering::Relaxed));
+                self.samples_until_update = SPATIAL_UPDATE_PERIOD;
+            }
+        }
+        Some(sample)
+    }
+}
+
+impl<I> Source for DopplerSource<I>
+where
+    I: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.previous_frame.len().max(1) as u16
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        // the duration changes with the Doppler ratio
+        None
+    }
+}
+
+/// Plays a source in stereo with the volumes of its left and right channels read from
+/// [`SpatialVolumes`]. Mono sources are played on both channels.
+pub(crate) struct SpatialSource<I> {
+    input: I,
+    volumes: SpatialVolumes,
+    current_volumes: [f32; 2],
+    /// The right sample of a mono source, played after its left sample
+    mono_sample: Option<f32>,
+    channel: u16,
+    samples_until_update: usize,
+}
+
+/// The volumes are read every this many samples rather than for every sample
+const SPATIAL_UPDATE_PERIOD: usize = 256;
+
+impl<I> SpatialSource<I>
+where
+    I: Source<Item = f32>,
+{
+    pub(crate) fn new(input: I, volumes: SpatialVolumes) -> Self {
+        let current_volumes = *volumes.lock();
+        SpatialSource {
+            input,
+            volumes,
+            current_volumes,
+            mono_sample: None,
+            channel: 0,
+            samples_until_update: SPATIAL_UPDATE_PERIOD,
+        }
+    }
+}
+
+impl<I> Iterator for SpatialSource<I>
+where
+    I: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if let Some(sample) = self.mono_sample.take() {
+            return Some(sample * self.current_volumes[1]);
+        }
+        if self.samples_until_update == 0 {
+            self.current_volumes = *self.volumes.lock();
+            self.samples_until_update = SPATIAL_UPDATE_PERIOD;
+        }
+        self.samples_until_update -= 1;
+
+        let channels = self.input.channels();
+        let sample = self.input.next()?;
+        if channels == 1 {
+            self.mono_sample = Some(sample);
+            return Some(sample * self.current_volumes[0]);
+        }
+        let volume = self.current_volumes[(self.channel % 2) as usize];
+        self.channel = (self.channel + 1) % channels;
+        Some(sample * volume)
+    }
+}
+
+impl<I> Source for SpatialSource<I>
+where
+    I: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        let frame_len = self.input.current_frame_len()?;
+        if self.input.channels() == 1 {
+            Some(frame_len * 2 + self.mono_sample.is_some() as usize)
+        } else {
+            Some(frame_len)
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels().max(2)
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+
+    fn doppler(ratio: f32) -> SharedDoppler {
+        Arc::new(AtomicU32::new(ratio.to_bits()))
+    }
+
+    fn resample(channels: u16, samples: Vec<f32>, ratio: f32) -> Vec<f32> {
+        let input = SamplesBuffer::new(channels, 44100, samples);
+        DopplerSource::new(input, doppler(ratio)).collect()
+    }
+
+    fn assert_volumes(volumes: [f32; 2], expected: [f32; 2]) {
+        assert!(
+            (volumes[0] - expected[0]).abs() < 1e-5 && (volumes[1] - expected[1]).abs() < 1e-5,
+            "{:?} != {:?}",
+            volumes,
+            expected
+        );
+    }
+
+    #[test]
+    fn attenuation() {
+        let emitter = AudioEmitter {
+            reference_distance: 2.0,
+            max_distance: 50.0,
+            rolloff: 0.5,
+            ..Default::default()
+        };
+        assert_eq!(emitter.attenuation(0.0), 1.0);
+        assert_eq!(emitter.attenuation(2.0), 1.0);
+        assert_eq!(emitter.attenuation(6.0), 0.5);
+        assert!(emitter.attenuation(20.0) < emitter.attenuation(10.0));
+        assert_eq!(emitter.attenuation(50.0), 0.0);
+        assert_eq!(emitter.attenuation(60.0), 0.0);
+    }
+
+    #[test]
+    fn equal_power_panning() {
+        let emitter = AudioEmitter::default();
+        let listener = GlobalTransform::identity();
+        let half_power = std::f32::consts::FRAC_1_SQRT_2;
+
+        assert_volumes(emitter.channel_volumes(Vec3::ZERO, &listener), [1.0, 1.0]);
+        assert_volumes(
+            emitter.channel_volumes(-Vec3::Z, &listener),
+            [half_power, half_power],
+        );
+        assert_volumes(emitter.channel_volumes(-Vec3::X, &listener), [1.0, 0.0]);
+        assert_volumes(emitter.channel_volumes(Vec3::X, &listener), [0.0, 1.0]);
+
+        // the power of the sound doesn't change as it moves around the listener
+        let position = Vec3::new(1.0, 0.0, -1.0).normalize() * 4.0;
+        let [left, right] = emitter.channel_volumes(position, &listener);
+        let attenuation = emitter.attenuation(4.0);
+        assert!(right > left);
+        assert!((left * left + right * right - attenuation * attenuation).abs() < 1e-5);
+
+        // the sides are relative to the listener's orientation
+        let listener =
+            GlobalTransform::from_rotation(bevy_math::Quat::from_rotation_y(std::f32::consts::PI));
+        assert_volumes(emitter.channel_volumes(Vec3::X, &listener), [1.0, 0.0]);
+    }
+
+    #[test]
+    fn spatial_source() {
+        let volumes = Arc::new(Mutex::new([0.5, 1.0]));
+        let mono = SamplesBuffer::new(1, 44100, vec![1.0, 2.0]);
+        let source = SpatialSource::new(mono, volumes.clone());
+        assert_eq!(source.channels(), 2);
+        assert_eq!(source.collect::<Vec<_>>(), vec![0.5, 1.0, 1.0, 2.0]);
+
+        let stereo = SamplesBuffer::new(2, 44100, vec![1.0, 2.0, 3.0, 4.0]);
+        let source = SpatialSource::new(stereo, volumes);
+        assert_eq!(source.collect::<Vec<_>>(), vec![0.5, 2.0, 1.5, 4.0]);
+    }
+
+    #[test]
+    fn doppler_ratio() {
+        let emitter = AudioEmitter::default();
+        let position = Vec3::new(10.0, 0.0, 0.0);
+        let toward = Vec3::new(-10.0, 0.0, 0.0);
+
+        // the emitter or the listener moving toward the other pitches the sound up
+        let ratio = emitter.doppler_ratio(position, toward, Vec3::ZERO, Vec3::ZERO);
+        assert!((ratio - SPEED_OF_SOUND / (SPEED_OF_SOUND - 10.0)).abs() < 1e-5);
+        let ratio = emitter.doppler_ratio(position, Vec3::ZERO, Vec3::ZERO, -toward);
+        assert!((ratio - (SPEED_OF_SOUND + 10.0) / SPEED_OF_SOUND).abs() < 1e-5);
+        // and moving away pitches it down
+        let ratio = emitter.doppler_ratio(position, -toward, Vec3::ZERO, Vec3::ZERO);
+        assert!((ratio - SPEED_OF_SOUND / (SPEED_OF_SOUND + 10.0)).abs() < 1e-5);
+        // moving sideways doesn't change the pitch
+        let sideways = Vec3::new(0.0, 0.0, 10.0);
+        assert_eq!(
+            emitter.doppler_ratio(position, sideways, Vec3::ZERO, sideways),
+            1.0
+        );
+
+        let fast = toward * 100.0;
+        assert_eq!(
+            emitter.doppler_ratio(position, fast, Vec3::ZERO, Vec3::ZERO),
+            2.0
+        );
+        assert_eq!(
+            emitter.doppler_ratio(position, Vec3::ZERO, Vec3::ZERO, fast),
+            0.5
+        );
+
+        assert_eq!(
+            emitter.doppler_ratio(Vec3::ZERO, toward, Vec3::ZERO, Vec3::ZERO),
+            1.0
+        );
+        let emitter = AudioEmitter {
+            doppler_factor: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            emitter.doppler_ratio(position, toward, Vec3::ZERO, Vec3::ZERO),
+            1.0
+        );
+    }
+
+    #[test]
+    fn air_absorption_cutoff() {
+        assert_eq!(AudioEmitter::default().air_absorption_cutoff(10.0), None);
+
+        let emitter = AudioEmitter {
+            air_absorption: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(emitter.air_absorption_cutoff(0.0), Some(20000.0));
+        assert_eq!(emitter.air_absorption_cutoff(1.0), Some(10000.0));
+        assert!(emitter.air_absorption_cutoff(100.0) < emitter.air_absorption_cutoff(10.0));
+    }
+
+    #[test]
+    fn doppler_resampling() {
+        let samples = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        assert_eq!(resample(2, samples.clone(), 1.0), samples);
+
+        assert_eq!(
+            resample(1, vec![0.0, 1.0, 2.0, 3.0, 4.0], 2.0),
+            vec![0.0, 2.0, 4.0]
+        );
+        // the last frame is played, without going past it
+        assert_eq!(
+            resample(1, vec![0.0, 2.0, 4.0], 0.5),
+            vec![0.0, 1.0, 2.0, 3.0, 4.0]
+        );
+        assert_eq!(
+            resample(2, vec![0.0, 10.0, 2.0, 30.0], 0.5),
+            vec![0.0, 10.0, 1.0, 20.0, 2.0, 30.0]
+        );
+        assert!(resample(2, Vec::new(), 1.0).is_empty());
+    }
+
+    #[test]
+    fn doppler_resampling_partial_frame() {
+        // the frame cut short by the end of the input is completed with silence
+        assert_eq!(
+            resample(2, vec![1.0, 2.0, 3.0], 1.0),
+            vec![1.0, 2.0, 3.0, 0.0]
+        );
+        assert_eq!(
+            resample(2, vec![2.0, 2.0, 4.0], 0.5),
+            vec![2.0, 2.0, 3.0, 1.0, 4.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn doppler_ratio_updates() {
+        let shared_doppler = doppler(1.0);
+        let input = SamplesBuffer::new(1, 44100, (0..1000).map(|i| i as f32).collect::<Vec<_>>());
+        let mut source = DopplerSource::new(input, shared_doppler.clone());
+        shared_doppler.store(2.0f32.to_bits(), Ordering::Relaxed);
+
+        // the ratio is read again after SPATIAL_UPDATE_PERIOD samples
+        let samples = source
+            .by_ref()
+            .take(SPATIAL_UPDATE_PERIOD + 3)
+            .collect::<Vec<_>>();
+        let expected = (0..=SPATIAL_UPDATE_PERIOD)
+            .chain([SPATIAL_UPDATE_PERIOD + 2, SPATIAL_UPDATE_PERIOD + 4])
+            .map(|i| i as f32)
+            .collect::<Vec<_>>();
+        assert_eq!(samples, expected);
+    }
+}
@@ -0,0 +1,354 @@
+use bevy_utils::Duration;
+use rodio::{Sample, Source};
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    mpsc::{self, Receiver, SyncSender},
+    Arc,
+};
+
+/// The part of a sound that is repeated when it's looped, see
+/// [`PlaybackSettings::looping`](crate::PlaybackSettings).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LoopRegion {
+    /// Where the sound is played again from when it reaches the end of the region
+    pub start: Duration,
+    /// The end of the region, the end of the sound if `None`
+    pub end: Option<Duration>,
+}
+
+/// Sends decoders to a looping [`LoopingSource`], which takes a new one each time it loops.
+/// Decoders can only be created from the source asset on the main thread, so one is always
+/// kept ready for the next loop.
+pub(crate) struct SpareDecoders<D> {
+    sender: SyncSender<D>,
+    needed: Arc<AtomicBool>,
+}
+
+impl<D> SpareDecoders<D> {
+    /// Whether the source took its spare decoder and needs a new one.
+    pub(crate) fn is_needed(&self) -> bool {
+        self.needed.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn send(&self, decoder: D) {
+        if self.sender.try_send(decoder).is_ok() {
+            self.needed.store(false, Ordering::Release);
+        }
+    }
+}
+
+/// Plays a decoder from a start position, looping over a [`LoopRegion`] if there is one, and
+/// keeps the playback position in the source.
+pub(crate) struct LoopingSource<D> {
+    decoder: D,
+    /// The position to skip to before playing the decoder
+    skip_to: Option<Duration>,
+    region: Option<LoopRegion>,
+    spare_decoders: Option<(Receiver<D>, Arc<AtomicBool>)>,
+    /// The position of the next sample in nanoseconds
+    position: Arc<AtomicU64>,
+    nanos: u64,
+    /// Set when the spare decoder wasn't ready at the end of the region, silence is played
+    /// until it is
+    waiting: bool,
+}
+
+impl<D> LoopingSource<D>
+where
+    D: Source,
+    D::Item: Sample,
+{
+    pub(crate) fn new(
+        decoder: D,
+        start: Duration,
+        region: Option<LoopRegion>,
+        position: Arc<AtomicU64>,
+    ) -> (Self, Option<SpareDecoders<D>>) {
+        let (spare_decoders, sender) = match region {
+            Some(_) => {
+                let (sender, receiver) = mpsc::sync_channel(1);
+                let needed = Arc::new(AtomicBool::new(true));
+                let sender = SpareDecoders {
+                    sender,
+                    needed: needed.clone(),
+                };
+                (Some((receiver, needed)), Some(sender))
+            }
+            None => (None, None),
+        };
+        let nanos = start.as_nanos() as u64;
+        position.store(nanos, Ordering::Relaxed);
+        let source = LoopingSource {
+            decoder,
+            skip_to: Some(start).filter(|start| *start > Duration::default()),
+            region,
+            spare_decoders,
+            position,
+            nanos,
+            waiting: false,
+        };
+        (source, sender)
+    }
+
+    fn nanos_per_sample(&self) -> u64 {
+        let samples_per_second = self.decoder.sample_rate() as u64 * self.decoder.channels() as u64;
+        1_000_000_000u64
+            .checked_div(samples_per_second)
+            .unwrap_or(0)
+    }
+
+    /// Decodes and drops the samples before `position`.
+    fn skip_samples_to(&mut self, position: Duration) {
+        let frames = (position.as_secs_f64() * self.decoder.sample_rate() as f64) as u64;
+        let samples = frames * self.decoder.channels() as u64;
+        for _ in 0..samples {
+            if self.decoder.next().is_none() {
+                break;
+            }
+        }
+    }
+
+    /// Starts the next loop with the spare decoder, returning whether it was ready.
+    fn start_next_loop(&mut self) -> bool {
+        let (region, (receiver, needed)) = match (self.region, &self.spare_decoders) {
+            (Some(region), Some(spare_decoders)) => (region, spare_decoders),
+            _ => return false,
+        };
+        match receiver.try_recv() {
+            Ok(decoder) => {
+                needed.store(true, Ordering::Release);
+                self.decoder = decoder;
+                self.skip_to = Some(region.start);
+                self.nanos = region.start.as_nanos() as u64;
+                self.position.store(self.nanos, Ordering::Relaxed);
+                self.waiting = false;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn region_end_reached(&self) -> bool {
+        match self.region.and_then(|region| region.end) {
+            Some(end) => self.nanos >= end.as_nanos() as u64,
+            None => false,
+        }
+    }
+}
+
+impl<D> Iterator for LoopingSource<D>
+where
+    D: Source,
+    D::Item: Sample,
+{
+    type Item = D::Item;
+
+    fn next(&mut self) -> Option<D::Item> {
+        if self.waiting && !self.start_next_loop() {
+            return Some(D::Item::zero_value());
+        }
+        if let Some(position) = self.skip_to.take() {
+            self.skip_samples_to(position);
+        }
+        if !self.region_end_reached() {
+            if let Some(sample) = self.decoder.next() {
+                self.nanos += self.nanos_per_sample();
+                self.position.store(self.nanos, Ordering::Relaxed);
+                return Some(sample);
+            }
+        }
+
+        // the end of the sound, or of its loop region
+        self.region?;
+        if self.start_next_loop() {
+            self.next()
+        } else {
+            self.waiting = true;
+            Some(D::Item::zero_value())
+        }
+    }
+}
+
+impl<D> Source for LoopingSource<D>
+where
+    D: Source,
+    D::Item: Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        // the decoders of a looping sound have the same channels and sample rate
+        match self.region {
+            Some(_) => None,
+            None => self.decoder.current_frame_len(),
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        self.decoder.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.decoder.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        match self.region {
+            Some(_) => None,
+            None => self.decoder.total_duration(),
+        }
+    }
+}
+
+/// The number of frames decoded at once by a [`StreamingSource`]
+#[cfg(not(target_arch = "wasm32"))]
+const CHUNK_FRAMES: usize = 4096;
+/// The number of chunks a [`StreamingSource`] decodes ahead, about 0.75s at 44.1kHz
+#[cfg(not(target_arch = "wasm32"))]
+const BUFFERED_CHUNKS: usize = 8;
+
+#[cfg(not(target_arch = "wasm32"))]
+struct Chunk<S> {
+    samples: Vec<S>,
+    channels: u16,
+    sample_rate: u32,
+    /// The position of the first sample of the chunk in nanoseconds
+    position: u64,
+}
+
+/// Decodes a source in chunks on a background thread, a few chunks ahead of the playback, so
+/// decoding long sounds doesn't cost time on the audio thread. The thread stops when the
+/// source ends or when the sound is dropped.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) struct StreamingSource<S> {
+    chunks: Receiver<Chunk<S>>,
+    chunk: Chunk<S>,
+    index: usize,
+    /// Whether the current chunk is silence played while the decoding catches up
+    silent: bool,
+    /// The position of the playback in nanoseconds
+    position: Arc<AtomicU64>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<S> StreamingSource<S>
+where
+    S: Sample + Send + 'static,
+{
+    /// Streams `input`, whose position in the source is `input_position`, tracking the position
+    /// of the playback in `position`.
+    pub(crate) fn new<I>(input: I, input_position: Arc<AtomicU64>, position: Arc<AtomicU64>) -> Self
+    where
+        I: Source<Item = S> + Send + 'static,
+    {
+        let (sender, chunks) = mpsc::sync_channel(BUFFERED_CHUNKS);
+        let chunk = Chunk {
+            samples: Vec::new(),
+            channels: input.channels(),
+            sample_rate: input.sample_rate(),
+            position: input_position.load(Ordering::Relaxed),
+        };
+        position.store(chunk.position, Ordering::Relaxed);
+        std::thread::Builder::new()
+            .name("audio stream".to_string())
+            .spawn(move || {
+                let mut input = input;
+                loop {
+                    let channels = input.channels();
+                    let sample_rate = input.sample_rate();
+                    let position = input_position.load(Ordering::Relaxed);
+                    let len = CHUNK_FRAMES * channels.max(1) as usize;
+                    let len = input
+                        .current_frame_len()
+                        .map_or(len, |frame| frame.min(len));
+                    let samples: Vec<S> = input.by_ref().take(len).collect();
+                    let finished = samples.len() < len || len == 0;
+                    let chunk = Chunk {
+                        samples,
+                        channels,
+                        sample_rate,
+                        position,
+                    };
+                    // the sound was stopped if the receiver was dropped
+                    if (!chunk.samples.is_empty() && sender.send(chunk).is_err()) || finished {
+                        return;
+                    }
+                }
+            })
+            .unwrap();
+        StreamingSource {
+            chunks,
+            chunk,
+            index: 0,
+            silent: false,
+            position,
+        }
+    }
+
+    fn nanos_per_sample(&self) -> u64 {
+        let samples_per_second = self.chunk.sample_rate as u64 * self.chunk.channels as u64;
+        1_000_000_000u64
+            .checked_div(samples_per_second)
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<S> Iterator for StreamingSource<S>
+where
+    S: Sample + Send + 'static,
+{
+    type Item = S;
+
+    fn next(&mut self) -> Option<S> {
+        if self.index >= self.chunk.samples.len() {
+            match self.chunks.try_recv() {
+                Ok(chunk) => {
+                    self.position.store(chunk.position, Ordering::Relaxed);
+                    self.chunk = chunk;
+                    self.silent = false;
+                }
+                Err(mpsc::TryRecvError::Empty) => {
+                    // the decoding fell behind, silent frames are played while it catches up
+                    let channels = self.chunk.channels.max(1) as usize;
+                    self.chunk.samples.clear();
+                    self.chunk.samples.resize(channels, S::zero_value());
+                    self.silent = true;
+                }
+                Err(mpsc::TryRecvError::Disconnected) => return None,
+            }
+            self.index = 0;
+        } else if !self.silent {
+            self.position
+                .fetch_add(self.nanos_per_sample(), Ordering::Relaxed);
+        }
+        let sample = self.chunk.samples[self.index];
+        self.index += 1;
+        Some(sample)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<S> Source for StreamingSource<S>
+where
+    S: Sample + Send + 'static,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        let remaining = self.chunk.samples.len() - self.index;
+        if remaining > 0 {
+            Some(remaining)
+        } else {
+            None
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        self.chunk.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.chunk.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
@@ -20,7 +20,7 @@ pub mod prelude {
 use bevy_app::prelude::*;
 use bevy_ecs::{
     entity::Entity,
-    schedule::{ExclusiveSystemDescriptorCoercion, SystemLabel},
+    schedule::{ExclusiveSystemDescriptorCoercion, SystemLabel, SystemStage},
     system::IntoExclusiveSystem,
 };
 use bevy_utils::HashSet;
@@ -46,6 +46,12 @@ impl Plugin for CorePlugin {
             .unwrap_or_default()
             .create_default_pools(&mut app.world);
 
+        let fixed_update_settings = app
+            .world
+            .get_resource::<FixedUpdateSettings>()
+            .cloned()
+            .unwrap_or_default();
+
         app.init_resource::<Time>()
             .init_resource::<EntityLabels>()
             .init_resource::<FixedTimesteps>()
@@ -63,7 +69,13 @@ impl Plugin for CorePlugin {
                 time_system.exclusive_system().label(CoreSystem::Time),
             )
             .add_startup_system_to_stage(StartupStage::PostStartup, entity_labels_system)
-            .add_system_to_stage(CoreStage::PostUpdate, entity_labels_system);
+            .add_system_to_stage(CoreStage::PostUpdate, entity_labels_system)
+            .stage(CoreStage::FixedUpdate, |stage: &mut SystemStage| {
+                stage.set_run_criteria(
+                    FixedTimestep::steps_per_second(fixed_update_settings.steps_per_second)
+                        .with_label(FIXED_UPDATE),
+                )
+            });
 
         register_rust_types(app);
         register_math_types(app);
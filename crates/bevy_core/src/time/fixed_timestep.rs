@@ -10,6 +10,25 @@ use bevy_ecs::{
 use bevy_utils::HashMap;
 use std::borrow::Cow;
 
+/// The label of the [FixedTimestep] driving `CoreStage::FixedUpdate`, which can be used to get its
+/// [FixedTimestepState] from [FixedTimesteps].
+pub const FIXED_UPDATE: &str = "fixed_update";
+
+/// Configures the rate of `CoreStage::FixedUpdate`. This resource must be inserted before the
+/// [CorePlugin](crate::CorePlugin) is added.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedUpdateSettings {
+    pub steps_per_second: f64,
+}
+
+impl Default for FixedUpdateSettings {
+    fn default() -> Self {
+        FixedUpdateSettings {
+            steps_per_second: 60.0,
+        }
+    }
+}
+
 pub struct FixedTimestepState {
     pub step: f64,
     pub accumulator: f64,
@@ -46,6 +65,12 @@ impl FixedTimesteps {
     pub fn get(&self, name: &str) -> Option<&FixedTimestepState> {
         self.fixed_timesteps.get(name)
     }
+
+    /// The state is overwritten by the next run of its [`FixedTimestep`], changing it is mostly
+    /// useful to test the systems reading it.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut FixedTimestepState> {
+        self.fixed_timesteps.get_mut(name)
+    }
 }
 
 pub struct FixedTimestep {
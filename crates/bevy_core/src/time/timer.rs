@@ -1,5 +1,5 @@
-use crate::Stopwatch;
-use bevy_ecs::{component::Component, reflect::ReflectComponent};
+use crate::{Stopwatch, Time};
+use bevy_ecs::{component::Component, reflect::ReflectComponent, schedule::ShouldRun, system::Res};
 use bevy_reflect::Reflect;
 use bevy_utils::Duration;
 
@@ -364,6 +364,20 @@ impl Timer {
     }
 }
 
+/// A run criteria that runs every `seconds`, at most once per frame. Unlike a
+/// [`FixedTimestep`](crate::FixedTimestep), frames that span several periods don't make systems
+/// catch up.
+pub fn on_timer(seconds: f32) -> impl FnMut(Res<Time>) -> ShouldRun {
+    let mut timer = Timer::from_seconds(seconds, true);
+    move |time: Res<Time>| {
+        if timer.tick(time.delta()).just_finished() {
+            ShouldRun::Yes
+        } else {
+            ShouldRun::No
+        }
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::float_cmp)]
 mod tests {
@@ -1,5 +1,6 @@
 extern crate proc_macro;
 
+mod app_label;
 mod app_plugin;
 mod bevy_main;
 mod bytes;
@@ -43,6 +44,12 @@ pub fn derive_dynamic_plugin(input: TokenStream) -> TokenStream {
     app_plugin::derive_dynamic_plugin(input)
 }
 
+/// Derives the AppLabel trait, to label a sub-app with a type instead of a string.
+#[proc_macro_derive(AppLabel)]
+pub fn derive_app_label(input: TokenStream) -> TokenStream {
+    app_label::derive_app_label(input)
+}
+
 #[proc_macro_attribute]
 pub fn bevy_main(attr: TokenStream, item: TokenStream) -> TokenStream {
     bevy_main::bevy_main(attr, item)
@@ -1,3 +1,4 @@
+pub const BEVY_APP: &str = "bevy_app";
 pub const BEVY_ASSET: &str = "bevy_asset";
 pub const BEVY_CORE: &str = "bevy_core";
 pub const BEVY_RENDER: &str = "bevy_render";
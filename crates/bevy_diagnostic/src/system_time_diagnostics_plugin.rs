@@ -0,0 +1,197 @@
+use bevy_app::prelude::*;
+use bevy_core::{Time, Timer};
+use bevy_ecs::{
+    schedule::SystemTimings,
+    system::{Res, ResMut},
+};
+use bevy_log::info;
+use bevy_utils::{Duration, HashMap};
+use std::{borrow::Cow, cmp::Reverse, collections::VecDeque};
+
+/// An App Plugin that measures how long each system takes to run every frame, and keeps
+/// statistics over the last frames in the [SystemTimeDiagnostics] resource
+pub struct SystemTimeDiagnosticsPlugin {
+    /// The number of frames the statistics are computed over
+    pub max_history_length: usize,
+    /// How often the slowest systems are logged, they aren't logged if `None`
+    pub log_wait_duration: Option<Duration>,
+    /// The number of systems logged, slowest first
+    pub log_count: usize,
+}
+
+impl Default for SystemTimeDiagnosticsPlugin {
+    fn default() -> Self {
+        SystemTimeDiagnosticsPlugin {
+            max_history_length: 120,
+            log_wait_duration: None,
+            log_count: 10,
+        }
+    }
+}
+
+/// The run times of every system over the last frames, collected by the
+/// [SystemTimeDiagnosticsPlugin]. Systems are identified by their name.
+#[derive(Debug)]
+pub struct SystemTimeDiagnostics {
+    systems: HashMap<Cow<'static, str>, SystemTime>,
+    max_history_length: usize,
+}
+
+impl SystemTimeDiagnostics {
+    pub fn new(max_history_length: usize) -> Self {
+        SystemTimeDiagnostics {
+            systems: Default::default(),
+            max_history_length,
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SystemTime> {
+        self.systems.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &SystemTime)> {
+        self.systems.iter().map(|(name, time)| (&**name, time))
+    }
+
+    /// The systems with the highest average run time, slowest first.
+    pub fn slowest(&self, count: usize) -> Vec<(&str, &SystemTime)> {
+        let mut systems = self.iter().collect::<Vec<_>>();
+        systems.sort_by_key(|(_, time)| Reverse(time.average()));
+        systems.truncate(count);
+        systems
+    }
+
+    pub fn clear(&mut self) {
+        self.systems.clear();
+    }
+
+    /// Adds the run times of a frame. The run times of a system that ran several times in the
+    /// frame are added up.
+    pub fn add_frame(&mut self, timings: impl IntoIterator<Item = (Cow<'static, str>, Duration)>) {
+        let mut frame = HashMap::<Cow<'static, str>, Duration>::default();
+        for (name, duration) in timings {
+            *frame.entry(name).or_default() += duration;
+        }
+        let max_history_length = self.max_history_length;
+        for (name, duration) in frame {
+            self.systems
+                .entry(name)
+                .or_insert_with(|| SystemTime::new(max_history_length))
+                .add_measurement(duration);
+        }
+    }
+}
+
+/// The run times of a system over the last frames it ran in, see [SystemTimeDiagnostics].
+#[derive(Debug)]
+pub struct SystemTime {
+    history: VecDeque<Duration>,
+    sum: Duration,
+    max_history_length: usize,
+}
+
+impl SystemTime {
+    fn new(max_history_length: usize) -> Self {
+        SystemTime {
+            history: VecDeque::with_capacity(max_history_length),
+            sum: Duration::ZERO,
+            max_history_length,
+        }
+    }
+
+    fn add_measurement(&mut self, duration: Duration) {
+        if self.history.len() == self.max_history_length {
+            if let Some(removed) = self.history.pop_back() {
+                self.sum -= removed;
+            }
+        }
+        self.sum += duration;
+        self.history.push_front(duration);
+    }
+
+    /// The run time of the last frame the system ran in
+    pub fn last(&self) -> Option<Duration> {
+        self.history.front().copied()
+    }
+
+    pub fn min(&self) -> Option<Duration> {
+        self.history.iter().min().copied()
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        self.history.iter().max().copied()
+    }
+
+    pub fn average(&self) -> Option<Duration> {
+        if self.history.is_empty() {
+            None
+        } else {
+            Some(self.sum / self.history.len() as u32)
+        }
+    }
+
+    /// The run times of the system, from the most recent frame
+    pub fn history(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.history.iter().copied()
+    }
+
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+}
+
+/// State used by the [SystemTimeDiagnosticsPlugin] to log the slowest systems
+struct SystemTimeLogState {
+    timer: Timer,
+    count: usize,
+}
+
+impl Plugin for SystemTimeDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SystemTimings>()
+            .insert_resource(SystemTimeDiagnostics::new(self.max_history_length))
+            // the run times of the previous frame are complete at the start of a frame
+            .add_system_to_stage(CoreStage::First, Self::diagnostic_system);
+
+        if let Some(wait_duration) = self.log_wait_duration {
+            app.insert_resource(SystemTimeLogState {
+                timer: Timer::new(wait_duration, true),
+                count: self.log_count,
+            })
+            .add_system_to_stage(CoreStage::PostUpdate, Self::log_system);
+        }
+    }
+}
+
+impl SystemTimeDiagnosticsPlugin {
+    pub fn diagnostic_system(
+        mut timings: ResMut<SystemTimings>,
+        mut diagnostics: ResMut<SystemTimeDiagnostics>,
+    ) {
+        diagnostics.add_frame(timings.drain());
+    }
+
+    fn log_system(
+        mut state: ResMut<SystemTimeLogState>,
+        time: Res<Time>,
+        diagnostics: Res<SystemTimeDiagnostics>,
+    ) {
+        if !state.timer.tick(time.delta()).finished() {
+            return;
+        }
+        for (name, system_time) in diagnostics.slowest(state.count) {
+            if let (Some(average), Some(min), Some(max)) =
+                (system_time.average(), system_time.min(), system_time.max())
+            {
+                info!(
+                    target: "bevy diagnostic",
+                    "{}: avg {:.3}ms (min {:.3}ms, max {:.3}ms)",
+                    name,
+                    average.as_secs_f64() * 1000.0,
+                    min.as_secs_f64() * 1000.0,
+                    max.as_secs_f64() * 1000.0,
+                );
+            }
+        }
+    }
+}
@@ -129,6 +129,31 @@ macro_rules! tuple_impl {
 
 all_tuples!(tuple_impl, 0, 15, C);
 
+/// A single component that isn't backed by a rust type, given as a pointer to its data. This is
+/// only used to write components registered at runtime through a [`BundleInserter`], with a
+/// [`BundleInfo`] from [`Bundles::init_dynamic_info`].
+pub(crate) struct DynamicComponent(pub(crate) *mut u8);
+
+// SAFE: dynamic components can only be registered for data that is Send + Sync
+unsafe impl Send for DynamicComponent {}
+unsafe impl Sync for DynamicComponent {}
+
+// SAFE: this is never used to initialize a BundleInfo, and get_components yields the single
+// component of the BundleInfo it is inserted with
+unsafe impl Bundle for DynamicComponent {
+    fn component_ids(_components: &mut Components, _storages: &mut Storages) -> Vec<ComponentId> {
+        unreachable!("dynamic components have no static component ids")
+    }
+
+    unsafe fn from_components(_func: impl FnMut() -> *mut u8) -> Self {
+        unreachable!("dynamic components can't be taken out of storage by value")
+    }
+
+    fn get_components(self, mut func: impl FnMut(*mut u8)) {
+        func(self.0)
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct BundleId(usize);
 
@@ -172,6 +197,15 @@ impl BundleInfo {
         &self.storage_types
     }
 
+    /// Whether any component of the bundle has an `on_add` or `on_insert` hook.
+    pub(crate) fn has_insert_hooks(&self, components: &Components) -> bool {
+        self.component_ids.iter().any(|&component_id| {
+            components
+                .get_info(component_id)
+                .is_some_and(|info| info.hooks().has_insert_hooks())
+        })
+    }
+
     pub(crate) fn get_bundle_inserter<'a, 'b>(
         &'b self,
         entities: &'a mut Entities,
@@ -576,6 +610,7 @@ impl<'a, 'b> BundleSpawner<'a, 'b> {
 pub struct Bundles {
     bundle_infos: Vec<BundleInfo>,
     bundle_ids: HashMap<TypeId, BundleId>,
+    dynamic_bundle_ids: HashMap<ComponentId, BundleId>,
 }
 
 impl Bundles {
@@ -608,6 +643,32 @@ impl Bundles {
         // SAFE: index either exists, or was initialized
         unsafe { self.bundle_infos.get_unchecked(id.0) }
     }
+
+    /// Initializes the [`BundleInfo`] of a bundle containing only the component `component_id`,
+    /// to insert it as a [`DynamicComponent`].
+    ///
+    /// # Safety
+    ///
+    /// `component_id` must be a valid [`ComponentId`]
+    pub(crate) unsafe fn init_dynamic_info<'a>(
+        &'a mut self,
+        components: &mut Components,
+        component_id: ComponentId,
+    ) -> &'a BundleInfo {
+        let bundle_infos = &mut self.bundle_infos;
+        let id = self
+            .dynamic_bundle_ids
+            .entry(component_id)
+            .or_insert_with(|| {
+                let id = BundleId(bundle_infos.len());
+                let bundle_info =
+                    initialize_bundle("DynamicComponent", vec![component_id], id, components);
+                bundle_infos.push(bundle_info);
+                id
+            });
+        // SAFE: index either exists, or was initialized
+        self.bundle_infos.get_unchecked(id.0)
+    }
 }
 
 /// # Safety
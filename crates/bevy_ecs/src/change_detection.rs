@@ -10,7 +10,8 @@ use std::ops::{Deref, DerefMut};
 /// Using types that implement [`DetectChanges`], such as [`ResMut`], provide
 /// a way to query if a value has been mutated in another system.
 /// Normally change detecting is triggered by either [`DerefMut`] or [`AsMut`], however
-/// it can be manually triggered via [`DetectChanges::set_changed`].
+/// it can be manually triggered via [`DetectChanges::set_changed`], or skipped via
+/// [`DetectChanges::bypass_change_detection`].
 ///
 /// ```
 /// use bevy_ecs::prelude::*;
@@ -27,6 +28,9 @@ use std::ops::{Deref, DerefMut};
 /// ```
 ///
 pub trait DetectChanges {
+    /// The type of the value whose changes are detected.
+    type Inner: ?Sized;
+
     /// Returns true if (and only if) this value been added since the last execution of this
     /// system.
     fn is_added(&self) -> bool;
@@ -41,11 +45,50 @@ pub trait DetectChanges {
     ///
     /// **Note**: This operation is irreversible.
     fn set_changed(&mut self);
+
+    /// Returns a mutable reference to the value without flagging it as changed. This is useful
+    /// when the value is rewritten with what it already held, so that systems reacting to its
+    /// changes don't run for nothing.
+    ///
+    /// **Note**: Mutating the value through this reference hides the change from change
+    /// detection, see [`DetectChanges::set_changed_if`] to flag it when needed.
+    fn bypass_change_detection(&mut self) -> &mut Self::Inner;
+
+    /// Runs `f` on the value without flagging it as changed, then flags it as changed if `f`
+    /// returned true.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Component, PartialEq, Clone, Copy)]
+    /// struct Position(f32);
+    ///
+    /// fn snap_system(mut positions: Query<&mut Position>) {
+    ///     for mut position in positions.iter_mut() {
+    ///         position.set_changed_if(|value| {
+    ///             let snapped = Position(value.0.round());
+    ///             let changed = *value != snapped;
+    ///             *value = snapped;
+    ///             changed
+    ///         });
+    ///     }
+    /// }
+    /// # snap_system.system();
+    /// ```
+    fn set_changed_if(&mut self, f: impl FnOnce(&mut Self::Inner) -> bool)
+    where
+        Self: Sized,
+    {
+        if f(self.bypass_change_detection()) {
+            self.set_changed();
+        }
+    }
 }
 
 macro_rules! change_detection_impl {
     ($name:ident < $( $generics:tt ),+ >, $target:ty, $($traits:ident)?) => {
         impl<$($generics),* $(: $traits)?> DetectChanges for $name<$($generics),*> {
+            type Inner = $target;
+
             #[inline]
             fn is_added(&self) -> bool {
                 self.ticks
@@ -66,6 +109,11 @@ macro_rules! change_detection_impl {
                     .component_ticks
                     .set_changed(self.ticks.change_tick);
             }
+
+            #[inline]
+            fn bypass_change_detection(&mut self) -> &mut Self::Inner {
+                self.value
+            }
         }
 
         impl<$($generics),* $(: $traits)?> Deref for $name<$($generics),*> {
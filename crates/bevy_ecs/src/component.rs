@@ -1,8 +1,10 @@
 //! Types for declaring and storing [`Component`]s.
 
 use crate::{
+    entity::Entity,
     storage::{SparseSetIndex, Storages},
     system::Resource,
+    world::World,
 };
 pub use bevy_ecs_macros::Component;
 use std::{
@@ -33,6 +35,134 @@ use thiserror::Error;
 /// Components can be grouped together into a [`Bundle`](crate::bundle::Bundle).
 pub trait Component: Send + Sync + 'static {
     type Storage: ComponentStorage;
+
+    /// Registers the [`ComponentHooks`] of this component. Called once, when the component is
+    /// registered in a [`World`].
+    fn register_component_hooks(_hooks: &mut ComponentHooks) {}
+}
+
+/// A function run on an entity when a component is added to or removed from it, see
+/// [`ComponentHooks`]. It receives the id of the component it was registered for.
+pub type ComponentHook = fn(&mut World, Entity, ComponentId);
+
+/// Functions run immediately when a component is added to or removed from an entity, to maintain
+/// invariants or caches without polling for changes in a system.
+///
+/// Hooks are registered with [`Component::register_component_hooks`], or with
+/// [`World::register_component_hooks`] for components defined in other crates. Hooks have full
+/// access to the [`World`], but must not despawn the entity they run on.
+///
+/// ```
+/// # use bevy_ecs::{component::{Component, ComponentHooks, TableStorage}, world::World};
+/// #[derive(Default)]
+/// struct Transform;
+/// impl Component for Transform {
+///     type Storage = TableStorage;
+/// }
+///
+/// /// Every entity with a `Target` also gets a `Transform`
+/// struct Target;
+/// impl Component for Target {
+///     type Storage = TableStorage;
+///
+///     fn register_component_hooks(hooks: &mut ComponentHooks) {
+///         hooks.on_add(|world, entity, _| {
+///             if world.get::<Transform>(entity).is_none() {
+///                 world.entity_mut(entity).insert(Transform::default());
+///             }
+///         });
+///     }
+/// }
+///
+/// let mut world = World::new();
+/// let entity = world.spawn().insert(Target).id();
+/// assert!(world.get::<Transform>(entity).is_some());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ComponentHooks {
+    pub(crate) on_add: Option<ComponentHook>,
+    pub(crate) on_insert: Option<ComponentHook>,
+    pub(crate) on_remove: Option<ComponentHook>,
+    /// Set by [`World::store_removed_values`], runs after `on_remove`
+    pub(crate) store_removed: Option<ComponentHook>,
+    /// Set by [`World::register_required_components`], runs before `on_add`
+    pub(crate) insert_required: Option<ComponentHook>,
+}
+
+impl ComponentHooks {
+    /// Sets the hook run when the component is added to an entity that didn't have it. It runs
+    /// before the `on_insert` hook.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an `on_add` hook was already set.
+    pub fn on_add(&mut self, hook: ComponentHook) -> &mut Self {
+        if self.on_add.replace(hook).is_some() {
+            panic!("Component already has an on_add hook");
+        }
+        self
+    }
+
+    /// Sets the hook run every time the component is inserted, including when it replaces a
+    /// previous value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an `on_insert` hook was already set.
+    pub fn on_insert(&mut self, hook: ComponentHook) -> &mut Self {
+        if self.on_insert.replace(hook).is_some() {
+            panic!("Component already has an on_insert hook");
+        }
+        self
+    }
+
+    /// Sets the hook run when the component is removed from an entity, including when the entity
+    /// is despawned. It runs before the component is removed, so its value can still be read.
+    ///
+    /// The hook can insert or remove other components of the entity. Only the components the
+    /// `on_remove` hooks ran for are removed, except on despawn, where the hooks of the inserted
+    /// components run too. When a hook removes part of a bundle given to
+    /// [`EntityMut::remove_bundle`](crate::world::EntityMut::remove_bundle), the rest of the
+    /// bundle is dropped and no value is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an `on_remove` hook was already set.
+    pub fn on_remove(&mut self, hook: ComponentHook) -> &mut Self {
+        if self.on_remove.replace(hook).is_some() {
+            panic!("Component already has an on_remove hook");
+        }
+        self
+    }
+
+    #[inline]
+    pub(crate) fn has_insert_hooks(&self) -> bool {
+        self.on_add.is_some() || self.on_insert.is_some() || self.insert_required.is_some()
+    }
+}
+
+/// A component inserted with its default value when a component requiring it is added to an
+/// entity that doesn't have it yet, see [`World::register_required_components`].
+#[derive(Debug, Clone, Copy)]
+pub struct RequiredComponent {
+    id: ComponentId,
+    pub(crate) insert_default: fn(&mut World, Entity),
+}
+
+impl RequiredComponent {
+    pub(crate) fn new<T: Component + Default>(id: ComponentId) -> Self {
+        RequiredComponent {
+            id,
+            insert_default: |world, entity| {
+                world.entity_mut(entity).insert(T::default());
+            },
+        }
+    }
+
+    #[inline]
+    pub fn id(&self) -> ComponentId {
+        self.id
+    }
 }
 
 pub struct TableStorage;
@@ -94,6 +224,8 @@ impl Default for StorageType {
 pub struct ComponentInfo {
     id: ComponentId,
     descriptor: ComponentDescriptor,
+    hooks: ComponentHooks,
+    required_components: Vec<RequiredComponent>,
 }
 
 impl ComponentInfo {
@@ -132,8 +264,25 @@ impl ComponentInfo {
         self.descriptor.is_send_and_sync
     }
 
+    #[inline]
+    pub fn hooks(&self) -> &ComponentHooks {
+        &self.hooks
+    }
+
+    /// The components added along with this one, see
+    /// [`World::register_required_components`].
+    #[inline]
+    pub fn required_components(&self) -> &[RequiredComponent] {
+        &self.required_components
+    }
+
     fn new(id: ComponentId, descriptor: ComponentDescriptor) -> Self {
-        ComponentInfo { id, descriptor }
+        ComponentInfo {
+            id,
+            descriptor,
+            hooks: ComponentHooks::default(),
+            required_components: Vec::new(),
+        }
     }
 }
 
@@ -194,6 +343,30 @@ impl ComponentDescriptor {
         }
     }
 
+    /// Describes a component that isn't backed by a rust type, such as one defined by a scripting
+    /// layer or an editor. Register it with
+    /// [`World::register_component`](crate::world::World::register_component), then access it
+    /// through the `*_by_id` methods of [`EntityMut`](crate::world::EntityMut).
+    ///
+    /// # Safety
+    /// `drop` must be safe to call on a valid value with the given `layout`, and values of this
+    /// component must be safe to send and share between threads.
+    pub unsafe fn new_dynamic(
+        name: impl Into<String>,
+        storage_type: StorageType,
+        layout: Layout,
+        drop: unsafe fn(*mut u8),
+    ) -> Self {
+        Self {
+            name: name.into(),
+            storage_type,
+            is_send_and_sync: true,
+            type_id: None,
+            layout,
+            drop,
+        }
+    }
+
     pub fn new_resource<T: Resource>(storage_type: StorageType) -> Self {
         Self {
             name: std::any::type_name::<T>().to_string(),
@@ -257,7 +430,8 @@ impl Components {
         let index = self.indices.entry(type_id).or_insert_with(|| {
             let index = components.len();
             let descriptor = ComponentDescriptor::new::<T>();
-            let info = ComponentInfo::new(ComponentId(index), descriptor);
+            let mut info = ComponentInfo::new(ComponentId(index), descriptor);
+            T::register_component_hooks(&mut info.hooks);
             if T::Storage::STORAGE_TYPE == StorageType::SparseSet {
                 storages.sparse_sets.get_or_insert(&info);
             }
@@ -267,6 +441,33 @@ impl Components {
         ComponentId(*index)
     }
 
+    /// Registers a component from its `descriptor`. Unlike [`Components::init_component`], this
+    /// fails if a component with the same [`TypeId`] was already registered. Components without a
+    /// [`TypeId`] are always registered as a new component.
+    pub fn add(
+        &mut self,
+        descriptor: ComponentDescriptor,
+        storages: &mut Storages,
+    ) -> Result<ComponentId, ComponentsError> {
+        let index = self.components.len();
+        if let Some(type_id) = descriptor.type_id {
+            if let Some(existing_index) = self.indices.get(&type_id) {
+                return Err(ComponentsError::ComponentAlreadyExists {
+                    type_id,
+                    name: descriptor.name,
+                    existing_id: ComponentId(*existing_index),
+                });
+            }
+            self.indices.insert(type_id, index);
+        }
+        let info = ComponentInfo::new(ComponentId(index), descriptor);
+        if info.storage_type() == StorageType::SparseSet {
+            storages.sparse_sets.get_or_insert(&info);
+        }
+        self.components.push(info);
+        Ok(ComponentId(index))
+    }
+
     #[inline]
     pub fn len(&self) -> usize {
         self.components.len()
@@ -282,6 +483,25 @@ impl Components {
         self.components.get(id.0)
     }
 
+    #[inline]
+    pub(crate) fn get_hooks_mut(&mut self, id: ComponentId) -> Option<&mut ComponentHooks> {
+        self.components.get_mut(id.0).map(|info| &mut info.hooks)
+    }
+
+    /// Makes `required` a required component of the component `id`, so that it is inserted with
+    /// it.
+    pub(crate) fn add_required_component(&mut self, id: ComponentId, required: RequiredComponent) {
+        if let Some(info) = self.components.get_mut(id.0) {
+            if !info
+                .required_components
+                .iter()
+                .any(|existing| existing.id == required.id)
+            {
+                info.required_components.push(required);
+            }
+        }
+    }
+
     /// # Safety
     ///
     /// `id` must be a valid [ComponentId]
@@ -0,0 +1,36 @@
+use crate as bevy_ecs;
+use crate::component::Component;
+
+/// A marker component that removes an entity from queries without despawning it, so it keeps its
+/// components and its [`Entity`](super::Entity) id. This is useful to pool entities, or to take
+/// them out of the simulation and rendering for a while.
+///
+/// Queries skip disabled entities, unless they mention `Disabled` themselves: a query with
+/// [`With<Disabled>`](crate::query::With) only returns disabled entities, and a query fetching
+/// `Option<&Disabled>` returns both disabled and enabled entities. The same goes for the
+/// component ids given to [`World::for_each_by_ids`](crate::world::World::for_each_by_ids).
+/// Direct access through the [`World`](crate::world::World), like
+/// [`World::get`](crate::world::World::get), still works on disabled entities.
+///
+/// ```
+/// # use bevy_ecs::{entity::Disabled, prelude::*};
+/// # #[derive(Component)]
+/// # struct Bullet;
+/// fn return_to_pool(mut commands: Commands, bullets: Query<Entity, With<Bullet>>) {
+///     for bullet in bullets.iter() {
+///         commands.entity(bullet).insert(Disabled);
+///     }
+/// }
+///
+/// fn reuse_from_pool(mut commands: Commands, pool: Query<Entity, (With<Bullet>, With<Disabled>)>) {
+///     if let Some(bullet) = pool.iter().next() {
+///         commands.entity(bullet).remove::<Disabled>();
+///     }
+/// }
+/// # return_to_pool.system();
+/// # reuse_from_pool.system();
+/// ```
+// NOTE: this must stay a table component, so disabled entities never share a table with the
+// enabled entities that dense queries iterate over
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Disabled;
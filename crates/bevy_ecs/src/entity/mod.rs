@@ -22,10 +22,12 @@
 //!   [`EntityCommands::insert_bundle`](crate::system::EntityCommands::insert_bundle).
 //! - **Removing a component to an entity:** use
 //!   [`EntityCommands::remove`](crate::system::EntityCommands::remove).
+mod disabled;
 mod map_entities;
 mod serde;
 
 pub use self::serde::*;
+pub use disabled::*;
 pub use map_entities::*;
 
 use crate::{archetype::ArchetypeId, storage::SparseSetIndex};
@@ -1,5 +1,6 @@
 use crate::entity::Entity;
 use serde::{de::Visitor, Deserialize, Serialize, Serializer};
+use std::convert::TryFrom;
 
 impl Serialize for Entity {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -34,4 +35,14 @@ impl<'de> Visitor<'de> for EntityVisitor {
     {
         Ok(Entity::new(v))
     }
+
+    // compact formats like MessagePack write ids in the smallest integer type that fits them
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        u32::try_from(v)
+            .map(Entity::new)
+            .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(v), &self))
+    }
 }
@@ -4,6 +4,7 @@ use crate::system::{Local, Res, ResMut, SystemParam};
 use crate::{self as bevy_ecs, system::Resource};
 use bevy_utils::tracing::trace;
 use std::{
+    collections::VecDeque,
     fmt::{self},
     hash::Hash,
     marker::PhantomData,
@@ -47,21 +48,39 @@ impl<T> fmt::Debug for EventId<T> {
 struct EventInstance<T> {
     pub event_id: EventId<T>,
     pub event: T,
+    /// The number of [`Events::update`] calls when the event was sent
+    pub update: usize,
 }
 
-#[derive(Debug)]
-enum State {
-    A,
-    B,
+/// How long [`Events`] keep the events sent to them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventRetention {
+    /// Events are dropped by the given number of [`Events::update`] calls after they were sent.
+    /// As [`Events::update_system`] runs once per frame, this is the number of frames the events
+    /// live for.
+    Frames(usize),
+    /// Events are kept until they are removed with [`Events::clear`], [`Events::drain`], or an
+    /// [`EventConsumer`]. The events will grow indefinitely if they are never removed.
+    Manual,
+}
+
+impl Default for EventRetention {
+    /// Keeps events for two frames, so that every [`EventReader`] running once per frame reads
+    /// every event, regardless of system ordering.
+    fn default() -> Self {
+        EventRetention::Frames(2)
+    }
 }
 
 /// An event collection that represents the events that occurred within the last two
-/// [`Events::update`] calls.
+/// [`Events::update`] calls, or any other [`EventRetention`].
 /// Events can be written to using an [`EventWriter`]
 /// and are typically cheaply read using an [`EventReader`].
 ///
 /// Each event can be consumed by multiple systems, in parallel,
 /// with consumption tracked by the [`EventReader`] on a per-system basis.
+/// An [`EventConsumer`] can instead remove the events it handled, so that no other system sees
+/// them.
 ///
 /// This collection is meant to be paired with a system that calls
 /// [`Events::update`] exactly once per update/frame.
@@ -103,40 +122,31 @@ enum State {
 ///
 /// # Details
 ///
-/// [Events] is implemented using a double buffer. Each call to [Events::update] swaps buffers and
-/// clears out the oldest buffer. [EventReader]s that read at least once per update will never drop
-/// events. [EventReader]s that read once within two updates might still receive some events.
-/// [EventReader]s that read after two updates are guaranteed to drop all events that occurred
-/// before those updates.
+/// [Events] keeps the events in the order they were sent. Each call to [Events::update] drops the
+/// events that are older than the [EventRetention] allows. With the default retention,
+/// [EventReader]s that read at least once per update will never drop events. [EventReader]s that
+/// read once within two updates might still receive some events. [EventReader]s that read after
+/// two updates are guaranteed to drop all events that occurred before those updates.
 ///
-/// The buffers in [Events] will grow indefinitely if [Events::update] is never called.
+/// The events in [Events] will grow indefinitely if [Events::update] is never called, or if the
+/// retention is [EventRetention::Manual] and the events are never removed.
 ///
-/// An alternative call pattern would be to call [Events::update] manually across frames to control
-/// when events are cleared.
-/// This complicates consumption and risks ever-expanding memory usage if not cleaned up,
-/// but can be done by adding your event as a resource instead of using [`App::add_event`].
+/// Use [`App::add_event_with_retention`] to keep events of a type for longer, for example when
+/// they are only read every few frames.
 ///
 /// [`App::add_event`]: https://docs.rs/bevy/*/bevy/app/struct.App.html#method.add_event
+/// [`App::add_event_with_retention`]: https://docs.rs/bevy/*/bevy/app/struct.App.html#method.add_event_with_retention
 #[derive(Debug)]
 pub struct Events<T> {
-    events_a: Vec<EventInstance<T>>,
-    events_b: Vec<EventInstance<T>>,
-    a_start_event_count: usize,
-    b_start_event_count: usize,
+    events: VecDeque<EventInstance<T>>,
     event_count: usize,
-    state: State,
+    update_count: usize,
+    retention: EventRetention,
 }
 
 impl<T> Default for Events<T> {
     fn default() -> Self {
-        Events {
-            a_start_event_count: 0,
-            b_start_event_count: 0,
-            event_count: 0,
-            events_a: Vec::new(),
-            events_b: Vec::new(),
-            state: State::A,
-        }
+        Events::with_retention(EventRetention::default())
     }
 }
 
@@ -173,6 +183,31 @@ impl<'w, 's, T: Resource> EventWriter<'w, 's, T> {
     }
 }
 
+/// Reads events of type `T` and removes the ones it handles, so that no other system reads them.
+///
+/// Unlike an [`EventReader`], an [`EventConsumer`] doesn't track which events it has already seen:
+/// events it doesn't handle are read again on the next call, until they are dropped according to
+/// their [`EventRetention`]. This fits request/response patterns, where a request is retried until
+/// a system is able to answer it.
+#[derive(SystemParam)]
+pub struct EventConsumer<'w, 's, T: Resource> {
+    events: ResMut<'w, Events<T>>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s usize>,
+}
+
+impl<'w, 's, T: Resource> EventConsumer<'w, 's, T> {
+    /// Calls `handler` on each event in order, and removes the events it returns `true` for.
+    pub fn consume(&mut self, handler: impl FnMut(&T) -> bool) {
+        self.events.consume(handler);
+    }
+
+    /// Removes all events, returning them in order.
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.events.drain()
+    }
+}
+
 pub struct ManualEventReader<T> {
     last_event_count: usize,
     _marker: PhantomData<T>,
@@ -208,49 +243,16 @@ fn internal_event_reader<'a, T>(
     last_event_count: &mut usize,
     events: &'a Events<T>,
 ) -> impl DoubleEndedIterator<Item = (&'a T, EventId<T>)> {
-    // if the reader has seen some of the events in a buffer, find the proper index offset.
-    // otherwise read all events in the buffer
-    let a_index = if *last_event_count > events.a_start_event_count {
-        *last_event_count - events.a_start_event_count
-    } else {
-        0
-    };
-    let b_index = if *last_event_count > events.b_start_event_count {
-        *last_event_count - events.b_start_event_count
-    } else {
-        0
-    };
+    // events are sorted by id, so the events the reader hasn't seen yet are at the end, even if
+    // some events were dropped or consumed since the last read
+    let unread_index = events
+        .events
+        .partition_point(|instance| instance.event_id.id < *last_event_count);
     *last_event_count = events.event_count;
-    match events.state {
-        State::A => events
-            .events_b
-            .get(b_index..)
-            .unwrap_or_else(|| &[])
-            .iter()
-            .map(map_instance_event_with_id)
-            .chain(
-                events
-                    .events_a
-                    .get(a_index..)
-                    .unwrap_or_else(|| &[])
-                    .iter()
-                    .map(map_instance_event_with_id),
-            ),
-        State::B => events
-            .events_a
-            .get(a_index..)
-            .unwrap_or_else(|| &[])
-            .iter()
-            .map(map_instance_event_with_id)
-            .chain(
-                events
-                    .events_b
-                    .get(b_index..)
-                    .unwrap_or_else(|| &[])
-                    .iter()
-                    .map(map_instance_event_with_id),
-            ),
-    }
+    events
+        .events
+        .range(unread_index..)
+        .map(map_instance_event_with_id)
 }
 
 impl<'w, 's, T: Resource> EventReader<'w, 's, T> {
@@ -276,7 +278,7 @@ impl<'w, 's, T: Resource> EventReader<'w, 's, T> {
     /// possible to mutate this.
     pub fn non_advancing_iter(&self) -> impl DoubleEndedIterator<Item = &T> {
         let mut last_event_count = self.last_event_count.0;
-        internal_event_reader(&mut last_event_count, &self.events).map(|(event,_id)| event)
+        internal_event_reader(&mut last_event_count, &self.events).map(|(event, _id)| event)
     }
 
     /// Acts as if the we called [`iter`](Self::iter)
@@ -285,8 +287,20 @@ impl<'w, 's, T: Resource> EventReader<'w, 's, T> {
     }
 }
 
+impl<T> Events<T> {
+    /// Creates an empty event collection that keeps events according to `retention`.
+    pub fn with_retention(retention: EventRetention) -> Self {
+        Events {
+            events: VecDeque::new(),
+            event_count: 0,
+            update_count: 0,
+            retention,
+        }
+    }
+}
+
 impl<T: Resource> Events<T> {
-    /// "Sends" an `event` by writing it to the current event buffer. [EventReader]s can then read
+    /// "Sends" an `event` by writing it to the event buffer. [EventReader]s can then read
     /// the event.
     pub fn send(&mut self, event: T) {
         let event_id = EventId {
@@ -295,16 +309,26 @@ impl<T: Resource> Events<T> {
         };
         trace!("Events::send() -> id: {}", event_id);
 
-        let event_instance = EventInstance { event_id, event };
-
-        match self.state {
-            State::A => self.events_a.push(event_instance),
-            State::B => self.events_b.push(event_instance),
-        }
+        self.events.push_back(EventInstance {
+            event_id,
+            event,
+            update: self.update_count,
+        });
 
         self.event_count += 1;
     }
 
+    #[inline]
+    pub fn retention(&self) -> EventRetention {
+        self.retention
+    }
+
+    /// Changes how long events are kept. This applies to the events that were already sent too.
+    #[inline]
+    pub fn set_retention(&mut self, retention: EventRetention) {
+        self.retention = retention;
+    }
+
     /// Gets a new [ManualEventReader]. This will include all events already in the event buffers.
     pub fn get_reader(&self) -> ManualEventReader<T> {
         ManualEventReader {
@@ -322,20 +346,16 @@ impl<T: Resource> Events<T> {
         }
     }
 
-    /// Swaps the event buffers and clears the oldest event buffer. In general, this should be
-    /// called once per frame/update.
+    /// Drops the events that are older than the [EventRetention] allows. In general, this should
+    /// be called once per frame/update.
     pub fn update(&mut self) {
-        match self.state {
-            State::A => {
-                self.events_b.clear();
-                self.state = State::B;
-                self.b_start_event_count = self.event_count;
-            }
-            State::B => {
-                self.events_a.clear();
-                self.state = State::A;
-                self.a_start_event_count = self.event_count;
-            }
+        self.update_count += 1;
+        if let EventRetention::Frames(frames) = self.retention {
+            let update_count = self.update_count;
+            let expired_count = self
+                .events
+                .partition_point(|instance| update_count - instance.update >= frames);
+            self.events.drain(..expired_count);
         }
     }
 
@@ -344,43 +364,27 @@ impl<T: Resource> Events<T> {
         events.update();
     }
 
-    #[inline]
-    fn reset_start_event_count(&mut self) {
-        self.a_start_event_count = self.event_count;
-        self.b_start_event_count = self.event_count;
-    }
-
     /// Removes all events.
     #[inline]
     pub fn clear(&mut self) {
-        self.reset_start_event_count();
-        self.events_a.clear();
-        self.events_b.clear();
+        self.events.clear();
     }
 
     /// Returns true if there are no events in this collection.
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.events_a.is_empty() && self.events_b.is_empty()
+        self.events.is_empty()
     }
 
     /// Creates a draining iterator that removes all events.
     pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
-        self.reset_start_event_count();
-
-        let map = |i: EventInstance<T>| i.event;
-        match self.state {
-            State::A => self
-                .events_b
-                .drain(..)
-                .map(map)
-                .chain(self.events_a.drain(..).map(map)),
-            State::B => self
-                .events_a
-                .drain(..)
-                .map(map)
-                .chain(self.events_b.drain(..).map(map)),
-        }
+        self.events.drain(..).map(|i| i.event)
+    }
+
+    /// Calls `handler` on each event in order, and removes the events it returns `true` for.
+    /// Removed events won't be read by any [EventReader] that didn't read them yet.
+    pub fn consume(&mut self, mut handler: impl FnMut(&T) -> bool) {
+        self.events.retain(|instance| !handler(&instance.event));
     }
 
     /// Iterates over events that happened since the last "update" call.
@@ -390,10 +394,11 @@ impl<T: Resource> Events<T> {
     /// If events happen outside that window, they will not be handled. For example, any events that
     /// happen after this call and before the next `update()` call will be dropped.
     pub fn iter_current_update_events(&self) -> impl DoubleEndedIterator<Item = &T> {
-        match self.state {
-            State::A => self.events_a.iter().map(map_instance_event),
-            State::B => self.events_b.iter().map(map_instance_event),
-        }
+        let update_count = self.update_count;
+        let current_index = self
+            .events
+            .partition_point(|instance| instance.update < update_count);
+        self.events.range(current_index..).map(map_instance_event)
     }
 }
 
@@ -403,19 +408,21 @@ impl<T> std::iter::Extend<T> for Events<T> {
         I: IntoIterator<Item = T>,
     {
         let mut event_count = self.event_count;
+        let update = self.update_count;
         let events = iter.into_iter().map(|event| {
             let event_id = EventId {
                 id: event_count,
                 _marker: PhantomData,
             };
             event_count += 1;
-            EventInstance { event_id, event }
+            EventInstance {
+                event_id,
+                event,
+                update,
+            }
         });
 
-        match self.state {
-            State::A => self.events_a.extend(events),
-            State::B => self.events_b.extend(events),
-        }
+        self.events.extend(events);
 
         trace!(
             "Events::extend() -> ids: ({}..{})",
@@ -596,4 +603,66 @@ mod tests {
         events.update();
         assert!(events.is_empty());
     }
+
+    #[test]
+    fn test_events_frames_retention() {
+        let mut events = Events::<E>::with_retention(EventRetention::Frames(3));
+        let mut reader = events.get_reader();
+
+        events.send(E(0));
+        events.update();
+        events.send(E(1));
+        events.update();
+        events.update();
+        assert!(
+            reader.iter(&events).eq([E(1)].iter()),
+            "E(0) was dropped after three updates"
+        );
+
+        events.update();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_events_manual_retention() {
+        let mut events = Events::<E>::with_retention(EventRetention::Manual);
+        let mut reader = events.get_reader();
+
+        events.send(E(0));
+        for _ in 0..10 {
+            events.update();
+        }
+        events.send(E(1));
+        assert!(events.iter_current_update_events().eq([E(1)].iter()));
+        assert!(reader.iter(&events).eq([E(0), E(1)].iter()));
+
+        events.clear();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_events_consume() {
+        let mut events = Events::<E>::default();
+        let mut reader = events.get_reader();
+
+        events.extend(vec![E(0), E(1), E(2), E(3)]);
+
+        let mut handled = Vec::new();
+        events.consume(|event| {
+            if event.0 % 2 == 0 {
+                handled.push(event.0);
+                true
+            } else {
+                false
+            }
+        });
+        assert_eq!(handled, vec![0, 2]);
+
+        events.send(E(4));
+        assert!(
+            reader.iter(&events).eq([E(1), E(3), E(4)].iter()),
+            "consumed events are skipped by readers"
+        );
+        assert!(events.drain().eq(vec![E(1), E(3), E(4)].into_iter()));
+    }
 }
@@ -0,0 +1,166 @@
+use crate::{
+    component::Component,
+    entity::{Disabled, Entity},
+    query::Changed,
+    system::{Query, RemovedComponents, ResMut},
+};
+use bevy_utils::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// A resource mapping the values of the component `T` to the entities holding them, to look up
+/// entities by value without iterating over a query.
+///
+/// The index is kept up to date by [`index_maintenance_system`], through change detection. It
+/// only reflects the changes made before the system last ran.
+///
+/// ```
+/// # use bevy_ecs::{index::Index, prelude::*};
+/// #[derive(Component, Clone, PartialEq, Eq, Hash)]
+/// struct NetworkId(u64);
+///
+/// fn on_message(index: Res<Index<NetworkId>>) {
+///     if let Some(entity) = index.get_single(&NetworkId(42)) {
+///         // handle the message for `entity`
+///     }
+/// }
+/// # on_message.system();
+/// ```
+#[derive(Debug)]
+pub struct Index<T: Component + Eq + Hash + Clone> {
+    entities_by_value: HashMap<T, HashSet<Entity>>,
+    value_by_entity: HashMap<Entity, T>,
+}
+
+impl<T: Component + Eq + Hash + Clone> Default for Index<T> {
+    fn default() -> Self {
+        Self {
+            entities_by_value: Default::default(),
+            value_by_entity: Default::default(),
+        }
+    }
+}
+
+impl<T: Component + Eq + Hash + Clone> Index<T> {
+    /// Iterates over the entities whose component `T` equals `value`, in no particular order.
+    pub fn get<'a>(&'a self, value: &T) -> impl Iterator<Item = Entity> + 'a {
+        self.entities_by_value
+            .get(value)
+            .into_iter()
+            .flat_map(|entities| entities.iter().copied())
+    }
+
+    /// Returns the entity whose component `T` equals `value`, if there is exactly one.
+    pub fn get_single(&self, value: &T) -> Option<Entity> {
+        match self.entities_by_value.get(value) {
+            Some(entities) if entities.len() == 1 => entities.iter().next().copied(),
+            _ => None,
+        }
+    }
+
+    /// Whether any entity's component `T` equals `value`.
+    pub fn contains(&self, value: &T) -> bool {
+        self.entities_by_value.contains_key(value)
+    }
+
+    /// The indexed value of `entity`.
+    pub fn value(&self, entity: Entity) -> Option<&T> {
+        self.value_by_entity.get(&entity)
+    }
+
+    /// Iterates over the distinct indexed values.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.entities_by_value.keys()
+    }
+
+    /// The number of indexed entities.
+    pub fn len(&self) -> usize {
+        self.value_by_entity.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value_by_entity.is_empty()
+    }
+
+    fn remove(&mut self, entity: Entity) {
+        if let Some(value) = self.value_by_entity.remove(&entity) {
+            let entities = self.entities_by_value.get_mut(&value).unwrap();
+            entities.remove(&entity);
+            if entities.is_empty() {
+                self.entities_by_value.remove(&value);
+            }
+        }
+    }
+
+    fn insert(&mut self, entity: Entity, value: &T) {
+        if self.value_by_entity.get(&entity) == Some(value) {
+            return;
+        }
+        self.remove(entity);
+        self.entities_by_value
+            .entry(value.clone())
+            .or_default()
+            .insert(entity);
+        self.value_by_entity.insert(entity, value.clone());
+    }
+}
+
+/// Updates the [`Index`] of the component `T` with the components that changed or were removed
+/// since it last ran.
+pub fn index_maintenance_system<T: Component + Eq + Hash + Clone>(
+    mut index: ResMut<Index<T>>,
+    // disabled entities still hold their value, so they stay indexed
+    changed: Query<(Entity, &T, Option<&Disabled>), Changed<T>>,
+    mut removed: RemovedComponents<T>,
+) {
+    for entity in removed.iter() {
+        index.remove(entity);
+    }
+    for (entity, value, _) in changed.iter() {
+        index.insert(entity, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as bevy_ecs;
+    use crate::{
+        schedule::{Stage, SystemStage},
+        world::World,
+    };
+
+    #[derive(Component, Clone, Debug, PartialEq, Eq, Hash)]
+    struct GridCell(i32, i32);
+
+    #[test]
+    fn index() {
+        let mut world = World::default();
+        world.insert_resource(Index::<GridCell>::default());
+        let mut stage = SystemStage::single_threaded();
+        stage.add_system(index_maintenance_system::<GridCell>);
+
+        let a = world.spawn().insert(GridCell(0, 0)).id();
+        let b = world.spawn().insert(GridCell(0, 0)).id();
+        let c = world.spawn().insert(GridCell(1, 0)).id();
+        stage.run(&mut world);
+
+        let index = world.get_resource::<Index<GridCell>>().unwrap();
+        let mut origin = index.get(&GridCell(0, 0)).collect::<Vec<_>>();
+        origin.sort();
+        assert_eq!(origin, vec![a, b]);
+        assert_eq!(index.get_single(&GridCell(1, 0)), Some(c));
+        assert_eq!(index.get_single(&GridCell(0, 0)), None);
+        assert_eq!(index.len(), 3);
+
+        world.clear_trackers();
+        world.get_mut::<GridCell>(a).unwrap().0 = 1;
+        world.despawn(c);
+        stage.run(&mut world);
+
+        let index = world.get_resource::<Index<GridCell>>().unwrap();
+        assert_eq!(index.get_single(&GridCell(0, 0)), Some(b));
+        assert_eq!(index.get_single(&GridCell(1, 0)), Some(a));
+        assert_eq!(index.value(c), None);
+        assert_eq!(index.len(), 2);
+    }
+}
@@ -4,9 +4,11 @@ pub mod change_detection;
 pub mod component;
 pub mod entity;
 pub mod event;
+pub mod index;
 pub mod query;
 #[cfg(feature = "bevy_reflect")]
 pub mod reflect;
+pub mod relation;
 pub mod schedule;
 pub mod storage;
 pub mod system;
@@ -16,23 +18,26 @@ pub mod world;
 pub mod prelude {
     #[doc(hidden)]
     #[cfg(feature = "bevy_reflect")]
-    pub use crate::reflect::ReflectComponent;
+    pub use crate::reflect::{ReflectComponent, ReflectResource};
     #[doc(hidden)]
     pub use crate::{
         bundle::Bundle,
         change_detection::DetectChanges,
         component::Component,
         entity::Entity,
-        event::{EventReader, EventWriter},
+        event::{EventConsumer, EventReader, EventWriter},
         query::{Added, ChangeTrackers, Changed, Or, QueryState, With, Without},
+        relation::{RelatedBy, Relation},
         schedule::{
             AmbiguitySetLabel, ExclusiveSystemDescriptorCoercion, ParallelSystemDescriptorCoercion,
-            RunCriteria, RunCriteriaDescriptorCoercion, RunCriteriaLabel, RunCriteriaPiping,
-            Schedule, Stage, StageLabel, State, SystemLabel, SystemSet, SystemStage,
+            RunCriteria, RunCriteriaCombinators, RunCriteriaDescriptorCoercion, RunCriteriaLabel,
+            RunCriteriaPiping, Schedule, Stage, StageLabel, State, SystemLabel, SystemSet,
+            SystemStage,
         },
         system::{
-            Commands, ConfigurableSystem, In, IntoChainSystem, IntoExclusiveSystem, IntoSystem,
-            Local, NonSend, NonSendMut, Query, QuerySet, RemovedComponents, Res, ResMut, System,
+            AsSystemLabel, Commands, ConfigurableSystem, In, IntoChainSystem, IntoExclusiveSystem,
+            IntoSystem, Local, NonSend, NonSendMarker, NonSendMut, Query, QuerySet,
+            RemovedComponents, Res, ResMut, System,
         },
         world::{FromWorld, Mut, World},
     };
@@ -43,6 +48,7 @@ mod tests {
     use crate as bevy_ecs;
     use crate::{
         bundle::Bundle,
+        change_detection::DetectChanges,
         component::{Component, ComponentId},
         entity::Entity,
         query::{
@@ -987,6 +993,34 @@ mod tests {
         assert_eq!(get_changed(&mut world), vec![e1]);
     }
 
+    #[test]
+    fn bypass_change_detection() {
+        let mut world = World::default();
+        let e1 = world.spawn().insert(A(0)).id();
+
+        fn get_changed(world: &mut World) -> Vec<Entity> {
+            world
+                .query_filtered::<Entity, Changed<A>>()
+                .iter(world)
+                .collect::<Vec<Entity>>()
+        }
+        world.clear_trackers();
+        *world.get_mut::<A>(e1).unwrap().bypass_change_detection() = A(1);
+        assert_eq!(get_changed(&mut world), vec![]);
+        assert_eq!(world.get::<A>(e1), Some(&A(1)));
+
+        world
+            .get_mut::<A>(e1)
+            .unwrap()
+            .set_changed_if(|a| std::mem::replace(a, A(1)) != A(1));
+        assert_eq!(get_changed(&mut world), vec![]);
+        world
+            .get_mut::<A>(e1)
+            .unwrap()
+            .set_changed_if(|a| std::mem::replace(a, A(2)) != A(2));
+        assert_eq!(get_changed(&mut world), vec![e1]);
+    }
+
     #[test]
     fn resource() {
         let mut world = World::default();
@@ -1560,6 +1594,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn insert_batch() {
+        let mut world = World::default();
+        let e0 = world.spawn().insert(A(0)).id();
+        let e1 = world.spawn().insert(A(1)).id();
+        let e2 = world.spawn().insert(B(2)).id();
+        let despawned = world.spawn().insert(A(3)).id();
+        world.despawn(despawned);
+
+        let values = vec![
+            (e0, (B(0), C)),
+            (despawned, (B(3), C)),
+            (e1, (B(1), C)),
+            (e2, (B(4), C)),
+        ];
+        let result = world.insert_batch(values);
+
+        assert_eq!(result, Err(vec![despawned]));
+        assert_eq!(world.get::<A>(e0), Some(&A(0)));
+        assert_eq!(world.get::<B>(e0), Some(&B(0)));
+        assert_eq!(world.get::<B>(e1), Some(&B(1)));
+        assert_eq!(world.get::<C>(e1), Some(&C));
+        assert_eq!(
+            world.get::<B>(e2),
+            Some(&B(4)),
+            "existing component was replaced"
+        );
+        assert_eq!(world.get::<C>(e2), Some(&C));
+        assert!(world.get_entity(despawned).is_none());
+    }
+
     #[test]
     fn insert_or_spawn_batch_invalid() {
         let mut world = World::default();
@@ -120,6 +120,8 @@ impl<T: SparseSetIndex> Access<T> {
 #[derive(Clone, Eq, PartialEq)]
 pub struct FilteredAccess<T: SparseSetIndex> {
     access: Access<T>,
+    /// The part of `access` on other entities than the filtered ones.
+    unfiltered_access: Access<T>,
     with: FixedBitSet,
     without: FixedBitSet,
 }
@@ -128,6 +130,7 @@ impl<T: SparseSetIndex> Default for FilteredAccess<T> {
     fn default() -> Self {
         Self {
             access: Access::default(),
+            unfiltered_access: Access::default(),
             with: Default::default(),
             without: Default::default(),
         }
@@ -150,6 +153,14 @@ impl<T: SparseSetIndex> FilteredAccess<T> {
         self.add_with(index);
     }
 
+    /// Adds a read access to `index` on any entity, not only on the ones matching the filters.
+    /// Unlike [`FilteredAccess::add_read`], it doesn't filter on `index`, and it isn't made
+    /// compatible with writes by disjoint filters.
+    pub fn add_unfiltered_read(&mut self, index: T) {
+        self.access.add_read(index.clone());
+        self.unfiltered_access.add_read(index);
+    }
+
     pub fn add_with(&mut self, index: T) {
         self.with.grow(index.sparse_set_index() + 1);
         self.with.insert(index.sparse_set_index());
@@ -160,9 +171,21 @@ impl<T: SparseSetIndex> FilteredAccess<T> {
         self.without.insert(index.sparse_set_index());
     }
 
+    /// Returns true if this access reads, writes or filters on `index` on the entities matching
+    /// the filters. Reads and writes also filter on their index, so only the filters are checked.
+    /// Reads added with [`FilteredAccess::add_unfiltered_read`] don't count.
+    pub fn mentions(&self, index: T) -> bool {
+        self.with.contains(index.sparse_set_index())
+            || self.without.contains(index.sparse_set_index())
+    }
+
     pub fn is_compatible(&self, other: &FilteredAccess<T>) -> bool {
         if self.access.is_compatible(&other.access) {
             true
+        } else if !self.unfiltered_access.is_compatible(&other.access)
+            || !other.unfiltered_access.is_compatible(&self.access)
+        {
+            false
         } else {
             self.with.intersection(&other.without).next().is_some()
                 || self.without.intersection(&other.with).next().is_some()
@@ -171,6 +194,7 @@ impl<T: SparseSetIndex> FilteredAccess<T> {
 
     pub fn extend(&mut self, access: &FilteredAccess<T>) {
         self.access.extend(&access.access);
+        self.unfiltered_access.extend(&access.unfiltered_access);
         self.with.union_with(&access.with);
         self.without.union_with(&access.without);
     }
@@ -276,4 +300,42 @@ mod tests {
 
         assert!(access_a.eq(&expected));
     }
+
+    #[test]
+    fn filtered_access_unfiltered_read() {
+        let mut access_a = FilteredAccess::<usize>::default();
+        access_a.add_read(0);
+        access_a.add_unfiltered_read(1);
+        access_a.add_with(2);
+
+        let mut access_b = FilteredAccess::<usize>::default();
+        access_b.add_write(1);
+        access_b.add_without(2);
+
+        assert!(!access_a.is_compatible(&access_b));
+        assert!(!access_b.is_compatible(&access_a));
+
+        // disjoint filters still apply to the other accesses
+        let mut access_c = FilteredAccess::<usize>::default();
+        access_c.add_write(0);
+        access_c.add_without(2);
+
+        assert!(access_a.is_compatible(&access_c));
+    }
+
+    #[test]
+    fn filtered_access_mentions() {
+        let mut access = FilteredAccess::<usize>::default();
+        access.add_read(0);
+        access.add_write(1);
+        access.add_with(2);
+        access.add_without(3);
+        access.add_unfiltered_read(4);
+
+        for index in 0..4 {
+            assert!(access.mentions(index));
+        }
+        assert!(!access.mentions(4));
+        assert!(!access.mentions(5));
+    }
 }
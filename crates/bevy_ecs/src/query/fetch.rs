@@ -124,6 +124,15 @@ pub unsafe trait FetchState: Send + Sync + Sized {
         archetype: &Archetype,
         access: &mut Access<ArchetypeComponentId>,
     );
+    /// Adds the access to the components of entities other than the ones the query matches, like
+    /// their ancestors, for fetches that read them. It is called for every archetype of the world,
+    /// whether the query matches it or not.
+    fn update_other_archetype_component_access(
+        &self,
+        _archetype: &Archetype,
+        _access: &mut Access<ArchetypeComponentId>,
+    ) {
+    }
     fn matches_archetype(&self, archetype: &Archetype) -> bool;
     fn matches_table(&self, table: &Table) -> bool;
 }
@@ -597,6 +606,15 @@ unsafe impl<T: FetchState> FetchState for OptionState<T> {
         }
     }
 
+    fn update_other_archetype_component_access(
+        &self,
+        archetype: &Archetype,
+        access: &mut Access<ArchetypeComponentId>,
+    ) {
+        self.state
+            .update_other_archetype_component_access(archetype, access);
+    }
+
     fn matches_archetype(&self, _archetype: &Archetype) -> bool {
         true
     }
@@ -967,6 +985,11 @@ macro_rules! impl_tuple_fetch {
                 $($name.update_archetype_component_access(_archetype, _access);)*
             }
 
+            fn update_other_archetype_component_access(&self, _archetype: &Archetype, _access: &mut Access<ArchetypeComponentId>) {
+                let ($($name,)*) = self;
+                $($name.update_other_archetype_component_access(_archetype, _access);)*
+            }
+
             fn matches_archetype(&self, _archetype: &Archetype) -> bool {
                 let ($($name,)*) = self;
                 true $(&& $name.matches_archetype(_archetype))*
@@ -416,6 +416,11 @@ macro_rules! impl_query_filter_tuple {
                 $($filter.update_archetype_component_access(archetype, access);)*
             }
 
+            fn update_other_archetype_component_access(&self, archetype: &Archetype, access: &mut Access<ArchetypeComponentId>) {
+                let ($($filter,)*) = &self.0;
+                $($filter.update_other_archetype_component_access(archetype, access);)*
+            }
+
             fn matches_archetype(&self, archetype: &Archetype) -> bool {
                 let ($($filter,)*) = &self.0;
                 false $(|| $filter.matches_archetype(archetype))*
@@ -1,5 +1,6 @@
 use crate::{
     archetype::{ArchetypeId, Archetypes},
+    entity::Entity,
     query::{Fetch, FilterFetch, QueryState, ReadOnlyFetch, WorldQuery},
     storage::{TableId, Tables},
     world::World,
@@ -383,6 +384,126 @@ where
     }
 }
 
+/// An [`Iterator`] over the entities matched by two queries, with the results of both queries for
+/// each of them.
+///
+/// This struct is created by the [`Query::join`](crate::system::Query::join) and
+/// [`Query::join_mut`](crate::system::Query::join_mut) methods.
+pub struct QueryJoinIter<'w, 's, Q1: WorldQuery, F1: WorldQuery, Q2: WorldQuery, F2: WorldQuery>
+where
+    F1::Fetch: FilterFetch,
+    F2::Fetch: FilterFetch,
+{
+    world: &'w World,
+    query_state_a: &'s QueryState<Q1, F1>,
+    query_state_b: &'s QueryState<Q2, F2>,
+    last_change_tick: u32,
+    change_tick: u32,
+    /// The archetypes matched by the query with the fewest entities, whose entities are looked up
+    /// in both queries
+    archetype_id_iter: std::slice::Iter<'s, ArchetypeId>,
+    entity_iter: std::slice::Iter<'w, Entity>,
+}
+
+impl<'w, 's, Q1: WorldQuery, F1: WorldQuery, Q2: WorldQuery, F2: WorldQuery>
+    QueryJoinIter<'w, 's, Q1, F1, Q2, F2>
+where
+    F1::Fetch: FilterFetch,
+    F2::Fetch: FilterFetch,
+{
+    /// # Safety
+    /// This does not check for mutable query correctness. To be safe, make sure mutable queries
+    /// have unique access to the components they query, and that the two queries don't conflict.
+    /// This does not validate that `world.id()` matches the `world_id` of both query states.
+    /// Calling this on a `world` with a mismatched WorldId is unsound.
+    pub(crate) unsafe fn new(
+        world: &'w World,
+        query_state_a: &'s QueryState<Q1, F1>,
+        query_state_b: &'s QueryState<Q2, F2>,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Self {
+        let matched_entities = |archetype_ids: &[ArchetypeId]| -> usize {
+            archetype_ids
+                .iter()
+                .map(|id| world.archetypes[*id].len())
+                .sum()
+        };
+        let archetype_ids = if matched_entities(&query_state_b.matched_archetype_ids)
+            < matched_entities(&query_state_a.matched_archetype_ids)
+        {
+            &query_state_b.matched_archetype_ids
+        } else {
+            &query_state_a.matched_archetype_ids
+        };
+        QueryJoinIter {
+            world,
+            query_state_a,
+            query_state_b,
+            last_change_tick,
+            change_tick,
+            archetype_id_iter: archetype_ids.iter(),
+            entity_iter: [].iter(),
+        }
+    }
+}
+
+impl<'w, 's, Q1: WorldQuery, F1: WorldQuery, Q2: WorldQuery, F2: WorldQuery> Iterator
+    for QueryJoinIter<'w, 's, Q1, F1, Q2, F2>
+where
+    F1::Fetch: FilterFetch,
+    F2::Fetch: FilterFetch,
+{
+    type Item = (
+        <Q1::Fetch as Fetch<'w, 's>>::Item,
+        <Q2::Fetch as Fetch<'w, 's>>::Item,
+    );
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let entity = match self.entity_iter.next() {
+                Some(entity) => *entity,
+                None => {
+                    let archetype_id = self.archetype_id_iter.next()?;
+                    self.entity_iter = self.world.archetypes[*archetype_id].entities().iter();
+                    continue;
+                }
+            };
+            // SAFE: each entity is visited once, so no two items alias, and the caller of `new`
+            // made sure the queries can be fetched together
+            unsafe {
+                let b = match self.query_state_b.get_unchecked_manual(
+                    self.world,
+                    entity,
+                    self.last_change_tick,
+                    self.change_tick,
+                ) {
+                    Ok(b) => b,
+                    Err(_) => continue,
+                };
+                if let Ok(a) = self.query_state_a.get_unchecked_manual(
+                    self.world,
+                    entity,
+                    self.last_change_tick,
+                    self.change_tick,
+                ) {
+                    return Some((a, b));
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let max_size = self.entity_iter.len()
+            + self
+                .archetype_id_iter
+                .clone()
+                .map(|id| self.world.archetypes[*id].len())
+                .sum::<usize>();
+        (0, Some(max_size))
+    }
+}
+
 // NOTE: We can cheaply implement this for unfiltered Queries because we have:
 // (1) pre-computed archetype matches
 // (2) each archetype pre-computes length
@@ -12,7 +12,9 @@ pub use state::*;
 
 #[cfg(test)]
 mod tests {
-    use crate::{self as bevy_ecs, component::Component, world::World};
+    use crate::{
+        self as bevy_ecs, component::Component, entity::Disabled, query::With, world::World,
+    };
 
     #[derive(Component, Debug, Eq, PartialEq)]
     struct A(usize);
@@ -184,4 +186,45 @@ mod tests {
         let values = world.query::<&B>().iter(&world).collect::<Vec<&B>>();
         assert_eq!(values, vec![&B(3)]);
     }
+
+    #[test]
+    fn disabled_entities() {
+        let mut world = World::new();
+        let enabled = world.spawn().insert(A(1)).id();
+        let disabled = world.spawn().insert_bundle((A(2), Disabled)).id();
+
+        let values = world.query::<&A>().iter(&world).collect::<Vec<&A>>();
+        assert_eq!(values, vec![&A(1)]);
+        assert!(world.query::<&A>().get(&world, disabled).is_err());
+        let values = world
+            .query_filtered::<&A, With<Disabled>>()
+            .iter(&world)
+            .collect::<Vec<&A>>();
+        assert_eq!(values, vec![&A(2)]);
+        assert_eq!(
+            world
+                .query::<(&A, Option<&Disabled>)>()
+                .iter(&world)
+                .count(),
+            2
+        );
+
+        world.entity_mut(disabled).remove::<Disabled>();
+        world.entity_mut(enabled).insert(Disabled);
+        let values = world.query::<&A>().iter(&world).collect::<Vec<&A>>();
+        assert_eq!(values, vec![&A(2)]);
+
+        // dynamic iteration skips disabled entities too, unless it asks for them
+        let a_id = world.init_component::<A>();
+        let disabled_id = world.init_component::<Disabled>();
+        let mut entities = Vec::new();
+        world.for_each_by_ids(&[a_id], |entity, _| entities.push(entity));
+        assert_eq!(entities, vec![disabled]);
+        let mut entities = Vec::new();
+        world.for_each_mut_by_ids(&[a_id], |entity, _| entities.push(entity));
+        assert_eq!(entities, vec![disabled]);
+        let mut entities = Vec::new();
+        world.for_each_by_ids(&[a_id, disabled_id], |entity, _| entities.push(entity));
+        assert_eq!(entities, vec![enabled]);
+    }
 }
@@ -1,7 +1,7 @@
 use crate::{
     archetype::{Archetype, ArchetypeComponentId, ArchetypeGeneration, ArchetypeId},
     component::ComponentId,
-    entity::Entity,
+    entity::{Disabled, Entity},
     query::{
         Access, Fetch, FetchState, FilterFetch, FilteredAccess, QueryCombinationIter, QueryIter,
         ReadOnlyFetch, WorldQuery,
@@ -30,6 +30,8 @@ where
     pub(crate) matched_archetype_ids: Vec<ArchetypeId>,
     pub(crate) fetch_state: Q::State,
     pub(crate) filter_state: F::State,
+    /// The id of [`Disabled`] if this query skips disabled entities
+    disabled: Option<ComponentId>,
 }
 
 impl<Q: WorldQuery, F: WorldQuery> QueryState<Q, F>
@@ -54,6 +56,16 @@ where
         // properly considered in a global "cross-query" context (both within systems and across systems).
         component_access.extend(&filter_component_access);
 
+        // Disabled entities are skipped unless the query explicitly asks for them. This isn't added
+        // to the `without` filters: `Option<&Disabled>` also registers `Disabled` as a `with`
+        // filter, which would then wrongly be considered disjoint from this query.
+        let disabled_id = world.init_component::<Disabled>();
+        let disabled = if component_access.mentions(disabled_id) {
+            None
+        } else {
+            Some(disabled_id)
+        };
+
         let mut state = Self {
             world_id: world.id(),
             archetype_generation: ArchetypeGeneration::initial(),
@@ -61,6 +73,7 @@ where
             matched_archetype_ids: Vec::new(),
             fetch_state,
             filter_state,
+            disabled,
             component_access,
             matched_tables: Default::default(),
             matched_archetypes: Default::default(),
@@ -104,8 +117,19 @@ where
 
     /// Creates a new [`Archetype`].
     pub fn new_archetype(&mut self, archetype: &Archetype) {
+        self.fetch_state.update_other_archetype_component_access(
+            archetype,
+            &mut self.archetype_component_access,
+        );
+        self.filter_state.update_other_archetype_component_access(
+            archetype,
+            &mut self.archetype_component_access,
+        );
         if self.fetch_state.matches_archetype(archetype)
             && self.filter_state.matches_archetype(archetype)
+            && !self
+                .disabled
+                .is_some_and(|disabled| archetype.contains(disabled))
         {
             self.fetch_state
                 .update_archetype_component_access(archetype, &mut self.archetype_component_access);
@@ -4,9 +4,13 @@ pub use crate::change_detection::ReflectMut;
 use crate::{
     component::Component,
     entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    system::Resource,
     world::{FromWorld, World},
 };
-use bevy_reflect::{impl_reflect_value, FromType, Reflect, ReflectDeserialize};
+use bevy_reflect::{impl_reflect_value, FromType, Reflect, ReflectDeserialize, TypeRegistryArc};
+use bevy_utils::{tracing::debug, HashSet};
+use std::any::TypeId;
+use thiserror::Error;
 
 #[derive(Clone)]
 pub struct ReflectComponent {
@@ -120,6 +124,44 @@ impl<C: Component + Reflect + FromWorld> FromType<C> for ReflectComponent {
     }
 }
 
+#[derive(Clone)]
+pub struct ReflectResource {
+    insert_resource: fn(&mut World, &dyn Reflect),
+    remove_resource: fn(&mut World),
+    reflect_resource: fn(&World) -> Option<&dyn Reflect>,
+}
+
+impl ReflectResource {
+    /// Inserts the resource built from `resource`, replacing the current one if any.
+    pub fn insert_resource(&self, world: &mut World, resource: &dyn Reflect) {
+        (self.insert_resource)(world, resource);
+    }
+
+    pub fn remove_resource(&self, world: &mut World) {
+        (self.remove_resource)(world);
+    }
+
+    pub fn reflect_resource<'a>(&self, world: &'a World) -> Option<&'a dyn Reflect> {
+        (self.reflect_resource)(world)
+    }
+}
+
+impl<R: Resource + Reflect + FromWorld> FromType<R> for ReflectResource {
+    fn from_type() -> Self {
+        ReflectResource {
+            insert_resource: |world, reflected_resource| {
+                let mut resource = R::from_world(world);
+                resource.apply(reflected_resource);
+                world.insert_resource(resource);
+            },
+            remove_resource: |world| {
+                world.remove_resource::<R>();
+            },
+            reflect_resource: |world| world.get_resource::<R>().map(|r| r as &dyn Reflect),
+        }
+    }
+}
+
 impl_reflect_value!(Entity(Hash, PartialEq, Serialize, Deserialize));
 
 #[derive(Clone)]
@@ -151,3 +193,272 @@ impl<C: Component + MapEntities> FromType<C> for ReflectMapEntities {
         }
     }
 }
+
+#[derive(Error, Debug)]
+pub enum CloneEntityError {
+    #[error("Entity {0:?} does not exist in the source world.")]
+    NoSuchEntity(Entity),
+    #[error("The source world has no TypeRegistryArc resource.")]
+    MissingTypeRegistry,
+}
+
+impl World {
+    /// Spawns a copy of `entity` in `destination`, and returns the new entity.
+    ///
+    /// Components are copied through reflection: only the components registered with
+    /// `#[reflect(Component)]` in the [`TypeRegistryArc`] resource of this world are copied, the
+    /// others are skipped. Entities referenced by the copied components (like a `Parent`) are not
+    /// remapped, and still refer to entities of this world.
+    pub fn clone_entity_into(
+        &self,
+        destination: &mut World,
+        entity: Entity,
+    ) -> Result<Entity, CloneEntityError> {
+        let type_registry = self
+            .get_resource::<TypeRegistryArc>()
+            .ok_or(CloneEntityError::MissingTypeRegistry)?
+            .read();
+        let source = self
+            .get_entity(entity)
+            .ok_or(CloneEntityError::NoSuchEntity(entity))?;
+        let destination_entity = destination.spawn().id();
+        for component_id in source.archetype().components() {
+            let info = self.components().get_info(component_id).unwrap();
+            let reflect_component = info
+                .type_id()
+                .and_then(|type_id| type_registry.get_type_data::<ReflectComponent>(type_id));
+            match reflect_component {
+                Some(reflect_component) => {
+                    reflect_component.copy_component(self, destination, entity, destination_entity)
+                }
+                None => debug!(
+                    "Component {} is not registered for reflection and was not cloned.",
+                    info.name()
+                ),
+            }
+        }
+        Ok(destination_entity)
+    }
+}
+
+/// A copy of the entities, reflected components and reflected resources of a [`World`], taken
+/// with [`World::snapshot`] and restored with [`World::restore`].
+#[derive(Default)]
+pub struct WorldSnapshot {
+    entities: Vec<EntitySnapshot>,
+    resources: Vec<(TypeId, Box<dyn Reflect>)>,
+}
+
+struct EntitySnapshot {
+    entity: Entity,
+    components: Vec<(TypeId, Box<dyn Reflect>)>,
+}
+
+impl WorldSnapshot {
+    /// The entities that existed when the snapshot was taken.
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.iter().map(|snapshot| snapshot.entity)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum SnapshotError {
+    #[error("The world has no TypeRegistryArc resource.")]
+    MissingTypeRegistry,
+}
+
+impl World {
+    /// Copies the entities of this world, with their components registered with
+    /// `#[reflect(Component)]`, and the resources registered with `#[reflect(Resource)]` in the
+    /// [`TypeRegistryArc`] resource of this world. Other components and resources are skipped.
+    ///
+    /// This is meant for rollback netcode, undo in editors and save states while debugging: the
+    /// snapshot stays in memory and can be restored any number of times with [`World::restore`].
+    pub fn snapshot(&self) -> Result<WorldSnapshot, SnapshotError> {
+        let type_registry = self
+            .get_resource::<TypeRegistryArc>()
+            .ok_or(SnapshotError::MissingTypeRegistry)?
+            .read();
+        let mut snapshot = WorldSnapshot::default();
+        for archetype in self.archetypes().iter() {
+            let reflect_components = archetype
+                .components()
+                .filter_map(|component_id| {
+                    let type_id = self.components().get_info(component_id)?.type_id()?;
+                    let reflect_component =
+                        type_registry.get_type_data::<ReflectComponent>(type_id)?;
+                    Some((type_id, reflect_component))
+                })
+                .collect::<Vec<_>>();
+            for &entity in archetype.entities() {
+                let components = reflect_components
+                    .iter()
+                    .filter_map(|(type_id, reflect_component)| {
+                        let component = reflect_component.reflect_component(self, entity)?;
+                        Some((*type_id, component.clone_value()))
+                    })
+                    .collect();
+                snapshot
+                    .entities
+                    .push(EntitySnapshot { entity, components });
+            }
+        }
+        for registration in type_registry.iter() {
+            if let Some(resource) = registration
+                .data::<ReflectResource>()
+                .and_then(|reflect_resource| reflect_resource.reflect_resource(self))
+            {
+                snapshot
+                    .resources
+                    .push((registration.type_id(), resource.clone_value()));
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Restores this world to the state of `snapshot`. The entities spawned since the snapshot
+    /// was taken are despawned, and the despawned ones are spawned again with the same [`Entity`]
+    /// ids. The reflected components and resources are set back to their snapshot value, or
+    /// removed if they didn't exist then, while the components and resources that aren't
+    /// registered for reflection are left untouched.
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) -> Result<(), SnapshotError> {
+        let type_registry = self
+            .get_resource::<TypeRegistryArc>()
+            .ok_or(SnapshotError::MissingTypeRegistry)?
+            .clone();
+        let type_registry = type_registry.read();
+
+        let snapshot_entities = snapshot.entities().collect::<HashSet<_>>();
+        let spawned = self
+            .archetypes()
+            .iter()
+            .flat_map(|archetype| archetype.entities())
+            .filter(|entity| !snapshot_entities.contains(entity))
+            .copied()
+            .collect::<Vec<_>>();
+        for entity in spawned {
+            self.despawn(entity);
+        }
+
+        for entity_snapshot in &snapshot.entities {
+            let entity = entity_snapshot.entity;
+            self.get_or_spawn(entity)
+                .expect("the entities spawned since the snapshot were despawned");
+            let current_type_ids = self
+                .entity(entity)
+                .archetype()
+                .components()
+                .filter_map(|component_id| self.components().get_info(component_id)?.type_id())
+                .collect::<Vec<_>>();
+            for type_id in current_type_ids {
+                if entity_snapshot
+                    .components
+                    .iter()
+                    .all(|(snapshot_type_id, _)| *snapshot_type_id != type_id)
+                {
+                    if let Some(reflect_component) =
+                        type_registry.get_type_data::<ReflectComponent>(type_id)
+                    {
+                        reflect_component.remove_component(self, entity);
+                    }
+                }
+            }
+            for (type_id, component) in &entity_snapshot.components {
+                // the type may have been unregistered since the snapshot was taken
+                if let Some(reflect_component) =
+                    type_registry.get_type_data::<ReflectComponent>(*type_id)
+                {
+                    // the component is replaced rather than applied, so that collections don't
+                    // keep the elements added since the snapshot
+                    reflect_component.add_component(self, entity, &**component);
+                }
+            }
+        }
+
+        for registration in type_registry.iter() {
+            let reflect_resource = match registration.data::<ReflectResource>() {
+                Some(reflect_resource) => reflect_resource,
+                None => continue,
+            };
+            match snapshot
+                .resources
+                .iter()
+                .find(|(type_id, _)| *type_id == registration.type_id())
+            {
+                Some((_, resource)) => reflect_resource.insert_resource(self, &**resource),
+                None => reflect_resource.remove_resource(self),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as bevy_ecs;
+
+    #[derive(Component, Reflect, Default, Debug, PartialEq)]
+    #[reflect(Component)]
+    struct Health(u32);
+
+    #[derive(Component)]
+    struct NotReflected;
+
+    #[derive(Reflect, Default, Debug, PartialEq)]
+    #[reflect(Resource)]
+    struct Score(u32);
+
+    #[test]
+    fn clone_entity_into() {
+        let mut source = World::new();
+        let type_registry = TypeRegistryArc::default();
+        type_registry.write().register::<Health>();
+        source.insert_resource(type_registry);
+        let entity = source.spawn().insert(Health(3)).insert(NotReflected).id();
+
+        let mut destination = World::new();
+        let clone = source.clone_entity_into(&mut destination, entity).unwrap();
+        assert_eq!(destination.get::<Health>(clone), Some(&Health(3)));
+        assert!(destination.get::<NotReflected>(clone).is_none());
+        assert!(source.get::<Health>(entity).is_some());
+
+        source.despawn(entity);
+        assert!(matches!(
+            source.clone_entity_into(&mut destination, entity),
+            Err(CloneEntityError::NoSuchEntity(_))
+        ));
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut world = World::new();
+        let type_registry = TypeRegistryArc::default();
+        {
+            let mut type_registry = type_registry.write();
+            type_registry.register::<Health>();
+            type_registry.register::<Score>();
+        }
+        world.insert_resource(type_registry);
+        world.insert_resource(Score(1));
+        let kept = world.spawn().insert(Health(3)).insert(NotReflected).id();
+        let despawned = world.spawn().insert(Health(5)).id();
+        let snapshot = world.snapshot().unwrap();
+
+        world.get_mut::<Health>(kept).unwrap().0 = 0;
+        world.entity_mut(kept).remove::<NotReflected>();
+        world.despawn(despawned);
+        let spawned = world.spawn().insert(Health(7)).id();
+        let without_health = world.spawn().id();
+        world.get_resource_mut::<Score>().unwrap().0 = 2;
+
+        world.restore(&snapshot).unwrap();
+        assert_eq!(world.get::<Health>(kept), Some(&Health(3)));
+        assert_eq!(world.get::<Health>(despawned), Some(&Health(5)));
+        assert!(world.get_entity(spawned).is_none());
+        assert!(world.get_entity(without_health).is_none());
+        assert_eq!(world.get_resource::<Score>(), Some(&Score(1)));
+        // components that aren't reflected aren't part of the snapshot
+        assert!(world.get::<NotReflected>(kept).is_none());
+    }
+}
@@ -0,0 +1,205 @@
+use crate as bevy_ecs;
+use crate::{
+    component::Component,
+    entity::{Entities, Entity},
+    query::Changed,
+    system::{Commands, Query, RemovedComponents},
+};
+use bevy_utils::{HashMap, HashSet};
+use std::marker::PhantomData;
+
+/// A component relating the entity it is added to (the source) to another entity (the target).
+///
+/// Unlike a raw [`Entity`] field, a relation never dangles: when its target is despawned,
+/// [`relation_maintenance_system`] removes the relation from the source, or despawns the source if
+/// [`Relation::DESPAWN_WITH_TARGET`] is set. The sources of a relation can be found from its
+/// target through the [`RelatedBy`] component.
+///
+/// ```
+/// # use bevy_ecs::{component::Component, entity::Entity, relation::Relation};
+/// #[derive(Component)]
+/// struct OwnedBy(Entity);
+///
+/// impl Relation for OwnedBy {
+///     const DESPAWN_WITH_TARGET: bool = true;
+///
+///     fn target(&self) -> Entity {
+///         self.0
+///     }
+/// }
+/// ```
+pub trait Relation: Component {
+    /// Whether the source of the relation is despawned along with its target. Otherwise only the
+    /// relation is removed from the source.
+    const DESPAWN_WITH_TARGET: bool = false;
+
+    /// The entity this relation points to.
+    fn target(&self) -> Entity;
+}
+
+/// The entities related to this entity through the relation `R`. This component is managed by
+/// [`relation_maintenance_system`], and removed once no entity is related to this one anymore.
+#[derive(Component, Debug)]
+pub struct RelatedBy<R: Relation> {
+    sources: Vec<Entity>,
+    marker: PhantomData<fn() -> R>,
+}
+
+impl<R: Relation> RelatedBy<R> {
+    /// The entities whose relation `R` points to this entity.
+    pub fn sources(&self) -> &[Entity] {
+        &self.sources
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Entity> {
+        self.sources.iter()
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.sources.contains(&entity)
+    }
+
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+}
+
+/// Cleans up relations `R` whose target was despawned, and keeps the [`RelatedBy`] components of
+/// the targets up to date.
+pub fn relation_maintenance_system<R: Relation>(
+    mut commands: Commands,
+    entities: &Entities,
+    relations: Query<(Entity, &R)>,
+    changed_relations: Query<Entity, Changed<R>>,
+    mut removed_relations: RemovedComponents<R>,
+    mut related_by: Query<(Entity, &mut RelatedBy<R>)>,
+) {
+    let mut dangling = HashSet::default();
+    for (source, relation) in relations.iter() {
+        if !entities.contains(relation.target()) {
+            if R::DESPAWN_WITH_TARGET {
+                commands.entity(source).despawn();
+            } else {
+                commands.entity(source).remove::<R>();
+            }
+            dangling.insert(source);
+        }
+    }
+
+    if dangling.is_empty()
+        && changed_relations.iter().next().is_none()
+        && removed_relations.iter().next().is_none()
+    {
+        return;
+    }
+
+    let mut sources_by_target = HashMap::<Entity, Vec<Entity>>::default();
+    for (source, relation) in relations.iter() {
+        if !dangling.contains(&source) {
+            sources_by_target
+                .entry(relation.target())
+                .or_default()
+                .push(source);
+        }
+    }
+
+    for (target, mut related_by) in related_by.iter_mut() {
+        match sources_by_target.remove(&target) {
+            Some(sources) => {
+                // only write on change so `Changed<RelatedBy<R>>` stays meaningful
+                if related_by.sources != sources {
+                    related_by.sources = sources;
+                }
+            }
+            None => {
+                commands.entity(target).remove::<RelatedBy<R>>();
+            }
+        }
+    }
+    for (target, sources) in sources_by_target {
+        commands.entity(target).insert(RelatedBy::<R> {
+            sources,
+            marker: PhantomData,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        schedule::{Stage, SystemStage},
+        world::World,
+    };
+
+    #[derive(Component)]
+    struct Targets(Entity);
+
+    impl Relation for Targets {
+        fn target(&self) -> Entity {
+            self.0
+        }
+    }
+
+    #[derive(Component)]
+    struct OwnedBy(Entity);
+
+    impl Relation for OwnedBy {
+        const DESPAWN_WITH_TARGET: bool = true;
+
+        fn target(&self) -> Entity {
+            self.0
+        }
+    }
+
+    #[test]
+    fn related_by() {
+        let mut world = World::default();
+        let mut stage = SystemStage::single_threaded();
+        stage.add_system(relation_maintenance_system::<Targets>);
+
+        let target = world.spawn().id();
+        let a = world.spawn().insert(Targets(target)).id();
+        let b = world.spawn().insert(Targets(target)).id();
+        stage.run(&mut world);
+
+        let related_by = world.get::<RelatedBy<Targets>>(target).unwrap();
+        assert_eq!(related_by.len(), 2);
+        assert!(related_by.contains(a));
+        assert!(related_by.contains(b));
+
+        world.despawn(a);
+        stage.run(&mut world);
+        assert_eq!(
+            world.get::<RelatedBy<Targets>>(target).unwrap().sources(),
+            &[b]
+        );
+
+        world.entity_mut(b).remove::<Targets>();
+        stage.run(&mut world);
+        assert!(world.get::<RelatedBy<Targets>>(target).is_none());
+    }
+
+    #[test]
+    fn despawned_target() {
+        let mut world = World::default();
+        let mut stage = SystemStage::single_threaded();
+        stage.add_system(relation_maintenance_system::<Targets>);
+        stage.add_system(relation_maintenance_system::<OwnedBy>);
+
+        let target = world.spawn().id();
+        let targeting = world.spawn().insert(Targets(target)).id();
+        let owned = world.spawn().insert(OwnedBy(target)).id();
+        stage.run(&mut world);
+
+        world.despawn(target);
+        stage.run(&mut world);
+        assert!(world.get_entity(targeting).is_some());
+        assert!(world.get::<Targets>(targeting).is_none());
+        assert!(world.get_entity(owned).is_none());
+    }
+}
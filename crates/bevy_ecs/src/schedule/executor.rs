@@ -1,4 +1,9 @@
-use crate::{archetype::ArchetypeGeneration, schedule::ParallelSystemContainer, world::World};
+use crate::{
+    archetype::ArchetypeGeneration,
+    schedule::{ParallelSystemContainer, SystemTimings},
+    world::World,
+};
+use bevy_utils::Instant;
 use downcast_rs::{impl_downcast, Downcast};
 
 pub trait ParallelSystemExecutor: Downcast + Send + Sync {
@@ -34,7 +39,16 @@ impl ParallelSystemExecutor for SingleThreadedExecutor {
                 let system_span = bevy_utils::tracing::info_span!("system", name = &*system.name());
                 #[cfg(feature = "trace")]
                 let _system_guard = system_span.enter();
-                system.system_mut().run((), world);
+                if world.contains_resource::<SystemTimings>() {
+                    let start = Instant::now();
+                    system.system_mut().run((), world);
+                    let duration = start.elapsed();
+                    if let Some(mut timings) = world.get_resource_mut::<SystemTimings>() {
+                        timings.record(system.name(), duration);
+                    }
+                } else {
+                    system.system_mut().run((), world);
+                }
             }
         }
     }
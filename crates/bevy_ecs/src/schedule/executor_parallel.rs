@@ -1,11 +1,12 @@
 use crate::{
     archetype::{ArchetypeComponentId, ArchetypeGeneration},
     query::Access,
-    schedule::{ParallelSystemContainer, ParallelSystemExecutor},
+    schedule::{ParallelSystemContainer, ParallelSystemExecutor, SystemTimings},
     world::World,
 };
 use async_channel::{Receiver, Sender};
 use bevy_tasks::{ComputeTaskPool, Scope, TaskPool};
+use bevy_utils::{Duration, Instant};
 use fixedbitset::FixedBitSet;
 
 #[cfg(test)]
@@ -34,10 +35,13 @@ pub struct ParallelExecutor {
     archetype_generation: ArchetypeGeneration,
     /// Cached metadata of every system.
     system_metadata: Vec<SystemSchedulingMetadata>,
-    /// Used by systems to notify the executor that they have finished.
-    finish_sender: Sender<usize>,
+    /// Used by systems to notify the executor that they have finished, with their run time if
+    /// it is measured.
+    finish_sender: Sender<(usize, Option<Duration>)>,
     /// Receives finish events from systems.
-    finish_receiver: Receiver<usize>,
+    finish_receiver: Receiver<(usize, Option<Duration>)>,
+    /// Run times of the systems that finished, when [`SystemTimings`] are recorded.
+    timings: Vec<(usize, Duration)>,
     /// Systems that should be started at next opportunity.
     queued: FixedBitSet,
     /// Systems that are currently running.
@@ -62,6 +66,7 @@ impl Default for ParallelExecutor {
             system_metadata: Default::default(),
             finish_sender,
             finish_receiver,
+            timings: Default::default(),
             queued: Default::default(),
             running: Default::default(),
             non_send_running: false,
@@ -117,8 +122,9 @@ impl ParallelSystemExecutor for ParallelExecutor {
         let compute_pool = world
             .get_resource_or_insert_with(|| ComputeTaskPool(TaskPool::default()))
             .clone();
+        let measure = world.contains_resource::<SystemTimings>();
         compute_pool.scope(|scope| {
-            self.prepare_systems(scope, systems, world);
+            self.prepare_systems(scope, systems, world, measure);
             scope.spawn(async {
                 // All systems have been ran if there are no queued or running systems.
                 while 0 != self.queued.count_ones(..) + self.running.count_ones(..) {
@@ -126,15 +132,15 @@ impl ParallelSystemExecutor for ParallelExecutor {
                     // Avoid deadlocking if no systems were actually started.
                     if self.running.count_ones(..) != 0 {
                         // Wait until at least one system has finished.
-                        let index = self
+                        let finished = self
                             .finish_receiver
                             .recv()
                             .await
                             .unwrap_or_else(|error| unreachable!(error));
-                        self.process_finished_system(index);
+                        self.process_finished_system(finished);
                         // Gather other systems than may have finished.
-                        while let Ok(index) = self.finish_receiver.try_recv() {
-                            self.process_finished_system(index);
+                        while let Ok(finished) = self.finish_receiver.try_recv() {
+                            self.process_finished_system(finished);
                         }
                         // At least one system has finished, so active access is outdated.
                         self.rebuild_active_access();
@@ -143,6 +149,14 @@ impl ParallelSystemExecutor for ParallelExecutor {
                 }
             });
         });
+
+        if let Some(mut timings) = world.get_resource_mut::<SystemTimings>() {
+            for (index, duration) in self.timings.drain(..) {
+                timings.record(systems[index].name(), duration);
+            }
+        } else {
+            self.timings.clear();
+        }
     }
 }
 
@@ -173,6 +187,7 @@ impl ParallelExecutor {
         scope: &mut Scope<'scope, ()>,
         systems: &'scope [ParallelSystemContainer],
         world: &'scope World,
+        measure: bool,
     ) {
         self.should_run.clear();
         for (index, system_data) in self.system_metadata.iter_mut().enumerate() {
@@ -191,11 +206,13 @@ impl ParallelExecutor {
                         .unwrap_or_else(|error| unreachable!(error));
                     #[cfg(feature = "trace")]
                     let system_guard = system_span.enter();
+                    let start = measure.then(Instant::now);
                     unsafe { system.run_unsafe((), world) };
+                    let duration = start.map(|start| start.elapsed());
                     #[cfg(feature = "trace")]
                     drop(system_guard);
                     finish_sender
-                        .send(index)
+                        .send((index, duration))
                         .await
                         .unwrap_or_else(|error| unreachable!(error));
                 };
@@ -267,7 +284,10 @@ impl ParallelExecutor {
 
     /// Unmarks the system give index as running, caches indices of its dependants
     /// in the `dependants_scratch`.
-    fn process_finished_system(&mut self, index: usize) {
+    fn process_finished_system(&mut self, (index, duration): (usize, Option<Duration>)) {
+        if let Some(duration) = duration {
+            self.timings.push((index, duration));
+        }
         let system_data = &self.system_metadata[index];
         if !system_data.is_send {
             self.non_send_running = false;
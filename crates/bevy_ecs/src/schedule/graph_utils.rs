@@ -1,6 +1,6 @@
 use bevy_utils::{tracing::warn, HashMap, HashSet};
 use fixedbitset::FixedBitSet;
-use std::{borrow::Cow, fmt::Debug, hash::Hash};
+use std::{borrow::Cow, cmp::Reverse, collections::BinaryHeap, fmt::Debug, hash::Hash};
 
 pub enum DependencyGraphError<Labels> {
     GraphCycles(Vec<(usize, Labels)>),
@@ -14,9 +14,11 @@ pub trait GraphNode {
     fn after(&self) -> &[Self::Label];
 }
 
-/// Constructs a dependency graph of given nodes.
+/// Constructs a dependency graph of given nodes. Ordering against `external_labels` doesn't
+/// create dependencies, but isn't reported as ordering against an unknown label either.
 pub fn build_dependency_graph<Node>(
     nodes: &[Node],
+    external_labels: &[Node::Label],
 ) -> HashMap<usize, HashMap<usize, HashSet<Node::Label>>>
 where
     Node: GraphNode,
@@ -48,6 +50,7 @@ where
                             .insert(label.clone());
                     }
                 }
+                None if external_labels.contains(label) => (),
                 None => warn!(
                     // TODO: plumb this as proper output?
                     "{} wants to be after unknown label: {:?}",
@@ -68,6 +71,7 @@ where
                             .insert(label.clone());
                     }
                 }
+                None if external_labels.contains(label) => (),
                 None => warn!(
                     "{} wants to be before unknown label: {:?}",
                     nodes[index].name(),
@@ -124,3 +128,47 @@ pub fn topological_order<Labels: Clone>(
     }
     Ok(sorted)
 }
+
+/// Like [`topological_order`], but the order of nodes that aren't ordered against each other
+/// only depends on `seed` and on the indices of the nodes, so it is the same on every run.
+pub fn seeded_topological_order<Labels: Clone>(
+    graph: &HashMap<usize, HashMap<usize, Labels>>,
+    seed: u64,
+) -> Result<Vec<usize>, DependencyGraphError<Labels>> {
+    // reports dependency cycles
+    topological_order(graph)?;
+    let mut dependencies_left = vec![0; graph.len()];
+    let mut dependants = vec![Vec::new(); graph.len()];
+    for (&node, dependencies) in graph {
+        dependencies_left[node] = dependencies.len();
+        for &dependency in dependencies.keys() {
+            dependants[dependency].push(node);
+        }
+    }
+    let priority = |node: usize| Reverse((seeded_hash(seed, node), node));
+    let mut ready = dependencies_left
+        .iter()
+        .enumerate()
+        .filter(|(_, &count)| count == 0)
+        .map(|(node, _)| priority(node))
+        .collect::<BinaryHeap<_>>();
+    let mut sorted = Vec::with_capacity(graph.len());
+    while let Some(Reverse((_, node))) = ready.pop() {
+        sorted.push(node);
+        for &dependant in &dependants[node] {
+            dependencies_left[dependant] -= 1;
+            if dependencies_left[dependant] == 0 {
+                ready.push(priority(dependant));
+            }
+        }
+    }
+    Ok(sorted)
+}
+
+/// Hashes `node` with `seed` using SplitMix64, which gives the same result on every platform.
+fn seeded_hash(seed: u64, node: usize) -> u64 {
+    let mut hash = seed.wrapping_add((node as u64 + 1).wrapping_mul(0x9e3779b97f4a7c15));
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+    hash ^ (hash >> 31)
+}
@@ -1,10 +1,11 @@
 pub use bevy_ecs_macros::{AmbiguitySetLabel, RunCriteriaLabel, StageLabel, SystemLabel};
 
 use std::{
-    any::Any,
+    any::{type_name, Any},
     borrow::Cow,
-    fmt::Debug,
+    fmt::{self, Debug},
     hash::{Hash, Hasher},
+    marker::PhantomData,
 };
 
 pub trait DynEq: Any {
@@ -113,3 +114,56 @@ impl_label!(StageLabel);
 impl_label!(SystemLabel);
 impl_label!(AmbiguitySetLabel);
 impl_label!(RunCriteriaLabel);
+
+/// A [`SystemLabel`] identifying a system by the type of its function. Every function system gets
+/// this label, so each instantiation of a generic system like `sync_component::<T>` can be ordered
+/// against without naming it by hand. Get it with
+/// [`AsSystemLabel::as_system_label`](crate::system::AsSystemLabel::as_system_label).
+pub struct SystemTypeIdLabel<F: 'static>(PhantomData<fn() -> F>);
+
+impl<F: 'static> SystemTypeIdLabel<F> {
+    pub fn new() -> Self {
+        SystemTypeIdLabel(PhantomData)
+    }
+}
+
+impl<F: 'static> Default for SystemTypeIdLabel<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: 'static> Debug for SystemTypeIdLabel<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SystemTypeIdLabel")
+            .field(&type_name::<F>())
+            .finish()
+    }
+}
+
+impl<F: 'static> Clone for SystemTypeIdLabel<F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F: 'static> Copy for SystemTypeIdLabel<F> {}
+
+impl<F: 'static> PartialEq for SystemTypeIdLabel<F> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<F: 'static> Eq for SystemTypeIdLabel<F> {}
+
+impl<F: 'static> Hash for SystemTypeIdLabel<F> {
+    // the type id is already hashed by `DynHash`
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+impl<F: 'static> SystemLabel for SystemTypeIdLabel<F> {
+    fn dyn_clone(&self) -> Box<dyn SystemLabel> {
+        Box::new(*self)
+    }
+}
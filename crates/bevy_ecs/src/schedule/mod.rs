@@ -8,22 +8,28 @@ mod executor_parallel;
 pub mod graph_utils;
 mod label;
 mod run_criteria;
+mod schedule_graph;
 mod stage;
 mod state;
+mod stepping;
 mod system_container;
 mod system_descriptor;
 mod system_set;
+mod system_timings;
 
 pub use executor::*;
 pub use executor_parallel::*;
 pub use graph_utils::GraphNode;
 pub use label::*;
 pub use run_criteria::*;
+pub use schedule_graph::*;
 pub use stage::*;
 pub use state::*;
+pub use stepping::*;
 pub use system_container::*;
 pub use system_descriptor::*;
 pub use system_set::*;
+pub use system_timings::*;
 
 use std::fmt::Debug;
 
@@ -252,6 +258,18 @@ impl Schedule {
         })
     }
 
+    /// Adds a flush point labelled `label` to the [`SystemStage`] identified by `stage_label`.
+    /// See [`SystemStage::add_flush_point`].
+    pub fn add_flush_point_to_stage(
+        &mut self,
+        stage_label: impl StageLabel,
+        label: impl SystemLabel,
+    ) -> &mut Self {
+        self.stage(stage_label, |stage: &mut SystemStage| {
+            stage.add_flush_point(label)
+        })
+    }
+
     /// Fetches the [`Stage`] of type `T` marked with `label`, then executes the provided
     /// `func` passing the fetched stage to it as an argument.
     ///
@@ -338,10 +356,40 @@ impl Schedule {
             #[cfg(feature = "trace")]
             let _stage_guard = stage_span.enter();
             let stage = self.stages.get_mut(label).unwrap();
-            stage.run(world);
+            let stepping = world
+                .get_resource::<Stepping>()
+                .map_or(StageStepping::NotStepped, |stepping| {
+                    stepping.stage_stepping(&**label)
+                });
+            match (stepping, stage.downcast_mut::<SystemStage>()) {
+                (StageStepping::Paused, Some(_)) => {}
+                (StageStepping::Step { from, step }, Some(stage)) => {
+                    if let Some(next_system) = stage.run_step(world, &**label, from, step) {
+                        let system_count = stage.step_system_count();
+                        if let Some(mut stepping) = world.get_resource_mut::<Stepping>() {
+                            stepping.advance(next_system, system_count);
+                        }
+                    }
+                }
+                // only `SystemStage`s can be stepped
+                _ => stage.run(world),
+            }
         }
     }
 
+    /// Makes every [`SystemStage`] of the schedule deterministic, including the stages of nested
+    /// schedules, see [`SystemStage::set_deterministic`]. Stages added afterwards aren't affected.
+    pub fn set_deterministic(&mut self, seed: u64) -> &mut Self {
+        for stage in self.stages.values_mut() {
+            if let Some(stage) = stage.downcast_mut::<SystemStage>() {
+                stage.set_deterministic(seed);
+            } else if let Some(schedule) = stage.downcast_mut::<Schedule>() {
+                schedule.set_deterministic(seed);
+            }
+        }
+        self
+    }
+
     /// Iterates over all of schedule's stages and their labels, in execution order.
     pub fn iter_stages(&self) -> impl Iterator<Item = (&dyn StageLabel, &dyn Stage)> {
         self.stage_order
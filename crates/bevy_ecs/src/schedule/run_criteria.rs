@@ -3,7 +3,7 @@ use crate::{
     component::ComponentId,
     query::Access,
     schedule::{BoxedRunCriteriaLabel, GraphNode, RunCriteriaLabel},
-    system::{BoxedSystem, IntoSystem, System},
+    system::{BoxedSystem, IntoSystem, Res, Resource, System},
     world::World,
 };
 use std::borrow::Cow;
@@ -396,6 +396,250 @@ where
     }
 }
 
+/// Combines run criteria systems, so a system can run when several conditions are met without
+/// writing a dedicated run criteria system:
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::schedule::{in_state, resource_exists};
+/// # #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// # enum GameState { Playing }
+/// # struct Settings;
+/// # fn my_system() {}
+/// # let mut stage = SystemStage::parallel();
+/// stage.add_system(my_system.with_run_criteria(
+///     in_state(GameState::Playing).and(resource_exists::<Settings>()),
+/// ));
+/// ```
+///
+/// Both criteria are always evaluated, so stateful criteria like timers keep counting whatever the
+/// other criteria returns. When a criteria asks to be checked again, the combined criteria is
+/// checked again as a whole.
+pub trait RunCriteriaCombinators<Param>: IntoSystem<(), ShouldRun, Param> + Sized {
+    /// Runs when both this criteria and `other` run.
+    fn and<Other, OtherParam>(
+        self,
+        other: Other,
+    ) -> CombinedRunCriteria<Self::System, Other::System>
+    where
+        Other: IntoSystem<(), ShouldRun, OtherParam>;
+
+    /// Runs when this criteria or `other` run.
+    fn or<Other, OtherParam>(
+        self,
+        other: Other,
+    ) -> CombinedRunCriteria<Self::System, Other::System>
+    where
+        Other: IntoSystem<(), ShouldRun, OtherParam>;
+
+    /// Runs when this criteria doesn't.
+    fn not(self) -> NotRunCriteria<Self::System>;
+}
+
+impl<S, Param> RunCriteriaCombinators<Param> for S
+where
+    S: IntoSystem<(), ShouldRun, Param>,
+{
+    fn and<Other, OtherParam>(self, other: Other) -> CombinedRunCriteria<S::System, Other::System>
+    where
+        Other: IntoSystem<(), ShouldRun, OtherParam>,
+    {
+        CombinedRunCriteria::new(self.system(), other.system(), Operator::And)
+    }
+
+    fn or<Other, OtherParam>(self, other: Other) -> CombinedRunCriteria<S::System, Other::System>
+    where
+        Other: IntoSystem<(), ShouldRun, OtherParam>,
+    {
+        CombinedRunCriteria::new(self.system(), other.system(), Operator::Or)
+    }
+
+    fn not(self) -> NotRunCriteria<S::System> {
+        let criteria = self.system();
+        NotRunCriteria {
+            name: Cow::Owned(format!("Not({})", criteria.name())),
+            criteria,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Operator {
+    And,
+    Or,
+}
+
+/// Two run criteria combined with [`RunCriteriaCombinators::and`] or
+/// [`RunCriteriaCombinators::or`].
+pub struct CombinedRunCriteria<CriteriaA, CriteriaB> {
+    criteria_a: CriteriaA,
+    criteria_b: CriteriaB,
+    operator: Operator,
+    name: Cow<'static, str>,
+    component_access: Access<ComponentId>,
+    archetype_component_access: Access<ArchetypeComponentId>,
+}
+
+impl<CriteriaA: System, CriteriaB: System> CombinedRunCriteria<CriteriaA, CriteriaB> {
+    fn new(criteria_a: CriteriaA, criteria_b: CriteriaB, operator: Operator) -> Self {
+        CombinedRunCriteria {
+            name: Cow::Owned(format!(
+                "{:?}({}, {})",
+                operator,
+                criteria_a.name(),
+                criteria_b.name()
+            )),
+            criteria_a,
+            criteria_b,
+            operator,
+            component_access: Default::default(),
+            archetype_component_access: Default::default(),
+        }
+    }
+}
+
+impl<CriteriaA, CriteriaB> System for CombinedRunCriteria<CriteriaA, CriteriaB>
+where
+    CriteriaA: System<In = (), Out = ShouldRun>,
+    CriteriaB: System<In = (), Out = ShouldRun>,
+{
+    type In = ();
+    type Out = ShouldRun;
+
+    fn name(&self) -> Cow<'static, str> {
+        self.name.clone()
+    }
+
+    fn new_archetype(&mut self, archetype: &Archetype) {
+        self.criteria_a.new_archetype(archetype);
+        self.criteria_b.new_archetype(archetype);
+
+        self.archetype_component_access
+            .extend(self.criteria_a.archetype_component_access());
+        self.archetype_component_access
+            .extend(self.criteria_b.archetype_component_access());
+    }
+
+    fn archetype_component_access(&self) -> &Access<ArchetypeComponentId> {
+        &self.archetype_component_access
+    }
+
+    fn component_access(&self) -> &Access<ComponentId> {
+        &self.component_access
+    }
+
+    fn is_send(&self) -> bool {
+        self.criteria_a.is_send() && self.criteria_b.is_send()
+    }
+
+    unsafe fn run_unsafe(&mut self, _input: (), world: &World) -> ShouldRun {
+        let a = self.criteria_a.run_unsafe((), world);
+        let b = self.criteria_b.run_unsafe((), world);
+        // the result with the highest priority wins
+        let priority = match self.operator {
+            Operator::And => [
+                ShouldRun::No,
+                ShouldRun::NoAndCheckAgain,
+                ShouldRun::YesAndCheckAgain,
+                ShouldRun::Yes,
+            ],
+            Operator::Or => [
+                ShouldRun::YesAndCheckAgain,
+                ShouldRun::Yes,
+                ShouldRun::NoAndCheckAgain,
+                ShouldRun::No,
+            ],
+        };
+        priority
+            .iter()
+            .copied()
+            .find(|&should_run| should_run == a || should_run == b)
+            .unwrap()
+    }
+
+    fn apply_buffers(&mut self, world: &mut World) {
+        self.criteria_a.apply_buffers(world);
+        self.criteria_b.apply_buffers(world);
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.criteria_a.initialize(world);
+        self.criteria_b.initialize(world);
+        self.component_access
+            .extend(self.criteria_a.component_access());
+        self.component_access
+            .extend(self.criteria_b.component_access());
+    }
+
+    fn check_change_tick(&mut self, change_tick: u32) {
+        self.criteria_a.check_change_tick(change_tick);
+        self.criteria_b.check_change_tick(change_tick);
+    }
+}
+
+/// A run criteria inverted with [`RunCriteriaCombinators::not`].
+pub struct NotRunCriteria<Criteria> {
+    criteria: Criteria,
+    name: Cow<'static, str>,
+}
+
+impl<Criteria: System<In = (), Out = ShouldRun>> System for NotRunCriteria<Criteria> {
+    type In = ();
+    type Out = ShouldRun;
+
+    fn name(&self) -> Cow<'static, str> {
+        self.name.clone()
+    }
+
+    fn new_archetype(&mut self, archetype: &Archetype) {
+        self.criteria.new_archetype(archetype);
+    }
+
+    fn archetype_component_access(&self) -> &Access<ArchetypeComponentId> {
+        self.criteria.archetype_component_access()
+    }
+
+    fn component_access(&self) -> &Access<ComponentId> {
+        self.criteria.component_access()
+    }
+
+    fn is_send(&self) -> bool {
+        self.criteria.is_send()
+    }
+
+    unsafe fn run_unsafe(&mut self, _input: (), world: &World) -> ShouldRun {
+        match self.criteria.run_unsafe((), world) {
+            ShouldRun::Yes => ShouldRun::No,
+            ShouldRun::No => ShouldRun::Yes,
+            ShouldRun::YesAndCheckAgain => ShouldRun::NoAndCheckAgain,
+            ShouldRun::NoAndCheckAgain => ShouldRun::YesAndCheckAgain,
+        }
+    }
+
+    fn apply_buffers(&mut self, world: &mut World) {
+        self.criteria.apply_buffers(world);
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.criteria.initialize(world);
+    }
+
+    fn check_change_tick(&mut self, change_tick: u32) {
+        self.criteria.check_change_tick(change_tick);
+    }
+}
+
+/// A run criteria that runs while the resource `T` exists.
+pub fn resource_exists<T: Resource>() -> impl FnMut(Option<Res<T>>) -> ShouldRun {
+    |resource: Option<Res<T>>| {
+        if resource.is_some() {
+            ShouldRun::Yes
+        } else {
+            ShouldRun::No
+        }
+    }
+}
+
 pub struct RunOnce {
     ran: bool,
     archetype_component_access: Access<ArchetypeComponentId>,
@@ -449,3 +693,42 @@ impl System for RunOnce {
 
     fn check_change_tick(&mut self, _change_tick: u32) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        prelude::*,
+        schedule::{resource_exists, ShouldRun},
+    };
+
+    fn yes() -> ShouldRun {
+        ShouldRun::Yes
+    }
+
+    fn no() -> ShouldRun {
+        ShouldRun::No
+    }
+
+    fn push(name: &'static str) -> impl FnMut(ResMut<Vec<&'static str>>) {
+        move |mut ran: ResMut<Vec<&'static str>>| ran.push(name)
+    }
+
+    #[test]
+    fn combinators() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<&'static str>::new());
+        let mut stage = SystemStage::parallel()
+            .with_system(push("and").with_run_criteria(yes.and(no)))
+            .with_system(push("or").with_run_criteria(no.or(yes)))
+            .with_system(push("not").with_run_criteria(no.not()))
+            .with_system(push("missing").with_run_criteria(resource_exists::<u32>()))
+            .with_system(
+                push("exists")
+                    .with_run_criteria(resource_exists::<Vec<&'static str>>().and(yes.not().not())),
+            );
+        stage.run(&mut world);
+        let mut ran = world.get_resource::<Vec<&'static str>>().unwrap().clone();
+        ran.sort_unstable();
+        assert_eq!(ran, vec!["exists", "not", "or"]);
+    }
+}
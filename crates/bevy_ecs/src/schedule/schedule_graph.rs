@@ -0,0 +1,287 @@
+use crate::{
+    schedule::{Schedule, SystemStage},
+    world::World,
+};
+use serde::Serialize;
+use std::fmt::Write;
+
+/// The stages of a [`Schedule`] and their systems, with the ordering constraints between systems
+/// and their execution order ambiguities. Created with [`Schedule::graph`].
+///
+/// It can be rendered with Graphviz using [`ScheduleGraph::to_dot`], or serialized with any
+/// [`serde`] format, like JSON, to be processed by other tools.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScheduleGraph {
+    /// The stages of the schedule, in execution order
+    pub stages: Vec<StageGraph>,
+}
+
+/// A stage of a [`ScheduleGraph`]. Only [`SystemStage`]s have systems, and only nested
+/// [`Schedule`]s have a `schedule`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StageGraph {
+    pub label: String,
+    /// The systems of the stage, in execution order
+    pub systems: Vec<SystemNode>,
+    /// The flush points of the stage, see [`SystemStage::add_flush_point`]
+    pub flush_points: Vec<String>,
+    pub ambiguities: Vec<Ambiguity>,
+    pub schedule: Option<ScheduleGraph>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemNode {
+    pub name: String,
+    pub kind: SystemKind,
+    pub labels: Vec<String>,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+    pub run_criteria: Option<String>,
+    /// The indices in [`StageGraph::systems`] of the systems this one runs after
+    pub dependencies: Vec<usize>,
+    /// The labels this system is ordered against that no system it can be ordered with has. These
+    /// are usually typos, or labels of systems in another stage.
+    pub unknown_labels: Vec<String>,
+}
+
+/// Where a system runs in its stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SystemKind {
+    ExclusiveAtStart,
+    Parallel,
+    ExclusiveBeforeCommands,
+    ExclusiveAtEnd,
+}
+
+/// Two systems of a stage that may run in any order, while accessing the same data.
+#[derive(Debug, Clone, Serialize)]
+pub struct Ambiguity {
+    /// The index of the first system in [`StageGraph::systems`]
+    pub system_a: usize,
+    /// The index of the second system in [`StageGraph::systems`]
+    pub system_b: usize,
+    /// The names of the components both systems access, one of them mutably
+    pub conflicts: Vec<String>,
+}
+
+impl Schedule {
+    /// Describes the stages and systems of the schedule, to find out why systems run in a given
+    /// order. The systems of a stage are only sorted once the stage ran, so this should be called
+    /// after running the schedule at least once.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # fn my_system() {}
+    /// # let mut world = World::new();
+    /// let mut schedule = Schedule::default();
+    /// schedule.add_stage("update", SystemStage::parallel().with_system(my_system));
+    /// schedule.run(&mut world);
+    /// let path = std::env::temp_dir().join("schedule.dot");
+    /// std::fs::write(&path, schedule.graph(&world).to_dot()).unwrap();
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn graph(&self, world: &World) -> ScheduleGraph {
+        let stages = self
+            .iter_stages()
+            .map(|(label, stage)| {
+                let label = format!("{:?}", label);
+                if let Some(stage) = stage.downcast_ref::<SystemStage>() {
+                    stage.graph(label, world)
+                } else {
+                    StageGraph {
+                        label,
+                        schedule: stage
+                            .downcast_ref::<Schedule>()
+                            .map(|schedule| schedule.graph(world)),
+                        ..Default::default()
+                    }
+                }
+            })
+            .collect();
+        ScheduleGraph { stages }
+    }
+}
+
+impl ScheduleGraph {
+    /// Writes the graph in the DOT format of Graphviz. Each stage is a cluster of its systems,
+    /// exclusive systems are drawn in bold, and ambiguities and unknown labels in red.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph schedule {{").unwrap();
+        writeln!(dot, "    node [shape = box];").unwrap();
+        write_dot_stages(&mut dot, self, "stage", 1);
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
+
+fn write_dot_stages(dot: &mut String, schedule: &ScheduleGraph, prefix: &str, depth: usize) {
+    let indent = "    ".repeat(depth);
+    for (stage_index, stage) in schedule.stages.iter().enumerate() {
+        let id = format!("{}_{}", prefix, stage_index);
+        writeln!(dot, "{}subgraph cluster_{} {{", indent, id).unwrap();
+        writeln!(dot, "{}    label = \"{}\";", indent, escape(&stage.label)).unwrap();
+        for (index, system) in stage.systems.iter().enumerate() {
+            let style = if system.kind == SystemKind::Parallel {
+                ""
+            } else {
+                ", style = bold"
+            };
+            writeln!(
+                dot,
+                "{}    {}_{} [label = \"{}\"{}];",
+                indent,
+                id,
+                index,
+                escape(&system.name),
+                style
+            )
+            .unwrap();
+        }
+        for (index, flush_point) in stage.flush_points.iter().enumerate() {
+            writeln!(
+                dot,
+                "{}    {}_flush_{} [label = \"flush {}\", shape = diamond];",
+                indent,
+                id,
+                index,
+                escape(flush_point)
+            )
+            .unwrap();
+        }
+        for (index, system) in stage.systems.iter().enumerate() {
+            for dependency in &system.dependencies {
+                writeln!(
+                    dot,
+                    "{}    {}_{} -> {}_{};",
+                    indent, id, dependency, id, index
+                )
+                .unwrap();
+            }
+            for (flush_index, flush_point) in stage.flush_points.iter().enumerate() {
+                if system.before.contains(flush_point) {
+                    writeln!(
+                        dot,
+                        "{}    {}_{} -> {}_flush_{};",
+                        indent, id, index, id, flush_index
+                    )
+                    .unwrap();
+                }
+                if system.after.contains(flush_point) {
+                    writeln!(
+                        dot,
+                        "{}    {}_flush_{} -> {}_{};",
+                        indent, id, flush_index, id, index
+                    )
+                    .unwrap();
+                }
+            }
+            for (label_index, label) in system.unknown_labels.iter().enumerate() {
+                writeln!(
+                    dot,
+                    "{}    {}_{}_unknown_{} [label = \"unknown label {}\", color = red];",
+                    indent,
+                    id,
+                    index,
+                    label_index,
+                    escape(label)
+                )
+                .unwrap();
+                writeln!(
+                    dot,
+                    "{}    {}_{} -> {}_{}_unknown_{} [color = red, style = dashed];",
+                    indent, id, index, id, index, label_index
+                )
+                .unwrap();
+            }
+        }
+        for ambiguity in &stage.ambiguities {
+            writeln!(
+                dot,
+                "{}    {}_{} -> {}_{} [dir = none, color = red, style = dashed, label = \"{}\"];",
+                indent,
+                id,
+                ambiguity.system_a,
+                id,
+                ambiguity.system_b,
+                escape(&ambiguity.conflicts.join(", "))
+            )
+            .unwrap();
+        }
+        if let Some(schedule) = &stage.schedule {
+            write_dot_stages(dot, schedule, &id, depth + 1);
+        }
+        writeln!(dot, "{}}}", indent).unwrap();
+    }
+}
+
+fn escape(string: &str) -> String {
+    string.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        component::Component,
+        schedule::{
+            ExclusiveSystemDescriptorCoercion, ParallelSystemDescriptorCoercion, Schedule, Stage,
+            SystemKind, SystemNode, SystemStage,
+        },
+        system::{IntoExclusiveSystem, Query},
+        world::World,
+    };
+
+    use crate as bevy_ecs;
+    #[derive(Component)]
+    struct A;
+
+    fn read(_: Query<&A>) {}
+    fn write(_: Query<&mut A>) {}
+    fn exclusive(_: &mut World) {}
+
+    #[test]
+    fn schedule_graph() {
+        let mut world = World::new();
+        let mut schedule = Schedule::default()
+            .with_stage(
+                "update",
+                SystemStage::parallel()
+                    .with_system(write.label("write"))
+                    .with_system(read.after("write"))
+                    .with_system(read.before("typo"))
+                    .with_system(exclusive.exclusive_system().at_start()),
+            )
+            .with_stage("startup", Schedule::default());
+        schedule.run(&mut world);
+
+        let graph = schedule.graph(&world);
+        assert_eq!(graph.stages.len(), 2);
+        let stage = &graph.stages[0];
+        assert_eq!(stage.label, "\"update\"");
+        assert_eq!(stage.systems.len(), 4);
+        assert_eq!(stage.systems[0].kind, SystemKind::ExclusiveAtStart);
+
+        let position =
+            |predicate: fn(&SystemNode) -> bool| stage.systems.iter().position(predicate).unwrap();
+        let write_index = position(|system| system.labels.contains(&"\"write\"".to_string()));
+        let after_index = position(|system| system.after == ["\"write\""]);
+        let typo_index = position(|system| system.before == ["\"typo\""]);
+        assert_eq!(stage.systems[after_index].dependencies, vec![write_index]);
+        assert_eq!(stage.systems[typo_index].unknown_labels, vec!["\"typo\""]);
+        assert_eq!(stage.ambiguities.len(), 1);
+        let ambiguity = &stage.ambiguities[0];
+        let mut pair = [ambiguity.system_a, ambiguity.system_b];
+        pair.sort_unstable();
+        let mut expected = [write_index, typo_index];
+        expected.sort_unstable();
+        assert_eq!(pair, expected);
+        assert!(graph.stages[1].schedule.is_some());
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph schedule {"));
+        assert!(dot.contains(&format!(
+            "stage_0_{} -> stage_0_{};",
+            write_index, after_index
+        )));
+    }
+}
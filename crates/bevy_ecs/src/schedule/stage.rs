@@ -3,15 +3,16 @@ use crate::{
     prelude::IntoSystem,
     schedule::{
         graph_utils::{self, DependencyGraphError},
-        BoxedRunCriteria, BoxedRunCriteriaLabel, BoxedSystemLabel, DuplicateLabelStrategy,
-        ExclusiveSystemContainer, GraphNode, InsertionPoint, ParallelExecutor,
-        ParallelSystemContainer, ParallelSystemExecutor, RunCriteriaContainer,
+        Ambiguity, BoxedRunCriteria, BoxedRunCriteriaLabel, BoxedSystemLabel,
+        DuplicateLabelStrategy, ExclusiveSystemContainer, GraphNode, InsertionPoint,
+        ParallelExecutor, ParallelSystemContainer, ParallelSystemExecutor, RunCriteriaContainer,
         RunCriteriaDescriptor, RunCriteriaDescriptorOrLabel, RunCriteriaInner, ShouldRun,
-        SingleThreadedExecutor, SystemContainer, SystemDescriptor, SystemSet,
+        SingleThreadedExecutor, StageGraph, StageLabel, Step, SystemContainer, SystemDescriptor,
+        SystemKind, SystemLabel, SystemNode, SystemSet, SystemTimings,
     },
     world::{World, WorldId},
 };
-use bevy_utils::{tracing::info, HashMap, HashSet};
+use bevy_utils::{tracing::info, HashMap, HashSet, Instant};
 use downcast_rs::{impl_downcast, Downcast};
 use fixedbitset::FixedBitSet;
 use std::fmt::Debug;
@@ -68,6 +69,8 @@ pub struct SystemStage {
     exclusive_at_end: Vec<ExclusiveSystemContainer>,
     /// Topologically sorted parallel systems.
     parallel: Vec<ParallelSystemContainer>,
+    /// Labels of the points between parallel systems where their command buffers are applied.
+    flush_points: Vec<BoxedSystemLabel>,
     /// Determines if the stage was modified and needs to rebuild its graphs and orders.
     systems_modified: bool,
     /// Determines if the stage's executor was changed.
@@ -84,6 +87,12 @@ pub struct SystemStage {
     uninitialized_parallel: Vec<usize>,
     /// Saves the value of the World change_tick during the last tick check
     last_tick_check: u32,
+    /// Parallel systems run by [`Stepping`](super::Stepping) whose command buffers weren't
+    /// applied yet.
+    stepped_parallel: FixedBitSet,
+    /// Picks the order of systems that aren't ordered against each other, if the stage is
+    /// deterministic.
+    deterministic_seed: Option<u64>,
 }
 
 impl SystemStage {
@@ -98,6 +107,7 @@ impl SystemStage {
             exclusive_before_commands: Default::default(),
             exclusive_at_end: Default::default(),
             parallel: vec![],
+            flush_points: vec![],
             systems_modified: true,
             executor_modified: true,
             uninitialized_parallel: vec![],
@@ -105,6 +115,8 @@ impl SystemStage {
             uninitialized_before_commands: vec![],
             uninitialized_at_end: vec![],
             last_tick_check: Default::default(),
+            stepped_parallel: Default::default(),
+            deterministic_seed: None,
         }
     }
 
@@ -120,6 +132,34 @@ impl SystemStage {
         Self::new(Box::new(ParallelExecutor::default()))
     }
 
+    /// Creates a stage that runs its systems one at a time, in the same order on every run. See
+    /// [`SystemStage::set_deterministic`].
+    pub fn deterministic(seed: u64) -> Self {
+        let mut stage = Self::single_threaded();
+        stage.deterministic_seed = Some(seed);
+        stage
+    }
+
+    /// Makes the stage run its systems one at a time, in the same order on every run, so that
+    /// lockstep multiplayer games and replays get identical results from identical inputs. This
+    /// replaces the executor of the stage with a [`SingleThreadedExecutor`].
+    ///
+    /// Systems that aren't ordered against each other run in an order picked from `seed` and from
+    /// the order the systems were added in. Running tests with several seeds can show whether
+    /// the results depend on an order that wasn't specified.
+    pub fn set_deterministic(&mut self, seed: u64) -> &mut Self {
+        self.set_executor(Box::new(SingleThreadedExecutor::default()));
+        self.deterministic_seed = Some(seed);
+        self.systems_modified = true;
+        self
+    }
+
+    /// The seed of the system order of a deterministic stage, see
+    /// [`SystemStage::set_deterministic`].
+    pub fn deterministic_seed(&self) -> Option<u64> {
+        self.deterministic_seed
+    }
+
     pub fn get_executor<T: ParallelSystemExecutor>(&self) -> Option<&T> {
         self.executor.downcast_ref()
     }
@@ -204,6 +244,37 @@ impl SystemStage {
         }
     }
 
+    /// Adds a point labelled `label` between parallel systems, where the command buffers of the
+    /// parallel systems that ran before it are applied. Parallel systems are placed around it
+    /// with `.before(label)` and `.after(label)`, so systems running after it see the entities
+    /// and components their dependencies added with [`Commands`](crate::system::Commands) in the
+    /// same frame. Flush points run in the order they are added.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # fn spawn_enemies() {}
+    /// # fn configure_enemies() {}
+    /// let mut stage = SystemStage::parallel();
+    /// stage
+    ///     .add_flush_point("enemies_spawned")
+    ///     .add_system(spawn_enemies.before("enemies_spawned"))
+    ///     .add_system(configure_enemies.after("enemies_spawned"));
+    /// ```
+    pub fn add_flush_point(&mut self, label: impl SystemLabel) -> &mut Self {
+        let label: BoxedSystemLabel = Box::new(label);
+        if self.flush_points.contains(&label) {
+            panic!("Flush point {:?} was already added to the stage.", label);
+        }
+        self.systems_modified = true;
+        self.flush_points.push(label);
+        self
+    }
+
+    pub fn with_flush_point(mut self, label: impl SystemLabel) -> Self {
+        self.add_flush_point(label);
+        self
+    }
+
     /// Topologically sorted parallel systems.
     ///
     /// Note that systems won't be fully-formed until the stage has been run at least once.
@@ -449,25 +520,115 @@ impl SystemStage {
             "run criteria",
         );
         unwrap_dependency_cycle_error(
-            process_systems(&mut self.parallel, &run_criteria_labels),
+            process_systems(
+                &mut self.parallel,
+                &run_criteria_labels,
+                &self.flush_points,
+                self.deterministic_seed,
+            ),
             &self.parallel,
             "parallel systems",
         );
         unwrap_dependency_cycle_error(
-            process_systems(&mut self.exclusive_at_start, &run_criteria_labels),
+            process_systems(
+                &mut self.exclusive_at_start,
+                &run_criteria_labels,
+                &[],
+                self.deterministic_seed,
+            ),
             &self.exclusive_at_start,
             "exclusive systems at start of stage",
         );
         unwrap_dependency_cycle_error(
-            process_systems(&mut self.exclusive_before_commands, &run_criteria_labels),
+            process_systems(
+                &mut self.exclusive_before_commands,
+                &run_criteria_labels,
+                &[],
+                self.deterministic_seed,
+            ),
             &self.exclusive_before_commands,
             "exclusive systems before commands of stage",
         );
         unwrap_dependency_cycle_error(
-            process_systems(&mut self.exclusive_at_end, &run_criteria_labels),
+            process_systems(
+                &mut self.exclusive_at_end,
+                &run_criteria_labels,
+                &[],
+                self.deterministic_seed,
+            ),
             &self.exclusive_at_end,
             "exclusive systems at end of stage",
         );
+        self.assign_flush_segments();
+    }
+
+    /// Finds the flush points each parallel system runs after, and sorts parallel systems by
+    /// flush segment. Parallel systems must be topologically sorted.
+    fn assign_flush_segments(&mut self) {
+        let flush_points = &self.flush_points;
+        let flush_point_index =
+            |label: &BoxedSystemLabel| flush_points.iter().position(|point| point == label);
+        for index in 0..self.parallel.len() {
+            let container = &self.parallel[index];
+            // dependencies come first in topological order, so their segment is already known
+            let segment = container
+                .after()
+                .iter()
+                .filter_map(|label| flush_point_index(label).map(|point| point + 1))
+                .chain(
+                    container
+                        .dependencies()
+                        .iter()
+                        .map(|&dependency| self.parallel[dependency].flush_segment),
+                )
+                .max()
+                .unwrap_or(0);
+            if let Some(label) = container
+                .before()
+                .iter()
+                .find(|&label| matches!(flush_point_index(label), Some(point) if point < segment))
+            {
+                panic!(
+                    "{} wants to be before the flush point {:?}, but is also ordered after it.",
+                    container.name(),
+                    label
+                );
+            }
+            self.parallel[index].flush_segment = segment;
+        }
+
+        if flush_points.is_empty() {
+            return;
+        }
+        // A stable sort keeps the order topological, as no system is in an earlier segment than
+        // its dependencies.
+        let mut order = (0..self.parallel.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&index| self.parallel[index].flush_segment);
+        let mut order_inverted = vec![0; order.len()];
+        for (new_index, &index) in order.iter().enumerate() {
+            order_inverted[index] = new_index;
+        }
+        let mut temp = self.parallel.drain(..).map(Some).collect::<Vec<_>>();
+        for index in order {
+            let mut container = temp[index].take().unwrap();
+            let dependencies = container
+                .dependencies()
+                .iter()
+                .map(|&dependency| order_inverted[dependency])
+                .collect::<Vec<_>>();
+            container.set_dependencies(dependencies);
+            self.parallel.push(container);
+        }
+    }
+
+    /// Finds the execution order ambiguities between parallel systems. System orders must be fresh.
+    fn parallel_ambiguities(&self) -> Vec<(usize, usize, Vec<ComponentId>)> {
+        let mut ambiguities = find_ambiguities(&self.parallel);
+        // systems on both sides of a flush point are ordered by it
+        ambiguities.retain(|(index_a, index_b, _)| {
+            self.parallel[*index_a].flush_segment == self.parallel[*index_b].flush_segment
+        });
+        ambiguities
     }
 
     /// Logs execution order ambiguities between systems. System orders must be fresh.
@@ -497,7 +658,7 @@ impl SystemStage {
                 }
             }
         }
-        let parallel = find_ambiguities(&self.parallel);
+        let parallel = self.parallel_ambiguities();
         let at_start = find_ambiguities(&self.exclusive_at_start);
         let before_commands = find_ambiguities(&self.exclusive_before_commands);
         let at_end = find_ambiguities(&self.exclusive_at_end);
@@ -539,6 +700,52 @@ impl SystemStage {
         }
     }
 
+    /// Describes the systems of the stage for [`Schedule::graph`](super::Schedule::graph).
+    pub(super) fn graph(&self, label: String, world: &World) -> StageGraph {
+        let mut graph = StageGraph {
+            label,
+            flush_points: self
+                .flush_points
+                .iter()
+                .map(|label| format!("{:?}", label))
+                .collect(),
+            ..Default::default()
+        };
+        add_systems_to_graph(
+            &mut graph,
+            &self.exclusive_at_start,
+            SystemKind::ExclusiveAtStart,
+            find_ambiguities(&self.exclusive_at_start),
+            &[],
+            world,
+        );
+        add_systems_to_graph(
+            &mut graph,
+            &self.parallel,
+            SystemKind::Parallel,
+            self.parallel_ambiguities(),
+            &self.flush_points,
+            world,
+        );
+        add_systems_to_graph(
+            &mut graph,
+            &self.exclusive_before_commands,
+            SystemKind::ExclusiveBeforeCommands,
+            find_ambiguities(&self.exclusive_before_commands),
+            &[],
+            world,
+        );
+        add_systems_to_graph(
+            &mut graph,
+            &self.exclusive_at_end,
+            SystemKind::ExclusiveAtEnd,
+            find_ambiguities(&self.exclusive_at_end),
+            &[],
+            world,
+        );
+        graph
+    }
+
     /// Checks for old component and system change ticks
     fn check_change_ticks(&mut self, world: &mut World) {
         let change_tick = world.change_tick();
@@ -578,8 +785,8 @@ impl SystemStage {
         HashMap<BoxedRunCriteriaLabel, usize>,
         DependencyGraphError<HashSet<BoxedRunCriteriaLabel>>,
     > {
-        let graph = graph_utils::build_dependency_graph(&self.run_criteria);
-        let order = graph_utils::topological_order(&graph)?;
+        let graph = graph_utils::build_dependency_graph(&self.run_criteria, &[]);
+        let order = topological_order(&graph, self.deterministic_seed)?;
         let mut order_inverted = order.iter().enumerate().collect::<Vec<_>>();
         order_inverted.sort_unstable_by_key(|(_, &key)| key);
         let labels: HashMap<_, _> = self
@@ -634,9 +841,11 @@ impl SystemStage {
 fn process_systems(
     systems: &mut Vec<impl SystemContainer>,
     run_criteria_labels: &HashMap<BoxedRunCriteriaLabel, usize>,
+    flush_points: &[BoxedSystemLabel],
+    deterministic_seed: Option<u64>,
 ) -> Result<(), DependencyGraphError<HashSet<BoxedSystemLabel>>> {
-    let mut graph = graph_utils::build_dependency_graph(systems);
-    let order = graph_utils::topological_order(&graph)?;
+    let mut graph = graph_utils::build_dependency_graph(systems, flush_points);
+    let order = topological_order(&graph, deterministic_seed)?;
     let mut order_inverted = order.iter().enumerate().collect::<Vec<_>>();
     order_inverted.sort_unstable_by_key(|(_, &key)| key);
     for (index, container) in systems.iter_mut().enumerate() {
@@ -662,6 +871,73 @@ fn process_systems(
     Ok(())
 }
 
+fn topological_order<Labels: Clone>(
+    graph: &HashMap<usize, HashMap<usize, Labels>>,
+    deterministic_seed: Option<u64>,
+) -> Result<Vec<usize>, DependencyGraphError<Labels>> {
+    match deterministic_seed {
+        Some(seed) => graph_utils::seeded_topological_order(graph, seed),
+        None => graph_utils::topological_order(graph),
+    }
+}
+
+/// Adds `systems` and their `ambiguities` to `graph`. Systems can be ordered against the labels of
+/// the other `systems` and against `flush_points`.
+fn add_systems_to_graph(
+    graph: &mut StageGraph,
+    systems: &[impl SystemContainer],
+    kind: SystemKind,
+    ambiguities: Vec<(usize, usize, Vec<ComponentId>)>,
+    flush_points: &[BoxedSystemLabel],
+    world: &World,
+) {
+    fn debug_strings(labels: &[BoxedSystemLabel]) -> Vec<String> {
+        labels.iter().map(|label| format!("{:?}", label)).collect()
+    }
+    let offset = graph.systems.len();
+    let known_labels = systems
+        .iter()
+        .flat_map(|container| container.labels())
+        .chain(flush_points)
+        .collect::<HashSet<_>>();
+    for container in systems {
+        graph.systems.push(SystemNode {
+            name: container.name().into_owned(),
+            kind,
+            labels: debug_strings(container.labels()),
+            before: debug_strings(container.before()),
+            after: debug_strings(container.after()),
+            run_criteria: container
+                .run_criteria_label()
+                .map(|label| format!("{:?}", label)),
+            dependencies: container
+                .dependencies()
+                .iter()
+                .map(|dependency| dependency + offset)
+                .collect(),
+            unknown_labels: container
+                .before()
+                .iter()
+                .chain(container.after())
+                .filter(|label| !known_labels.contains(label))
+                .map(|label| format!("{:?}", label))
+                .collect(),
+        });
+    }
+    graph.ambiguities.extend(
+        ambiguities
+            .into_iter()
+            .map(|(index_a, index_b, conflicts)| Ambiguity {
+                system_a: index_a + offset,
+                system_b: index_b + offset,
+                conflicts: conflicts
+                    .iter()
+                    .map(|id| world.components().get_info(*id).unwrap().name().to_owned())
+                    .collect(),
+            }),
+    );
+}
+
 /// Returns vector containing all pairs of indices of systems with ambiguous execution order,
 /// along with specific components that have triggered the warning.
 /// Systems must be topologically sorted beforehand.
@@ -738,8 +1014,9 @@ fn find_ambiguities(systems: &[impl SystemContainer]) -> Vec<(usize, usize, Vec<
     ambiguities
 }
 
-impl Stage for SystemStage {
-    fn run(&mut self, world: &mut World) {
+impl SystemStage {
+    /// Initializes new systems and rebuilds the system orders if they changed.
+    fn prepare_run(&mut self, world: &mut World) {
         if let Some(world_id) = self.world_id {
             assert!(
                 world.id() == world_id,
@@ -762,11 +1039,173 @@ impl Stage for SystemStage {
             self.executor.rebuild_cached_data(&self.parallel);
             self.executor_modified = false;
         }
+    }
+
+    /// Evaluates the run criteria of systems, before running the first loop over the systems.
+    fn evaluate_run_criteria(&mut self, world: &mut World) {
+        for index in 0..self.run_criteria.len() {
+            let (run_criteria, tail) = self.run_criteria.split_at_mut(index);
+            let mut criteria = &mut tail[0];
+            criteria.update_archetypes(world);
+            match &mut criteria.inner {
+                RunCriteriaInner::Single(system) => criteria.should_run = system.run((), world),
+                RunCriteriaInner::Piped {
+                    input: parent,
+                    system,
+                    ..
+                } => criteria.should_run = system.run(run_criteria[*parent].should_run, world),
+            }
+        }
+    }
+
+    /// The number of systems in the stage, which are stepped through in execution order by
+    /// [`Stepping`](super::Stepping): exclusive systems at the start of the stage, parallel
+    /// systems, exclusive systems before commands and exclusive systems at the end of the stage.
+    pub(crate) fn step_system_count(&self) -> usize {
+        self.exclusive_at_start.len()
+            + self.parallel.len()
+            + self.exclusive_before_commands.len()
+            + self.exclusive_at_end.len()
+    }
+
+    /// Runs `step` from the system at index `from`, for [`Stepping`](super::Stepping). System
+    /// run criteria are evaluated once, and the stage runs a single pass over its systems.
+    /// Returns the index of the next system to step, or `None` if the stage's run criteria didn't
+    /// let it run.
+    pub(crate) fn run_step(
+        &mut self,
+        world: &mut World,
+        label: &dyn StageLabel,
+        from: usize,
+        step: Step,
+    ) -> Option<usize> {
+        self.prepare_run(world);
+        if let ShouldRun::No | ShouldRun::NoAndCheckAgain =
+            self.stage_run_criteria.should_run(world)
+        {
+            return None;
+        }
+        self.evaluate_run_criteria(world);
+
+        let system_count = self.step_system_count();
+        let to = match step {
+            Step::System => (from + 1).min(system_count),
+            Step::Stage => system_count,
+        };
+        let parallel_start = self.exclusive_at_start.len();
+        let before_commands_start = parallel_start + self.parallel.len();
+        let at_end_start = before_commands_start + self.exclusive_before_commands.len();
+        if from == 0 {
+            self.stepped_parallel.clear();
+        }
+        self.stepped_parallel.grow(self.parallel.len());
+
+        for index in from..to {
+            if index < parallel_start {
+                let container = &mut self.exclusive_at_start[index];
+                let ran = should_run(container, &self.run_criteria, ShouldRun::Yes);
+                log_step(label, &container.name(), ran);
+                if ran {
+                    run_exclusive_system(container, world);
+                }
+            } else if index < before_commands_start {
+                let parallel_index = index - parallel_start;
+                for (i, container) in self.parallel.iter_mut().enumerate() {
+                    container.should_run = i == parallel_index
+                        && should_run(container, &self.run_criteria, ShouldRun::Yes);
+                }
+                let container = &self.parallel[parallel_index];
+                let ran = container.should_run;
+                log_step(label, &container.name(), ran);
+                // the executor only runs the system that should run, after updating the
+                // archetypes of every system
+                self.executor.run_systems(&mut self.parallel, world);
+                self.stepped_parallel.set(parallel_index, ran);
+            } else if index < at_end_start {
+                let container = &mut self.exclusive_before_commands[index - before_commands_start];
+                let ran = should_run(container, &self.run_criteria, ShouldRun::Yes);
+                log_step(label, &container.name(), ran);
+                if ran {
+                    run_exclusive_system(container, world);
+                }
+            } else {
+                let container = &mut self.exclusive_at_end[index - at_end_start];
+                let ran = should_run(container, &self.run_criteria, ShouldRun::Yes);
+                log_step(label, &container.name(), ran);
+                if ran {
+                    run_exclusive_system(container, world);
+                }
+            }
+
+            // Apply the buffers of the parallel systems that were stepped through, at the same
+            // points as when running the whole stage.
+            let at_flush_point = index >= parallel_start
+                && index + 1 < before_commands_start
+                && self.parallel[index - parallel_start].flush_segment
+                    != self.parallel[index + 1 - parallel_start].flush_segment;
+            if at_flush_point || index + 1 == at_end_start {
+                for parallel_index in self.stepped_parallel.ones() {
+                    self.parallel[parallel_index]
+                        .system_mut()
+                        .apply_buffers(world);
+                }
+                self.stepped_parallel.clear();
+            }
+        }
+
+        self.check_change_ticks(world);
+        Some(to)
+    }
+}
+
+fn should_run(
+    container: &impl SystemContainer,
+    run_criteria: &[RunCriteriaContainer],
+    default: ShouldRun,
+) -> bool {
+    matches!(
+        container
+            .run_criteria()
+            .map(|index| run_criteria[index].should_run)
+            .unwrap_or(default),
+        ShouldRun::Yes | ShouldRun::YesAndCheckAgain
+    )
+}
+
+/// Runs an exclusive system, measuring its run time if [`SystemTimings`] are recorded.
+fn run_exclusive_system(container: &mut ExclusiveSystemContainer, world: &mut World) {
+    if !world.contains_resource::<SystemTimings>() {
+        container.system_mut().run(world);
+        return;
+    }
+    let start = Instant::now();
+    container.system_mut().run(world);
+    let duration = start.elapsed();
+    // the system may have removed the resource
+    if let Some(mut timings) = world.get_resource_mut::<SystemTimings>() {
+        timings.record(container.name(), duration);
+    }
+}
+
+fn log_step(label: &dyn StageLabel, system_name: &str, ran: bool) {
+    if ran {
+        info!("Stepping {:?}: running {}", label, system_name);
+    } else {
+        info!(
+            "Stepping {:?}: skipping {} because of its run criteria",
+            label, system_name
+        );
+    }
+}
+
+impl Stage for SystemStage {
+    fn run(&mut self, world: &mut World) {
+        self.prepare_run(world);
 
         let mut run_stage_loop = true;
         while run_stage_loop {
-            let should_run = self.stage_run_criteria.should_run(world);
-            match should_run {
+            let stage_should_run = self.stage_run_criteria.should_run(world);
+            match stage_should_run {
                 ShouldRun::No => return,
                 ShouldRun::NoAndCheckAgain => continue,
                 ShouldRun::YesAndCheckAgain => (),
@@ -776,58 +1215,43 @@ impl Stage for SystemStage {
             };
 
             // Evaluate system run criteria.
-            for index in 0..self.run_criteria.len() {
-                let (run_criteria, tail) = self.run_criteria.split_at_mut(index);
-                let mut criteria = &mut tail[0];
-                criteria.update_archetypes(world);
-                match &mut criteria.inner {
-                    RunCriteriaInner::Single(system) => criteria.should_run = system.run((), world),
-                    RunCriteriaInner::Piped {
-                        input: parent,
-                        system,
-                        ..
-                    } => criteria.should_run = system.run(run_criteria[*parent].should_run, world),
-                }
-            }
+            self.evaluate_run_criteria(world);
 
             let mut run_system_loop = true;
             let mut default_should_run = ShouldRun::Yes;
             while run_system_loop {
                 run_system_loop = false;
 
-                fn should_run(
-                    container: &impl SystemContainer,
-                    run_criteria: &[RunCriteriaContainer],
-                    default: ShouldRun,
-                ) -> bool {
-                    matches!(
-                        container
-                            .run_criteria()
-                            .map(|index| run_criteria[index].should_run)
-                            .unwrap_or(default),
-                        ShouldRun::Yes | ShouldRun::YesAndCheckAgain
-                    )
-                }
-
                 // Run systems that want to be at the start of stage.
                 for container in &mut self.exclusive_at_start {
                     if should_run(container, &self.run_criteria, default_should_run) {
-                        container.system_mut().run(world);
+                        run_exclusive_system(container, world);
                     }
                 }
 
-                // Run parallel systems using the executor.
+                // Run parallel systems using the executor, one flush segment at a time. The
+                // buffers of the last segment are applied after exclusive systems before commands.
                 // TODO: hard dependencies, nested sets, whatever... should be evaluated here.
-                for container in &mut self.parallel {
-                    container.should_run =
-                        should_run(container, &self.run_criteria, default_should_run);
+                let last_segment = self.flush_points.len();
+                for segment in 0..=last_segment {
+                    for container in &mut self.parallel {
+                        container.should_run = container.flush_segment == segment
+                            && should_run(container, &self.run_criteria, default_should_run);
+                    }
+                    self.executor.run_systems(&mut self.parallel, world);
+                    if segment < last_segment {
+                        for container in &mut self.parallel {
+                            if container.should_run {
+                                container.system_mut().apply_buffers(world);
+                            }
+                        }
+                    }
                 }
-                self.executor.run_systems(&mut self.parallel, world);
 
                 // Run systems that want to be between parallel systems and their command buffers.
                 for container in &mut self.exclusive_before_commands {
                     if should_run(container, &self.run_criteria, default_should_run) {
-                        container.system_mut().run(world);
+                        run_exclusive_system(container, world);
                     }
                 }
 
@@ -841,7 +1265,7 @@ impl Stage for SystemStage {
                 // Run systems that want to be at the end of stage.
                 for container in &mut self.exclusive_at_end {
                     if should_run(container, &self.run_criteria, default_should_run) {
-                        container.system_mut().run(world);
+                        run_exclusive_system(container, world);
                     }
                 }
 
@@ -901,7 +1325,7 @@ mod tests {
             RunCriteria, RunCriteriaDescriptorCoercion, RunCriteriaPiping, ShouldRun,
             SingleThreadedExecutor, Stage, SystemSet, SystemStage,
         },
-        system::{In, IntoExclusiveSystem, IntoSystem, Local, Query, ResMut},
+        system::{AsSystemLabel, In, IntoExclusiveSystem, IntoSystem, Local, Query, ResMut},
         world::World,
     };
 
@@ -1323,6 +1747,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parallel_generic_systems() {
+        fn push_size<T>(mut resource: ResMut<Vec<usize>>) {
+            resource.push(std::mem::size_of::<T>());
+        }
+
+        let mut world = World::new();
+        world.insert_resource(Vec::<usize>::new());
+        let mut stage = SystemStage::parallel()
+            .with_system(push_size::<u16>.after(push_size::<u32>.as_system_label()))
+            .with_system(push_size::<u32>.after(push_size::<u8>.as_system_label()))
+            .with_system(push_size::<u8>);
+        stage.run(&mut world);
+        stage.set_executor(Box::new(SingleThreadedExecutor::default()));
+        stage.run(&mut world);
+        assert_eq!(
+            *world.get_resource::<Vec<usize>>().unwrap(),
+            vec![1, 4, 2, 1, 4, 2]
+        );
+    }
+
+    #[test]
+    fn deterministic_order() {
+        fn run_order(seed: u64) -> Vec<usize> {
+            let mut world = World::new();
+            world.insert_resource(Vec::<usize>::new());
+            let mut stage = SystemStage::deterministic(seed);
+            for tag in 0..8 {
+                stage.add_system(make_parallel(tag));
+            }
+            stage.add_system(make_parallel(8).label("8"));
+            stage.add_system(make_parallel(9).before("8"));
+            stage.run(&mut world);
+            stage.run(&mut world);
+            world.get_resource::<Vec<usize>>().unwrap().clone()
+        }
+
+        let order = run_order(1);
+        assert_eq!(order.len(), 20);
+        assert_eq!(order[..10], order[10..]);
+        let position = |tag| order.iter().position(|&other| other == tag).unwrap();
+        assert!(position(9) < position(8));
+        assert_eq!(run_order(1), order);
+        // other seeds pick another order for the systems that aren't ordered against each other
+        assert!((2..10).any(|seed| run_order(seed) != order));
+    }
+
     #[test]
     fn parallel_redundant_constraints() {
         let mut world = World::new();
@@ -1558,6 +2029,54 @@ mod tests {
         stage.run(&mut world);
     }
 
+    #[test]
+    fn flush_points() {
+        fn spawn(mut commands: crate::prelude::Commands) {
+            commands.spawn().insert(W(0usize));
+        }
+        fn count(query: Query<&W<usize>>, mut counts: ResMut<Vec<usize>>) {
+            counts.push(query.iter().count());
+        }
+
+        let mut world = World::new();
+        world.insert_resource(Vec::<usize>::new());
+        let mut stage = SystemStage::parallel()
+            .with_flush_point("flush")
+            .with_system(count.label("count").after("flush"))
+            .with_system(spawn.before("flush"))
+            .with_system(make_parallel(0).after("count"));
+        stage.run(&mut world);
+        assert_eq!(*world.get_resource::<Vec<usize>>().unwrap(), vec![1, 0]);
+        stage.run(&mut world);
+        assert_eq!(
+            *world.get_resource::<Vec<usize>>().unwrap(),
+            vec![1, 0, 2, 0]
+        );
+
+        // systems ordered after a system running after a flush point run after it as well
+        let mut world = World::new();
+        world.insert_resource(Vec::<usize>::new());
+        let mut stage = SystemStage::single_threaded()
+            .with_flush_point("flush")
+            .with_system(count.after("spawn"))
+            .with_system(make_parallel(0).label("0").after("flush"))
+            .with_system(spawn.label("spawn").after("0"));
+        stage.run(&mut world);
+        assert_eq!(*world.get_resource::<Vec<usize>>().unwrap(), vec![0, 0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn flush_point_conflict() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<usize>::new());
+        let mut stage = SystemStage::parallel()
+            .with_flush_point("flush")
+            .with_system(make_parallel(0).label("0").after("flush"))
+            .with_system(make_parallel(1).after("0").before("flush"));
+        stage.run(&mut world);
+    }
+
     #[test]
     fn ambiguity_detection() {
         use super::{find_ambiguities, SystemContainer};
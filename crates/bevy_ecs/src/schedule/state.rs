@@ -95,6 +95,7 @@ impl<T> State<T>
 where
     T: StateData,
 {
+    /// Runs while `s` is the current state.
     pub fn on_update(s: T) -> RunCriteriaDescriptor {
         (|state: Res<State<T>>, pred: Local<Option<T>>| {
             state.stack.last().unwrap() == pred.as_ref().unwrap() && state.transition.is_none()
@@ -105,58 +106,30 @@ where
         .label_discard_if_duplicate(StateCallback::Update.into_label(s))
     }
 
+    /// Runs while `s` is paused, i.e. while it is in the stack below the current state.
     pub fn on_inactive_update(s: T) -> RunCriteriaDescriptor {
-        (|state: Res<State<T>>, mut is_inactive: Local<bool>, pred: Local<Option<T>>| match &state
-            .transition
-        {
-            Some(StateTransition::Pausing(ref relevant, _))
-            | Some(StateTransition::Resuming(_, ref relevant)) => {
-                if relevant == pred.as_ref().unwrap() {
-                    *is_inactive = !*is_inactive;
-                }
-                false
-            }
-            Some(_) => false,
-            None => *is_inactive,
+        (|state: Res<State<T>>, pred: Local<Option<T>>| {
+            state.inactives().contains(pred.as_ref().unwrap()) && state.transition.is_none()
         })
-        .config(|(_, _, pred)| *pred = Some(Some(s.clone())))
+        .config(|(_, pred)| *pred = Some(Some(s.clone())))
         .chain(should_run_adapter::<T>)
         .after(DriverLabel::of::<T>())
         .label_discard_if_duplicate(StateCallback::InactiveUpdate.into_label(s))
     }
 
+    /// Runs while `s` is anywhere in the stack, whether it is the current state or paused.
     pub fn on_in_stack_update(s: T) -> RunCriteriaDescriptor {
-        (|state: Res<State<T>>, mut is_in_stack: Local<bool>, pred: Local<Option<T>>| match &state
-            .transition
-        {
-            Some(StateTransition::Entering(ref relevant, _))
-            | Some(StateTransition::ExitingToResume(_, ref relevant)) => {
-                if relevant == pred.as_ref().unwrap() {
-                    *is_in_stack = !*is_in_stack;
-                }
-                false
-            }
-            Some(StateTransition::ExitingFull(_, ref relevant)) => {
-                if relevant == pred.as_ref().unwrap() {
-                    *is_in_stack = !*is_in_stack;
-                }
-                false
-            }
-            Some(StateTransition::Startup) => {
-                if state.stack.last().unwrap() == pred.as_ref().unwrap() {
-                    *is_in_stack = !*is_in_stack;
-                }
-                false
-            }
-            Some(_) => false,
-            None => *is_in_stack,
+        (|state: Res<State<T>>, pred: Local<Option<T>>| {
+            state.stack.contains(pred.as_ref().unwrap()) && state.transition.is_none()
         })
-        .config(|(_, _, pred)| *pred = Some(Some(s.clone())))
+        .config(|(_, pred)| *pred = Some(Some(s.clone())))
         .chain(should_run_adapter::<T>)
         .after(DriverLabel::of::<T>())
         .label_discard_if_duplicate(StateCallback::InStackUpdate.into_label(s))
     }
 
+    /// Runs once when `s` becomes the current state, at startup or through a set, replace or
+    /// push.
     pub fn on_enter(s: T) -> RunCriteriaDescriptor {
         (|state: Res<State<T>>, pred: Local<Option<T>>| {
             state
@@ -176,6 +149,7 @@ where
         .label_discard_if_duplicate(StateCallback::Enter.into_label(s))
     }
 
+    /// Runs once when `s` is removed from the stack through a set, replace or pop.
     pub fn on_exit(s: T) -> RunCriteriaDescriptor {
         (|state: Res<State<T>>, pred: Local<Option<T>>| {
             state
@@ -193,6 +167,7 @@ where
         .label_discard_if_duplicate(StateCallback::Exit.into_label(s))
     }
 
+    /// Runs once when another state is pushed on top of `s`.
     pub fn on_pause(s: T) -> RunCriteriaDescriptor {
         (|state: Res<State<T>>, pred: Local<Option<T>>| {
             state
@@ -209,6 +184,8 @@ where
         .label_discard_if_duplicate(StateCallback::Pause.into_label(s))
     }
 
+    /// Runs once when the state on top of `s` is popped. The [Self::on_enter] systems of `s` don't
+    /// run again.
     pub fn on_resume(s: T) -> RunCriteriaDescriptor {
         (|state: Res<State<T>>, pred: Local<Option<T>>| {
             state
@@ -233,6 +210,10 @@ where
         SystemSet::new().with_run_criteria(Self::on_inactive_update(s))
     }
 
+    pub fn on_in_stack_update_set(s: T) -> SystemSet {
+        SystemSet::new().with_run_criteria(Self::on_in_stack_update(s))
+    }
+
     pub fn on_enter_set(s: T) -> SystemSet {
         SystemSet::new().with_run_criteria(Self::on_enter(s))
     }
@@ -378,6 +359,20 @@ where
     }
 }
 
+/// A run criteria that runs while `state` is the current state, which can be combined with other
+/// criteria through [`RunCriteriaCombinators`](super::RunCriteriaCombinators). Unlike
+/// [`State::on_update`], it isn't evaluated again when the state changes during the stage running
+/// the state driver, so it's best used in the other stages.
+pub fn in_state<T: StateData>(state: T) -> impl FnMut(Res<State<T>>) -> ShouldRun {
+    move |current: Res<State<T>>| {
+        if current.current() == &state && current.transition.is_none() {
+            ShouldRun::Yes
+        } else {
+            ShouldRun::No
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum StateError {
     #[error("Attempted to change the state to the current state.")]
@@ -627,6 +622,131 @@ mod test {
         );
     }
 
+    #[test]
+    fn push_pop() {
+        #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+        enum GameState {
+            Playing,
+            Paused,
+        }
+
+        let mut world = World::default();
+        world.insert_resource(Vec::<&'static str>::new());
+        world.insert_resource(State::new(GameState::Playing));
+
+        let mut stage = SystemStage::parallel();
+        stage.add_system_set(State::<GameState>::get_driver());
+        stage
+            .add_system_set(
+                State::on_enter_set(GameState::Playing)
+                    .with_system(|mut r: ResMut<Vec<&'static str>>| r.push("enter playing")),
+            )
+            .add_system_set(
+                State::on_update_set(GameState::Playing)
+                    .with_system(|mut r: ResMut<Vec<&'static str>>| r.push("update playing")),
+            )
+            .add_system_set(
+                State::on_pause_set(GameState::Playing)
+                    .with_system(|mut r: ResMut<Vec<&'static str>>| r.push("pause playing")),
+            )
+            .add_system_set(
+                State::on_inactive_update_set(GameState::Playing)
+                    .with_system(|mut r: ResMut<Vec<&'static str>>| r.push("inactive playing")),
+            )
+            .add_system_set(
+                State::on_resume_set(GameState::Playing)
+                    .with_system(|mut r: ResMut<Vec<&'static str>>| r.push("resume playing")),
+            )
+            .add_system_set(
+                State::on_enter_set(GameState::Paused)
+                    .with_system(|mut r: ResMut<Vec<&'static str>>| r.push("enter paused")),
+            )
+            .add_system_set(
+                State::on_exit_set(GameState::Paused)
+                    .with_system(|mut r: ResMut<Vec<&'static str>>| r.push("exit paused")),
+            );
+
+        let mut run = |world: &mut World| {
+            stage.run(world);
+            let mut collected = world.get_resource_mut::<Vec<&'static str>>().unwrap();
+            collected.drain(..).collect::<Vec<_>>()
+        };
+
+        assert_eq!(run(&mut world), vec!["enter playing", "update playing"]);
+
+        world
+            .get_resource_mut::<State<GameState>>()
+            .unwrap()
+            .push(GameState::Paused)
+            .unwrap();
+        assert_eq!(
+            run(&mut world),
+            vec!["pause playing", "enter paused", "inactive playing"]
+        );
+        assert_eq!(run(&mut world), vec!["inactive playing"]);
+        let state = world.get_resource::<State<GameState>>().unwrap();
+        assert_eq!(state.current(), &GameState::Paused);
+        assert_eq!(state.inactives(), &[GameState::Playing]);
+
+        world
+            .get_resource_mut::<State<GameState>>()
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(
+            run(&mut world),
+            vec!["exit paused", "resume playing", "update playing"]
+        );
+        assert_eq!(
+            world.get_resource::<State<GameState>>().unwrap().current(),
+            &GameState::Playing
+        );
+    }
+
+    #[test]
+    fn in_stack() {
+        #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+        enum GameState {
+            Playing,
+            Paused,
+        }
+
+        let mut world = World::default();
+        world.insert_resource(0usize);
+        world.insert_resource(State::new(GameState::Playing));
+
+        let mut stage = SystemStage::parallel();
+        stage.add_system_set(State::<GameState>::get_driver());
+        stage.add_system_set(
+            State::on_in_stack_update_set(GameState::Playing)
+                .with_system(|mut count: ResMut<usize>| *count += 1),
+        );
+
+        stage.run(&mut world);
+        assert_eq!(*world.get_resource::<usize>().unwrap(), 1);
+
+        world
+            .get_resource_mut::<State<GameState>>()
+            .unwrap()
+            .push(GameState::Paused)
+            .unwrap();
+        stage.run(&mut world);
+        assert_eq!(*world.get_resource::<usize>().unwrap(), 2);
+
+        world
+            .get_resource_mut::<State<GameState>>()
+            .unwrap()
+            .set(GameState::Paused)
+            .unwrap_err();
+        world
+            .get_resource_mut::<State<GameState>>()
+            .unwrap()
+            .replace(GameState::Playing)
+            .unwrap();
+        stage.run(&mut world);
+        assert_eq!(*world.get_resource::<usize>().unwrap(), 3);
+    }
+
     #[test]
     fn issue_1753() {
         #[derive(Clone, PartialEq, Eq, Debug, Hash)]
@@ -0,0 +1,195 @@
+use crate::schedule::{BoxedStageLabel, StageLabel};
+
+/// A debugging resource that pauses [`SystemStage`](super::SystemStage)s and advances them one
+/// system, or one stage, at a time. Each system is logged as it runs, which helps with
+/// diagnosing system ordering bugs.
+///
+/// Only the stages added with [`Stepping::add_stage`] are stepped, in the order they were added,
+/// which should match the order they run in. Other stages keep running every frame, so input
+/// handling and rendering keep working while the stepped stages are paused.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::schedule::Stepping;
+/// # let mut world = World::new();
+/// let mut stepping = Stepping::default();
+/// stepping.add_stage("update").enable();
+/// world.insert_resource(stepping);
+///
+/// // later, for example when a key is pressed
+/// world.get_resource_mut::<Stepping>().unwrap().step_system();
+/// ```
+#[derive(Debug, Default)]
+pub struct Stepping {
+    enabled: bool,
+    stages: Vec<BoxedStageLabel>,
+    /// The index in `stages` of the stage the next step runs in
+    current_stage: usize,
+    /// The index of the next system to run in the current stage, in execution order
+    next_system: usize,
+    step: Option<Step>,
+}
+
+/// How far [`Stepping`] advances the stepped stages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// Runs the next system
+    System,
+    /// Runs the remaining systems of the current stage
+    Stage,
+}
+
+/// What a stage should run this frame according to [`Stepping`].
+pub(crate) enum StageStepping {
+    /// The stage isn't stepped and runs normally
+    NotStepped,
+    /// The stage is stepped, but isn't advanced this frame
+    Paused,
+    /// The stage runs `step`, starting with the system at index `from`
+    Step { from: usize, step: Step },
+}
+
+impl Stepping {
+    /// Adds a stage to step through. Stages are stepped in the order they are added.
+    pub fn add_stage(&mut self, label: impl StageLabel) -> &mut Self {
+        self.stages.push(Box::new(label));
+        self
+    }
+
+    /// Pauses the stepped stages. They then only run when a step is requested.
+    pub fn enable(&mut self) -> &mut Self {
+        self.enabled = true;
+        self
+    }
+
+    /// Resumes running the stepped stages every frame. When enabled again, stepping starts over
+    /// from the first system of the first stepped stage.
+    pub fn disable(&mut self) -> &mut Self {
+        self.enabled = false;
+        self.current_stage = 0;
+        self.next_system = 0;
+        self.step = None;
+        self
+    }
+
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Runs the next system of the stepped stages, the next time its stage runs.
+    pub fn step_system(&mut self) {
+        self.step = Some(Step::System);
+    }
+
+    /// Runs the remaining systems of the current stepped stage, the next time it runs.
+    pub fn step_stage(&mut self) {
+        self.step = Some(Step::Stage);
+    }
+
+    /// The step that will be run, if one was requested.
+    #[inline]
+    pub fn pending_step(&self) -> Option<Step> {
+        self.step
+    }
+
+    /// The stage and index of the system the next step will start with.
+    pub fn cursor(&self) -> Option<(&dyn StageLabel, usize)> {
+        self.stages
+            .get(self.current_stage)
+            .map(|label| (&**label, self.next_system))
+    }
+
+    pub(crate) fn stage_stepping(&self, label: &dyn StageLabel) -> StageStepping {
+        if !self.enabled || !self.stages.iter().any(|stage| **stage == *label) {
+            return StageStepping::NotStepped;
+        }
+        match self.step {
+            Some(step) if *self.stages[self.current_stage] == *label => StageStepping::Step {
+                from: self.next_system,
+                step,
+            },
+            _ => StageStepping::Paused,
+        }
+    }
+
+    /// Moves the cursor of the current stage to `next_system`, after a step ran. Moves on to the
+    /// next stage once all of the `system_count` systems of the current stage ran.
+    pub(crate) fn advance(&mut self, next_system: usize, system_count: usize) {
+        if next_system < system_count {
+            self.next_system = next_system;
+            self.step = None;
+            return;
+        }
+        self.next_system = 0;
+        self.current_stage = (self.current_stage + 1) % self.stages.len();
+        // a stage without systems doesn't consume the step
+        if system_count > 0 {
+            self.step = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schedule::{ParallelSystemDescriptorCoercion, Schedule, Stage, Stepping, SystemStage},
+        system::ResMut,
+        world::World,
+    };
+
+    fn first(mut ran: ResMut<Vec<&'static str>>) {
+        ran.push("first");
+    }
+
+    fn second(mut ran: ResMut<Vec<&'static str>>) {
+        ran.push("second");
+    }
+
+    fn unstepped(mut ran: ResMut<Vec<&'static str>>) {
+        ran.push("unstepped");
+    }
+
+    fn take_ran(world: &mut World) -> Vec<&'static str> {
+        std::mem::take(&mut *world.get_resource_mut::<Vec<&'static str>>().unwrap())
+    }
+
+    #[test]
+    fn step_systems_and_stages() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<&'static str>::new());
+        let mut stepping = Stepping::default();
+        stepping.add_stage("stepped").enable();
+        world.insert_resource(stepping);
+
+        let mut schedule = Schedule::default()
+            .with_stage(
+                "stepped",
+                SystemStage::single_threaded()
+                    .with_system(first.label("first"))
+                    .with_system(second.after("first")),
+            )
+            .with_stage("unstepped", SystemStage::single(unstepped));
+
+        schedule.run(&mut world);
+        assert_eq!(take_ran(&mut world), vec!["unstepped"]);
+
+        world.get_resource_mut::<Stepping>().unwrap().step_system();
+        schedule.run(&mut world);
+        assert_eq!(take_ran(&mut world), vec!["first", "unstepped"]);
+        schedule.run(&mut world);
+        assert_eq!(take_ran(&mut world), vec!["unstepped"]);
+
+        world.get_resource_mut::<Stepping>().unwrap().step_system();
+        schedule.run(&mut world);
+        assert_eq!(take_ran(&mut world), vec!["second", "unstepped"]);
+
+        world.get_resource_mut::<Stepping>().unwrap().step_stage();
+        schedule.run(&mut world);
+        assert_eq!(take_ran(&mut world), vec!["first", "second", "unstepped"]);
+
+        world.get_resource_mut::<Stepping>().unwrap().disable();
+        schedule.run(&mut world);
+        assert_eq!(take_ran(&mut world), vec!["first", "second", "unstepped"]);
+    }
+}
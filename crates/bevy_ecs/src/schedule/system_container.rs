@@ -110,6 +110,8 @@ pub struct ParallelSystemContainer {
     pub(crate) run_criteria_index: Option<usize>,
     pub(crate) run_criteria_label: Option<BoxedRunCriteriaLabel>,
     pub(crate) should_run: bool,
+    /// The number of flush points of the stage this system runs after.
+    pub(crate) flush_segment: usize,
     dependencies: Vec<usize>,
     labels: Vec<BoxedSystemLabel>,
     before: Vec<BoxedSystemLabel>,
@@ -122,14 +124,18 @@ unsafe impl Sync for ParallelSystemContainer {}
 
 impl ParallelSystemContainer {
     pub(crate) fn from_descriptor(descriptor: ParallelSystemDescriptor) -> Self {
+        // default labels go last, so the labels given by the user come first
+        let mut labels = descriptor.labels;
+        labels.extend(descriptor.system.default_labels());
         ParallelSystemContainer {
             // SAFE: it is fine to wrap inner value with UnsafeCell, as it is repr(transparent)
             system: unsafe { Box::from_raw(Box::into_raw(descriptor.system) as *mut _) },
             should_run: false,
+            flush_segment: 0,
             run_criteria_index: None,
             run_criteria_label: None,
             dependencies: Vec::new(),
-            labels: descriptor.labels,
+            labels,
             before: descriptor.before,
             after: descriptor.after,
             ambiguity_sets: descriptor.ambiguity_sets,
@@ -0,0 +1,99 @@
+use bevy_utils::Duration;
+use std::borrow::Cow;
+
+/// A resource that makes [`SystemStage`](super::SystemStage)s measure how long each of their
+/// systems takes to run. Stages only measure their systems while this resource exists, so
+/// measuring has no cost otherwise.
+///
+/// The measurements accumulate until they are taken with [`SystemTimings::drain`], usually once
+/// per frame by a diagnostics plugin.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::schedule::SystemTimings;
+/// # fn my_system() {}
+/// let mut world = World::new();
+/// world.insert_resource(SystemTimings::default());
+/// let mut stage = SystemStage::single_threaded().with_system(my_system);
+/// stage.run(&mut world);
+///
+/// let mut timings = world.get_resource_mut::<SystemTimings>().unwrap();
+/// for (name, duration) in timings.drain() {
+///     println!("{} took {:?}", name, duration);
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct SystemTimings {
+    timings: Vec<(Cow<'static, str>, Duration)>,
+}
+
+impl SystemTimings {
+    /// Records that the system named `name` ran for `duration`.
+    pub fn record(&mut self, name: Cow<'static, str>, duration: Duration) {
+        self.timings.push((name, duration));
+    }
+
+    /// The measurements recorded since the last call to [`SystemTimings::drain`], in the order
+    /// the systems finished running.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Duration)> {
+        self.timings
+            .iter()
+            .map(|(name, duration)| (&**name, *duration))
+    }
+
+    /// Takes the recorded measurements.
+    pub fn drain(&mut self) -> impl Iterator<Item = (Cow<'static, str>, Duration)> + '_ {
+        self.timings.drain(..)
+    }
+
+    pub fn len(&self) -> usize {
+        self.timings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timings.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schedule::{Stage, SystemStage, SystemTimings},
+        system::IntoExclusiveSystem,
+        world::World,
+    };
+
+    fn parallel() {}
+    fn exclusive(_: &mut World) {}
+
+    #[test]
+    fn record_system_timings() {
+        let mut world = World::new();
+        let mut stage = SystemStage::parallel()
+            .with_system(parallel)
+            .with_system(exclusive.exclusive_system());
+        stage.run(&mut world);
+
+        world.insert_resource(SystemTimings::default());
+        stage.run(&mut world);
+        stage.run(&mut world);
+        let mut timings = world.get_resource_mut::<SystemTimings>().unwrap();
+        assert_eq!(timings.len(), 4);
+        let names = timings.drain().map(|(name, _)| name).collect::<Vec<_>>();
+        assert_eq!(
+            names
+                .iter()
+                .filter(|name| name.ends_with("parallel"))
+                .count(),
+            2
+        );
+        assert_eq!(
+            names
+                .iter()
+                .filter(|name| name.ends_with("exclusive"))
+                .count(),
+            2
+        );
+        assert!(timings.is_empty());
+    }
+}
@@ -1,16 +1,25 @@
 mod command_queue;
 
 use crate::{
+    archetype::ArchetypeGeneration,
     bundle::Bundle,
     component::Component,
     entity::{Entities, Entity},
+    event::Events,
     world::World,
 };
-use bevy_utils::tracing::{error, warn};
+use bevy_utils::{
+    tracing::{error, warn},
+    HashMap,
+};
 pub use command_queue::CommandQueue;
-use std::marker::PhantomData;
+use std::{
+    any::{Any, TypeId},
+    marker::PhantomData,
+};
+use thiserror::Error;
 
-use super::Resource;
+use super::{IntoSystem, Resource, System};
 
 /// A [`World`] mutation.
 pub trait Command: Send + Sync + 'static {
@@ -260,6 +269,21 @@ impl<'w, 's> Commands<'w, 's> {
         self.queue.push(InsertOrSpawnBatch { bundles_iter });
     }
 
+    /// For a given batch of ([Entity], [Bundle]) pairs, inserts the [Bundle] into each existing
+    /// [Entity].
+    ///
+    /// This is faster than inserting the bundles one by one, see [`World::insert_batch`]. The
+    /// entities that don't exist anymore when the command is applied are skipped, and logged as an
+    /// error.
+    pub fn insert_batch<I, B>(&mut self, bundles_iter: I)
+    where
+        I: IntoIterator + Send + Sync + 'static,
+        I::IntoIter: Iterator<Item = (Entity, B)>,
+        B: Bundle,
+    {
+        self.queue.push(InsertBatch { bundles_iter });
+    }
+
     /// Inserts a resource to the [`World`], overwriting any previous value of the same type.
     ///
     /// See [`World::insert_resource`] for more details.
@@ -286,6 +310,31 @@ impl<'w, 's> Commands<'w, 's> {
         self.queue.push(InsertResource { resource })
     }
 
+    /// Inserts the non-send resource built by `constructor`. Commands are applied on the main
+    /// thread, so this lets a system running on any thread, or a task it spawned, set up a
+    /// resource that must stay on the main thread, like an OS handle or an audio device.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use std::rc::Rc;
+    /// #
+    /// struct AudioDevice(Rc<()>);
+    ///
+    /// # fn system(mut commands: Commands) {
+    /// commands.insert_non_send_resource_with(|| AudioDevice(Rc::new(())));
+    /// # }
+    /// # system.system();
+    /// ```
+    pub fn insert_non_send_resource_with<T, F>(&mut self, constructor: F)
+    where
+        T: 'static,
+        F: FnOnce() -> T + Send + Sync + 'static,
+    {
+        self.queue.push(InsertNonSendResource { constructor })
+    }
+
     /// Removes a resource from the [`World`].
     ///
     /// See [`World::remove_resource`] for more details.
@@ -311,6 +360,56 @@ impl<'w, 's> Commands<'w, 's> {
         });
     }
 
+    /// Runs `system` when the commands are applied, with access to any system parameter. This lets
+    /// button callbacks or scripted events run complex logic without scheduling a system that
+    /// runs every frame.
+    ///
+    /// Function systems are identified by their type: a system is initialized the first time it
+    /// runs, and later runs reuse it along with its [`Local`](crate::system::Local)s. Closures
+    /// that capture values can capture different values each time, so they are initialized
+    /// again on every run instead. Its own commands are applied right after it runs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// # #[derive(Component)]
+    /// # struct Enemy;
+    /// #
+    /// fn clear_level(mut commands: Commands, enemies: Query<Entity, With<Enemy>>) {
+    ///     for enemy in enemies.iter() {
+    ///         commands.entity(enemy).despawn();
+    ///     }
+    /// }
+    ///
+    /// fn on_reset_button(mut commands: Commands) {
+    ///     commands.run_system(clear_level);
+    /// }
+    /// # on_reset_button.system();
+    /// ```
+    pub fn run_system<Params>(&mut self, system: impl IntoSystem<(), (), Params>) {
+        self.run_system_with_input(system, ());
+    }
+
+    /// Like [`run_system`](Self::run_system), for a system taking `input` through an
+    /// [`In`](crate::system::In) parameter.
+    pub fn run_system_with_input<Input, Params>(
+        &mut self,
+        system: impl IntoSystem<Input, (), Params>,
+        input: Input,
+    ) where
+        Input: Send + Sync + 'static,
+    {
+        // only systems without captured state are the same system every time
+        let cache = std::mem::size_of_val(&system) == 0;
+        self.queue.push(RunSystem {
+            system: system.system(),
+            input,
+            cache,
+        });
+    }
+
     /// Adds a command directly to the command list.
     ///
     /// # Example
@@ -546,12 +645,69 @@ impl<'w, 's, 'a> EntityCommands<'w, 's, 'a> {
         })
     }
 
+    /// Like [`insert_bundle`](Self::insert_bundle), but if the entity doesn't exist anymore
+    /// when the command is applied, a [`CommandError`] event is sent instead of panicking.
+    pub fn try_insert_bundle(&mut self, bundle: impl Bundle) -> &mut Self {
+        self.commands.add(TryInsertBundle {
+            entity: self.entity,
+            bundle,
+        });
+        self
+    }
+
+    /// Like [`insert`](Self::insert), but if the entity doesn't exist anymore when the command
+    /// is applied, a [`CommandError`] event is sent instead of panicking.
+    pub fn try_insert(&mut self, component: impl Component) -> &mut Self {
+        self.try_insert_bundle((component,))
+    }
+
+    /// Like [`despawn`](Self::despawn), but if the entity doesn't exist anymore when the
+    /// command is applied, a [`CommandError`] event is sent instead of logging a warning.
+    pub fn try_despawn(&mut self) {
+        self.commands.add(TryDespawn {
+            entity: self.entity,
+        })
+    }
+
     /// Returns the underlying [`Commands`].
     pub fn commands(&mut self) -> &mut Commands<'w, 's> {
         self.commands
     }
 }
 
+/// An error from applying a fallible command, like [`EntityCommands::try_insert`].
+///
+/// These errors are sent as events when an [`Events<CommandError>`] resource exists, which
+/// `App` adds by default. Otherwise they are logged.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CommandError {
+    #[error("Could not insert a bundle (of type `{bundle_type}`) for entity {entity:?} because it doesn't exist in this World")]
+    InsertIntoMissingEntity {
+        entity: Entity,
+        bundle_type: &'static str,
+    },
+    #[error("Could not despawn entity {0:?} because it doesn't exist in this World")]
+    DespawnMissingEntity(Entity),
+}
+
+impl CommandError {
+    /// The entity the failed command was applied to.
+    pub fn entity(&self) -> Entity {
+        match self {
+            CommandError::InsertIntoMissingEntity { entity, .. } => *entity,
+            CommandError::DespawnMissingEntity(entity) => *entity,
+        }
+    }
+
+    fn send(self, world: &mut World) {
+        if let Some(mut events) = world.get_resource_mut::<Events<CommandError>>() {
+            events.send(self);
+        } else {
+            warn!("{}", self);
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Spawn<T> {
     pub bundle: T,
@@ -620,6 +776,32 @@ where
     }
 }
 
+pub struct InsertBatch<I, B>
+where
+    I: IntoIterator + Send + Sync + 'static,
+    B: Bundle,
+    I::IntoIter: Iterator<Item = (Entity, B)>,
+{
+    pub bundles_iter: I,
+}
+
+impl<I, B> Command for InsertBatch<I, B>
+where
+    I: IntoIterator + Send + Sync + 'static,
+    B: Bundle,
+    I::IntoIter: Iterator<Item = (Entity, B)>,
+{
+    fn write(self, world: &mut World) {
+        if let Err(invalid_entities) = world.insert_batch(self.bundles_iter) {
+            error!(
+                "Failed to insert bundle of type {} into the following invalid entities: {:?}",
+                std::any::type_name::<B>(),
+                invalid_entities
+            );
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Despawn {
     pub entity: Entity,
@@ -635,6 +817,19 @@ impl Command for Despawn {
     }
 }
 
+#[derive(Debug)]
+pub struct TryDespawn {
+    pub entity: Entity,
+}
+
+impl Command for TryDespawn {
+    fn write(self, world: &mut World) {
+        if !world.despawn(self.entity) {
+            CommandError::DespawnMissingEntity(self.entity).send(world);
+        }
+    }
+}
+
 pub struct InsertBundle<T> {
     pub entity: Entity,
     pub bundle: T,
@@ -655,6 +850,28 @@ where
     }
 }
 
+pub struct TryInsertBundle<T> {
+    pub entity: Entity,
+    pub bundle: T,
+}
+
+impl<T> Command for TryInsertBundle<T>
+where
+    T: Bundle + 'static,
+{
+    fn write(self, world: &mut World) {
+        if let Some(mut entity) = world.get_entity_mut(self.entity) {
+            entity.insert_bundle(self.bundle);
+        } else {
+            CommandError::InsertIntoMissingEntity {
+                entity: self.entity,
+                bundle_type: std::any::type_name::<T>(),
+            }
+            .send(world);
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Insert<T> {
     pub entity: Entity,
@@ -722,6 +939,20 @@ impl<T: Resource> Command for InsertResource<T> {
     }
 }
 
+pub struct InsertNonSendResource<F> {
+    pub constructor: F,
+}
+
+impl<T, F> Command for InsertNonSendResource<F>
+where
+    T: 'static,
+    F: FnOnce() -> T + Send + Sync + 'static,
+{
+    fn write(self, world: &mut World) {
+        world.insert_non_send((self.constructor)());
+    }
+}
+
 pub struct RemoveResource<T: Resource> {
     pub phantom: PhantomData<T>,
 }
@@ -732,13 +963,81 @@ impl<T: Resource> Command for RemoveResource<T> {
     }
 }
 
+pub struct RunSystem<S: System<Out = ()>> {
+    pub system: S,
+    pub input: S::In,
+    /// Whether the system is kept to be reused by the later runs of systems of the same type.
+    /// Otherwise it is initialized for this run only.
+    pub cache: bool,
+}
+
+/// The systems run by [`RunSystem`], by type.
+#[derive(Default)]
+struct OneShotSystems {
+    systems: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+struct OneShotSystem<S> {
+    system: S,
+    archetype_generation: ArchetypeGeneration,
+}
+
+impl<S> Command for RunSystem<S>
+where
+    S: System<Out = ()>,
+    S::In: Send + Sync + 'static,
+{
+    fn write(self, world: &mut World) {
+        // the system is taken out of the world while it runs
+        let cached = if self.cache {
+            world
+                .get_resource_or_insert_with(OneShotSystems::default)
+                .systems
+                .remove(&TypeId::of::<S>())
+        } else {
+            None
+        };
+        let mut one_shot = match cached {
+            Some(one_shot) => *one_shot.downcast::<OneShotSystem<S>>().unwrap(),
+            None => {
+                let mut system = self.system;
+                system.initialize(world);
+                OneShotSystem {
+                    system,
+                    archetype_generation: ArchetypeGeneration::initial(),
+                }
+            }
+        };
+
+        let archetypes = world.archetypes();
+        let new_generation = archetypes.generation();
+        let old_generation = std::mem::replace(&mut one_shot.archetype_generation, new_generation);
+        for archetype in
+            archetypes.archetypes[old_generation.value()..new_generation.value()].iter()
+        {
+            one_shot.system.new_archetype(archetype);
+        }
+        one_shot.system.check_change_tick(world.change_tick());
+        one_shot.system.run(self.input, world);
+        one_shot.system.apply_buffers(world);
+
+        if self.cache {
+            world
+                .get_resource_or_insert_with(OneShotSystems::default)
+                .systems
+                .insert(TypeId::of::<S>(), Box::new(one_shot));
+        }
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::float_cmp, clippy::approx_constant)]
 mod tests {
     use crate::{
         self as bevy_ecs,
         component::Component,
-        system::{CommandQueue, Commands},
+        event::Events,
+        system::{CommandError, CommandQueue, Commands, In, Local, Query, ResMut},
         world::World,
     };
     use std::sync::{
@@ -868,4 +1167,81 @@ mod tests {
         assert!(!world.contains_resource::<i32>());
         assert!(world.contains_resource::<f64>());
     }
+
+    #[test]
+    fn fallible_commands() {
+        let mut world = World::default();
+        world.insert_resource(Events::<CommandError>::default());
+        let mut queue = CommandQueue::default();
+        let entity = world.spawn().id();
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands.entity(entity).despawn();
+            commands.entity(entity).try_insert(W(1u32));
+            commands.entity(entity).try_despawn();
+        }
+        queue.apply(&mut world);
+
+        let events = world.get_resource::<Events<CommandError>>().unwrap();
+        let errors = events
+            .get_reader()
+            .iter(events)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            vec![
+                CommandError::InsertIntoMissingEntity {
+                    entity,
+                    bundle_type: std::any::type_name::<(W<u32>,)>(),
+                },
+                CommandError::DespawnMissingEntity(entity),
+            ]
+        );
+    }
+
+    #[test]
+    fn run_system() {
+        fn count_runs(mut runs: Local<u32>, mut counts: ResMut<Vec<u32>>, mut commands: Commands) {
+            *runs += 1;
+            counts.push(*runs);
+            commands.spawn().insert(W(*runs));
+        }
+
+        fn add(In(amount): In<u32>, mut counts: ResMut<Vec<u32>>, query: Query<&W<u32>>) {
+            counts.push(amount + query.iter().count() as u32);
+        }
+
+        let mut world = World::default();
+        world.insert_resource(Vec::<u32>::new());
+        let mut command_queue = CommandQueue::default();
+        {
+            let mut commands = Commands::new(&mut command_queue, &world);
+            commands.run_system(count_runs);
+            commands.run_system(count_runs);
+            commands.run_system_with_input(add, 10);
+        }
+        command_queue.apply(&mut world);
+        // the system keeps its locals, and sees the entities spawned by the previous systems
+        assert_eq!(*world.get_resource::<Vec<u32>>().unwrap(), vec![1, 2, 12]);
+    }
+
+    #[test]
+    fn run_capturing_system() {
+        fn push_value(value: u32) -> impl FnMut(ResMut<Vec<u32>>) {
+            move |mut values: ResMut<Vec<u32>>| values.push(value)
+        }
+
+        let mut world = World::default();
+        world.insert_resource(Vec::<u32>::new());
+        let mut command_queue = CommandQueue::default();
+        {
+            let mut commands = Commands::new(&mut command_queue, &world);
+            commands.run_system(push_value(1));
+            commands.run_system(push_value(2));
+        }
+        command_queue.apply(&mut world);
+        // both closures have the same type, but each one runs with the value it captured
+        assert_eq!(*world.get_resource::<Vec<u32>>().unwrap(), vec![1, 2]);
+    }
 }
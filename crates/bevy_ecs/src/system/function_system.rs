@@ -2,11 +2,12 @@ use crate::{
     archetype::{Archetype, ArchetypeComponentId, ArchetypeGeneration, ArchetypeId},
     component::ComponentId,
     query::{Access, FilteredAccessSet},
+    schedule::{SystemLabel, SystemTypeIdLabel},
     system::{
         check_system_change_tick, ReadOnlySystemParamFetch, System, SystemParam, SystemParamFetch,
         SystemParamState,
     },
-    world::{World, WorldId},
+    world::{FromWorld, World, WorldId},
 };
 use bevy_ecs_macros::all_tuples;
 use std::{borrow::Cow, marker::PhantomData};
@@ -50,7 +51,31 @@ impl SystemMeta {
 
 // TODO: Actually use this in FunctionSystem. We should probably only do this once Systems are constructed using a World reference
 // (to avoid the need for unwrapping to retrieve SystemMeta)
-/// Holds on to persistent state required to drive [`SystemParam`] for a [`System`].  
+/// Holds on to persistent state required to drive [`SystemParam`] for a [`System`].
+///
+/// This can be used to fetch system params from a [`World`] outside of a system, like in
+/// exclusive systems, tests or custom runners. Create it once and keep it around: the access of
+/// the params is computed on creation, and only updated for new archetypes on later calls.
+///
+/// ```
+/// # use bevy_ecs::{prelude::*, system::SystemState};
+/// #[derive(Component)]
+/// struct Health(f32);
+/// struct Damage(f32);
+///
+/// let mut world = World::new();
+/// world.insert_resource(Damage(1.0));
+/// world.spawn().insert(Health(10.0));
+///
+/// let mut system_state: SystemState<(Res<Damage>, Query<&mut Health>)> =
+///     SystemState::new(&mut world);
+/// for _ in 0..3 {
+///     let (damage, mut query) = system_state.get_mut(&mut world);
+///     for mut health in query.iter_mut() {
+///         health.0 -= damage.0;
+///     }
+/// }
+/// ```
 pub struct SystemState<Param: SystemParam> {
     meta: SystemMeta,
     param_state: <Param as SystemParam>::Fetch,
@@ -159,6 +184,12 @@ impl<Param: SystemParam> SystemState<Param> {
     }
 }
 
+impl<Param: SystemParam> FromWorld for SystemState<Param> {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(world)
+    }
+}
+
 /// Conversion trait to turn something into a [`System`].
 ///
 /// Use this to get a system from a function. Also note that every system implements this trait as
@@ -396,6 +427,43 @@ where
             self.system_meta.name.as_ref(),
         );
     }
+
+    fn default_labels(&self) -> Vec<Box<dyn SystemLabel>> {
+        vec![Box::new(SystemTypeIdLabel::<F>::new())]
+    }
+}
+
+/// Provides `my_system.as_system_label()`, the label every function system has. This is mostly
+/// useful to order against one instantiation of a generic system, without labeling each of them.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// #[derive(Component, Clone)]
+/// struct Position(f32);
+/// #[derive(Component, Clone)]
+/// struct Velocity(f32);
+///
+/// fn sync_component<T: Component + Clone>(_query: Query<&T>) {}
+/// fn interpolate<T: Component + Clone>(_query: Query<&mut T>) {}
+///
+/// let mut stage = SystemStage::parallel();
+/// stage
+///     .add_system(sync_component::<Position>)
+///     .add_system(sync_component::<Velocity>)
+///     .add_system(interpolate::<Position>.after(sync_component::<Position>.as_system_label()));
+/// ```
+pub trait AsSystemLabel<Marker>: Sized + 'static {
+    fn as_system_label(&self) -> SystemTypeIdLabel<Self>;
+}
+
+impl<In, Out, Param, Marker, F> AsSystemLabel<(In, Out, Param, Marker)> for F
+where
+    Param: SystemParam,
+    F: SystemParamFunction<In, Out, Param, Marker>,
+{
+    fn as_system_label(&self) -> SystemTypeIdLabel<Self> {
+        SystemTypeIdLabel::new()
+    }
 }
 
 /// A trait implemented for all functions that can be used as [`System`]s.
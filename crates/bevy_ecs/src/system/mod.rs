@@ -56,6 +56,7 @@
 //! - [`EventWriter`](crate::event::EventWriter)
 //! - [`NonSend`] and `Option<NonSend>`
 //! - [`NonSendMut`] and `Option<NonSendMut>`
+//! - [`NonSendMarker`] (Runs the system on the main thread)
 //! - [`RemovedComponents`]
 //! - [`SystemChangeTick`]
 //! - [`Archetypes`](crate::archetype::Archetypes) (Provides Archetype metadata)
@@ -92,13 +93,15 @@ mod tests {
         bundle::Bundles,
         component::{Component, Components},
         entity::{Entities, Entity},
+        event::Events,
         query::{Added, Changed, Or, QueryState, With, Without},
         schedule::{Schedule, Stage, SystemStage},
         system::{
-            ConfigurableSystem, IntoExclusiveSystem, IntoSystem, Local, NonSend, NonSendMut, Query,
-            QuerySet, RemovedComponents, Res, ResMut, System, SystemState,
+            Commands, ConfigurableSystem, IntoChainSystem, IntoExclusiveSystem, IntoSystem, Local,
+            NonSend, NonSendMarker, NonSendMut, Query, QuerySet, RemovedComponents, Res, ResMut,
+            System, SystemState,
         },
-        world::{FromWorld, World},
+        world::{FromWorld, RemovedValue, World},
     };
 
     #[derive(Component, Debug, Eq, PartialEq, Default)]
@@ -449,6 +452,24 @@ mod tests {
         assert!(*world.get_resource::<bool>().unwrap());
     }
 
+    #[test]
+    fn non_send_marker_and_command() {
+        let mut world = World::default();
+
+        struct NotSend(std::rc::Rc<i32>);
+
+        fn sys(_main_thread: NonSendMarker, mut commands: Commands) {
+            commands.insert_non_send_resource_with(|| NotSend(std::rc::Rc::new(3)));
+        }
+
+        let mut system = sys.system();
+        system.initialize(&mut world);
+        assert!(!system.is_send());
+
+        run_system(&mut world, sys);
+        assert_eq!(*world.get_non_send_resource::<NotSend>().unwrap().0, 3);
+    }
+
     #[test]
     fn remove_tracking() {
         let mut world = World::new();
@@ -461,7 +482,7 @@ mod tests {
         world.entity_mut(a).despawn();
 
         fn validate_removed(
-            removed_i32: RemovedComponents<W<i32>>,
+            mut removed_i32: RemovedComponents<W<i32>>,
             despawned: Res<Despawned>,
             mut ran: ResMut<bool>,
         ) {
@@ -478,6 +499,38 @@ mod tests {
         assert!(*world.get_resource::<bool>().unwrap(), "system ran");
     }
 
+    #[test]
+    fn removals_survive_a_frame_and_carry_values() {
+        #[derive(Component, Clone, Debug, PartialEq)]
+        struct Health(u32);
+
+        let mut world = World::default();
+        world.store_removed_values::<Health>();
+        let entity = world.spawn().insert(Health(3)).id();
+        world.despawn(entity);
+        world.clear_trackers();
+
+        let mut state = SystemState::<RemovedComponents<Health>>::new(&mut world);
+        assert_eq!(
+            state.get_mut(&mut world).iter().collect::<Vec<_>>(),
+            vec![entity]
+        );
+        // each removal is only seen once
+        assert_eq!(state.get_mut(&mut world).iter().count(), 0);
+
+        let events = world
+            .get_resource::<Events<RemovedValue<Health>>>()
+            .unwrap();
+        let values = events
+            .get_reader()
+            .iter(events)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(values.len(), 1);
+        assert_eq!(values[0].entity, entity);
+        assert_eq!(values[0].value, Health(3));
+    }
+
     #[test]
     fn configure_system_local() {
         let mut world = World::default();
@@ -683,6 +736,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn pipe_into_adapters() {
+        use super::adapter;
+        use std::num::ParseIntError;
+
+        fn parse(message: Res<&'static str>) -> Result<usize, ParseIntError> {
+            message.parse()
+        }
+
+        let mut world = World::default();
+        world.insert_resource("42");
+        let mut system = parse
+            .pipe(adapter::unwrap)
+            .pipe(adapter::new(|n: usize| n + 1));
+        system.initialize(&mut world);
+        assert_eq!(system.run((), &mut world), 43);
+
+        let mut system = parse.pipe(adapter::new(|result: Result<usize, ParseIntError>| {
+            result.ok()
+        }));
+        system.initialize(&mut world);
+        world.insert_resource("not a number");
+        assert_eq!(system.run((), &mut world), None);
+    }
+
+    #[test]
+    fn query_join() {
+        let mut world = World::default();
+        let both = world.spawn().insert_bundle((W(1u32), W(10u64))).id();
+        world.spawn().insert(W(2u32));
+        for i in 0..4 {
+            world.spawn().insert(W(20u64 + i));
+        }
+        let filtered = world.spawn().insert_bundle((W(3u32), W(30u64), B)).id();
+
+        let mut system_state: SystemState<(
+            Query<(Entity, &W<u32>)>,
+            Query<&mut W<u64>, Without<B>>,
+        )> = SystemState::new(&mut world);
+        let (mut a, mut b) = system_state.get_mut(&mut world);
+        let joined = a
+            .join_mut(&mut b)
+            .map(|((entity, a), mut b)| {
+                b.0 += a.0 as u64;
+                entity
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(joined, vec![both]);
+        assert_eq!(world.get::<W<u64>>(both).unwrap().0, 11);
+        assert_eq!(world.get::<W<u64>>(filtered).unwrap().0, 30);
+
+        let mut system_state: SystemState<(Query<&W<u64>>, Query<&W<u32>>)> =
+            SystemState::new(&mut world);
+        let (a, b) = system_state.get(&world);
+        let mut joined = a.join(&b).map(|(a, b)| (a.0, b.0)).collect::<Vec<_>>();
+        joined.sort_unstable();
+        assert_eq!(joined, vec![(11, 1), (30, 3)]);
+    }
+
     #[test]
     fn system_state_change_detection() {
         #[derive(Component, Eq, PartialEq, Debug)]
@@ -709,6 +821,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn system_state_from_world() {
+        #[derive(Eq, PartialEq, Debug)]
+        struct Counter(usize);
+
+        let mut world = World::default();
+        world.insert_resource(Counter(42));
+
+        let mut system_state = SystemState::<Res<Counter>>::from_world(&mut world);
+        assert_eq!(*system_state.get(&world), Counter(42));
+    }
+
     #[test]
     #[should_panic]
     fn system_state_invalid_world() {
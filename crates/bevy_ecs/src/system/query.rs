@@ -2,8 +2,8 @@ use crate::{
     component::Component,
     entity::Entity,
     query::{
-        Fetch, FilterFetch, QueryCombinationIter, QueryEntityError, QueryIter, QueryState,
-        ReadOnlyFetch, WorldQuery,
+        Fetch, FilterFetch, QueryCombinationIter, QueryEntityError, QueryIter, QueryJoinIter,
+        QueryState, ReadOnlyFetch, WorldQuery,
     },
     world::{Mut, World},
 };
@@ -240,6 +240,17 @@ use thiserror::Error;
 /// methods instead. Keep in mind though that they will return a [`QuerySingleError`] if the
 /// number of query results differ from being exactly one. If that's the case, use `iter.next()`
 /// (or `iter_mut.next()`) to only get the first query result.
+///
+/// ## Iteration order
+///
+/// Query results are yielded archetype by archetype, in the order the archetypes were created,
+/// and in the order of the entities in each archetype. Despawning an entity, or moving it to
+/// another archetype by adding or removing components, moves the last entity of its archetype
+/// to its place. The order is not sorted by [`Entity`], but it only depends on the operations
+/// that were done on the [`World`], so it is the same on every run that does the same operations
+/// in the same order, for example with a
+/// [deterministic stage](crate::schedule::SystemStage::deterministic).
+/// [`par_for_each`](Self::par_for_each) doesn't guarantee any order.
 pub struct Query<'world, 'state, Q: WorldQuery, F: WorldQuery = ()>
 where
     F::Fetch: FilterFetch,
@@ -434,6 +445,116 @@ where
         )
     }
 
+    /// Returns an [`Iterator`] over the entities matched by both this query and `other`, with the
+    /// results of both queries. Only the entities of the query matching the fewest entities are
+    /// looked up in the other one, which is faster than calling [`Query::get`] on one query in a
+    /// loop over the other.
+    ///
+    /// This can only be called for read-only queries, see [`Self::join_mut`] for write-queries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// # #[derive(Component)]
+    /// # struct Player { name: String }
+    /// # #[derive(Component)]
+    /// # struct Health(u32);
+    /// #
+    /// fn report_health_system(players: Query<&Player>, healths: Query<&Health>) {
+    ///     for (player, health) in players.join(&healths) {
+    ///         println!("{} has {} health", player.name, health.0);
+    ///     }
+    /// }
+    /// # report_health_system.system();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the queries don't belong to the same [`World`].
+    #[inline]
+    pub fn join<'a, Q2: WorldQuery, F2: WorldQuery>(
+        &'a self,
+        other: &'a Query<'_, '_, Q2, F2>,
+    ) -> QueryJoinIter<'a, 'a, Q, F, Q2, F2>
+    where
+        Q::Fetch: ReadOnlyFetch,
+        Q2::Fetch: ReadOnlyFetch,
+        F2::Fetch: FilterFetch,
+    {
+        assert_eq!(
+            self.world.id(),
+            other.world.id(),
+            "joined queries must belong to the same world"
+        );
+        // SAFE: both queries are read-only
+        unsafe {
+            QueryJoinIter::new(
+                self.world,
+                self.state,
+                other.state,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
+    /// Returns an [`Iterator`] over the entities matched by both this query and `other`, with the
+    /// results of both queries. See [`Query::join`].
+    ///
+    /// # Example
+    ///
+    /// Here, `join_mut` matches animation players to the controllers driving them:
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// # #[derive(Component)]
+    /// # struct Controller { speed: f32 }
+    /// # #[derive(Component)]
+    /// # struct AnimationPlayer { speed: f32 }
+    /// #
+    /// fn drive_animations_system(
+    ///     mut players: Query<&mut AnimationPlayer>,
+    ///     mut controllers: Query<&Controller>,
+    /// ) {
+    ///     for (mut player, controller) in players.join_mut(&mut controllers) {
+    ///         player.speed = controller.speed;
+    ///     }
+    /// }
+    /// # drive_animations_system.system();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the queries don't belong to the same [`World`].
+    #[inline]
+    pub fn join_mut<'a, Q2: WorldQuery, F2: WorldQuery>(
+        &'a mut self,
+        other: &'a mut Query<'_, '_, Q2, F2>,
+    ) -> QueryJoinIter<'a, 'a, Q, F, Q2, F2>
+    where
+        F2::Fetch: FilterFetch,
+    {
+        assert_eq!(
+            self.world.id(),
+            other.world.id(),
+            "joined queries must belong to the same world"
+        );
+        // SAFE: system runs without conflicts with other systems, and the access of both queries
+        // was checked not to conflict when they were added to the same system
+        unsafe {
+            QueryJoinIter::new(
+                self.world,
+                self.state,
+                other.state,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
     /// Runs `f` on each query result. This is faster than the equivalent iter() method, but cannot
     /// be chained like a normal [`Iterator`].
     ///
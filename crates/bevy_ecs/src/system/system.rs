@@ -4,6 +4,7 @@ use crate::{
     archetype::{Archetype, ArchetypeComponentId},
     component::ComponentId,
     query::Access,
+    schedule::SystemLabel,
     world::World,
 };
 use std::borrow::Cow;
@@ -55,6 +56,11 @@ pub trait System: Send + Sync + 'static {
     /// Initialize the system.
     fn initialize(&mut self, _world: &mut World);
     fn check_change_tick(&mut self, change_tick: u32);
+    /// Labels the system gets without them being added to its descriptor, like the
+    /// [`SystemTypeIdLabel`](crate::schedule::SystemTypeIdLabel) of function systems.
+    fn default_labels(&self) -> Vec<Box<dyn SystemLabel>> {
+        Vec::new()
+    }
 }
 
 /// A convenience type alias for a boxed [`System`] trait object.
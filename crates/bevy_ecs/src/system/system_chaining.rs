@@ -10,8 +10,9 @@ use std::borrow::Cow;
 /// A [`System`] that chains two systems together, creating a new system that routes the output of
 /// the first system into the input of the second system, yielding the output of the second system.
 ///
-/// Given two systems A and B, A may be chained with B as `A.chain(B)` if the output type of A is
-/// equal to the input type of B.
+/// Given two systems A and B, A may be chained with B as `A.chain(B)`, or equivalently
+/// `A.pipe(B)`, if the output type of A is equal to the input type of B. The [`adapter`] module
+/// provides common systems to chain fallible systems with, to handle their errors.
 ///
 /// Note that for [`FunctionSystem`](crate::system::FunctionSystem)s the output is the return value
 /// of the function and the input is the first [`SystemParam`](crate::system::SystemParam) if it is
@@ -121,6 +122,26 @@ where
     /// Chain this system `A` with another system `B` creating a new system that feeds system A's
     /// output into system `B`, returning the output of system `B`.
     fn chain(self, system: SystemB) -> ChainSystem<Self::System, SystemB::System>;
+
+    /// Pipes the output of this system into `system`, see [`IntoChainSystem::chain`]. This reads
+    /// better than `chain` for handler systems, and doesn't clash with [`Iterator::chain`].
+    ///
+    /// ```
+    /// # use bevy_ecs::{prelude::*, system::adapter};
+    /// # use std::num::ParseIntError;
+    /// struct Config(String);
+    ///
+    /// fn load_config(config: Res<Config>) -> Result<(), ParseIntError> {
+    ///     let _volume: u32 = config.0.parse()?;
+    ///     Ok(())
+    /// }
+    ///
+    /// # let mut stage = SystemStage::single_threaded();
+    /// stage.add_system(load_config.pipe(adapter::error));
+    /// ```
+    fn pipe(self, system: SystemB) -> ChainSystem<Self::System, SystemB::System> {
+        self.chain(system)
+    }
 }
 
 impl<SystemA, ParamA, Payload, SystemB, ParamB, Out>
@@ -141,3 +162,72 @@ where
         }
     }
 }
+
+/// Systems to chain other systems with, mostly to handle the errors of fallible systems.
+///
+/// ```
+/// # use bevy_ecs::{prelude::*, system::adapter};
+/// # use std::num::ParseIntError;
+/// struct Message(String);
+///
+/// fn parse_message(message: Res<Message>) -> Result<u32, ParseIntError> {
+///     message.0.parse()
+/// }
+///
+/// fn log_message(message: Res<Message>) -> Result<(), String> {
+///     Err(format!("can't log {}", message.0))
+/// }
+///
+/// # let mut stage = SystemStage::single_threaded();
+/// stage
+///     .add_system(parse_message.pipe(adapter::unwrap).pipe(adapter::ignore))
+///     .add_system(log_message.pipe(adapter::warn))
+///     .add_system(
+///         parse_message
+///             .pipe(adapter::new(|result: Result<u32, ParseIntError>| result.ok()))
+///             .pipe(adapter::dbg),
+///     );
+/// ```
+pub mod adapter {
+    use crate::system::In;
+    use bevy_utils::tracing;
+    use std::fmt::Debug;
+
+    /// Converts a closure into a system that takes its argument as [`In`], to map the output of
+    /// another system.
+    pub fn new<T, U>(mut f: impl FnMut(T) -> U) -> impl FnMut(In<T>) -> U {
+        move |In(x)| f(x)
+    }
+
+    /// Unwraps the [`Result`] output of a system, panicking with the error if there is one.
+    pub fn unwrap<T, E: Debug>(In(result): In<Result<T, E>>) -> T {
+        result.unwrap()
+    }
+
+    /// Logs the output of a system at the info level.
+    pub fn info<T: Debug>(In(data): In<T>) {
+        tracing::info!("{:?}", data);
+    }
+
+    /// Logs the output of a system at the debug level.
+    pub fn dbg<T: Debug>(In(data): In<T>) {
+        tracing::debug!("{:?}", data);
+    }
+
+    /// Logs the error of a system at the warn level, if there is one.
+    pub fn warn<E: Debug>(In(result): In<Result<(), E>>) {
+        if let Err(error) = result {
+            tracing::warn!("{:?}", error);
+        }
+    }
+
+    /// Logs the error of a system at the error level, if there is one.
+    pub fn error<E: Debug>(In(result): In<Result<(), E>>) {
+        if let Err(error) = result {
+            tracing::error!("{:?}", error);
+        }
+    }
+
+    /// Discards the output of a system.
+    pub fn ignore<T>(In(_): In<T>) {}
+}
@@ -5,6 +5,7 @@ use crate::{
     change_detection::Ticks,
     component::{Component, ComponentId, ComponentTicks, Components},
     entity::{Entities, Entity},
+    event::{Events, ManualEventReader},
     query::{
         FilterFetch, FilteredAccess, FilteredAccessSet, QueryState, ReadOnlyFetch, WorldQuery,
     },
@@ -622,6 +623,11 @@ impl<'w, 's, T: Resource + FromWorld> SystemParamFetch<'w, 's> for LocalState<T>
 
 /// A [`SystemParam`] that grants access to the entities that had their `T` [`Component`] removed.
 ///
+/// Like an [`EventReader`](crate::event::EventReader), each system sees each removal once.
+/// Removals are kept for two frames (two calls to [`World::clear_trackers`]), so a system running
+/// once per frame sees all of them, whatever stage it runs in. Use
+/// [`World::store_removed_values`](crate::world::World::store_removed_values) to also receive the values of the removed components.
+///
 /// # Examples
 ///
 /// Basic usage:
@@ -634,22 +640,27 @@ impl<'w, 's, T: Resource + FromWorld> SystemParamFetch<'w, 's> for LocalState<T>
 /// # #[derive(Component)]
 /// # struct MyComponent;
 ///
-/// fn react_on_removal(removed: RemovedComponents<MyComponent>) {
+/// fn react_on_removal(mut removed: RemovedComponents<MyComponent>) {
 ///     removed.iter().for_each(|removed_entity| println!("{:?}", removed_entity));
 /// }
 ///
 /// # react_on_removal.system();
 /// ```
-pub struct RemovedComponents<'a, T: Component> {
-    world: &'a World,
-    component_id: ComponentId,
+pub struct RemovedComponents<'w, 's, T: Component> {
+    events: Option<&'w Events<Entity>>,
+    reader: &'s mut ManualEventReader<Entity>,
     marker: PhantomData<T>,
 }
 
-impl<'a, T: Component> RemovedComponents<'a, T> {
-    /// Returns an iterator over the entities that had their `T` [`Component`] removed.
-    pub fn iter(&self) -> std::iter::Cloned<std::slice::Iter<'_, Entity>> {
-        self.world.removed_with_id(self.component_id)
+impl<'w, 's, T: Component> RemovedComponents<'w, 's, T> {
+    /// Returns an iterator over the entities that had their `T` [`Component`] removed since this
+    /// system last read them.
+    pub fn iter(&mut self) -> impl Iterator<Item = Entity> + '_ {
+        let reader = &mut self.reader;
+        self.events
+            .map(|events| reader.iter(events).copied())
+            .into_iter()
+            .flatten()
     }
 }
 
@@ -659,10 +670,11 @@ unsafe impl<T: Component> ReadOnlySystemParamFetch for RemovedComponentsState<T>
 /// The [`SystemParamState`] of [`RemovedComponents<T>`].
 pub struct RemovedComponentsState<T> {
     component_id: ComponentId,
+    reader: ManualEventReader<Entity>,
     marker: PhantomData<T>,
 }
 
-impl<'a, T: Component> SystemParam for RemovedComponents<'a, T> {
+impl<'w, 's, T: Component> SystemParam for RemovedComponents<'w, 's, T> {
     type Fetch = RemovedComponentsState<T>;
 }
 
@@ -674,6 +686,7 @@ unsafe impl<T: Component> SystemParamState for RemovedComponentsState<T> {
     fn init(world: &mut World, _system_meta: &mut SystemMeta, _config: Self::Config) -> Self {
         Self {
             component_id: world.init_component::<T>(),
+            reader: Default::default(),
             marker: PhantomData,
         }
     }
@@ -682,7 +695,7 @@ unsafe impl<T: Component> SystemParamState for RemovedComponentsState<T> {
 }
 
 impl<'w, 's, T: Component> SystemParamFetch<'w, 's> for RemovedComponentsState<T> {
-    type Item = RemovedComponents<'w, T>;
+    type Item = RemovedComponents<'w, 's, T>;
 
     #[inline]
     unsafe fn get_param(
@@ -692,8 +705,8 @@ impl<'w, 's, T: Component> SystemParamFetch<'w, 's> for RemovedComponentsState<T
         _change_tick: u32,
     ) -> Self::Item {
         RemovedComponents {
-            world,
-            component_id: state.component_id,
+            events: world.removed_components.get(state.component_id),
+            reader: &mut state.reader,
             marker: PhantomData,
         }
     }
@@ -1171,6 +1184,56 @@ impl<'w, 's> SystemParamFetch<'w, 's> for SystemChangeTickState {
     }
 }
 
+/// A [`SystemParam`] that makes its system run on the main thread, without accessing any data.
+///
+/// Systems that use [`NonSend`] or [`NonSendMut`] already run on the main thread. This is for
+/// systems that call main thread only APIs directly, like some OS or audio device APIs.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// fn poll_audio_device(_main_thread: NonSendMarker) {
+///     // call APIs that must run on the main thread
+/// }
+/// # poll_audio_device.system();
+/// ```
+pub struct NonSendMarker(PhantomData<*mut ()>);
+
+// SAFE: Doesn't access any data
+unsafe impl ReadOnlySystemParamFetch for NonSendMarkerState {}
+
+impl SystemParam for NonSendMarker {
+    type Fetch = NonSendMarkerState;
+}
+
+/// The [`SystemParamState`] of [`NonSendMarker`].
+pub struct NonSendMarkerState {}
+
+// SAFE: Doesn't access any data, and marks the system as non-send
+unsafe impl SystemParamState for NonSendMarkerState {
+    type Config = ();
+
+    fn init(_world: &mut World, system_meta: &mut SystemMeta, _config: Self::Config) -> Self {
+        system_meta.set_non_send();
+        Self {}
+    }
+
+    fn default_config() {}
+}
+
+impl<'w, 's> SystemParamFetch<'w, 's> for NonSendMarkerState {
+    type Item = NonSendMarker;
+
+    #[inline]
+    unsafe fn get_param(
+        _state: &'s mut Self,
+        _system_meta: &SystemMeta,
+        _world: &'w World,
+        _change_tick: u32,
+    ) -> Self::Item {
+        NonSendMarker(PhantomData)
+    }
+}
+
 macro_rules! impl_system_param_tuple {
     ($($param: ident),*) => {
         impl<$($param: SystemParam),*> SystemParam for ($($param,)*) {
@@ -1,9 +1,13 @@
 use crate::{
     archetype::{Archetype, ArchetypeId, Archetypes},
-    bundle::{Bundle, BundleInfo},
+    bundle::{Bundle, BundleId, BundleInfo, DynamicComponent},
     change_detection::Ticks,
-    component::{Component, ComponentId, ComponentTicks, Components, StorageType},
+    component::{
+        Component, ComponentHook, ComponentHooks, ComponentId, ComponentTicks, Components,
+        StorageType,
+    },
     entity::{Entities, Entity, EntityLocation},
+    event::Events,
     storage::{SparseSet, Storages},
     world::{Mut, World},
 };
@@ -69,6 +73,13 @@ impl<'w> EntityRef<'w> {
         }
     }
 
+    /// Gets a pointer to the data of the component `component_id`, which can be a component
+    /// registered at runtime. Returns `None` if the entity doesn't have this component.
+    #[inline]
+    pub fn get_by_id(&self, component_id: ComponentId) -> Option<*const u8> {
+        get_component_by_id(self.world, component_id, self.entity, self.location)
+    }
+
     /// # Safety
     /// This allows aliased mutability. You must make sure this call does not result in multiple
     /// mutable references to the same component
@@ -173,6 +184,30 @@ impl<'w> EntityMut<'w> {
         }
     }
 
+    /// Gets a pointer to the data of the component `component_id`, which can be a component
+    /// registered at runtime. Returns `None` if the entity doesn't have this component.
+    #[inline]
+    pub fn get_by_id(&self, component_id: ComponentId) -> Option<*const u8> {
+        get_component_by_id(self.world, component_id, self.entity, self.location)
+    }
+
+    /// Gets a pointer to the data of the component `component_id` to mutate it, and marks the
+    /// component as changed. Returns `None` if the entity doesn't have this component.
+    #[inline]
+    pub fn get_mut_by_id(&mut self, component_id: ComponentId) -> Option<*mut u8> {
+        self.world.components.get_info(component_id)?;
+        let change_tick = self.world.change_tick();
+        // SAFE: world access is unique, entity location is valid and component_id exists
+        unsafe {
+            get_component_and_ticks(self.world, component_id, self.entity, self.location).map(
+                |(value, ticks)| {
+                    (*ticks).set_changed(change_tick);
+                    value
+                },
+            )
+        }
+    }
+
     /// # Safety
     /// This allows aliased mutability. You must make sure this call does not result in multiple
     /// mutable references to the same component
@@ -195,6 +230,11 @@ impl<'w> EntityMut<'w> {
             .world
             .bundles
             .init_info::<T>(&mut self.world.components, &mut self.world.storages);
+        let hooks = insert_hooks(
+            &self.world.components,
+            &self.world.archetypes[self.location.archetype_id],
+            &bundle_info.component_ids,
+        );
         let mut bundle_inserter = bundle_info.get_bundle_inserter(
             &mut self.world.entities,
             &mut self.world.archetypes,
@@ -207,12 +247,94 @@ impl<'w> EntityMut<'w> {
         unsafe {
             self.location = bundle_inserter.insert(self.entity, self.location.index, bundle);
         }
+        self.run_insert_hooks(hooks);
+
+        self
+    }
+
+    /// Inserts the component `component_id`, which can be a component registered at runtime,
+    /// moving its value out of `component`. If the entity already has this component, its previous
+    /// value is dropped.
+    ///
+    /// # Safety
+    /// `component_id` must be a valid [`ComponentId`] of this world, and `component` must point to
+    /// a valid value of this component. The value is owned by the entity afterwards, so the caller
+    /// must not drop it.
+    pub unsafe fn insert_by_id(
+        &mut self,
+        component_id: ComponentId,
+        component: *mut u8,
+    ) -> &mut Self {
+        let change_tick = self.world.change_tick();
+        let bundle_info = self
+            .world
+            .bundles
+            .init_dynamic_info(&mut self.world.components, component_id);
+        let hooks = insert_hooks(
+            &self.world.components,
+            &self.world.archetypes[self.location.archetype_id],
+            &bundle_info.component_ids,
+        );
+        let mut bundle_inserter = bundle_info.get_bundle_inserter(
+            &mut self.world.entities,
+            &mut self.world.archetypes,
+            &mut self.world.components,
+            &mut self.world.storages,
+            self.location.archetype_id,
+            change_tick,
+        );
+        // SAFE: location matches current entity. `bundle_info` contains the single component of
+        // the `DynamicComponent`
+        self.location = bundle_inserter.insert(
+            self.entity,
+            self.location.index,
+            DynamicComponent(component),
+        );
+        self.run_insert_hooks(hooks);
 
         self
     }
 
     // TODO: move to BundleInfo
     pub fn remove_bundle<T: Bundle>(&mut self) -> Option<T> {
+        let bundle_info = self
+            .world
+            .bundles
+            .init_info::<T>(&mut self.world.components, &mut self.world.storages);
+        // the hooks only run if the entity has the whole bundle
+        // SAFE: the archetype and bundle come from this world
+        let new_archetype_id = unsafe {
+            remove_bundle_from_archetype(
+                &mut self.world.archetypes,
+                &mut self.world.storages,
+                &mut self.world.components,
+                self.location.archetype_id,
+                bundle_info,
+                false,
+            )?
+        };
+        if new_archetype_id == self.location.archetype_id {
+            return None;
+        }
+        let bundle_id = bundle_info.id();
+        let hooks = remove_hooks(
+            &self.world.components,
+            &self.world.archetypes[self.location.archetype_id],
+            bundle_info.component_ids.iter().copied(),
+        );
+        self.run_remove_hooks(hooks);
+        let archetype = &self.world.archetypes[self.location.archetype_id];
+        let bundle_info = self.world.bundles.get(bundle_id).unwrap();
+        if !bundle_info
+            .component_ids
+            .iter()
+            .all(|&component_id| archetype.contains(component_id))
+        {
+            // a hook removed part of the bundle, the rest of it is dropped
+            self.remove_intersection_without_hooks(bundle_id);
+            return None;
+        }
+
         let archetypes = &mut self.world.archetypes;
         let storages = &mut self.world.storages;
         let components = &mut self.world.components;
@@ -335,13 +457,80 @@ impl<'w> EntityMut<'w> {
     // TODO: move to BundleInfo
     /// Remove any components in the bundle that the entity has.
     pub fn remove_bundle_intersection<T: Bundle>(&mut self) {
+        let bundle_id = self
+            .world
+            .bundles
+            .init_info::<T>(&mut self.world.components, &mut self.world.storages)
+            .id();
+        self.remove_intersection(bundle_id);
+    }
+
+    /// Removes and drops the component `component_id`, which can be a component registered at
+    /// runtime, if the entity has it.
+    pub fn remove_by_id(&mut self, component_id: ComponentId) {
+        if self.world.components.get_info(component_id).is_none() {
+            return;
+        }
+        // SAFE: component_id was checked to be valid
+        let bundle_id = unsafe {
+            self.world
+                .bundles
+                .init_dynamic_info(&mut self.world.components, component_id)
+                .id()
+        };
+        self.remove_intersection(bundle_id);
+    }
+
+    fn remove_intersection(&mut self, bundle_id: BundleId) {
+        let archetype = &self.world.archetypes[self.location.archetype_id];
+        let removed = self
+            .world
+            .bundles
+            .get(bundle_id)
+            .unwrap()
+            .component_ids
+            .iter()
+            .copied()
+            .filter(|&component_id| archetype.contains(component_id))
+            .collect::<Vec<_>>();
+        let hooks = remove_hooks(&self.world.components, archetype, removed.iter().copied());
+        self.run_remove_hooks(hooks);
+
+        let archetype = &self.world.archetypes[self.location.archetype_id];
+        if self
+            .world
+            .bundles
+            .get(bundle_id)
+            .unwrap()
+            .component_ids
+            .iter()
+            .all(|component_id| removed.contains(component_id) == archetype.contains(*component_id))
+        {
+            self.remove_intersection_without_hooks(bundle_id);
+        } else {
+            // a hook inserted or removed components of the bundle, only the components the hooks
+            // ran for are removed
+            for component_id in removed {
+                // SAFE: component_id is in a bundle, so it is valid
+                let bundle_id = unsafe {
+                    self.world
+                        .bundles
+                        .init_dynamic_info(&mut self.world.components, component_id)
+                        .id()
+                };
+                self.remove_intersection_without_hooks(bundle_id);
+            }
+        }
+    }
+
+    fn remove_intersection_without_hooks(&mut self, bundle_id: BundleId) {
         let archetypes = &mut self.world.archetypes;
         let storages = &mut self.world.storages;
         let components = &mut self.world.components;
         let entities = &mut self.world.entities;
         let removed_components = &mut self.world.removed_components;
 
-        let bundle_info = self.world.bundles.init_info::<T>(components, storages);
+        let bundle_info = self.world.bundles.get(bundle_id).unwrap();
         let old_location = self.location;
         let new_archetype_id = unsafe {
             remove_bundle_from_archetype(
@@ -364,8 +553,8 @@ impl<'w> EntityMut<'w> {
         for component_id in bundle_info.component_ids.iter().cloned() {
             if old_archetype.contains(component_id) {
                 removed_components
-                    .get_or_insert_with(component_id, Vec::new)
-                    .push(entity);
+                    .get_or_insert_with(component_id, Events::default)
+                    .send(entity);
 
                 // Make sure to drop components stored in sparse sets.
                 // Dense components are dropped later in `move_to_and_drop_missing_unchecked`.
@@ -401,7 +590,24 @@ impl<'w> EntityMut<'w> {
         self.remove_bundle::<(T,)>().map(|v| v.0)
     }
 
-    pub fn despawn(self) {
+    pub fn despawn(mut self) {
+        // the hooks can insert components, which are despawned too, so their hooks run until
+        // they have run for every component of the entity
+        let mut removed = Vec::new();
+        loop {
+            let archetype = &self.world.archetypes[self.location.archetype_id];
+            let added = archetype
+                .components()
+                .filter(|component_id| !removed.contains(component_id))
+                .collect::<Vec<_>>();
+            if added.is_empty() {
+                break;
+            }
+            let hooks = remove_hooks(&self.world.components, archetype, added.iter().copied());
+            removed.extend(added);
+            self.run_remove_hooks(hooks);
+        }
+
         let world = self.world;
         world.flush();
         let location = world
@@ -413,10 +619,10 @@ impl<'w> EntityMut<'w> {
         {
             let archetype = &mut world.archetypes[location.archetype_id];
             for component_id in archetype.components() {
-                let removed_components = world
+                world
                     .removed_components
-                    .get_or_insert_with(component_id, Vec::new);
-                removed_components.push(self.entity);
+                    .get_or_insert_with(component_id, Events::default)
+                    .send(self.entity);
             }
             let remove_result = archetype.swap_remove(location.index);
             if let Some(swapped_entity) = remove_result.swapped_entity {
@@ -461,6 +667,98 @@ impl<'w> EntityMut<'w> {
     pub fn update_location(&mut self) {
         self.location = self.world.entities().get(self.entity).unwrap();
     }
+
+    /// Inserts the components required by the added components, then runs the `on_add` hooks of
+    /// the added components and the `on_insert` hooks of all the inserted components.
+    fn run_insert_hooks(&mut self, hooks: Vec<(ComponentId, ComponentHooks, bool)>) {
+        if hooks.is_empty() {
+            return;
+        }
+        for (component_id, component_hooks, added) in &hooks {
+            if let (Some(insert_required), true) = (component_hooks.insert_required, *added) {
+                insert_required(self.world, self.entity, *component_id);
+            }
+        }
+        for (component_id, component_hooks, added) in &hooks {
+            if let (Some(on_add), true) = (component_hooks.on_add, *added) {
+                on_add(self.world, self.entity, *component_id);
+            }
+        }
+        for (component_id, component_hooks, _) in &hooks {
+            if let Some(on_insert) = component_hooks.on_insert {
+                on_insert(self.world, self.entity, *component_id);
+            }
+        }
+        self.update_location_after_hooks();
+    }
+
+    fn run_remove_hooks(&mut self, hooks: Vec<(ComponentId, ComponentHook)>) {
+        if hooks.is_empty() {
+            return;
+        }
+        for (component_id, on_remove) in hooks {
+            // an earlier hook can have removed the component
+            if self
+                .world
+                .get_entity(self.entity)
+                .is_some_and(|entity| entity.contains_id(component_id))
+            {
+                on_remove(self.world, self.entity, component_id);
+            }
+        }
+        self.update_location_after_hooks();
+    }
+
+    /// Hooks can change the archetype of the entity
+    fn update_location_after_hooks(&mut self) {
+        self.location = self
+            .world
+            .entities
+            .get(self.entity)
+            .expect("a component hook despawned the entity it ran on");
+    }
+}
+
+/// The hooks of the components in `component_ids` with `on_add` or `on_insert` hooks, and whether
+/// each one is added to an entity of `archetype`, rather than replacing a previous value.
+fn insert_hooks(
+    components: &Components,
+    archetype: &Archetype,
+    component_ids: &[ComponentId],
+) -> Vec<(ComponentId, ComponentHooks, bool)> {
+    component_ids
+        .iter()
+        .filter_map(|&component_id| {
+            let hooks = *components.get_info(component_id)?.hooks();
+            if hooks.has_insert_hooks() {
+                Some((component_id, hooks, !archetype.contains(component_id)))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// The `on_remove` hooks of the components in `component_ids` that entities of `archetype` have.
+fn remove_hooks(
+    components: &Components,
+    archetype: &Archetype,
+    component_ids: impl Iterator<Item = ComponentId>,
+) -> Vec<(ComponentId, ComponentHook)> {
+    component_ids
+        .filter(|&component_id| archetype.contains(component_id))
+        .flat_map(|component_id| {
+            let hooks = components
+                .get_info(component_id)
+                .map(|info| *info.hooks())
+                .unwrap_or_default();
+            hooks
+                .on_remove
+                .into_iter()
+                .chain(hooks.store_removed)
+                .map(move |hook| (component_id, hook))
+        })
+        .collect()
 }
 
 // TODO: move to Storages?
@@ -468,7 +766,7 @@ impl<'w> EntityMut<'w> {
 /// `entity_location` must be within bounds of the given archetype and `entity` must exist inside
 /// the archetype
 #[inline]
-unsafe fn get_component(
+pub(crate) unsafe fn get_component(
     world: &World,
     component_id: ComponentId,
     entity: Entity,
@@ -493,11 +791,24 @@ unsafe fn get_component(
     }
 }
 
+/// Like [`get_component`], but returns `None` for invalid component ids.
+#[inline]
+fn get_component_by_id(
+    world: &World,
+    component_id: ComponentId,
+    entity: Entity,
+    location: EntityLocation,
+) -> Option<*const u8> {
+    world.components.get_info(component_id)?;
+    // SAFE: component_id exists, and the location of the entity is valid
+    unsafe { get_component(world, component_id, entity, location).map(|value| value as *const u8) }
+}
+
 // TODO: move to Storages?
 /// # Safety
 /// Caller must ensure that `component_id` is valid
 #[inline]
-unsafe fn get_component_and_ticks(
+pub(crate) unsafe fn get_component_and_ticks(
     world: &World,
     component_id: ComponentId,
     entity: Entity,
@@ -540,14 +851,15 @@ unsafe fn take_component(
     components: &Components,
     storages: &mut Storages,
     archetype: &Archetype,
-    removed_components: &mut SparseSet<ComponentId, Vec<Entity>>,
+    removed_components: &mut SparseSet<ComponentId, Events<Entity>>,
     component_id: ComponentId,
     entity: Entity,
     location: EntityLocation,
 ) -> *mut u8 {
     let component_info = components.get_info_unchecked(component_id);
-    let removed_components = removed_components.get_or_insert_with(component_id, Vec::new);
-    removed_components.push(entity);
+    removed_components
+        .get_or_insert_with(component_id, Events::default)
+        .send(entity);
     match component_info.storage_type() {
         StorageType::Table => {
             let table = &storages.tables[archetype.table_id()];
@@ -725,6 +1037,307 @@ fn sorted_remove<T: Eq + Ord + Copy>(source: &mut Vec<T>, remove: &[T]) {
 
 #[cfg(test)]
 mod tests {
+    use crate::{
+        component::{Component, ComponentDescriptor, ComponentHooks, StorageType, TableStorage},
+        event::Events,
+        world::{RemovedValue, World},
+    };
+    use std::alloc::Layout;
+
+    #[derive(Clone)]
+    struct Hooked(u32);
+
+    impl Component for Hooked {
+        type Storage = TableStorage;
+
+        fn register_component_hooks(hooks: &mut ComponentHooks) {
+            hooks
+                .on_add(|world, _, _| log(world, "add"))
+                .on_insert(|world, _, _| log(world, "insert"))
+                .on_remove(|world, entity, _| {
+                    // the value can still be read when it is removed
+                    let value = world.get::<Hooked>(entity).unwrap().0;
+                    world
+                        .get_resource_mut::<Vec<String>>()
+                        .unwrap()
+                        .push(format!("remove {}", value));
+                });
+        }
+    }
+
+    fn log(world: &mut World, event: &str) {
+        world
+            .get_resource_mut::<Vec<String>>()
+            .unwrap()
+            .push(event.to_string());
+    }
+
+    fn take_log(world: &mut World) -> Vec<String> {
+        std::mem::take(&mut *world.get_resource_mut::<Vec<String>>().unwrap())
+    }
+
+    #[test]
+    fn component_hooks() {
+        let mut world = World::default();
+        world.insert_resource(Vec::<String>::new());
+
+        let entity = world.spawn().insert(Hooked(1)).id();
+        assert_eq!(take_log(&mut world), vec!["add", "insert"]);
+        world.entity_mut(entity).insert(Hooked(2));
+        assert_eq!(take_log(&mut world), vec!["insert"]);
+        world.entity_mut(entity).remove::<Hooked>();
+        assert_eq!(take_log(&mut world), vec!["remove 2"]);
+
+        world.spawn_batch(vec![(Hooked(3),), (Hooked(4),)]);
+        assert_eq!(take_log(&mut world), vec!["add", "insert", "add", "insert"]);
+        let entity = world.spawn().insert(Hooked(5)).id();
+        take_log(&mut world);
+        world.despawn(entity);
+        assert_eq!(take_log(&mut world), vec!["remove 5"]);
+    }
+
+    #[test]
+    fn remove_partial_bundle_runs_no_hooks() {
+        struct Other;
+        impl Component for Other {
+            type Storage = TableStorage;
+        }
+
+        let mut world = World::default();
+        world.insert_resource(Vec::<String>::new());
+        world.store_removed_values::<Hooked>();
+
+        let entity = world.spawn().insert(Hooked(1)).id();
+        take_log(&mut world);
+        assert!(world
+            .entity_mut(entity)
+            .remove_bundle::<(Hooked, Other)>()
+            .is_none());
+        assert!(take_log(&mut world).is_empty());
+        assert_eq!(
+            world
+                .get_resource::<Events<RemovedValue<Hooked>>>()
+                .unwrap()
+                .iter_current_update_events()
+                .count(),
+            0
+        );
+        assert_eq!(world.get::<Hooked>(entity).unwrap().0, 1);
+    }
+
+    /// Removes `Hooked` from its entity when it is removed
+    struct RemovesHooked;
+
+    impl Component for RemovesHooked {
+        type Storage = TableStorage;
+
+        fn register_component_hooks(hooks: &mut ComponentHooks) {
+            hooks.on_remove(|world, entity, _| {
+                world.entity_mut(entity).remove::<Hooked>();
+            });
+        }
+    }
+
+    /// Inserts `Hooked(7)` in its entity when it is removed
+    struct InsertsHooked;
+
+    impl Component for InsertsHooked {
+        type Storage = TableStorage;
+
+        fn register_component_hooks(hooks: &mut ComponentHooks) {
+            hooks.on_remove(|world, entity, _| {
+                world.entity_mut(entity).insert(Hooked(7));
+            });
+        }
+    }
+
+    #[test]
+    fn remove_hooks_removing_components() {
+        let mut world = World::default();
+        world.insert_resource(Vec::<String>::new());
+
+        let entity = world.spawn().insert_bundle((RemovesHooked, Hooked(1))).id();
+        take_log(&mut world);
+        assert!(world
+            .entity_mut(entity)
+            .remove_bundle::<(RemovesHooked, Hooked)>()
+            .is_none());
+        // the hook of the removed component runs once
+        assert_eq!(take_log(&mut world), vec!["remove 1"]);
+        assert!(!world.entity(entity).contains::<RemovesHooked>());
+        assert!(!world.entity(entity).contains::<Hooked>());
+
+        let entity = world.spawn().insert_bundle((RemovesHooked, Hooked(2))).id();
+        take_log(&mut world);
+        world.despawn(entity);
+        assert_eq!(take_log(&mut world), vec!["remove 2"]);
+    }
+
+    #[test]
+    fn remove_hooks_inserting_components() {
+        let mut world = World::default();
+        world.insert_resource(Vec::<String>::new());
+
+        // the hooks didn't run for the inserted component, so it isn't removed
+        let entity = world.spawn().insert(InsertsHooked).id();
+        world
+            .entity_mut(entity)
+            .remove_bundle_intersection::<(InsertsHooked, Hooked)>();
+        assert_eq!(take_log(&mut world), vec!["add", "insert"]);
+        assert!(!world.entity(entity).contains::<InsertsHooked>());
+        assert_eq!(world.get::<Hooked>(entity).unwrap().0, 7);
+
+        // on despawn, the hooks of the inserted components run too
+        let entity = world.spawn().insert(InsertsHooked).id();
+        world.despawn(entity);
+        assert_eq!(take_log(&mut world), vec!["add", "insert", "remove 7"]);
+    }
+
+    #[test]
+    fn required_components() {
+        #[derive(Debug, Default, PartialEq)]
+        struct Transform(u32);
+        impl Component for Transform {
+            type Storage = TableStorage;
+        }
+        #[derive(Default)]
+        struct GlobalTransform;
+        impl Component for GlobalTransform {
+            type Storage = TableStorage;
+        }
+        struct AnimationTarget;
+        impl Component for AnimationTarget {
+            type Storage = TableStorage;
+        }
+
+        let mut world = World::default();
+        world.register_required_components::<AnimationTarget, Transform>();
+        world.register_required_components::<Transform, GlobalTransform>();
+
+        let entity = world.spawn().insert(AnimationTarget).id();
+        assert_eq!(world.get::<Transform>(entity), Some(&Transform(0)));
+        assert!(world.get::<GlobalTransform>(entity).is_some());
+
+        // required components inserted along with the component keep their value
+        let entity = world
+            .spawn()
+            .insert_bundle((AnimationTarget, Transform(3)))
+            .id();
+        assert_eq!(world.get::<Transform>(entity), Some(&Transform(3)));
+        assert!(world.get::<GlobalTransform>(entity).is_some());
+
+        let entities = world
+            .spawn_batch(vec![(AnimationTarget,), (AnimationTarget,)])
+            .collect::<Vec<_>>();
+        for entity in entities {
+            assert!(world.get::<GlobalTransform>(entity).is_some());
+        }
+    }
+
+    unsafe fn drop_u64(_: *mut u8) {}
+
+    fn dynamic_component(storage_type: StorageType) {
+        let mut world = World::default();
+        // SAFE: u64 needs no drop and is Send + Sync
+        let descriptor = unsafe {
+            ComponentDescriptor::new_dynamic(
+                "Counter",
+                storage_type,
+                Layout::new::<u64>(),
+                drop_u64,
+            )
+        };
+        let component_id = world.register_component(descriptor).unwrap();
+        assert_eq!(
+            world.components().get_info(component_id).unwrap().name(),
+            "Counter"
+        );
+
+        let mut value = 3u64;
+        let mut entity = world.spawn();
+        // SAFE: value is a valid u64, and is copied into the entity
+        unsafe {
+            entity.insert_by_id(component_id, &mut value as *mut u64 as *mut u8);
+        }
+        let id = entity.id();
+
+        let pointer = world.entity_mut(id).get_mut_by_id(component_id).unwrap();
+        // SAFE: the component was registered with the layout of u64
+        unsafe { *(pointer as *mut u64) += 1 };
+        let pointer = world.entity(id).get_by_id(component_id).unwrap();
+        assert_eq!(unsafe { *(pointer as *const u64) }, 4);
+
+        world.entity_mut(id).remove_by_id(component_id);
+        assert!(!world.entity(id).contains_id(component_id));
+        assert!(world.entity(id).get_by_id(component_id).is_none());
+    }
+
+    #[test]
+    fn dynamic_table_component() {
+        dynamic_component(StorageType::Table);
+    }
+
+    #[test]
+    fn dynamic_sparse_set_component() {
+        dynamic_component(StorageType::SparseSet);
+    }
+
+    #[test]
+    fn query_dynamic_components() {
+        struct Marker;
+        impl Component for Marker {
+            type Storage = TableStorage;
+        }
+
+        let mut world = World::default();
+        // SAFE: u64 needs no drop and is Send + Sync
+        let descriptor = unsafe {
+            ComponentDescriptor::new_dynamic(
+                "Counter",
+                StorageType::Table,
+                Layout::new::<u64>(),
+                drop_u64,
+            )
+        };
+        let counter_id = world.register_component(descriptor).unwrap();
+        let marker_id = world.init_component::<Marker>();
+        let spawn_counter = |world: &mut World, mut value: u64| {
+            let mut entity = world.spawn();
+            // SAFE: value is a valid u64, and is copied into the entity
+            unsafe { entity.insert_by_id(counter_id, &mut value as *mut u64 as *mut u8) };
+            entity.id()
+        };
+        let a = spawn_counter(&mut world, 1);
+        let b = spawn_counter(&mut world, 2);
+        world.entity_mut(b).insert(Marker);
+        world.spawn().insert(Marker);
+
+        world.for_each_mut_by_ids(&[counter_id], |_, values| {
+            // SAFE: the component was registered with the layout of u64
+            unsafe { *(values[0] as *mut u64) *= 10 };
+        });
+
+        let mut counters = Vec::new();
+        world.for_each_by_ids(&[counter_id], |entity, values| {
+            // SAFE: the component was registered with the layout of u64
+            counters.push((entity, unsafe { *(values[0] as *const u64) }));
+        });
+        counters.sort_by_key(|(_, value)| *value);
+        assert_eq!(counters, vec![(a, 10), (b, 20)]);
+
+        let mut matched = Vec::new();
+        world.for_each_by_ids(&[marker_id, counter_id], |entity, values| {
+            assert_eq!(values.len(), 2);
+            matched.push(entity);
+        });
+        assert_eq!(matched, vec![b]);
+
+        let unregistered_id = crate::component::ComponentId::new(1000);
+        world.for_each_by_ids(&[counter_id, unregistered_id], |_, _| {
+            panic!("unregistered components match no entity")
+        });
+    }
+
     #[test]
     fn sorted_remove() {
         let mut a = vec![1, 2, 3, 4, 5, 6, 7];
@@ -11,8 +11,12 @@ use crate::{
     archetype::{ArchetypeComponentId, ArchetypeComponentInfo, ArchetypeId, Archetypes},
     bundle::{Bundle, BundleInserter, BundleSpawner, Bundles},
     change_detection::Ticks,
-    component::{Component, ComponentId, ComponentTicks, Components, StorageType},
-    entity::{AllocAtWithoutReplacement, Entities, Entity},
+    component::{
+        Component, ComponentDescriptor, ComponentHooks, ComponentId, ComponentTicks, Components,
+        ComponentsError, RequiredComponent, StorageType,
+    },
+    entity::{AllocAtWithoutReplacement, Disabled, Entities, Entity, EntityLocation},
+    event::Events,
     query::{FilterFetch, QueryState, WorldQuery},
     storage::{Column, SparseSet, Storages},
     system::Resource,
@@ -75,7 +79,8 @@ pub struct World {
     pub(crate) archetypes: Archetypes,
     pub(crate) storages: Storages,
     pub(crate) bundles: Bundles,
-    pub(crate) removed_components: SparseSet<ComponentId, Vec<Entity>>,
+    /// The entities that had each component removed, kept for two [`World::clear_trackers`]
+    pub(crate) removed_components: SparseSet<ComponentId, Events<Entity>>,
     /// Access cache used by [WorldCell].
     pub(crate) archetype_component_access: ArchetypeComponentAccess,
     main_thread_validator: MainThreadValidator,
@@ -174,6 +179,67 @@ impl World {
         self.components.init_component::<T>(&mut self.storages)
     }
 
+    /// Registers a component described at runtime, such as one created with
+    /// [`ComponentDescriptor::new_dynamic`]. Values of this component can then be inserted,
+    /// accessed and removed with [`EntityMut::insert_by_id`], [`EntityMut::get_by_id`] and
+    /// [`EntityMut::remove_by_id`], and queried with [`World::for_each_by_ids`].
+    pub fn register_component(
+        &mut self,
+        descriptor: ComponentDescriptor,
+    ) -> Result<ComponentId, ComponentsError> {
+        self.components.add(descriptor, &mut self.storages)
+    }
+
+    /// Returns the [`ComponentHooks`] of the component `T`, to register hooks for a component
+    /// defined in another crate. Hooks only run for later changes, not for entities that already
+    /// have the component.
+    pub fn register_component_hooks<T: Component>(&mut self) -> &mut ComponentHooks {
+        let component_id = self.init_component::<T>();
+        self.components.get_hooks_mut(component_id).unwrap()
+    }
+
+    /// Declares that the component `R` is required by the component `T`: when `T` is added to an
+    /// entity that doesn't have an `R`, the default value of `R` is inserted along with it. Like
+    /// for `T`, the components required by `R` are then inserted too.
+    ///
+    /// This only affects later insertions of `T`, not the entities that already have it.
+    ///
+    /// ```
+    /// # use bevy_ecs::{component::Component, world::World};
+    /// #[derive(Component, Default)]
+    /// struct Transform;
+    /// #[derive(Component, Default)]
+    /// struct GlobalTransform;
+    /// #[derive(Component)]
+    /// struct AnimationTarget;
+    ///
+    /// let mut world = World::new();
+    /// world.register_required_components::<AnimationTarget, Transform>();
+    /// world.register_required_components::<AnimationTarget, GlobalTransform>();
+    /// let entity = world.spawn().insert(AnimationTarget).id();
+    /// assert!(world.get::<Transform>(entity).is_some());
+    /// assert!(world.get::<GlobalTransform>(entity).is_some());
+    /// ```
+    pub fn register_required_components<T: Component, R: Component + Default>(&mut self) {
+        let component_id = self.init_component::<T>();
+        let required_id = self.init_component::<R>();
+        self.components
+            .add_required_component(component_id, RequiredComponent::new::<R>(required_id));
+        self.components
+            .get_hooks_mut(component_id)
+            .unwrap()
+            .insert_required = Some(insert_required_components);
+    }
+
+    /// Returns the [`ComponentHooks`] of the component `component_id`, which can be a component
+    /// registered at runtime, or `None` if no such component exists.
+    pub fn register_component_hooks_by_id(
+        &mut self,
+        component_id: ComponentId,
+    ) -> Option<&mut ComponentHooks> {
+        self.components.get_hooks_mut(component_id)
+    }
+
     /// Retrieves an [EntityRef] that exposes read-only operations for the given `entity`.
     /// This will panic if the `entity` does not exist. Use [World::get_entity] if you want
     /// to check for entity existence instead of implicitly panic-ing.
@@ -461,10 +527,12 @@ impl World {
             .unwrap_or(false)
     }
 
-    /// Clears component tracker state
+    /// Clears component tracker state. Removed components are kept until the next call, so that
+    /// [`RemovedComponents`](crate::system::RemovedComponents) readers running once per frame
+    /// see every removal, whatever stage they run in.
     pub fn clear_trackers(&mut self) {
         for entities in self.removed_components.values_mut() {
-            entities.clear();
+            entities.update();
         }
 
         self.last_change_tick = self.increment_change_tick();
@@ -564,26 +632,120 @@ impl World {
         QueryState::new(self)
     }
 
+    /// Calls `f` with each entity that has all the components in `component_ids`, along with
+    /// pointers to the values of these components in the same order. Unlike [World::query], the
+    /// components don't need a rust type, so this can query the components registered at runtime
+    /// with [World::register_component]. Unregistered component ids match no entity.
+    ///
+    /// Like queries, this skips [Disabled] entities, unless `component_ids` contains the id of
+    /// [Disabled].
+    pub fn for_each_by_ids(
+        &self,
+        component_ids: &[ComponentId],
+        mut f: impl FnMut(Entity, &[*const u8]),
+    ) {
+        let mut values = Vec::with_capacity(component_ids.len());
+        self.for_each_location_by_ids(component_ids, |entity, location| {
+            values.clear();
+            for &component_id in component_ids {
+                // SAFE: component_id exists, and the location of the entity is valid
+                let value = unsafe { get_component(self, component_id, entity, location) };
+                values.push(value.unwrap() as *const u8);
+            }
+            f(entity, &values);
+        });
+    }
+
+    /// Like [World::for_each_by_ids], but the values can be mutated through the pointers. The
+    /// components of every matching entity are marked as changed.
+    pub fn for_each_mut_by_ids(
+        &mut self,
+        component_ids: &[ComponentId],
+        mut f: impl FnMut(Entity, &[*mut u8]),
+    ) {
+        let change_tick = self.change_tick();
+        let mut values = Vec::with_capacity(component_ids.len());
+        self.for_each_location_by_ids(component_ids, |entity, location| {
+            values.clear();
+            for &component_id in component_ids {
+                // SAFE: world access is unique, component_id exists, and the location of the
+                // entity is valid
+                unsafe {
+                    let (value, ticks) =
+                        get_component_and_ticks(self, component_id, entity, location).unwrap();
+                    (*ticks).set_changed(change_tick);
+                    values.push(value);
+                }
+            }
+            f(entity, &values);
+        });
+    }
+
+    fn for_each_location_by_ids(
+        &self,
+        component_ids: &[ComponentId],
+        mut f: impl FnMut(Entity, EntityLocation),
+    ) {
+        if component_ids
+            .iter()
+            .any(|&component_id| self.components.get_info(component_id).is_none())
+        {
+            return;
+        }
+        let disabled = self
+            .components
+            .get_id(TypeId::of::<Disabled>())
+            .filter(|disabled_id| !component_ids.contains(disabled_id));
+        for archetype in self.archetypes.iter() {
+            if !component_ids
+                .iter()
+                .all(|&component_id| archetype.contains(component_id))
+                || disabled.is_some_and(|disabled_id| archetype.contains(disabled_id))
+            {
+                continue;
+            }
+            for (index, &entity) in archetype.entities().iter().enumerate() {
+                let location = EntityLocation {
+                    archetype_id: archetype.id(),
+                    index,
+                };
+                f(entity, location);
+            }
+        }
+    }
+
     /// Returns an iterator of entities that had components of type `T` removed
     /// since the last call to [World::clear_trackers].
-    pub fn removed<T: Component>(&self) -> std::iter::Cloned<std::slice::Iter<'_, Entity>> {
-        if let Some(component_id) = self.components.get_id(TypeId::of::<T>()) {
-            self.removed_with_id(component_id)
-        } else {
-            [].iter().cloned()
-        }
+    pub fn removed<T: Component>(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.components
+            .get_id(TypeId::of::<T>())
+            .into_iter()
+            .flat_map(move |component_id| self.removed_with_id(component_id))
     }
 
     /// Returns an iterator of entities that had components with the given `component_id` removed
     /// since the last call to [World::clear_trackers].
-    pub fn removed_with_id(
-        &self,
-        component_id: ComponentId,
-    ) -> std::iter::Cloned<std::slice::Iter<'_, Entity>> {
-        if let Some(removed) = self.removed_components.get(component_id) {
-            removed.iter().cloned()
-        } else {
-            [].iter().cloned()
+    pub fn removed_with_id(&self, component_id: ComponentId) -> impl Iterator<Item = Entity> + '_ {
+        self.removed_components
+            .get(component_id)
+            .into_iter()
+            .flat_map(|removed| removed.iter_current_update_events().copied())
+    }
+
+    /// Sends the values of the `T` components removed from entities, including by despawning
+    /// them, as [`RemovedValue<T>`] events. They can then be read with an
+    /// [`EventReader`](crate::event::EventReader), for example to free the resources a component
+    /// owns.
+    ///
+    /// This inserts the [`Events<RemovedValue<T>>`] resource if it doesn't exist yet, which must
+    /// be updated once per frame like other events.
+    pub fn store_removed_values<T: Component + Clone>(&mut self) {
+        if !self.contains_resource::<Events<RemovedValue<T>>>() {
+            self.insert_resource(Events::<RemovedValue<T>>::default());
+        }
+        let hooks = self.register_component_hooks::<T>();
+        if hooks.store_removed.is_none() {
+            hooks.store_removed = Some(store_removed_value::<T>);
         }
     }
 
@@ -606,6 +768,16 @@ impl World {
         unsafe { self.insert_resource_with_id(component_id, value) };
     }
 
+    /// Initializes a non-send resource with its [`FromWorld`] implementation, if it doesn't exist
+    /// yet. Like every access to non-send resources, this must happen on the main thread, for
+    /// example in an exclusive system.
+    pub fn init_non_send_resource<T: FromWorld + 'static>(&mut self) {
+        if self.get_non_send_resource::<T>().is_none() {
+            let resource = T::from_world(self);
+            self.insert_non_send(resource);
+        }
+    }
+
     /// Removes the resource of a given type and returns it, if it exists. Otherwise returns [None].
     /// Resources are "unique" data of a given type.
     #[inline]
@@ -800,6 +972,23 @@ impl World {
         let bundle_info = self
             .bundles
             .init_info::<B>(&mut self.components, &mut self.storages);
+        if bundle_info.has_insert_hooks(&self.components) {
+            // hooks need access to the world, so the entities are inserted one by one
+            let mut invalid_entities = Vec::new();
+            for (entity, bundle) in iter {
+                match self.get_or_spawn(entity) {
+                    Some(mut entity_mut) => {
+                        entity_mut.insert_bundle(bundle);
+                    }
+                    None => invalid_entities.push(entity),
+                }
+            }
+            return if invalid_entities.is_empty() {
+                Ok(())
+            } else {
+                Err(invalid_entities)
+            };
+        }
         enum SpawnOrInsert<'a, 'b> {
             Spawn(BundleSpawner<'a, 'b>),
             Insert(BundleInserter<'a, 'b>, ArchetypeId),
@@ -884,6 +1073,108 @@ impl World {
         }
     }
 
+    /// For a given batch of ([Entity], [Bundle]) pairs, inserts the [Bundle] into each existing
+    /// [Entity]. Returns the entities that don't exist, which are skipped.
+    ///
+    /// This is faster than inserting the bundles one by one: the entities are moved to their new
+    /// archetype in bulk, and the archetype transition is only looked up again when an entity
+    /// isn't in the same archetype as the previous one.
+    ///
+    /// ```
+    /// use bevy_ecs::{entity::Entity, world::World, component::Component};
+    /// #[derive(Component, PartialEq, Debug)]
+    /// struct Velocity(f32);
+    ///
+    /// let mut world = World::new();
+    /// let entities = (0..1000).map(|_| world.spawn().id()).collect::<Vec<_>>();
+    /// world
+    ///     .insert_batch(entities.iter().map(|entity| (*entity, (Velocity(0.0),))))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(world.get::<Velocity>(entities[0]), Some(&Velocity(0.0)));
+    /// ```
+    pub fn insert_batch<I, B>(&mut self, iter: I) -> Result<(), Vec<Entity>>
+    where
+        I: IntoIterator,
+        I::IntoIter: Iterator<Item = (Entity, B)>,
+        B: Bundle,
+    {
+        self.flush();
+
+        let mut iter = iter.into_iter();
+        let change_tick = *self.change_tick.get_mut();
+
+        let bundle_info = self
+            .bundles
+            .init_info::<B>(&mut self.components, &mut self.storages);
+        let mut invalid_entities = Vec::new();
+        if bundle_info.has_insert_hooks(&self.components) {
+            // hooks need access to the world, so the entities are inserted one by one
+            for (entity, bundle) in iter {
+                match self.get_entity_mut(entity) {
+                    Some(mut entity_mut) => {
+                        entity_mut.insert_bundle(bundle);
+                    }
+                    None => invalid_entities.push(entity),
+                }
+            }
+        } else {
+            let mut first = None;
+            for (entity, bundle) in &mut iter {
+                match self.entities.get(entity) {
+                    Some(location) => {
+                        first = Some((entity, bundle, location));
+                        break;
+                    }
+                    None => invalid_entities.push(entity),
+                }
+            }
+            if let Some((entity, bundle, location)) = first {
+                // the inserter is only replaced when an entity is in another archetype than the
+                // previous one
+                let mut archetype_id = location.archetype_id;
+                let mut inserter = bundle_info.get_bundle_inserter(
+                    &mut self.entities,
+                    &mut self.archetypes,
+                    &mut self.components,
+                    &mut self.storages,
+                    archetype_id,
+                    change_tick,
+                );
+                // SAFE: `entity` is valid, `location` matches entity, bundle matches inserter
+                unsafe { inserter.insert(entity, location.index, bundle) };
+                for (entity, bundle) in iter {
+                    let location = match inserter.entities.get(entity) {
+                        Some(location) => location,
+                        None => {
+                            invalid_entities.push(entity);
+                            continue;
+                        }
+                    };
+                    if location.archetype_id != archetype_id {
+                        archetype_id = location.archetype_id;
+                        inserter = bundle_info.get_bundle_inserter(
+                            &mut self.entities,
+                            &mut self.archetypes,
+                            &mut self.components,
+                            &mut self.storages,
+                            archetype_id,
+                            change_tick,
+                        );
+                    }
+                    // SAFE: `entity` is valid, `location` matches entity, bundle matches inserter
+                    unsafe { inserter.insert(entity, location.index, bundle) };
+                }
+            }
+        }
+
+        if invalid_entities.is_empty() {
+            Ok(())
+        } else {
+            Err(invalid_entities)
+        }
+    }
+
     /// Temporarily removes the requested resource from this [World], then re-adds it before
     /// returning. This enables safe mutable access to a resource while still providing mutable
     /// world access
@@ -1140,6 +1431,41 @@ impl World {
     }
 }
 
+/// A component removed from `entity`, sent as an event for the components registered with
+/// [`World::store_removed_values`].
+#[derive(Debug, Clone)]
+pub struct RemovedValue<T> {
+    pub entity: Entity,
+    pub value: T,
+}
+
+fn store_removed_value<T: Component + Clone>(
+    world: &mut World,
+    entity: Entity,
+    _component_id: ComponentId,
+) {
+    // the hook runs before the component is removed
+    let value = world.get::<T>(entity).unwrap().clone();
+    if let Some(mut events) = world.get_resource_mut::<Events<RemovedValue<T>>>() {
+        events.send(RemovedValue { entity, value });
+    }
+}
+
+fn insert_required_components(world: &mut World, entity: Entity, component_id: ComponentId) {
+    let required_components = world
+        .components
+        .get_info(component_id)
+        .unwrap()
+        .required_components()
+        .to_vec();
+    for required in required_components {
+        // the required component may have been part of the inserted bundle
+        if !world.entity(entity).contains_id(required.id()) {
+            (required.insert_default)(world, entity);
+        }
+    }
+}
+
 impl fmt::Debug for World {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("World")
@@ -10,7 +10,14 @@ where
     I::Item: Bundle,
 {
     inner: I,
-    spawner: BundleSpawner<'w, 'w>,
+    spawner: BatchSpawner<'w>,
+}
+
+enum BatchSpawner<'w> {
+    Spawner(BundleSpawner<'w, 'w>),
+    /// The bundle has component hooks, which need access to the world, so entities are spawned
+    /// one by one
+    WithHooks(&'w mut World),
 }
 
 impl<'w, I> SpawnBatchIter<'w, I>
@@ -27,6 +34,16 @@ where
         let (lower, upper) = iter.size_hint();
         let length = upper.unwrap_or(lower);
 
+        let has_hooks = world
+            .bundles
+            .init_info::<I::Item>(&mut world.components, &mut world.storages)
+            .has_insert_hooks(&world.components);
+        if has_hooks {
+            return Self {
+                inner: iter,
+                spawner: BatchSpawner::WithHooks(world),
+            };
+        }
         let bundle_info = world
             .bundles
             .init_info::<I::Item>(&mut world.components, &mut world.storages);
@@ -42,7 +59,7 @@ where
 
         Self {
             inner: iter,
-            spawner,
+            spawner: BatchSpawner::Spawner(spawner),
         }
     }
 }
@@ -66,8 +83,11 @@ where
 
     fn next(&mut self) -> Option<Entity> {
         let bundle = self.inner.next()?;
-        // SAFE: bundle matches spawner type
-        unsafe { Some(self.spawner.spawn(bundle)) }
+        match &mut self.spawner {
+            // SAFE: bundle matches spawner type
+            BatchSpawner::Spawner(spawner) => unsafe { Some(spawner.spawn(bundle)) },
+            BatchSpawner::WithHooks(world) => Some(world.spawn().insert_bundle(bundle).id()),
+        }
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
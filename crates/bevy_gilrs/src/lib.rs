@@ -1,10 +1,12 @@
 mod converter;
 mod gilrs_system;
+mod rumble;
 
 use bevy_app::{App, CoreStage, Plugin, StartupStage};
 use bevy_utils::tracing::error;
 use gilrs::GilrsBuilder;
 use gilrs_system::{gilrs_event_startup_system, gilrs_event_system};
+use rumble::{gilrs_rumble_system, RunningRumbles};
 
 #[derive(Default)]
 pub struct GilrsPlugin;
@@ -18,11 +20,13 @@ impl Plugin for GilrsPlugin {
         {
             Ok(gilrs) => {
                 app.insert_non_send_resource(gilrs)
+                    .init_non_send_resource::<RunningRumbles>()
                     .add_startup_system_to_stage(
                         StartupStage::PreStartup,
                         gilrs_event_startup_system,
                     )
-                    .add_system_to_stage(CoreStage::PreUpdate, gilrs_event_system);
+                    .add_system_to_stage(CoreStage::PreUpdate, gilrs_event_system)
+                    .add_system_to_stage(CoreStage::PostUpdate, gilrs_rumble_system);
             }
             Err(err) => error!("Failed to start Gilrs. {}", err),
         }
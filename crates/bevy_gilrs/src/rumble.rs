@@ -0,0 +1,168 @@
+use crate::converter::convert_gamepad_id;
+use bevy_app::EventReader;
+use bevy_ecs::system::NonSendMut;
+use bevy_input::gamepad::{Gamepad, GamepadRumbleRequest};
+use bevy_utils::{tracing::warn, HashMap, Instant};
+use gilrs::{
+    ff::{self, BaseEffect, BaseEffectType, EffectBuilder, Repeat, Replay, Ticks},
+    GamepadId, Gilrs,
+};
+
+/// The force feedback effects playing on the gamepads. Effects stop when they're dropped, so
+/// they are kept until they end.
+pub struct RunningRumbles<E = ff::Effect> {
+    effects: HashMap<Gamepad, (E, Instant)>,
+}
+
+impl<E> Default for RunningRumbles<E> {
+    fn default() -> Self {
+        RunningRumbles {
+            effects: Default::default(),
+        }
+    }
+}
+
+impl<E> RunningRumbles<E> {
+    /// Drops the effects that ended at `now`
+    fn remove_finished(&mut self, now: Instant) {
+        self.effects.retain(|_, (_, end)| *end > now);
+    }
+
+    /// Stops the effect of the gamepad of `request`, then keeps the effect started by `play`
+    /// until the end of the request. Nothing is played for requests with a zero duration.
+    fn request(
+        &mut self,
+        request: &GamepadRumbleRequest,
+        now: Instant,
+        play: impl FnOnce() -> Option<E>,
+    ) {
+        self.effects.remove(&request.gamepad);
+        if request.duration.as_millis() == 0 {
+            return;
+        }
+        if let Some(effect) = play() {
+            self.effects
+                .insert(request.gamepad, (effect, now + request.duration));
+        }
+    }
+}
+
+pub fn gilrs_rumble_system(
+    mut gilrs: NonSendMut<Gilrs>,
+    mut running_rumbles: NonSendMut<RunningRumbles>,
+    mut requests: EventReader<GamepadRumbleRequest>,
+) {
+    let now = Instant::now();
+    running_rumbles.remove_finished(now);
+
+    for request in requests.iter() {
+        let id = match gilrs
+            .gamepads()
+            .find(|(id, _)| convert_gamepad_id(*id) == request.gamepad)
+        {
+            Some((id, gamepad)) if gamepad.is_ff_supported() => id,
+            _ => continue,
+        };
+        running_rumbles.request(request, now, || {
+            match play_rumble(&mut gilrs, id, request) {
+                Ok(effect) => Some(effect),
+                Err(err) => {
+                    warn!("Failed to play a rumble on {:?}. {}", request.gamepad, err);
+                    None
+                }
+            }
+        });
+    }
+}
+
+fn play_rumble(
+    gilrs: &mut Gilrs,
+    id: GamepadId,
+    request: &GamepadRumbleRequest,
+) -> Result<ff::Effect, ff::Error> {
+    let duration = Ticks::from_ms(request.duration.as_millis().min(u32::MAX as u128) as u32);
+    let scheduling = Replay {
+        play_for: duration,
+        ..Default::default()
+    };
+    let magnitude = |intensity: f32| (intensity.clamp(0.0, 1.0) * u16::MAX as f32) as u16;
+    let effect = EffectBuilder::new()
+        .add_effect(BaseEffect {
+            kind: BaseEffectType::Strong {
+                magnitude: magnitude(request.strong),
+            },
+            scheduling,
+            ..Default::default()
+        })
+        .add_effect(BaseEffect {
+            kind: BaseEffectType::Weak {
+                magnitude: magnitude(request.weak),
+            },
+            scheduling,
+            ..Default::default()
+        })
+        .repeat(Repeat::For(duration))
+        .gamepads(&[id])
+        .finish(gilrs)?;
+    effect.play()?;
+    Ok(effect)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_utils::Duration;
+    use std::rc::Rc;
+
+    fn rumble(gamepad: usize, millis: u64) -> GamepadRumbleRequest {
+        GamepadRumbleRequest::new(Gamepad(gamepad), 1.0, 0.5, Duration::from_millis(millis))
+    }
+
+    #[test]
+    fn rumble_expiry() {
+        let mut running_rumbles = RunningRumbles::default();
+        let start = Instant::now();
+        let effect = Rc::new(());
+        running_rumbles.request(&rumble(0, 100), start, || Some(effect.clone()));
+        assert_eq!(Rc::strong_count(&effect), 2);
+
+        running_rumbles.remove_finished(start + Duration::from_millis(99));
+        assert_eq!(Rc::strong_count(&effect), 2);
+        // the effect is dropped, which stops it, once it ended
+        running_rumbles.remove_finished(start + Duration::from_millis(100));
+        assert_eq!(Rc::strong_count(&effect), 1);
+        assert!(running_rumbles.effects.is_empty());
+    }
+
+    #[test]
+    fn rumble_requests() {
+        let mut running_rumbles = RunningRumbles::default();
+        let now = Instant::now();
+        let first = Rc::new(());
+        let second = Rc::new(());
+        let other_gamepad = Rc::new(());
+        running_rumbles.request(&rumble(0, 100), now, || Some(first.clone()));
+        running_rumbles.request(&rumble(1, 100), now, || Some(other_gamepad.clone()));
+
+        // a request replaces the rumble of its gamepad only
+        running_rumbles.request(&rumble(0, 500), now, || Some(second.clone()));
+        assert_eq!(Rc::strong_count(&first), 1);
+        assert_eq!(Rc::strong_count(&second), 2);
+        assert_eq!(Rc::strong_count(&other_gamepad), 2);
+        running_rumbles.remove_finished(now + Duration::from_millis(200));
+        assert_eq!(Rc::strong_count(&second), 2);
+        assert_eq!(Rc::strong_count(&other_gamepad), 1);
+
+        // and stop requests play nothing
+        running_rumbles.request(&GamepadRumbleRequest::stop(Gamepad(0)), now, || {
+            panic!("played a stop request")
+        });
+        assert_eq!(Rc::strong_count(&second), 1);
+
+        // a rumble that fails to play still stops the previous one
+        running_rumbles.request(&rumble(1, 100), now, || Some(other_gamepad.clone()));
+        running_rumbles.request(&rumble(1, 100), now, || None);
+        assert_eq!(Rc::strong_count(&other_gamepad), 1);
+        assert!(running_rumbles.effects.is_empty());
+    }
+}
@@ -1,12 +1,13 @@
 use anyhow::Result;
 use bevy_animation_rig::{SkinnedMesh, SkinnedMeshInverseBindposes, SKINNED_MESH_PIPELINE_HANDLE};
 use bevy_asset::{
-    AssetIoError, AssetLoader, AssetPath, BoxedFuture, Handle, LoadContext, LoadedAsset,
+    AssetIoError, AssetLoader, AssetPath, BoxedFuture, Handle, ImportSettingsError, LoadContext,
+    LoadedAsset,
 };
 use bevy_core::Name;
 use bevy_ecs::{entity::Entity, world::World};
 use bevy_log::warn;
-use bevy_math::Mat4;
+use bevy_math::{Mat4, Vec3};
 use bevy_pbr::{
     prelude::{PbrBundle, StandardMaterial},
     render_graph::PBR_PIPELINE_HANDLE,
@@ -31,6 +32,7 @@ use gltf::{
     texture::{MagFilter, MinFilter, WrappingMode},
     Material, Primitive,
 };
+use serde::Deserialize;
 use std::{
     collections::{HashMap, HashSet},
     path::Path,
@@ -58,6 +60,23 @@ pub enum GltfError {
     ImageError(#[from] TextureError),
     #[error("failed to load an asset path: {0}")]
     AssetIoError(#[from] AssetIoError),
+    #[error("{0}")]
+    ImportSettingsError(#[from] ImportSettingsError),
+}
+
+/// The import settings of the GLTF files loaded by the [`GltfLoader`], read from the `.meta` file
+/// next to a GLTF file, like a `model.gltf.meta` file containing `(scale: 0.01)`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GltfSettings {
+    /// The scale of the root entity of the loaded scenes, for models authored in other units
+    pub scale: f32,
+}
+
+impl Default for GltfSettings {
+    fn default() -> Self {
+        GltfSettings { scale: 1.0 }
+    }
 }
 
 /// Loads meshes from GLTF files into Mesh assets
@@ -82,6 +101,7 @@ async fn load_gltf<'a, 'b>(
     bytes: &'a [u8],
     load_context: &'a mut LoadContext<'b>,
 ) -> Result<(), GltfError> {
+    let settings: GltfSettings = load_context.import_settings()?;
     let gltf = gltf::Gltf::from_slice(bytes)?;
     let buffer_data = load_buffers(&gltf, load_context, load_context.path()).await?;
 
@@ -89,7 +109,7 @@ async fn load_gltf<'a, 'b>(
     let mut named_materials = HashMap::new();
     let mut linear_textures = HashSet::new();
     for material in gltf.materials() {
-        let handle = load_material(&material, load_context);
+        let handle = load_material(&gltf, &material, load_context);
         if let Some(name) = material.name() {
             named_materials.insert(name.to_string(), handle.clone());
         }
@@ -107,6 +127,15 @@ async fn load_gltf<'a, 'b>(
             linear_textures.insert(texture.texture().index());
         }
     }
+    // The default material is not explicitly listed in the gltf, so it has to be loaded separately
+    // if any primitive uses it.
+    if let Some(primitive) = gltf
+        .meshes()
+        .flat_map(|mesh| mesh.primitives())
+        .find(|primitive| primitive.material().index().is_none())
+    {
+        load_material(&gltf, &primitive.material(), load_context);
+    }
 
     let mut meshes = vec![];
     let mut named_meshes = HashMap::new();
@@ -418,7 +447,10 @@ async fn load_gltf<'a, 'b>(
 
         world
             .spawn()
-            .insert_bundle((Transform::identity(), GlobalTransform::identity()))
+            .insert_bundle((
+                Transform::from_scale(Vec3::splat(settings.scale)),
+                GlobalTransform::identity(),
+            ))
             .with_children(|parent| {
                 for node in scene.nodes() {
                     let result = load_node(
@@ -567,7 +599,11 @@ async fn load_texture<'a>(
     Ok((texture, texture_label(&gltf_texture)))
 }
 
-fn load_material(material: &Material, load_context: &mut LoadContext) -> Handle<StandardMaterial> {
+fn load_material(
+    gltf: &gltf::Gltf,
+    material: &Material,
+    load_context: &mut LoadContext,
+) -> Handle<StandardMaterial> {
     let material_label = material_label(material);
 
     let pbr = material.pbr_metallic_roughness();
@@ -636,6 +672,7 @@ fn load_material(material: &Material, load_context: &mut LoadContext) -> Handle<
             emissive: Color::rgba(emissive[0], emissive[1], emissive[2], 1.0),
             emissive_texture,
             unlit: material.unlit(),
+            vertex_colors: uses_vertex_colors(gltf, material),
             ..Default::default()
         }),
     )
@@ -735,13 +772,6 @@ fn load_node(
                 let material = primitive.material();
                 let material_label = material_label(&material);
 
-                // This will make sure we load the default material now since it would not have been
-                // added when iterating over all the gltf materials (since the default material is
-                // not explicitly listed in the gltf).
-                if !load_context.has_labeled_asset(&material_label) {
-                    load_material(&material, load_context);
-                }
-
                 let mut node = parent.spawn();
 
                 let mut pipeline = PBR_PIPELINE_HANDLE.typed();
@@ -803,6 +833,18 @@ fn primitive_label(mesh: &gltf::Mesh, primitive: &Primitive) -> String {
     format!("Mesh{}/Primitive{}", mesh.index(), primitive.index())
 }
 
+/// Vertex colors are only enabled if every primitive using the material has them, as the
+/// material's shader requires them from all of its meshes.
+fn uses_vertex_colors(gltf: &gltf::Gltf, material: &Material) -> bool {
+    let mut primitives = gltf
+        .meshes()
+        .flat_map(|mesh| mesh.primitives())
+        .filter(|primitive| primitive.material().index() == material.index())
+        .peekable();
+    primitives.peek().is_some()
+        && primitives.all(|primitive| primitive.get(&gltf::Semantic::Colors(0)).is_some())
+}
+
 fn material_label(material: &gltf::Material) -> String {
     if let Some(index) = material.index() {
         format!("Material{}", index)
@@ -1021,7 +1063,7 @@ impl<'a> DataUri<'a> {
 
 #[cfg(test)]
 mod test {
-    use super::resolve_node_hierarchy;
+    use super::{resolve_node_hierarchy, uses_vertex_colors};
     use crate::GltfNode;
 
     impl GltfNode {
@@ -1120,4 +1162,47 @@ mod test {
         assert_eq!(result[0].0, "l2");
         assert_eq!(result[0].1.children.len(), 0);
     }
+
+    #[test]
+    fn material_vertex_colors() {
+        // material 0 is used by a primitive without colors, material 1 by colored primitives only,
+        // material 2 by nothing, and the default material by a colored primitive
+        let gltf = gltf::Gltf::from_slice(
+            br#"{
+                "asset": { "version": "2.0" },
+                "buffers": [{ "byteLength": 84 }],
+                "bufferViews": [{ "buffer": 0, "byteLength": 84 }],
+                "accessors": [
+                    {
+                        "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                        "min": [0, 0, 0], "max": [1, 1, 0]
+                    },
+                    {
+                        "bufferView": 0, "byteOffset": 36, "componentType": 5126, "count": 3,
+                        "type": "VEC4"
+                    }
+                ],
+                "materials": [{}, {}, {}],
+                "meshes": [
+                    { "primitives": [
+                        { "attributes": { "POSITION": 0, "COLOR_0": 1 }, "material": 0 },
+                        { "attributes": { "POSITION": 0 }, "material": 0 },
+                        { "attributes": { "POSITION": 0, "COLOR_0": 1 }, "material": 1 }
+                    ] },
+                    { "primitives": [
+                        { "attributes": { "POSITION": 0, "COLOR_0": 1 }, "material": 1 },
+                        { "attributes": { "POSITION": 0, "COLOR_0": 1 } }
+                    ] }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let materials = gltf.materials().collect::<Vec<_>>();
+        assert!(!uses_vertex_colors(&gltf, &materials[0]));
+        assert!(uses_vertex_colors(&gltf, &materials[1]));
+        assert!(!uses_vertex_colors(&gltf, &materials[2]));
+        let default_material = gltf.meshes().nth(1).unwrap().primitives().nth(1).unwrap();
+        assert!(uses_vertex_colors(&gltf, &default_material.material()));
+    }
 }
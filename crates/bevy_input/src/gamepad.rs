@@ -1,7 +1,7 @@
 use crate::{Axis, Input};
 use bevy_app::{EventReader, EventWriter};
 use bevy_ecs::system::{Res, ResMut};
-use bevy_utils::HashMap;
+use bevy_utils::{Duration, HashMap};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
@@ -24,6 +24,36 @@ pub struct GamepadEvent(pub Gamepad, pub GamepadEventType);
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct GamepadEventRaw(pub Gamepad, pub GamepadEventType);
 
+/// Makes a gamepad vibrate, for the haptic feedback of hits and explosions. A request replaces
+/// the vibration the gamepad is playing, and a request with a zero duration stops it. Requests
+/// are ignored by gamepads that don't support force feedback.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct GamepadRumbleRequest {
+    pub gamepad: Gamepad,
+    /// The intensity of the low frequency motor, from 0 to 1
+    pub strong: f32,
+    /// The intensity of the high frequency motor, from 0 to 1
+    pub weak: f32,
+    pub duration: Duration,
+}
+
+impl GamepadRumbleRequest {
+    pub fn new(gamepad: Gamepad, strong: f32, weak: f32, duration: Duration) -> Self {
+        GamepadRumbleRequest {
+            gamepad,
+            strong,
+            weak,
+            duration,
+        }
+    }
+
+    /// Stops the vibration of the gamepad.
+    pub fn stop(gamepad: Gamepad) -> Self {
+        GamepadRumbleRequest::new(gamepad, 0.0, 0.0, Duration::default())
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum GamepadButtonType {
@@ -69,6 +99,22 @@ pub enum GamepadAxisType {
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct GamepadAxis(pub Gamepad, pub GamepadAxisType);
 
+/// How the raw values of gamepads are filtered into the [`Input`] and [`Axis`] resources. It can
+/// be changed at runtime, from the input settings of a game for example.
+///
+/// ```
+/// # use bevy_input::gamepad::{AxisCurve, GamepadAxis, GamepadAxisType, GamepadSettings};
+/// # use bevy_input::prelude::*;
+/// # use bevy_ecs::prelude::*;
+/// fn invert_look(mut settings: ResMut<GamepadSettings>) {
+///     let axis = GamepadAxis(Gamepad(0), GamepadAxisType::RightStickY);
+///     let axis_settings = settings.axis_settings_mut(axis);
+///     axis_settings.inverted = true;
+///     axis_settings.curve = AxisCurve::Power(2.0);
+///     axis_settings.set_deadzone(0.15);
+/// }
+/// # invert_look.system();
+/// ```
 #[derive(Default, Debug)]
 pub struct GamepadSettings {
     pub default_button_settings: ButtonSettings,
@@ -92,6 +138,14 @@ impl GamepadSettings {
             .unwrap_or(&self.default_axis_settings)
     }
 
+    /// The settings of an axis, starting from the default axis settings if it has none yet.
+    pub fn axis_settings_mut(&mut self, axis: GamepadAxis) -> &mut AxisSettings {
+        let default_axis_settings = &self.default_axis_settings;
+        self.axis_settings
+            .entry(axis)
+            .or_insert_with(|| default_axis_settings.clone())
+    }
+
     pub fn get_button_axis_settings(&self, button: GamepadButton) -> &ButtonAxisSettings {
         self.button_axis_settings
             .get(&button)
@@ -124,6 +178,10 @@ impl ButtonSettings {
     }
 }
 
+/// How the raw values of an axis are filtered. The raw values are first calibrated, then values
+/// between `negative_low` and `positive_low`, the deadzone, are set to 0, values beyond
+/// `positive_high` and `negative_high` are set to 1 and -1, and the curve and the inversion are
+/// applied. Changes smaller than `threshold` are ignored.
 #[derive(Debug, Clone)]
 pub struct AxisSettings {
     pub positive_high: f32,
@@ -131,6 +189,59 @@ pub struct AxisSettings {
     pub negative_high: f32,
     pub negative_low: f32,
     pub threshold: f32,
+    pub curve: AxisCurve,
+    pub inverted: bool,
+    /// The travel of the axis, to scale raw values to the full range, see [`GamepadCalibration`]
+    pub calibration: Option<AxisCalibration>,
+}
+
+/// The response curve of an axis, applied to the distance of its value from 0 so that it keeps
+/// its sign.
+#[derive(Debug, Default, Clone, Copy)]
+pub enum AxisCurve {
+    #[default]
+    Linear,
+    /// Raises the value to a power. Powers above 1 give finer control of small movements.
+    Power(f32),
+    Custom(fn(f32) -> f32),
+}
+
+impl AxisCurve {
+    pub fn apply(&self, value: f32) -> f32 {
+        let magnitude = match self {
+            AxisCurve::Linear => return value,
+            AxisCurve::Power(power) => value.abs().powf(*power),
+            AxisCurve::Custom(curve) => curve(value.abs()),
+        };
+        magnitude.copysign(value)
+    }
+}
+
+/// The lowest and the highest raw values an axis reached, to scale its values so that they
+/// reach -1 and 1 on controllers whose sticks don't travel the full range.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AxisCalibration {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl AxisCalibration {
+    /// Scales a raw value, the positive and negative values separately so that 0 stays at 0.
+    pub fn apply(&self, value: f32) -> f32 {
+        if value > 0.0 && self.max > 0.0 {
+            (value / self.max).min(1.0)
+        } else if value < 0.0 && self.min < 0.0 {
+            -(value / self.min).min(1.0)
+        } else {
+            value
+        }
+    }
+
+    fn record(&mut self, value: f32) {
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
 }
 
 impl Default for AxisSettings {
@@ -141,12 +252,25 @@ impl Default for AxisSettings {
             negative_high: -0.95,
             negative_low: -0.05,
             threshold: 0.01,
+            curve: AxisCurve::Linear,
+            inverted: false,
+            calibration: None,
         }
     }
 }
 
 impl AxisSettings {
+    /// Sets the values around 0 that are filtered to 0.
+    pub fn set_deadzone(&mut self, deadzone: f32) {
+        self.positive_low = deadzone;
+        self.negative_low = -deadzone;
+    }
+
     fn filter(&self, new_value: f32, old_value: Option<f32>) -> Option<f32> {
+        let new_value = match &self.calibration {
+            Some(calibration) => calibration.apply(new_value),
+            None => new_value,
+        };
         let new_value = if new_value <= self.positive_low && new_value >= self.negative_low {
             0.0
         } else if new_value >= self.positive_high {
@@ -156,6 +280,8 @@ impl AxisSettings {
         } else {
             new_value
         };
+        let new_value = self.curve.apply(new_value);
+        let new_value = if self.inverted { -new_value } else { new_value };
 
         if let Some(old_value) = old_value {
             if (new_value - old_value).abs() <= self.threshold {
@@ -167,6 +293,77 @@ impl AxisSettings {
     }
 }
 
+/// Records the travel of the axes of gamepads to calibrate them, for controllers whose sticks
+/// don't reach the full range. Start the calibration of a gamepad, ask the player to move its
+/// sticks and triggers all the way in every direction, then finish it to apply the calibration
+/// to the [`GamepadSettings`].
+///
+/// ```
+/// # use bevy_input::gamepad::{GamepadCalibration, GamepadSettings};
+/// # use bevy_input::prelude::*;
+/// # use bevy_ecs::prelude::*;
+/// fn calibrate(
+///     buttons: Res<Input<GamepadButton>>,
+///     mut calibration: ResMut<GamepadCalibration>,
+///     mut settings: ResMut<GamepadSettings>,
+/// ) {
+///     let gamepad = Gamepad(0);
+///     if buttons.just_pressed(GamepadButton(gamepad, GamepadButtonType::Select)) {
+///         calibration.start(gamepad);
+///     }
+///     if buttons.just_pressed(GamepadButton(gamepad, GamepadButtonType::Start)) {
+///         calibration.finish(gamepad, &mut settings);
+///     }
+/// }
+/// # calibrate.system();
+/// ```
+#[derive(Debug, Default)]
+pub struct GamepadCalibration {
+    recordings: HashMap<Gamepad, HashMap<GamepadAxisType, AxisCalibration>>,
+}
+
+impl GamepadCalibration {
+    /// Starts recording the travel of the axes of a gamepad, restarting it if it was already
+    /// being recorded.
+    pub fn start(&mut self, gamepad: Gamepad) {
+        self.recordings.insert(gamepad, HashMap::default());
+    }
+
+    pub fn is_calibrating(&self, gamepad: Gamepad) -> bool {
+        self.recordings.contains_key(&gamepad)
+    }
+
+    /// The travel of an axis recorded since the calibration of its gamepad started.
+    pub fn travel(&self, axis: GamepadAxis) -> Option<AxisCalibration> {
+        self.recordings.get(&axis.0)?.get(&axis.1).copied()
+    }
+
+    /// Stops recording without changing the settings of the gamepad.
+    pub fn cancel(&mut self, gamepad: Gamepad) {
+        self.recordings.remove(&gamepad);
+    }
+
+    /// Stops recording and sets the calibration of the axes of the gamepad that moved. The
+    /// other axes keep their calibration.
+    pub fn finish(&mut self, gamepad: Gamepad, settings: &mut GamepadSettings) {
+        let recording = match self.recordings.remove(&gamepad) {
+            Some(recording) => recording,
+            None => return,
+        };
+        for (axis_type, calibration) in recording {
+            settings
+                .axis_settings_mut(GamepadAxis(gamepad, axis_type))
+                .calibration = Some(calibration);
+        }
+    }
+
+    fn record(&mut self, axis: GamepadAxis, value: f32) {
+        if let Some(recording) = self.recordings.get_mut(&axis.0) {
+            recording.entry(axis.1).or_default().record(value);
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ButtonAxisSettings {
     pub high: f32,
@@ -274,6 +471,22 @@ pub fn gamepad_event_system(
     }
 }
 
+/// Records the raw values of the axes of the gamepads being calibrated by the
+/// [`GamepadCalibration`].
+pub fn gamepad_calibration_system(
+    mut calibration: ResMut<GamepadCalibration>,
+    mut raw_events: EventReader<GamepadEventRaw>,
+) {
+    if calibration.recordings.is_empty() {
+        return;
+    }
+    for event in raw_events.iter() {
+        if let GamepadEventType::AxisChanged(axis_type, value) = event.1 {
+            calibration.record(GamepadAxis(event.0, axis_type), value);
+        }
+    }
+}
+
 const ALL_BUTTON_TYPES: [GamepadButtonType; 19] = [
     GamepadButtonType::South,
     GamepadButtonType::East,
@@ -306,3 +519,49 @@ const ALL_AXIS_TYPES: [GamepadAxisType; 8] = [
     GamepadAxisType::DPadX,
     GamepadAxisType::DPadY,
 ];
+
+#[cfg(test)]
+mod test {
+    use super::{AxisCalibration, AxisCurve, AxisSettings};
+
+    #[test]
+    fn axis_settings_filter() {
+        let mut settings = AxisSettings::default();
+        assert_eq!(settings.filter(0.02, None), Some(0.0));
+        assert_eq!(settings.filter(0.5, None), Some(0.5));
+        assert_eq!(settings.filter(0.505, Some(0.5)), None);
+        assert_eq!(settings.filter(-0.98, None), Some(-1.0));
+
+        settings.curve = AxisCurve::Power(2.0);
+        assert_eq!(settings.filter(-0.5, None), Some(-0.25));
+
+        settings.inverted = true;
+        assert_eq!(settings.filter(0.5, None), Some(-0.25));
+    }
+
+    #[test]
+    fn axis_calibration() {
+        let mut calibration = AxisCalibration::default();
+        for value in [0.1, 0.8, -0.4, -0.6, 0.3].iter() {
+            calibration.record(*value);
+        }
+        assert_eq!(
+            calibration,
+            AxisCalibration {
+                min: -0.6,
+                max: 0.8
+            }
+        );
+        assert_eq!(calibration.apply(0.4), 0.5);
+        assert_eq!(calibration.apply(-0.3), -0.5);
+        assert_eq!(calibration.apply(0.9), 1.0);
+
+        let mut settings = AxisSettings {
+            calibration: Some(calibration),
+            ..Default::default()
+        };
+        settings.set_deadzone(0.1);
+        assert_eq!(settings.filter(0.04, None), Some(0.0));
+        assert_eq!(settings.filter(-0.6, None), Some(-1.0));
+    }
+}
@@ -1,15 +1,92 @@
 use crate::{ElementState, Input};
 use bevy_app::EventReader;
 use bevy_ecs::system::ResMut;
+use bevy_utils::HashMap;
 
 /// A key input event from a keyboard device
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct KeyboardInput {
+    /// The physical key, see [`ScanCode`]
     pub scan_code: u32,
+    /// The key of the keyboard layout, like [`KeyCode::Z`] for the key at the position of W on
+    /// QWERTY keyboards when the layout is AZERTY
     pub key_code: Option<KeyCode>,
     pub state: ElementState,
 }
 
+/// The physical position of a key on the keyboard, independent of the keyboard layout. Scan
+/// codes are platform specific, use [`ScanCode::qwerty_key_code`] or [`KeyboardLayout`] to
+/// relate them to [`KeyCode`]s.
+#[derive(Debug, Hash, Ord, PartialOrd, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScanCode(pub u32);
+
+impl ScanCode {
+    /// The key at this position on a QWERTY keyboard, for the keys of the main block of the
+    /// keyboard.
+    pub fn qwerty_key_code(&self) -> Option<KeyCode> {
+        QWERTY_SCAN_CODES
+            .iter()
+            .find(|(scan_code, _)| *scan_code == self.0)
+            .map(|(_, key_code)| *key_code)
+    }
+
+    /// The scan code of the key at the position of `key_code` on a QWERTY keyboard.
+    pub fn from_qwerty_key_code(key_code: KeyCode) -> Option<ScanCode> {
+        QWERTY_SCAN_CODES
+            .iter()
+            .find(|(_, qwerty_key_code)| *qwerty_key_code == key_code)
+            .map(|(scan_code, _)| ScanCode(*scan_code))
+    }
+}
+
+/// The keys of the keyboard layout of the user, learned from the [`KeyboardInput`] events that
+/// carry both the physical and the logical key. Until a key is pressed, its key in a QWERTY
+/// layout is assumed.
+///
+/// This lets games bind actions to physical keys, so that movement keys stay in place with any
+/// layout, while showing the labels of the keys of the user in rebinding menus.
+#[derive(Debug, Default)]
+pub struct KeyboardLayout {
+    key_codes: HashMap<ScanCode, KeyCode>,
+}
+
+impl KeyboardLayout {
+    /// The key of the layout at the physical position of `scan_code`.
+    pub fn key_code(&self, scan_code: ScanCode) -> Option<KeyCode> {
+        self.key_codes
+            .get(&scan_code)
+            .copied()
+            .or_else(|| scan_code.qwerty_key_code())
+    }
+
+    /// The physical position of the key `key_code` of the layout.
+    pub fn scan_code(&self, key_code: KeyCode) -> Option<ScanCode> {
+        self.key_codes
+            .iter()
+            .find(|(_, layout_key_code)| **layout_key_code == key_code)
+            .map(|(scan_code, _)| *scan_code)
+            .or_else(|| {
+                ScanCode::from_qwerty_key_code(key_code)
+                    .filter(|scan_code| !self.key_codes.contains_key(scan_code))
+            })
+    }
+
+    /// The name of the key at the physical position of `scan_code` in the layout, like "Z" for
+    /// the key at the position of W on QWERTY keyboards when the layout is AZERTY.
+    pub fn key_display_name(&self, scan_code: ScanCode) -> String {
+        match self.key_code(scan_code) {
+            Some(key_code) => key_code.display_name(),
+            None => format!("Key {}", scan_code.0),
+        }
+    }
+
+    pub fn learn(&mut self, scan_code: ScanCode, key_code: KeyCode) {
+        self.key_codes.insert(scan_code, key_code);
+    }
+}
+
 /// Updates the Input<KeyCode> resource with the latest KeyboardInput events
 pub fn keyboard_input_system(
     mut keyboard_input: ResMut<Input<KeyCode>>,
@@ -31,6 +108,28 @@ pub fn keyboard_input_system(
     }
 }
 
+/// Updates the Input<ScanCode> resource and the [`KeyboardLayout`] with the latest
+/// KeyboardInput events
+pub fn scan_code_input_system(
+    mut scan_code_input: ResMut<Input<ScanCode>>,
+    mut keyboard_layout: ResMut<KeyboardLayout>,
+    mut keyboard_input_events: EventReader<KeyboardInput>,
+) {
+    scan_code_input.clear();
+    for event in keyboard_input_events.iter() {
+        let scan_code = ScanCode(event.scan_code);
+        match event.state {
+            ElementState::Pressed => scan_code_input.press(scan_code),
+            ElementState::Released => scan_code_input.release(scan_code),
+        }
+        if let Some(key_code) = event.key_code {
+            if keyboard_layout.key_codes.get(&scan_code) != Some(&key_code) {
+                keyboard_layout.learn(scan_code, key_code);
+            }
+        }
+    }
+}
+
 /// The key code of a keyboard input.
 #[derive(Debug, Hash, Ord, PartialOrd, PartialEq, Eq, Clone, Copy)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
@@ -230,3 +329,247 @@ pub enum KeyCode {
     Paste,
     Cut,
 }
+
+impl KeyCode {
+    /// The name of the key, as printed on keyboards.
+    pub fn display_name(&self) -> String {
+        let name = match self {
+            KeyCode::Key1 => "1",
+            KeyCode::Key2 => "2",
+            KeyCode::Key3 => "3",
+            KeyCode::Key4 => "4",
+            KeyCode::Key5 => "5",
+            KeyCode::Key6 => "6",
+            KeyCode::Key7 => "7",
+            KeyCode::Key8 => "8",
+            KeyCode::Key9 => "9",
+            KeyCode::Key0 => "0",
+            KeyCode::Escape => "Esc",
+            KeyCode::Snapshot => "Print Screen",
+            KeyCode::Scroll => "Scroll Lock",
+            KeyCode::PageDown => "Page Down",
+            KeyCode::PageUp => "Page Up",
+            KeyCode::Back => "Backspace",
+            KeyCode::Return => "Enter",
+            KeyCode::Caret => "^",
+            KeyCode::Numlock => "Num Lock",
+            KeyCode::Numpad0 => "Numpad 0",
+            KeyCode::Numpad1 => "Numpad 1",
+            KeyCode::Numpad2 => "Numpad 2",
+            KeyCode::Numpad3 => "Numpad 3",
+            KeyCode::Numpad4 => "Numpad 4",
+            KeyCode::Numpad5 => "Numpad 5",
+            KeyCode::Numpad6 => "Numpad 6",
+            KeyCode::Numpad7 => "Numpad 7",
+            KeyCode::Numpad8 => "Numpad 8",
+            KeyCode::Numpad9 => "Numpad 9",
+            KeyCode::NumpadAdd => "Numpad +",
+            KeyCode::NumpadDecimal => "Numpad .",
+            KeyCode::NumpadDivide => "Numpad /",
+            KeyCode::NumpadMultiply => "Numpad *",
+            KeyCode::NumpadSubtract => "Numpad -",
+            KeyCode::NumpadComma => "Numpad ,",
+            KeyCode::NumpadEnter => "Numpad Enter",
+            KeyCode::NumpadEquals => "Numpad =",
+            KeyCode::Apostrophe => "'",
+            KeyCode::Asterisk => "*",
+            KeyCode::Plus => "+",
+            KeyCode::At => "@",
+            KeyCode::Backslash => "\\",
+            KeyCode::Capital => "Caps Lock",
+            KeyCode::Colon => ":",
+            KeyCode::Comma => ",",
+            KeyCode::Equals => "=",
+            KeyCode::Grave => "`",
+            KeyCode::LAlt => "Left Alt",
+            KeyCode::LBracket => "[",
+            KeyCode::LControl => "Left Ctrl",
+            KeyCode::LShift => "Left Shift",
+            KeyCode::LWin => "Left Super",
+            KeyCode::Minus => "-",
+            KeyCode::Period => ".",
+            KeyCode::RAlt => "Right Alt",
+            KeyCode::RBracket => "]",
+            KeyCode::RControl => "Right Ctrl",
+            KeyCode::RShift => "Right Shift",
+            KeyCode::RWin => "Right Super",
+            KeyCode::Semicolon => ";",
+            KeyCode::Slash => "/",
+            KeyCode::Underline => "_",
+            KeyCode::Yen => "¥",
+            _ => return format!("{:?}", self),
+        };
+        name.to_string()
+    }
+}
+
+/// The scan codes of the keys of the main block of a QWERTY keyboard, which are the PC set 1
+/// scan codes on Windows and the evdev codes on Linux.
+#[cfg(not(any(target_os = "macos", target_arch = "wasm32")))]
+const QWERTY_SCAN_CODES: &[(u32, KeyCode)] = &[
+    (0x01, KeyCode::Escape),
+    (0x02, KeyCode::Key1),
+    (0x03, KeyCode::Key2),
+    (0x04, KeyCode::Key3),
+    (0x05, KeyCode::Key4),
+    (0x06, KeyCode::Key5),
+    (0x07, KeyCode::Key6),
+    (0x08, KeyCode::Key7),
+    (0x09, KeyCode::Key8),
+    (0x0A, KeyCode::Key9),
+    (0x0B, KeyCode::Key0),
+    (0x0C, KeyCode::Minus),
+    (0x0D, KeyCode::Equals),
+    (0x0E, KeyCode::Back),
+    (0x0F, KeyCode::Tab),
+    (0x10, KeyCode::Q),
+    (0x11, KeyCode::W),
+    (0x12, KeyCode::E),
+    (0x13, KeyCode::R),
+    (0x14, KeyCode::T),
+    (0x15, KeyCode::Y),
+    (0x16, KeyCode::U),
+    (0x17, KeyCode::I),
+    (0x18, KeyCode::O),
+    (0x19, KeyCode::P),
+    (0x1A, KeyCode::LBracket),
+    (0x1B, KeyCode::RBracket),
+    (0x1C, KeyCode::Return),
+    (0x1D, KeyCode::LControl),
+    (0x1E, KeyCode::A),
+    (0x1F, KeyCode::S),
+    (0x20, KeyCode::D),
+    (0x21, KeyCode::F),
+    (0x22, KeyCode::G),
+    (0x23, KeyCode::H),
+    (0x24, KeyCode::J),
+    (0x25, KeyCode::K),
+    (0x26, KeyCode::L),
+    (0x27, KeyCode::Semicolon),
+    (0x28, KeyCode::Apostrophe),
+    (0x29, KeyCode::Grave),
+    (0x2A, KeyCode::LShift),
+    (0x2B, KeyCode::Backslash),
+    (0x2C, KeyCode::Z),
+    (0x2D, KeyCode::X),
+    (0x2E, KeyCode::C),
+    (0x2F, KeyCode::V),
+    (0x30, KeyCode::B),
+    (0x31, KeyCode::N),
+    (0x32, KeyCode::M),
+    (0x33, KeyCode::Comma),
+    (0x34, KeyCode::Period),
+    (0x35, KeyCode::Slash),
+    (0x36, KeyCode::RShift),
+    (0x38, KeyCode::LAlt),
+    (0x39, KeyCode::Space),
+    (0x3A, KeyCode::Capital),
+    (0x3B, KeyCode::F1),
+    (0x3C, KeyCode::F2),
+    (0x3D, KeyCode::F3),
+    (0x3E, KeyCode::F4),
+    (0x3F, KeyCode::F5),
+    (0x40, KeyCode::F6),
+    (0x41, KeyCode::F7),
+    (0x42, KeyCode::F8),
+    (0x43, KeyCode::F9),
+    (0x44, KeyCode::F10),
+    (0x56, KeyCode::Oem102),
+    (0x57, KeyCode::F11),
+    (0x58, KeyCode::F12),
+];
+
+/// The virtual key codes of the keys of the main block of a QWERTY keyboard on macOS.
+#[cfg(target_os = "macos")]
+const QWERTY_SCAN_CODES: &[(u32, KeyCode)] = &[
+    (0x00, KeyCode::A),
+    (0x01, KeyCode::S),
+    (0x02, KeyCode::D),
+    (0x03, KeyCode::F),
+    (0x04, KeyCode::H),
+    (0x05, KeyCode::G),
+    (0x06, KeyCode::Z),
+    (0x07, KeyCode::X),
+    (0x08, KeyCode::C),
+    (0x09, KeyCode::V),
+    (0x0A, KeyCode::Oem102),
+    (0x0B, KeyCode::B),
+    (0x0C, KeyCode::Q),
+    (0x0D, KeyCode::W),
+    (0x0E, KeyCode::E),
+    (0x0F, KeyCode::R),
+    (0x10, KeyCode::Y),
+    (0x11, KeyCode::T),
+    (0x12, KeyCode::Key1),
+    (0x13, KeyCode::Key2),
+    (0x14, KeyCode::Key3),
+    (0x15, KeyCode::Key4),
+    (0x16, KeyCode::Key6),
+    (0x17, KeyCode::Key5),
+    (0x18, KeyCode::Equals),
+    (0x19, KeyCode::Key9),
+    (0x1A, KeyCode::Key7),
+    (0x1B, KeyCode::Minus),
+    (0x1C, KeyCode::Key8),
+    (0x1D, KeyCode::Key0),
+    (0x1E, KeyCode::RBracket),
+    (0x1F, KeyCode::O),
+    (0x20, KeyCode::U),
+    (0x21, KeyCode::LBracket),
+    (0x22, KeyCode::I),
+    (0x23, KeyCode::P),
+    (0x24, KeyCode::Return),
+    (0x25, KeyCode::L),
+    (0x26, KeyCode::J),
+    (0x27, KeyCode::Apostrophe),
+    (0x28, KeyCode::K),
+    (0x29, KeyCode::Semicolon),
+    (0x2A, KeyCode::Backslash),
+    (0x2B, KeyCode::Comma),
+    (0x2C, KeyCode::Slash),
+    (0x2D, KeyCode::N),
+    (0x2E, KeyCode::M),
+    (0x2F, KeyCode::Period),
+    (0x30, KeyCode::Tab),
+    (0x31, KeyCode::Space),
+    (0x32, KeyCode::Grave),
+    (0x33, KeyCode::Back),
+    (0x35, KeyCode::Escape),
+    (0x37, KeyCode::LWin),
+    (0x38, KeyCode::LShift),
+    (0x39, KeyCode::Capital),
+    (0x3A, KeyCode::LAlt),
+    (0x3B, KeyCode::LControl),
+    (0x3C, KeyCode::RShift),
+    (0x3D, KeyCode::RAlt),
+    (0x3E, KeyCode::RControl),
+    (0x7B, KeyCode::Left),
+    (0x7C, KeyCode::Right),
+    (0x7D, KeyCode::Down),
+    (0x7E, KeyCode::Up),
+];
+
+/// Scan codes aren't consistent across browsers, keys are only known once pressed.
+#[cfg(target_arch = "wasm32")]
+const QWERTY_SCAN_CODES: &[(u32, KeyCode)] = &[];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_learns_keys() {
+        let w = ScanCode::from_qwerty_key_code(KeyCode::W).unwrap();
+        let mut layout = KeyboardLayout::default();
+        assert_eq!(layout.key_code(w), Some(KeyCode::W));
+        assert_eq!(layout.key_display_name(w), "W");
+
+        // AZERTY has Z at the position of W
+        layout.learn(w, KeyCode::Z);
+        assert_eq!(layout.key_code(w), Some(KeyCode::Z));
+        assert_eq!(layout.key_display_name(w), "Z");
+        assert_eq!(layout.scan_code(KeyCode::Z), Some(w));
+        // W isn't at its QWERTY position anymore
+        assert_eq!(layout.scan_code(KeyCode::W), None);
+    }
+}
@@ -3,6 +3,9 @@ pub mod gamepad;
 mod input;
 pub mod keyboard;
 pub mod mouse;
+pub mod pen;
+#[cfg(feature = "serialize")]
+pub mod recording;
 pub mod system;
 pub mod touch;
 
@@ -15,23 +18,30 @@ pub mod prelude {
     pub use crate::{
         gamepad::{
             Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType, GamepadEvent,
-            GamepadEventType,
+            GamepadEventType, GamepadRumbleRequest,
         },
-        keyboard::KeyCode,
+        keyboard::{KeyCode, KeyboardLayout, ScanCode},
         mouse::MouseButton,
+        pen::{PenButton, PenInput, Pens},
         touch::{TouchInput, Touches},
         Axis, Input,
     };
 }
 
 use bevy_app::prelude::*;
-use keyboard::{keyboard_input_system, KeyCode, KeyboardInput};
-use mouse::{mouse_button_input_system, MouseButton, MouseButtonInput, MouseMotion, MouseWheel};
+use keyboard::{
+    keyboard_input_system, scan_code_input_system, KeyCode, KeyboardInput, KeyboardLayout, ScanCode,
+};
+use mouse::{
+    accumulate_mouse_motion_system, mouse_button_input_system, AccumulatedMouseMotion, MouseButton,
+    MouseButtonInput, MouseMotion, MouseWheel,
+};
+use pen::{pen_input_system, PenButton, PenInput, Pens};
 use touch::{touch_screen_input_system, TouchInput, Touches};
 
 use gamepad::{
-    gamepad_event_system, GamepadAxis, GamepadButton, GamepadEvent, GamepadEventRaw,
-    GamepadSettings,
+    gamepad_calibration_system, gamepad_event_system, GamepadAxis, GamepadButton,
+    GamepadCalibration, GamepadEvent, GamepadEventRaw, GamepadRumbleRequest, GamepadSettings,
 };
 
 /// Adds keyboard and mouse input to an App
@@ -47,23 +57,36 @@ impl Plugin for InputPlugin {
             // keyboard
             .add_event::<KeyboardInput>()
             .init_resource::<Input<KeyCode>>()
+            .init_resource::<Input<ScanCode>>()
+            .init_resource::<KeyboardLayout>()
             .add_system_to_stage(
                 CoreStage::PreUpdate,
                 keyboard_input_system.label(InputSystem),
             )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                scan_code_input_system.label(InputSystem),
+            )
             // mouse
             .add_event::<MouseButtonInput>()
             .add_event::<MouseMotion>()
             .add_event::<MouseWheel>()
             .init_resource::<Input<MouseButton>>()
+            .init_resource::<AccumulatedMouseMotion>()
             .add_system_to_stage(
                 CoreStage::PreUpdate,
                 mouse_button_input_system.label(InputSystem),
             )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                accumulate_mouse_motion_system.label(InputSystem),
+            )
             // gamepad
             .add_event::<GamepadEvent>()
             .add_event::<GamepadEventRaw>()
+            .add_event::<GamepadRumbleRequest>()
             .init_resource::<GamepadSettings>()
+            .init_resource::<GamepadCalibration>()
             .init_resource::<Input<GamepadButton>>()
             .init_resource::<Axis<GamepadAxis>>()
             .init_resource::<Axis<GamepadButton>>()
@@ -71,6 +94,15 @@ impl Plugin for InputPlugin {
                 CoreStage::PreUpdate,
                 gamepad_event_system.label(InputSystem),
             )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                gamepad_calibration_system.label(InputSystem),
+            )
+            // pen
+            .add_event::<PenInput>()
+            .init_resource::<Pens>()
+            .init_resource::<Input<PenButton>>()
+            .add_system_to_stage(CoreStage::PreUpdate, pen_input_system.label(InputSystem))
             // touch
             .add_event::<TouchInput>()
             .init_resource::<Touches>()
@@ -3,7 +3,8 @@ use bevy_ecs::{event::EventReader, system::ResMut};
 use bevy_math::Vec2;
 
 /// A mouse button input event
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct MouseButtonInput {
     pub button: MouseButton,
     pub state: ElementState,
@@ -19,14 +20,31 @@ pub enum MouseButton {
     Other(u16),
 }
 
-/// A mouse motion event
-#[derive(Debug, Clone)]
+/// A mouse motion event, with the raw relative movement of the mouse as reported by the device.
+///
+/// Unlike [`CursorMoved`](https://docs.rs/bevy/*/bevy/window/struct.CursorMoved.html), the delta
+/// isn't affected by the acceleration of the pointer by the OS (on platforms with raw input),
+/// the scale factor of the window or the cursor reaching the edge of the screen, and motion
+/// events are still sent while the cursor is locked. This makes them suited to aiming and
+/// camera controls. The delta is in device units, with y increasing downwards.
+///
+/// Motion is reported whether or not a window of the app is focused.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct MouseMotion {
     pub delta: Vec2,
 }
 
+/// The sum of the [`MouseMotion`] events of the current frame, for systems that only need the
+/// total movement of the mouse.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AccumulatedMouseMotion {
+    pub delta: Vec2,
+}
+
 /// Unit of scroll
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum MouseScrollUnit {
     Line,
     Pixel,
@@ -34,7 +52,8 @@ pub enum MouseScrollUnit {
 
 /// A mouse scroll wheel event, where x represents horizontal scroll and y represents vertical
 /// scroll.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct MouseWheel {
     pub unit: MouseScrollUnit,
     pub x: f32,
@@ -54,3 +73,62 @@ pub fn mouse_button_input_system(
         }
     }
 }
+
+/// Updates the [`AccumulatedMouseMotion`] resource with the latest [`MouseMotion`] events
+pub fn accumulate_mouse_motion_system(
+    mut accumulated_mouse_motion: ResMut<AccumulatedMouseMotion>,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+) {
+    accumulated_mouse_motion.delta = mouse_motion_events
+        .iter()
+        .map(|event| event.delta)
+        .fold(Vec2::ZERO, |total, delta| total + delta);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy_ecs::{event::Events, prelude::*};
+
+    #[test]
+    fn accumulated_mouse_motion() {
+        let mut world = World::default();
+        world.insert_resource(Events::<MouseMotion>::default());
+        world.insert_resource(AccumulatedMouseMotion::default());
+        let mut stage = SystemStage::single(accumulate_mouse_motion_system);
+        let send_motion = |world: &mut World, x: f32, y: f32| {
+            let mut events = world.get_resource_mut::<Events<MouseMotion>>().unwrap();
+            events.send(MouseMotion {
+                delta: Vec2::new(x, y),
+            });
+        };
+        let accumulated = |world: &World| {
+            world
+                .get_resource::<AccumulatedMouseMotion>()
+                .unwrap()
+                .delta
+        };
+
+        send_motion(&mut world, 1.0, 2.0);
+        send_motion(&mut world, 3.0, -1.0);
+        stage.run(&mut world);
+        assert_eq!(accumulated(&world), Vec2::new(4.0, 1.0));
+
+        // only the motion of the current frame is summed
+        world
+            .get_resource_mut::<Events<MouseMotion>>()
+            .unwrap()
+            .update();
+        send_motion(&mut world, -1.0, 0.5);
+        stage.run(&mut world);
+        assert_eq!(accumulated(&world), Vec2::new(-1.0, 0.5));
+
+        // and it's reset on frames without motion
+        world
+            .get_resource_mut::<Events<MouseMotion>>()
+            .unwrap()
+            .update();
+        stage.run(&mut world);
+        assert_eq!(accumulated(&world), Vec2::ZERO);
+    }
+}
@@ -0,0 +1,287 @@
+use crate::Input;
+use bevy_app::EventReader;
+use bevy_ecs::system::ResMut;
+use bevy_math::Vec2;
+use bevy_utils::{HashMap, HashSet};
+
+/// A stylus event, from a pen tablet or a touch screen with a pen.
+///
+/// A pen near the surface sends `Hovered` events, a `Started` event when its tip touches the
+/// surface, `Moved` events while it is drawn with, and an `Ended` event when it is lifted. A
+/// `Left` event is sent when it leaves the range of the tablet.
+///
+/// ## Platform-specific
+///
+/// - With winit, only the Apple Pencil on **iOS** is reported as a pen, without its azimuth and
+///   buttons. The pen is also reported as a touch.
+/// - Other input backends can send these events for the pens they support.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PenInput {
+    pub phase: PenPhase,
+    /// In logical pixels from the bottom left corner of the window
+    pub position: Vec2,
+    /// How hard the tip is pressed, from 0.0 to 1.0. `None` if the pen isn't pressure sensitive
+    pub pressure: Option<f32>,
+    /// `None` if the pen doesn't report its tilt
+    pub tilt: Option<PenTilt>,
+    pub buttons: PenButtons,
+    /// If the eraser end of the pen is used
+    pub eraser: bool,
+    /// Unique identifier of a pen
+    pub id: u64,
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum PenPhase {
+    Hovered,
+    Started,
+    Moved,
+    Ended,
+    Left,
+    Cancelled,
+}
+
+/// The orientation of a pen
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PenTilt {
+    /// The angle between the pen and the surface, in radians. 0 when the pen is parallel to the
+    /// surface, Pi/2 when it is perpendicular to it
+    pub altitude: f32,
+    /// The direction the pen points to on the surface, in radians counterclockwise from the x
+    /// axis. `None` if the pen only reports its altitude
+    pub azimuth: Option<f32>,
+}
+
+/// The pressed buttons on the barrel of a pen
+#[derive(Debug, Default, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PenButtons {
+    pub barrel: bool,
+    pub secondary_barrel: bool,
+}
+
+/// A button on the barrel of a pen
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum PenButton {
+    Barrel,
+    SecondaryBarrel,
+}
+
+/// The state of a pen in range of the tablet, see [`Pens`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pen {
+    id: u64,
+    previous_position: Vec2,
+    position: Vec2,
+    pressure: Option<f32>,
+    tilt: Option<PenTilt>,
+    buttons: PenButtons,
+    eraser: bool,
+    touching: bool,
+}
+
+impl Pen {
+    pub fn delta(&self) -> Vec2 {
+        self.position - self.previous_position
+    }
+
+    #[inline]
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    #[inline]
+    pub fn previous_position(&self) -> Vec2 {
+        self.previous_position
+    }
+
+    #[inline]
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    #[inline]
+    pub fn pressure(&self) -> Option<f32> {
+        self.pressure
+    }
+
+    #[inline]
+    pub fn tilt(&self) -> Option<PenTilt> {
+        self.tilt
+    }
+
+    #[inline]
+    pub fn buttons(&self) -> PenButtons {
+        self.buttons
+    }
+
+    #[inline]
+    pub fn eraser(&self) -> bool {
+        self.eraser
+    }
+
+    /// If the tip of the pen touches the surface
+    #[inline]
+    pub fn touching(&self) -> bool {
+        self.touching
+    }
+}
+
+impl From<&PenInput> for Pen {
+    fn from(input: &PenInput) -> Pen {
+        Pen {
+            id: input.id,
+            previous_position: input.position,
+            position: input.position,
+            pressure: input.pressure,
+            tilt: input.tilt,
+            buttons: input.buttons,
+            eraser: input.eraser,
+            touching: matches!(input.phase, PenPhase::Started | PenPhase::Moved),
+        }
+    }
+}
+
+/// The pens in range of the tablet
+#[derive(Debug, Clone, Default)]
+pub struct Pens {
+    pens: HashMap<u64, Pen>,
+    just_touched: HashSet<u64>,
+    just_lifted: HashSet<u64>,
+}
+
+impl Pens {
+    pub fn iter(&self) -> impl Iterator<Item = &Pen> + '_ {
+        self.pens.values()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Pen> {
+        self.pens.get(&id)
+    }
+
+    /// If the tip of the pen touched the surface this frame
+    pub fn just_touched(&self, id: u64) -> bool {
+        self.just_touched.contains(&id)
+    }
+
+    /// If the tip of the pen was lifted from the surface this frame
+    pub fn just_lifted(&self, id: u64) -> bool {
+        self.just_lifted.contains(&id)
+    }
+
+    fn process_pen_event(&mut self, event: &PenInput) {
+        match event.phase {
+            PenPhase::Left | PenPhase::Cancelled => {
+                if let Some(pen) = self.pens.remove(&event.id) {
+                    if pen.touching {
+                        self.just_lifted.insert(event.id);
+                    }
+                }
+            }
+            _ => {
+                let mut pen = Pen::from(event);
+                if let Some(previous) = self.pens.get(&event.id) {
+                    pen.previous_position = previous.position;
+                    if previous.touching && !pen.touching {
+                        self.just_lifted.insert(event.id);
+                    }
+                    if !previous.touching && pen.touching {
+                        self.just_touched.insert(event.id);
+                    }
+                } else if pen.touching {
+                    self.just_touched.insert(event.id);
+                }
+                self.pens.insert(event.id, pen);
+            }
+        }
+    }
+
+    fn update(&mut self) {
+        self.just_touched.clear();
+        self.just_lifted.clear();
+        for pen in self.pens.values_mut() {
+            pen.previous_position = pen.position;
+        }
+    }
+}
+
+/// Updates the Pens and Input<PenButton> resources with the latest PenInput events
+pub fn pen_input_system(
+    mut pens: ResMut<Pens>,
+    mut pen_button_input: ResMut<Input<PenButton>>,
+    mut pen_input_events: EventReader<PenInput>,
+) {
+    pens.update();
+    pen_button_input.clear();
+
+    for event in pen_input_events.iter() {
+        pens.process_pen_event(event);
+    }
+
+    let mut buttons = PenButtons::default();
+    for pen in pens.iter() {
+        buttons.barrel |= pen.buttons.barrel;
+        buttons.secondary_barrel |= pen.buttons.secondary_barrel;
+    }
+    for (button, pressed) in [
+        (PenButton::Barrel, buttons.barrel),
+        (PenButton::SecondaryBarrel, buttons.secondary_barrel),
+    ]
+    .iter()
+    {
+        if *pressed && !pen_button_input.pressed(*button) {
+            pen_button_input.press(*button);
+        } else if !*pressed && pen_button_input.pressed(*button) {
+            pen_button_input.release(*button);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pen_event(phase: PenPhase, position: Vec2) -> PenInput {
+        PenInput {
+            phase,
+            position,
+            pressure: Some(0.5),
+            tilt: None,
+            buttons: PenButtons::default(),
+            eraser: false,
+            id: 1,
+        }
+    }
+
+    #[test]
+    fn pen_process() {
+        let mut pens = Pens::default();
+
+        pens.process_pen_event(&pen_event(PenPhase::Hovered, Vec2::new(1.0, 1.0)));
+        assert!(!pens.get(1).unwrap().touching());
+        assert!(!pens.just_touched(1));
+
+        pens.update();
+        pens.process_pen_event(&pen_event(PenPhase::Started, Vec2::new(2.0, 2.0)));
+        assert!(pens.get(1).unwrap().touching());
+        assert!(pens.just_touched(1));
+        assert_eq!(
+            pens.get(1).unwrap().previous_position(),
+            Vec2::new(1.0, 1.0)
+        );
+
+        pens.update();
+        pens.process_pen_event(&pen_event(PenPhase::Ended, Vec2::new(3.0, 3.0)));
+        assert!(pens.just_lifted(1));
+        assert!(!pens.get(1).unwrap().touching());
+
+        pens.update();
+        pens.process_pen_event(&pen_event(PenPhase::Left, Vec2::new(3.0, 3.0)));
+        assert!(pens.get(1).is_none());
+        assert!(!pens.just_lifted(1));
+    }
+}
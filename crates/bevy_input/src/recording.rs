@@ -0,0 +1,410 @@
+use crate::{
+    gamepad::GamepadEventRaw,
+    keyboard::KeyboardInput,
+    mouse::{MouseButtonInput, MouseMotion, MouseWheel},
+    pen::PenInput,
+    touch::TouchInput,
+    InputSystem,
+};
+use anyhow::Result;
+use bevy_app::{prelude::*, EventReader, Events};
+use bevy_asset::{AddAsset, AssetLoader, Assets, Handle, LoadContext, LoadedAsset};
+use bevy_ecs::{
+    schedule::ParallelSystemDescriptorCoercion,
+    system::{Res, ResMut},
+};
+use bevy_reflect::TypeUuid;
+use bevy_utils::{tracing::warn, BoxedFuture};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// An input event captured by the [`InputRecorder`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RecordedInputEvent {
+    Keyboard(KeyboardInput),
+    MouseButton(MouseButtonInput),
+    MouseMotion(MouseMotion),
+    MouseWheel(MouseWheel),
+    Touch(TouchInput),
+    Pen(PenInput),
+    Gamepad(GamepadEventRaw),
+}
+
+/// An input event and the frame it was sent on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedInput {
+    /// The frame of the event, counted from the start of the recording
+    pub frame: u64,
+    pub event: RecordedInputEvent,
+}
+
+/// The input events of a recording, see [`InputRecorder`]. Recordings are saved and loaded as
+/// RON files with the `input.ron` extension.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TypeUuid)]
+#[uuid = "4e1a5c47-0b7d-4c8e-9a53-2f6c1d8b7e90"]
+pub struct InputRecording {
+    /// Sorted by frame
+    pub inputs: Vec<RecordedInput>,
+    /// The number of frames recorded
+    pub frames: u64,
+}
+
+impl InputRecording {
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    pub fn from_ron(ron: &str) -> Result<Self, ron::Error> {
+        ron::de::from_str(ron)
+    }
+
+    /// Saves the recording to `path`, to load it as an asset.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.to_ron()?)?;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InputRecordingLoader;
+
+impl AssetLoader for InputRecordingLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let recording = InputRecording::from_ron(std::str::from_utf8(bytes)?)?;
+            load_context.set_default_asset(LoadedAsset::new(recording));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["input.ron"]
+    }
+}
+
+#[derive(Debug)]
+enum RecorderState {
+    Idle,
+    Recording(InputRecording),
+    Playing {
+        recording: Handle<InputRecording>,
+        frame: u64,
+        next_input: usize,
+    },
+}
+
+/// Records the input events of the app, and plays recordings back into the input resources, for
+/// automated gameplay tests and reproductions of bugs.
+///
+/// Inputs are recorded with the frame they were sent on, and played back on the same frames
+/// counted from the start of the playback, so a playback is deterministic as long as the app
+/// is, like with a fixed timestep. The live input events are discarded during a playback.
+#[derive(Debug)]
+pub struct InputRecorder {
+    state: RecorderState,
+}
+
+impl Default for InputRecorder {
+    fn default() -> Self {
+        InputRecorder {
+            state: RecorderState::Idle,
+        }
+    }
+}
+
+impl InputRecorder {
+    /// Starts recording the input events, stopping any recording or playback.
+    pub fn start_recording(&mut self) {
+        self.state = RecorderState::Recording(InputRecording::default());
+    }
+
+    /// Stops the recording and returns it, `None` if nothing was being recorded.
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        match std::mem::replace(&mut self.state, RecorderState::Idle) {
+            RecorderState::Recording(recording) => Some(recording),
+            state => {
+                self.state = state;
+                None
+            }
+        }
+    }
+
+    /// Plays `recording` back from its first frame, stopping any recording or playback. The
+    /// playback starts once the recording is loaded.
+    pub fn play(&mut self, recording: Handle<InputRecording>) {
+        self.state = RecorderState::Playing {
+            recording,
+            frame: 0,
+            next_input: 0,
+        };
+    }
+
+    pub fn stop_playback(&mut self) {
+        if self.is_playing() {
+            self.state = RecorderState::Idle;
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        matches!(self.state, RecorderState::Recording(_))
+    }
+
+    /// If a recording is being played back, until its last frame.
+    pub fn is_playing(&self) -> bool {
+        matches!(self.state, RecorderState::Playing { .. })
+    }
+}
+
+/// Adds the [`InputRecorder`] resource and the [`InputRecording`] asset.
+#[derive(Default)]
+pub struct InputRecorderPlugin;
+
+impl Plugin for InputRecorderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<InputRecording>()
+            .init_asset_loader::<InputRecordingLoader>()
+            .init_resource::<InputRecorder>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                input_playback_system.before(InputSystem),
+            )
+            .add_system_to_stage(CoreStage::PostUpdate, input_recording_system);
+    }
+}
+
+/// Records the input events of the frame. It runs at the end of the frame to capture the events
+/// of every input backend.
+#[allow(clippy::too_many_arguments)]
+pub fn input_recording_system(
+    mut recorder: ResMut<InputRecorder>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut mouse_button_events: EventReader<MouseButtonInput>,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    mut touch_events: EventReader<TouchInput>,
+    mut pen_events: EventReader<PenInput>,
+    mut gamepad_events: EventReader<GamepadEventRaw>,
+) {
+    let recording = match &mut recorder.state {
+        RecorderState::Recording(recording) => recording,
+        _ => {
+            // skip the events sent before the recording starts
+            keyboard_events.iter().last();
+            mouse_button_events.iter().last();
+            mouse_motion_events.iter().last();
+            mouse_wheel_events.iter().last();
+            touch_events.iter().last();
+            pen_events.iter().last();
+            gamepad_events.iter().last();
+            return;
+        }
+    };
+
+    let frame = recording.frames;
+    let events = keyboard_events
+        .iter()
+        .cloned()
+        .map(RecordedInputEvent::Keyboard)
+        .chain(
+            mouse_button_events
+                .iter()
+                .cloned()
+                .map(RecordedInputEvent::MouseButton),
+        )
+        .chain(
+            mouse_motion_events
+                .iter()
+                .cloned()
+                .map(RecordedInputEvent::MouseMotion),
+        )
+        .chain(
+            mouse_wheel_events
+                .iter()
+                .cloned()
+                .map(RecordedInputEvent::MouseWheel),
+        )
+        .chain(touch_events.iter().cloned().map(RecordedInputEvent::Touch))
+        .chain(pen_events.iter().cloned().map(RecordedInputEvent::Pen))
+        .chain(
+            gamepad_events
+                .iter()
+                .cloned()
+                .map(RecordedInputEvent::Gamepad),
+        );
+    recording
+        .inputs
+        .extend(events.map(|event| RecordedInput { frame, event }));
+    recording.frames += 1;
+}
+
+/// Sends the input events of the current frame of the recording being played back, in place of
+/// the live input events.
+#[allow(clippy::too_many_arguments)]
+pub fn input_playback_system(
+    mut recorder: ResMut<InputRecorder>,
+    recordings: Res<Assets<InputRecording>>,
+    mut keyboard_events: ResMut<Events<KeyboardInput>>,
+    mut mouse_button_events: ResMut<Events<MouseButtonInput>>,
+    mut mouse_motion_events: ResMut<Events<MouseMotion>>,
+    mut mouse_wheel_events: ResMut<Events<MouseWheel>>,
+    mut touch_events: ResMut<Events<TouchInput>>,
+    mut pen_events: ResMut<Events<PenInput>>,
+    mut gamepad_events: ResMut<Events<GamepadEventRaw>>,
+) {
+    let (recording, frame, next_input) = match &mut recorder.state {
+        RecorderState::Playing {
+            recording,
+            frame,
+            next_input,
+        } => match recordings.get(&*recording) {
+            Some(recording) => (recording, frame, next_input),
+            None => return,
+        },
+        _ => return,
+    };
+
+    keyboard_events.clear();
+    mouse_button_events.clear();
+    mouse_motion_events.clear();
+    mouse_wheel_events.clear();
+    touch_events.clear();
+    pen_events.clear();
+    gamepad_events.clear();
+
+    while let Some(input) = recording.inputs.get(*next_input) {
+        if input.frame > *frame {
+            break;
+        }
+        if input.frame < *frame {
+            warn!(
+                "Skipped an input of frame {} recorded out of order",
+                input.frame
+            );
+        } else {
+            match input.event.clone() {
+                RecordedInputEvent::Keyboard(event) => keyboard_events.send(event),
+                RecordedInputEvent::MouseButton(event) => mouse_button_events.send(event),
+                RecordedInputEvent::MouseMotion(event) => mouse_motion_events.send(event),
+                RecordedInputEvent::MouseWheel(event) => mouse_wheel_events.send(event),
+                RecordedInputEvent::Touch(event) => touch_events.send(event),
+                RecordedInputEvent::Pen(event) => pen_events.send(event),
+                RecordedInputEvent::Gamepad(event) => gamepad_events.send(event),
+            }
+        }
+        *next_input += 1;
+    }
+
+    *frame += 1;
+    if *frame >= recording.frames {
+        recorder.state = RecorderState::Idle;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        keyboard::KeyCode, mouse::AccumulatedMouseMotion, ElementState, Input, InputPlugin,
+    };
+    use bevy_asset::AssetPlugin;
+    use bevy_ecs::{system::Resource, world::Mut};
+    use bevy_math::Vec2;
+    use bevy_tasks::{IoTaskPool, TaskPool};
+
+    fn recorder_app() -> App {
+        let mut app = App::new();
+        app.insert_resource(IoTaskPool(TaskPool::new()))
+            .add_plugin(AssetPlugin)
+            .add_plugin(InputPlugin)
+            .add_plugin(InputRecorderPlugin);
+        app
+    }
+
+    fn press(key_code: KeyCode) -> KeyboardInput {
+        KeyboardInput {
+            scan_code: 0,
+            key_code: Some(key_code),
+            state: ElementState::Pressed,
+        }
+    }
+
+    fn send<T: Resource>(app: &mut App, event: T) {
+        app.world
+            .get_resource_mut::<Events<T>>()
+            .unwrap()
+            .send(event);
+    }
+
+    fn recorder(app: &mut App) -> Mut<'_, InputRecorder> {
+        app.world.get_resource_mut::<InputRecorder>().unwrap()
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let mut app = recorder_app();
+        // events sent before the recording starts aren't recorded
+        send(&mut app, press(KeyCode::B));
+        app.update();
+
+        recorder(&mut app).start_recording();
+        send(&mut app, press(KeyCode::A));
+        app.update();
+        app.update();
+        let motion = MouseMotion {
+            delta: Vec2::new(3.0, -2.0),
+        };
+        send(&mut app, motion.clone());
+        app.update();
+        let recording = recorder(&mut app).stop_recording().unwrap();
+        assert!(!recorder(&mut app).is_recording());
+
+        assert_eq!(recording.frames, 3);
+        assert_eq!(
+            recording.inputs,
+            vec![
+                RecordedInput {
+                    frame: 0,
+                    event: RecordedInputEvent::Keyboard(press(KeyCode::A)),
+                },
+                RecordedInput {
+                    frame: 2,
+                    event: RecordedInputEvent::MouseMotion(motion.clone()),
+                },
+            ]
+        );
+        let ron = recording.to_ron().unwrap();
+        assert_eq!(InputRecording::from_ron(&ron).unwrap(), recording);
+
+        // played back on the same frames, in place of the live input events
+        let mut app = recorder_app();
+        let recording = app
+            .world
+            .get_resource_mut::<Assets<InputRecording>>()
+            .unwrap()
+            .add(recording);
+        recorder(&mut app).play(recording);
+        send(&mut app, press(KeyCode::B));
+        app.update();
+        let keys = app.world.get_resource::<Input<KeyCode>>().unwrap();
+        assert!(keys.just_pressed(KeyCode::A));
+        assert!(!keys.pressed(KeyCode::B));
+
+        app.update();
+        let mouse_motion = |app: &App| {
+            app.world
+                .get_resource::<AccumulatedMouseMotion>()
+                .unwrap()
+                .delta
+        };
+        assert_eq!(mouse_motion(&app), Vec2::ZERO);
+        assert!(recorder(&mut app).is_playing());
+
+        app.update();
+        assert_eq!(mouse_motion(&app), motion.delta);
+        assert!(!recorder(&mut app).is_playing());
+    }
+}
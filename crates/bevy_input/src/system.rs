@@ -1,9 +1,13 @@
 use crate::{
     keyboard::{KeyCode, KeyboardInput},
-    ElementState,
+    ElementState, Input,
 };
 use bevy_app::AppExit;
-use bevy_ecs::prelude::{EventReader, EventWriter};
+use bevy_ecs::{
+    prelude::{EventReader, EventWriter},
+    schedule::Stepping,
+    system::{Res, ResMut},
+};
 
 /// Sends the AppExit event whenever the "esc" key is pressed.
 pub fn exit_on_esc_system(
@@ -18,3 +22,29 @@ pub fn exit_on_esc_system(
         }
     }
 }
+
+/// Controls [`Stepping`] with the keyboard: "F8" toggles stepping, "F9" runs the next system of
+/// the stepped stages and "F10" runs the rest of the current stepped stage. This system must run
+/// in a stage that isn't stepped.
+pub fn stepping_keyboard_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    stepping: Option<ResMut<Stepping>>,
+) {
+    let mut stepping = match stepping {
+        Some(stepping) => stepping,
+        None => return,
+    };
+    if keyboard_input.just_pressed(KeyCode::F8) {
+        if stepping.is_enabled() {
+            stepping.disable();
+        } else {
+            stepping.enable();
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::F9) {
+        stepping.step_system();
+    }
+    if keyboard_input.just_pressed(KeyCode::F10) {
+        stepping.step_stage();
+    }
+}
@@ -21,6 +21,8 @@ use bevy_scene::ScenePlugin;
 use bevy_sprite::SpritePlugin;
 #[cfg(feature = "bevy_text")]
 use bevy_text::TextPlugin;
+#[cfg(feature = "bevy_tilemap")]
+use bevy_tilemap::TileMapPlugin;
 use bevy_transform::TransformPlugin;
 #[cfg(feature = "bevy_ui")]
 use bevy_ui::UiPlugin;
@@ -47,6 +49,7 @@ use bevy_winit::WinitPlugin;
 /// * [`AudioPlugin`] - with feature `bevy_audio`
 /// * [`GilrsPlugin`] - with feature `bevy_gilrs`
 /// * [`GltfPlugin`] - with feature `bevy_gltf`
+/// * [`TileMapPlugin`] - with feature `bevy_tilemap`
 /// * [`WinitPlugin`] - with feature `bevy_winit`
 /// * [`WgpuPlugin`] - with feature `bevy_wgpu`
 ///
@@ -91,6 +94,9 @@ impl PluginGroup for DefaultPlugins {
         #[cfg(feature = "bevy_gltf")]
         group.add(GltfPlugin::default());
 
+        #[cfg(feature = "bevy_tilemap")]
+        group.add(TileMapPlugin::default());
+
         #[cfg(feature = "bevy_winit")]
         group.add(WinitPlugin::default());
 
@@ -123,6 +123,12 @@ pub mod text {
     pub use bevy_text::*;
 }
 
+#[cfg(feature = "bevy_tilemap")]
+pub mod tilemap {
+    //! Support for Tiled and LDtk map loading.
+    pub use bevy_tilemap::*;
+}
+
 #[cfg(feature = "bevy_ui")]
 pub mod ui {
     //! User interface components and widgets.
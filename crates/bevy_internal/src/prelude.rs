@@ -31,6 +31,10 @@ pub use crate::sprite::prelude::*;
 #[cfg(feature = "bevy_text")]
 pub use crate::text::prelude::*;
 
+#[doc(hidden)]
+#[cfg(feature = "bevy_tilemap")]
+pub use crate::tilemap::prelude::*;
+
 #[doc(hidden)]
 #[cfg(feature = "bevy_ui")]
 pub use crate::ui::prelude::*;
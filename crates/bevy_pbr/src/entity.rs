@@ -1,4 +1,7 @@
-use crate::{light::PointLight, material::StandardMaterial, render_graph::PBR_PIPELINE_HANDLE};
+use crate::{
+    light::PointLight, material::StandardMaterial, reflection_probe::ReflectionProbe,
+    render_graph::PBR_PIPELINE_HANDLE,
+};
 use bevy_asset::Handle;
 use bevy_ecs::bundle::Bundle;
 use bevy_render::{
@@ -47,3 +50,11 @@ pub struct PointLightBundle {
     pub transform: Transform,
     pub global_transform: GlobalTransform,
 }
+
+/// A component bundle for "reflection probe" entities
+#[derive(Debug, Bundle, Default)]
+pub struct ReflectionProbeBundle {
+    pub reflection_probe: ReflectionProbe,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+}
@@ -3,10 +3,12 @@ pub mod render_graph;
 mod entity;
 mod light;
 mod material;
+mod reflection_probe;
 
 pub use entity::*;
 pub use light::*;
 pub use material::*;
+pub use reflection_probe::*;
 
 pub mod prelude {
     #[doc(hidden)]
@@ -14,15 +16,24 @@ pub mod prelude {
         entity::*,
         light::{DirectionalLight, PointLight},
         material::StandardMaterial,
+        reflection_probe::ReflectionProbe,
     };
 }
 
 use bevy_app::prelude::*;
 use bevy_asset::{AddAsset, Assets, Handle};
-use bevy_render::{prelude::Color, shader};
+use bevy_ecs::schedule::{ParallelSystemDescriptorCoercion, SystemLabel};
+use bevy_render::{prelude::Color, shader, RenderSystem};
+use bevy_transform::TransformSystem;
 use material::StandardMaterial;
 use render_graph::add_pbr_graph;
 
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
+pub enum PbrSystem {
+    ReflectionProbeCapture,
+    ReflectionProbeAssignment,
+}
+
 /// NOTE: this isn't PBR yet. consider this name "aspirational" :)
 #[derive(Default)]
 pub struct PbrPlugin;
@@ -31,10 +42,34 @@ impl Plugin for PbrPlugin {
     fn build(&self, app: &mut App) {
         app.add_asset::<StandardMaterial>()
             .register_type::<PointLight>()
+            .register_type::<ReflectionProbe>()
+            .register_type::<ReflectionProbeCapture>()
+            .init_resource::<ReflectionProbeCaptureSettings>()
+            .init_resource::<ReflectionProbeCaptureTarget>()
+            .add_startup_system(spawn_reflection_probe_capture_cameras_system)
             .add_system_to_stage(
                 CoreStage::PostUpdate,
                 shader::asset_shader_defs_system::<StandardMaterial>,
             )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                reflection_probe_capture_system
+                    .label(PbrSystem::ReflectionProbeCapture)
+                    .after(TransformSystem::TransformPropagate)
+                    .before(RenderSystem::VisibleEntities),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                reflection_probe_assignment_system
+                    .label(PbrSystem::ReflectionProbeAssignment)
+                    .after(TransformSystem::TransformPropagate)
+                    .after(PbrSystem::ReflectionProbeCapture),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                shader::shader_defs_system::<ActiveReflectionProbe>
+                    .after(PbrSystem::ReflectionProbeAssignment),
+            )
             .init_resource::<AmbientLight>();
         add_pbr_graph(&mut app.world);
 
@@ -37,11 +37,22 @@ pub struct StandardMaterial {
     // Use a color for user friendliness even though we technically don't use the alpha channel
     // Might be used in the future for exposure correction in HDR
     pub emissive: Color,
+    /// If used together with an emissive_texture, this is factored into the final emissive color
+    /// as `emissive * emissive_texture_value`
     #[shader_def]
     pub emissive_texture: Option<Handle<Texture>>,
+    /// Multiplies the emissive color. Values above 1.0 produce HDR colors, which make bright
+    /// surfaces like neon signs or LEDs stand out from lit surfaces after tone mapping
+    pub emissive_strength: f32,
     #[render_resources(ignore)]
     #[shader_def]
     pub unlit: bool,
+    /// Multiplies the `Vertex_Color` attribute of the mesh into the base color. Meshes drawn with
+    /// this material must have a [Mesh::ATTRIBUTE_COLOR](bevy_render::mesh::Mesh::ATTRIBUTE_COLOR)
+    /// attribute
+    #[render_resources(ignore)]
+    #[shader_def]
+    pub vertex_colors: bool,
 }
 
 impl Default for StandardMaterial {
@@ -68,7 +79,9 @@ impl Default for StandardMaterial {
             occlusion_texture: None,
             emissive: Color::BLACK,
             emissive_texture: None,
+            emissive_strength: 1.0,
             unlit: false,
+            vertex_colors: false,
         }
     }
 }
@@ -90,3 +103,76 @@ impl From<Handle<Texture>> for StandardMaterial {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_render::shader::{glsl_to_spirv, ShaderStage};
+
+    fn render_resource_index(material: &StandardMaterial, name: &str) -> usize {
+        (0..material.render_resources_len())
+            .find(|index| material.get_render_resource_name(*index) == Some(name))
+            .unwrap()
+    }
+
+    #[test]
+    fn emissive_strength_uniform() {
+        assert_eq!(StandardMaterial::default().emissive_strength, 1.0);
+        let material = StandardMaterial {
+            emissive_strength: 4.0,
+            ..Default::default()
+        };
+        // bound right after the emissive texture and its sampler
+        let index = render_resource_index(&material, "StandardMaterial_emissive_strength");
+        assert_eq!(
+            material.get_render_resource_name(index - 1),
+            Some("StandardMaterial_emissive_texture")
+        );
+
+        let resource = material.get_render_resource(index).unwrap();
+        let mut buffer = vec![0; resource.buffer_byte_len().unwrap()];
+        resource.write_buffer_bytes(&mut buffer);
+        assert_eq!(buffer, 4.0f32.to_ne_bytes());
+    }
+
+    fn compile_pbr_shaders(shader_defs: &[String]) {
+        glsl_to_spirv(
+            include_str!("render_graph/pbr_pipeline/pbr.vert"),
+            ShaderStage::Vertex,
+            Some(shader_defs),
+        )
+        .unwrap();
+        glsl_to_spirv(
+            include_str!("render_graph/pbr_pipeline/pbr.frag"),
+            ShaderStage::Fragment,
+            Some(shader_defs),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn pbr_shaders_compile() {
+        compile_pbr_shaders(&["STANDARDMATERIAL_EMISSIVE_TEXTURE".to_string()]);
+    }
+
+    #[test]
+    fn vertex_colors_shader_def() {
+        let has_vertex_colors = |material: &StandardMaterial| {
+            material
+                .iter_shader_defs()
+                .any(|shader_def| shader_def == "STANDARDMATERIAL_VERTEX_COLORS")
+        };
+        assert!(!has_vertex_colors(&StandardMaterial::default()));
+        let material = StandardMaterial {
+            vertex_colors: true,
+            ..Default::default()
+        };
+        assert!(has_vertex_colors(&material));
+
+        let shader_defs = material
+            .iter_shader_defs()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        compile_pbr_shaders(&shader_defs);
+    }
+}
@@ -0,0 +1,495 @@
+use crate::material::StandardMaterial;
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    query::{With, Without},
+    reflect::ReflectComponent,
+    system::{Commands, Query, Res, ResMut},
+};
+use bevy_math::{Mat4, Vec3};
+use bevy_reflect::Reflect;
+use bevy_render::{
+    camera::{ActiveCameras, Camera, CameraProjection, PerspectiveProjection, RenderLayers},
+    entity::PerspectiveCameraBundle,
+    renderer::RenderResources,
+    shader::ShaderDefs,
+    texture::{AddressMode, Extent3d, Texture, TextureDimension, TextureFormat},
+};
+use bevy_transform::components::{GlobalTransform, Transform};
+use bevy_window::WindowId;
+
+/// A reflection probe: a pre-captured image of the surroundings of a box shaped region, used for
+/// the specular reflections of every [StandardMaterial] mesh inside of it.
+///
+/// The box is centered on the probe's transform and extends `half_extents` along each local axis.
+/// Reflections are box projected, so they line up with the walls of rooms that match the box.
+///
+/// The `image` is an equirectangular panorama taken from the center of the box, with the local -Z
+/// axis at the center of the image. It can be baked ahead of time, or captured from the scene by
+/// adding a [ReflectionProbeCapture] to the probe.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct ReflectionProbe {
+    pub image: Handle<Texture>,
+    pub half_extents: Vec3,
+    pub intensity: f32,
+}
+
+impl Default for ReflectionProbe {
+    fn default() -> Self {
+        ReflectionProbe {
+            image: Default::default(),
+            half_extents: Vec3::splat(5.0),
+            intensity: 1.0,
+        }
+    }
+}
+
+impl ReflectionProbe {
+    /// Maps world space into the local space of the probe, where its box spans `[-1, 1]` along
+    /// every axis.
+    fn world_to_probe(&self, global_transform: &GlobalTransform) -> Mat4 {
+        (global_transform.compute_matrix() * Mat4::from_scale(self.half_extents)).inverse()
+    }
+}
+
+/// Captures the surroundings of the [ReflectionProbe] of its entity into the `image` of the probe.
+///
+/// The probe is captured on the frame the component is added, and again after each call to
+/// [ReflectionProbeCapture::request]. Every [MainPass](bevy_render::render_graph::base::MainPass)
+/// entity is rendered in the six directions of a cube around the center of the probe, then
+/// the six faces are projected into the panorama. Captures are spread over frames, one probe at
+/// a time.
+///
+/// When the `image` of the probe isn't a texture of the size of a capture, it is replaced by a new
+/// one. The captured pixels only exist on the GPU.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct ReflectionProbeCapture {
+    requested: bool,
+}
+
+impl Default for ReflectionProbeCapture {
+    fn default() -> Self {
+        ReflectionProbeCapture { requested: true }
+    }
+}
+
+impl ReflectionProbeCapture {
+    /// Captures the probe again, for example after the scene around it changed.
+    pub fn request(&mut self) {
+        self.requested = true;
+    }
+
+    /// Whether the probe is waiting to be captured.
+    pub fn is_requested(&self) -> bool {
+        self.requested
+    }
+}
+
+/// Settings of the render graph nodes capturing [ReflectionProbe]s, read when the
+/// [PbrPlugin](crate::PbrPlugin) is built.
+#[derive(Debug, Clone)]
+pub struct ReflectionProbeCaptureSettings {
+    /// The size in pixels of each face of a capture. The captured panorama is four faces wide and
+    /// two faces tall.
+    pub face_size: u32,
+}
+
+impl Default for ReflectionProbeCaptureSettings {
+    fn default() -> Self {
+        ReflectionProbeCaptureSettings { face_size: 256 }
+    }
+}
+
+impl ReflectionProbeCaptureSettings {
+    pub fn image_size(&self) -> Extent3d {
+        Extent3d::new(self.face_size * 4, self.face_size * 2, 1)
+    }
+}
+
+/// The direction each face of a capture looks at and its up direction, in the local space of the
+/// probe. This must match the faces of `reflection_probe_capture.frag`.
+pub(crate) const CAPTURE_FACES: [([f32; 3], [f32; 3]); 6] = [
+    ([1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+    ([-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+    ([0.0, 1.0, 0.0], [0.0, 0.0, 1.0]),
+    ([0.0, -1.0, 0.0], [0.0, 0.0, -1.0]),
+    ([0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+    ([0.0, 0.0, -1.0], [0.0, 1.0, 0.0]),
+];
+
+/// The name of the camera rendering a face of the captures.
+pub fn reflection_probe_capture_camera(face: usize) -> String {
+    format!("reflection_probe_capture_{}", face)
+}
+
+/// One of the cameras rendering the faces of the captures. Between captures they render nothing.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ReflectionProbeCaptureCamera {
+    pub face: usize,
+}
+
+/// The [ReflectionProbe] captured this frame, if any.
+#[derive(Debug, Default)]
+pub struct ReflectionProbeCaptureTarget(pub(crate) Option<Handle<Texture>>);
+
+impl ReflectionProbeCaptureTarget {
+    /// The `image` of the probe the capture is rendered to.
+    pub fn image(&self) -> Option<&Handle<Texture>> {
+        self.0.as_ref()
+    }
+}
+
+/// The transform of the camera rendering a face of the capture of a probe. The probe's scale is
+/// ignored.
+fn capture_face_transform(probe_transform: &GlobalTransform, face: usize) -> Transform {
+    let (forward, up) = CAPTURE_FACES[face];
+    let face_rotation = Transform::identity()
+        .looking_at(Vec3::from(forward), Vec3::from(up))
+        .rotation;
+    Transform {
+        translation: probe_transform.translation,
+        rotation: probe_transform.rotation * face_rotation,
+        scale: Vec3::ONE,
+    }
+}
+
+pub fn spawn_reflection_probe_capture_cameras_system(
+    mut commands: Commands,
+    mut active_cameras: ResMut<ActiveCameras>,
+) {
+    let perspective_projection = PerspectiveProjection {
+        fov: std::f32::consts::FRAC_PI_2,
+        aspect_ratio: 1.0,
+        near: 0.1,
+        ..Default::default()
+    };
+    for face in 0..CAPTURE_FACES.len() {
+        let name = reflection_probe_capture_camera(face);
+        active_cameras.add(&name);
+        commands
+            .spawn_bundle(PerspectiveCameraBundle {
+                camera: Camera {
+                    name: Some(name),
+                    // keeps the projection from following the size of a window
+                    window: WindowId::new(),
+                    projection_matrix: perspective_projection.get_projection_matrix(),
+                    depth_calculation: perspective_projection.depth_calculation(),
+                },
+                perspective_projection: perspective_projection.clone(),
+                ..Default::default()
+            })
+            .insert_bundle((ReflectionProbeCaptureCamera { face }, RenderLayers::none()));
+    }
+}
+
+/// Moves the capture cameras to the next probe waiting to be captured, and makes them render the
+/// scene for this frame.
+#[allow(clippy::type_complexity)]
+pub fn reflection_probe_capture_system(
+    settings: Res<ReflectionProbeCaptureSettings>,
+    mut capture_target: ResMut<ReflectionProbeCaptureTarget>,
+    mut textures: ResMut<Assets<Texture>>,
+    mut probes: Query<(
+        &mut ReflectionProbe,
+        &mut ReflectionProbeCapture,
+        &GlobalTransform,
+    )>,
+    mut cameras: Query<
+        (
+            &ReflectionProbeCaptureCamera,
+            &mut Transform,
+            &mut GlobalTransform,
+            &mut RenderLayers,
+        ),
+        Without<ReflectionProbe>,
+    >,
+) {
+    capture_target.0 = None;
+    let mut probe_transform = None;
+    if let Some((mut probe, mut capture, global_transform)) = probes
+        .iter_mut()
+        .find(|(_, capture, _)| capture.is_requested())
+    {
+        capture.requested = false;
+        let image_size = settings.image_size();
+        let format = TextureFormat::default();
+        if textures
+            .get(&probe.image)
+            .is_none_or(|image| image.size != image_size || image.format != format)
+        {
+            let mut image =
+                Texture::new_fill(image_size, TextureDimension::D2, &[0, 0, 0, 255], format);
+            // the panorama wraps around horizontally
+            image.sampler.address_mode_u = AddressMode::Repeat;
+            probe.image = textures.add(image);
+        }
+        capture_target.0 = Some(probe.image.clone_weak());
+        probe_transform = Some(*global_transform);
+    }
+
+    for (camera, mut transform, mut global_transform, mut render_layers) in cameras.iter_mut() {
+        match probe_transform {
+            Some(ref probe_transform) => {
+                *transform = capture_face_transform(probe_transform, camera.face);
+                // the cameras are positioned after the transforms are propagated
+                *global_transform = GlobalTransform::from(*transform);
+                *render_layers = RenderLayers::all();
+            }
+            None => {
+                if *render_layers != RenderLayers::none() {
+                    *render_layers = RenderLayers::none();
+                }
+            }
+        }
+    }
+}
+
+/// The [ReflectionProbe] used by a mesh. This is managed by
+/// [reflection_probe_assignment_system], which picks the smallest probe containing the mesh's
+/// origin.
+#[derive(Component, Debug, Clone, PartialEq, RenderResources, ShaderDefs)]
+pub struct ActiveReflectionProbe {
+    #[shader_def]
+    image: Option<Handle<Texture>>,
+    world_to_probe: Mat4,
+    intensity: f32,
+}
+
+impl Default for ActiveReflectionProbe {
+    fn default() -> Self {
+        ActiveReflectionProbe {
+            image: None,
+            world_to_probe: Mat4::IDENTITY,
+            intensity: 0.0,
+        }
+    }
+}
+
+impl ActiveReflectionProbe {
+    /// The image of the probe currently used by the mesh, if any.
+    pub fn image(&self) -> Option<&Handle<Texture>> {
+        self.image.as_ref()
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn reflection_probe_assignment_system(
+    mut commands: Commands,
+    probes: Query<(&ReflectionProbe, &GlobalTransform)>,
+    mut meshes: Query<
+        (Entity, &GlobalTransform, Option<&mut ActiveReflectionProbe>),
+        With<Handle<StandardMaterial>>,
+    >,
+) {
+    let probes = probes
+        .iter()
+        .map(|(probe, global_transform)| {
+            let volume = (global_transform.scale * probe.half_extents).abs();
+            (
+                probe,
+                probe.world_to_probe(global_transform),
+                volume.x * volume.y * volume.z,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for (entity, global_transform, active_probe) in meshes.iter_mut() {
+        let closest_probe = probes
+            .iter()
+            .filter(|(_, world_to_probe, _)| {
+                let position = world_to_probe.transform_point3(global_transform.translation);
+                position.abs().max_element() <= 1.0
+            })
+            .min_by(|(_, _, a), (_, _, b)| a.partial_cmp(b).unwrap());
+
+        let probe = match closest_probe {
+            Some((probe, world_to_probe, _)) => ActiveReflectionProbe {
+                image: Some(probe.image.clone()),
+                world_to_probe: *world_to_probe,
+                intensity: probe.intensity,
+            },
+            None => ActiveReflectionProbe::default(),
+        };
+
+        match active_probe {
+            // only write on change to avoid uploading the uniforms every frame
+            Some(mut active_probe) => {
+                if *active_probe != probe {
+                    *active_probe = probe;
+                }
+            }
+            None => {
+                commands.entity(entity).insert(probe);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin};
+    use bevy_math::Quat;
+    use bevy_tasks::{IoTaskPool, TaskPool};
+
+    fn assert_near(a: Vec3, b: Vec3) {
+        assert!(a.abs_diff_eq(b, 1e-5), "{} != {}", a, b);
+    }
+
+    #[test]
+    fn face_transforms() {
+        let probe_transform = GlobalTransform::from_translation(Vec3::new(1.0, 2.0, 3.0));
+        for (face, (forward, up)) in CAPTURE_FACES.iter().enumerate() {
+            let transform = capture_face_transform(&probe_transform, face);
+            assert_eq!(transform.translation, Vec3::new(1.0, 2.0, 3.0));
+            // cameras look along their local -Z axis
+            assert_near(transform.rotation * -Vec3::Z, Vec3::from(*forward));
+            assert_near(transform.rotation * Vec3::Y, Vec3::from(*up));
+        }
+
+        let probe_transform = GlobalTransform {
+            rotation: Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+            scale: Vec3::splat(2.0),
+            ..Default::default()
+        };
+        let transform = capture_face_transform(&probe_transform, 0);
+        assert_near(transform.rotation * -Vec3::Z, -Vec3::Z);
+        assert_eq!(transform.scale, Vec3::ONE);
+    }
+
+    fn capture_app() -> App {
+        let mut app = App::new();
+        app.insert_resource(IoTaskPool(TaskPool::new()))
+            .add_plugin(AssetPlugin)
+            .add_asset::<Texture>()
+            .init_resource::<ActiveCameras>()
+            .init_resource::<ReflectionProbeCaptureSettings>()
+            .init_resource::<ReflectionProbeCaptureTarget>()
+            .add_startup_system(spawn_reflection_probe_capture_cameras_system)
+            .add_system(reflection_probe_capture_system);
+        app
+    }
+
+    fn spawn_probe(app: &mut App, translation: Vec3) -> Entity {
+        app.world
+            .spawn()
+            .insert_bundle((
+                ReflectionProbe::default(),
+                ReflectionProbeCapture::default(),
+                GlobalTransform::from_translation(translation),
+            ))
+            .id()
+    }
+
+    fn cameras(app: &mut App) -> Vec<(usize, Vec3, RenderLayers)> {
+        let mut cameras = app
+            .world
+            .query::<(
+                &ReflectionProbeCaptureCamera,
+                &GlobalTransform,
+                &RenderLayers,
+            )>()
+            .iter(&app.world)
+            .map(|(camera, transform, layers)| (camera.face, transform.translation, *layers))
+            .collect::<Vec<_>>();
+        cameras.sort_by_key(|(face, _, _)| *face);
+        cameras
+    }
+
+    fn capture_target(app: &App) -> Option<Handle<Texture>> {
+        app.world
+            .get_resource::<ReflectionProbeCaptureTarget>()
+            .unwrap()
+            .image()
+            .cloned()
+    }
+
+    #[test]
+    fn spawn_capture_cameras() {
+        let mut app = capture_app();
+        app.update();
+
+        let active_cameras = app.world.get_resource::<ActiveCameras>().unwrap();
+        for face in 0..CAPTURE_FACES.len() {
+            assert!(active_cameras
+                .get(&reflection_probe_capture_camera(face))
+                .is_some());
+        }
+        let cameras = cameras(&mut app);
+        assert_eq!(cameras.len(), CAPTURE_FACES.len());
+        for (face, (camera_face, _, layers)) in cameras.into_iter().enumerate() {
+            assert_eq!(camera_face, face);
+            assert_eq!(layers, RenderLayers::none());
+        }
+    }
+
+    #[test]
+    fn capture_one_probe_per_frame() {
+        let mut app = capture_app();
+        let probes = [
+            spawn_probe(&mut app, Vec3::new(1.0, 0.0, 0.0)),
+            spawn_probe(&mut app, Vec3::new(-1.0, 0.0, 0.0)),
+        ];
+        let image_size = ReflectionProbeCaptureSettings::default().image_size();
+
+        let mut captured = Vec::new();
+        for _ in 0..probes.len() {
+            app.update();
+            let target = capture_target(&app).expect("a probe is captured");
+            let probe = probes
+                .iter()
+                .copied()
+                .find(|probe| {
+                    let probe = app.world.get::<ReflectionProbe>(*probe).unwrap();
+                    probe.image == target
+                })
+                .expect("the target is the image of a probe");
+            assert!(!app
+                .world
+                .get::<ReflectionProbeCapture>(probe)
+                .unwrap()
+                .is_requested());
+            assert!(!captured.contains(&probe), "each probe is captured once");
+            captured.push(probe);
+
+            let textures = app.world.get_resource::<Assets<Texture>>().unwrap();
+            let image = textures.get(&target).unwrap();
+            assert_eq!(image.size, image_size);
+            assert_eq!(image.sampler.address_mode_u, AddressMode::Repeat);
+
+            let translation = app.world.get::<GlobalTransform>(probe).unwrap().translation;
+            for (_, camera_translation, layers) in cameras(&mut app) {
+                assert_eq!(camera_translation, translation);
+                assert_eq!(layers, RenderLayers::all());
+            }
+        }
+
+        app.update();
+        assert!(capture_target(&app).is_none());
+        for (_, _, layers) in cameras(&mut app) {
+            assert_eq!(layers, RenderLayers::none());
+        }
+
+        // a new capture renders to the image of the previous one
+        let image = app
+            .world
+            .get::<ReflectionProbe>(probes[1])
+            .unwrap()
+            .image
+            .clone();
+        app.world
+            .get_mut::<ReflectionProbeCapture>(probes[1])
+            .unwrap()
+            .request();
+        app.update();
+        assert_eq!(capture_target(&app), Some(image.clone()));
+        assert_eq!(
+            app.world.get::<ReflectionProbe>(probes[1]).unwrap().image,
+            image
+        );
+    }
+}
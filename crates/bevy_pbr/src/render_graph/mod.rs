@@ -1,15 +1,31 @@
 mod lights_node;
 mod pbr_pipeline;
+mod reflection_probe_capture_node;
 
 use bevy_ecs::world::World;
 pub use lights_node::*;
 pub use pbr_pipeline::*;
+pub use reflection_probe_capture_node::*;
 
 /// the names of pbr graph nodes
 pub mod node {
     pub const TRANSFORM: &str = "transform";
     pub const STANDARD_MATERIAL: &str = "standard_material";
     pub const LIGHTS: &str = "lights";
+    pub const REFLECTION_PROBE: &str = "reflection_probe";
+    pub const REFLECTION_PROBE_CAPTURE: &str = "reflection_probe_capture";
+    pub const REFLECTION_PROBE_CAPTURE_DEPTH: &str = "reflection_probe_capture_depth";
+    /// Only exists with multisampling
+    pub const REFLECTION_PROBE_CAPTURE_SAMPLED_COLOR: &str =
+        "reflection_probe_capture_sampled_color";
+
+    pub fn reflection_probe_capture_face(face: usize) -> String {
+        format!("reflection_probe_capture_face_{}", face)
+    }
+
+    pub fn reflection_probe_capture_pass(face: usize) -> String {
+        format!("reflection_probe_capture_pass_{}", face)
+    }
 }
 
 /// the names of pbr uniforms
@@ -17,11 +33,14 @@ pub mod uniform {
     pub const LIGHTS: &str = "Lights";
 }
 
-use crate::prelude::StandardMaterial;
+use crate::{prelude::StandardMaterial, ActiveReflectionProbe, ReflectionProbeCaptureSettings};
 use bevy_asset::Assets;
 use bevy_render::{
     pipeline::PipelineDescriptor,
-    render_graph::{base, AssetRenderResourcesNode, RenderGraph, RenderResourcesNode},
+    render_graph::{
+        base::{self, Msaa},
+        build_fullscreen_pipeline, AssetRenderResourcesNode, RenderGraph, RenderResourcesNode,
+    },
     shader::Shader,
 };
 use bevy_transform::prelude::GlobalTransform;
@@ -44,6 +63,10 @@ pub(crate) fn add_pbr_graph(world: &mut World) {
             node::LIGHTS,
             LightsNode::new(MAX_POINT_LIGHTS, MAX_DIRECTIONAL_LIGHTS),
         );
+        graph.add_system_node(
+            node::REFLECTION_PROBE,
+            RenderResourcesNode::<ActiveReflectionProbe>::new(true),
+        );
 
         // TODO: replace these with "autowire" groups
         graph
@@ -55,10 +78,27 @@ pub(crate) fn add_pbr_graph(world: &mut World) {
         graph
             .add_node_edge(node::LIGHTS, base::node::MAIN_PASS)
             .unwrap();
+        graph
+            .add_node_edge(node::REFLECTION_PROBE, base::node::MAIN_PASS)
+            .unwrap();
+    }
+    {
+        let world = world.cell();
+        let mut graph = world.get_resource_mut::<RenderGraph>().unwrap();
+        let msaa = world.get_resource::<Msaa>().unwrap();
+        let settings = world
+            .get_resource::<ReflectionProbeCaptureSettings>()
+            .unwrap();
+        add_reflection_probe_capture_graph(&mut graph, &msaa, &settings);
     }
-    let pipeline = build_pbr_pipeline(&mut world.get_resource_mut::<Assets<Shader>>().unwrap());
+    let world = world.cell();
+    let mut shaders = world.get_resource_mut::<Assets<Shader>>().unwrap();
     let mut pipelines = world
         .get_resource_mut::<Assets<PipelineDescriptor>>()
         .unwrap();
-    pipelines.set_untracked(PBR_PIPELINE_HANDLE, pipeline);
+    pipelines.set_untracked(PBR_PIPELINE_HANDLE, build_pbr_pipeline(&mut shaders));
+    pipelines.set_untracked(
+        REFLECTION_PROBE_CAPTURE_PIPELINE_HANDLE,
+        build_fullscreen_pipeline(&mut shaders, include_str!("reflection_probe_capture.frag")),
+    );
 }
@@ -57,6 +57,10 @@ layout(location = 2) in vec2 v_Uv;
 layout(location = 3) in vec4 v_WorldTangent;
 #endif
 
+#ifdef STANDARDMATERIAL_VERTEX_COLORS
+layout(location = 4) in vec4 v_Color;
+#endif
+
 layout(location = 0) out vec4 o_Target;
 
 layout(set = 0, binding = 0) uniform CameraViewProj {
@@ -125,6 +129,22 @@ layout(set = 3,
        binding = 14) uniform sampler StandardMaterial_emissive_texture_sampler;
 #    endif
 
+layout(set = 3, binding = 15) uniform StandardMaterial_emissive_strength {
+    float emissive_strength;
+};
+
+#    ifdef ACTIVEREFLECTIONPROBE_IMAGE
+layout(set = 2, binding = 1) uniform texture2D ActiveReflectionProbe_image;
+layout(set = 2,
+       binding = 2) uniform sampler ActiveReflectionProbe_image_sampler;
+layout(set = 2, binding = 3) uniform ActiveReflectionProbe_world_to_probe {
+    mat4 WorldToProbe;
+};
+layout(set = 2, binding = 4) uniform ActiveReflectionProbe_intensity {
+    float probe_intensity;
+};
+#    endif
+
 #    define saturate(x) clamp(x, 0.0, 1.0)
 const float PI = 3.141592653589793;
 
@@ -283,6 +303,28 @@ vec3 reinhard_extended_luminance(vec3 color, float max_white_l) {
     return change_luminance(color, l_new);
 }
 
+#    ifdef ACTIVEREFLECTIONPROBE_IMAGE
+// Samples the reflection probe in direction R as seen from world_position. The reflected ray is
+// intersected with the box of the probe, so that reflections of the walls of a room line up.
+// See https://seblagarde.wordpress.com/2012/09/29/image-based-lighting-approaches-and-parallax-corrected-cubemap/
+vec3 reflection_probe(vec3 world_position, vec3 R) {
+    // in probe space the box spans [-1, 1] along every axis
+    vec3 position = (WorldToProbe * vec4(world_position, 1.0)).xyz;
+    vec3 direction = mat3(WorldToProbe) * R;
+    vec3 first_plane = (vec3(1.0) - position) / direction;
+    vec3 second_plane = (vec3(-1.0) - position) / direction;
+    vec3 furthest_plane = max(first_plane, second_plane);
+    float hit_distance = min(min(furthest_plane.x, furthest_plane.y), furthest_plane.z);
+    vec3 hit = position + direction * max(hit_distance, 0.0);
+
+    // the probe was captured from the center of the box
+    vec3 sample_direction = normalize(inverse(mat3(WorldToProbe)) * hit);
+    vec2 uv = vec2(atan(sample_direction.x, -sample_direction.z) / (2.0 * PI) + 0.5,
+                   acos(clamp(sample_direction.y, -1.0, 1.0)) / PI);
+    return texture(sampler2D(ActiveReflectionProbe_image, ActiveReflectionProbe_image_sampler), uv).rgb * probe_intensity;
+}
+#    endif
+
 vec3 point_light(PointLight light, float roughness, float NdotV, vec3 N, vec3 V, vec3 R, vec3 F0, vec3 diffuseColor) {
     vec3 light_to_frag = light.pos.xyz - v_WorldPosition.xyz;
     float distance_square = dot(light_to_frag, light_to_frag);
@@ -356,6 +398,9 @@ void main() {
                                       StandardMaterial_base_color_texture_sampler),
                             v_Uv);
 #endif
+#ifdef STANDARDMATERIAL_VERTEX_COLORS
+    output_color *= v_Color;
+#endif
 
 #ifndef STANDARDMATERIAL_UNLIT
     // calculate non-linear roughness from linear perceptualRoughness
@@ -430,9 +475,16 @@ void main() {
     vec3 diffuse_ambient = EnvBRDFApprox(diffuseColor, 1.0, NdotV);
     vec3 specular_ambient = EnvBRDFApprox(F0, perceptual_roughness, NdotV);
 
+    vec3 specular_environment = AmbientColor.xyz;
+#    ifdef ACTIVEREFLECTIONPROBE_IMAGE
+    // probes don't have prefiltered mips yet, so rough surfaces fade to the ambient light instead
+    // of blurring the reflection
+    specular_environment = mix(reflection_probe(v_WorldPosition, R), AmbientColor.xyz, perceptual_roughness);
+#    endif
+
     output_color.rgb = light_accum;
-    output_color.rgb += (diffuse_ambient + specular_ambient) * AmbientColor.xyz * occlusion;
-    output_color.rgb += emissive.rgb * output_color.a;
+    output_color.rgb += (diffuse_ambient * AmbientColor.xyz + specular_ambient * specular_environment) * occlusion;
+    output_color.rgb += emissive.rgb * emissive_strength * output_color.a;
 
     // tone_mapping
     output_color.rgb = reinhard_luminance(output_color.rgb);
@@ -8,6 +8,10 @@ layout(location = 2) in vec2 Vertex_Uv;
 layout(location = 3) in vec4 Vertex_Tangent;
 #endif
 
+#ifdef STANDARDMATERIAL_VERTEX_COLORS
+layout(location = 4) in vec4 Vertex_Color;
+#endif
+
 layout(location = 0) out vec3 v_WorldPosition;
 layout(location = 1) out vec3 v_WorldNormal;
 layout(location = 2) out vec2 v_Uv;
@@ -20,6 +24,10 @@ layout(set = 0, binding = 0) uniform CameraViewProj {
 layout(location = 3) out vec4 v_WorldTangent;
 #endif
 
+#ifdef STANDARDMATERIAL_VERTEX_COLORS
+layout(location = 4) out vec4 v_Color;
+#endif
+
 layout(set = 2, binding = 0) uniform Transform {
     mat4 Model;
 };
@@ -31,6 +39,9 @@ void main() {
     v_Uv = Vertex_Uv;
 #ifdef STANDARDMATERIAL_NORMAL_MAP
     v_WorldTangent = vec4(mat3(Model) * Vertex_Tangent.xyz, Vertex_Tangent.w);
+#endif
+#ifdef STANDARDMATERIAL_VERTEX_COLORS
+    v_Color = Vertex_Color;
 #endif
     gl_Position = ViewProj * world_position;
 }
@@ -0,0 +1,82 @@
+#version 450
+
+const float PI = 3.141592653589793;
+
+layout(location = 0) in vec2 v_Uv;
+
+layout(location = 0) out vec4 o_Target;
+
+layout(set = 0, binding = 0) uniform texture2D Face0;
+layout(set = 0, binding = 1) uniform sampler Face0_sampler;
+layout(set = 0, binding = 2) uniform texture2D Face1;
+layout(set = 0, binding = 3) uniform sampler Face1_sampler;
+layout(set = 0, binding = 4) uniform texture2D Face2;
+layout(set = 0, binding = 5) uniform sampler Face2_sampler;
+layout(set = 0, binding = 6) uniform texture2D Face3;
+layout(set = 0, binding = 7) uniform sampler Face3_sampler;
+layout(set = 0, binding = 8) uniform texture2D Face4;
+layout(set = 0, binding = 9) uniform sampler Face4_sampler;
+layout(set = 0, binding = 10) uniform texture2D Face5;
+layout(set = 0, binding = 11) uniform sampler Face5_sampler;
+
+// the direction each face looks at and its up direction, matching CAPTURE_FACES in
+// bevy_pbr/src/reflection_probe.rs
+const vec3 FACE_FORWARD[6] = vec3[](
+    vec3(1.0, 0.0, 0.0),
+    vec3(-1.0, 0.0, 0.0),
+    vec3(0.0, 1.0, 0.0),
+    vec3(0.0, -1.0, 0.0),
+    vec3(0.0, 0.0, 1.0),
+    vec3(0.0, 0.0, -1.0)
+);
+const vec3 FACE_UP[6] = vec3[](
+    vec3(0.0, 1.0, 0.0),
+    vec3(0.0, 1.0, 0.0),
+    vec3(0.0, 0.0, 1.0),
+    vec3(0.0, 0.0, -1.0),
+    vec3(0.0, 1.0, 0.0),
+    vec3(0.0, 1.0, 0.0)
+);
+
+// the faces aren't sampled in uniform control flow, so the mip level is explicit
+vec3 sample_face(int face, vec2 uv) {
+    switch (face) {
+    case 0:
+        return textureLod(sampler2D(Face0, Face0_sampler), uv, 0.0).rgb;
+    case 1:
+        return textureLod(sampler2D(Face1, Face1_sampler), uv, 0.0).rgb;
+    case 2:
+        return textureLod(sampler2D(Face2, Face2_sampler), uv, 0.0).rgb;
+    case 3:
+        return textureLod(sampler2D(Face3, Face3_sampler), uv, 0.0).rgb;
+    case 4:
+        return textureLod(sampler2D(Face4, Face4_sampler), uv, 0.0).rgb;
+    default:
+        return textureLod(sampler2D(Face5, Face5_sampler), uv, 0.0).rgb;
+    }
+}
+
+void main() {
+    // the inverse of the panorama mapping of reflection_probe() in pbr.frag
+    float longitude = (v_Uv.x - 0.5) * 2.0 * PI;
+    float colatitude = v_Uv.y * PI;
+    vec3 direction = vec3(sin(longitude) * sin(colatitude),
+                          cos(colatitude),
+                          -cos(longitude) * sin(colatitude));
+
+    int face = 0;
+    float facing = dot(direction, FACE_FORWARD[0]);
+    for (int i = 1; i < 6; i++) {
+        float face_facing = dot(direction, FACE_FORWARD[i]);
+        if (face_facing > facing) {
+            face = i;
+            facing = face_facing;
+        }
+    }
+
+    // project the direction on the face, like the 90 degree camera that rendered it
+    vec3 right = cross(FACE_FORWARD[face], FACE_UP[face]);
+    vec2 uv = vec2(0.5 + 0.5 * dot(direction, right) / facing,
+                   0.5 - 0.5 * dot(direction, FACE_UP[face]) / facing);
+    o_Target = vec4(sample_face(face, uv), 1.0);
+}
@@ -0,0 +1,388 @@
+use super::node;
+use crate::{
+    reflection_probe_capture_camera, ReflectionProbeCaptureSettings, ReflectionProbeCaptureTarget,
+    CAPTURE_FACES,
+};
+use bevy_asset::HandleUntyped;
+use bevy_ecs::world::World;
+use bevy_reflect::TypeUuid;
+use bevy_render::{
+    color::Color,
+    pass::{
+        LoadOp, Operations, PassDescriptor, RenderPassDepthStencilAttachment, TextureAttachment,
+    },
+    pipeline::PipelineDescriptor,
+    render_graph::{
+        base::{self, MainPass, Msaa},
+        CameraNode, Edge, FullscreenPassNode, Node, PassNode, RenderGraph, ResourceSlotInfo,
+        ResourceSlots, TextureNode,
+    },
+    renderer::{RenderContext, RenderResourceId, TextureId},
+    texture::{
+        Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsage,
+        TEXTURE_ASSET_INDEX,
+    },
+};
+
+pub const REFLECTION_PROBE_CAPTURE_PIPELINE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 0x2c9f61d3a85e4b07);
+
+/// A render graph [Node] that projects the six faces rendered for the capture of a
+/// [ReflectionProbe](crate::ReflectionProbe) into the panorama of the probe. Nothing is rendered
+/// on the frames no probe is captured.
+pub struct ReflectionProbeCaptureNode {
+    pass: FullscreenPassNode,
+    inputs: Vec<ResourceSlotInfo>,
+    image_size: Extent3d,
+    panorama: Option<TextureId>,
+}
+
+impl ReflectionProbeCaptureNode {
+    pub fn new(image_size: Extent3d) -> Self {
+        let mut pass = FullscreenPassNode::new(REFLECTION_PROBE_CAPTURE_PIPELINE_HANDLE.typed());
+        for face in 0..CAPTURE_FACES.len() {
+            pass.add_texture_input(&Self::face_input(face));
+        }
+        // the color attachment of the pass is the panorama owned by this node
+        let inputs = pass.input()[1..].to_vec();
+        ReflectionProbeCaptureNode {
+            pass,
+            inputs,
+            image_size,
+            panorama: None,
+        }
+    }
+
+    /// The name of the input slot of a face, which is also the name of its shader binding
+    pub fn face_input(face: usize) -> String {
+        format!("Face{}", face)
+    }
+}
+
+impl Node for ReflectionProbeCaptureNode {
+    fn input(&self) -> &[ResourceSlotInfo] {
+        &self.inputs
+    }
+
+    fn prepare(&mut self, world: &mut World) {
+        self.pass.prepare(world);
+    }
+
+    fn update(
+        &mut self,
+        world: &World,
+        render_context: &mut dyn RenderContext,
+        input: &ResourceSlots,
+        output: &mut ResourceSlots,
+    ) {
+        let image = match world
+            .get_resource::<ReflectionProbeCaptureTarget>()
+            .and_then(|capture_target| capture_target.image())
+            .and_then(|image| {
+                render_context
+                    .resources()
+                    .get_asset_resource(image, TEXTURE_ASSET_INDEX)
+            })
+            .and_then(|resource| resource.get_texture())
+        {
+            Some(image) => image,
+            None => return,
+        };
+
+        let image_size = self.image_size;
+        let panorama = *self.panorama.get_or_insert_with(|| {
+            render_context
+                .resources()
+                .create_texture(TextureDescriptor {
+                    size: image_size,
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: TextureDimension::D2,
+                    format: TextureFormat::default(),
+                    usage: TextureUsage::OUTPUT_ATTACHMENT | TextureUsage::COPY_SRC,
+                })
+        });
+        let mut pass_input = ResourceSlots::from(self.pass.input());
+        pass_input.set(0, RenderResourceId::Texture(panorama));
+        for face in 0..CAPTURE_FACES.len() {
+            pass_input.set(face + 1, input.get(face).unwrap());
+        }
+        self.pass.update(world, render_context, &pass_input, output);
+
+        // the image of the probe is a texture asset, which can't be rendered to
+        render_context.copy_texture_to_texture(
+            panorama,
+            [0, 0, 0],
+            0,
+            image,
+            [0, 0, 0],
+            0,
+            image_size,
+        );
+    }
+}
+
+fn face_texture_descriptor(
+    settings: &ReflectionProbeCaptureSettings,
+    sample_count: u32,
+    format: TextureFormat,
+    usage: TextureUsage,
+) -> TextureDescriptor {
+    TextureDescriptor {
+        size: Extent3d::new(settings.face_size, settings.face_size, 1),
+        mip_level_count: 1,
+        sample_count,
+        dimension: TextureDimension::D2,
+        format,
+        usage,
+    }
+}
+
+/// Adds the nodes capturing reflection probes. The faces are rendered with the same dependencies
+/// as the main pass, which waits for the capture so that it shows the probe captured this frame.
+pub(crate) fn add_reflection_probe_capture_graph(
+    graph: &mut RenderGraph,
+    msaa: &Msaa,
+    settings: &ReflectionProbeCaptureSettings,
+) {
+    let main_pass_dependencies = graph
+        .iter_node_inputs(base::node::MAIN_PASS)
+        .unwrap()
+        .filter(|(edge, _)| matches!(edge, Edge::NodeEdge { .. }))
+        .map(|(_, node_state)| node_state.id)
+        .collect::<Vec<_>>();
+
+    // the faces are rendered one after the other, so they share their depth and multisampled
+    // color attachments
+    graph.add_node(
+        node::REFLECTION_PROBE_CAPTURE_DEPTH,
+        TextureNode::new(
+            face_texture_descriptor(
+                settings,
+                msaa.samples,
+                TextureFormat::Depth32Float,
+                TextureUsage::OUTPUT_ATTACHMENT,
+            ),
+            None,
+            None,
+        ),
+    );
+    if msaa.samples > 1 {
+        graph.add_node(
+            node::REFLECTION_PROBE_CAPTURE_SAMPLED_COLOR,
+            TextureNode::new(
+                face_texture_descriptor(
+                    settings,
+                    msaa.samples,
+                    TextureFormat::default(),
+                    TextureUsage::OUTPUT_ATTACHMENT,
+                ),
+                None,
+                None,
+            ),
+        );
+    }
+
+    graph.add_node(
+        node::REFLECTION_PROBE_CAPTURE,
+        ReflectionProbeCaptureNode::new(settings.image_size()),
+    );
+    for face in 0..CAPTURE_FACES.len() {
+        let camera_name = reflection_probe_capture_camera(face);
+        graph.add_system_node(camera_name.clone(), CameraNode::new(camera_name.clone()));
+        graph.add_node(
+            node::reflection_probe_capture_face(face),
+            TextureNode::new(
+                face_texture_descriptor(
+                    settings,
+                    1,
+                    TextureFormat::default(),
+                    TextureUsage::OUTPUT_ATTACHMENT | TextureUsage::SAMPLED,
+                ),
+                None,
+                None,
+            ),
+        );
+
+        let mut pass_node = PassNode::<&MainPass>::new(PassDescriptor {
+            color_attachments: vec![msaa.color_attachment(
+                TextureAttachment::Input("color_attachment".to_string()),
+                TextureAttachment::Input("color_resolve_target".to_string()),
+                Operations {
+                    load: LoadOp::Clear(Color::BLACK),
+                    store: true,
+                },
+            )],
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                attachment: TextureAttachment::Input("depth".to_string()),
+                depth_ops: Some(Operations {
+                    load: LoadOp::Clear(1.0),
+                    store: true,
+                }),
+                stencil_ops: None,
+            }),
+            sample_count: msaa.samples,
+        });
+        pass_node.add_camera(&camera_name);
+        pass_node.use_default_clear_color(0);
+        let face_pass = node::reflection_probe_capture_pass(face);
+        graph.add_node(face_pass.clone(), pass_node);
+
+        if msaa.samples > 1 {
+            graph
+                .add_slot_edge(
+                    node::REFLECTION_PROBE_CAPTURE_SAMPLED_COLOR,
+                    TextureNode::TEXTURE,
+                    face_pass.clone(),
+                    "color_attachment",
+                )
+                .unwrap();
+            graph
+                .add_slot_edge(
+                    node::reflection_probe_capture_face(face),
+                    TextureNode::TEXTURE,
+                    face_pass.clone(),
+                    "color_resolve_target",
+                )
+                .unwrap();
+        } else {
+            graph
+                .add_slot_edge(
+                    node::reflection_probe_capture_face(face),
+                    TextureNode::TEXTURE,
+                    face_pass.clone(),
+                    "color_attachment",
+                )
+                .unwrap();
+        }
+        graph
+            .add_slot_edge(
+                node::REFLECTION_PROBE_CAPTURE_DEPTH,
+                TextureNode::TEXTURE,
+                face_pass.clone(),
+                "depth",
+            )
+            .unwrap();
+        graph
+            .add_node_edge(camera_name.clone(), face_pass.clone())
+            .unwrap();
+        for dependency in main_pass_dependencies.iter() {
+            graph.add_node_edge(*dependency, face_pass.clone()).unwrap();
+        }
+        // the faces share attachments, so their passes must not overlap
+        if face > 0 {
+            graph
+                .add_node_edge(
+                    node::reflection_probe_capture_pass(face - 1),
+                    face_pass.clone(),
+                )
+                .unwrap();
+        }
+
+        graph
+            .add_slot_edge(
+                node::reflection_probe_capture_face(face),
+                TextureNode::TEXTURE,
+                node::REFLECTION_PROBE_CAPTURE,
+                ReflectionProbeCaptureNode::face_input(face),
+            )
+            .unwrap();
+        graph
+            .add_node_edge(face_pass, node::REFLECTION_PROBE_CAPTURE)
+            .unwrap();
+    }
+    graph
+        .add_node_edge(node::REFLECTION_PROBE_CAPTURE, base::node::MAIN_PASS)
+        .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_render::{
+        render_graph::NodeId,
+        shader::{glsl_to_spirv, ShaderStage},
+    };
+
+    struct TestNode;
+
+    impl Node for TestNode {
+        fn update(
+            &mut self,
+            _: &World,
+            _: &mut dyn RenderContext,
+            _: &ResourceSlots,
+            _: &mut ResourceSlots,
+        ) {
+        }
+    }
+
+    fn node_inputs(graph: &RenderGraph, label: impl Into<String>) -> Vec<NodeId> {
+        graph
+            .iter_node_inputs(label.into())
+            .unwrap()
+            .map(|(_, node_state)| node_state.id)
+            .collect()
+    }
+
+    fn capture_graph(samples: u32) -> (RenderGraph, NodeId) {
+        let mut graph = RenderGraph::default();
+        let dependency = graph.add_node("dependency", TestNode);
+        graph.add_node(base::node::MAIN_PASS, TestNode);
+        graph
+            .add_node_edge("dependency", base::node::MAIN_PASS)
+            .unwrap();
+        add_reflection_probe_capture_graph(
+            &mut graph,
+            &Msaa { samples },
+            &ReflectionProbeCaptureSettings::default(),
+        );
+        (graph, dependency)
+    }
+
+    #[test]
+    fn capture_graph_edges() {
+        for samples in [1, 4] {
+            let (graph, dependency) = capture_graph(samples);
+            let id = |label: String| graph.get_node_id(label).unwrap();
+
+            let capture = id(node::REFLECTION_PROBE_CAPTURE.to_string());
+            assert!(node_inputs(&graph, base::node::MAIN_PASS).contains(&capture));
+            assert_eq!(
+                graph
+                    .get_node_id(node::REFLECTION_PROBE_CAPTURE_SAMPLED_COLOR)
+                    .is_ok(),
+                samples > 1
+            );
+
+            let capture_inputs = node_inputs(&graph, node::REFLECTION_PROBE_CAPTURE);
+            for face in 0..CAPTURE_FACES.len() {
+                let face_pass = node::reflection_probe_capture_pass(face);
+                let face_texture = id(node::reflection_probe_capture_face(face));
+                assert!(capture_inputs.contains(&id(face_pass.clone())));
+                assert!(capture_inputs.contains(&face_texture));
+
+                let pass_inputs = node_inputs(&graph, face_pass);
+                assert!(pass_inputs.contains(&dependency));
+                assert!(pass_inputs.contains(&id(reflection_probe_capture_camera(face))));
+                assert!(pass_inputs.contains(&face_texture));
+                assert!(pass_inputs.contains(&id(node::REFLECTION_PROBE_CAPTURE_DEPTH.to_string())));
+                if face > 0 {
+                    assert!(
+                        pass_inputs.contains(&id(node::reflection_probe_capture_pass(face - 1)))
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn capture_shader_compiles() {
+        glsl_to_spirv(
+            include_str!("reflection_probe_capture.frag"),
+            ShaderStage::Fragment,
+            None,
+        )
+        .unwrap();
+    }
+}
@@ -1,4 +1,5 @@
-use super::CameraProjection;
+use super::{CameraProjection, OrthographicProjection};
+use crate::{color::Color, render_graph::base::window_camera_name};
 use bevy_ecs::{
     change_detection::DetectChanges,
     component::Component,
@@ -7,12 +8,12 @@ use bevy_ecs::{
     prelude::QueryState,
     query::Added,
     reflect::ReflectComponent,
-    system::{QuerySet, Res},
+    system::{Query, QuerySet, Res},
 };
 use bevy_math::{Mat4, Vec2, Vec3};
 use bevy_reflect::{Reflect, ReflectDeserialize};
 use bevy_transform::components::GlobalTransform;
-use bevy_window::{WindowCreated, WindowId, WindowResized, Windows};
+use bevy_window::{WindowCreated, WindowId, WindowResized, WindowScaleFactorChanged, Windows};
 use serde::{Deserialize, Serialize};
 
 #[derive(Component, Default, Debug, Reflect)]
@@ -41,7 +42,46 @@ impl Default for DepthCalculation {
     }
 }
 
+/// Controls how the render target of a camera is cleared before the camera draws into it.
+///
+/// Cameras without this component fall back to the clear operations of the pass they render in,
+/// which for the main pass is the global [`ClearColor`](crate::pass::ClearColor) resource.
+/// Cameras that share a pass (like the default 2d and 3d cameras in the main pass) each clear the
+/// target with their own behavior before drawing. Cameras without this component drawing after the
+/// first camera of the pass keep what the previous cameras drew.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect_value(Component, PartialEq, Serialize, Deserialize)]
+pub enum ClearBehavior {
+    /// Clear color to the given value and clear depth.
+    Color(Color),
+    /// Keep both the color and depth contents of the target. Use this for overlay cameras that are
+    /// stacked on top of another camera's output.
+    DontClear,
+    /// Keep the color contents of the target but clear depth. Use this when a skybox (or any other
+    /// full screen geometry) is guaranteed to cover every pixel, so clearing color is wasted work.
+    Skybox,
+}
+
+impl Default for ClearBehavior {
+    fn default() -> Self {
+        ClearBehavior::Color(Color::rgb(0.4, 0.4, 0.4))
+    }
+}
+
 impl Camera {
+    /// Makes the camera draw to a window. A camera drawing to the primary window is renamed with
+    /// [`window_camera_name`], so that a camera named like a camera of the base graph is drawn by
+    /// the graph of the window.
+    pub fn set_window(&mut self, window_id: WindowId) {
+        if self.window.is_primary() {
+            self.name = self
+                .name
+                .as_ref()
+                .map(|name| window_camera_name(name, window_id));
+        }
+        self.window = window_id;
+    }
+
     /// Given a position in world space, use the camera to compute the screen space coordinates.
     pub fn world_to_screen(
         &self,
@@ -69,6 +109,7 @@ impl Camera {
 pub fn camera_system<T: CameraProjection + Component>(
     mut window_resized_events: EventReader<WindowResized>,
     mut window_created_events: EventReader<WindowCreated>,
+    mut window_scale_factor_changed_events: EventReader<WindowScaleFactorChanged>,
     windows: Res<Windows>,
     mut queries: QuerySet<(
         QueryState<(Entity, &mut Camera, &mut T)>,
@@ -96,6 +137,12 @@ pub fn camera_system<T: CameraProjection + Component>(
         changed_window_ids.push(event.id);
     }
 
+    for event in window_scale_factor_changed_events.iter() {
+        if !changed_window_ids.contains(&event.id) {
+            changed_window_ids.push(event.id);
+        }
+    }
+
     let mut added_cameras = vec![];
     for entity in &mut queries.q1().iter() {
         added_cameras.push(entity);
@@ -106,10 +153,41 @@ pub fn camera_system<T: CameraProjection + Component>(
                 || added_cameras.contains(&entity)
                 || camera_projection.is_changed()
             {
-                camera_projection.update(window.width(), window.height());
+                camera_projection.update_with_scale_factor(
+                    window.width(),
+                    window.height(),
+                    window.scale_factor() as f32,
+                );
                 camera.projection_matrix = camera_projection.get_projection_matrix();
                 camera.depth_calculation = camera_projection.depth_calculation();
             }
         }
     }
 }
+
+/// Rounds the translation of cameras with [`OrthographicProjection::snap_to_pixels`] to whole
+/// physical pixels of their window
+pub fn camera_pixel_snapping_system(
+    windows: Res<Windows>,
+    mut query: Query<(&Camera, &OrthographicProjection, &mut GlobalTransform)>,
+) {
+    for (camera, projection, mut transform) in query.iter_mut() {
+        if !projection.snap_to_pixels {
+            continue;
+        }
+        let window = match windows.get(camera.window) {
+            Some(window) if window.physical_width() > 0 => window,
+            _ => continue,
+        };
+        let pixel_size = projection.world_units_per_pixel(window.physical_width() as f32);
+        // snap the bottom left corner of the view, so that the pixels of the window line up with
+        // the world whatever the window origin is
+        let corner = Vec2::new(projection.left, projection.bottom) * projection.scale;
+        let translation = transform.translation.truncate() + corner;
+        let snapped = (translation / pixel_size).round() * pixel_size - corner;
+        if snapped != transform.translation.truncate() {
+            transform.translation.x = snapped.x;
+            transform.translation.y = snapped.y;
+        }
+    }
+}
@@ -7,6 +7,11 @@ use serde::{Deserialize, Serialize};
 pub trait CameraProjection {
     fn get_projection_matrix(&self) -> Mat4;
     fn update(&mut self, width: f32, height: f32);
+    /// Updates the projection for a window of `width` by `height` logical pixels, with
+    /// `scale_factor` physical pixels per logical pixel
+    fn update_with_scale_factor(&mut self, width: f32, height: f32, _scale_factor: f32) {
+        self.update(width, height);
+    }
     fn depth_calculation(&self) -> DepthCalculation;
 }
 
@@ -52,18 +57,24 @@ pub enum WindowOrigin {
     BottomLeft,
 }
 
-#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
-#[reflect_value(Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect_value(PartialEq, Serialize, Deserialize)]
 pub enum ScalingMode {
     /// Manually specify left/right/top/bottom values.
     /// Ignore window resizing; the image will stretch.
     None,
-    /// Match the window size. 1 world unit = 1 pixel.
+    /// Match the window size. 1 world unit = 1 logical pixel, so the image keeps the same size on
+    /// screens with different scale factors.
     WindowSize,
-    /// Keep vertical axis constant; resize horizontal with aspect ratio.
-    FixedVertical,
-    /// Keep horizontal axis constant; resize vertical with aspect ratio.
-    FixedHorizontal,
+    /// Keep the given number of world units visible vertically; resize horizontal with aspect
+    /// ratio.
+    FixedVertical(f32),
+    /// Keep the given number of world units visible horizontally; resize vertical with aspect
+    /// ratio.
+    FixedHorizontal(f32),
+    /// Keep at least `min_width` by `min_height` world units visible, showing more along one axis
+    /// when the aspect ratio of the window doesn't match.
+    AutoMin { min_width: f32, min_height: f32 },
 }
 
 #[derive(Component, Debug, Clone, Reflect)]
@@ -79,6 +90,20 @@ pub struct OrthographicProjection {
     pub scaling_mode: ScalingMode,
     pub scale: f32,
     pub depth_calculation: DepthCalculation,
+    /// Rounds down the number of physical pixels per world unit to a whole number, so that pixel
+    /// art drawn one texel per world unit stays crisp. The visible area grows to fill the window.
+    pub integer_scaling: bool,
+    /// Rounds the translation of the camera to whole physical pixels, so that pixel art doesn't
+    /// shimmer as the camera moves. This expects the camera not to be rotated.
+    pub snap_to_pixels: bool,
+}
+
+impl OrthographicProjection {
+    /// The number of world units covered by a physical pixel of a window `physical_width` pixels
+    /// wide
+    pub fn world_units_per_pixel(&self, physical_width: f32) -> f32 {
+        (self.right - self.left) * self.scale / physical_width
+    }
 }
 
 impl CameraProjection for OrthographicProjection {
@@ -94,50 +119,50 @@ impl CameraProjection for OrthographicProjection {
     }
 
     fn update(&mut self, width: f32, height: f32) {
-        match (&self.scaling_mode, &self.window_origin) {
-            (ScalingMode::WindowSize, WindowOrigin::Center) => {
-                let half_width = width / 2.0;
-                let half_height = height / 2.0;
-                self.left = -half_width;
-                self.right = half_width;
-                self.top = half_height;
-                self.bottom = -half_height;
-            }
-            (ScalingMode::WindowSize, WindowOrigin::BottomLeft) => {
-                self.left = 0.0;
-                self.right = width;
-                self.top = height;
-                self.bottom = 0.0;
-            }
-            (ScalingMode::FixedVertical, WindowOrigin::Center) => {
-                let aspect_ratio = width / height;
-                self.left = -aspect_ratio;
-                self.right = aspect_ratio;
-                self.top = 1.0;
-                self.bottom = -1.0;
-            }
-            (ScalingMode::FixedVertical, WindowOrigin::BottomLeft) => {
-                let aspect_ratio = width / height;
-                self.left = 0.0;
-                self.right = aspect_ratio;
-                self.top = 1.0;
-                self.bottom = 0.0;
+        self.update_with_scale_factor(width, height, 1.0);
+    }
+
+    fn update_with_scale_factor(&mut self, width: f32, height: f32, scale_factor: f32) {
+        let (mut area_width, mut area_height) = match self.scaling_mode {
+            ScalingMode::None => return,
+            ScalingMode::WindowSize => (width, height),
+            ScalingMode::FixedVertical(area_height) => (area_height * width / height, area_height),
+            ScalingMode::FixedHorizontal(area_width) => (area_width, area_width * height / width),
+            ScalingMode::AutoMin {
+                min_width,
+                min_height,
+            } => {
+                if width * min_height > min_width * height {
+                    (min_height * width / height, min_height)
+                } else {
+                    (min_width, min_width * height / width)
+                }
             }
-            (ScalingMode::FixedHorizontal, WindowOrigin::Center) => {
-                let aspect_ratio = height / width;
-                self.left = -1.0;
-                self.right = 1.0;
-                self.top = aspect_ratio;
-                self.bottom = -aspect_ratio;
+        };
+
+        if self.integer_scaling {
+            let physical_width = width * scale_factor;
+            let pixels_per_unit = (physical_width / (area_width * self.scale))
+                .floor()
+                .max(1.0);
+            let area_scale = physical_width / (pixels_per_unit * area_width * self.scale);
+            area_width *= area_scale;
+            area_height *= area_scale;
+        }
+
+        match self.window_origin {
+            WindowOrigin::Center => {
+                self.left = -area_width / 2.0;
+                self.right = area_width / 2.0;
+                self.top = area_height / 2.0;
+                self.bottom = -area_height / 2.0;
             }
-            (ScalingMode::FixedHorizontal, WindowOrigin::BottomLeft) => {
-                let aspect_ratio = height / width;
+            WindowOrigin::BottomLeft => {
                 self.left = 0.0;
-                self.right = 1.0;
-                self.top = aspect_ratio;
+                self.right = area_width;
+                self.top = area_height;
                 self.bottom = 0.0;
             }
-            (ScalingMode::None, _) => {}
         }
     }
 
@@ -159,6 +184,55 @@ impl Default for OrthographicProjection {
             scaling_mode: ScalingMode::WindowSize,
             scale: 1.0,
             depth_calculation: DepthCalculation::Distance,
+            integer_scaling: false,
+            snap_to_pixels: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(projection: &OrthographicProjection) -> (f32, f32) {
+        (
+            projection.right - projection.left,
+            projection.top - projection.bottom,
+        )
+    }
+
+    #[test]
+    fn auto_min_keeps_minimum_area_visible() {
+        let mut projection = OrthographicProjection {
+            scaling_mode: ScalingMode::AutoMin {
+                min_width: 320.0,
+                min_height: 180.0,
+            },
+            ..Default::default()
+        };
+
+        projection.update(1000.0, 500.0);
+        assert_eq!(area(&projection), (360.0, 180.0));
+
+        projection.update(400.0, 400.0);
+        assert_eq!(area(&projection), (320.0, 320.0));
+    }
+
+    #[test]
+    fn integer_scaling_rounds_down_pixels_per_unit() {
+        let mut projection = OrthographicProjection {
+            scaling_mode: ScalingMode::FixedVertical(180.0),
+            integer_scaling: true,
+            ..Default::default()
+        };
+
+        for scale_factor in [1.0, 2.0] {
+            projection.update_with_scale_factor(1280.0, 800.0, scale_factor);
+            let (width, height) = area(&projection);
+            assert!((width - 320.0).abs() < 1e-3);
+            assert!((height - 200.0).abs() < 1e-3);
+            let pixels_per_unit = 1.0 / projection.world_units_per_pixel(1280.0 * scale_factor);
+            assert!((pixels_per_unit - 4.0 * scale_factor).abs() < 1e-3);
         }
     }
 }
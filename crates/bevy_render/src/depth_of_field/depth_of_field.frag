@@ -0,0 +1,69 @@
+#version 450
+
+layout(location = 0) in vec2 v_Uv;
+
+layout(location = 0) out vec4 o_Target;
+
+layout(set = 0, binding = 0) uniform texture2D ColorTexture;
+layout(set = 0, binding = 1) uniform sampler ColorTexture_sampler;
+layout(set = 0, binding = 2) uniform texture2D DepthTexture;
+layout(set = 0, binding = 3) uniform sampler DepthTexture_sampler;
+
+// reflects DepthOfFieldUniform in bevy_render/src/depth_of_field/mod.rs
+layout(std140, set = 0, binding = 4) uniform DepthOfFieldSettings {
+    float focal_distance;
+    float aperture_diameter;
+    float focal_length;
+    float sensor_height;
+    float near;
+    float far;
+    float screen_height;
+    float max_blur_radius;
+    uint sample_count;
+};
+
+const float GOLDEN_ANGLE = 2.39996323;
+
+float linear_depth(vec2 uv) {
+    float depth = texture(sampler2D(DepthTexture, DepthTexture_sampler), uv).r;
+    return near * far / (far - depth * (far - near));
+}
+
+// Radius of the circle of confusion in pixels for a point at the given view space depth.
+// See https://en.wikipedia.org/wiki/Circle_of_confusion#Determining_a_circle_of_confusion_diameter_from_the_object_field
+float circle_of_confusion(float depth) {
+    float diameter = abs(aperture_diameter * focal_length * (depth - focal_distance) /
+                         (depth * (focal_distance - focal_length)));
+    return min(0.5 * diameter / sensor_height * screen_height, max_blur_radius);
+}
+
+void main() {
+    vec3 color = texture(sampler2D(ColorTexture, ColorTexture_sampler), v_Uv).rgb;
+    float center_depth = linear_depth(v_Uv);
+    float center_coc = circle_of_confusion(center_depth);
+    if (sample_count == 0 || center_coc < 0.5) {
+        o_Target = vec4(color, 1.0);
+        return;
+    }
+
+    vec2 texel_size = 1.0 / vec2(textureSize(sampler2D(ColorTexture, ColorTexture_sampler), 0));
+    float total_weight = 1.0;
+    // gather samples on a golden angle spiral covering the circle of confusion
+    for (uint i = 0; i < sample_count; ++i) {
+        float radius = sqrt((float(i) + 0.5) / float(sample_count)) * center_coc;
+        float theta = float(i) * GOLDEN_ANGLE;
+        vec2 uv = v_Uv + vec2(cos(theta), sin(theta)) * radius * texel_size;
+
+        float sample_depth = linear_depth(uv);
+        // samples in front of this pixel bleed over it, samples behind it only contribute if their
+        // own blur reaches this far. This keeps sharp foreground edges from smearing into blurry
+        // backgrounds.
+        float sample_coc = sample_depth < center_depth ? center_coc : circle_of_confusion(sample_depth);
+        float weight = smoothstep(radius - 1.0, radius + 1.0, sample_coc);
+
+        color += texture(sampler2D(ColorTexture, ColorTexture_sampler), uv).rgb * weight;
+        total_weight += weight;
+    }
+
+    o_Target = vec4(color / total_weight, 1.0);
+}
@@ -0,0 +1,286 @@
+use crate::{
+    camera::{ActiveCameras, PerspectiveProjection},
+    pipeline::PipelineDescriptor,
+    render_graph::{
+        base::{self, Msaa},
+        build_fullscreen_pipeline, FullscreenPassNode, Node, RenderGraph, ResourceSlotInfo,
+        ResourceSlots, WindowTextureNode,
+    },
+    renderer::{BufferId, BufferInfo, BufferUsage, RenderContext, RenderResourceBinding},
+    shader::Shader,
+};
+use bevy_app::prelude::*;
+use bevy_asset::{Assets, HandleUntyped};
+use bevy_core::{bytes_of, Pod, Zeroable};
+use bevy_ecs::{
+    component::Component, entity::Entity, reflect::ReflectComponent,
+    schedule::ParallelSystemDescriptorCoercion, system::Query, world::World,
+};
+use bevy_reflect::{Reflect, ReflectDeserialize, TypeUuid};
+use bevy_transform::{components::GlobalTransform, TransformSystem};
+use bevy_utils::tracing::warn;
+use bevy_window::Windows;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+pub const DEPTH_OF_FIELD_PIPELINE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 0x4a3c0a1b19e5d5e1);
+
+/// the name of the depth of field graph node
+pub const DEPTH_OF_FIELD: &str = "depth_of_field";
+
+/// Adds a depth of field post processing effect to the 3d camera. The effect is configured by
+/// adding a [DepthOfField] component to the camera.
+///
+/// This plugin must be added after the [RenderPlugin](crate::RenderPlugin). Multisampling isn't
+/// supported yet, so [Msaa] must be left at one sample.
+#[derive(Debug, Default)]
+pub struct DepthOfFieldPlugin;
+
+impl Plugin for DepthOfFieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<DepthOfField>()
+            .register_type::<BokehQuality>()
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                depth_of_field_focus_system.after(TransformSystem::TransformPropagate),
+            );
+
+        let world = app.world.cell();
+        let msaa = world.get_resource::<Msaa>().unwrap();
+        if msaa.samples > 1 {
+            warn!("Depth of field doesn't support MSAA yet. DepthOfFieldPlugin will be ignored.");
+            return;
+        }
+
+        let mut shaders = world.get_resource_mut::<Assets<Shader>>().unwrap();
+        let mut pipelines = world
+            .get_resource_mut::<Assets<PipelineDescriptor>>()
+            .unwrap();
+        pipelines.set_untracked(
+            DEPTH_OF_FIELD_PIPELINE_HANDLE,
+            build_fullscreen_pipeline(&mut shaders, include_str!("depth_of_field.frag")),
+        );
+
+        let mut graph = world.get_resource_mut::<RenderGraph>().unwrap();
+        base::add_main_pass_post_process_node(
+            &mut graph,
+            &msaa,
+            DEPTH_OF_FIELD,
+            DepthOfFieldNode::new(base::camera::CAMERA_3D),
+            DepthOfFieldNode::COLOR_TEXTURE,
+        )
+        .unwrap();
+        graph
+            .add_slot_edge(
+                base::node::MAIN_DEPTH_TEXTURE,
+                WindowTextureNode::OUT_TEXTURE,
+                DEPTH_OF_FIELD,
+                DepthOfFieldNode::DEPTH_TEXTURE,
+            )
+            .unwrap();
+    }
+}
+
+/// Controls the depth of field of the camera it is added to. Objects at `focal_distance` are in
+/// perfect focus, everything closer or further away is blurred according to a thin lens model.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct DepthOfField {
+    /// Distance from the camera, in world units, at which objects are in perfect focus.
+    pub focal_distance: f32,
+    /// The aperture of the lens as an f-number. Lower values produce a shallower depth of field.
+    pub aperture_f_stops: f32,
+    /// Height of the simulated camera sensor in world units. Together with the camera's field of
+    /// view this determines the focal length of the lens. Defaults to a 35mm film frame.
+    pub sensor_height: f32,
+    /// Upper bound of the blur radius in pixels.
+    pub max_blur_radius: f32,
+    pub quality: BokehQuality,
+}
+
+impl Default for DepthOfField {
+    fn default() -> Self {
+        DepthOfField {
+            focal_distance: 10.0,
+            aperture_f_stops: 2.8,
+            sensor_height: 0.024,
+            max_blur_radius: 16.0,
+            quality: BokehQuality::Medium,
+        }
+    }
+}
+
+/// The number of samples taken for each blurred pixel. Higher qualities produce smoother bokeh
+/// with large blur radii at a higher cost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[reflect_value(PartialEq, Serialize, Deserialize)]
+pub enum BokehQuality {
+    Low,
+    Medium,
+    High,
+}
+
+impl BokehQuality {
+    pub fn sample_count(&self) -> u32 {
+        match self {
+            BokehQuality::Low => 16,
+            BokehQuality::Medium => 32,
+            BokehQuality::High => 64,
+        }
+    }
+}
+
+/// Keeps the [DepthOfField] of a camera focused on the given entity.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct DepthOfFieldTarget(pub Entity);
+
+pub fn depth_of_field_focus_system(
+    mut cameras: Query<(&mut DepthOfField, &DepthOfFieldTarget, &GlobalTransform)>,
+    targets: Query<&GlobalTransform>,
+) {
+    for (mut depth_of_field, target, camera_transform) in cameras.iter_mut() {
+        if let Ok(target_transform) = targets.get(target.0) {
+            // focus is measured along the view direction, not as a straight distance
+            let focal_distance = (target_transform.translation - camera_transform.translation)
+                .dot(camera_transform.forward());
+            depth_of_field.focal_distance = focal_distance.max(0.0);
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, Pod, Zeroable)]
+struct DepthOfFieldUniform {
+    focal_distance: f32,
+    aperture_diameter: f32,
+    focal_length: f32,
+    sensor_height: f32,
+    near: f32,
+    far: f32,
+    screen_height: f32,
+    max_blur_radius: f32,
+    sample_count: u32,
+    _padding: [u32; 3],
+}
+
+/// A render graph [Node] that blurs the output of the main pass based on the [DepthOfField] of a
+/// camera.
+pub struct DepthOfFieldNode {
+    pass: FullscreenPassNode,
+    camera_name: Cow<'static, str>,
+    uniform: DepthOfFieldUniform,
+    uniform_buffer: Option<BufferId>,
+}
+
+impl DepthOfFieldNode {
+    pub const COLOR_TEXTURE: &'static str = "ColorTexture";
+    pub const DEPTH_TEXTURE: &'static str = "DepthTexture";
+    const SETTINGS: &'static str = "DepthOfFieldSettings";
+
+    pub fn new<T>(camera_name: T) -> Self
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        let mut pass = FullscreenPassNode::new(DEPTH_OF_FIELD_PIPELINE_HANDLE.typed());
+        pass.add_texture_input(Self::COLOR_TEXTURE);
+        pass.add_depth_input(Self::DEPTH_TEXTURE);
+        DepthOfFieldNode {
+            pass,
+            camera_name: camera_name.into(),
+            uniform: Default::default(),
+            uniform_buffer: None,
+        }
+    }
+}
+
+impl Node for DepthOfFieldNode {
+    fn input(&self) -> &[ResourceSlotInfo] {
+        self.pass.input()
+    }
+
+    fn prepare(&mut self, world: &mut World) {
+        self.pass.prepare(world);
+
+        // without a camera or settings the shader leaves every pixel untouched
+        self.uniform = DepthOfFieldUniform::default();
+        let active_cameras = world.get_resource::<ActiveCameras>().unwrap();
+        let entity = match active_cameras
+            .get(&self.camera_name)
+            .and_then(|active_camera| active_camera.entity)
+        {
+            Some(entity) => entity,
+            None => return,
+        };
+        let entity = world.entity(entity);
+        if let (Some(depth_of_field), Some(projection)) = (
+            entity.get::<DepthOfField>(),
+            entity.get::<PerspectiveProjection>(),
+        ) {
+            let screen_height = world
+                .get_resource::<Windows>()
+                .and_then(|windows| windows.get_primary())
+                .map_or(1.0, |window| window.physical_height() as f32);
+            let focal_length = 0.5 * depth_of_field.sensor_height / (0.5 * projection.fov).tan();
+            self.uniform = DepthOfFieldUniform {
+                focal_distance: depth_of_field.focal_distance.max(focal_length * 1.01),
+                aperture_diameter: focal_length / depth_of_field.aperture_f_stops,
+                focal_length,
+                sensor_height: depth_of_field.sensor_height,
+                near: projection.near,
+                far: projection.far,
+                screen_height,
+                max_blur_radius: depth_of_field.max_blur_radius,
+                sample_count: depth_of_field.quality.sample_count(),
+                _padding: [0; 3],
+            };
+        }
+    }
+
+    fn update(
+        &mut self,
+        world: &World,
+        render_context: &mut dyn RenderContext,
+        input: &ResourceSlots,
+        output: &mut ResourceSlots,
+    ) {
+        let uniform_size = std::mem::size_of::<DepthOfFieldUniform>();
+        let uniform_buffer = if let Some(uniform_buffer) = self.uniform_buffer {
+            uniform_buffer
+        } else {
+            let uniform_buffer = render_context.resources().create_buffer(BufferInfo {
+                size: uniform_size,
+                buffer_usage: BufferUsage::COPY_DST | BufferUsage::UNIFORM,
+                ..Default::default()
+            });
+            self.pass.bindings_mut().set(
+                Self::SETTINGS,
+                RenderResourceBinding::Buffer {
+                    buffer: uniform_buffer,
+                    range: 0..uniform_size as u64,
+                    dynamic_index: None,
+                },
+            );
+            self.uniform_buffer = Some(uniform_buffer);
+            uniform_buffer
+        };
+
+        let staging_buffer = render_context.resources().create_buffer_with_data(
+            BufferInfo {
+                buffer_usage: BufferUsage::COPY_SRC,
+                ..Default::default()
+            },
+            bytes_of(&self.uniform),
+        );
+        render_context.copy_buffer_to_buffer(
+            staging_buffer,
+            0,
+            uniform_buffer,
+            0,
+            uniform_size as u64,
+        );
+        render_context.resources().remove_buffer(staging_buffer);
+
+        self.pass.update(world, render_context, input, output);
+    }
+}
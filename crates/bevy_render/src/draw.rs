@@ -95,6 +95,12 @@ impl Default for Draw {
     }
 }
 
+impl AsRef<Draw> for Draw {
+    fn as_ref(&self) -> &Draw {
+        self
+    }
+}
+
 impl Draw {
     pub fn clear_render_commands(&mut self) {
         self.render_commands.clear();
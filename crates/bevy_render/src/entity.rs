@@ -12,6 +12,7 @@ use base::MainPass;
 use bevy_asset::Handle;
 use bevy_ecs::bundle::Bundle;
 use bevy_transform::components::{GlobalTransform, Transform};
+use bevy_window::WindowId;
 
 /// A component bundle for "mesh" entities
 #[derive(Bundle, Default)]
@@ -54,6 +55,12 @@ impl PerspectiveCameraBundle {
             global_transform: Default::default(),
         }
     }
+
+    /// Draws the camera to a window instead of the primary window, see [`Camera::set_window`].
+    pub fn for_window(mut self, window_id: WindowId) -> Self {
+        self.camera.set_window(window_id);
+        self
+    }
 }
 
 impl Default for PerspectiveCameraBundle {
@@ -111,7 +118,7 @@ impl OrthographicCameraBundle {
                 ..Default::default()
             },
             orthographic_projection: OrthographicProjection {
-                scaling_mode: ScalingMode::FixedVertical,
+                scaling_mode: ScalingMode::FixedVertical(2.0),
                 depth_calculation: DepthCalculation::Distance,
                 ..Default::default()
             },
@@ -133,4 +140,10 @@ impl OrthographicCameraBundle {
             global_transform: Default::default(),
         }
     }
+
+    /// Draws the camera to a window instead of the primary window, see [`Camera::set_window`].
+    pub fn for_window(mut self, window_id: WindowId) -> Self {
+        self.camera.set_window(window_id);
+        self
+    }
 }
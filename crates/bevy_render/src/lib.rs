@@ -1,15 +1,20 @@
 pub mod camera;
 pub mod color;
 pub mod colorspace;
+pub mod depth_of_field;
 pub mod draw;
 pub mod entity;
 pub mod mesh;
+pub mod motion_blur;
+pub mod motion_vectors;
 pub mod pass;
 pub mod pipeline;
 pub mod render_graph;
 pub mod renderer;
+pub mod screenshot;
 pub mod shader;
 pub mod texture;
+pub mod window_icon;
 pub mod wireframe;
 
 use bevy_ecs::{
@@ -40,11 +45,11 @@ pub mod prelude {
 use crate::prelude::*;
 use base::Msaa;
 use bevy_app::prelude::*;
-use bevy_asset::{AddAsset, AssetStage};
+use bevy_asset::{AddAsset, AssetServer, AssetStage};
 use bevy_ecs::schedule::{StageLabel, SystemLabel};
 use camera::{
-    ActiveCameras, Camera, DepthCalculation, OrthographicProjection, PerspectiveProjection,
-    RenderLayers, ScalingMode, VisibleEntities, WindowOrigin,
+    ActiveCameras, Camera, ClearBehavior, DepthCalculation, OrthographicProjection,
+    PerspectiveProjection, RenderLayers, ScalingMode, VisibleEntities, WindowOrigin,
 };
 use pipeline::{
     IndexFormat, PipelineCompiler, PipelineDescriptor, PipelineSpecialization, PrimitiveTopology,
@@ -55,6 +60,7 @@ use render_graph::{
     RenderGraph,
 };
 use renderer::{AssetRenderResourceBindings, RenderResourceBindings, RenderResourceContext};
+use screenshot::{ScreenshotCaptured, Screenshots};
 use shader::ShaderLoader;
 #[cfg(feature = "hdr")]
 use texture::HdrTextureLoader;
@@ -66,10 +72,14 @@ use texture::HdrTextureLoader;
     feature = "bmp"
 ))]
 use texture::ImageTextureLoader;
+use texture::{Extent3d, TextureDimension, TextureFormat};
+use window_icon::WindowIcons;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
 pub enum RenderSystem {
     VisibleEntities,
+    /// After this label, the render graph has the nodes of the windows created this frame
+    WindowGraph,
 }
 
 /// The names of "render" App stages
@@ -149,6 +159,7 @@ impl Plugin for RenderPlugin {
         .add_asset::<Shader>()
         .add_asset::<PipelineDescriptor>()
         .register_type::<Camera>()
+        .register_type::<ClearBehavior>()
         .register_type::<DepthCalculation>()
         .register_type::<Draw>()
         .register_type::<Visible>()
@@ -174,9 +185,14 @@ impl Plugin for RenderPlugin {
         .init_resource::<RenderResourceBindings>()
         .init_resource::<AssetRenderResourceBindings>()
         .init_resource::<ActiveCameras>()
+        .init_resource::<Screenshots>()
+        .init_resource::<WindowIcons>()
+        .add_event::<ScreenshotCaptured>()
         .add_startup_system_to_stage(StartupStage::PreStartup, check_for_render_resource_context)
         .add_system_to_stage(CoreStage::PreUpdate, draw::clear_draw_system)
+        .add_system_to_stage(CoreStage::PreUpdate, screenshot::save_screenshots_system)
         .add_system_to_stage(CoreStage::PostUpdate, camera::active_cameras_system)
+        .add_system_to_stage(CoreStage::PostUpdate, window_icon::window_icon_system)
         .add_system_to_stage(
             CoreStage::PostUpdate,
             camera::camera_system::<OrthographicProjection>.before(RenderSystem::VisibleEntities),
@@ -185,6 +201,12 @@ impl Plugin for RenderPlugin {
             CoreStage::PostUpdate,
             camera::camera_system::<PerspectiveProjection>.before(RenderSystem::VisibleEntities),
         )
+        .add_system_to_stage(
+            CoreStage::PostUpdate,
+            camera::camera_pixel_snapping_system
+                .after(TransformSystem::TransformPropagate)
+                .before(RenderSystem::VisibleEntities),
+        )
         .add_system_to_stage(
             CoreStage::PostUpdate,
             camera::visible_entities_system
@@ -207,8 +229,24 @@ impl Plugin for RenderPlugin {
         .add_system_to_stage(RenderStage::Draw, pipeline::draw_render_pipelines_system)
         .add_system_to_stage(RenderStage::PostRender, shader::clear_shader_defs_system);
 
+        // textures and meshes that fail to load are replaced by a magenta texture and a cube, which
+        // stand out rather than leaving invisible entities
+        if let Some(asset_server) = app.world.get_resource::<AssetServer>() {
+            asset_server.set_fallback_asset(Texture::new_fill(
+                Extent3d::new(1, 1, 1),
+                TextureDimension::D2,
+                &[255, 0, 255, 255],
+                TextureFormat::Rgba8UnormSrgb,
+            ));
+            asset_server.set_fallback_asset(Mesh::from(shape::Cube::default()));
+        }
+
         if let Some(ref config) = self.base_render_graph_config {
             crate::base::add_base_graph(config, &mut app.world);
+            app.add_system_to_stage(
+                CoreStage::PostUpdate,
+                base::window_graph_system.label(RenderSystem::WindowGraph),
+            );
             let mut active_cameras = app.world.get_resource_mut::<ActiveCameras>().unwrap();
             if config.add_3d_camera {
                 active_cameras.add(base::camera::CAMERA_3D);
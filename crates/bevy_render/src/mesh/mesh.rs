@@ -4,7 +4,7 @@ use crate::{
     pipeline::{IndexFormat, PrimitiveTopology, RenderPipelines, VertexFormat},
     renderer::{BufferInfo, BufferUsage, RenderResourceContext, RenderResourceId},
 };
-use bevy_asset::{AssetEvent, Assets, Handle};
+use bevy_asset::{diagnostic::AssetMemoryUsage, AssetEvent, Assets, Handle};
 use bevy_core::cast_slice;
 use bevy_ecs::{
     entity::Entity,
@@ -476,6 +476,17 @@ fn face_normal(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> [f32; 3] {
     (b - a).cross(c - a).normalize().into()
 }
 
+impl AssetMemoryUsage for Mesh {
+    fn memory_usage(&self) -> usize {
+        let vertex_bytes: usize = self
+            .attributes
+            .values()
+            .map(|values| values.get_bytes().len())
+            .sum();
+        vertex_bytes + self.get_index_buffer_bytes().map_or(0, |bytes| bytes.len())
+    }
+}
+
 fn remove_resource_save(
     render_resource_context: &dyn RenderResourceContext,
     handle: &Handle<Mesh>,
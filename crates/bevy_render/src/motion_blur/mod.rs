@@ -0,0 +1,209 @@
+use crate::{
+    camera::ActiveCameras,
+    motion_vectors::{self, MotionVectorsPlugin},
+    pipeline::PipelineDescriptor,
+    render_graph::{
+        base::{self, Msaa},
+        build_fullscreen_pipeline, FullscreenPassNode, Node, RenderGraph, ResourceSlotInfo,
+        ResourceSlots, WindowTextureNode,
+    },
+    renderer::{BufferId, BufferInfo, BufferUsage, RenderContext, RenderResourceBinding},
+    shader::Shader,
+};
+use bevy_app::prelude::*;
+use bevy_asset::{Assets, HandleUntyped};
+use bevy_core::{bytes_of, Pod, Zeroable};
+use bevy_ecs::{component::Component, reflect::ReflectComponent, world::World};
+use bevy_reflect::{Reflect, TypeUuid};
+use std::borrow::Cow;
+
+pub const MOTION_BLUR_PIPELINE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 0x2f9e47c1d0a85b36);
+
+/// the name of the motion blur graph node
+pub const MOTION_BLUR: &str = "motion_blur";
+
+/// Adds a motion blur post processing effect to the 3d camera, driven by the motion vectors of
+/// the [MotionVectorsPlugin] (which is added automatically). The effect is configured by adding a
+/// [MotionBlur] component to the camera.
+///
+/// Only surfaces drawn by the motion vector pass are blurred: pixels that don't show any mesh
+/// (like the clear color) have no motion.
+///
+/// This plugin must be added after the [RenderPlugin](crate::RenderPlugin).
+#[derive(Debug, Default)]
+pub struct MotionBlurPlugin;
+
+impl Plugin for MotionBlurPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(MotionVectorsPlugin)
+            .register_type::<MotionBlur>();
+
+        let world = app.world.cell();
+        let mut shaders = world.get_resource_mut::<Assets<Shader>>().unwrap();
+        let mut pipelines = world
+            .get_resource_mut::<Assets<PipelineDescriptor>>()
+            .unwrap();
+        pipelines.set_untracked(
+            MOTION_BLUR_PIPELINE_HANDLE,
+            build_fullscreen_pipeline(&mut shaders, include_str!("motion_blur.frag")),
+        );
+
+        let msaa = world.get_resource::<Msaa>().unwrap();
+        let mut graph = world.get_resource_mut::<RenderGraph>().unwrap();
+        base::add_main_pass_post_process_node(
+            &mut graph,
+            &msaa,
+            MOTION_BLUR,
+            MotionBlurNode::new(base::camera::CAMERA_3D),
+            MotionBlurNode::COLOR_TEXTURE,
+        )
+        .unwrap();
+        graph
+            .add_slot_edge(
+                motion_vectors::node::MOTION_VECTOR_TEXTURE,
+                WindowTextureNode::OUT_TEXTURE,
+                MOTION_BLUR,
+                MotionBlurNode::MOTION_VECTOR_TEXTURE,
+            )
+            .unwrap();
+        graph
+            .add_node_edge(motion_vectors::node::MOTION_VECTOR_PASS, MOTION_BLUR)
+            .unwrap();
+    }
+}
+
+/// Controls the motion blur of the camera it is added to.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct MotionBlur {
+    /// The fraction of a frame the simulated shutter stays open. `0.5` matches a 180° shutter,
+    /// larger values produce longer streaks.
+    pub shutter_fraction: f32,
+    /// Upper bound of the blur length in pixels.
+    pub max_blur_length: f32,
+    /// The number of samples taken along the motion of each pixel.
+    pub sample_count: u32,
+}
+
+impl Default for MotionBlur {
+    fn default() -> Self {
+        MotionBlur {
+            shutter_fraction: 0.5,
+            max_blur_length: 32.0,
+            sample_count: 8,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, Pod, Zeroable)]
+struct MotionBlurUniform {
+    shutter_fraction: f32,
+    max_blur_length: f32,
+    sample_count: u32,
+    _padding: u32,
+}
+
+/// A render graph [Node] that blurs the output of the main pass along the motion vectors of the
+/// [MotionVectorsPlugin], based on the [MotionBlur] of a camera.
+pub struct MotionBlurNode {
+    pass: FullscreenPassNode,
+    camera_name: Cow<'static, str>,
+    uniform: MotionBlurUniform,
+    uniform_buffer: Option<BufferId>,
+}
+
+impl MotionBlurNode {
+    pub const COLOR_TEXTURE: &'static str = "ColorTexture";
+    pub const MOTION_VECTOR_TEXTURE: &'static str = "MotionVectorTexture";
+    const SETTINGS: &'static str = "MotionBlurSettings";
+
+    pub fn new<T>(camera_name: T) -> Self
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        let mut pass = FullscreenPassNode::new(MOTION_BLUR_PIPELINE_HANDLE.typed());
+        pass.add_texture_input(Self::COLOR_TEXTURE);
+        pass.add_texture_input(Self::MOTION_VECTOR_TEXTURE);
+        MotionBlurNode {
+            pass,
+            camera_name: camera_name.into(),
+            uniform: Default::default(),
+            uniform_buffer: None,
+        }
+    }
+}
+
+impl Node for MotionBlurNode {
+    fn input(&self) -> &[ResourceSlotInfo] {
+        self.pass.input()
+    }
+
+    fn prepare(&mut self, world: &mut World) {
+        self.pass.prepare(world);
+
+        // without a camera or settings the shader leaves every pixel untouched
+        self.uniform = MotionBlurUniform::default();
+        let active_cameras = world.get_resource::<ActiveCameras>().unwrap();
+        let motion_blur = active_cameras
+            .get(&self.camera_name)
+            .and_then(|active_camera| active_camera.entity)
+            .and_then(|entity| world.get::<MotionBlur>(entity));
+        if let Some(motion_blur) = motion_blur {
+            self.uniform = MotionBlurUniform {
+                shutter_fraction: motion_blur.shutter_fraction,
+                max_blur_length: motion_blur.max_blur_length,
+                sample_count: motion_blur.sample_count,
+                _padding: 0,
+            };
+        }
+    }
+
+    fn update(
+        &mut self,
+        world: &World,
+        render_context: &mut dyn RenderContext,
+        input: &ResourceSlots,
+        output: &mut ResourceSlots,
+    ) {
+        let uniform_size = std::mem::size_of::<MotionBlurUniform>();
+        let uniform_buffer = if let Some(uniform_buffer) = self.uniform_buffer {
+            uniform_buffer
+        } else {
+            let uniform_buffer = render_context.resources().create_buffer(BufferInfo {
+                size: uniform_size,
+                buffer_usage: BufferUsage::COPY_DST | BufferUsage::UNIFORM,
+                ..Default::default()
+            });
+            self.pass.bindings_mut().set(
+                Self::SETTINGS,
+                RenderResourceBinding::Buffer {
+                    buffer: uniform_buffer,
+                    range: 0..uniform_size as u64,
+                    dynamic_index: None,
+                },
+            );
+            self.uniform_buffer = Some(uniform_buffer);
+            uniform_buffer
+        };
+
+        let staging_buffer = render_context.resources().create_buffer_with_data(
+            BufferInfo {
+                buffer_usage: BufferUsage::COPY_SRC,
+                ..Default::default()
+            },
+            bytes_of(&self.uniform),
+        );
+        render_context.copy_buffer_to_buffer(
+            staging_buffer,
+            0,
+            uniform_buffer,
+            0,
+            uniform_size as u64,
+        );
+        render_context.resources().remove_buffer(staging_buffer);
+
+        self.pass.update(world, render_context, input, output);
+    }
+}
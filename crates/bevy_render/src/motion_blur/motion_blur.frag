@@ -0,0 +1,43 @@
+#version 450
+
+layout(location = 0) in vec2 v_Uv;
+
+layout(location = 0) out vec4 o_Target;
+
+layout(set = 0, binding = 0) uniform texture2D ColorTexture;
+layout(set = 0, binding = 1) uniform sampler ColorTexture_sampler;
+layout(set = 0, binding = 2) uniform texture2D MotionVectorTexture;
+layout(set = 0, binding = 3) uniform sampler MotionVectorTexture_sampler;
+
+// reflects MotionBlurUniform in bevy_render/src/motion_blur/mod.rs
+layout(std140, set = 0, binding = 4) uniform MotionBlurSettings {
+    float shutter_fraction;
+    float max_blur_length;
+    uint sample_count;
+};
+
+void main() {
+    vec3 color = texture(sampler2D(ColorTexture, ColorTexture_sampler), v_Uv).rgb;
+    vec2 motion = texture(sampler2D(MotionVectorTexture, MotionVectorTexture_sampler), v_Uv).rg;
+    vec2 screen_size = vec2(textureSize(sampler2D(ColorTexture, ColorTexture_sampler), 0));
+
+    // the distance the surface travelled while the shutter was open, in pixels
+    vec2 blur = motion * shutter_fraction * screen_size;
+    float blur_length = length(blur);
+    if (sample_count < 2 || blur_length < 0.5) {
+        o_Target = vec4(color, 1.0);
+        return;
+    }
+    blur *= min(blur_length, max_blur_length) / blur_length;
+
+    // sample along the motion, centered on the current position
+    vec2 step_uv = blur / screen_size / float(sample_count - 1);
+    vec2 start_uv = v_Uv - 0.5 * blur / screen_size;
+    vec3 total = vec3(0.0);
+    for (uint i = 0; i < sample_count; ++i) {
+        vec2 uv = clamp(start_uv + float(i) * step_uv, vec2(0.0), vec2(1.0));
+        total += texture(sampler2D(ColorTexture, ColorTexture_sampler), uv).rgb;
+    }
+
+    o_Target = vec4(total / float(sample_count), 1.0);
+}
@@ -0,0 +1,483 @@
+use crate::{
+    color::Color,
+    draw::{Draw, DrawContext, OutsideFrustum, Visible},
+    mesh::{Indices, Mesh},
+    pass::{
+        LoadOp, Operations, PassDescriptor, RenderPassColorAttachment,
+        RenderPassDepthStencilAttachment, TextureAttachment,
+    },
+    pipeline::{
+        ColorTargetState, ColorWrite, PipelineDescriptor, PipelineSpecialization, RenderPipeline,
+        RenderPipelines,
+    },
+    render_graph::{
+        base::{self, MainPass},
+        Edge, PassNode, RenderGraph, RenderResourcesNode, WindowTextureNode,
+    },
+    renderer::{RenderResource, RenderResourceIterator, RenderResources},
+    shader::{Shader, ShaderStage, ShaderStages},
+    texture::{Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsage},
+    RenderStage,
+};
+use bevy_app::prelude::*;
+use bevy_asset::{Assets, Handle, HandleUntyped};
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    query::{With, Without},
+    reflect::ReflectComponent,
+    schedule::ParallelSystemDescriptorCoercion,
+    system::{Commands, Query, Res},
+};
+use bevy_math::Mat4;
+use bevy_reflect::{Reflect, TypeUuid};
+use bevy_transform::{components::GlobalTransform, TransformSystem};
+use bevy_utils::HashSet;
+use bevy_window::WindowId;
+
+pub const MOTION_VECTORS_PIPELINE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 0x61d2a3f0c6b4e98a);
+
+/// The format of the motion vector texture. Each texel holds the screen space motion of the
+/// surface it shows since the previous frame, in uv units.
+pub const MOTION_VECTOR_FORMAT: TextureFormat = TextureFormat::Rg16Float;
+
+pub mod node {
+    pub const MOTION_VECTOR_PASS: &str = "motion_vector_pass";
+    /// Outputs the motion vector texture through [WindowTextureNode::OUT_TEXTURE](crate::render_graph::WindowTextureNode::OUT_TEXTURE)
+    pub const MOTION_VECTOR_TEXTURE: &str = "motion_vector_texture";
+    pub const MOTION_VECTOR_DEPTH_TEXTURE: &str = "motion_vector_depth_texture";
+    pub const PREVIOUS_TRANSFORM: &str = "previous_transform";
+}
+
+/// Renders the per-pixel motion of the 3d camera's view into a texture that can be used by
+/// effects like motion blur or temporal anti-aliasing. Motion vectors include camera motion,
+/// object motion and, with a matching [MotionVectors::pipeline], skinned vertex motion.
+///
+/// Every mesh drawn in the main pass is given [MotionVectors] and [PreviousGlobalTransform]
+/// automatically. The resulting texture is available from the
+/// [node::MOTION_VECTOR_TEXTURE] render graph node. Adding this plugin multiple times is allowed.
+#[derive(Debug, Default)]
+pub struct MotionVectorsPlugin;
+
+impl Plugin for MotionVectorsPlugin {
+    fn build(&self, app: &mut App) {
+        let graph = app.world.get_resource::<RenderGraph>().unwrap();
+        if graph.get_node_state(node::MOTION_VECTOR_PASS).is_ok() {
+            return;
+        }
+
+        app.register_type::<MotionVectors>()
+            .register_type::<PreviousGlobalTransform>()
+            .add_system_to_stage(CoreStage::PreUpdate, clear_motion_vector_draw_system)
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                add_motion_vectors_system.after(TransformSystem::TransformPropagate),
+            )
+            .add_system_to_stage(RenderStage::Draw, draw_motion_vectors_system)
+            .add_system_to_stage(
+                RenderStage::PostRender,
+                update_previous_global_transform_system,
+            );
+
+        let world = app.world.cell();
+        let mut shaders = world.get_resource_mut::<Assets<Shader>>().unwrap();
+        let mut pipelines = world
+            .get_resource_mut::<Assets<PipelineDescriptor>>()
+            .unwrap();
+        pipelines.set_untracked(
+            MOTION_VECTORS_PIPELINE_HANDLE,
+            build_motion_vectors_pipeline(&mut shaders, include_str!("motion_vectors.vert")),
+        );
+        let mut graph = world.get_resource_mut::<RenderGraph>().unwrap();
+        add_motion_vector_graph(&mut graph);
+    }
+}
+
+/// Builds a motion vector pipeline using the given vertex shader. The vertex shader must write
+/// the current and previous clip space positions to locations 0 and 1.
+pub fn build_motion_vectors_pipeline(
+    shaders: &mut Assets<Shader>,
+    vertex_shader: &str,
+) -> PipelineDescriptor {
+    let mut descriptor = PipelineDescriptor::default_config(ShaderStages {
+        vertex: shaders.add(Shader::from_glsl(ShaderStage::Vertex, vertex_shader)),
+        fragment: Some(shaders.add(Shader::from_glsl(
+            ShaderStage::Fragment,
+            include_str!("motion_vectors.frag"),
+        ))),
+    });
+    descriptor.name = Some("motion_vectors".into());
+    descriptor.color_target_states = vec![ColorTargetState {
+        format: MOTION_VECTOR_FORMAT,
+        blend: None,
+        write_mask: ColorWrite::ALL,
+    }];
+    descriptor
+}
+
+fn add_motion_vector_graph(graph: &mut RenderGraph) {
+    graph.add_system_node(
+        node::PREVIOUS_TRANSFORM,
+        RenderResourcesNode::<PreviousGlobalTransform>::new(true),
+    );
+    graph.add_node(
+        node::MOTION_VECTOR_TEXTURE,
+        WindowTextureNode::new(
+            WindowId::primary(),
+            TextureDescriptor {
+                size: Extent3d {
+                    depth_or_array_layers: 1,
+                    width: 1,
+                    height: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: MOTION_VECTOR_FORMAT,
+                usage: TextureUsage::OUTPUT_ATTACHMENT | TextureUsage::SAMPLED,
+            },
+        ),
+    );
+    graph.add_node(
+        node::MOTION_VECTOR_DEPTH_TEXTURE,
+        WindowTextureNode::new(
+            WindowId::primary(),
+            TextureDescriptor {
+                size: Extent3d {
+                    depth_or_array_layers: 1,
+                    width: 1,
+                    height: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Depth32Float,
+                usage: TextureUsage::OUTPUT_ATTACHMENT,
+            },
+        ),
+    );
+
+    let mut pass_node = PassNode::<&MotionVectors, MotionVectorDraw>::new(PassDescriptor {
+        color_attachments: vec![RenderPassColorAttachment {
+            attachment: TextureAttachment::Input("color_attachment".to_string()),
+            resolve_target: None,
+            ops: Operations {
+                load: LoadOp::Clear(Color::NONE),
+                store: true,
+            },
+        }],
+        depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+            attachment: TextureAttachment::Input("depth".to_string()),
+            depth_ops: Some(Operations {
+                load: LoadOp::Clear(1.0),
+                store: true,
+            }),
+            stencil_ops: None,
+        }),
+        sample_count: 1,
+    });
+    pass_node.add_camera(base::camera::CAMERA_3D);
+    pass_node.ignore_clear_behavior();
+    graph.add_node(node::MOTION_VECTOR_PASS, pass_node);
+
+    graph
+        .add_slot_edge(
+            node::MOTION_VECTOR_TEXTURE,
+            WindowTextureNode::OUT_TEXTURE,
+            node::MOTION_VECTOR_PASS,
+            "color_attachment",
+        )
+        .unwrap();
+    graph
+        .add_slot_edge(
+            node::MOTION_VECTOR_DEPTH_TEXTURE,
+            WindowTextureNode::OUT_TEXTURE,
+            node::MOTION_VECTOR_PASS,
+            "depth",
+        )
+        .unwrap();
+    graph
+        .add_node_edge(node::PREVIOUS_TRANSFORM, node::MOTION_VECTOR_PASS)
+        .unwrap();
+    // the motion vector pass draws the same entities as the main pass, so it waits for the same
+    // nodes (cameras, transforms, shared buffers, ...). it doesn't wait for the main pass itself,
+    // which lets post processing nodes that run after the main pass read motion vectors.
+    let main_pass_dependencies = graph
+        .iter_node_inputs(base::node::MAIN_PASS)
+        .unwrap()
+        .filter(|(edge, _)| matches!(edge, Edge::NodeEdge { .. }))
+        .map(|(_, node_state)| node_state.id)
+        .collect::<Vec<_>>();
+    for dependency in main_pass_dependencies {
+        graph
+            .add_node_edge(dependency, node::MOTION_VECTOR_PASS)
+            .unwrap();
+    }
+}
+
+/// Marks an entity to be drawn into the motion vector texture using `pipeline`.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct MotionVectors {
+    /// The pipeline used to draw the entity. Meshes that are deformed in the vertex shader need
+    /// a pipeline that applies the same deformation for both the current and the previous frame.
+    pub pipeline: Handle<PipelineDescriptor>,
+}
+
+impl Default for MotionVectors {
+    fn default() -> Self {
+        MotionVectors {
+            pipeline: MOTION_VECTORS_PIPELINE_HANDLE.typed(),
+        }
+    }
+}
+
+/// Holds the render commands of the motion vector pass, which are kept apart from the [Draw]
+/// component used by the main pass.
+#[derive(Component, Debug, Default, Clone)]
+pub struct MotionVectorDraw(pub Draw);
+
+impl AsRef<Draw> for MotionVectorDraw {
+    fn as_ref(&self) -> &Draw {
+        &self.0
+    }
+}
+
+/// The model matrix of an entity as it was rendered in the previous frame. Bound to shaders as
+/// the `PreviousTransform` uniform.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct PreviousGlobalTransform(pub Mat4);
+
+impl Default for PreviousGlobalTransform {
+    fn default() -> Self {
+        PreviousGlobalTransform(Mat4::IDENTITY)
+    }
+}
+
+impl RenderResources for PreviousGlobalTransform {
+    fn render_resources_len(&self) -> usize {
+        1
+    }
+
+    fn get_render_resource(&self, index: usize) -> Option<&dyn RenderResource> {
+        (index == 0).then_some(&self.0 as &dyn RenderResource)
+    }
+
+    fn get_render_resource_name(&self, index: usize) -> Option<&str> {
+        (index == 0).then_some("PreviousTransform")
+    }
+
+    fn iter(&self) -> RenderResourceIterator<'_> {
+        RenderResourceIterator::new(self)
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn add_motion_vectors_system(
+    mut commands: Commands,
+    query: Query<
+        (Entity, &GlobalTransform),
+        (With<MainPass>, With<Handle<Mesh>>, Without<MotionVectors>),
+    >,
+) {
+    for (entity, global_transform) in query.iter() {
+        commands.entity(entity).insert_bundle((
+            MotionVectors::default(),
+            MotionVectorDraw::default(),
+            // the entity hasn't moved yet
+            PreviousGlobalTransform(global_transform.compute_matrix()),
+        ));
+    }
+}
+
+pub fn update_previous_global_transform_system(
+    mut query: Query<(&mut PreviousGlobalTransform, &GlobalTransform)>,
+) {
+    for (mut previous_global_transform, global_transform) in query.iter_mut() {
+        let matrix = global_transform.compute_matrix();
+        // avoid triggering change detection (and a buffer upload) for entities that didn't move
+        if previous_global_transform.0 != matrix {
+            previous_global_transform.0 = matrix;
+        }
+    }
+}
+
+pub fn clear_motion_vector_draw_system(mut query: Query<&mut MotionVectorDraw>) {
+    for mut draw in query.iter_mut() {
+        draw.0.clear_render_commands();
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn draw_motion_vectors_system(
+    mut draw_context: DrawContext,
+    meshes: Res<Assets<Mesh>>,
+    mut query: Query<
+        (
+            &mut MotionVectorDraw,
+            &mut RenderPipelines,
+            &MotionVectors,
+            &Handle<Mesh>,
+            &Visible,
+        ),
+        (With<PreviousGlobalTransform>, Without<OutsideFrustum>),
+    >,
+) {
+    for (mut draw, mut render_pipelines, motion_vectors, mesh_handle, visible) in query.iter_mut() {
+        // transparent surfaces don't write depth, so they don't own the pixels they cover
+        if !visible.is_visible || visible.is_transparent {
+            continue;
+        }
+
+        // don't render if the mesh isn't loaded yet
+        let mesh = if let Some(mesh) = meshes.get(mesh_handle) {
+            mesh
+        } else {
+            continue;
+        };
+
+        let render_pipeline = RenderPipeline::specialized(
+            motion_vectors.pipeline.clone_weak(),
+            PipelineSpecialization {
+                sample_count: 1,
+                strip_index_format: None,
+                shader_specialization: Default::default(),
+                primitive_topology: mesh.primitive_topology(),
+                dynamic_bindings: render_pipelines
+                    .bindings
+                    .iter_dynamic_bindings()
+                    .map(|name| name.to_string())
+                    .collect::<HashSet<String>>(),
+                vertex_buffer_layout: mesh.get_vertex_buffer_layout(),
+            },
+        );
+
+        let draw = &mut draw.0;
+        draw_context
+            .set_pipeline(
+                draw,
+                &render_pipeline.pipeline,
+                &render_pipeline.specialization,
+            )
+            .unwrap();
+        draw_context
+            .set_bind_groups_from_bindings(draw, &mut [&mut render_pipelines.bindings])
+            .unwrap();
+        draw_context
+            .set_vertex_buffers_from_bindings(draw, &[&render_pipelines.bindings])
+            .unwrap();
+
+        match mesh.indices() {
+            Some(Indices::U32(indices)) => draw.draw_indexed(0..indices.len() as u32, 0, 0..1),
+            Some(Indices::U16(indices)) => draw.draw_indexed(0..indices.len() as u32, 0, 0..1),
+            None => draw.draw(0..mesh.count_vertices() as u32, 0..1),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{render_graph::base::BaseRenderGraphConfig, Msaa};
+    use bevy_ecs::{
+        query::Changed,
+        schedule::{Stage, SystemStage},
+        world::World,
+    };
+    use bevy_math::Vec3;
+
+    #[test]
+    fn track_previous_transforms() {
+        let mut world = World::default();
+        let mesh = world
+            .spawn()
+            .insert_bundle((
+                MainPass,
+                Handle::<Mesh>::default(),
+                GlobalTransform::from_translation(Vec3::X),
+            ))
+            .id();
+        let unrendered = world
+            .spawn()
+            .insert_bundle((Handle::<Mesh>::default(), GlobalTransform::identity()))
+            .id();
+
+        let mut add_stage = SystemStage::parallel();
+        add_stage.add_system(add_motion_vectors_system);
+        add_stage.run(&mut world);
+        // meshes start out as if they didn't move
+        assert_eq!(
+            world.get::<PreviousGlobalTransform>(mesh),
+            Some(&PreviousGlobalTransform(Mat4::from_translation(Vec3::X)))
+        );
+        assert!(world.get::<MotionVectors>(mesh).is_some());
+        assert!(world.get::<MotionVectorDraw>(mesh).is_some());
+        assert!(world.get::<MotionVectors>(unrendered).is_none());
+
+        let moved = world
+            .spawn()
+            .insert_bundle((
+                PreviousGlobalTransform::default(),
+                GlobalTransform::identity(),
+            ))
+            .id();
+        world.clear_trackers();
+        *world.get_mut::<GlobalTransform>(moved).unwrap() =
+            GlobalTransform::from_translation(Vec3::Y);
+        let mut update_stage = SystemStage::parallel();
+        update_stage.add_system(update_previous_global_transform_system);
+        update_stage.run(&mut world);
+        assert_eq!(
+            world.get::<PreviousGlobalTransform>(moved),
+            Some(&PreviousGlobalTransform(Mat4::from_translation(Vec3::Y)))
+        );
+        // only the entity that moved is uploaded again
+        let changed = world
+            .query_filtered::<Entity, Changed<PreviousGlobalTransform>>()
+            .iter(&world)
+            .collect::<Vec<_>>();
+        assert_eq!(changed, [moved]);
+    }
+
+    #[test]
+    fn bind_previous_transform() {
+        let previous_global_transform = PreviousGlobalTransform(Mat4::from_scale(Vec3::ONE * 2.0));
+        assert_eq!(previous_global_transform.render_resources_len(), 1);
+        assert_eq!(
+            previous_global_transform.get_render_resource_name(0),
+            Some("PreviousTransform")
+        );
+        assert!(previous_global_transform.get_render_resource(0).is_some());
+        assert!(previous_global_transform.get_render_resource(1).is_none());
+        assert_eq!(previous_global_transform.iter().count(), 1);
+    }
+
+    #[test]
+    fn run_alongside_main_pass() {
+        let mut world = World::default();
+        world.insert_resource(RenderGraph::default());
+        world.insert_resource(Msaa::default());
+        base::add_base_graph(&BaseRenderGraphConfig::default(), &mut world);
+        let mut graph = world.get_resource_mut::<RenderGraph>().unwrap();
+        add_motion_vector_graph(&mut graph);
+
+        let inputs = graph
+            .iter_node_inputs(node::MOTION_VECTOR_PASS)
+            .unwrap()
+            .map(|(_, node_state)| node_state.id)
+            .collect::<Vec<_>>();
+        for label in [
+            base::node::CAMERA_3D,
+            base::node::SHARED_BUFFERS,
+            node::PREVIOUS_TRANSFORM,
+            node::MOTION_VECTOR_TEXTURE,
+            node::MOTION_VECTOR_DEPTH_TEXTURE,
+        ] {
+            assert!(inputs.contains(&graph.get_node_id(label).unwrap()));
+        }
+        // post processing after the main pass can read the motion vectors
+        assert!(!inputs.contains(&graph.get_node_id(base::node::MAIN_PASS).unwrap()));
+    }
+}
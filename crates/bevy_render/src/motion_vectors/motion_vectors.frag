@@ -0,0 +1,13 @@
+#version 450
+
+layout(location = 0) in vec4 v_ClipPosition;
+layout(location = 1) in vec4 v_PreviousClipPosition;
+
+layout(location = 0) out vec2 o_Target;
+
+void main() {
+    vec2 ndc = v_ClipPosition.xy / v_ClipPosition.w;
+    vec2 previous_ndc = v_PreviousClipPosition.xy / v_PreviousClipPosition.w;
+    // convert to uv units, where y points down
+    o_Target = (ndc - previous_ndc) * vec2(0.5, -0.5);
+}
@@ -1,6 +1,10 @@
 use super::{
-    CameraNode, PassNode, RenderGraph, SharedBuffersNode, TextureCopyNode, WindowSwapChainNode,
-    WindowTextureNode,
+    CameraNode, Edge, Node, PassNode, RenderGraph, RenderGraphError, SharedBuffersNode,
+    TextureCopyNode, WindowSwapChainNode, WindowTextureNode,
+};
+use crate::{
+    camera::ActiveCameras,
+    renderer::{RenderResourceContext, TextureId},
 };
 use crate::{
     pass::{
@@ -10,9 +14,16 @@ use crate::{
     texture::{Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsage},
     Color,
 };
-use bevy_ecs::{component::Component, reflect::ReflectComponent, world::World};
+use bevy_app::EventReader;
+use bevy_ecs::{
+    component::Component,
+    reflect::ReflectComponent,
+    system::{Res, ResMut},
+    world::World,
+};
 use bevy_reflect::Reflect;
-use bevy_window::WindowId;
+use bevy_window::{WindowClosed, WindowCreated, WindowId};
+use std::borrow::Cow;
 
 /// A component that indicates that an entity should be drawn in the "main pass"
 #[derive(Component, Clone, Debug, Default, Reflect)]
@@ -113,47 +124,12 @@ pub(crate) fn add_base_graph(config: &BaseRenderGraphConfig, world: &mut World)
     if config.add_main_depth_texture {
         graph.add_node(
             node::MAIN_DEPTH_TEXTURE,
-            WindowTextureNode::new(
-                WindowId::primary(),
-                TextureDescriptor {
-                    size: Extent3d {
-                        depth_or_array_layers: 1,
-                        width: 1,
-                        height: 1,
-                    },
-                    mip_level_count: 1,
-                    sample_count: msaa.samples,
-                    dimension: TextureDimension::D2,
-                    format: TextureFormat::Depth32Float, /* PERF: vulkan docs recommend using 24
-                                                          * bit depth for better performance */
-                    usage: TextureUsage::OUTPUT_ATTACHMENT,
-                },
-            ),
+            WindowTextureNode::new(WindowId::primary(), main_depth_texture_descriptor(&msaa)),
         );
     }
 
     if config.add_main_pass {
-        let mut main_pass_node = PassNode::<&MainPass>::new(PassDescriptor {
-            color_attachments: vec![msaa.color_attachment(
-                TextureAttachment::Input("color_attachment".to_string()),
-                TextureAttachment::Input("color_resolve_target".to_string()),
-                Operations {
-                    load: LoadOp::Clear(Color::rgb(0.1, 0.1, 0.1)),
-                    store: true,
-                },
-            )],
-            depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
-                attachment: TextureAttachment::Input("depth".to_string()),
-                depth_ops: Some(Operations {
-                    load: LoadOp::Clear(1.0),
-                    store: true,
-                }),
-                stencil_ops: None,
-            }),
-            sample_count: msaa.samples,
-        });
-
-        main_pass_node.use_default_clear_color(0);
+        let mut main_pass_node = main_pass_node(&msaa);
 
         if config.add_3d_camera {
             main_pass_node.add_camera(camera::CAMERA_3D);
@@ -210,18 +186,7 @@ pub(crate) fn add_base_graph(config: &BaseRenderGraphConfig, world: &mut World)
             node::MAIN_SAMPLED_COLOR_ATTACHMENT,
             WindowTextureNode::new(
                 WindowId::primary(),
-                TextureDescriptor {
-                    size: Extent3d {
-                        depth_or_array_layers: 1,
-                        width: 1,
-                        height: 1,
-                    },
-                    mip_level_count: 1,
-                    sample_count: msaa.samples,
-                    dimension: TextureDimension::D2,
-                    format: TextureFormat::default(),
-                    usage: TextureUsage::OUTPUT_ATTACHMENT,
-                },
+                main_sampled_color_attachment_descriptor(&msaa),
             ),
         );
 
@@ -246,3 +211,313 @@ pub(crate) fn add_base_graph(config: &BaseRenderGraphConfig, world: &mut World)
             .unwrap();
     }
 }
+
+/// Inserts a post processing `node` between the main pass and the texture it currently renders
+/// into (the primary swap chain, unless another post processing node was added before).
+///
+/// The main pass is redirected into a new intermediate texture, which is connected to the
+/// `color_input` slot of `node`. `node` then renders into the previous target of the main pass
+/// through its `"color_attachment"` input slot. Because every call takes over the output of the
+/// main pass, post processing nodes added later run before the ones added earlier.
+fn main_depth_texture_descriptor(msaa: &Msaa) -> TextureDescriptor {
+    TextureDescriptor {
+        size: Extent3d {
+            depth_or_array_layers: 1,
+            width: 1,
+            height: 1,
+        },
+        mip_level_count: 1,
+        sample_count: msaa.samples,
+        dimension: TextureDimension::D2,
+        format: TextureFormat::Depth32Float, /* PERF: vulkan docs recommend using 24
+                                              * bit depth for better performance */
+        // sampled by post processing effects that read the depth of the main pass
+        usage: TextureUsage::OUTPUT_ATTACHMENT | TextureUsage::SAMPLED,
+    }
+}
+
+fn main_sampled_color_attachment_descriptor(msaa: &Msaa) -> TextureDescriptor {
+    TextureDescriptor {
+        size: Extent3d {
+            depth_or_array_layers: 1,
+            width: 1,
+            height: 1,
+        },
+        mip_level_count: 1,
+        sample_count: msaa.samples,
+        dimension: TextureDimension::D2,
+        format: TextureFormat::default(),
+        usage: TextureUsage::OUTPUT_ATTACHMENT,
+    }
+}
+
+fn main_pass_node(msaa: &Msaa) -> PassNode<&'static MainPass> {
+    let mut main_pass_node = PassNode::<&MainPass>::new(PassDescriptor {
+        color_attachments: vec![msaa.color_attachment(
+            TextureAttachment::Input("color_attachment".to_string()),
+            TextureAttachment::Input("color_resolve_target".to_string()),
+            Operations {
+                load: LoadOp::Clear(Color::rgb(0.1, 0.1, 0.1)),
+                store: true,
+            },
+        )],
+        depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+            attachment: TextureAttachment::Input("depth".to_string()),
+            depth_ops: Some(Operations {
+                load: LoadOp::Clear(1.0),
+                store: true,
+            }),
+            stencil_ops: None,
+        }),
+        sample_count: msaa.samples,
+    });
+    main_pass_node.use_default_clear_color(0);
+    main_pass_node
+}
+
+/// The name of a node of the graph of a window. The nodes of the primary window keep the names
+/// of [`node`], the nodes of the other windows are suffixed with the id of their window.
+pub fn window_node_name(node: &str, window_id: WindowId) -> String {
+    if window_id.is_primary() {
+        node.to_string()
+    } else {
+        format!("{}_{}", node, window_id)
+    }
+}
+
+/// The name of a camera drawn to a window. The cameras of the primary window keep the names of
+/// [`camera`], the cameras of the other windows are suffixed with the id of their window.
+pub fn window_camera_name(camera: &str, window_id: WindowId) -> String {
+    if window_id.is_primary() {
+        camera.to_string()
+    } else {
+        format!("{}_{}", camera, window_id)
+    }
+}
+
+/// Adds the nodes drawing the 3d and 2d cameras of a secondary window to its swap chain, like the
+/// base graph does for the primary window, and makes its cameras active. The nodes and cameras
+/// are named with [`window_node_name`] and [`window_camera_name`].
+pub fn add_window_graph(
+    graph: &mut RenderGraph,
+    active_cameras: &mut ActiveCameras,
+    msaa: &Msaa,
+    window_id: WindowId,
+) {
+    let swap_chain = window_node_name(node::PRIMARY_SWAP_CHAIN, window_id);
+    let depth_texture = window_node_name(node::MAIN_DEPTH_TEXTURE, window_id);
+    let main_pass = window_node_name(node::MAIN_PASS, window_id);
+
+    graph.add_node(swap_chain.clone(), WindowSwapChainNode::new(window_id));
+    graph.add_node(
+        depth_texture.clone(),
+        WindowTextureNode::new(window_id, main_depth_texture_descriptor(msaa)),
+    );
+
+    let mut main_pass_node = main_pass_node(msaa);
+    for camera in &[camera::CAMERA_3D, camera::CAMERA_2D] {
+        main_pass_node.add_camera(&window_camera_name(camera, window_id));
+    }
+    graph.add_node(main_pass.clone(), main_pass_node);
+
+    graph
+        .add_node_edge(node::TEXTURE_COPY, main_pass.clone())
+        .unwrap();
+    graph
+        .add_node_edge(node::SHARED_BUFFERS, main_pass.clone())
+        .unwrap();
+    for (camera_node, camera) in &[
+        (node::CAMERA_3D, camera::CAMERA_3D),
+        (node::CAMERA_2D, camera::CAMERA_2D),
+    ] {
+        let camera_node = window_node_name(camera_node, window_id);
+        let camera = window_camera_name(camera, window_id);
+        graph.add_system_node(camera_node.clone(), CameraNode::new(camera.clone()));
+        graph.add_node_edge(camera_node, main_pass.clone()).unwrap();
+        active_cameras.add(&camera);
+    }
+
+    graph
+        .add_slot_edge(
+            swap_chain.clone(),
+            WindowSwapChainNode::OUT_TEXTURE,
+            main_pass.clone(),
+            if msaa.samples > 1 {
+                "color_resolve_target"
+            } else {
+                "color_attachment"
+            },
+        )
+        .unwrap();
+    if msaa.samples > 1 {
+        let sampled_color_attachment =
+            window_node_name(node::MAIN_SAMPLED_COLOR_ATTACHMENT, window_id);
+        graph.add_node(
+            sampled_color_attachment.clone(),
+            WindowTextureNode::new(window_id, main_sampled_color_attachment_descriptor(msaa)),
+        );
+        graph
+            .add_slot_edge(
+                sampled_color_attachment,
+                WindowSwapChainNode::OUT_TEXTURE,
+                main_pass.clone(),
+                "color_attachment",
+            )
+            .unwrap();
+    }
+    graph
+        .add_slot_edge(
+            depth_texture,
+            WindowTextureNode::OUT_TEXTURE,
+            main_pass,
+            "depth",
+        )
+        .unwrap();
+}
+
+/// Removes the nodes added by [`add_window_graph`] and the cameras of the window, returning the
+/// textures of the removed nodes, which are to be removed from the render resource context. The
+/// systems of the camera nodes keep running, but don't do anything without their active camera.
+pub fn remove_window_graph(
+    graph: &mut RenderGraph,
+    active_cameras: &mut ActiveCameras,
+    window_id: WindowId,
+) -> Vec<TextureId> {
+    for node in &[
+        node::PRIMARY_SWAP_CHAIN,
+        node::MAIN_PASS,
+        node::CAMERA_3D,
+        node::CAMERA_2D,
+    ] {
+        let _ = graph.remove_node(window_node_name(node, window_id));
+    }
+    // the sampled color attachment only exists with msaa
+    let textures = [
+        node::MAIN_DEPTH_TEXTURE,
+        node::MAIN_SAMPLED_COLOR_ATTACHMENT,
+    ]
+    .iter()
+    .filter_map(|node| graph.remove_node(window_node_name(node, window_id)).ok())
+    .filter_map(|node_state| node_state.output_slots.get(WindowTextureNode::OUT_TEXTURE))
+    .filter_map(|resource| resource.get_texture())
+    .collect();
+    for camera in &[camera::CAMERA_3D, camera::CAMERA_2D] {
+        active_cameras.remove(&window_camera_name(camera, window_id));
+    }
+    textures
+}
+
+/// Adds the graph of the secondary windows when they are created, and removes it when they are
+/// closed.
+pub fn window_graph_system(
+    mut graph: ResMut<RenderGraph>,
+    mut active_cameras: ResMut<ActiveCameras>,
+    msaa: Res<Msaa>,
+    mut window_created_events: EventReader<WindowCreated>,
+    mut window_closed_events: EventReader<WindowClosed>,
+    render_resource_context: Option<Res<Box<dyn RenderResourceContext>>>,
+) {
+    for event in window_created_events.iter() {
+        if !event.id.is_primary() {
+            add_window_graph(&mut graph, &mut active_cameras, &msaa, event.id);
+        }
+    }
+    for event in window_closed_events.iter() {
+        if !event.id.is_primary() {
+            let textures = remove_window_graph(&mut graph, &mut active_cameras, event.id);
+            if let Some(render_resource_context) = &render_resource_context {
+                for texture in textures {
+                    render_resource_context.remove_texture(texture);
+                }
+            }
+        }
+    }
+}
+
+pub fn add_main_pass_post_process_node<T: Node>(
+    graph: &mut RenderGraph,
+    msaa: &Msaa,
+    name: impl Into<Cow<'static, str>>,
+    node: T,
+    color_input: &'static str,
+) -> Result<(), RenderGraphError> {
+    let name = name.into();
+    let main_pass_target = if msaa.samples > 1 {
+        "color_resolve_target"
+    } else {
+        "color_attachment"
+    };
+
+    let (previous_target_node, previous_target_index) = {
+        let main_pass = graph.get_node_state(node::MAIN_PASS)?;
+        let target_index = main_pass.input_slots.get_slot_index(main_pass_target)?;
+        match *main_pass.edges.get_input_slot_edge(target_index)? {
+            Edge::SlotEdge {
+                output_node,
+                output_index,
+                ..
+            } => (output_node, output_index),
+            Edge::NodeEdge { .. } => unreachable!(),
+        }
+    };
+    // nodes that run after the main pass read from its previous target, so they have to wait for
+    // this node to write into it
+    let main_pass_dependents = graph
+        .iter_node_outputs(node::MAIN_PASS)?
+        .filter(|(edge, _)| matches!(edge, Edge::NodeEdge { .. }))
+        .map(|(_, node_state)| node_state.id)
+        .collect::<Vec<_>>();
+
+    let node_id = graph.add_node(name.clone(), node);
+    let texture_node = format!("{}_input_texture", name);
+    graph.add_node(
+        texture_node.clone(),
+        WindowTextureNode::new(
+            WindowId::primary(),
+            TextureDescriptor {
+                size: Extent3d {
+                    depth_or_array_layers: 1,
+                    width: 1,
+                    height: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::default(),
+                usage: TextureUsage::OUTPUT_ATTACHMENT | TextureUsage::SAMPLED,
+            },
+        ),
+    );
+
+    graph.remove_slot_edge(
+        previous_target_node,
+        previous_target_index,
+        node::MAIN_PASS,
+        main_pass_target,
+    )?;
+    graph.add_slot_edge(
+        previous_target_node,
+        previous_target_index,
+        node_id,
+        "color_attachment",
+    )?;
+    graph.add_slot_edge(
+        texture_node.clone(),
+        WindowTextureNode::OUT_TEXTURE,
+        node::MAIN_PASS,
+        main_pass_target,
+    )?;
+    graph.add_slot_edge(
+        texture_node,
+        WindowTextureNode::OUT_TEXTURE,
+        node_id,
+        color_input,
+    )?;
+
+    graph.add_node_edge(node::MAIN_PASS, node_id)?;
+    for dependent in main_pass_dependents {
+        graph.add_node_edge(node_id, dependent)?;
+    }
+
+    Ok(())
+}
@@ -52,6 +52,34 @@ impl RenderGraph {
         self.add_node(name, node)
     }
 
+    /// Removes a node and its edges, returning its state. The system of a system node keeps
+    /// running.
+    pub fn remove_node(
+        &mut self,
+        label: impl Into<NodeLabel>,
+    ) -> Result<NodeState, RenderGraphError> {
+        let label = label.into();
+        let node_id = self.get_node_id(&label)?;
+        let node_state = self
+            .nodes
+            .remove(&node_id)
+            .ok_or(RenderGraphError::InvalidNode(label))?;
+        for edge in node_state.edges.input_edges.iter() {
+            if let Some(output_node) = self.nodes.get_mut(&edge.get_output_node()) {
+                output_node.edges.remove_output_edge(edge.clone())?;
+            }
+        }
+        for edge in node_state.edges.output_edges.iter() {
+            if let Some(input_node) = self.nodes.get_mut(&edge.get_input_node()) {
+                input_node.edges.remove_input_edge(edge.clone())?;
+            }
+        }
+        if let Some(name) = &node_state.name {
+            self.node_names.remove(name);
+        }
+        Ok(node_state)
+    }
+
     pub fn get_node_state(
         &self,
         label: impl Into<NodeLabel>,
@@ -166,6 +194,62 @@ impl RenderGraph {
         Ok(())
     }
 
+    pub fn remove_slot_edge(
+        &mut self,
+        output_node: impl Into<NodeLabel>,
+        output_slot: impl Into<SlotLabel>,
+        input_node: impl Into<NodeLabel>,
+        input_slot: impl Into<SlotLabel>,
+    ) -> Result<(), RenderGraphError> {
+        let output_node_id = self.get_node_id(output_node)?;
+        let input_node_id = self.get_node_id(input_node)?;
+
+        let output_index = self
+            .get_node_state(output_node_id)?
+            .output_slots
+            .get_slot_index(output_slot)?;
+        let input_index = self
+            .get_node_state(input_node_id)?
+            .input_slots
+            .get_slot_index(input_slot)?;
+
+        self.remove_edge(Edge::SlotEdge {
+            output_node: output_node_id,
+            output_index,
+            input_node: input_node_id,
+            input_index,
+        })
+    }
+
+    pub fn remove_node_edge(
+        &mut self,
+        output_node: impl Into<NodeLabel>,
+        input_node: impl Into<NodeLabel>,
+    ) -> Result<(), RenderGraphError> {
+        let output_node_id = self.get_node_id(output_node)?;
+        let input_node_id = self.get_node_id(input_node)?;
+
+        self.remove_edge(Edge::NodeEdge {
+            output_node: output_node_id,
+            input_node: input_node_id,
+        })
+    }
+
+    fn remove_edge(&mut self, edge: Edge) -> Result<(), RenderGraphError> {
+        if !self.has_edge(&edge) {
+            return Err(RenderGraphError::EdgeDoesNotExist(edge));
+        }
+
+        {
+            let output_node = self.get_node_state_mut(edge.get_output_node())?;
+            output_node.edges.remove_output_edge(edge.clone())?;
+        }
+        let input_node = self.get_node_state_mut(edge.get_input_node())?;
+        input_node.edges.remove_input_edge(edge)?;
+
+        Ok(())
+    }
+
     pub fn validate_edge(&mut self, edge: &Edge) -> Result<(), RenderGraphError> {
         if self.has_edge(edge) {
             return Err(RenderGraphError::EdgeAlreadyExists(edge.clone()));
@@ -410,6 +494,28 @@ mod tests {
         assert!(output_nodes("D", &graph).is_empty(), "D has no outputs");
     }
 
+    #[test]
+    fn test_remove_node() {
+        let mut graph = RenderGraph::default();
+        graph.add_node("A", TestNode::new(0, 1));
+        graph.add_node("B", TestNode::new(1, 1));
+        let c_id = graph.add_node("C", TestNode::new(1, 0));
+
+        graph.add_slot_edge("A", 0, "B", 0).unwrap();
+        graph.add_slot_edge("B", 0, "C", 0).unwrap();
+        graph.remove_node("B").unwrap();
+
+        assert!(graph.get_node_id("B").is_err(), "B is removed");
+        assert_eq!(graph.iter_node_outputs("A").unwrap().count(), 0);
+        assert_eq!(graph.iter_node_inputs(c_id).unwrap().count(), 0);
+        // the input slot of C is free again
+        graph.add_slot_edge("A", 0, "C", 0).unwrap();
+        assert!(matches!(
+            graph.remove_node("B"),
+            Err(RenderGraphError::InvalidNode(_))
+        ));
+    }
+
     #[test]
     fn test_get_node_typed() {
         struct MyNode {
@@ -462,6 +568,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_remove_edges() {
+        let mut graph = RenderGraph::default();
+
+        graph.add_node("A", TestNode::new(0, 1));
+        graph.add_node("B", TestNode::new(0, 1));
+        graph.add_node("C", TestNode::new(1, 0));
+
+        graph.add_slot_edge("A", 0, "C", 0).unwrap();
+        graph.add_node_edge("B", "C").unwrap();
+
+        graph.remove_slot_edge("A", 0, "C", 0).unwrap();
+        graph.remove_node_edge("B", "C").unwrap();
+        assert!(
+            graph.iter_node_inputs("C").unwrap().next().is_none(),
+            "C has no inputs after removing its edges"
+        );
+
+        graph.add_slot_edge("B", 0, "C", 0).unwrap();
+        assert_eq!(
+            graph.remove_node_edge("A", "C"),
+            Err(RenderGraphError::EdgeDoesNotExist(Edge::NodeEdge {
+                output_node: graph.get_node_id("A").unwrap(),
+                input_node: graph.get_node_id("C").unwrap(),
+            })),
+            "Removing an edge that doesn't exist should return an error"
+        );
+    }
+
     #[test]
     fn test_edge_already_exists() {
         let mut graph = RenderGraph::default();
@@ -11,6 +11,7 @@ use bevy_ecs::{
     system::{BoxedSystem, ConfigurableSystem, Local, Query, Res, ResMut},
     world::World,
 };
+use bevy_math::Mat4;
 use bevy_transform::prelude::*;
 use std::borrow::Cow;
 
@@ -51,6 +52,7 @@ impl SystemNode for CameraNode {
                 camera_name: self.camera_name.clone(),
                 command_queue: self.command_queue.clone(),
                 staging_buffer: None,
+                previous_view_proj: None,
             })
         });
         Box::new(system)
@@ -58,6 +60,7 @@ impl SystemNode for CameraNode {
 }
 
 const CAMERA_VIEW_PROJ: &str = "CameraViewProj";
+const CAMERA_PREVIOUS_VIEW_PROJ: &str = "CameraPreviousViewProj";
 const CAMERA_VIEW: &str = "CameraView";
 const CAMERA_POSITION: &str = "CameraPosition";
 
@@ -66,6 +69,7 @@ pub struct CameraNodeState {
     command_queue: CommandQueue,
     camera_name: Cow<'static, str>,
     staging_buffer: Option<BufferId>,
+    previous_view_proj: Option<Mat4>,
 }
 
 const MATRIX_SIZE: usize = std::mem::size_of::<[[f32; 4]; 4]>();
@@ -98,6 +102,8 @@ pub fn camera_node_system(
             size:
                 // ViewProj
                 MATRIX_SIZE +
+                // PreviousViewProj
+                MATRIX_SIZE +
                 // View
                 MATRIX_SIZE +
                 // Position
@@ -126,6 +132,22 @@ pub fn camera_node_system(
         );
     }
 
+    if bindings.get(CAMERA_PREVIOUS_VIEW_PROJ).is_none() {
+        let buffer = render_resource_context.create_buffer(BufferInfo {
+            size: MATRIX_SIZE,
+            buffer_usage: BufferUsage::COPY_DST | BufferUsage::UNIFORM,
+            ..Default::default()
+        });
+        bindings.set(
+            CAMERA_PREVIOUS_VIEW_PROJ,
+            RenderResourceBinding::Buffer {
+                buffer,
+                range: 0..MATRIX_SIZE as u64,
+                dynamic_index: None,
+            },
+        );
+    }
+
     if bindings.get(CAMERA_VIEW).is_none() {
         let buffer = render_resource_context.create_buffer(BufferInfo {
             size: MATRIX_SIZE,
@@ -159,6 +181,10 @@ pub fn camera_node_system(
     }
 
     let view = global_transform.compute_matrix();
+    let view_proj = camera.projection_matrix * view.inverse();
+    // used to compute motion vectors. on the first frame the camera hasn't moved yet
+    let previous_view_proj = state.previous_view_proj.unwrap_or(view_proj);
+    state.previous_view_proj = Some(view_proj);
     let mut offset = 0;
 
     if let Some(RenderResourceBinding::Buffer { buffer, .. }) = bindings.get(CAMERA_VIEW) {
@@ -180,7 +206,6 @@ pub fn camera_node_system(
     }
 
     if let Some(RenderResourceBinding::Buffer { buffer, .. }) = bindings.get(CAMERA_VIEW_PROJ) {
-        let view_proj = camera.projection_matrix * view.inverse();
         render_resource_context.write_mapped_buffer(
             staging_buffer,
             offset..(offset + MATRIX_SIZE as u64),
@@ -198,6 +223,26 @@ pub fn camera_node_system(
         offset += MATRIX_SIZE as u64;
     }
 
+    if let Some(RenderResourceBinding::Buffer { buffer, .. }) =
+        bindings.get(CAMERA_PREVIOUS_VIEW_PROJ)
+    {
+        render_resource_context.write_mapped_buffer(
+            staging_buffer,
+            offset..(offset + MATRIX_SIZE as u64),
+            &mut |data, _renderer| {
+                data[0..MATRIX_SIZE].copy_from_slice(bytes_of(&previous_view_proj));
+            },
+        );
+        state.command_queue.copy_buffer_to_buffer(
+            staging_buffer,
+            offset,
+            *buffer,
+            0,
+            MATRIX_SIZE as u64,
+        );
+        offset += MATRIX_SIZE as u64;
+    }
+
     if let Some(RenderResourceBinding::Buffer { buffer, .. }) = bindings.get(CAMERA_POSITION) {
         let position: [f32; 3] = global_transform.translation.into();
         let position: [f32; 4] = [position[0], position[1], position[2], 0.0];
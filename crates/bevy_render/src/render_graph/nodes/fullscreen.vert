@@ -0,0 +1,11 @@
+#version 450
+
+layout(location = 0) out vec2 v_Uv;
+
+// Covers the whole screen with a single triangle. No vertex buffers are required: draw it with
+// three vertices.
+void main() {
+    vec2 uv = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+    gl_Position = vec4(uv * 2.0 - 1.0, 0.0, 1.0);
+    v_Uv = vec2(uv.x, 1.0 - uv.y);
+}
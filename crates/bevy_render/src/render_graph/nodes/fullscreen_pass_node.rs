@@ -0,0 +1,253 @@
+use crate::{
+    pass::{LoadOp, Operations, PassDescriptor, RenderPassColorAttachment, TextureAttachment},
+    pipeline::{BindGroupDescriptorId, BindType, ColorTargetState, ColorWrite, PipelineDescriptor},
+    render_graph::{Node, ResourceSlotInfo, ResourceSlots},
+    renderer::{
+        BindGroupId, RenderContext, RenderResourceBinding, RenderResourceBindings,
+        RenderResourceContext, RenderResourceType, SamplerId,
+    },
+    shader::{Shader, ShaderStage, ShaderStages},
+    texture::{FilterMode, SamplerDescriptor, TextureFormat, TextureSampleType},
+    Color,
+};
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::world::World;
+use bevy_utils::tracing::debug;
+
+/// Builds a [PipelineDescriptor] that runs `fragment_shader` over every pixel of the target of a
+/// [FullscreenPassNode]. The fragment shader receives the screen uv in `v_Uv` (location 0).
+pub fn build_fullscreen_pipeline(
+    shaders: &mut Assets<Shader>,
+    fragment_shader: &str,
+) -> PipelineDescriptor {
+    let mut descriptor = PipelineDescriptor::new(ShaderStages {
+        vertex: shaders.add(Shader::from_glsl(
+            ShaderStage::Vertex,
+            include_str!("fullscreen.vert"),
+        )),
+        fragment: Some(shaders.add(Shader::from_glsl(ShaderStage::Fragment, fragment_shader))),
+    });
+    descriptor.primitive.cull_mode = None;
+    descriptor.color_target_states = vec![ColorTargetState {
+        format: TextureFormat::default(),
+        blend: None,
+        write_mask: ColorWrite::ALL,
+    }];
+    descriptor
+}
+
+/// A render graph [Node] that draws a single full screen triangle using a pipeline created with
+/// [build_fullscreen_pipeline]. This is the building block for post processing effects.
+///
+/// The pass renders into the [FullscreenPassNode::COLOR_ATTACHMENT] input. Every additional
+/// texture input is bound to the shader binding with the same name, along with a sampler bound to
+/// `{name}_sampler`. Other shader bindings (like uniform buffers holding effect settings) must be
+/// provided through [FullscreenPassNode::bindings_mut].
+pub struct FullscreenPassNode {
+    pipeline: Handle<PipelineDescriptor>,
+    compiled_pipeline: Option<Handle<PipelineDescriptor>>,
+    descriptor: PassDescriptor,
+    inputs: Vec<ResourceSlotInfo>,
+    depth_inputs: Vec<String>,
+    sampler: Option<SamplerId>,
+    depth_sampler: Option<SamplerId>,
+    bindings: RenderResourceBindings,
+}
+
+impl FullscreenPassNode {
+    pub const COLOR_ATTACHMENT: &'static str = "color_attachment";
+
+    pub fn new(pipeline: Handle<PipelineDescriptor>) -> Self {
+        FullscreenPassNode {
+            pipeline,
+            compiled_pipeline: None,
+            descriptor: PassDescriptor {
+                color_attachments: vec![RenderPassColorAttachment {
+                    attachment: TextureAttachment::Input(Self::COLOR_ATTACHMENT.to_string()),
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(Color::BLACK),
+                        store: true,
+                    },
+                }],
+                depth_stencil_attachment: None,
+                sample_count: 1,
+            },
+            inputs: vec![ResourceSlotInfo::new(
+                Self::COLOR_ATTACHMENT,
+                RenderResourceType::Texture,
+            )],
+            depth_inputs: Vec::new(),
+            sampler: None,
+            depth_sampler: None,
+            bindings: Default::default(),
+        }
+    }
+
+    /// Adds a texture input that is bound to the shader binding `name`.
+    pub fn add_texture_input(&mut self, name: &str) {
+        self.inputs.push(ResourceSlotInfo::new(
+            name.to_string(),
+            RenderResourceType::Texture,
+        ));
+    }
+
+    /// Adds a depth texture input that is bound to the shader binding `name`. Depth textures can't
+    /// be filtered, so they are sampled with a nearest sampler.
+    pub fn add_depth_input(&mut self, name: &str) {
+        self.add_texture_input(name);
+        self.depth_inputs.push(name.to_string());
+    }
+
+    pub fn bindings(&self) -> &RenderResourceBindings {
+        &self.bindings
+    }
+
+    pub fn bindings_mut(&mut self) -> &mut RenderResourceBindings {
+        &mut self.bindings
+    }
+
+    fn compile_pipeline(&self, world: &mut World) -> Option<Handle<PipelineDescriptor>> {
+        let world = world.cell();
+        let render_resource_context = world.get_resource::<Box<dyn RenderResourceContext>>()?;
+        let mut pipelines = world.get_resource_mut::<Assets<PipelineDescriptor>>()?;
+        let shaders = world.get_resource::<Assets<Shader>>()?;
+
+        let mut descriptor = pipelines.get(&self.pipeline)?.clone();
+        let mut layout = render_resource_context.reflect_pipeline_layout(
+            &shaders,
+            &descriptor.shader_stages,
+            true,
+        );
+        for bind_group in layout.bind_groups.iter_mut() {
+            let mut binding_changed = false;
+            for binding in bind_group.bindings.iter_mut() {
+                let is_depth_input = self.depth_inputs.iter().any(|name| {
+                    binding.name == *name || binding.name == format!("{}_sampler", name)
+                });
+                if !is_depth_input {
+                    continue;
+                }
+                match binding.bind_type {
+                    BindType::Texture {
+                        ref mut sample_type,
+                        ..
+                    } => {
+                        *sample_type = TextureSampleType::Depth;
+                        binding_changed = true;
+                    }
+                    BindType::Sampler {
+                        ref mut filtering, ..
+                    } => {
+                        *filtering = false;
+                        binding_changed = true;
+                    }
+                    _ => {}
+                }
+            }
+
+            if binding_changed {
+                bind_group.update_id();
+            }
+        }
+        descriptor.layout = Some(layout);
+
+        let compiled_pipeline = pipelines.add(descriptor);
+        render_resource_context.create_render_pipeline(
+            compiled_pipeline.clone_weak(),
+            pipelines.get(&compiled_pipeline).unwrap(),
+            &shaders,
+        );
+        Some(compiled_pipeline)
+    }
+}
+
+impl Node for FullscreenPassNode {
+    fn input(&self) -> &[ResourceSlotInfo] {
+        &self.inputs
+    }
+
+    fn prepare(&mut self, world: &mut World) {
+        if self.compiled_pipeline.is_none() {
+            self.compiled_pipeline = self.compile_pipeline(world);
+        }
+    }
+
+    fn update(
+        &mut self,
+        world: &World,
+        render_context: &mut dyn RenderContext,
+        input: &ResourceSlots,
+        _output: &mut ResourceSlots,
+    ) {
+        let pipeline = if let Some(ref pipeline) = self.compiled_pipeline {
+            pipeline.clone_weak()
+        } else {
+            return;
+        };
+
+        self.descriptor.color_attachments[0].attachment =
+            TextureAttachment::Id(input.get(0).unwrap().get_texture().unwrap());
+
+        let render_resource_context = render_context.resources();
+        let sampler = *self
+            .sampler
+            .get_or_insert_with(|| render_resource_context.create_sampler(&Default::default()));
+        let depth_sampler = *self.depth_sampler.get_or_insert_with(|| {
+            render_resource_context.create_sampler(&SamplerDescriptor {
+                mag_filter: FilterMode::Nearest,
+                min_filter: FilterMode::Nearest,
+                ..Default::default()
+            })
+        });
+        for (index, slot) in self.inputs.iter().enumerate().skip(1) {
+            let texture = input.get(index).unwrap().get_texture().unwrap();
+            let sampler = if self.depth_inputs.iter().any(|name| *name == slot.name) {
+                depth_sampler
+            } else {
+                sampler
+            };
+            self.bindings
+                .set(&slot.name, RenderResourceBinding::Texture(texture));
+            self.bindings.set(
+                &format!("{}_sampler", slot.name),
+                RenderResourceBinding::Sampler(sampler),
+            );
+        }
+
+        let pipelines = world.get_resource::<Assets<PipelineDescriptor>>().unwrap();
+        let layout = pipelines.get(&pipeline).unwrap().get_layout().unwrap();
+        let mut bind_groups: Vec<(u32, BindGroupDescriptorId, BindGroupId)> = Vec::new();
+        for bind_group_descriptor in layout.bind_groups.iter() {
+            if let Some(bind_group) = self
+                .bindings
+                .update_bind_group(bind_group_descriptor, render_resource_context)
+            {
+                bind_groups.push((
+                    bind_group_descriptor.index,
+                    bind_group_descriptor.id,
+                    bind_group.id,
+                ));
+            } else {
+                debug!(
+                    "Skipping fullscreen pass because bind group {} isn't fully bound for pipeline: {:?}",
+                    bind_group_descriptor.index, self.pipeline
+                );
+                return;
+            }
+        }
+
+        let render_resource_bindings = world.get_resource::<RenderResourceBindings>().unwrap();
+        render_context.begin_pass(
+            &self.descriptor,
+            render_resource_bindings,
+            &mut |render_pass| {
+                render_pass.set_pipeline(&pipeline);
+                for (index, bind_group_descriptor, bind_group) in bind_groups.iter() {
+                    render_pass.set_bind_group(*index, *bind_group_descriptor, *bind_group, None);
+                }
+                render_pass.draw(0..3, 0..1);
+            },
+        );
+    }
+}
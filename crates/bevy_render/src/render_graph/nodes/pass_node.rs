@@ -1,5 +1,6 @@
 use crate::{
-    camera::{ActiveCameras, VisibleEntities},
+    camera::{ActiveCameras, ClearBehavior, VisibleEntities},
+    color::Color,
     draw::{Draw, RenderCommand},
     pass::{ClearColor, LoadOp, PassDescriptor, TextureAttachment},
     pipeline::{IndexFormat, PipelineDescriptor},
@@ -11,13 +12,18 @@ use crate::{
 };
 use bevy_asset::{Assets, Handle};
 use bevy_ecs::{
+    component::Component,
     query::{QueryState, ReadOnlyFetch, WorldQuery},
     world::{Mut, World},
 };
 use bevy_utils::{tracing::debug, HashMap};
-use std::fmt;
+use std::{fmt, marker::PhantomData};
 
-pub struct PassNode<Q: WorldQuery> {
+/// A render graph [Node] that executes the render commands of every visible entity matching `Q`.
+///
+/// By default the commands are read from the [Draw] component. Passes that need to draw entities
+/// differently than the main pass can read them from another component through `D`.
+pub struct PassNode<Q: WorldQuery, D: Component + AsRef<Draw> = Draw> {
     descriptor: PassDescriptor,
     inputs: Vec<ResourceSlotInfo>,
     cameras: Vec<String>,
@@ -25,11 +31,24 @@ pub struct PassNode<Q: WorldQuery> {
     color_resolve_target_indices: Vec<Option<usize>>,
     depth_stencil_attachment_input_index: Option<usize>,
     default_clear_color_inputs: Vec<usize>,
+    color_attachment_load_ops: Vec<LoadOp<Color>>,
+    depth_load_op: Option<LoadOp<f32>>,
+    camera_passes: Vec<CameraPass>,
+    use_clear_behavior: bool,
     query_state: Option<QueryState<Q>>,
     commands: Vec<RenderCommand>,
+    marker: PhantomData<fn() -> D>,
 }
 
-impl<Q: WorldQuery> fmt::Debug for PassNode<Q> {
+/// The commands of a camera drawing in a [PassNode]. Each camera draws in its own render pass, so
+/// that the attachments are cleared with the [ClearBehavior] of that camera.
+#[derive(Debug)]
+struct CameraPass {
+    clear_behavior: Option<ClearBehavior>,
+    command_count: usize,
+}
+
+impl<Q: WorldQuery, D: Component + AsRef<Draw>> fmt::Debug for PassNode<Q, D> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("PassNode")
             .field("descriptor", &self.descriptor)
@@ -51,11 +70,13 @@ impl<Q: WorldQuery> fmt::Debug for PassNode<Q> {
                 "default_clear_color_inputs",
                 &self.default_clear_color_inputs,
             )
+            .field("camera_passes", &self.camera_passes)
+            .field("use_clear_behavior", &self.use_clear_behavior)
             .finish()
     }
 }
 
-impl<Q: WorldQuery> PassNode<Q> {
+impl<Q: WorldQuery, D: Component + AsRef<Draw>> PassNode<Q, D> {
     pub fn new(descriptor: PassDescriptor) -> Self {
         let mut inputs = Vec::new();
         let mut color_attachment_input_indices = Vec::new();
@@ -93,6 +114,17 @@ impl<Q: WorldQuery> PassNode<Q> {
             }
         }
 
+        let color_attachment_load_ops = descriptor
+            .color_attachments
+            .iter()
+            .map(|color_attachment| color_attachment.ops.load)
+            .collect();
+        let depth_load_op = descriptor
+            .depth_stencil_attachment
+            .as_ref()
+            .and_then(|depth_stencil_attachment| depth_stencil_attachment.depth_ops.as_ref())
+            .map(|depth_ops| depth_ops.load);
+
         PassNode {
             descriptor,
             inputs,
@@ -101,8 +133,13 @@ impl<Q: WorldQuery> PassNode<Q> {
             color_resolve_target_indices,
             depth_stencil_attachment_input_index,
             default_clear_color_inputs: Vec::new(),
+            color_attachment_load_ops,
+            depth_load_op,
+            camera_passes: Vec::new(),
+            use_clear_behavior: true,
             query_state: None,
             commands: Vec::new(),
+            marker: PhantomData,
         }
     }
 
@@ -113,9 +150,53 @@ impl<Q: WorldQuery> PassNode<Q> {
     pub fn use_default_clear_color(&mut self, color_attachment_index: usize) {
         self.default_clear_color_inputs.push(color_attachment_index);
     }
+
+    /// Makes the pass ignore the [ClearBehavior] of its cameras. This is useful for passes that
+    /// render something other than color, where the camera's clear color has no meaning.
+    pub fn ignore_clear_behavior(&mut self) {
+        self.use_clear_behavior = false;
+    }
+
+    /// Sets the load operations of the attachments for the pass of a camera, from its
+    /// [ClearBehavior]. Without one, the first camera uses the load operations of the pass, and
+    /// the next cameras keep what the previous cameras drew.
+    fn set_load_ops(
+        &mut self,
+        world: &World,
+        first_camera: bool,
+        clear_behavior: Option<ClearBehavior>,
+    ) {
+        for (i, color_attachment) in self.descriptor.color_attachments.iter_mut().enumerate() {
+            color_attachment.ops.load = match clear_behavior {
+                Some(ClearBehavior::Color(color)) => LoadOp::Clear(color),
+                Some(ClearBehavior::DontClear) | Some(ClearBehavior::Skybox) => LoadOp::Load,
+                None if !first_camera => LoadOp::Load,
+                None => match world.get_resource::<ClearColor>() {
+                    Some(default_clear_color) if self.default_clear_color_inputs.contains(&i) => {
+                        LoadOp::Clear(default_clear_color.0)
+                    }
+                    _ => self.color_attachment_load_ops[i],
+                },
+            };
+        }
+
+        if let Some(depth_ops) = self
+            .descriptor
+            .depth_stencil_attachment
+            .as_mut()
+            .and_then(|depth_stencil_attachment| depth_stencil_attachment.depth_ops.as_mut())
+        {
+            depth_ops.load = match clear_behavior {
+                Some(ClearBehavior::DontClear) => LoadOp::Load,
+                Some(ClearBehavior::Color(_)) | Some(ClearBehavior::Skybox) => LoadOp::Clear(1.0),
+                None if !first_camera => LoadOp::Load,
+                None => self.depth_load_op.unwrap_or(LoadOp::Clear(1.0)),
+            };
+        }
+    }
 }
 
-impl<Q: WorldQuery + Send + Sync + 'static> Node for PassNode<Q>
+impl<Q: WorldQuery + Send + Sync + 'static, D: Component + AsRef<Draw>> Node for PassNode<Q, D>
 where
     Q::Fetch: ReadOnlyFetch,
 {
@@ -127,8 +208,10 @@ where
         let query_state = self.query_state.get_or_insert_with(|| world.query());
         let cameras = &self.cameras;
         let commands = &mut self.commands;
+        let use_clear_behavior = self.use_clear_behavior;
+        let camera_passes = &mut self.camera_passes;
+        camera_passes.clear();
         world.resource_scope(|world, mut active_cameras: Mut<ActiveCameras>| {
-            let mut pipeline_camera_commands = HashMap::default();
             let pipelines = world.get_resource::<Assets<PipelineDescriptor>>().unwrap();
             let render_resource_context = &**world
                 .get_resource::<Box<dyn RenderResourceContext>>()
@@ -142,19 +225,30 @@ where
                     continue;
                 };
 
-                let visible_entities = if let Some(entity) = active_camera.entity {
-                    world.get::<VisibleEntities>(entity).unwrap()
+                let (visible_entities, clear_behavior) = if let Some(entity) = active_camera.entity
+                {
+                    let clear_behavior = if use_clear_behavior {
+                        world.get::<ClearBehavior>(entity).copied()
+                    } else {
+                        None
+                    };
+                    (
+                        world.get::<VisibleEntities>(entity).unwrap(),
+                        clear_behavior,
+                    )
                 } else {
                     continue;
                 };
+                let first_command = commands.len();
+                let mut pipeline_camera_commands = HashMap::default();
                 for visible_entity in visible_entities.iter() {
                     if query_state.get(world, visible_entity.entity).is_err() {
                         // visible entity does not match the Pass query
                         continue;
                     }
 
-                    let draw = if let Some(draw) = world.get::<Draw>(visible_entity.entity) {
-                        draw
+                    let draw = if let Some(draw) = world.get::<D>(visible_entity.entity) {
+                        draw.as_ref()
                     } else {
                         continue;
                     };
@@ -193,6 +287,10 @@ where
                         }
                     }
                 }
+                camera_passes.push(CameraPass {
+                    clear_behavior,
+                    command_count: commands.len() - first_command,
+                });
             }
         });
     }
@@ -205,11 +303,6 @@ where
         _output: &mut ResourceSlots,
     ) {
         for (i, color_attachment) in self.descriptor.color_attachments.iter_mut().enumerate() {
-            if self.default_clear_color_inputs.contains(&i) {
-                if let Some(default_clear_color) = world.get_resource::<ClearColor>() {
-                    color_attachment.ops.load = LoadOp::Clear(default_clear_color.0);
-                }
-            }
             if let Some(input_index) = self.color_attachment_input_indices[i] {
                 color_attachment.attachment =
                     TextureAttachment::Id(input.get(input_index).unwrap().get_texture().unwrap());
@@ -233,84 +326,100 @@ where
         let render_resource_bindings = world.get_resource::<RenderResourceBindings>().unwrap();
         let pipelines = world.get_resource::<Assets<PipelineDescriptor>>().unwrap();
 
-        let mut draw_state = DrawState::default();
-        let commands = &mut self.commands;
-        render_context.begin_pass(
-            &self.descriptor,
-            render_resource_bindings,
-            &mut |render_pass| {
-            for render_command in commands.drain(..) {
-                match render_command {
-                    RenderCommand::SetPipeline { pipeline } => {
-                        if draw_state.is_pipeline_set(pipeline.clone_weak()) {
-                            continue;
+        // the pass is still run without cameras, to clear its attachments
+        let mut camera_passes = std::mem::take(&mut self.camera_passes);
+        if camera_passes.is_empty() {
+            camera_passes.push(CameraPass {
+                clear_behavior: None,
+                command_count: 0,
+            });
+        }
+        let mut commands = std::mem::take(&mut self.commands);
+        let mut camera_commands = commands.drain(..);
+        for (pass_index, camera_pass) in camera_passes.iter().enumerate() {
+            self.set_load_ops(world, pass_index == 0, camera_pass.clear_behavior);
+            let mut draw_state = DrawState::default();
+            let mut pass_commands = camera_commands.by_ref().take(camera_pass.command_count);
+            render_context.begin_pass(
+                &self.descriptor,
+                render_resource_bindings,
+                &mut |render_pass| {
+                for render_command in pass_commands.by_ref() {
+                    match render_command {
+                        RenderCommand::SetPipeline { pipeline } => {
+                            if draw_state.is_pipeline_set(pipeline.clone_weak()) {
+                                continue;
+                            }
+                            render_pass.set_pipeline(&pipeline);
+                            let descriptor = pipelines.get(&pipeline).unwrap();
+                            draw_state.set_pipeline(&pipeline, descriptor);
                         }
-                        render_pass.set_pipeline(&pipeline);
-                        let descriptor = pipelines.get(&pipeline).unwrap();
-                        draw_state.set_pipeline(&pipeline, descriptor);
-                    }
-                    RenderCommand::DrawIndexed {
-                        base_vertex,
-                        indices,
-                        instances,
-                    } => {
-                        if draw_state.can_draw_indexed() {
-                            render_pass.draw_indexed(
-                                indices.clone(),
-                                base_vertex,
-                                instances.clone(),
-                            );
-                        } else {
-                            debug!("Could not draw indexed because the pipeline layout wasn't fully set for pipeline: {:?}", draw_state.pipeline);
+                        RenderCommand::DrawIndexed {
+                            base_vertex,
+                            indices,
+                            instances,
+                        } => {
+                            if draw_state.can_draw_indexed() {
+                                render_pass.draw_indexed(
+                                    indices.clone(),
+                                    base_vertex,
+                                    instances.clone(),
+                                );
+                            } else {
+                                debug!("Could not draw indexed because the pipeline layout wasn't fully set for pipeline: {:?}", draw_state.pipeline);
+                            }
                         }
-                    }
-                    RenderCommand::Draw { vertices, instances } => {
-                        if draw_state.can_draw() {
-                            render_pass.draw(vertices.clone(), instances.clone());
-                        } else {
-                            debug!("Could not draw because the pipeline layout wasn't fully set for pipeline: {:?}", draw_state.pipeline);
+                        RenderCommand::Draw { vertices, instances } => {
+                            if draw_state.can_draw() {
+                                render_pass.draw(vertices.clone(), instances.clone());
+                            } else {
+                                debug!("Could not draw because the pipeline layout wasn't fully set for pipeline: {:?}", draw_state.pipeline);
+                            }
                         }
-                    }
-                    RenderCommand::SetVertexBuffer {
-                        buffer,
-                        offset,
-                        slot,
-                    } => {
-                        if draw_state.is_vertex_buffer_set(slot, buffer, offset) {
-                            continue;
+                        RenderCommand::SetVertexBuffer {
+                            buffer,
+                            offset,
+                            slot,
+                        } => {
+                            if draw_state.is_vertex_buffer_set(slot, buffer, offset) {
+                                continue;
+                            }
+                            render_pass.set_vertex_buffer(slot, buffer, offset);
+                            draw_state.set_vertex_buffer(slot, buffer, offset);
                         }
-                        render_pass.set_vertex_buffer(slot, buffer, offset);
-                        draw_state.set_vertex_buffer(slot, buffer, offset);
-                    }
-                    RenderCommand::SetIndexBuffer { buffer, offset, index_format } => {
-                        if draw_state.is_index_buffer_set(buffer, offset, index_format) {
-                            continue;
-                        }
-                        render_pass.set_index_buffer(buffer, offset, index_format);
-                        draw_state.set_index_buffer(buffer, offset, index_format);
-                    }
-                    RenderCommand::SetBindGroup {
-                        index,
-                        bind_group,
-                        dynamic_uniform_indices,
-                    } => {
-                        if dynamic_uniform_indices.is_none() && draw_state.is_bind_group_set(index, bind_group) {
-                            continue;
+                        RenderCommand::SetIndexBuffer { buffer, offset, index_format } => {
+                            if draw_state.is_index_buffer_set(buffer, offset, index_format) {
+                                continue;
+                            }
+                            render_pass.set_index_buffer(buffer, offset, index_format);
+                            draw_state.set_index_buffer(buffer, offset, index_format);
                         }
-                        let pipeline = pipelines.get(draw_state.pipeline.as_ref().unwrap()).unwrap();
-                        let layout = pipeline.get_layout().unwrap();
-                        let bind_group_descriptor = layout.get_bind_group(index).unwrap();
-                        render_pass.set_bind_group(
+                        RenderCommand::SetBindGroup {
                             index,
-                            bind_group_descriptor.id,
                             bind_group,
-                            dynamic_uniform_indices.as_deref()
-                        );
-                        draw_state.set_bind_group(index, bind_group);
+                            dynamic_uniform_indices,
+                        } => {
+                            if dynamic_uniform_indices.is_none() && draw_state.is_bind_group_set(index, bind_group) {
+                                continue;
+                            }
+                            let pipeline = pipelines.get(draw_state.pipeline.as_ref().unwrap()).unwrap();
+                            let layout = pipeline.get_layout().unwrap();
+                            let bind_group_descriptor = layout.get_bind_group(index).unwrap();
+                            render_pass.set_bind_group(
+                                index,
+                                bind_group_descriptor.id,
+                                bind_group,
+                                dynamic_uniform_indices.as_deref()
+                            );
+                            draw_state.set_bind_group(index, bind_group);
+                        }
                     }
                 }
-            }
-        });
+            });
+        }
+        drop(camera_commands);
+        self.commands = commands;
+        self.camera_passes = camera_passes;
     }
 }
 
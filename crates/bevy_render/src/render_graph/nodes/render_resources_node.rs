@@ -435,7 +435,7 @@ fn render_resources_node_system<T: RenderResources + Component>(
     mut state: Local<RenderResourcesNodeState<Entity, T>>,
     mut entities_waiting_for_textures: Local<Vec<Entity>>,
     render_resource_context: Res<Box<dyn RenderResourceContext>>,
-    removed: RemovedComponents<T>,
+    mut removed: RemovedComponents<T>,
     mut queries: QuerySet<(
         QueryState<
             (Entity, &T, &Visible, &mut RenderPipelines),
@@ -619,7 +619,7 @@ fn asset_render_resources_node_system<T: RenderResources + Asset>(
     mut asset_events: EventReader<AssetEvent<T>>,
     mut asset_render_resource_bindings: ResMut<AssetRenderResourceBindings>,
     render_resource_context: Res<Box<dyn RenderResourceContext>>,
-    removed_handles: RemovedComponents<Handle<T>>,
+    mut removed_handles: RemovedComponents<Handle<T>>,
     mut queries: QuerySet<(
         QueryState<(&Handle<T>, &mut RenderPipelines), Changed<Handle<T>>>,
         QueryState<&mut RenderPipelines, With<Handle<T>>>,
@@ -0,0 +1,139 @@
+use crate::{camera::Camera, texture::Texture};
+use bevy_app::EventReader;
+use bevy_utils::tracing::{error, info};
+use bevy_window::WindowId;
+use std::{convert::TryInto, path::PathBuf};
+
+/// Queues screenshots of the frames rendered by cameras. Captured frames are sent as
+/// [ScreenshotCaptured] events once they have been read back from the GPU.
+///
+/// Only windows rendered offscreen (like the ones created by
+/// [HeadlessWindowPlugin](bevy_window::HeadlessWindowPlugin)) can be captured for now, as the
+/// swap chain of a native window can't be read from.
+#[derive(Debug, Default)]
+pub struct Screenshots {
+    requests: Vec<ScreenshotRequest>,
+}
+
+/// A pending request of [Screenshots].
+#[derive(Debug, Clone)]
+pub struct ScreenshotRequest {
+    pub window: WindowId,
+    /// Where to save the screenshot, if it should be saved to a file
+    pub path: Option<PathBuf>,
+}
+
+impl Screenshots {
+    /// Captures the next frame rendered by `camera`.
+    pub fn capture(&mut self, camera: &Camera) {
+        self.requests.push(ScreenshotRequest {
+            window: camera.window,
+            path: None,
+        });
+    }
+
+    /// Captures the next frame rendered by `camera` and saves it to `path`. The image format is
+    /// derived from the extension of `path`, and must be enabled as a feature (like `png`).
+    pub fn save_to_file(&mut self, camera: &Camera, path: impl Into<PathBuf>) {
+        self.requests.push(ScreenshotRequest {
+            window: camera.window,
+            path: Some(path.into()),
+        });
+    }
+
+    /// Takes all pending requests. This is called by the render backend after a frame is
+    /// rendered.
+    pub fn drain_requests(&mut self) -> impl Iterator<Item = ScreenshotRequest> + '_ {
+        self.requests.drain(..)
+    }
+}
+
+/// Sent when a screenshot requested through [Screenshots] has been captured.
+#[derive(Debug, Clone)]
+pub struct ScreenshotCaptured {
+    pub window: WindowId,
+    pub path: Option<PathBuf>,
+    pub texture: Texture,
+}
+
+pub fn save_screenshots_system(mut screenshot_captured_events: EventReader<ScreenshotCaptured>) {
+    for screenshot in screenshot_captured_events.iter() {
+        let path = if let Some(path) = &screenshot.path {
+            path
+        } else {
+            continue;
+        };
+        let image: Result<image::DynamicImage, _> = screenshot.texture.clone().try_into();
+        // swap chain formats are usually BGRA, which most encoders can't write
+        match image.map(|image| image.to_rgba8().save(path)) {
+            Ok(Ok(())) => info!("Saved screenshot to {:?}", path),
+            Ok(Err(err)) => error!("Failed to save screenshot to {:?}: {}", path, err),
+            Err(err) => error!("Failed to convert screenshot: {}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::texture::{Extent3d, TextureDimension, TextureFormat};
+    use bevy_app::Events;
+    use bevy_ecs::{
+        schedule::{Stage, SystemStage},
+        world::World,
+    };
+
+    #[test]
+    fn queue_screenshots() {
+        let mut screenshots = Screenshots::default();
+        let other_window = WindowId::new();
+        screenshots.capture(&Camera::default());
+        screenshots.save_to_file(
+            &Camera {
+                window: other_window,
+                ..Default::default()
+            },
+            "screenshot.png",
+        );
+
+        let requests = screenshots.drain_requests().collect::<Vec<_>>();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].window, WindowId::primary());
+        assert_eq!(requests[0].path, None);
+        assert_eq!(requests[1].window, other_window);
+        assert_eq!(requests[1].path, Some(PathBuf::from("screenshot.png")));
+        assert_eq!(screenshots.drain_requests().count(), 0);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn save_bgra_screenshot() {
+        let path = std::env::temp_dir().join("bevy_render_save_bgra_screenshot.png");
+        let mut world = World::default();
+        let mut events = Events::<ScreenshotCaptured>::default();
+        events.send(ScreenshotCaptured {
+            window: WindowId::primary(),
+            path: Some(path.clone()),
+            texture: Texture::new(
+                Extent3d::new(2, 1, 1),
+                TextureDimension::D2,
+                vec![255, 0, 0, 255, 0, 128, 255, 64],
+                TextureFormat::Bgra8UnormSrgb,
+            ),
+        });
+        // screenshots without a path are only sent as events
+        events.send(ScreenshotCaptured {
+            window: WindowId::primary(),
+            path: None,
+            texture: Texture::default(),
+        });
+        world.insert_resource(events);
+        SystemStage::single(save_screenshots_system).run(&mut world);
+
+        let image = image::open(&path).unwrap().to_rgba8();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(image.dimensions(), (2, 1));
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(1, 0).0, [255, 128, 0, 64]);
+    }
+}
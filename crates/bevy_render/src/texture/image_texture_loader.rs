@@ -1,7 +1,11 @@
-use super::texture::{ImageType, Texture, TextureError};
+use super::{
+    texture::{ImageType, Texture, TextureError},
+    AddressMode, FilterMode, SamplerDescriptor, TextureFormat,
+};
 use anyhow::Result;
 use bevy_asset::{AssetLoader, LoadContext, LoadedAsset};
 use bevy_utils::BoxedFuture;
+use serde::Deserialize;
 use thiserror::Error;
 
 /// Loader for images that can be read by the `image` crate.
@@ -33,7 +37,8 @@ impl AssetLoader for ImageTextureLoader {
             // use the file extension for the image type
             let ext = load_context.path().extension().unwrap().to_str().unwrap();
 
-            let dyn_img =
+            let settings: ImageTextureSettings = load_context.import_settings()?;
+            let mut dyn_img =
                 Texture::from_buffer(bytes, ImageType::Extension(ext)).map_err(|err| {
                     FileTextureError {
                         error: err,
@@ -41,6 +46,7 @@ impl AssetLoader for ImageTextureLoader {
                     }
                 })?;
 
+            settings.apply(&mut dyn_img);
             load_context.set_default_asset(LoadedAsset::new(dyn_img));
             Ok(())
         })
@@ -51,6 +57,51 @@ impl AssetLoader for ImageTextureLoader {
     }
 }
 
+/// The import settings of the images loaded by the [`ImageTextureLoader`], read from the `.meta`
+/// file next to an image, like a `normal_map.png.meta` file containing
+/// `(srgb: false, address_mode: Repeat)`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ImageTextureSettings {
+    /// Whether the image holds sRGB encoded colors. Images holding other data, like normal maps,
+    /// should be loaded as linear.
+    pub srgb: bool,
+    pub address_mode: AddressMode,
+    pub mag_filter: FilterMode,
+    pub min_filter: FilterMode,
+    pub mipmap_filter: FilterMode,
+}
+
+impl Default for ImageTextureSettings {
+    fn default() -> Self {
+        let sampler = SamplerDescriptor::default();
+        ImageTextureSettings {
+            srgb: true,
+            address_mode: sampler.address_mode_u,
+            mag_filter: sampler.mag_filter,
+            min_filter: sampler.min_filter,
+            mipmap_filter: sampler.mipmap_filter,
+        }
+    }
+}
+
+impl ImageTextureSettings {
+    /// Applies the settings to a texture loaded from an image.
+    pub fn apply(&self, texture: &mut Texture) {
+        if !self.srgb {
+            texture.format = match texture.format {
+                TextureFormat::Rgba8UnormSrgb => TextureFormat::Rgba8Unorm,
+                TextureFormat::Bgra8UnormSrgb => TextureFormat::Bgra8Unorm,
+                format => format,
+            };
+        }
+        texture.sampler.set_address_mode(self.address_mode);
+        texture.sampler.mag_filter = self.mag_filter;
+        texture.sampler.min_filter = self.min_filter;
+        texture.sampler.mipmap_filter = self.mipmap_filter;
+    }
+}
+
 /// An error that occurs when loading a texture from a file
 #[derive(Error, Debug)]
 pub struct FileTextureError {
@@ -77,4 +128,18 @@ mod tests {
             assert!(image::ImageFormat::from_extension(ext).is_some())
         }
     }
+
+    #[test]
+    fn test_image_texture_settings() {
+        let settings: ImageTextureSettings =
+            ron::de::from_str("(srgb: false, address_mode: Repeat)").unwrap();
+        let mut texture = Texture::default();
+        settings.apply(&mut texture);
+        assert_eq!(texture.format, TextureFormat::Rgba8Unorm);
+        assert_eq!(texture.sampler.address_mode_v, AddressMode::Repeat);
+        assert_eq!(
+            texture.sampler.min_filter,
+            SamplerDescriptor::default().min_filter
+        );
+    }
 }
@@ -4,7 +4,7 @@ use super::{Extent3d, SamplerDescriptor, TextureDescriptor, TextureDimension, Te
 use crate::renderer::{
     RenderResource, RenderResourceContext, RenderResourceId, RenderResourceType,
 };
-use bevy_asset::{AssetEvent, Assets, Handle};
+use bevy_asset::{diagnostic::AssetMemoryUsage, AssetEvent, Assets, Handle};
 use bevy_ecs::{event::EventReader, system::Res};
 use bevy_reflect::TypeUuid;
 use bevy_utils::HashSet;
@@ -247,6 +247,12 @@ impl Texture {
     }
 }
 
+impl AssetMemoryUsage for Texture {
+    fn memory_usage(&self) -> usize {
+        self.data.len()
+    }
+}
+
 impl RenderResource for Option<Handle<Texture>> {
     fn resource_type(&self) -> Option<RenderResourceType> {
         self.as_ref().map(|_texture| RenderResourceType::Texture)
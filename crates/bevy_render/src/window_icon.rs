@@ -0,0 +1,204 @@
+use crate::texture::{Texture, TextureFormat};
+use bevy_app::EventReader;
+use bevy_asset::{AssetEvent, Assets, Handle};
+use bevy_ecs::system::{Local, Res, ResMut};
+use bevy_utils::{tracing::warn, HashMap, HashSet};
+use bevy_window::{WindowIcon, WindowId, Windows};
+
+/// The textures used as the icons of windows, shown in their title bar and in the taskbar (or
+/// dock) of the desktop. An icon is set once its texture is loaded, and updated when the texture
+/// is modified. Windows without a texture here keep the icon of their
+/// [`WindowDescriptor`](bevy_window::WindowDescriptor).
+///
+/// ```
+/// # use bevy_asset::AssetServer;
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_render::window_icon::WindowIcons;
+/// # use bevy_window::WindowId;
+/// fn setup(asset_server: Res<AssetServer>, mut window_icons: ResMut<WindowIcons>) {
+///     window_icons.set(WindowId::primary(), asset_server.load("branding/icon.png"));
+/// }
+/// # setup.system();
+/// ```
+#[derive(Debug, Default)]
+pub struct WindowIcons {
+    icons: HashMap<WindowId, Handle<Texture>>,
+}
+
+impl WindowIcons {
+    pub fn set(&mut self, window: WindowId, texture: Handle<Texture>) {
+        self.icons.insert(window, texture);
+    }
+
+    /// Removes the icon of `window`, restoring its default icon.
+    pub fn remove(&mut self, window: WindowId) -> Option<Handle<Texture>> {
+        self.icons.remove(&window)
+    }
+
+    pub fn get(&self, window: WindowId) -> Option<&Handle<Texture>> {
+        self.icons.get(&window)
+    }
+}
+
+fn texture_to_icon(texture: &Texture) -> Option<WindowIcon> {
+    let rgba = match texture.format {
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => texture.data.clone(),
+        _ => texture.clone().convert(TextureFormat::Rgba8UnormSrgb)?.data,
+    };
+    Some(WindowIcon::new(
+        rgba,
+        texture.size.width,
+        texture.size.height,
+    ))
+}
+
+/// Sets the icons of [`WindowIcons`] on their windows.
+pub fn window_icon_system(
+    window_icons: Res<WindowIcons>,
+    textures: Res<Assets<Texture>>,
+    mut windows: ResMut<Windows>,
+    mut texture_events: EventReader<AssetEvent<Texture>>,
+    mut set_icons: Local<HashMap<WindowId, Handle<Texture>>>,
+) {
+    let mut changed_textures = HashSet::default();
+    for event in texture_events.iter() {
+        if let AssetEvent::Modified { handle } = event {
+            changed_textures.insert(handle.clone_weak());
+        }
+    }
+
+    // windows whose icon was removed go back to the default icon
+    let removed = set_icons
+        .keys()
+        .filter(|window_id| !window_icons.icons.contains_key(window_id))
+        .copied()
+        .collect::<Vec<_>>();
+    for window_id in removed {
+        set_icons.remove(&window_id);
+        if let Some(window) = windows.get_mut(window_id) {
+            window.set_icon(None);
+        }
+    }
+
+    for (window_id, handle) in window_icons.icons.iter() {
+        if set_icons.get(window_id) == Some(handle) && !changed_textures.contains(handle) {
+            continue;
+        }
+        let (window, texture) = match (windows.get_mut(*window_id), textures.get(handle)) {
+            (Some(window), Some(texture)) => (window, texture),
+            // wait for the window to be created and the texture to be loaded
+            _ => continue,
+        };
+        match texture_to_icon(texture) {
+            Some(icon) => window.set_icon(Some(icon)),
+            None => warn!(
+                "Can't use a texture of format {:?} as a window icon",
+                texture.format
+            ),
+        }
+        set_icons.insert(*window_id, handle.clone_weak());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::texture::{Extent3d, TextureDimension};
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin, HandleId};
+    use bevy_tasks::{IoTaskPool, TaskPool};
+    use bevy_window::{Window, WindowCommand, WindowDescriptor};
+
+    fn texture(pixel: &[u8], format: TextureFormat) -> Texture {
+        Texture::new_fill(Extent3d::new(2, 1, 1), TextureDimension::D2, pixel, format)
+    }
+
+    #[test]
+    fn texture_icons() {
+        let icon = texture_to_icon(&texture(&[1, 2, 3, 4], TextureFormat::Rgba8UnormSrgb)).unwrap();
+        assert_eq!(icon, WindowIcon::new(vec![1, 2, 3, 4, 1, 2, 3, 4], 2, 1));
+        let icon = texture_to_icon(&texture(&[1, 2, 3, 4], TextureFormat::Bgra8UnormSrgb)).unwrap();
+        assert_eq!(icon.rgba, vec![3, 2, 1, 4, 3, 2, 1, 4]);
+        assert!(texture_to_icon(&texture(&[0; 16], TextureFormat::Rgba32Float)).is_none());
+    }
+
+    #[test]
+    fn window_icons() {
+        let mut windows = Windows::default();
+        windows.add(Window::new(
+            WindowId::primary(),
+            &WindowDescriptor::default(),
+            800,
+            600,
+            1.0,
+            None,
+        ));
+        let mut app = App::new();
+        app.insert_resource(IoTaskPool(TaskPool::new()))
+            .add_plugin(AssetPlugin)
+            .add_asset::<Texture>()
+            .insert_resource(windows)
+            .init_resource::<WindowIcons>()
+            .add_system(window_icon_system);
+        let icon_commands = |app: &mut App| {
+            let mut windows = app.world.get_resource_mut::<Windows>().unwrap();
+            windows
+                .get_primary_mut()
+                .unwrap()
+                .drain_commands()
+                .filter_map(|command| match command {
+                    WindowCommand::SetIcon { icon } => Some(icon),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // set once the texture is loaded
+        let handle = Handle::weak(HandleId::random::<Texture>());
+        app.world
+            .get_resource_mut::<WindowIcons>()
+            .unwrap()
+            .set(WindowId::primary(), handle.clone());
+        app.update();
+        assert_eq!(icon_commands(&mut app), vec![]);
+        let mut textures = app.world.get_resource_mut::<Assets<Texture>>().unwrap();
+        // the returned strong handle keeps the texture loaded
+        let handle = textures.set(
+            handle,
+            texture(&[255, 0, 0, 255], TextureFormat::Rgba8UnormSrgb),
+        );
+        app.update();
+        assert_eq!(
+            icon_commands(&mut app),
+            vec![Some(WindowIcon::new(
+                vec![255, 0, 0, 255, 255, 0, 0, 255],
+                2,
+                1
+            ))]
+        );
+
+        // updated when the texture is modified
+        let mut textures = app.world.get_resource_mut::<Assets<Texture>>().unwrap();
+        textures.get_mut(&handle).unwrap().data[0] = 0;
+        // the modified event is sent at the end of the frame
+        app.update();
+        app.update();
+        assert_eq!(
+            icon_commands(&mut app),
+            vec![Some(WindowIcon::new(
+                vec![0, 0, 0, 255, 255, 0, 0, 255],
+                2,
+                1
+            ))]
+        );
+
+        app.world
+            .get_resource_mut::<WindowIcons>()
+            .unwrap()
+            .remove(WindowId::primary());
+        app.update();
+        assert_eq!(icon_commands(&mut app), vec![None]);
+        let windows = app.world.get_resource::<Windows>().unwrap();
+        assert_eq!(windows.get_primary().unwrap().icon(), None);
+    }
+}
@@ -1,43 +1,52 @@
 use bevy_asset::Handle;
 use bevy_ecs::{
     entity::Entity,
-    system::{Command, Commands},
+    system::{Command, Commands, EntityCommands},
     world::World,
 };
 use bevy_transform::hierarchy::ChildBuilder;
 
-use crate::{Scene, SceneSpawner};
+use crate::{DynamicScene, InstanceId, Scene, SceneSpawner};
 
 pub struct SpawnScene {
     scene_handle: Handle<Scene>,
+    instance_id: InstanceId,
 }
 
 impl Command for SpawnScene {
     fn write(self, world: &mut World) {
         let mut spawner = world.get_resource_mut::<SceneSpawner>().unwrap();
-        spawner.spawn(self.scene_handle);
+        spawner.spawn_with_id(self.scene_handle, self.instance_id, None);
     }
 }
 
 pub trait SpawnSceneCommands {
-    fn spawn_scene(&mut self, scene: Handle<Scene>);
+    /// Spawns an instance of the scene. The id of the instance can be used with the
+    /// [`SceneSpawner`] once the instance is spawned.
+    fn spawn_scene(&mut self, scene: Handle<Scene>) -> InstanceId;
 }
 
 impl<'w, 's> SpawnSceneCommands for Commands<'w, 's> {
-    fn spawn_scene(&mut self, scene_handle: Handle<Scene>) {
-        self.add(SpawnScene { scene_handle });
+    fn spawn_scene(&mut self, scene_handle: Handle<Scene>) -> InstanceId {
+        let instance_id = InstanceId::new();
+        self.add(SpawnScene {
+            scene_handle,
+            instance_id,
+        });
+        instance_id
     }
 }
 
 pub struct SpawnSceneAsChild {
     scene_handle: Handle<Scene>,
     parent: Entity,
+    instance_id: InstanceId,
 }
 
 impl Command for SpawnSceneAsChild {
     fn write(self, world: &mut World) {
         let mut spawner = world.get_resource_mut::<SceneSpawner>().unwrap();
-        spawner.spawn_as_child(self.scene_handle, self.parent);
+        spawner.spawn_with_id(self.scene_handle, self.instance_id, Some(self.parent));
     }
 }
 
@@ -50,7 +59,78 @@ impl<'w, 's, 'a> SpawnSceneAsChildCommands for ChildBuilder<'w, 's, 'a> {
         self.add_command(SpawnSceneAsChild {
             scene_handle,
             parent: self.parent_entity(),
+            instance_id: InstanceId::new(),
         });
         self
     }
 }
+
+pub struct SpawnDynamicSceneAsChild {
+    scene_handle: Handle<DynamicScene>,
+    parent: Entity,
+    instance_id: InstanceId,
+}
+
+impl Command for SpawnDynamicSceneAsChild {
+    fn write(self, world: &mut World) {
+        let mut spawner = world.get_resource_mut::<SceneSpawner>().unwrap();
+        spawner.spawn_dynamic_with_id(self.scene_handle, self.instance_id, Some(self.parent));
+    }
+}
+
+/// Spawns scenes as children of an entity.
+///
+/// ```
+/// # use bevy_asset::{AssetServer, Handle};
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_scene::{InstanceId, SceneSpawner, SpawnSceneEntityCommands};
+/// struct Level(InstanceId);
+///
+/// fn spawn_level(mut commands: Commands, asset_server: Res<AssetServer>) {
+///     let parent = commands.spawn().id();
+///     let instance_id = commands
+///         .entity(parent)
+///         .spawn_scene(asset_server.load("level.gltf#Scene0"));
+///     commands.insert_resource(Level(instance_id));
+/// }
+///
+/// fn find_player(scene_spawner: Res<SceneSpawner>, level: Res<Level>) {
+///     if let Some(entity_map) = scene_spawner.get_instance_entity_map(level.0) {
+///         // the level is spawned
+///     }
+/// }
+/// # spawn_level.system();
+/// # find_player.system();
+/// ```
+pub trait SpawnSceneEntityCommands {
+    /// Spawns an instance of the scene as a child of the entity, returning the id of the instance.
+    fn spawn_scene(&mut self, scene: Handle<Scene>) -> InstanceId;
+
+    /// Spawns an instance of the dynamic scene as a child of the entity, returning the id of the
+    /// instance.
+    fn spawn_dynamic_scene(&mut self, scene: Handle<DynamicScene>) -> InstanceId;
+}
+
+impl<'w, 's, 'a> SpawnSceneEntityCommands for EntityCommands<'w, 's, 'a> {
+    fn spawn_scene(&mut self, scene_handle: Handle<Scene>) -> InstanceId {
+        let instance_id = InstanceId::new();
+        let parent = self.id();
+        self.commands().add(SpawnSceneAsChild {
+            scene_handle,
+            parent,
+            instance_id,
+        });
+        instance_id
+    }
+
+    fn spawn_dynamic_scene(&mut self, scene_handle: Handle<DynamicScene>) -> InstanceId {
+        let instance_id = InstanceId::new();
+        let parent = self.id();
+        self.commands().add(SpawnDynamicSceneAsChild {
+            scene_handle,
+            parent,
+            instance_id,
+        });
+        instance_id
+    }
+}
@@ -1,8 +1,14 @@
-use crate::{serde::SceneSerializer, Scene, SceneSpawnError};
+use crate::{
+    serde::{read_asset_paths, serialize_binary_scene, BinarySceneError, SceneSerializer},
+    Scene, SceneFilter, SceneSpawnError,
+};
 use anyhow::Result;
+use bevy_asset::AssetPath;
 use bevy_ecs::{
+    archetype::Archetype,
+    component::Component,
     entity::EntityMap,
-    reflect::{ReflectComponent, ReflectMapEntities},
+    reflect::{ReflectComponent, ReflectMapEntities, ReflectResource},
     world::World,
 };
 use bevy_reflect::{Reflect, TypeRegistryArc, TypeUuid};
@@ -12,6 +18,9 @@ use serde::Serialize;
 #[uuid = "749479b1-fb8c-4ff8-a775-623aa76014f5"]
 pub struct DynamicScene {
     pub entities: Vec<Entity>,
+    /// The resources the scene inserts when it's spawned, like the lighting or the gravity of a
+    /// level
+    pub resources: Vec<Box<dyn Reflect>>,
 }
 
 pub struct Entity {
@@ -19,15 +28,65 @@ pub struct Entity {
     pub components: Vec<Box<dyn Reflect>>,
 }
 
+/// Spawns another [`DynamicScene`] as a child of its entity when the scene it's in is spawned, so
+/// that levels can be composed of reusable scenes. The referenced scene is spawned recursively,
+/// and its root entities are placed relative to the `Transform` of the entity, which overrides
+/// where the referenced scene is placed.
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct SceneReference {
+    /// The asset path of the referenced scene
+    pub path: String,
+}
+
+impl SceneReference {
+    pub fn new<S: Into<String>>(path: S) -> Self {
+        SceneReference { path: path.into() }
+    }
+}
+
 impl DynamicScene {
     pub fn from_scene(scene: &Scene, type_registry: &TypeRegistryArc) -> Self {
         Self::from_world(&scene.world, type_registry)
     }
 
     pub fn from_world(world: &World, type_registry: &TypeRegistryArc) -> Self {
+        Self::from_world_filtered(world, type_registry, &SceneFilter::default())
+    }
+
+    /// Creates a scene from a [`Scene`], with only the components the filter allows.
+    pub fn from_scene_filtered(
+        scene: &Scene,
+        type_registry: &TypeRegistryArc,
+        filter: &SceneFilter,
+    ) -> Self {
+        Self::from_world_filtered(&scene.world, type_registry, filter)
+    }
+
+    /// Creates a scene from a [`World`], with only the components the filter allows. Entities
+    /// are kept even if none of their components are allowed.
+    pub fn from_world_filtered(
+        world: &World,
+        type_registry: &TypeRegistryArc,
+        filter: &SceneFilter,
+    ) -> Self {
+        Self::from_world_archetypes(world, type_registry, filter, |_| true)
+    }
+
+    /// Creates a scene from the entities of the archetypes for which `archetype_filter` returns
+    /// `true`.
+    pub(crate) fn from_world_archetypes(
+        world: &World,
+        type_registry: &TypeRegistryArc,
+        filter: &SceneFilter,
+        archetype_filter: impl Fn(&Archetype) -> bool,
+    ) -> Self {
         let mut scene = DynamicScene::default();
         let type_registry = type_registry.read();
         for archetype in world.archetypes().iter() {
+            if !archetype_filter(archetype) {
+                continue;
+            }
             let entities_offset = scene.entities.len();
             for entity in archetype.entities() {
                 scene.entities.push(Entity {
@@ -40,7 +99,9 @@ impl DynamicScene {
                 let reflect_component = world
                     .components()
                     .get_info(component_id)
-                    .and_then(|info| type_registry.get(info.type_id().unwrap()))
+                    .and_then(|info| info.type_id())
+                    .filter(|type_id| filter.is_allowed_by_id(*type_id))
+                    .and_then(|type_id| type_registry.get(type_id))
                     .and_then(|registration| registration.data::<ReflectComponent>());
                 if let Some(reflect_component) = reflect_component {
                     for (i, entity) in archetype.entities().iter().enumerate() {
@@ -62,6 +123,18 @@ impl DynamicScene {
         &self,
         world: &mut World,
         entity_map: &mut EntityMap,
+    ) -> Result<(), SceneSpawnError> {
+        self.write_to_world_filtered(world, entity_map, |_, _| true)
+    }
+
+    /// Writes the scene to the world like [`DynamicScene::write_to_world`], but only the
+    /// components for which `filter` returns `true`, given the entity of the world and the type
+    /// name of the component.
+    pub fn write_to_world_filtered(
+        &self,
+        world: &mut World,
+        entity_map: &mut EntityMap,
+        filter: impl Fn(bevy_ecs::entity::Entity, &str) -> bool,
     ) -> Result<(), SceneSpawnError> {
         let registry = world.get_resource::<TypeRegistryArc>().unwrap().clone();
         let type_registry = registry.read();
@@ -70,6 +143,9 @@ impl DynamicScene {
                 .entry(bevy_ecs::entity::Entity::new(scene_entity.entity))
                 .or_insert_with(|| world.spawn().id());
             for component in scene_entity.components.iter() {
+                if !filter(entity, component.type_name()) {
+                    continue;
+                }
                 let registration = type_registry
                     .get_with_name(component.type_name())
                     .ok_or_else(|| SceneSpawnError::UnregisteredType {
@@ -92,6 +168,20 @@ impl DynamicScene {
             }
         }
 
+        for resource in self.resources.iter() {
+            let registration = type_registry
+                .get_with_name(resource.type_name())
+                .ok_or_else(|| SceneSpawnError::UnregisteredType {
+                    type_name: resource.type_name().to_string(),
+                })?;
+            let reflect_resource = registration.data::<ReflectResource>().ok_or_else(|| {
+                SceneSpawnError::UnregisteredResource {
+                    type_name: resource.type_name().to_string(),
+                }
+            })?;
+            reflect_resource.insert_resource(world, &**resource);
+        }
+
         for registration in type_registry.iter() {
             if let Some(map_entities_reflect) = registration.data::<ReflectMapEntities>() {
                 map_entities_reflect
@@ -103,10 +193,71 @@ impl DynamicScene {
         Ok(())
     }
 
+    /// Adds the resources of the world that are registered with `#[reflect(Resource)]` and that
+    /// the filter allows to the scene, replacing the resources it had.
+    pub fn with_resources(
+        mut self,
+        world: &World,
+        type_registry: &TypeRegistryArc,
+        filter: &SceneFilter,
+    ) -> Self {
+        let type_registry = type_registry.read();
+        self.resources = type_registry
+            .iter()
+            .filter(|registration| filter.is_allowed_by_id(registration.type_id()))
+            .filter_map(|registration| {
+                registration
+                    .data::<ReflectResource>()?
+                    .reflect_resource(world)
+                    .map(|resource| resource.clone_value())
+            })
+            .collect();
+        self
+    }
+
+    /// Replaces the handles that were serialized as asset paths, see
+    /// [`SceneSerializer::with_asset_server`], with handles to these assets, returning their
+    /// paths so that they can be loaded.
+    pub fn resolve_asset_paths(&mut self) -> Vec<AssetPath<'static>> {
+        let mut paths = Vec::new();
+        for component in self
+            .entities
+            .iter_mut()
+            .flat_map(|entity| entity.components.iter_mut())
+            .chain(self.resources.iter_mut())
+        {
+            read_asset_paths(&mut **component, &mut paths);
+        }
+        paths
+    }
+
+    /// The paths of the scenes referenced by the [`SceneReference`]s of the entities.
+    pub fn scene_references(&self) -> Vec<String> {
+        self.entities
+            .iter()
+            .flat_map(|entity| entity.components.iter())
+            .filter(|component| component.type_name() == std::any::type_name::<SceneReference>())
+            .map(|component| {
+                let mut reference = SceneReference::default();
+                reference.apply(&**component);
+                reference.path
+            })
+            .collect()
+    }
+
     // TODO: move to AssetSaver when it is implemented
     pub fn serialize_ron(&self, registry: &TypeRegistryArc) -> Result<String, ron::Error> {
         serialize_ron(SceneSerializer::new(self, registry))
     }
+
+    /// Serializes the scene in the binary format, see [`serialize_binary_scene`]. The
+    /// [`SceneLoader`](crate::SceneLoader) detects whether a scene is binary or RON.
+    pub fn serialize_binary(
+        &self,
+        registry: &TypeRegistryArc,
+    ) -> Result<Vec<u8>, BinarySceneError> {
+        serialize_binary_scene(SceneSerializer::new(self, registry))
+    }
 }
 
 pub fn serialize_ron<S>(serialize: S) -> Result<String, ron::Error>
@@ -122,3 +273,48 @@ where
     serialize.serialize(&mut ron_serializer)?;
     Ok(String::from_utf8(buf).unwrap())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::reflect::ReflectResource;
+
+    #[derive(Reflect, Default)]
+    #[reflect(Resource)]
+    struct Gravity(f32);
+
+    #[derive(Reflect, Default)]
+    #[reflect(Resource)]
+    struct FrameCount(u32);
+
+    fn world_with_registry() -> World {
+        let registry = TypeRegistryArc::default();
+        registry.write().register::<Gravity>();
+        registry.write().register::<FrameCount>();
+        let mut world = World::default();
+        world.insert_resource(registry);
+        world
+    }
+
+    #[test]
+    fn capture_resources() {
+        let mut world = world_with_registry();
+        world.insert_resource(Gravity(9.8));
+        world.insert_resource(FrameCount(42));
+        let registry = world.get_resource::<TypeRegistryArc>().unwrap().clone();
+        let scene = DynamicScene::from_world(&world, &registry).with_resources(
+            &world,
+            &registry,
+            &SceneFilter::allow_all().deny::<FrameCount>(),
+        );
+        assert_eq!(scene.resources.len(), 1);
+
+        let mut other_world = world_with_registry();
+        other_world.insert_resource(Gravity(1.6));
+        scene
+            .write_to_world(&mut other_world, &mut EntityMap::default())
+            .unwrap();
+        assert_eq!(other_world.get_resource::<Gravity>().unwrap().0, 9.8);
+        assert!(other_world.get_resource::<FrameCount>().is_none());
+    }
+}
@@ -1,20 +1,25 @@
 mod command;
 mod dynamic_scene;
+mod save_game;
 mod scene;
+mod scene_filter;
 mod scene_loader;
 mod scene_spawner;
 pub mod serde;
 
 pub use command::*;
 pub use dynamic_scene::*;
+pub use save_game::*;
 pub use scene::*;
+pub use scene_filter::*;
 pub use scene_loader::*;
 pub use scene_spawner::*;
 
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        DynamicScene, Scene, SceneSpawner, SpawnSceneAsChildCommands, SpawnSceneCommands,
+        DynamicScene, Scene, SceneReference, SceneSpawner, SpawnSceneAsChildCommands,
+        SpawnSceneCommands, SpawnSceneEntityCommands,
     };
 }
 
@@ -31,6 +36,8 @@ impl Plugin for ScenePlugin {
             .add_asset::<Scene>()
             .init_asset_loader::<SceneLoader>()
             .init_resource::<SceneSpawner>()
+            .register_type::<SceneReference>()
+            .register_type::<SaveId>()
             .add_system_to_stage(
                 CoreStage::PreUpdate,
                 scene_spawner_system.exclusive_system().at_end(),
@@ -0,0 +1,336 @@
+use crate::{
+    serde::{deserialize_binary_scene, serialize_binary_scene, BinarySceneError, SceneSerializer},
+    DynamicScene, SceneFilter, SceneSpawnError,
+};
+use bevy_asset::{AssetServer, HandleUntyped};
+use bevy_ecs::{
+    component::Component,
+    entity::{Entity, EntityMap},
+    reflect::ReflectComponent,
+    world::World,
+};
+use bevy_reflect::{Reflect, TypeRegistryArc};
+use bevy_utils::HashMap;
+use std::{any::TypeId, path::Path};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// The bytes save games start with.
+pub const SAVE_GAME_MAGIC: &[u8; 4] = b"BSAV";
+
+/// Marks the entities that are saved by [`SaveGame`], with an id that identifies the entity
+/// across saves and loads, unlike its [`Entity`].
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Component)]
+pub struct SaveId(pub u64);
+
+impl SaveId {
+    pub fn random() -> Self {
+        SaveId(Uuid::new_v4().as_u128() as u64)
+    }
+}
+
+/// The resource holding the assets referenced by the last loaded save game, so that they stay
+/// loaded while the loaded entities use them.
+#[derive(Debug, Default)]
+pub struct SavedGameAssets {
+    pub handles: Vec<HandleUntyped>,
+}
+
+#[derive(Error, Debug)]
+pub enum SaveGameError {
+    #[error("not a save game")]
+    NotSaveGame,
+    #[error("save game version {version} is newer than the latest supported version {latest}")]
+    UnsupportedVersion { version: u32, latest: u32 },
+    #[error("the world has no TypeRegistryArc resource")]
+    MissingTypeRegistry,
+    #[error(transparent)]
+    Scene(#[from] BinarySceneError),
+    #[error(transparent)]
+    Spawn(#[from] SceneSpawnError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// A save game that was loaded into a world.
+#[derive(Debug)]
+pub struct LoadedGame {
+    /// The version of the game the save was made with, to migrate older saves
+    pub version: u32,
+    /// The entities of the world, by entity the save was made with
+    pub entity_map: EntityMap,
+}
+
+/// Saves the entities with a [`SaveId`] and the chosen resources of a world, and loads them
+/// back later.
+///
+/// Loading a save matches the saved entities with the entities of the world by [`SaveId`]: the
+/// matching entities are updated, the missing ones are spawned, and the entities with a
+/// [`SaveId`] that aren't in the save are despawned. The components referencing saved entities
+/// are remapped to the loaded entities, and handles to assets loaded from a path are saved as
+/// their path.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_scene::{SaveGame, SceneFilter};
+/// # struct Score;
+/// fn save(world: &mut World) {
+///     let save_game =
+///         SaveGame::new(1).with_resource_filter(SceneFilter::deny_all().allow::<Score>());
+///     save_game.save_to_file(world, "save.bsav").unwrap();
+/// }
+/// # save.exclusive_system();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SaveGame {
+    /// The version of the game, stored in the saves. Saves of newer versions aren't loaded.
+    pub version: u32,
+    /// The components saved, [`SaveId`] is always saved
+    pub components: SceneFilter,
+    /// The resources saved, none by default
+    pub resources: SceneFilter,
+}
+
+impl SaveGame {
+    pub fn new(version: u32) -> Self {
+        SaveGame {
+            version,
+            components: SceneFilter::allow_all(),
+            resources: SceneFilter::deny_all(),
+        }
+    }
+
+    pub fn with_component_filter(mut self, components: SceneFilter) -> Self {
+        self.components = components;
+        self
+    }
+
+    pub fn with_resource_filter(mut self, resources: SceneFilter) -> Self {
+        self.resources = resources;
+        self
+    }
+
+    /// Snapshots the world: [`SAVE_GAME_MAGIC`], the version as little endian, then a binary
+    /// scene.
+    pub fn save(&self, world: &World) -> Result<Vec<u8>, SaveGameError> {
+        let type_registry = world
+            .get_resource::<TypeRegistryArc>()
+            .ok_or(SaveGameError::MissingTypeRegistry)?;
+        let save_id = world.components().get_id(TypeId::of::<SaveId>());
+        let components = self.components.clone().allow::<SaveId>();
+        let scene =
+            DynamicScene::from_world_archetypes(world, type_registry, &components, |archetype| {
+                save_id.map_or(false, |save_id| archetype.contains(save_id))
+            })
+            .with_resources(world, type_registry, &self.resources);
+
+        let mut serializer = SceneSerializer::new(&scene, type_registry);
+        if let Some(asset_server) = world.get_resource::<AssetServer>() {
+            serializer = serializer.with_asset_server(asset_server);
+        }
+        let mut bytes = SAVE_GAME_MAGIC.to_vec();
+        bytes.extend_from_slice(&self.version.to_le_bytes());
+        bytes.extend(serialize_binary_scene(serializer)?);
+        Ok(bytes)
+    }
+
+    pub fn save_to_file<P: AsRef<Path>>(
+        &self,
+        world: &World,
+        path: P,
+    ) -> Result<(), SaveGameError> {
+        let bytes = self.save(world)?;
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// Restores a snapshot made by [`SaveGame::save`]. The components of the matching entities
+    /// that aren't in the save are kept.
+    pub fn load(&self, world: &mut World, bytes: &[u8]) -> Result<LoadedGame, SaveGameError> {
+        if !bytes.starts_with(SAVE_GAME_MAGIC) || bytes.len() < SAVE_GAME_MAGIC.len() + 4 {
+            return Err(SaveGameError::NotSaveGame);
+        }
+        let body = &bytes[SAVE_GAME_MAGIC.len()..];
+        let version = u32::from_le_bytes([body[0], body[1], body[2], body[3]]);
+        if version > self.version {
+            return Err(SaveGameError::UnsupportedVersion {
+                version,
+                latest: self.version,
+            });
+        }
+        let type_registry = world
+            .get_resource::<TypeRegistryArc>()
+            .ok_or(SaveGameError::MissingTypeRegistry)?
+            .clone();
+        let mut scene = deserialize_binary_scene(&body[4..], &type_registry.read())?;
+
+        let asset_paths = scene.resolve_asset_paths();
+        if let Some(asset_server) = world.get_resource::<AssetServer>() {
+            let handles = asset_paths
+                .into_iter()
+                .map(|path| asset_server.load_untyped(path))
+                .collect();
+            world.insert_resource(SavedGameAssets { handles });
+        }
+
+        let mut query = world.query::<(Entity, &SaveId)>();
+        let mut unmatched = query
+            .iter(world)
+            .map(|(entity, save_id)| (*save_id, entity))
+            .collect::<HashMap<_, _>>();
+        let mut entity_map = EntityMap::default();
+        for scene_entity in scene.entities.iter() {
+            let save_id = scene_entity
+                .components
+                .iter()
+                .find(|component| component.type_name() == std::any::type_name::<SaveId>())
+                .map(|component| {
+                    let mut save_id = SaveId::default();
+                    save_id.apply(&**component);
+                    save_id
+                });
+            if let Some(entity) = save_id.and_then(|save_id| unmatched.remove(&save_id)) {
+                entity_map.insert(Entity::new(scene_entity.entity), entity);
+            }
+        }
+        // the entities spawned since the save was made
+        for (_, entity) in unmatched {
+            world.despawn(entity);
+        }
+
+        scene.write_to_world(world, &mut entity_map)?;
+        Ok(LoadedGame {
+            version,
+            entity_map,
+        })
+    }
+
+    pub fn load_from_file<P: AsRef<Path>>(
+        &self,
+        world: &mut World,
+        path: P,
+    ) -> Result<LoadedGame, SaveGameError> {
+        let bytes = std::fs::read(path)?;
+        self.load(world, &bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serde::BINARY_SCENE_VERSION;
+    use bevy_transform::prelude::Parent;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Health(u32);
+
+    fn world() -> World {
+        let registry = TypeRegistryArc::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<SaveId>();
+            registry.register::<Health>();
+            registry.register::<u32>();
+            registry.register::<u64>();
+            registry.register::<Parent>();
+            registry.register::<Entity>();
+        }
+        let mut world = World::default();
+        world.insert_resource(registry);
+        world
+    }
+
+    #[test]
+    fn save_game_round_trip() {
+        let mut world = world();
+        let entity = world.spawn().insert_bundle((SaveId(7), Health(3))).id();
+        let bytes = SaveGame::new(2).save(&world).unwrap();
+        assert_eq!(&bytes[..4], SAVE_GAME_MAGIC);
+        assert_eq!(&bytes[4..8], &2u32.to_le_bytes());
+
+        world.get_mut::<Health>(entity).unwrap().0 = 1;
+        let loaded = SaveGame::new(2).load(&mut world, &bytes).unwrap();
+        assert_eq!(loaded.version, 2);
+        assert_eq!(world.get::<Health>(entity).unwrap().0, 3);
+
+        // saves of older versions of the game are loaded, to be migrated
+        assert_eq!(
+            SaveGame::new(3).load(&mut world, &bytes).unwrap().version,
+            2
+        );
+    }
+
+    #[test]
+    fn load_by_save_id() {
+        let mut world = world();
+        let kept = world.spawn().insert_bundle((SaveId(1), Health(1))).id();
+        let despawned = world.spawn().insert_bundle((SaveId(2), Health(2))).id();
+        world
+            .spawn()
+            .insert_bundle((SaveId(3), Health(3), Parent(despawned)));
+        let bytes = SaveGame::new(1).save(&world).unwrap();
+
+        world.despawn(despawned);
+        let spawned_after_save = world.spawn().insert_bundle((SaveId(4), Health(4))).id();
+        let unsaved = world.spawn().insert(Health(5)).id();
+        world.get_mut::<Health>(kept).unwrap().0 = 6;
+
+        SaveGame::new(1).load(&mut world, &bytes).unwrap();
+        let mut query = world.query::<(Entity, &SaveId, &Health, Option<&Parent>)>();
+        let mut loaded = query
+            .iter(&world)
+            .map(|(entity, save_id, health, parent)| {
+                (save_id.0, (entity, health.0, parent.map(|parent| parent.0)))
+            })
+            .collect::<HashMap<_, _>>();
+        assert_eq!(loaded.len(), 3);
+        // matching entities are updated in place
+        assert_eq!(loaded[&1], (kept, 1, None));
+        // missing entities are respawned, and the references to them are remapped
+        let (respawned, health, _) = loaded.remove(&2).unwrap();
+        assert_ne!(respawned, despawned);
+        assert_eq!(health, 2);
+        assert_eq!(loaded[&3].2, Some(respawned));
+        // entities with a save id that aren't in the save are despawned, not the others
+        assert!(world.get_entity(spawned_after_save).is_none());
+        assert_eq!(world.get::<Health>(unsaved).unwrap().0, 5);
+    }
+
+    #[test]
+    fn save_game_header_rejected() {
+        let mut world = world();
+        world.spawn().insert_bundle((SaveId(7), Health(3)));
+        let bytes = SaveGame::new(2).save(&world).unwrap();
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[..4].copy_from_slice(b"BSCN");
+        assert!(matches!(
+            SaveGame::new(2).load(&mut world, &bad_magic),
+            Err(SaveGameError::NotSaveGame)
+        ));
+        assert!(matches!(
+            SaveGame::new(2).load(&mut world, &bytes[..6]),
+            Err(SaveGameError::NotSaveGame)
+        ));
+
+        assert!(matches!(
+            SaveGame::new(1).load(&mut world, &bytes),
+            Err(SaveGameError::UnsupportedVersion {
+                version: 2,
+                latest: 1
+            })
+        ));
+
+        // the scene in the save has its own version
+        for version in [0, BINARY_SCENE_VERSION + 1] {
+            let mut unsupported = bytes.clone();
+            unsupported[12..14].copy_from_slice(&version.to_le_bytes());
+            assert!(matches!(
+                SaveGame::new(2).load(&mut world, &unsupported),
+                Err(SaveGameError::Scene(BinarySceneError::UnsupportedVersion(v))) if v == version
+            ));
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use bevy_utils::HashSet;
+use std::any::{Any, TypeId};
+
+/// Chooses which component types are written to a [`DynamicScene`](crate::DynamicScene), so that
+/// runtime-only state, like handles, caches or `GlobalTransform`, isn't exported.
+///
+/// ```
+/// # use bevy_scene::SceneFilter;
+/// # struct GlobalTransform;
+/// # struct Transform;
+/// // everything but GlobalTransform
+/// let filter = SceneFilter::allow_all().deny::<GlobalTransform>();
+/// // only Transform
+/// let filter = SceneFilter::deny_all().allow::<Transform>();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SceneFilter {
+    /// Only the listed component types are written
+    Allowlist(HashSet<TypeId>),
+    /// Every component type is written but the listed ones
+    Denylist(HashSet<TypeId>),
+}
+
+impl Default for SceneFilter {
+    fn default() -> Self {
+        Self::allow_all()
+    }
+}
+
+impl SceneFilter {
+    pub fn allow_all() -> Self {
+        SceneFilter::Denylist(HashSet::default())
+    }
+
+    pub fn deny_all() -> Self {
+        SceneFilter::Allowlist(HashSet::default())
+    }
+
+    pub fn allow<T: Any>(self) -> Self {
+        self.allow_by_id(TypeId::of::<T>())
+    }
+
+    pub fn allow_by_id(mut self, type_id: TypeId) -> Self {
+        match &mut self {
+            SceneFilter::Allowlist(types) => {
+                types.insert(type_id);
+            }
+            SceneFilter::Denylist(types) => {
+                types.remove(&type_id);
+            }
+        }
+        self
+    }
+
+    pub fn deny<T: Any>(self) -> Self {
+        self.deny_by_id(TypeId::of::<T>())
+    }
+
+    pub fn deny_by_id(mut self, type_id: TypeId) -> Self {
+        match &mut self {
+            SceneFilter::Allowlist(types) => {
+                types.remove(&type_id);
+            }
+            SceneFilter::Denylist(types) => {
+                types.insert(type_id);
+            }
+        }
+        self
+    }
+
+    pub fn is_allowed<T: Any>(&self) -> bool {
+        self.is_allowed_by_id(TypeId::of::<T>())
+    }
+
+    pub fn is_allowed_by_id(&self, type_id: TypeId) -> bool {
+        match self {
+            SceneFilter::Allowlist(types) => types.contains(&type_id),
+            SceneFilter::Denylist(types) => !types.contains(&type_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DynamicScene;
+    use bevy_ecs::{component::Component, reflect::ReflectComponent, world::World};
+    use bevy_reflect::{Reflect, TypeRegistryArc};
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Health(u32);
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Cache(u32);
+
+    #[test]
+    fn allow_and_deny() {
+        let filter = SceneFilter::allow_all().deny::<Cache>();
+        assert!(filter.is_allowed::<Health>());
+        assert!(!filter.is_allowed::<Cache>());
+        assert!(filter.allow::<Cache>().is_allowed::<Cache>());
+
+        let filter = SceneFilter::deny_all().allow::<Health>();
+        assert!(filter.is_allowed::<Health>());
+        assert!(!filter.is_allowed::<Cache>());
+        assert!(!filter.deny::<Health>().is_allowed::<Health>());
+    }
+
+    #[test]
+    fn filtered_scene() {
+        let registry = TypeRegistryArc::default();
+        registry.write().register::<Health>();
+        registry.write().register::<Cache>();
+        let mut world = World::default();
+        world.spawn().insert_bundle((Health(1), Cache(1)));
+        world.spawn().insert(Cache(2));
+
+        let scene = DynamicScene::from_world_filtered(
+            &world,
+            &registry,
+            &SceneFilter::allow_all().deny::<Cache>(),
+        );
+        // entities without allowed components are kept
+        assert_eq!(scene.entities.len(), 2);
+        let type_names = scene
+            .entities
+            .iter()
+            .flat_map(|entity| entity.components.iter())
+            .map(|component| component.type_name())
+            .collect::<Vec<_>>();
+        assert_eq!(type_names, vec![std::any::type_name::<Health>()]);
+    }
+}
@@ -1,6 +1,6 @@
-use crate::serde::SceneDeserializer;
+use crate::serde::{deserialize_binary_scene, is_binary_scene, SceneDeserializer};
 use anyhow::Result;
-use bevy_asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy_asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset};
 use bevy_ecs::world::{FromWorld, World};
 use bevy_reflect::TypeRegistryArc;
 use bevy_utils::BoxedFuture;
@@ -27,17 +27,30 @@ impl AssetLoader for SceneLoader {
         load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<()>> {
         Box::pin(async move {
-            let mut deserializer = ron::de::Deserializer::from_bytes(bytes)?;
-            let scene_deserializer = SceneDeserializer {
-                type_registry: &*self.type_registry.read(),
+            let mut scene = if is_binary_scene(bytes) {
+                deserialize_binary_scene(bytes, &*self.type_registry.read())?
+            } else {
+                let mut deserializer = ron::de::Deserializer::from_bytes(bytes)?;
+                let scene_deserializer = SceneDeserializer {
+                    type_registry: &*self.type_registry.read(),
+                };
+                scene_deserializer.deserialize(&mut deserializer)?
             };
-            let scene = scene_deserializer.deserialize(&mut deserializer)?;
-            load_context.set_default_asset(LoadedAsset::new(scene));
+            // the referenced scenes and assets are loaded with the scene, so that it's only loaded
+            // once they can be spawned
+            let mut dependencies = scene.resolve_asset_paths();
+            dependencies.extend(
+                scene
+                    .scene_references()
+                    .into_iter()
+                    .map(|path| AssetPath::from(path.as_str()).to_owned()),
+            );
+            load_context.set_default_asset(LoadedAsset::new(scene).with_dependencies(dependencies));
             Ok(())
         })
     }
 
     fn extensions(&self) -> &[&str] {
-        &["scn", "scn.ron"]
+        &["scn", "scn.ron", "scn.bin"]
     }
 }
@@ -1,41 +1,95 @@
-use crate::{DynamicScene, Scene};
+use crate::{DynamicScene, Scene, SceneReference};
 use bevy_app::{Events, ManualEventReader};
-use bevy_asset::{AssetEvent, Assets, Handle};
+use bevy_asset::{AssetEvent, AssetServer, Assets, Handle, HandleId};
 use bevy_ecs::{
+    component::Component,
     entity::{Entity, EntityMap},
     reflect::{ReflectComponent, ReflectMapEntities},
     world::{Mut, World},
 };
 use bevy_reflect::TypeRegistryArc;
 use bevy_transform::prelude::Parent;
-use bevy_utils::{tracing::error, HashMap};
+use bevy_utils::{tracing::error, HashMap, HashSet};
 use thiserror::Error;
 use uuid::Uuid;
 
 #[derive(Debug)]
 struct InstanceInfo {
     entity_map: EntityMap,
+    /// The instances of the scenes referenced by the [`SceneReference`]s of the instance
+    nested_instances: Vec<InstanceId>,
+    /// The type names of the components that aren't updated from the scene, by entity
+    overrides: HashMap<Entity, HashSet<String>>,
+    /// The components the instance was spawned with from its scene
+    scene_components: SceneComponents,
+}
+
+/// The type names of the components of each entity of a scene, by entity of the scene.
+type SceneComponents = HashMap<Entity, HashSet<String>>;
+
+fn scene_components(scene: &Scene) -> SceneComponents {
+    let mut scene_components = SceneComponents::default();
+    for archetype in scene.world.archetypes().iter() {
+        for entity in archetype.entities() {
+            let components = scene_components
+                .entry(*entity)
+                .or_insert_with(HashSet::default);
+            for component_id in archetype.components() {
+                if let Some(component_info) = scene.world.components().get_info(component_id) {
+                    components.insert(component_info.name().to_string());
+                }
+            }
+        }
+    }
+    scene_components
+}
+
+fn dynamic_scene_components(scene: &DynamicScene) -> SceneComponents {
+    scene
+        .entities
+        .iter()
+        .map(|entity| {
+            let components = entity
+                .components
+                .iter()
+                .map(|component| component.type_name().to_string())
+                .collect();
+            (Entity::new(entity.entity), components)
+        })
+        .collect()
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct InstanceId(Uuid);
 
 impl InstanceId {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         InstanceId(Uuid::new_v4())
     }
 }
 
+/// Added to the entities of a prefab instance, spawned with [`SceneSpawner::spawn_prefab`], to
+/// link them to the scene they were spawned from.
+#[derive(Component, Debug, Clone)]
+pub struct PrefabInstance {
+    pub instance_id: InstanceId,
+    /// A weak handle to the scene of the prefab
+    pub scene: Handle<DynamicScene>,
+}
+
 #[derive(Default)]
 pub struct SceneSpawner {
     spawned_scenes: HashMap<Handle<Scene>, Vec<InstanceId>>,
     spawned_dynamic_scenes: HashMap<Handle<DynamicScene>, Vec<InstanceId>>,
     spawned_instances: HashMap<InstanceId, InstanceInfo>,
     scene_asset_event_reader: ManualEventReader<AssetEvent<DynamicScene>>,
-    dynamic_scenes_to_spawn: Vec<Handle<DynamicScene>>,
+    real_scene_asset_event_reader: ManualEventReader<AssetEvent<Scene>>,
+    /// With the scenes the instances are nested in, see [`SceneReference`]
+    dynamic_scenes_to_spawn: Vec<(Handle<DynamicScene>, InstanceId, Vec<HandleId>)>,
     scenes_to_spawn: Vec<(Handle<Scene>, InstanceId)>,
     scenes_to_despawn: Vec<Handle<DynamicScene>>,
     scenes_with_parent: Vec<(InstanceId, Entity)>,
+    prefab_instances: HashSet<InstanceId>,
 }
 
 #[derive(Error, Debug)]
@@ -44,6 +98,8 @@ pub enum SceneSpawnError {
     UnregisteredComponent { type_name: String },
     #[error("scene contains the unregistered type `{type_name}`. consider registering the type using `app.register_type::<T>()`")]
     UnregisteredType { type_name: String },
+    #[error("scene contains the unregistered resource `{type_name}`. consider adding `#[reflect(Resource)]` to your type")]
+    UnregisteredResource { type_name: String },
     #[error("scene does not exist")]
     NonExistentScene { handle: Handle<DynamicScene> },
     #[error("scene does not exist")]
@@ -51,23 +107,133 @@ pub enum SceneSpawnError {
 }
 
 impl SceneSpawner {
-    pub fn spawn_dynamic(&mut self, scene_handle: Handle<DynamicScene>) {
-        self.dynamic_scenes_to_spawn.push(scene_handle);
+    pub fn spawn_dynamic(&mut self, scene_handle: Handle<DynamicScene>) -> InstanceId {
+        let instance_id = InstanceId::new();
+        self.spawn_dynamic_with_id(scene_handle, instance_id, None);
+        instance_id
+    }
+
+    /// Queues an instance of a dynamic scene with an id that was already given out, like by
+    /// [`SpawnSceneEntityCommands::spawn_dynamic_scene`](crate::SpawnSceneEntityCommands).
+    pub(crate) fn spawn_dynamic_with_id(
+        &mut self,
+        scene_handle: Handle<DynamicScene>,
+        instance_id: InstanceId,
+        parent: Option<Entity>,
+    ) {
+        self.dynamic_scenes_to_spawn
+            .push((scene_handle, instance_id, Vec::new()));
+        if let Some(parent) = parent {
+            self.scenes_with_parent.push((instance_id, parent));
+        }
+    }
+
+    /// Spawns a prefab instance of a scene. Prefab instances are dynamic scene instances whose
+    /// entities have a [`PrefabInstance`] component linking them to their scene. When the scene
+    /// is modified, like when it's hot reloaded, the components of the instance are updated,
+    /// except those that are overridden with [`SceneSpawner::set_override`].
+    pub fn spawn_prefab(&mut self, scene_handle: Handle<DynamicScene>) -> InstanceId {
+        let instance_id = self.spawn_dynamic(scene_handle);
+        self.prefab_instances.insert(instance_id);
+        instance_id
+    }
+
+    /// Overrides the component `T` of an entity of an instance, so that it keeps its value when
+    /// the scene is modified. Returns `false` if the instance isn't spawned yet.
+    pub fn set_override<T: Component>(&mut self, instance_id: InstanceId, entity: Entity) -> bool {
+        self.set_override_by_name(instance_id, entity, std::any::type_name::<T>())
+    }
+
+    pub fn set_override_by_name(
+        &mut self,
+        instance_id: InstanceId,
+        entity: Entity,
+        type_name: &str,
+    ) -> bool {
+        match self.spawned_instances.get_mut(&instance_id) {
+            Some(instance) => {
+                instance
+                    .overrides
+                    .entry(entity)
+                    .or_insert_with(HashSet::default)
+                    .insert(type_name.to_string());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the override of the component `T` of an entity, so that it's updated again when the
+    /// scene is modified. Returns whether the component was overridden.
+    pub fn remove_override<T: Component>(
+        &mut self,
+        instance_id: InstanceId,
+        entity: Entity,
+    ) -> bool {
+        self.remove_override_by_name(instance_id, entity, std::any::type_name::<T>())
+    }
+
+    pub fn remove_override_by_name(
+        &mut self,
+        instance_id: InstanceId,
+        entity: Entity,
+        type_name: &str,
+    ) -> bool {
+        self.spawned_instances
+            .get_mut(&instance_id)
+            .and_then(|instance| instance.overrides.get_mut(&entity))
+            .map_or(false, |overrides| overrides.remove(type_name))
+    }
+
+    /// The type names of the overridden components of an entity of an instance.
+    pub fn get_overrides(&self, instance_id: InstanceId, entity: Entity) -> Vec<&str> {
+        self.spawned_instances
+            .get(&instance_id)
+            .and_then(|instance| instance.overrides.get(&entity))
+            .map_or_else(Vec::new, |overrides| {
+                overrides
+                    .iter()
+                    .map(|type_name| type_name.as_str())
+                    .collect()
+            })
+    }
+
+    pub fn spawn_dynamic_as_child(
+        &mut self,
+        scene_handle: Handle<DynamicScene>,
+        parent: Entity,
+    ) -> InstanceId {
+        let instance_id = InstanceId::new();
+        self.spawn_dynamic_with_id(scene_handle, instance_id, Some(parent));
+        instance_id
     }
 
     pub fn spawn(&mut self, scene_handle: Handle<Scene>) -> InstanceId {
         let instance_id = InstanceId::new();
-        self.scenes_to_spawn.push((scene_handle, instance_id));
+        self.spawn_with_id(scene_handle, instance_id, None);
         instance_id
     }
 
     pub fn spawn_as_child(&mut self, scene_handle: Handle<Scene>, parent: Entity) -> InstanceId {
         let instance_id = InstanceId::new();
-        self.scenes_to_spawn.push((scene_handle, instance_id));
-        self.scenes_with_parent.push((instance_id, parent));
+        self.spawn_with_id(scene_handle, instance_id, Some(parent));
         instance_id
     }
 
+    /// Queues an instance of a scene with an id that was already given out, like by
+    /// [`SpawnSceneCommands::spawn_scene`](crate::SpawnSceneCommands::spawn_scene).
+    pub(crate) fn spawn_with_id(
+        &mut self,
+        scene_handle: Handle<Scene>,
+        instance_id: InstanceId,
+        parent: Option<Entity>,
+    ) {
+        self.scenes_to_spawn.push((scene_handle, instance_id));
+        if let Some(parent) = parent {
+            self.scenes_with_parent.push((instance_id, parent));
+        }
+    }
+
     pub fn despawn(&mut self, scene_handle: Handle<DynamicScene>) {
         self.scenes_to_despawn.push(scene_handle);
     }
@@ -77,36 +243,110 @@ impl SceneSpawner {
         world: &mut World,
         scene_handle: Handle<DynamicScene>,
     ) -> Result<(), SceneSpawnError> {
-        if let Some(instance_ids) = self.spawned_dynamic_scenes.get(&scene_handle) {
+        if let Some(instance_ids) = self.spawned_dynamic_scenes.remove(&scene_handle) {
             for instance_id in instance_ids {
-                if let Some(instance) = self.spawned_instances.get(instance_id) {
-                    for entity in instance.entity_map.values() {
-                        let _ = world.despawn(entity); // Ignore the result, despawn only cares if
-                                                       // it exists.
-                    }
-                }
+                self.despawn_instance_sync(world, instance_id);
             }
-
-            self.spawned_dynamic_scenes.remove(&scene_handle);
         }
         Ok(())
     }
 
+    /// Despawns the entities of an instance and of the instances nested in it.
+    fn despawn_instance_sync(&mut self, world: &mut World, instance_id: InstanceId) {
+        // nested instances may not be spawned yet
+        self.dynamic_scenes_to_spawn
+            .retain(|(_, queued_instance_id, _)| *queued_instance_id != instance_id);
+        self.prefab_instances.remove(&instance_id);
+        if let Some(instance) = self.spawned_instances.remove(&instance_id) {
+            for entity in instance.entity_map.values() {
+                let _ = world.despawn(entity); // Ignore the result, despawn only cares if
+                                               // it exists.
+            }
+            for nested_instance_id in instance.nested_instances {
+                self.despawn_instance_sync(world, nested_instance_id);
+            }
+        }
+    }
+
     pub fn spawn_dynamic_sync(
         &mut self,
         world: &mut World,
         scene_handle: &Handle<DynamicScene>,
+    ) -> Result<(), SceneSpawnError> {
+        self.spawn_dynamic_sync_internal(world, scene_handle, InstanceId::new(), Vec::new())
+    }
+
+    /// Spawns an instance of the scene, then the scenes referenced by its [`SceneReference`]s as
+    /// children of their entity. `ancestors` are the scenes the instance is nested in, which
+    /// can't be referenced again.
+    fn spawn_dynamic_sync_internal(
+        &mut self,
+        world: &mut World,
+        scene_handle: &Handle<DynamicScene>,
+        instance_id: InstanceId,
+        mut ancestors: Vec<HandleId>,
     ) -> Result<(), SceneSpawnError> {
         let mut entity_map = EntityMap::default();
-        Self::spawn_dynamic_internal(world, scene_handle, &mut entity_map)?;
-        let instance_id = InstanceId::new();
-        self.spawned_instances
-            .insert(instance_id, InstanceInfo { entity_map });
+        let scene_components = Self::spawn_dynamic_internal(
+            world,
+            scene_handle,
+            &mut entity_map,
+            &HashMap::default(),
+        )?;
+        if self.prefab_instances.contains(&instance_id) {
+            Self::insert_prefab_instance(world, scene_handle, instance_id, &entity_map);
+        }
+        let references = entity_map
+            .values()
+            .filter_map(|entity| {
+                world
+                    .get::<SceneReference>(entity)
+                    .map(|reference| (entity, reference.path.clone()))
+            })
+            .collect::<Vec<_>>();
+        self.spawned_instances.insert(
+            instance_id,
+            InstanceInfo {
+                entity_map,
+                nested_instances: Vec::new(),
+                overrides: HashMap::default(),
+                scene_components,
+            },
+        );
         let spawned = self
             .spawned_dynamic_scenes
             .entry(scene_handle.clone())
             .or_insert_with(Vec::new);
         spawned.push(instance_id);
+
+        ancestors.push(scene_handle.id);
+        for (entity, path) in references {
+            let nested_handle: Handle<DynamicScene> = match world.get_resource::<AssetServer>() {
+                Some(asset_server) => asset_server.load(path.as_str()),
+                None => break,
+            };
+            if ancestors.contains(&nested_handle.id) {
+                error!("scene {} references itself, it isn't spawned again", path);
+                continue;
+            }
+            let nested_instance_id = InstanceId::new();
+            if let Some(instance) = self.spawned_instances.get_mut(&instance_id) {
+                instance.nested_instances.push(nested_instance_id);
+            }
+            self.scenes_with_parent.push((nested_instance_id, entity));
+            match self.spawn_dynamic_sync_internal(
+                world,
+                &nested_handle,
+                nested_instance_id,
+                ancestors.clone(),
+            ) {
+                Ok(_) => {}
+                Err(SceneSpawnError::NonExistentScene { .. }) => self
+                    .dynamic_scenes_to_spawn
+                    .push((nested_handle, nested_instance_id, ancestors.clone())),
+                Err(err) => return Err(err),
+            }
+        }
         Ok(())
     }
 
@@ -114,7 +354,8 @@ impl SceneSpawner {
         world: &mut World,
         scene_handle: &Handle<DynamicScene>,
         entity_map: &mut EntityMap,
-    ) -> Result<(), SceneSpawnError> {
+        overrides: &HashMap<Entity, HashSet<String>>,
+    ) -> Result<SceneComponents, SceneSpawnError> {
         world.resource_scope(|world, scenes: Mut<Assets<DynamicScene>>| {
             let scene =
                 scenes
@@ -122,10 +363,31 @@ impl SceneSpawner {
                     .ok_or_else(|| SceneSpawnError::NonExistentScene {
                         handle: scene_handle.clone_weak(),
                     })?;
-            scene.write_to_world(world, entity_map)
+            scene.write_to_world_filtered(world, entity_map, |entity, type_name| {
+                overrides
+                    .get(&entity)
+                    .map_or(true, |overrides| !overrides.contains(type_name))
+            })?;
+            Ok(dynamic_scene_components(scene))
         })
     }
 
+    fn insert_prefab_instance(
+        world: &mut World,
+        scene_handle: &Handle<DynamicScene>,
+        instance_id: InstanceId,
+        entity_map: &EntityMap,
+    ) {
+        for entity in entity_map.values() {
+            if let Some(mut entity_mut) = world.get_entity_mut(entity) {
+                entity_mut.insert(PrefabInstance {
+                    instance_id,
+                    scene: scene_handle.clone_weak(),
+                });
+            }
+        }
+    }
+
     pub fn spawn_sync(
         &mut self,
         world: &mut World,
@@ -142,9 +404,11 @@ impl SceneSpawner {
     ) -> Result<InstanceId, SceneSpawnError> {
         let mut instance_info = InstanceInfo {
             entity_map: EntityMap::default(),
+            nested_instances: Vec::new(),
+            overrides: HashMap::default(),
+            scene_components: HashMap::default(),
         };
         let type_registry = world.get_resource::<TypeRegistryArc>().unwrap().clone();
-        let type_registry = type_registry.read();
         world.resource_scope(|world, scenes: Mut<Assets<Scene>>| {
             let scene =
                 scenes
@@ -152,48 +416,14 @@ impl SceneSpawner {
                     .ok_or_else(|| SceneSpawnError::NonExistentRealScene {
                         handle: scene_handle.clone(),
                     })?;
-
-            for archetype in scene.world.archetypes().iter() {
-                for scene_entity in archetype.entities() {
-                    let entity = *instance_info
-                        .entity_map
-                        .entry(*scene_entity)
-                        .or_insert_with(|| world.spawn().id());
-                    for component_id in archetype.components() {
-                        let component_info = scene
-                            .world
-                            .components()
-                            .get_info(component_id)
-                            .expect("component_ids in archetypes should have ComponentInfo");
-
-                        let reflect_component = type_registry
-                            .get(component_info.type_id().unwrap())
-                            .ok_or_else(|| SceneSpawnError::UnregisteredType {
-                                type_name: component_info.name().to_string(),
-                            })
-                            .and_then(|registration| {
-                                registration.data::<ReflectComponent>().ok_or_else(|| {
-                                    SceneSpawnError::UnregisteredComponent {
-                                        type_name: component_info.name().to_string(),
-                                    }
-                                })
-                            })?;
-                        reflect_component.copy_component(
-                            &scene.world,
-                            world,
-                            *scene_entity,
-                            entity,
-                        );
-                    }
-                }
-            }
-            for registration in type_registry.iter() {
-                if let Some(map_entities_reflect) = registration.data::<ReflectMapEntities>() {
-                    map_entities_reflect
-                        .map_entities(world, &instance_info.entity_map)
-                        .unwrap();
-                }
-            }
+            Self::write_scene(
+                world,
+                scene,
+                &mut instance_info.entity_map,
+                &instance_info.overrides,
+                &type_registry,
+            )?;
+            instance_info.scene_components = scene_components(scene);
             self.spawned_instances.insert(instance_id, instance_info);
             let spawned = self
                 .spawned_scenes
@@ -204,6 +434,104 @@ impl SceneSpawner {
         })
     }
 
+    /// Copies the entities of the scene to the world, except the overridden components.
+    fn write_scene(
+        world: &mut World,
+        scene: &Scene,
+        entity_map: &mut EntityMap,
+        overrides: &HashMap<Entity, HashSet<String>>,
+        type_registry: &TypeRegistryArc,
+    ) -> Result<(), SceneSpawnError> {
+        let type_registry = type_registry.read();
+        for archetype in scene.world.archetypes().iter() {
+            for scene_entity in archetype.entities() {
+                let entity = *entity_map
+                    .entry(*scene_entity)
+                    .or_insert_with(|| world.spawn().id());
+                let overrides = overrides.get(&entity);
+                for component_id in archetype.components() {
+                    let component_info = scene
+                        .world
+                        .components()
+                        .get_info(component_id)
+                        .expect("component_ids in archetypes should have ComponentInfo");
+                    if overrides
+                        .map_or(false, |overrides| overrides.contains(component_info.name()))
+                    {
+                        continue;
+                    }
+
+                    let reflect_component = type_registry
+                        .get(component_info.type_id().unwrap())
+                        .ok_or_else(|| SceneSpawnError::UnregisteredType {
+                            type_name: component_info.name().to_string(),
+                        })
+                        .and_then(|registration| {
+                            registration.data::<ReflectComponent>().ok_or_else(|| {
+                                SceneSpawnError::UnregisteredComponent {
+                                    type_name: component_info.name().to_string(),
+                                }
+                            })
+                        })?;
+                    reflect_component.copy_component(&scene.world, world, *scene_entity, entity);
+                }
+            }
+        }
+        for registration in type_registry.iter() {
+            if let Some(map_entities_reflect) = registration.data::<ReflectMapEntities>() {
+                map_entities_reflect
+                    .map_entities(world, entity_map)
+                    .unwrap();
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the entities and components of an instance that were removed from its scene, given
+    /// the components the scene has now. Components that weren't spawned from the scene, like
+    /// those added at runtime, and overridden components are kept.
+    fn remove_stale_components(
+        world: &mut World,
+        instance_info: &mut InstanceInfo,
+        scene_components: SceneComponents,
+    ) {
+        let type_registry = world.get_resource::<TypeRegistryArc>().unwrap().clone();
+        let type_registry = type_registry.read();
+        for (scene_entity, previous_components) in instance_info.scene_components.iter() {
+            let entity = match instance_info.entity_map.get(*scene_entity) {
+                Ok(entity) => entity,
+                Err(_) => continue,
+            };
+            let components = match scene_components.get(scene_entity) {
+                Some(components) => components,
+                None => {
+                    let _ = world.despawn(entity);
+                    instance_info.entity_map.remove(*scene_entity);
+                    continue;
+                }
+            };
+            if world.get_entity(entity).is_none() {
+                continue;
+            }
+            let overrides = instance_info.overrides.get(&entity);
+            for type_name in previous_components.difference(components) {
+                if overrides.map_or(false, |overrides| overrides.contains(type_name)) {
+                    continue;
+                }
+                if let Some(reflect_component) = type_registry
+                    .get_with_name(type_name)
+                    .and_then(|registration| registration.data::<ReflectComponent>())
+                {
+                    reflect_component.remove_component(world, entity);
+                }
+            }
+        }
+        instance_info.scene_components = scene_components;
+    }
+
+    /// Updates the instances of dynamic scenes that were modified: components are added, updated
+    /// and removed to match the scene, except overridden components and components that were
+    /// added at runtime.
     pub fn update_spawned_scenes(
         &mut self,
         world: &mut World,
@@ -213,11 +541,58 @@ impl SceneSpawner {
             if let Some(spawned_instances) = self.spawned_dynamic_scenes.get(scene_handle) {
                 for instance_id in spawned_instances.iter() {
                     if let Some(instance_info) = self.spawned_instances.get_mut(instance_id) {
-                        Self::spawn_dynamic_internal(
+                        let scene_components = Self::spawn_dynamic_internal(
                             world,
                             scene_handle,
                             &mut instance_info.entity_map,
+                            &instance_info.overrides,
                         )?;
+                        Self::remove_stale_components(world, instance_info, scene_components);
+                        // entities added to the scene are linked to it too
+                        if self.prefab_instances.contains(instance_id) {
+                            Self::insert_prefab_instance(
+                                world,
+                                scene_handle,
+                                *instance_id,
+                                &instance_info.entity_map,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Updates the instances of scenes that were modified, like
+    /// [`SceneSpawner::update_spawned_scenes`] does for dynamic scenes.
+    pub fn update_spawned_real_scenes(
+        &mut self,
+        world: &mut World,
+        scene_handles: &[Handle<Scene>],
+    ) -> Result<(), SceneSpawnError> {
+        let type_registry = world.get_resource::<TypeRegistryArc>().unwrap().clone();
+        for scene_handle in scene_handles {
+            if let Some(spawned_instances) = self.spawned_scenes.get(scene_handle) {
+                for instance_id in spawned_instances.iter() {
+                    if let Some(instance_info) = self.spawned_instances.get_mut(instance_id) {
+                        let scene_components =
+                            world.resource_scope(|world, scenes: Mut<Assets<Scene>>| {
+                                let scene = scenes.get(scene_handle).ok_or_else(|| {
+                                    SceneSpawnError::NonExistentRealScene {
+                                        handle: scene_handle.clone_weak(),
+                                    }
+                                })?;
+                                Self::write_scene(
+                                    world,
+                                    scene,
+                                    &mut instance_info.entity_map,
+                                    &instance_info.overrides,
+                                    &type_registry,
+                                )?;
+                                Ok::<_, SceneSpawnError>(scene_components(scene))
+                            })?;
+                        Self::remove_stale_components(world, instance_info, scene_components);
                     }
                 }
             }
@@ -237,12 +612,17 @@ impl SceneSpawner {
     pub fn spawn_queued_scenes(&mut self, world: &mut World) -> Result<(), SceneSpawnError> {
         let scenes_to_spawn = std::mem::take(&mut self.dynamic_scenes_to_spawn);
 
-        for scene_handle in scenes_to_spawn {
-            match self.spawn_dynamic_sync(world, &scene_handle) {
+        for (scene_handle, instance_id, ancestors) in scenes_to_spawn {
+            match self.spawn_dynamic_sync_internal(
+                world,
+                &scene_handle,
+                instance_id,
+                ancestors.clone(),
+            ) {
                 Ok(_) => {}
-                Err(SceneSpawnError::NonExistentScene { .. }) => {
-                    self.dynamic_scenes_to_spawn.push(scene_handle)
-                }
+                Err(SceneSpawnError::NonExistentScene { .. }) => self
+                    .dynamic_scenes_to_spawn
+                    .push((scene_handle, instance_id, ancestors)),
                 Err(err) => return Err(err),
             }
         }
@@ -285,6 +665,25 @@ impl SceneSpawner {
         self.spawned_instances.contains_key(&instance_id)
     }
 
+    /// The entities of an instance, by entity of its scene, once it's spawned. The entities of a
+    /// [`DynamicScene`] are identified by `Entity::new(id)`.
+    pub fn get_instance_entity_map(&self, instance_id: InstanceId) -> Option<&EntityMap> {
+        self.spawned_instances
+            .get(&instance_id)
+            .map(|instance| &instance.entity_map)
+    }
+
+    /// The entity an entity of the scene was spawned as in an instance, once it's spawned.
+    pub fn get_instance_entity(
+        &self,
+        instance_id: InstanceId,
+        scene_entity: Entity,
+    ) -> Option<Entity> {
+        self.spawned_instances
+            .get(&instance_id)
+            .and_then(|instance| instance.entity_map.get(scene_entity).ok())
+    }
+
     /// Get an iterator over the entities in an instance, once it's spawned
     pub fn iter_instance_entities(
         &'_ self,
@@ -303,6 +702,7 @@ pub fn scene_spawner_system(world: &mut World) {
             .unwrap();
 
         let mut updated_spawned_scenes = Vec::new();
+        let mut updated_spawned_real_scenes = Vec::new();
         for event in scene_spawner
             .scene_asset_event_reader
             .iter(scene_asset_events)
@@ -313,6 +713,17 @@ pub fn scene_spawner_system(world: &mut World) {
                 }
             }
         }
+        let real_scene_asset_events = world.get_resource::<Events<AssetEvent<Scene>>>().unwrap();
+        for event in scene_spawner
+            .real_scene_asset_event_reader
+            .iter(real_scene_asset_events)
+        {
+            if let AssetEvent::Modified { handle } = event {
+                if scene_spawner.spawned_scenes.contains_key(handle) {
+                    updated_spawned_real_scenes.push(handle.clone_weak());
+                }
+            }
+        }
 
         scene_spawner.despawn_queued_scenes(world).unwrap();
         scene_spawner
@@ -321,6 +732,218 @@ pub fn scene_spawner_system(world: &mut World) {
         scene_spawner
             .update_spawned_scenes(world, &updated_spawned_scenes)
             .unwrap();
+        scene_spawner
+            .update_spawned_real_scenes(world, &updated_spawned_real_scenes)
+            .unwrap();
         scene_spawner.set_scene_instance_parent_sync(world);
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ScenePlugin, SpawnSceneEntityCommands};
+    use bevy_app::App;
+    use bevy_asset::{AssetPath, AssetPlugin};
+    use bevy_ecs::system::{CommandQueue, Commands};
+    use bevy_reflect::Reflect;
+    use bevy_tasks::{IoTaskPool, TaskPool};
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Health(u32);
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Armor(u32);
+
+    #[derive(Component)]
+    struct Selected;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.insert_resource(IoTaskPool(TaskPool::new()))
+            .add_plugin(AssetPlugin)
+            .add_plugin(ScenePlugin)
+            .register_type::<Health>()
+            .register_type::<Armor>();
+        app
+    }
+
+    /// A scene with an entity with each of the component lists, identified by their index.
+    fn scene(entities: Vec<Vec<Box<dyn Reflect>>>) -> DynamicScene {
+        DynamicScene {
+            entities: entities
+                .into_iter()
+                .enumerate()
+                .map(|(entity, components)| crate::Entity {
+                    entity: entity as u32,
+                    components,
+                })
+                .collect(),
+            resources: Vec::new(),
+        }
+    }
+
+    /// Adds the scene as the asset loaded from `path`.
+    fn add_scene(app: &mut App, path: &str, scene: DynamicScene) -> Handle<DynamicScene> {
+        app.world
+            .get_resource_mut::<Assets<DynamicScene>>()
+            .unwrap()
+            .set(AssetPath::from(path), scene)
+    }
+
+    fn spawner(app: &App) -> &SceneSpawner {
+        app.world.get_resource::<SceneSpawner>().unwrap()
+    }
+
+    fn spawner_mut(app: &mut App) -> Mut<'_, SceneSpawner> {
+        app.world.get_resource_mut::<SceneSpawner>().unwrap()
+    }
+
+    #[test]
+    fn spawn_scene_references() {
+        let mut app = app();
+        let level = add_scene(
+            &mut app,
+            "level.scn.ron",
+            scene(vec![vec![Box::new(SceneReference::new("room.scn.ron"))]]),
+        );
+        add_scene(
+            &mut app,
+            "room.scn.ron",
+            scene(vec![
+                vec![Box::new(Health(1))],
+                vec![Box::new(SceneReference::new("level.scn.ron"))],
+            ]),
+        );
+        let instance_id = spawner_mut(&mut app).spawn_dynamic(level);
+        app.update();
+
+        let room_entity = spawner(&app)
+            .get_instance_entity(instance_id, Entity::new(0))
+            .unwrap();
+        let mut query = app.world.query::<(&Health, &Parent)>();
+        let nested = query.iter(&app.world).collect::<Vec<_>>();
+        assert_eq!(nested.len(), 1);
+        assert_eq!(nested[0].0 .0, 1);
+        assert_eq!(nested[0].1 .0, room_entity);
+
+        // the room references the level it's in, which isn't spawned again
+        let mut query = app.world.query::<&SceneReference>();
+        assert_eq!(query.iter(&app.world).count(), 2);
+    }
+
+    #[test]
+    fn prefab_keeps_overrides_on_reload() {
+        let mut app = app();
+        let prefab = add_scene(
+            &mut app,
+            "prefab.scn.ron",
+            scene(vec![vec![Box::new(Health(1)), Box::new(Armor(1))]]),
+        );
+        let instance_id = spawner_mut(&mut app).spawn_prefab(prefab.clone());
+        app.update();
+
+        let entity = spawner(&app)
+            .get_instance_entity(instance_id, Entity::new(0))
+            .unwrap();
+        let prefab_instance = app.world.get::<PrefabInstance>(entity).unwrap();
+        assert_eq!(prefab_instance.instance_id, instance_id);
+        assert_eq!(prefab_instance.scene, prefab);
+        app.world.get_mut::<Health>(entity).unwrap().0 = 5;
+        assert!(spawner_mut(&mut app).set_override::<Health>(instance_id, entity));
+        assert_eq!(
+            spawner(&app).get_overrides(instance_id, entity),
+            vec![std::any::type_name::<Health>()]
+        );
+
+        add_scene(
+            &mut app,
+            "prefab.scn.ron",
+            scene(vec![vec![Box::new(Health(2)), Box::new(Armor(2))]]),
+        );
+        // asset events are sent at the end of the frame, for the spawner to read the next one
+        app.update();
+        app.update();
+        assert_eq!(app.world.get::<Health>(entity).unwrap().0, 5);
+        assert_eq!(app.world.get::<Armor>(entity).unwrap().0, 2);
+
+        // without its override, the component is updated again
+        assert!(spawner_mut(&mut app).remove_override::<Health>(instance_id, entity));
+        add_scene(
+            &mut app,
+            "prefab.scn.ron",
+            scene(vec![vec![Box::new(Health(3)), Box::new(Armor(3))]]),
+        );
+        app.update();
+        app.update();
+        assert_eq!(app.world.get::<Health>(entity).unwrap().0, 3);
+    }
+
+    #[test]
+    fn patch_instances_on_reload() {
+        let mut app = app();
+        let level = add_scene(
+            &mut app,
+            "level.scn.ron",
+            scene(vec![
+                vec![Box::new(Health(1)), Box::new(Armor(1))],
+                vec![Box::new(Health(1))],
+            ]),
+        );
+        let instance_id = spawner_mut(&mut app).spawn_dynamic(level);
+        app.update();
+        let instance_entity =
+            |app: &App, entity| spawner(app).get_instance_entity(instance_id, Entity::new(entity));
+        let first = instance_entity(&app, 0).unwrap();
+        let removed = instance_entity(&app, 1).unwrap();
+        app.world.entity_mut(first).insert(Selected);
+
+        let mut level = scene(vec![vec![Box::new(Health(2))], vec![Box::new(Health(3))]]);
+        level.entities[1].entity = 2;
+        add_scene(&mut app, "level.scn.ron", level);
+        app.update();
+        app.update();
+
+        assert_eq!(instance_entity(&app, 0), Some(first));
+        assert_eq!(app.world.get::<Health>(first).unwrap().0, 2);
+        assert!(app.world.get::<Armor>(first).is_none());
+        // components added at runtime are kept
+        assert!(app.world.get::<Selected>(first).is_some());
+
+        assert!(instance_entity(&app, 1).is_none());
+        assert!(app.world.get_entity(removed).is_none());
+
+        let added = instance_entity(&app, 2).unwrap();
+        assert_eq!(app.world.get::<Health>(added).unwrap().0, 3);
+    }
+
+    #[test]
+    fn spawn_scene_as_child() {
+        let mut app = app();
+        let room = add_scene(
+            &mut app,
+            "room.scn.ron",
+            scene(vec![vec![Box::new(Health(1))], vec![Box::new(Armor(1))]]),
+        );
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &app.world);
+        let parent = commands.spawn().id();
+        let instance_id = commands.entity(parent).spawn_dynamic_scene(room);
+        queue.apply(&mut app.world);
+        assert!(!spawner(&app).instance_is_ready(instance_id));
+
+        app.update();
+        assert!(spawner(&app).instance_is_ready(instance_id));
+        let entity_map = spawner(&app).get_instance_entity_map(instance_id).unwrap();
+        assert_eq!(entity_map.keys().count(), 2);
+        let health = entity_map.get(Entity::new(0)).unwrap();
+        let armor = entity_map.get(Entity::new(1)).unwrap();
+        assert_eq!(app.world.get::<Health>(health).unwrap().0, 1);
+        assert_eq!(app.world.get::<Armor>(armor).unwrap().0, 1);
+        for entity in [health, armor] {
+            assert_eq!(app.world.get::<Parent>(entity).unwrap().0, parent);
+        }
+    }
+}
@@ -1,36 +1,235 @@
 use crate::{DynamicScene, Entity};
 use anyhow::Result;
+use bevy_asset::{AssetPath, AssetServer, Handle, HandleId};
 use bevy_reflect::{
     serde::{ReflectDeserializer, ReflectSerializer},
-    Reflect, TypeRegistry, TypeRegistryArc,
+    DynamicStruct, Reflect, ReflectMut, Struct, TypeRegistry, TypeRegistryArc,
 };
 use serde::{
     de::{DeserializeSeed, Error, MapAccess, SeqAccess, Visitor},
-    ser::{SerializeSeq, SerializeStruct},
+    ser::{SerializeMap, SerializeSeq, SerializeStruct},
     Deserialize, Serialize,
 };
+use thiserror::Error;
+
+/// The bytes scenes in the binary format start with, so that the format can be detected.
+pub const BINARY_SCENE_MAGIC: &[u8; 4] = b"BSCN";
+/// The version of the binary scene format, written after [`BINARY_SCENE_MAGIC`]. Scenes of
+/// version 1 have no resources.
+pub const BINARY_SCENE_VERSION: u16 = 2;
+
+#[derive(Error, Debug)]
+pub enum BinarySceneError {
+    #[error("not a binary scene")]
+    NotBinaryScene,
+    #[error(
+        "binary scene version {0} is not supported, the latest version is {}",
+        BINARY_SCENE_VERSION
+    )]
+    UnsupportedVersion(u16),
+    #[error("failed to serialize the scene: {0}")]
+    Serialize(#[from] rmp_serde::encode::Error),
+    #[error("failed to deserialize the scene: {0}")]
+    Deserialize(#[from] rmp_serde::decode::Error),
+}
+
+/// Whether the bytes are a scene in the binary format, rather than RON.
+pub fn is_binary_scene(bytes: &[u8]) -> bool {
+    bytes.starts_with(BINARY_SCENE_MAGIC)
+}
+
+/// Serializes a scene in the binary format: [`BINARY_SCENE_MAGIC`], [`BINARY_SCENE_VERSION`] as
+/// little endian, then the scene as MessagePack. It's smaller and faster to load than RON, for
+/// shipping scenes that are authored in RON.
+pub fn serialize_binary_scene<S>(serialize: S) -> Result<Vec<u8>, BinarySceneError>
+where
+    S: Serialize,
+{
+    let mut bytes = BINARY_SCENE_MAGIC.to_vec();
+    bytes.extend_from_slice(&BINARY_SCENE_VERSION.to_le_bytes());
+    rmp_serde::encode::write(&mut bytes, &serialize)?;
+    Ok(bytes)
+}
+
+/// Deserializes a scene serialized with [`serialize_binary_scene`].
+pub fn deserialize_binary_scene(
+    bytes: &[u8],
+    type_registry: &TypeRegistry,
+) -> Result<DynamicScene, BinarySceneError> {
+    if !is_binary_scene(bytes) || bytes.len() < BINARY_SCENE_MAGIC.len() + 2 {
+        return Err(BinarySceneError::NotBinaryScene);
+    }
+    let body = &bytes[BINARY_SCENE_MAGIC.len()..];
+    let version = u16::from_le_bytes([body[0], body[1]]);
+    if version == 0 || version > BINARY_SCENE_VERSION {
+        return Err(BinarySceneError::UnsupportedVersion(version));
+    }
+    let mut deserializer = rmp_serde::Deserializer::new(&body[2..]);
+    let scene = SceneDeserializer { type_registry }.deserialize(&mut deserializer)?;
+    Ok(scene)
+}
+
+/// The field of the id of a reflected [`Handle`]
+const HANDLE_FIELD_ID: &str = "id";
+
+fn is_handle(value: &DynamicStruct) -> bool {
+    let handle_type_name = std::any::type_name::<Handle<DynamicScene>>();
+    let prefix = &handle_type_name[..=handle_type_name.find('<').unwrap()];
+    value.name().starts_with(prefix)
+}
+
+/// Calls `f` with the handles of `value`, at any depth, once cloned by `Reflect::clone_value`.
+fn visit_handles(value: &mut dyn Reflect, f: &mut dyn FnMut(&mut DynamicStruct)) {
+    if let Some(value) = value.downcast_mut::<DynamicStruct>() {
+        if is_handle(value) {
+            f(value);
+            return;
+        }
+    }
+    match value.reflect_mut() {
+        ReflectMut::Struct(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_at_mut(index) {
+                    visit_handles(field, f);
+                }
+            }
+        }
+        ReflectMut::TupleStruct(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_mut(index) {
+                    visit_handles(field, f);
+                }
+            }
+        }
+        ReflectMut::Tuple(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_mut(index) {
+                    visit_handles(field, f);
+                }
+            }
+        }
+        ReflectMut::List(value) => {
+            for index in 0..value.len() {
+                if let Some(element) = value.get_mut(index) {
+                    visit_handles(element, f);
+                }
+            }
+        }
+        ReflectMut::Map(_) | ReflectMut::Value(_) => {}
+    }
+}
+
+/// Replaces the ids of the handles of `value` with the paths of their assets, for the handles
+/// of assets loaded from a path.
+fn write_asset_paths(value: &mut dyn Reflect, asset_server: &AssetServer) {
+    visit_handles(value, &mut |handle: &mut DynamicStruct| {
+        let id = match handle
+            .field(HANDLE_FIELD_ID)
+            .and_then(|id| id.downcast_ref::<HandleId>())
+        {
+            Some(id) => *id,
+            None => return,
+        };
+        if let Some(path) = asset_server.get_asset_path(id) {
+            let mut path_string = path.path().to_string_lossy().replace('\\', "/");
+            if let Some(label) = path.label() {
+                path_string.push('#');
+                path_string.push_str(label);
+            }
+            handle.insert(HANDLE_FIELD_ID, path_string);
+        }
+    });
+}
+
+/// Replaces the asset paths written in place of the ids of the handles of `value` with the ids
+/// of the handles of these assets, and adds the paths to `paths`.
+pub(crate) fn read_asset_paths(value: &mut dyn Reflect, paths: &mut Vec<AssetPath<'static>>) {
+    visit_handles(value, &mut |handle: &mut DynamicStruct| {
+        let path = match handle
+            .field(HANDLE_FIELD_ID)
+            .and_then(|id| id.downcast_ref::<String>())
+        {
+            Some(path) => AssetPath::from(path.as_str()).to_owned(),
+            None => return,
+        };
+        handle.insert(HANDLE_FIELD_ID, HandleId::from(path.get_id()));
+        paths.push(path);
+    });
+}
 
 pub struct SceneSerializer<'a> {
     pub scene: &'a DynamicScene,
     pub registry: &'a TypeRegistryArc,
+    pub asset_server: Option<&'a AssetServer>,
 }
 
 impl<'a> SceneSerializer<'a> {
     pub fn new(scene: &'a DynamicScene, registry: &'a TypeRegistryArc) -> Self {
-        SceneSerializer { scene, registry }
+        SceneSerializer {
+            scene,
+            registry,
+            asset_server: None,
+        }
+    }
+
+    /// Serializes the handles of assets loaded from a path as their path, so that the scene
+    /// references the same assets wherever it's loaded. The [`SceneLoader`](crate::SceneLoader)
+    /// loads these assets with the scene.
+    pub fn with_asset_server(mut self, asset_server: &'a AssetServer) -> Self {
+        self.asset_server = Some(asset_server);
+        self
     }
 }
 
+pub const SCENE_FIELD_RESOURCES: &str = "resources";
+pub const SCENE_FIELD_ENTITIES: &str = "entities";
+
 impl<'a> Serialize for SceneSerializer<'a> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_seq(Some(self.scene.entities.len()))?;
-        for entity in self.scene.entities.iter() {
+        let entities = EntitiesSerializer {
+            entities: &self.scene.entities,
+            registry: self.registry,
+            asset_server: self.asset_server,
+        };
+        // scenes without resources are written as a list of entities, like before scenes could
+        // have resources
+        if self.scene.resources.is_empty() {
+            return entities.serialize(serializer);
+        }
+        let mut state = serializer.serialize_map(Some(2))?;
+        state.serialize_entry(
+            SCENE_FIELD_RESOURCES,
+            &ComponentsSerializer {
+                components: &self.scene.resources,
+                registry: self.registry,
+                asset_server: self.asset_server,
+            },
+        )?;
+        state.serialize_entry(SCENE_FIELD_ENTITIES, &entities)?;
+        state.end()
+    }
+}
+
+pub struct EntitiesSerializer<'a> {
+    pub entities: &'a [Entity],
+    pub registry: &'a TypeRegistryArc,
+    pub asset_server: Option<&'a AssetServer>,
+}
+
+impl<'a> Serialize for EntitiesSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_seq(Some(self.entities.len()))?;
+        for entity in self.entities.iter() {
             state.serialize_element(&EntitySerializer {
                 entity,
                 registry: self.registry,
+                asset_server: self.asset_server,
             })?;
         }
         state.end()
@@ -40,6 +239,7 @@ impl<'a> Serialize for SceneSerializer<'a> {
 pub struct EntitySerializer<'a> {
     pub entity: &'a Entity,
     pub registry: &'a TypeRegistryArc,
+    pub asset_server: Option<&'a AssetServer>,
 }
 
 impl<'a> Serialize for EntitySerializer<'a> {
@@ -54,6 +254,7 @@ impl<'a> Serialize for EntitySerializer<'a> {
             &ComponentsSerializer {
                 components: &self.entity.components,
                 registry: self.registry,
+                asset_server: self.asset_server,
             },
         )?;
         state.end()
@@ -63,6 +264,7 @@ impl<'a> Serialize for EntitySerializer<'a> {
 pub struct ComponentsSerializer<'a> {
     pub components: &'a [Box<dyn Reflect>],
     pub registry: &'a TypeRegistryArc,
+    pub asset_server: Option<&'a AssetServer>,
 }
 
 impl<'a> Serialize for ComponentsSerializer<'a> {
@@ -72,15 +274,28 @@ impl<'a> Serialize for ComponentsSerializer<'a> {
     {
         let mut state = serializer.serialize_seq(Some(self.components.len()))?;
         for component in self.components.iter() {
-            state.serialize_element(&ReflectSerializer::new(
-                &**component,
-                &*self.registry.read(),
-            ))?;
+            match self.asset_server {
+                Some(asset_server) => {
+                    let mut component = component.clone_value();
+                    write_asset_paths(&mut *component, asset_server);
+                    state.serialize_element(&ReflectSerializer::new(
+                        &*component,
+                        &*self.registry.read(),
+                    ))?;
+                }
+                None => state.serialize_element(&ReflectSerializer::new(
+                    &**component,
+                    &*self.registry.read(),
+                ))?,
+            }
         }
         state.end()
     }
 }
 
+/// Deserializes a [`DynamicScene`]. The handles serialized as asset paths must be resolved with
+/// [`DynamicScene::resolve_asset_paths`] before the scene is spawned, which the
+/// [`SceneLoader`](crate::SceneLoader) does.
 pub struct SceneDeserializer<'a> {
     pub type_registry: &'a TypeRegistry,
 }
@@ -92,10 +307,90 @@ impl<'a, 'de> DeserializeSeed<'de> for SceneDeserializer<'a> {
     where
         D: serde::Deserializer<'de>,
     {
+        deserializer.deserialize_any(SceneVisitor {
+            type_registry: self.type_registry,
+        })
+    }
+}
+
+struct SceneVisitor<'a> {
+    pub type_registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> Visitor<'de> for SceneVisitor<'a> {
+    type Value = DynamicScene;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("list of entities, or map of resources and entities")
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let entities = SceneEntitySeqVisitor {
+            type_registry: self.type_registry,
+        }
+        .visit_seq(seq)?;
+        Ok(DynamicScene {
+            entities,
+            resources: Vec::new(),
+        })
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut resources = None;
+        let mut entities = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                SCENE_FIELD_RESOURCES => {
+                    if resources.is_some() {
+                        return Err(Error::duplicate_field(SCENE_FIELD_RESOURCES));
+                    }
+                    resources = Some(map.next_value_seed(ComponentVecDeserializer {
+                        registry: self.type_registry,
+                    })?);
+                }
+                SCENE_FIELD_ENTITIES => {
+                    if entities.is_some() {
+                        return Err(Error::duplicate_field(SCENE_FIELD_ENTITIES));
+                    }
+                    entities = Some(map.next_value_seed(SceneEntitiesDeserializer {
+                        type_registry: self.type_registry,
+                    })?);
+                }
+                _ => {
+                    return Err(Error::unknown_field(
+                        &key,
+                        &[SCENE_FIELD_RESOURCES, SCENE_FIELD_ENTITIES],
+                    ))
+                }
+            }
+        }
+
         Ok(DynamicScene {
-            entities: deserializer.deserialize_seq(SceneEntitySeqVisitor {
-                type_registry: self.type_registry,
-            })?,
+            entities: entities.unwrap_or_default(),
+            resources: resources.unwrap_or_default(),
+        })
+    }
+}
+
+pub struct SceneEntitiesDeserializer<'a> {
+    pub type_registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for SceneEntitiesDeserializer<'a> {
+    type Value = Vec<Entity>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(SceneEntitySeqVisitor {
+            type_registry: self.type_registry,
         })
     }
 }
@@ -169,6 +464,22 @@ impl<'a, 'de> Visitor<'de> for SceneEntityVisitor<'a> {
         formatter.write_str("entities")
     }
 
+    // binary scenes serialize entities as a sequence of their fields
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let entity = seq
+            .next_element::<u32>()?
+            .ok_or_else(|| Error::invalid_length(0, &self))?;
+        let components = seq
+            .next_element_seed(ComponentVecDeserializer {
+                registry: self.registry,
+            })?
+            .ok_or_else(|| Error::invalid_length(1, &self))?;
+        Ok(Entity { entity, components })
+    }
+
     fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
     where
         A: MapAccess<'de>,
@@ -249,3 +560,113 @@ impl<'a, 'de> Visitor<'de> for ComponentSeqVisitor<'a> {
         Ok(dynamic_properties)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialize_ron;
+    use bevy_asset::FileAssetIo;
+    use bevy_ecs::{component::Component, reflect::ReflectComponent, world::World};
+    use bevy_tasks::TaskPool;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Health(u32);
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Room {
+        scene: Handle<DynamicScene>,
+    }
+
+    fn binary_scene() -> (Vec<u8>, TypeRegistryArc) {
+        let registry = TypeRegistryArc::default();
+        registry.write().register::<Health>();
+        registry.write().register::<u32>();
+        let mut world = World::default();
+        world.spawn().insert(Health(3));
+        let bytes = DynamicScene::from_world(&world, &registry)
+            .serialize_binary(&registry)
+            .unwrap();
+        (bytes, registry)
+    }
+
+    #[test]
+    fn binary_scene_round_trip() {
+        let (bytes, registry) = binary_scene();
+        assert!(is_binary_scene(&bytes));
+        assert_eq!(&bytes[4..6], &BINARY_SCENE_VERSION.to_le_bytes());
+
+        let scene = deserialize_binary_scene(&bytes, &registry.read()).unwrap();
+        assert_eq!(scene.entities.len(), 1);
+        let mut health = Health::default();
+        health.apply(&*scene.entities[0].components[0]);
+        assert_eq!(health.0, 3);
+    }
+
+    #[test]
+    fn binary_scene_header_rejected() {
+        let (bytes, registry) = binary_scene();
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert!(!is_binary_scene(&bad_magic));
+        assert!(matches!(
+            deserialize_binary_scene(&bad_magic, &registry.read()),
+            Err(BinarySceneError::NotBinaryScene)
+        ));
+        assert!(matches!(
+            deserialize_binary_scene(BINARY_SCENE_MAGIC, &registry.read()),
+            Err(BinarySceneError::NotBinaryScene)
+        ));
+
+        for version in [0, BINARY_SCENE_VERSION + 1] {
+            let mut unsupported = bytes.clone();
+            unsupported[4..6].copy_from_slice(&version.to_le_bytes());
+            assert!(matches!(
+                deserialize_binary_scene(&unsupported, &registry.read()),
+                Err(BinarySceneError::UnsupportedVersion(v)) if v == version
+            ));
+        }
+    }
+
+    #[test]
+    fn handles_as_asset_paths() {
+        let registry = TypeRegistryArc::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<Room>();
+            registry.register::<Handle<DynamicScene>>();
+            registry.register::<HandleId>();
+            registry.register::<String>();
+        }
+        let asset_server = AssetServer::new(FileAssetIo::new("."), TaskPool::new());
+        let room: Handle<DynamicScene> = asset_server.load("rooms/room.scn.ron");
+        let mut world = World::default();
+        world.spawn().insert(Room {
+            scene: room.clone(),
+        });
+
+        let scene = DynamicScene::from_world(&world, &registry);
+        let ron =
+            serialize_ron(SceneSerializer::new(&scene, &registry).with_asset_server(&asset_server))
+                .unwrap();
+        assert!(ron.contains("\"rooms/room.scn.ron\""));
+
+        let mut deserializer = ron::de::Deserializer::from_str(&ron).unwrap();
+        let mut scene = SceneDeserializer {
+            type_registry: &registry.read(),
+        }
+        .deserialize(&mut deserializer)
+        .unwrap();
+        let paths = scene.resolve_asset_paths();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(
+            paths[0].get_id(),
+            AssetPath::from("rooms/room.scn.ron").get_id()
+        );
+        let mut loaded_room = Room::default();
+        loaded_room.apply(&*scene.entities[0].components[0]);
+        assert_eq!(loaded_room.scene.id, room.id);
+    }
+}
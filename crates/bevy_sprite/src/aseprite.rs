@@ -0,0 +1,675 @@
+use crate::{
+    AnimationMode, SpriteAnimation, SpriteAnimationClip, SpriteAnimationFrame, TextureAtlas,
+};
+use anyhow::Result;
+use bevy_asset::{AssetLoader, Handle, LoadContext, LoadedAsset};
+use bevy_math::Vec2;
+use bevy_reflect::TypeUuid;
+use bevy_render::texture::{Extent3d, FilterMode, Texture, TextureDimension, TextureFormat};
+use bevy_utils::{BoxedFuture, Duration, HashMap};
+use flate2::read::ZlibDecoder;
+use std::io::Read;
+use thiserror::Error;
+
+const HEADER_MAGIC: u16 = 0xA5E0;
+const FRAME_MAGIC: u16 = 0xF1FA;
+const HEADER_SIZE: usize = 128;
+
+const CHUNK_OLD_PALETTE: u16 = 0x0004;
+const CHUNK_OLD_PALETTE_6_BITS: u16 = 0x0011;
+const CHUNK_LAYER: u16 = 0x2004;
+const CHUNK_CEL: u16 = 0x2005;
+const CHUNK_TAGS: u16 = 0x2018;
+const CHUNK_PALETTE: u16 = 0x2019;
+
+/// A sprite sheet loaded from an [Aseprite](https://www.aseprite.org/) file, with a texture in
+/// the atlas for each frame of the file, and a clip for each animation tag.
+#[derive(Debug, TypeUuid)]
+#[uuid = "b9c6a3f0-5e2d-4d83-8e41-2f7c9a1d6e54"]
+pub struct Aseprite {
+    pub atlas: Handle<TextureAtlas>,
+    /// The duration of each frame
+    pub frame_durations: Vec<Duration>,
+    /// The animation tags, by name
+    pub tags: HashMap<String, SpriteAnimationClip>,
+}
+
+impl Aseprite {
+    /// A clip of every frame of the file, looping
+    pub fn all_frames(&self) -> SpriteAnimationClip {
+        SpriteAnimationClip::new(
+            self.frame_durations
+                .iter()
+                .enumerate()
+                .map(|(index, &duration)| SpriteAnimationFrame {
+                    index: index as u32,
+                    duration,
+                })
+                .collect(),
+        )
+    }
+
+    /// Creates a [`SpriteAnimation`] with a clip for each tag, playing the tag `tag`
+    pub fn animation(&self, tag: &str) -> SpriteAnimation {
+        let mut animation = SpriteAnimation::default();
+        for (name, clip) in self.tags.iter() {
+            animation.add_clip(name.clone(), clip.clone());
+        }
+        animation.play(tag);
+        animation
+    }
+}
+
+/// An error that occurs when loading an Aseprite file
+#[derive(Error, Debug)]
+pub enum AsepriteError {
+    #[error("not an Aseprite file")]
+    InvalidMagic,
+    #[error("unexpected end of file")]
+    UnexpectedEof,
+    #[error("unsupported color depth: {0} bits per pixel")]
+    UnsupportedColorDepth(u16),
+    #[error("failed to decompress cel: {0}")]
+    Decompress(#[from] std::io::Error),
+}
+
+/// Loads [Aseprite](https://www.aseprite.org/) `.aseprite` and `.ase` files into [`Aseprite`]
+/// assets. The visible layers of each frame are flattened with normal blending, whatever their
+/// blend mode.
+#[derive(Default)]
+pub struct AsepriteLoader;
+
+impl AssetLoader for AsepriteLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<()>> {
+        Box::pin(async move { Ok(load_aseprite(bytes, load_context)?) })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aseprite", "ase"]
+    }
+}
+
+struct Layer {
+    visible: bool,
+    opacity: u8,
+}
+
+struct Cel {
+    layer: usize,
+    x: i32,
+    y: i32,
+    opacity: u8,
+    content: CelContent,
+}
+
+enum CelContent {
+    Image {
+        width: usize,
+        height: usize,
+        /// RGBA pixels
+        pixels: Vec<u8>,
+    },
+    /// The cel of the same layer in another frame
+    Linked(usize),
+    Unsupported,
+}
+
+struct Tag {
+    name: String,
+    from: usize,
+    to: usize,
+    direction: u8,
+    repeat: u16,
+}
+
+fn load_aseprite(bytes: &[u8], load_context: &mut LoadContext) -> Result<(), AsepriteError> {
+    let mut reader = Reader { bytes, position: 0 };
+    reader.skip(4)?; // file size
+    if reader.u16()? != HEADER_MAGIC {
+        return Err(AsepriteError::InvalidMagic);
+    }
+    let frame_count = reader.u16()? as usize;
+    let width = reader.u16()? as usize;
+    let height = reader.u16()? as usize;
+    let color_depth = reader.u16()?;
+    let layer_opacity_valid = reader.u32()? & 1 != 0;
+    reader.skip(2 + 4 + 4)?; // speed and reserved
+    let transparent_index = reader.u8()?;
+    reader.position = HEADER_SIZE;
+
+    let mut palette = vec![[0u8; 4]; 256];
+    let mut layers = Vec::new();
+    let mut group_visibility = Vec::new();
+    let mut tags = Vec::new();
+    let mut frame_durations = Vec::with_capacity(frame_count);
+    let mut frame_cels = Vec::with_capacity(frame_count);
+
+    for _ in 0..frame_count {
+        let frame_start = reader.position;
+        let frame_size = reader.u32()? as usize;
+        if reader.u16()? != FRAME_MAGIC {
+            return Err(AsepriteError::InvalidMagic);
+        }
+        let old_chunk_count = reader.u16()? as u32;
+        frame_durations.push(Duration::from_millis(reader.u16()? as u64));
+        reader.skip(2)?;
+        let chunk_count = match reader.u32()? {
+            0 => old_chunk_count,
+            count => count,
+        };
+
+        let mut cels = Vec::new();
+        for _ in 0..chunk_count {
+            let chunk_start = reader.position;
+            let chunk_size = reader.u32()? as usize;
+            let chunk_type = reader.u16()?;
+            let mut chunk = Reader {
+                bytes: reader.bytes(chunk_size.saturating_sub(6))?,
+                position: 0,
+            };
+            match chunk_type {
+                CHUNK_OLD_PALETTE | CHUNK_OLD_PALETTE_6_BITS => {
+                    let scale = if chunk_type == CHUNK_OLD_PALETTE {
+                        1
+                    } else {
+                        4
+                    };
+                    let mut index = 0;
+                    for _ in 0..chunk.u16()? {
+                        index += chunk.u8()? as usize;
+                        let count = match chunk.u8()? {
+                            0 => 256,
+                            count => count as usize,
+                        };
+                        for _ in 0..count {
+                            let rgb = chunk.bytes(3)?;
+                            if let Some(color) = palette.get_mut(index) {
+                                *color = [rgb[0] * scale, rgb[1] * scale, rgb[2] * scale, 255];
+                            }
+                            index += 1;
+                        }
+                    }
+                }
+                CHUNK_PALETTE => {
+                    chunk.skip(4)?; // new palette size
+                    let first = chunk.u32()? as usize;
+                    let last = chunk.u32()? as usize;
+                    chunk.skip(8)?;
+                    if palette.len() <= last {
+                        palette.resize(last + 1, [0; 4]);
+                    }
+                    for color in palette.iter_mut().take(last + 1).skip(first) {
+                        let flags = chunk.u16()?;
+                        let rgba = chunk.bytes(4)?;
+                        *color = [rgba[0], rgba[1], rgba[2], rgba[3]];
+                        if flags & 1 != 0 {
+                            chunk.string()?;
+                        }
+                    }
+                }
+                CHUNK_LAYER => {
+                    let flags = chunk.u16()?;
+                    chunk.skip(2)?; // type
+                    let child_level = chunk.u16()? as usize;
+                    chunk.skip(2 + 2 + 2)?; // size and blend mode
+                    let opacity = chunk.u8()?;
+                    // layers in hidden groups are hidden
+                    group_visibility.truncate(child_level);
+                    let visible = flags & 1 != 0 && group_visibility.iter().all(|&visible| visible);
+                    group_visibility.push(visible);
+                    layers.push(Layer {
+                        visible,
+                        opacity: if layer_opacity_valid { opacity } else { 255 },
+                    });
+                }
+                CHUNK_CEL => {
+                    let layer = chunk.u16()? as usize;
+                    let x = chunk.i16()? as i32;
+                    let y = chunk.i16()? as i32;
+                    let opacity = chunk.u8()?;
+                    let cel_type = chunk.u16()?;
+                    chunk.skip(7)?;
+                    let content = match cel_type {
+                        0 | 2 => {
+                            let width = chunk.u16()? as usize;
+                            let height = chunk.u16()? as usize;
+                            let data = chunk.bytes(chunk.bytes.len() - chunk.position)?;
+                            let data = if cel_type == 2 {
+                                let mut decoded = Vec::new();
+                                ZlibDecoder::new(data).read_to_end(&mut decoded)?;
+                                decoded
+                            } else {
+                                data.to_vec()
+                            };
+                            CelContent::Image {
+                                width,
+                                height,
+                                pixels: to_rgba(
+                                    &data,
+                                    width * height,
+                                    color_depth,
+                                    &palette,
+                                    transparent_index,
+                                )?,
+                            }
+                        }
+                        1 => CelContent::Linked(chunk.u16()? as usize),
+                        // tilemap cels
+                        _ => CelContent::Unsupported,
+                    };
+                    cels.push(Cel {
+                        layer,
+                        x,
+                        y,
+                        opacity,
+                        content,
+                    });
+                }
+                CHUNK_TAGS => {
+                    let count = chunk.u16()?;
+                    chunk.skip(8)?;
+                    for _ in 0..count {
+                        let from = chunk.u16()? as usize;
+                        let to = chunk.u16()? as usize;
+                        let direction = chunk.u8()?;
+                        let repeat = chunk.u16()?;
+                        chunk.skip(6 + 3 + 1)?; // reserved and color
+                        tags.push(Tag {
+                            name: chunk.string()?,
+                            from,
+                            to,
+                            direction,
+                            repeat,
+                        });
+                    }
+                }
+                _ => {}
+            }
+            reader.position = chunk_start + chunk_size.max(6);
+        }
+        frame_cels.push(cels);
+        reader.position = frame_start + frame_size;
+    }
+
+    // the frames are laid out in a grid, as square as possible
+    let columns = (frame_count as f32).sqrt().ceil().max(1.0) as usize;
+    let rows = ((frame_count + columns - 1) / columns).max(1);
+    let atlas_width = width * columns;
+    let mut data = vec![0u8; atlas_width * height * rows * 4];
+    for frame in 0..frame_count {
+        let origin_x = (frame % columns) * width;
+        let origin_y = (frame / columns) * height;
+        for (layer_index, layer) in layers.iter().enumerate() {
+            if !layer.visible {
+                continue;
+            }
+            let cel = match find_cel(&frame_cels, frame, layer_index) {
+                Some(cel) => cel,
+                None => continue,
+            };
+            let (cel_width, cel_height, pixels) = match &cel.content {
+                CelContent::Image {
+                    width,
+                    height,
+                    pixels,
+                } => (*width, *height, pixels),
+                _ => continue,
+            };
+            let opacity = cel.opacity as u32 * layer.opacity as u32 / 255;
+            for cel_y in 0..cel_height {
+                let y = cel.y + cel_y as i32;
+                if y < 0 || y >= height as i32 {
+                    continue;
+                }
+                for cel_x in 0..cel_width {
+                    let x = cel.x + cel_x as i32;
+                    if x < 0 || x >= width as i32 {
+                        continue;
+                    }
+                    let source = &pixels[(cel_y * cel_width + cel_x) * 4..][..4];
+                    let target =
+                        ((origin_y + y as usize) * atlas_width + origin_x + x as usize) * 4;
+                    blend(&mut data[target..target + 4], source, opacity);
+                }
+            }
+        }
+    }
+
+    let mut texture = Texture::new(
+        Extent3d::new(atlas_width as u32, (height * rows) as u32, 1),
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    // Aseprite is used for pixel art, which should not be blurred when scaled up
+    texture.sampler.mag_filter = FilterMode::Nearest;
+    texture.sampler.min_filter = FilterMode::Nearest;
+    let texture = load_context.set_labeled_asset("Texture", LoadedAsset::new(texture));
+    let atlas = TextureAtlas::from_grid(
+        texture,
+        Vec2::new(width as f32, height as f32),
+        columns,
+        rows,
+    );
+    let atlas = load_context.set_labeled_asset("Atlas", LoadedAsset::new(atlas));
+
+    let tags = tags
+        .into_iter()
+        .filter(|tag| tag.from <= tag.to && tag.to < frame_count)
+        .map(|tag| {
+            let mut frames = (tag.from..=tag.to)
+                .map(|index| SpriteAnimationFrame {
+                    index: index as u32,
+                    duration: frame_durations[index],
+                })
+                .collect::<Vec<_>>();
+            // reverse and ping-pong reverse
+            if tag.direction == 1 || tag.direction == 3 {
+                frames.reverse();
+            }
+            let mode = match (tag.direction, tag.repeat) {
+                (2, _) | (3, _) => AnimationMode::PingPong,
+                (_, 1) => AnimationMode::Once,
+                _ => AnimationMode::Loop,
+            };
+            (tag.name, SpriteAnimationClip::new(frames).with_mode(mode))
+        })
+        .collect();
+
+    load_context.set_default_asset(LoadedAsset::new(Aseprite {
+        atlas,
+        frame_durations,
+        tags,
+    }));
+    Ok(())
+}
+
+/// Finds the cel of a layer in a frame, following links to other frames
+fn find_cel(frame_cels: &[Vec<Cel>], frame: usize, layer: usize) -> Option<&Cel> {
+    let cel = frame_cels
+        .get(frame)?
+        .iter()
+        .find(|cel| cel.layer == layer)?;
+    match cel.content {
+        // links always point to an earlier frame
+        CelContent::Linked(linked_frame) if linked_frame < frame => {
+            find_cel(frame_cels, linked_frame, layer)
+        }
+        CelContent::Linked(_) => None,
+        _ => Some(cel),
+    }
+}
+
+/// Converts the pixels of a cel to RGBA
+fn to_rgba(
+    data: &[u8],
+    pixel_count: usize,
+    color_depth: u16,
+    palette: &[[u8; 4]],
+    transparent_index: u8,
+) -> Result<Vec<u8>, AsepriteError> {
+    let bytes_per_pixel = match color_depth {
+        32 => 4,
+        16 => 2,
+        8 => 1,
+        depth => return Err(AsepriteError::UnsupportedColorDepth(depth)),
+    };
+    if data.len() < pixel_count * bytes_per_pixel {
+        return Err(AsepriteError::UnexpectedEof);
+    }
+    Ok(data[..pixel_count * bytes_per_pixel]
+        .chunks_exact(bytes_per_pixel)
+        .flat_map(|pixel| match pixel {
+            [r, g, b, a] => [*r, *g, *b, *a],
+            [value, alpha] => [*value, *value, *value, *alpha],
+            [index] if *index == transparent_index => [0; 4],
+            [index] => palette.get(*index as usize).copied().unwrap_or([0; 4]),
+            _ => unreachable!(),
+        })
+        .collect())
+}
+
+/// Blends a straight alpha `source` pixel over `target` with the "normal" blend mode
+fn blend(target: &mut [u8], source: &[u8], opacity: u32) {
+    let source_alpha = source[3] as u32 * opacity / 255;
+    if source_alpha == 0 {
+        return;
+    }
+    let target_alpha = target[3] as u32;
+    let alpha = source_alpha + target_alpha * (255 - source_alpha) / 255;
+    for channel in 0..3 {
+        let source_part = source[channel] as u32 * source_alpha;
+        let target_part = target[channel] as u32 * target_alpha * (255 - source_alpha) / 255;
+        target[channel] = ((source_part + target_part) / alpha) as u8;
+    }
+    target[3] = alpha as u8;
+}
+
+/// Reads the little endian values of an Aseprite file
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], AsepriteError> {
+        let bytes = self
+            .bytes
+            .get(self.position..self.position + count)
+            .ok_or(AsepriteError::UnexpectedEof)?;
+        self.position += count;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, count: usize) -> Result<(), AsepriteError> {
+        self.bytes(count).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, AsepriteError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, AsepriteError> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn i16(&mut self) -> Result<i16, AsepriteError> {
+        Ok(self.u16()? as i16)
+    }
+
+    fn u32(&mut self) -> Result<u32, AsepriteError> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn string(&mut self) -> Result<String, AsepriteError> {
+        let length = self.u16()? as usize;
+        Ok(String::from_utf8_lossy(self.bytes(length)?).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin, AssetServer, AssetServerSettings, Assets, LoadState};
+    use bevy_tasks::{IoTaskPool, TaskPool};
+
+    fn chunk(chunk_type: u16, data: &[u8]) -> Vec<u8> {
+        let mut chunk = (6 + data.len() as u32).to_le_bytes().to_vec();
+        chunk.extend_from_slice(&chunk_type.to_le_bytes());
+        chunk.extend_from_slice(data);
+        chunk
+    }
+
+    fn frame(duration: u16, chunks: &[Vec<u8>]) -> Vec<u8> {
+        let size = 16 + chunks.iter().map(Vec::len).sum::<usize>();
+        let mut frame = (size as u32).to_le_bytes().to_vec();
+        frame.extend_from_slice(&FRAME_MAGIC.to_le_bytes());
+        frame.extend_from_slice(&(chunks.len() as u16).to_le_bytes());
+        frame.extend_from_slice(&duration.to_le_bytes());
+        frame.extend_from_slice(&[0; 2]);
+        frame.extend_from_slice(&(chunks.len() as u32).to_le_bytes());
+        for chunk in chunks {
+            frame.extend_from_slice(chunk);
+        }
+        frame
+    }
+
+    /// A 32 bits per pixel file of 1x1 frames
+    fn file(frames: &[Vec<u8>]) -> Vec<u8> {
+        let mut file = Vec::new();
+        file.extend_from_slice(&0u32.to_le_bytes());
+        file.extend_from_slice(&HEADER_MAGIC.to_le_bytes());
+        file.extend_from_slice(&(frames.len() as u16).to_le_bytes());
+        for value in [1u16, 1, 32] {
+            file.extend_from_slice(&value.to_le_bytes());
+        }
+        file.extend_from_slice(&1u32.to_le_bytes());
+        file.resize(HEADER_SIZE, 0);
+        for frame in frames {
+            file.extend_from_slice(frame);
+        }
+        file
+    }
+
+    fn string(value: &str) -> Vec<u8> {
+        let mut bytes = (value.len() as u16).to_le_bytes().to_vec();
+        bytes.extend_from_slice(value.as_bytes());
+        bytes
+    }
+
+    fn layer() -> Vec<u8> {
+        let mut data = vec![1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255, 0, 0, 0];
+        data.extend(string("Layer"));
+        chunk(CHUNK_LAYER, &data)
+    }
+
+    /// A cel of the layer with a single pixel
+    fn image_cel(rgba: [u8; 4]) -> Vec<u8> {
+        let mut data = vec![0, 0, 0, 0, 0, 0, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&rgba);
+        chunk(CHUNK_CEL, &data)
+    }
+
+    fn linked_cel(frame: u16) -> Vec<u8> {
+        let mut data = vec![0, 0, 0, 0, 0, 0, 255, 1, 0, 0, 0, 0, 0, 0, 0, 0];
+        data.extend_from_slice(&frame.to_le_bytes());
+        chunk(CHUNK_CEL, &data)
+    }
+
+    /// Tags of name, first and last frame, direction and repeat count
+    fn tags(tags: &[(&str, u16, u16, u8, u16)]) -> Vec<u8> {
+        let mut data = (tags.len() as u16).to_le_bytes().to_vec();
+        data.extend_from_slice(&[0; 8]);
+        for &(name, from, to, direction, repeat) in tags {
+            data.extend_from_slice(&from.to_le_bytes());
+            data.extend_from_slice(&to.to_le_bytes());
+            data.push(direction);
+            data.extend_from_slice(&repeat.to_le_bytes());
+            data.extend_from_slice(&[0; 10]);
+            data.extend(string(name));
+        }
+        chunk(CHUNK_TAGS, &data)
+    }
+
+    fn load(bytes: &[u8]) -> (App, Handle<Aseprite>) {
+        let asset_folder = tempfile::tempdir().unwrap();
+        std::fs::write(asset_folder.path().join("player.aseprite"), bytes).unwrap();
+        let mut app = App::new();
+        app.insert_resource(IoTaskPool(TaskPool::new()))
+            .insert_resource(AssetServerSettings {
+                asset_folder: asset_folder.path().to_string_lossy().to_string(),
+            })
+            .add_plugin(AssetPlugin)
+            .add_asset::<Texture>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<Aseprite>()
+            .init_asset_loader::<AsepriteLoader>();
+        let handle = app
+            .world
+            .get_resource::<AssetServer>()
+            .unwrap()
+            .load("player.aseprite");
+        for _ in 0..1000 {
+            app.update();
+            if app
+                .world
+                .get_resource::<Assets<Aseprite>>()
+                .unwrap()
+                .contains(&handle)
+            {
+                return (app, handle);
+            }
+            let asset_server = app.world.get_resource::<AssetServer>().unwrap();
+            assert_ne!(asset_server.get_load_state(&handle), LoadState::Failed);
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        panic!("the file wasn't loaded in time");
+    }
+
+    fn frames(clip: &SpriteAnimationClip) -> Vec<(u32, u64)> {
+        clip.frames
+            .iter()
+            .map(|frame| (frame.index, frame.duration.as_millis() as u64))
+            .collect()
+    }
+
+    #[test]
+    fn load_tags() {
+        const RED: [u8; 4] = [255, 0, 0, 255];
+        const GREEN: [u8; 4] = [0, 255, 0, 255];
+        let bytes = file(&[
+            frame(
+                100,
+                &[
+                    layer(),
+                    image_cel(RED),
+                    tags(&[
+                        ("walk", 0, 2, 0, 0),
+                        ("hit", 1, 2, 1, 1),
+                        ("bounce", 0, 1, 2, 0),
+                        ("missing", 1, 5, 0, 0),
+                    ]),
+                ],
+            ),
+            frame(150, &[linked_cel(0)]),
+            frame(200, &[image_cel(GREEN)]),
+        ]);
+        let (app, handle) = load(&bytes);
+        let aseprites = app.world.get_resource::<Assets<Aseprite>>().unwrap();
+        let aseprite = aseprites.get(&handle).unwrap();
+
+        assert_eq!(
+            frames(&aseprite.all_frames()),
+            [(0, 100), (1, 150), (2, 200)]
+        );
+        // tags past the last frame are skipped
+        assert_eq!(aseprite.tags.len(), 3);
+        let walk = &aseprite.tags["walk"];
+        assert_eq!(frames(walk), [(0, 100), (1, 150), (2, 200)]);
+        assert_eq!(walk.mode, AnimationMode::Loop);
+        let hit = &aseprite.tags["hit"];
+        assert_eq!(frames(hit), [(2, 200), (1, 150)]);
+        assert_eq!(hit.mode, AnimationMode::Once);
+        let bounce = &aseprite.tags["bounce"];
+        assert_eq!(frames(bounce), [(0, 100), (1, 150)]);
+        assert_eq!(bounce.mode, AnimationMode::PingPong);
+
+        // the frames are laid out in a 2x2 grid, the linked cel repeating the first frame
+        let atlases = app.world.get_resource::<Assets<TextureAtlas>>().unwrap();
+        let atlas = atlases.get(&aseprite.atlas).unwrap();
+        assert_eq!(atlas.textures.len(), 4);
+        let textures = app.world.get_resource::<Assets<Texture>>().unwrap();
+        let texture = textures.get(&atlas.texture).unwrap();
+        assert_eq!(texture.data[..8], [RED, RED].concat()[..]);
+        assert_eq!(texture.data[8..12], GREEN);
+    }
+}
@@ -1,12 +1,20 @@
-use crate::{Rect, TextureAtlas};
+use crate::{texture_atlas_builder::copy_texture_to_atlas, Rect, TextureAtlas};
 use bevy_asset::Assets;
 use bevy_math::Vec2;
-use bevy_render::texture::Texture;
-use guillotiere::{size2, Allocation, AtlasAllocator};
+use bevy_render::texture::{Extent3d, Texture};
+use guillotiere::{point2, size2, Allocation, AtlasAllocator};
 
+/// Adds textures to a [`TextureAtlas`] one at a time, growing the atlas when it is full
 pub struct DynamicTextureAtlasBuilder {
     pub atlas_allocator: AtlasAllocator,
+    /// The empty space between textures in pixels
     pub padding: i32,
+    /// The number of pixels the edges of each texture are repeated outwards, so that sampling
+    /// near the edges of a texture doesn't bleed its neighbours in
+    pub extrusion: i32,
+    /// The size in pixels the atlas can grow to when a texture doesn't fit in it. The atlas
+    /// doesn't grow past its initial size by default.
+    pub max_size: Vec2,
 }
 
 impl DynamicTextureAtlasBuilder {
@@ -14,54 +22,97 @@ impl DynamicTextureAtlasBuilder {
         Self {
             atlas_allocator: AtlasAllocator::new(to_size2(size)),
             padding,
+            extrusion: 0,
+            max_size: size,
         }
     }
 
+    /// Creates a builder adding textures to an atlas that was already built, for example by a
+    /// [`TextureAtlasBuilder`](crate::TextureAtlasBuilder). The space from the top left corner
+    /// of the atlas to the bottom right corner of its textures is left to them.
+    pub fn from_atlas(texture_atlas: &TextureAtlas, padding: i32) -> Self {
+        let mut builder = DynamicTextureAtlasBuilder::new(texture_atlas.size, padding);
+        let used = texture_atlas
+            .textures
+            .iter()
+            .fold(Vec2::ZERO, |used, rect| used.max(rect.max));
+        if used.x > 0.0 && used.y > 0.0 {
+            // the first allocation in an empty atlas is placed in its top left corner
+            let allocation = builder.atlas_allocator.allocate(size2(
+                used.x.ceil() as i32 + padding,
+                used.y.ceil() as i32 + padding,
+            ));
+            if let Some(allocation) = allocation {
+                debug_assert_eq!(allocation.rectangle.min, point2(0, 0));
+            }
+        }
+        builder
+    }
+
+    /// Adds a texture to the atlas, growing the atlas up to [`Self::max_size`] if it doesn't fit.
+    /// Returns the index of the texture in the atlas, or `None` if it doesn't fit in the largest
+    /// atlas.
+    ///
+    /// Growing the atlas keeps the textures that were already added in place.
     pub fn add_texture(
         &mut self,
         texture_atlas: &mut TextureAtlas,
         textures: &mut Assets<Texture>,
         texture: &Texture,
     ) -> Option<u32> {
-        let allocation = self.atlas_allocator.allocate(size2(
-            texture.size.width as i32 + self.padding,
-            texture.size.height as i32 + self.padding,
-        ));
-        if let Some(allocation) = allocation {
-            let atlas_texture = textures.get_mut(&texture_atlas.texture).unwrap();
-            self.place_texture(atlas_texture, allocation, texture);
-            let mut rect: Rect = allocation.rectangle.into();
-            rect.max.x -= self.padding as f32;
-            rect.max.y -= self.padding as f32;
-            texture_atlas.add_texture(rect);
-            Some((texture_atlas.len() - 1) as u32)
-        } else {
-            None
-        }
+        let size = size2(
+            texture.size.width as i32 + 2 * self.extrusion + self.padding,
+            texture.size.height as i32 + 2 * self.extrusion + self.padding,
+        );
+        let allocation = loop {
+            if let Some(allocation) = self.atlas_allocator.allocate(size) {
+                break allocation;
+            }
+            if !self.grow(texture_atlas, textures) {
+                return None;
+            }
+        };
+        let atlas_texture = textures.get_mut(&texture_atlas.texture).unwrap();
+        self.place_texture(atlas_texture, allocation, texture);
+        let mut rect: Rect = allocation.rectangle.into();
+        rect.min += Vec2::splat(self.extrusion as f32);
+        rect.max = rect.min + texture.size.as_vec3().truncate();
+        texture_atlas.add_texture(rect);
+        Some((texture_atlas.len() - 1) as u32)
     }
 
-    // fn resize(
-    //     &mut self,
-    //     texture_atlas: &mut TextureAtlas,
-    //     textures: &mut Assets<Texture>,
-    //     size: Vec2,
-    // ) {
-    //     let new_size2 = to_size2(new_size);
-    //     self.atlas_texture = Texture::new_fill(new_size, &[0,0,0,0]);
-    //     let change_list = self.atlas_allocator.resize_and_rearrange(new_size2);
-
-    //     for change in change_list.changes {
-    //         if let Some(changed_texture_handle) = self.allocation_textures.remove(&change.old.id)
-    // {             let changed_texture = textures.get(&changed_texture_handle).unwrap();
-    //             self.place_texture(change.new, changed_texture_handle, changed_texture);
-    //         }
-    //     }
+    /// Doubles the size of the atlas, up to [`Self::max_size`]. Returns `false` if the atlas is
+    /// already as large as it can be.
+    fn grow(&mut self, texture_atlas: &mut TextureAtlas, textures: &mut Assets<Texture>) -> bool {
+        let size = self.atlas_allocator.size();
+        let new_size = size2(
+            (size.width * 2).min(self.max_size.x as i32).max(size.width),
+            (size.height * 2)
+                .min(self.max_size.y as i32)
+                .max(size.height),
+        );
+        if new_size == size {
+            return false;
+        }
+        self.atlas_allocator.grow(new_size);
 
-    //     for failure in change_list.failures {
-    //         let failed_texture = self.allocation_textures.remove(&failure.id).unwrap();
-    //         queued_textures.push(failed_texture);
-    //     }
-    // }
+        let atlas_texture = textures.get_mut(&texture_atlas.texture).unwrap();
+        let format_size = atlas_texture.format.pixel_size();
+        let old_row_size = atlas_texture.size.width as usize * format_size;
+        let new_row_size = new_size.width as usize * format_size;
+        let mut data = vec![0; new_row_size * new_size.height as usize];
+        for (old_row, new_row) in atlas_texture
+            .data
+            .chunks_exact(old_row_size)
+            .zip(data.chunks_exact_mut(new_row_size))
+        {
+            new_row[..old_row_size].copy_from_slice(old_row);
+        }
+        atlas_texture.data = data;
+        atlas_texture.size = Extent3d::new(new_size.width as u32, new_size.height as u32, 1);
+        texture_atlas.size = Vec2::new(new_size.width as f32, new_size.height as f32);
+        true
+    }
 
     fn place_texture(
         &mut self,
@@ -69,21 +120,14 @@ impl DynamicTextureAtlasBuilder {
         allocation: Allocation,
         texture: &Texture,
     ) {
-        let mut rect = allocation.rectangle;
-        rect.max.x -= self.padding;
-        rect.max.y -= self.padding;
-        let atlas_width = atlas_texture.size.width as usize;
-        let rect_width = rect.width() as usize;
-        let format_size = atlas_texture.format.pixel_size();
-
-        for (texture_y, bound_y) in (rect.min.y..rect.max.y).map(|i| i as usize).enumerate() {
-            let begin = (bound_y * atlas_width + rect.min.x as usize) * format_size;
-            let end = begin + rect_width * format_size;
-            let texture_begin = texture_y * rect_width * format_size;
-            let texture_end = texture_begin + rect_width * format_size;
-            atlas_texture.data[begin..end]
-                .copy_from_slice(&texture.data[texture_begin..texture_end]);
-        }
+        let extrusion = self.extrusion as usize;
+        copy_texture_to_atlas(
+            atlas_texture,
+            texture,
+            allocation.rectangle.min.x as usize + extrusion,
+            allocation.rectangle.min.y as usize + extrusion,
+            extrusion,
+        );
     }
 }
 
@@ -1,6 +1,5 @@
 use crate::{
-    render::SPRITE_PIPELINE_HANDLE, sprite::Sprite, ColorMaterial, TextureAtlas,
-    TextureAtlasSprite, QUAD_HANDLE, SPRITE_SHEET_PIPELINE_HANDLE,
+    sprite::Sprite, ColorMaterial, Shape, TextureAtlas, TextureAtlasSprite, SHAPE_PIPELINE_HANDLE,
 };
 use bevy_asset::Handle;
 use bevy_ecs::bundle::Bundle;
@@ -12,15 +11,14 @@ use bevy_render::{
 };
 use bevy_transform::prelude::{GlobalTransform, Transform};
 
+/// A Bundle of components for drawing a sprite. Sprites are drawn in batches, see
+/// [`SpriteSortMode`](crate::SpriteSortMode) for the order they are drawn in.
 #[derive(Bundle, Clone)]
 pub struct SpriteBundle {
     pub sprite: Sprite,
-    pub mesh: Handle<Mesh>, // TODO: maybe abstract this out
     pub material: Handle<ColorMaterial>,
     pub main_pass: MainPass,
-    pub draw: Draw,
     pub visible: Visible,
-    pub render_pipelines: RenderPipelines,
     pub transform: Transform,
     pub global_transform: GlobalTransform,
 }
@@ -28,16 +26,11 @@ pub struct SpriteBundle {
 impl Default for SpriteBundle {
     fn default() -> Self {
         Self {
-            mesh: QUAD_HANDLE.typed(),
-            render_pipelines: RenderPipelines::from_pipelines(vec![RenderPipeline::new(
-                SPRITE_PIPELINE_HANDLE.typed(),
-            )]),
             visible: Visible {
                 is_transparent: true,
                 ..Default::default()
             },
             main_pass: MainPass,
-            draw: Default::default(),
             sprite: Default::default(),
             material: Default::default(),
             transform: Default::default(),
@@ -54,31 +47,56 @@ pub struct SpriteSheetBundle {
     pub sprite: TextureAtlasSprite,
     /// A handle to the texture atlas that holds the sprite images
     pub texture_atlas: Handle<TextureAtlas>,
-    /// Data pertaining to how the sprite is drawn on the screen
-    pub draw: Draw,
     pub visible: Visible,
-    pub render_pipelines: RenderPipelines,
     pub main_pass: MainPass,
-    pub mesh: Handle<Mesh>, // TODO: maybe abstract this out
     pub transform: Transform,
     pub global_transform: GlobalTransform,
 }
 
 impl Default for SpriteSheetBundle {
+    fn default() -> Self {
+        Self {
+            visible: Visible {
+                is_transparent: true,
+                ..Default::default()
+            },
+            main_pass: MainPass,
+            sprite: Default::default(),
+            texture_atlas: Default::default(),
+            transform: Default::default(),
+            global_transform: Default::default(),
+        }
+    }
+}
+
+/// A Bundle of components for drawing a 2D vector [`Shape`]. The mesh is tessellated from the
+/// shape whenever it changes.
+#[derive(Bundle, Clone)]
+pub struct ShapeBundle {
+    pub shape: Shape,
+    pub mesh: Handle<Mesh>,
+    pub main_pass: MainPass,
+    pub draw: Draw,
+    pub visible: Visible,
+    pub render_pipelines: RenderPipelines,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+}
+
+impl Default for ShapeBundle {
     fn default() -> Self {
         Self {
             render_pipelines: RenderPipelines::from_pipelines(vec![RenderPipeline::new(
-                SPRITE_SHEET_PIPELINE_HANDLE.typed(),
+                SHAPE_PIPELINE_HANDLE.typed(),
             )]),
             visible: Visible {
                 is_transparent: true,
                 ..Default::default()
             },
             main_pass: MainPass,
-            mesh: QUAD_HANDLE.typed(),
             draw: Default::default(),
-            sprite: Default::default(),
-            texture_atlas: Default::default(),
+            shape: Default::default(),
+            mesh: Default::default(),
             transform: Default::default(),
             global_transform: Default::default(),
         }
@@ -53,7 +53,10 @@ pub fn sprite_frustum_culling_system(
 
             for (entity, drawable_transform, sprite) in sprites.iter() {
                 let sprite_rect = Rect {
-                    position: drawable_transform.translation.truncate(),
+                    position: drawable_transform.translation.truncate()
+                        + sprite
+                            .anchor
+                            .center_offset(sprite.size, sprite.flip_x, sprite.flip_y),
                     size: sprite.size,
                 };
 
@@ -97,11 +100,16 @@ pub fn atlas_frustum_culling_system(
 
             for (entity, drawable_transform, sprite, atlas_handle) in sprites.iter() {
                 if let Some(atlas) = textures.get(atlas_handle) {
-                    if let Some(sprite) = atlas.textures.get(sprite.index as usize) {
-                        let size = Vec2::new(sprite.width(), sprite.height());
+                    if let Some(texture_rect) = atlas.textures.get(sprite.index as usize) {
+                        let size = sprite.custom_size.unwrap_or_else(|| {
+                            Vec2::new(texture_rect.width(), texture_rect.height())
+                        });
 
                         let sprite_rect = Rect {
-                            position: drawable_transform.translation.truncate(),
+                            position: drawable_transform.translation.truncate()
+                                + sprite
+                                    .anchor
+                                    .center_offset(size, sprite.flip_x, sprite.flip_y),
                             size,
                         };
 
@@ -1,33 +1,49 @@
 pub mod collide_aabb;
 pub mod entity;
 
+#[cfg(feature = "aseprite")]
+mod aseprite;
 mod color_material;
 mod dynamic_texture_atlas_builder;
 mod frustum_culling;
+mod light2d;
 mod rect;
 mod render;
 mod sprite;
+mod sprite_animation;
+mod sprite_batch;
 mod texture_atlas;
 mod texture_atlas_builder;
+mod vector_shape;
 
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        entity::{SpriteBundle, SpriteSheetBundle},
-        ColorMaterial, Sprite, SpriteResizeMode, TextureAtlas, TextureAtlasSprite,
+        entity::{ShapeBundle, SpriteBundle, SpriteSheetBundle},
+        AmbientLight2d, AnimationMode, ColorMaterial, ConeLight2d, LightOccluder2d, PointLight2d,
+        Shape, ShapePath, SliceBorder, Sprite, SpriteAnimation, SpriteAnimationClip,
+        SpriteAnimationEvent, SpriteDrawMode, SpriteLayer, SpriteNormalMap, SpriteResizeMode,
+        SpriteSortMode, TextureAtlas, TextureAtlasSprite,
     };
 }
 
+#[cfg(feature = "aseprite")]
+pub use aseprite::*;
 pub use color_material::*;
 pub use dynamic_texture_atlas_builder::*;
+pub use light2d::*;
 pub use rect::*;
 pub use render::*;
 pub use sprite::*;
+pub use sprite_animation::*;
+pub use sprite_batch::*;
 pub use texture_atlas::*;
 pub use texture_atlas_builder::*;
+pub use vector_shape::*;
 
 use bevy_app::prelude::*;
 use bevy_asset::{AddAsset, Assets, Handle, HandleUntyped};
+use bevy_ecs::schedule::ParallelSystemDescriptorCoercion;
 use bevy_math::Vec2;
 use bevy_reflect::TypeUuid;
 use bevy_render::{
@@ -35,7 +51,10 @@ use bevy_render::{
     pipeline::PipelineDescriptor,
     render_graph::RenderGraph,
     shader::{asset_shader_defs_system, Shader},
+    texture::{Extent3d, Texture, TextureDimension, TextureFormat},
+    RenderSystem,
 };
+use bevy_transform::TransformSystem;
 use sprite::sprite_system;
 
 #[derive(Debug, Clone)]
@@ -45,12 +64,15 @@ pub struct SpriteSettings {
     /// # Warning
     /// This is currently experimental. It does not work correctly in all cases.
     pub frustum_culling_enabled: bool,
+    /// The order in which sprites are drawn
+    pub sort_mode: SpriteSortMode,
 }
 
 impl Default for SpriteSettings {
     fn default() -> Self {
         Self {
             frustum_culling_enabled: false,
+            sort_mode: SpriteSortMode::default(),
         }
     }
 }
@@ -67,13 +89,33 @@ impl Plugin for SpritePlugin {
             .add_asset::<TextureAtlas>()
             .register_type::<Sprite>()
             .register_type::<SpriteResizeMode>()
+            .register_type::<SpriteDrawMode>()
+            .register_type::<SpriteLayer>()
+            .register_type::<PointLight2d>()
+            .register_type::<ConeLight2d>()
+            .register_type::<SpriteNormalMap>()
+            .init_resource::<AmbientLight2d>()
+            .add_event::<SpriteAnimationEvent>()
+            .add_system_to_stage(CoreStage::PostUpdate, sprite_animation_system)
             .add_system_to_stage(CoreStage::PostUpdate, sprite_system)
+            .add_system_to_stage(CoreStage::PostUpdate, shape_mesh_system)
+            .add_system_to_stage(CoreStage::PostUpdate, normal_map_texture_system)
             .add_system_to_stage(CoreStage::PostUpdate, material_texture_detection_system)
             .add_system_to_stage(
                 CoreStage::PostUpdate,
                 asset_shader_defs_system::<ColorMaterial>,
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                sprite_batch_system
+                    .after(TransformSystem::TransformPropagate)
+                    .before(RenderSystem::VisibleEntities),
             );
 
+        #[cfg(feature = "aseprite")]
+        app.add_asset::<Aseprite>()
+            .init_asset_loader::<AsepriteLoader>();
+
         let sprite_settings = app
             .world
             .get_resource_or_insert_with(SpriteSettings::default)
@@ -101,6 +143,25 @@ impl Plugin for SpritePlugin {
             .get_resource_mut::<Assets<ColorMaterial>>()
             .unwrap();
         color_materials.set_untracked(Handle::<ColorMaterial>::default(), ColorMaterial::default());
+        let mut textures = world_cell.get_resource_mut::<Assets<Texture>>().unwrap();
+        textures.set_untracked(
+            WHITE_TEXTURE_HANDLE,
+            Texture::new_fill(
+                Extent3d::new(1, 1, 1),
+                TextureDimension::D2,
+                &[255, 255, 255, 255],
+                TextureFormat::Rgba8UnormSrgb,
+            ),
+        );
+        textures.set_untracked(
+            FLAT_NORMAL_TEXTURE_HANDLE,
+            Texture::new_fill(
+                Extent3d::new(1, 1, 1),
+                TextureDimension::D2,
+                &[128, 128, 255, 255],
+                TextureFormat::Rgba8Unorm,
+            ),
+        );
         meshes.set_untracked(
             QUAD_HANDLE,
             // Use a flipped quad because the camera is facing "forward" but quads should face
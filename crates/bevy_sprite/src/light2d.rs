@@ -0,0 +1,331 @@
+use crate::ShapePath;
+use bevy_asset::{AssetEvent, Assets, Handle, HandleUntyped};
+use bevy_core::{Pod, Zeroable};
+use bevy_ecs::{
+    component::Component,
+    event::EventReader,
+    query::Changed,
+    reflect::ReflectComponent,
+    system::{Query, ResMut},
+};
+use bevy_math::Vec3;
+use bevy_reflect::{Reflect, TypeUuid};
+use bevy_render::{
+    color::Color,
+    texture::{Texture, TextureFormat},
+};
+use bevy_transform::components::GlobalTransform;
+
+/// The most 2D lights, point and cone lights together, that light sprites at once
+pub const MAX_LIGHTS_2D: usize = 32;
+/// The most edges of [`LightOccluder2d`]s that cast shadows at once
+pub const MAX_OCCLUDER_SEGMENTS_2D: usize = 256;
+
+/// The normal map of sprites without a [`SpriteNormalMap`], facing the camera
+pub const FLAT_NORMAL_TEXTURE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Texture::TYPE_UUID, 13264815392184035916);
+
+/// The light of every sprite, that [`PointLight2d`]s and [`ConeLight2d`]s add to.
+///
+/// The default ambient light leaves sprites unchanged, lower it to make lights stand out.
+#[derive(Debug, Clone, Copy)]
+pub struct AmbientLight2d {
+    pub color: Color,
+    /// Color is premultiplied by brightness before being passed to the shader
+    pub brightness: f32,
+}
+
+impl Default for AmbientLight2d {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            brightness: 1.0,
+        }
+    }
+}
+
+/// A light shining in every direction from its position, lighting the sprites within `radius`
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct PointLight2d {
+    pub color: Color,
+    pub intensity: f32,
+    /// The distance at which the light fades out completely
+    pub radius: f32,
+    /// How far the light is in front of the sprites. Lower lights light the bumps of normal
+    /// maps at a steeper angle.
+    pub height: f32,
+    /// Whether [`LightOccluder2d`]s block this light
+    pub shadows: bool,
+}
+
+impl Default for PointLight2d {
+    fn default() -> Self {
+        PointLight2d {
+            color: Color::WHITE,
+            intensity: 1.0,
+            radius: 200.0,
+            height: 50.0,
+            shadows: false,
+        }
+    }
+}
+
+/// A light shining in a cone along its local x axis, lighting the sprites within `radius`
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct ConeLight2d {
+    pub color: Color,
+    pub intensity: f32,
+    /// The distance at which the light fades out completely
+    pub radius: f32,
+    /// How far the light is in front of the sprites. Lower lights light the bumps of normal
+    /// maps at a steeper angle.
+    pub height: f32,
+    /// The angle in radians between the direction of the light and the edge of the cone where it
+    /// starts fading out
+    pub inner_angle: f32,
+    /// The angle in radians between the direction of the light and the edge of the cone
+    pub outer_angle: f32,
+    /// Whether [`LightOccluder2d`]s block this light
+    pub shadows: bool,
+}
+
+impl Default for ConeLight2d {
+    fn default() -> Self {
+        ConeLight2d {
+            color: Color::WHITE,
+            intensity: 1.0,
+            radius: 400.0,
+            height: 50.0,
+            inner_angle: std::f32::consts::FRAC_PI_8,
+            outer_angle: std::f32::consts::FRAC_PI_6,
+            shadows: false,
+        }
+    }
+}
+
+/// An outline blocking the 2D lights with `shadows` enabled. The outline is transformed by the
+/// [`GlobalTransform`] of its entity.
+///
+/// Sprites behind the outline from a light are in its shadow, including the parts of sprites
+/// inside the outline, so outlines are usually drawn slightly inside the sprite casting them.
+#[derive(Component, Debug, Clone, Default)]
+pub struct LightOccluder2d {
+    pub path: ShapePath,
+}
+
+/// The normal map of a sprite, laid out like its texture: for sprites from a [`TextureAtlas`],
+/// the normal map is an atlas with the same layout.
+///
+/// Normal maps point their green channel up, and are read as linear textures.
+///
+/// [`TextureAtlas`]: crate::TextureAtlas
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[reflect(Component)]
+pub struct SpriteNormalMap(pub Handle<Texture>);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub(crate) struct Light2dUniform {
+    // xy: position, z: height, w: radius
+    pub pos: [f32; 4],
+    // rgb: color premultiplied by intensity, w: 1.0 when the light casts shadows
+    pub color: [f32; 4],
+    // xy: direction, z: cosine of the inner angle, w: cosine of the outer angle
+    pub cone: [f32; 4],
+}
+
+impl Light2dUniform {
+    fn new(
+        transform: &GlobalTransform,
+        color: Color,
+        intensity: f32,
+        radius: f32,
+        height: f32,
+        shadows: bool,
+        cone: [f32; 4],
+    ) -> Self {
+        let [r, g, b, _] = color.as_linear_rgba_f32();
+        Light2dUniform {
+            pos: [
+                transform.translation.x,
+                transform.translation.y,
+                height,
+                radius,
+            ],
+            color: [
+                r * intensity,
+                g * intensity,
+                b * intensity,
+                if shadows { 1.0 } else { 0.0 },
+            ],
+            cone,
+        }
+    }
+
+    pub fn from_point_light(light: &PointLight2d, transform: &GlobalTransform) -> Self {
+        // a cone that covers every direction
+        Light2dUniform::new(
+            transform,
+            light.color,
+            light.intensity,
+            light.radius,
+            light.height,
+            light.shadows,
+            [1.0, 0.0, -1.0, -1.0],
+        )
+    }
+
+    pub fn from_cone_light(light: &ConeLight2d, transform: &GlobalTransform) -> Self {
+        let direction = (transform.rotation * Vec3::X)
+            .truncate()
+            .normalize_or_zero();
+        Light2dUniform::new(
+            transform,
+            light.color,
+            light.intensity,
+            light.radius,
+            light.height,
+            light.shadows,
+            [
+                direction.x,
+                direction.y,
+                light.inner_angle.cos(),
+                light.outer_angle.cos(),
+            ],
+        )
+    }
+}
+
+/// Marks the textures of [`SpriteNormalMap`]s as linear, as they hold directions and not colors
+pub fn normal_map_texture_system(
+    mut texture_events: EventReader<AssetEvent<Texture>>,
+    mut textures: ResMut<Assets<Texture>>,
+    normal_maps: Query<&SpriteNormalMap>,
+    changed_normal_maps: Query<&SpriteNormalMap, Changed<SpriteNormalMap>>,
+) {
+    let mut handles = changed_normal_maps
+        .iter()
+        .map(|normal_map| normal_map.0.clone_weak())
+        .collect::<Vec<_>>();
+    for event in texture_events.iter() {
+        match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                if normal_maps.iter().any(|normal_map| normal_map.0 == *handle) {
+                    handles.push(handle.clone_weak());
+                }
+            }
+            AssetEvent::Removed { .. } => {}
+        }
+    }
+
+    for handle in handles.iter() {
+        let linear_format = match textures.get(handle).map(|texture| texture.format) {
+            Some(TextureFormat::Rgba8UnormSrgb) => TextureFormat::Rgba8Unorm,
+            Some(TextureFormat::Bgra8UnormSrgb) => TextureFormat::Bgra8Unorm,
+            _ => continue,
+        };
+        // only changing the format of sRGB textures, so that the asset event sent here is skipped
+        // next frame
+        textures.get_mut(handle).unwrap().format = linear_format;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin, HandleId};
+    use bevy_ecs::prelude::*;
+    use bevy_math::Quat;
+    use bevy_render::texture::{Extent3d, TextureDimension};
+    use bevy_tasks::{IoTaskPool, TaskPool};
+
+    fn assert_approx_eq(a: [f32; 4], b: [f32; 4]) {
+        assert!(
+            a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < 1e-5),
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn point_light_uniform() {
+        let light = PointLight2d {
+            color: Color::rgb_linear(1.0, 0.5, 0.0),
+            intensity: 2.0,
+            radius: 100.0,
+            height: 20.0,
+            shadows: true,
+        };
+        let transform = GlobalTransform::from_xyz(3.0, 4.0, 5.0);
+        let uniform = Light2dUniform::from_point_light(&light, &transform);
+        assert_eq!(uniform.pos, [3.0, 4.0, 20.0, 100.0]);
+        // premultiplied by the intensity
+        assert_eq!(uniform.color, [2.0, 1.0, 0.0, 1.0]);
+        assert_eq!(uniform.cone, [1.0, 0.0, -1.0, -1.0]);
+    }
+
+    #[test]
+    fn cone_light_uniform() {
+        let light = ConeLight2d {
+            inner_angle: std::f32::consts::FRAC_PI_3,
+            outer_angle: std::f32::consts::FRAC_PI_2,
+            ..Default::default()
+        };
+        // the cone points along the x axis of the light, here rotated to point up
+        let transform =
+            GlobalTransform::from_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2));
+        let uniform = Light2dUniform::from_cone_light(&light, &transform);
+        assert_approx_eq(uniform.cone, [0.0, 1.0, 0.5, 0.0]);
+        assert_eq!(uniform.color[3], 0.0);
+    }
+
+    #[test]
+    fn linear_normal_maps() {
+        let mut app = App::new();
+        app.insert_resource(IoTaskPool(TaskPool::new()))
+            .add_plugin(AssetPlugin)
+            .add_asset::<Texture>()
+            .add_system(normal_map_texture_system.system());
+        let texture = |format| {
+            Texture::new_fill(
+                Extent3d::new(1, 1, 1),
+                TextureDimension::D2,
+                &[128, 128, 255, 255],
+                format,
+            )
+        };
+        let mut textures = app.world.get_resource_mut::<Assets<Texture>>().unwrap();
+        let normal_map = textures.add(texture(TextureFormat::Rgba8UnormSrgb));
+        let color = textures.add(texture(TextureFormat::Rgba8UnormSrgb));
+        // a normal map that isn't loaded yet
+        let loading_normal_map = Handle::weak(HandleId::random::<Texture>());
+        app.world
+            .spawn()
+            .insert(SpriteNormalMap(normal_map.clone()));
+        app.world
+            .spawn()
+            .insert(SpriteNormalMap(loading_normal_map.clone()));
+        app.update();
+
+        let format = |app: &App, handle: &Handle<Texture>| {
+            let textures = app.world.get_resource::<Assets<Texture>>().unwrap();
+            textures.get(handle).unwrap().format
+        };
+        assert_eq!(format(&app, &normal_map), TextureFormat::Rgba8Unorm);
+        assert_eq!(format(&app, &color), TextureFormat::Rgba8UnormSrgb);
+
+        // converted once it's loaded, when its asset event is read
+        let mut textures = app.world.get_resource_mut::<Assets<Texture>>().unwrap();
+        let _ = textures.set(
+            loading_normal_map.clone(),
+            texture(TextureFormat::Bgra8UnormSrgb),
+        );
+        app.update();
+        app.update();
+        assert_eq!(format(&app, &loading_normal_map), TextureFormat::Bgra8Unorm);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{
+    render::uniform, AmbientLight2d, ConeLight2d, Light2dUniform, LightOccluder2d, PointLight2d,
+    MAX_LIGHTS_2D, MAX_OCCLUDER_SEGMENTS_2D,
+};
+use bevy_core::{bytes_of, cast_slice};
+use bevy_ecs::{
+    system::{BoxedSystem, ConfigurableSystem, Local, Query, Res, ResMut},
+    world::World,
+};
+use bevy_render::{
+    render_graph::{CommandQueue, Node, ResourceSlots, SystemNode},
+    renderer::{
+        BufferId, BufferInfo, BufferMapMode, BufferUsage, RenderContext, RenderResourceBinding,
+        RenderResourceBindings, RenderResourceContext,
+    },
+};
+use bevy_transform::prelude::*;
+
+/// A Render Graph [Node] that writes the 2D lights and occluders from the ECS to a GPU buffer
+#[derive(Debug, Default)]
+pub struct Lights2dNode {
+    command_queue: CommandQueue,
+}
+
+impl Node for Lights2dNode {
+    fn update(
+        &mut self,
+        _world: &World,
+        render_context: &mut dyn RenderContext,
+        _input: &ResourceSlots,
+        _output: &mut ResourceSlots,
+    ) {
+        self.command_queue.execute(render_context);
+    }
+}
+
+impl SystemNode for Lights2dNode {
+    fn get_system(&self) -> BoxedSystem {
+        let system = lights_2d_node_system.config(|config| {
+            config.0 = Some(Lights2dNodeSystemState {
+                command_queue: self.command_queue.clone(),
+                light_buffer: None,
+                staging_buffer: None,
+                occluder_segments: Vec::new(),
+            })
+        });
+        Box::new(system)
+    }
+}
+
+/// Local "lights 2d node system" state
+#[derive(Debug, Default)]
+pub struct Lights2dNodeSystemState {
+    light_buffer: Option<BufferId>,
+    staging_buffer: Option<BufferId>,
+    command_queue: CommandQueue,
+    occluder_segments: Vec<[f32; 4]>,
+}
+
+pub fn lights_2d_node_system(
+    mut state: Local<Lights2dNodeSystemState>,
+    render_resource_context: Res<Box<dyn RenderResourceContext>>,
+    ambient_light: Res<AmbientLight2d>,
+    mut render_resource_bindings: ResMut<RenderResourceBindings>,
+    point_lights: Query<(&PointLight2d, &GlobalTransform)>,
+    cone_lights: Query<(&ConeLight2d, &GlobalTransform)>,
+    occluders: Query<(&LightOccluder2d, &GlobalTransform)>,
+) {
+    let state = &mut state;
+    let render_resource_context = &**render_resource_context;
+
+    // premultiply ambient brightness
+    let [r, g, b, _] = ambient_light.color.as_linear_rgba_f32();
+    let brightness = ambient_light.brightness;
+    let ambient_light = [r * brightness, g * brightness, b * brightness, 1.0];
+    let ambient_light_size = std::mem::size_of::<[f32; 4]>();
+    let light_count_size = ambient_light_size + std::mem::size_of::<[u32; 4]>();
+
+    let mut lights = point_lights
+        .iter()
+        .map(|(light, transform)| Light2dUniform::from_point_light(light, transform))
+        .chain(
+            cone_lights
+                .iter()
+                .map(|(light, transform)| Light2dUniform::from_cone_light(light, transform)),
+        )
+        .take(MAX_LIGHTS_2D);
+    let light_size = std::mem::size_of::<Light2dUniform>();
+    let light_array_max_size = light_size * MAX_LIGHTS_2D;
+
+    state.occluder_segments.clear();
+    for (occluder, transform) in occluders.iter() {
+        let matrix = transform.compute_matrix();
+        state
+            .occluder_segments
+            .extend(occluder.path.segments().map(|(start, end)| {
+                let start = matrix.transform_point3(start.extend(0.0));
+                let end = matrix.transform_point3(end.extend(0.0));
+                [start.x, start.y, end.x, end.y]
+            }));
+    }
+    state.occluder_segments.truncate(MAX_OCCLUDER_SEGMENTS_2D);
+    let segment_size = std::mem::size_of::<[f32; 4]>();
+
+    let light_uniform_start = light_count_size;
+    let segment_uniform_start = light_count_size + light_array_max_size;
+    let segment_uniform_end = segment_uniform_start + segment_size * state.occluder_segments.len();
+    let max_light_uniform_size = segment_uniform_start + segment_size * MAX_OCCLUDER_SEGMENTS_2D;
+
+    if let Some(staging_buffer) = state.staging_buffer {
+        render_resource_context.map_buffer(staging_buffer, BufferMapMode::Write);
+    } else {
+        let buffer = render_resource_context.create_buffer(BufferInfo {
+            size: max_light_uniform_size,
+            buffer_usage: BufferUsage::UNIFORM | BufferUsage::COPY_SRC | BufferUsage::COPY_DST,
+            ..Default::default()
+        });
+        render_resource_bindings.set(
+            uniform::LIGHTS_2D,
+            RenderResourceBinding::Buffer {
+                buffer,
+                range: 0..max_light_uniform_size as u64,
+                dynamic_index: None,
+            },
+        );
+        state.light_buffer = Some(buffer);
+
+        let staging_buffer = render_resource_context.create_buffer(BufferInfo {
+            size: max_light_uniform_size,
+            buffer_usage: BufferUsage::COPY_SRC | BufferUsage::MAP_WRITE,
+            mapped_at_creation: true,
+        });
+        state.staging_buffer = Some(staging_buffer);
+    }
+
+    let staging_buffer = state.staging_buffer.unwrap();
+    let occluder_segments = &state.occluder_segments;
+    render_resource_context.write_mapped_buffer(
+        staging_buffer,
+        0..max_light_uniform_size as u64,
+        &mut |data, _renderer| {
+            // ambient light
+            data[0..ambient_light_size].copy_from_slice(bytes_of(&ambient_light));
+
+            // light array
+            let mut light_count = 0;
+            for (light, slot) in lights
+                .by_ref()
+                .zip(data[light_uniform_start..segment_uniform_start].chunks_exact_mut(light_size))
+            {
+                slot.copy_from_slice(bytes_of(&light));
+                light_count += 1;
+            }
+
+            // light and occluder segment count
+            data[ambient_light_size..light_count_size].copy_from_slice(bytes_of(&[
+                light_count as u32,
+                occluder_segments.len() as u32,
+                0,
+                0,
+            ]));
+
+            // occluder segment array
+            data[segment_uniform_start..segment_uniform_end]
+                .copy_from_slice(cast_slice(occluder_segments));
+        },
+    );
+    render_resource_context.unmap_buffer(staging_buffer);
+    let light_buffer = state.light_buffer.unwrap();
+    state.command_queue.copy_buffer_to_buffer(
+        staging_buffer,
+        0,
+        light_buffer,
+        0,
+        max_light_uniform_size as u64,
+    );
+}
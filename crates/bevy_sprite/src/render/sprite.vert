@@ -16,6 +16,7 @@ layout(set = 2, binding = 0) uniform Transform {
 layout(set = 2, binding = 1) uniform Sprite {
     vec2 size;
     uint flip;
+    vec2 anchor;
 };
 
 void main() {
@@ -39,6 +40,15 @@ void main() {
 
     v_Uv = uv;
 
-    vec3 position = Vertex_Position * vec3(size, 1.0);
+    // The anchor is flipped with the sprite, so that it stays on the same point of the texture
+    vec2 sprite_anchor = anchor;
+    if ((flip & x_flip_bit) == x_flip_bit) {
+        sprite_anchor.x = -sprite_anchor.x;
+    }
+    if ((flip & y_flip_bit) == y_flip_bit) {
+        sprite_anchor.y = -sprite_anchor.y;
+    }
+
+    vec3 position = (Vertex_Position - vec3(sprite_anchor, 0.0)) * vec3(size, 1.0);
     gl_Position = ViewProj * Model * vec4(position, 1.0);
 }
@@ -0,0 +1,100 @@
+#version 450
+
+const int MAX_LIGHTS = 32;
+const int MAX_OCCLUDER_SEGMENTS = 256;
+
+struct Light2d {
+    // xy: position, z: height, w: radius
+    vec4 pos;
+    // rgb: color premultiplied by intensity, w: 1.0 when the light casts shadows
+    vec4 color;
+    // xy: direction, z: cosine of the inner angle, w: cosine of the outer angle
+    vec4 cone;
+};
+
+layout(location = 0) in vec2 v_Uv;
+layout(location = 1) in vec4 v_Color;
+layout(location = 2) in vec2 v_Position;
+// xy: the direction of the x axis of the texture, zw: the direction of its y axis
+layout(location = 3) in vec4 v_Tangent;
+
+layout(location = 0) out vec4 o_Target;
+
+layout(set = 1, binding = 0) uniform texture2D SpriteBatch_texture;
+layout(set = 1, binding = 1) uniform sampler SpriteBatch_texture_sampler;
+layout(set = 1, binding = 2) uniform texture2D SpriteBatch_normal_map;
+layout(set = 1, binding = 3) uniform sampler SpriteBatch_normal_map_sampler;
+
+layout(set = 2, binding = 0) uniform Lights2d {
+    vec4 AmbientColor;
+    // x: number of lights, y: number of occluder segments
+    uvec4 NumLightsAndSegments;
+    Light2d Lights[MAX_LIGHTS];
+    // xy: start, zw: end
+    vec4 OccluderSegments[MAX_OCCLUDER_SEGMENTS];
+};
+
+bool intersects(vec2 from, vec2 to, vec4 segment) {
+    vec2 r = to - from;
+    vec2 s = segment.zw - segment.xy;
+    float denominator = r.x * s.y - r.y * s.x;
+    if (abs(denominator) < 1e-6) {
+        return false;
+    }
+    vec2 offset = segment.xy - from;
+    float t = (offset.x * s.y - offset.y * s.x) / denominator;
+    float u = (offset.x * r.y - offset.y * r.x) / denominator;
+    return t > 0.0 && t < 1.0 && u >= 0.0 && u <= 1.0;
+}
+
+bool in_shadow(vec2 from, vec2 to) {
+    for (int i = 0; i < int(NumLightsAndSegments.y) && i < MAX_OCCLUDER_SEGMENTS; ++i) {
+        if (intersects(from, to, OccluderSegments[i])) {
+            return true;
+        }
+    }
+    return false;
+}
+
+void main() {
+    vec4 color = v_Color * texture(
+        sampler2D(SpriteBatch_texture, SpriteBatch_texture_sampler),
+        v_Uv);
+
+    vec3 tangent_normal = texture(
+        sampler2D(SpriteBatch_normal_map, SpriteBatch_normal_map_sampler),
+        v_Uv).rgb * 2.0 - 1.0;
+    vec3 normal = normalize(vec3(
+        v_Tangent.xy * tangent_normal.x + v_Tangent.zw * tangent_normal.y,
+        tangent_normal.z));
+
+    vec3 light = AmbientColor.rgb;
+    for (int i = 0; i < int(NumLightsAndSegments.x) && i < MAX_LIGHTS; ++i) {
+        Light2d l = Lights[i];
+        vec2 to_light = l.pos.xy - v_Position;
+        float distance = length(to_light);
+        if (distance >= l.pos.w) {
+            continue;
+        }
+        float attenuation = 1.0 - distance / l.pos.w;
+        attenuation *= attenuation;
+        // point lights have a cone covering every direction
+        if (distance > 0.0 && l.cone.w > -1.0) {
+            float cos_angle = dot(-to_light / distance, l.cone.xy);
+            attenuation *= l.cone.z > l.cone.w
+                ? smoothstep(l.cone.w, l.cone.z, cos_angle)
+                : step(l.cone.w, cos_angle);
+        }
+        vec3 light_direction = normalize(vec3(to_light, l.pos.z));
+        float diffuse = max(dot(normal, light_direction), 0.0);
+        if (attenuation * diffuse <= 0.0) {
+            continue;
+        }
+        if (l.color.w > 0.0 && in_shadow(v_Position, l.pos.xy)) {
+            continue;
+        }
+        light += l.color.rgb * attenuation * diffuse;
+    }
+
+    o_Target = vec4(color.rgb * light, color.a);
+}
@@ -0,0 +1,23 @@
+#version 450
+
+layout(location = 0) in vec3 Vertex_Position;
+layout(location = 1) in vec2 Vertex_Uv;
+layout(location = 2) in vec4 Vertex_Color;
+layout(location = 3) in vec4 Vertex_Tangent;
+
+layout(location = 0) out vec2 v_Uv;
+layout(location = 1) out vec4 v_Color;
+layout(location = 2) out vec2 v_Position;
+layout(location = 3) out vec4 v_Tangent;
+
+layout(set = 0, binding = 0) uniform CameraViewProj {
+    mat4 ViewProj;
+};
+
+void main() {
+    v_Uv = Vertex_Uv;
+    v_Color = Vertex_Color;
+    v_Position = Vertex_Position.xy;
+    v_Tangent = Vertex_Tangent;
+    gl_Position = ViewProj * vec4(Vertex_Position, 1.0);
+}
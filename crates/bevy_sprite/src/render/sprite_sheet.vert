@@ -34,12 +34,12 @@ layout(set = 2, binding = 1) uniform TextureAtlasSprite {
     vec4 color;
     uint index;
     uint flip;
+    vec2 anchor;
 };
 
 void main() {
     Rect sprite_rect = Textures[index];
     vec2 sprite_dimensions = sprite_rect.end - sprite_rect.begin;
-    vec3 vertex_position = vec3(Vertex_Position.xy * sprite_dimensions, 0.0);
 
     // Specify the corners of the sprite
     vec2 bottom_left = vec2(sprite_rect.begin.x, sprite_rect.end.y);
@@ -71,6 +71,16 @@ void main() {
         top_right = tmp;
     }
 
+    // The anchor is flipped with the sprite, so that it stays on the same point of the texture
+    vec2 sprite_anchor = anchor;
+    if ((flip & x_flip_bit) == x_flip_bit) {
+        sprite_anchor.x = -sprite_anchor.x;
+    }
+    if ((flip & y_flip_bit) == y_flip_bit) {
+        sprite_anchor.y = -sprite_anchor.y;
+    }
+    vec3 vertex_position = vec3((Vertex_Position.xy - sprite_anchor) * sprite_dimensions, 0.0);
+
     vec2 atlas_positions[4] = vec2[](
         bottom_left,
         top_left,
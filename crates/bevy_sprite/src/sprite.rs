@@ -1,4 +1,4 @@
-use crate::ColorMaterial;
+use crate::{ColorMaterial, Rect};
 use bevy_asset::{Assets, Handle};
 use bevy_core::Bytes;
 use bevy_ecs::{
@@ -27,6 +27,10 @@ pub struct Sprite {
     /// When true flips sprite upside down. [Example](https://github.com/bevyengine/bevy/blob/latest/examples/2d/sprite_flipping.rs)
     pub flip_y: bool,
     pub resize_mode: SpriteResizeMode,
+    /// The point of the sprite placed at its translation
+    pub anchor: Anchor,
+    /// How the texture fills the size of the sprite
+    pub draw_mode: SpriteDrawMode,
 }
 
 impl RenderResource for Sprite {
@@ -35,19 +39,25 @@ impl RenderResource for Sprite {
     }
 
     fn buffer_byte_len(&self) -> Option<usize> {
-        Some(12)
+        Some(24)
     }
 
     fn write_buffer_bytes(&self, buffer: &mut [u8]) {
         // Write the size buffer
-        let (size_buf, flip_buf) = buffer.split_at_mut(8);
+        let (size_buf, rest) = buffer.split_at_mut(8);
         self.size.write_bytes(size_buf);
 
         // First bit means flip x, second bit means flip y
+        let (flip_buf, rest) = rest.split_at_mut(4);
         flip_buf[0] = if self.flip_x { 0b01 } else { 0 } | if self.flip_y { 0b10 } else { 0 };
         flip_buf[1] = 0;
         flip_buf[2] = 0;
         flip_buf[3] = 0;
+
+        // The anchor is aligned to 8 bytes
+        let (padding_buf, anchor_buf) = rest.split_at_mut(4);
+        padding_buf.fill(0);
+        self.anchor.as_vec().write_bytes(anchor_buf);
     }
 
     fn texture(&self) -> Option<&Handle<Texture>> {
@@ -69,6 +79,198 @@ impl Default for SpriteResizeMode {
     }
 }
 
+/// The point of a sprite that is placed at the translation of its transform, and that the sprite
+/// rotates and scales around. When a sprite is flipped, its anchor is flipped with it, so that the
+/// anchor stays on the same point of the texture.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
+#[reflect_value(PartialEq, Serialize, Deserialize)]
+pub enum Anchor {
+    #[default]
+    Center,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+    CenterLeft,
+    CenterRight,
+    TopLeft,
+    TopCenter,
+    TopRight,
+    /// A point relative to the size of the sprite, from `(-0.5, -0.5)` at its bottom left corner
+    /// to `(0.5, 0.5)` at its top right corner
+    Custom(Vec2),
+}
+
+/// The borders of a texture, in pixels, that keep their size when a [`SpriteDrawMode::Sliced`]
+/// sprite is resized
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SliceBorder {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl SliceBorder {
+    /// The same border on every side
+    pub fn all(border: f32) -> Self {
+        SliceBorder {
+            left: border,
+            right: border,
+            top: border,
+            bottom: border,
+        }
+    }
+}
+
+/// How the texture of a sprite fills the size of the sprite. The texture of sprites from a
+/// [`TextureAtlas`](crate::TextureAtlas) is their section of the atlas.
+///
+/// Only batched sprites, without [`RenderPipelines`](bevy_render::pipeline::RenderPipelines),
+/// are drawn sliced or tiled. As sprites with [`SpriteResizeMode::Automatic`] keep the size of
+/// their texture, sliced and tiled sprites are usually created with [`Sprite::new`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
+#[reflect_value(PartialEq, Serialize, Deserialize)]
+pub enum SpriteDrawMode {
+    /// The texture is stretched to the size of the sprite
+    #[default]
+    Simple,
+    /// The texture is split in nine by the border: the corners keep their size in pixels, with one
+    /// world unit per pixel, the edges are stretched along the side of the sprite and the center
+    /// is stretched both ways. When the sprite is smaller than its borders, the borders shrink.
+    Sliced(SliceBorder),
+    /// The texture is repeated to fill the sprite, in tiles of the given size in world units
+    /// starting from the bottom left corner of the sprite. The tiles on the top and right edges
+    /// are cut.
+    Tiled(Vec2),
+}
+
+impl SpriteDrawMode {
+    /// Splits a sprite of `size` into the quads drawing it, as the rectangle of each quad from the
+    /// bottom left corner of the sprite and the section of the texture drawn on it. `uv` is the
+    /// section of the texture drawn on the whole sprite, with `texture_size` pixels.
+    pub(crate) fn quads(
+        &self,
+        size: Vec2,
+        uv: Rect,
+        texture_size: Vec2,
+        mut quad: impl FnMut(Rect, Rect),
+    ) {
+        let uv_size = uv.max - uv.min;
+        match *self {
+            SpriteDrawMode::Sliced(border) => {
+                let scale = |start: f32, end: f32, length: f32| {
+                    if start + end > length {
+                        length / (start + end)
+                    } else {
+                        1.0
+                    }
+                };
+                let scale_x = scale(border.left, border.right, size.x);
+                let scale_y = scale(border.bottom, border.top, size.y);
+                let xs = [
+                    0.0,
+                    border.left * scale_x,
+                    size.x - border.right * scale_x,
+                    size.x,
+                ];
+                let ys = [
+                    0.0,
+                    border.bottom * scale_y,
+                    size.y - border.top * scale_y,
+                    size.y,
+                ];
+                let texture_size = texture_size.max(Vec2::ONE);
+                let us = [
+                    uv.min.x,
+                    uv.min.x + border.left / texture_size.x * uv_size.x,
+                    uv.max.x - border.right / texture_size.x * uv_size.x,
+                    uv.max.x,
+                ];
+                // the v coordinate goes down the texture
+                let vs = [
+                    uv.max.y,
+                    uv.max.y - border.bottom / texture_size.y * uv_size.y,
+                    uv.min.y + border.top / texture_size.y * uv_size.y,
+                    uv.min.y,
+                ];
+                for row in 0..3 {
+                    for column in 0..3 {
+                        if xs[column] >= xs[column + 1] || ys[row] >= ys[row + 1] {
+                            continue;
+                        }
+                        quad(
+                            Rect {
+                                min: Vec2::new(xs[column], ys[row]),
+                                max: Vec2::new(xs[column + 1], ys[row + 1]),
+                            },
+                            Rect {
+                                min: Vec2::new(us[column], vs[row + 1]),
+                                max: Vec2::new(us[column + 1], vs[row]),
+                            },
+                        );
+                    }
+                }
+            }
+            SpriteDrawMode::Tiled(tile_size) if tile_size.x > 0.0 && tile_size.y > 0.0 => {
+                let tiles = (size / tile_size).ceil();
+                for row in 0..tiles.y as u32 {
+                    for column in 0..tiles.x as u32 {
+                        let min = Vec2::new(column as f32, row as f32) * tile_size;
+                        let max = (min + tile_size).min(size);
+                        // the fraction of the tile left on the top and right edges
+                        let fraction = (max - min) / tile_size;
+                        quad(
+                            Rect { min, max },
+                            Rect {
+                                min: Vec2::new(uv.min.x, uv.max.y - fraction.y * uv_size.y),
+                                max: Vec2::new(uv.min.x + fraction.x * uv_size.x, uv.max.y),
+                            },
+                        );
+                    }
+                }
+            }
+            SpriteDrawMode::Simple | SpriteDrawMode::Tiled(_) => quad(
+                Rect {
+                    min: Vec2::ZERO,
+                    max: size,
+                },
+                uv,
+            ),
+        }
+    }
+}
+
+impl Anchor {
+    /// The point relative to the size of the sprite, from `(-0.5, -0.5)` at its bottom left corner
+    /// to `(0.5, 0.5)` at its top right corner
+    pub fn as_vec(&self) -> Vec2 {
+        match self {
+            Anchor::Center => Vec2::new(0.0, 0.0),
+            Anchor::BottomLeft => Vec2::new(-0.5, -0.5),
+            Anchor::BottomCenter => Vec2::new(0.0, -0.5),
+            Anchor::BottomRight => Vec2::new(0.5, -0.5),
+            Anchor::CenterLeft => Vec2::new(-0.5, 0.0),
+            Anchor::CenterRight => Vec2::new(0.5, 0.0),
+            Anchor::TopLeft => Vec2::new(-0.5, 0.5),
+            Anchor::TopCenter => Vec2::new(0.0, 0.5),
+            Anchor::TopRight => Vec2::new(0.5, 0.5),
+            Anchor::Custom(point) => *point,
+        }
+    }
+
+    /// The offset of the center of a sprite of `size` from its anchor
+    pub(crate) fn center_offset(&self, size: Vec2, flip_x: bool, flip_y: bool) -> Vec2 {
+        let mut anchor = self.as_vec();
+        if flip_x {
+            anchor.x = -anchor.x;
+        }
+        if flip_y {
+            anchor.y = -anchor.y;
+        }
+        -anchor * size
+    }
+}
+
 impl Sprite {
     /// Creates new `Sprite` with `SpriteResizeMode::Manual` value for `resize_mode`
     pub fn new(size: Vec2) -> Self {
@@ -77,6 +279,8 @@ impl Sprite {
             resize_mode: SpriteResizeMode::Manual,
             flip_x: false,
             flip_y: false,
+            anchor: Anchor::default(),
+            draw_mode: SpriteDrawMode::default(),
         }
     }
 }
@@ -105,3 +309,164 @@ pub fn sprite_system(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anchor_offsets() {
+        let size = Vec2::new(40.0, 20.0);
+        assert_eq!(Anchor::Center.center_offset(size, false, false), Vec2::ZERO);
+        assert_eq!(
+            Anchor::BottomLeft.center_offset(size, false, false),
+            Vec2::new(20.0, 10.0)
+        );
+        assert_eq!(
+            Anchor::TopCenter.center_offset(size, false, false),
+            Vec2::new(0.0, -10.0)
+        );
+        assert_eq!(
+            Anchor::Custom(Vec2::new(0.25, -0.5)).center_offset(size, false, false),
+            Vec2::new(-10.0, 10.0)
+        );
+    }
+
+    #[test]
+    fn flipped_anchor_offsets() {
+        // the anchor stays on the same point of the flipped texture
+        let size = Vec2::new(40.0, 20.0);
+        let anchor = Anchor::BottomLeft;
+        assert_eq!(
+            anchor.center_offset(size, true, false),
+            Vec2::new(-20.0, 10.0)
+        );
+        assert_eq!(
+            anchor.center_offset(size, false, true),
+            Vec2::new(20.0, -10.0)
+        );
+        assert_eq!(
+            anchor.center_offset(size, true, true),
+            Vec2::new(-20.0, -10.0)
+        );
+        assert_eq!(
+            Anchor::CenterRight.center_offset(size, false, true),
+            Vec2::new(-20.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn sprite_buffer_anchor() {
+        let sprite = Sprite {
+            flip_y: true,
+            anchor: Anchor::TopRight,
+            ..Sprite::new(Vec2::new(2.0, 3.0))
+        };
+        let mut buffer = vec![0xff; sprite.buffer_byte_len().unwrap()];
+        sprite.write_buffer_bytes(&mut buffer);
+        let float = |offset: usize| {
+            f32::from_le_bytes([
+                buffer[offset],
+                buffer[offset + 1],
+                buffer[offset + 2],
+                buffer[offset + 3],
+            ])
+        };
+        assert_eq!((float(0), float(4)), (2.0, 3.0));
+        assert_eq!(&buffer[8..16], &[0b10, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!((float(16), float(20)), (0.5, 0.5));
+    }
+
+    type Quad = ([f32; 4], [f32; 4]);
+
+    /// The rectangles and texture sections of the quads of a sprite drawing its whole texture
+    fn draw_quads(draw_mode: SpriteDrawMode, size: Vec2, texture_size: Vec2) -> Vec<Quad> {
+        let uv = Rect {
+            min: Vec2::ZERO,
+            max: Vec2::ONE,
+        };
+        let mut quads = Vec::new();
+        draw_mode.quads(size, uv, texture_size, |rect, uv| {
+            quads.push((corners(rect), corners(uv)))
+        });
+        quads
+    }
+
+    fn corners(rect: Rect) -> [f32; 4] {
+        [rect.min.x, rect.min.y, rect.max.x, rect.max.y]
+    }
+
+    fn rect(min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> [f32; 4] {
+        [min_x, min_y, max_x, max_y]
+    }
+
+    #[test]
+    fn simple_quad() {
+        let size = Vec2::new(30.0, 20.0);
+        let quads = draw_quads(SpriteDrawMode::Simple, size, Vec2::splat(16.0));
+        assert_eq!(
+            quads,
+            vec![(rect(0.0, 0.0, 30.0, 20.0), rect(0.0, 0.0, 1.0, 1.0))]
+        );
+    }
+
+    #[test]
+    fn sliced_quads() {
+        let draw_mode = SpriteDrawMode::Sliced(SliceBorder::all(5.0));
+        let quads = draw_quads(draw_mode, Vec2::new(100.0, 50.0), Vec2::splat(20.0));
+        assert_eq!(quads.len(), 9);
+        // the bottom left corner keeps its size and is drawn with the bottom left of the texture
+        assert_eq!(
+            quads[0],
+            (rect(0.0, 0.0, 5.0, 5.0), rect(0.0, 0.75, 0.25, 1.0))
+        );
+        // the center is stretched
+        assert_eq!(
+            quads[4],
+            (rect(5.0, 5.0, 95.0, 45.0), rect(0.25, 0.25, 0.75, 0.75))
+        );
+        assert_eq!(
+            quads[8],
+            (rect(95.0, 45.0, 100.0, 50.0), rect(0.75, 0.0, 1.0, 0.25))
+        );
+    }
+
+    #[test]
+    fn sliced_quads_smaller_than_borders() {
+        // the borders shrink to fit, leaving no room for the middle column
+        let draw_mode = SpriteDrawMode::Sliced(SliceBorder::all(5.0));
+        let quads = draw_quads(draw_mode, Vec2::new(6.0, 50.0), Vec2::splat(20.0));
+        assert_eq!(quads.len(), 6);
+        assert_eq!(
+            quads[0],
+            (rect(0.0, 0.0, 3.0, 5.0), rect(0.0, 0.75, 0.25, 1.0))
+        );
+        assert_eq!(quads[1].0, rect(3.0, 0.0, 6.0, 5.0));
+    }
+
+    #[test]
+    fn tiled_quads() {
+        let draw_mode = SpriteDrawMode::Tiled(Vec2::new(10.0, 20.0));
+        let quads = draw_quads(draw_mode, Vec2::new(25.0, 20.0), Vec2::splat(16.0));
+        assert_eq!(
+            quads,
+            vec![
+                (rect(0.0, 0.0, 10.0, 20.0), rect(0.0, 0.0, 1.0, 1.0)),
+                (rect(10.0, 0.0, 20.0, 20.0), rect(0.0, 0.0, 1.0, 1.0)),
+                // the last tile is cut
+                (rect(20.0, 0.0, 25.0, 20.0), rect(0.0, 0.0, 0.5, 1.0)),
+            ]
+        );
+
+        let quads = draw_quads(draw_mode, Vec2::new(10.0, 30.0), Vec2::splat(16.0));
+        assert_eq!(
+            quads[1],
+            (rect(0.0, 20.0, 10.0, 30.0), rect(0.0, 0.5, 1.0, 1.0))
+        );
+
+        // tiles without a size draw the texture once
+        let draw_mode = SpriteDrawMode::Tiled(Vec2::ZERO);
+        let quads = draw_quads(draw_mode, Vec2::new(25.0, 20.0), Vec2::splat(16.0));
+        assert_eq!(quads.len(), 1);
+    }
+}
@@ -0,0 +1,391 @@
+use crate::TextureAtlasSprite;
+use bevy_core::Time;
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    event::EventWriter,
+    system::{Local, Query, Res},
+};
+use bevy_utils::{Duration, HashMap};
+
+/// How a [`SpriteAnimationClip`] continues after its last frame
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationMode {
+    /// Stops on the last frame
+    Once,
+    /// Starts over from the first frame
+    #[default]
+    Loop,
+    /// Plays backward to the first frame, then forward again
+    PingPong,
+}
+
+/// A frame of a [`SpriteAnimationClip`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpriteAnimationFrame {
+    /// The index of the frame in the [`TextureAtlas`](crate::TextureAtlas)
+    pub index: u32,
+    pub duration: Duration,
+}
+
+/// A sequence of frames played by a [`SpriteAnimation`]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SpriteAnimationClip {
+    pub frames: Vec<SpriteAnimationFrame>,
+    pub mode: AnimationMode,
+}
+
+impl SpriteAnimationClip {
+    pub fn new(frames: Vec<SpriteAnimationFrame>) -> Self {
+        SpriteAnimationClip {
+            frames,
+            mode: AnimationMode::default(),
+        }
+    }
+
+    /// Creates a clip of the atlas textures at `indices`, each shown for `frame_duration`
+    pub fn from_indices(indices: impl IntoIterator<Item = u32>, frame_duration: Duration) -> Self {
+        Self::new(
+            indices
+                .into_iter()
+                .map(|index| SpriteAnimationFrame {
+                    index,
+                    duration: frame_duration,
+                })
+                .collect(),
+        )
+    }
+
+    pub fn with_mode(mut self, mode: AnimationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// The time it takes to play every frame once
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(|frame| frame.duration).sum()
+    }
+}
+
+/// Plays named [`SpriteAnimationClip`]s on the [`TextureAtlasSprite`] of an entity, by setting
+/// its index to the index of the current frame.
+/// [Example](https://github.com/bevyengine/bevy/blob/latest/examples/2d/sprite_sheet.rs)
+#[derive(Component, Debug, Clone)]
+pub struct SpriteAnimation {
+    clips: HashMap<String, SpriteAnimationClip>,
+    current_clip: Option<String>,
+    frame: usize,
+    elapsed: Duration,
+    backward: bool,
+    finished: bool,
+    /// Multiplies the duration of the frames. Values above 1.0 play faster.
+    pub speed: f32,
+    pub paused: bool,
+}
+
+impl Default for SpriteAnimation {
+    fn default() -> Self {
+        SpriteAnimation {
+            clips: Default::default(),
+            current_clip: None,
+            frame: 0,
+            elapsed: Duration::default(),
+            backward: false,
+            finished: false,
+            speed: 1.0,
+            paused: false,
+        }
+    }
+}
+
+impl SpriteAnimation {
+    /// Creates an animation playing `clip`
+    pub fn new(name: impl Into<String>, clip: SpriteAnimationClip) -> Self {
+        let name = name.into();
+        let mut animation = SpriteAnimation::default().with_clip(name.clone(), clip);
+        animation.play(name);
+        animation
+    }
+
+    pub fn with_clip(mut self, name: impl Into<String>, clip: SpriteAnimationClip) -> Self {
+        self.add_clip(name, clip);
+        self
+    }
+
+    /// Adds a clip, replacing the clip with the same name
+    pub fn add_clip(&mut self, name: impl Into<String>, clip: SpriteAnimationClip) {
+        self.clips.insert(name.into(), clip);
+    }
+
+    pub fn clip(&self, name: &str) -> Option<&SpriteAnimationClip> {
+        self.clips.get(name)
+    }
+
+    /// Plays the clip `name` from its first frame, unless it is already playing
+    pub fn play(&mut self, name: impl Into<String>) {
+        let name = name.into();
+        if self.current_clip.as_ref() != Some(&name) {
+            self.current_clip = Some(name);
+            self.restart();
+        }
+    }
+
+    /// Plays the current clip again from its first frame
+    pub fn restart(&mut self) {
+        self.frame = 0;
+        self.elapsed = Duration::default();
+        self.backward = false;
+        self.finished = false;
+    }
+
+    /// Stops playing the current clip. The sprite keeps its current index.
+    pub fn stop(&mut self) {
+        self.current_clip = None;
+        self.restart();
+    }
+
+    pub fn current_clip(&self) -> Option<&str> {
+        self.current_clip.as_deref()
+    }
+
+    /// The index of the current frame in the current clip
+    pub fn current_frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Whether the current clip is played with [`AnimationMode::Once`] and reached its end
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Advances the current clip by `delta`, pushing to `events` when it finishes or loops, and
+    /// returns the atlas index of the current frame
+    fn tick(&mut self, delta: Duration, events: &mut Vec<SpriteAnimationEventKind>) -> Option<u32> {
+        let clip = self.clips.get(self.current_clip.as_ref()?)?;
+        if clip.frames.is_empty() {
+            return None;
+        }
+        self.frame = self.frame.min(clip.frames.len() - 1);
+        if self.paused || self.finished || clip.duration() == Duration::default() {
+            return Some(clip.frames[self.frame].index);
+        }
+
+        // scaled in nanoseconds, as `Duration::mul_f32` would lose the end of frames to rounding
+        let speed = self.speed.max(0.0) as f64;
+        self.elapsed += Duration::from_nanos((delta.as_nanos() as f64 * speed).round() as u64);
+        let last = clip.frames.len() - 1;
+        while self.elapsed >= clip.frames[self.frame].duration {
+            self.elapsed -= clip.frames[self.frame].duration;
+            if self.backward {
+                if self.frame > 0 {
+                    self.frame -= 1;
+                } else {
+                    self.backward = false;
+                    self.frame = last.min(1);
+                    events.push(SpriteAnimationEventKind::Looped);
+                }
+            } else if self.frame < last {
+                self.frame += 1;
+            } else {
+                match clip.mode {
+                    AnimationMode::Once => {
+                        self.finished = true;
+                        self.elapsed = Duration::default();
+                        events.push(SpriteAnimationEventKind::Finished);
+                        break;
+                    }
+                    AnimationMode::Loop => {
+                        self.frame = 0;
+                        events.push(SpriteAnimationEventKind::Looped);
+                    }
+                    AnimationMode::PingPong => {
+                        self.backward = true;
+                        self.frame = last.saturating_sub(1);
+                    }
+                }
+            }
+        }
+        Some(clip.frames[self.frame].index)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpriteAnimationEventKind {
+    /// A clip played with [`AnimationMode::Once`] reached the end of its last frame
+    Finished,
+    /// A looping clip started over from its first frame
+    Looped,
+}
+
+/// An event sent when the clip of a [`SpriteAnimation`] finishes or loops
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteAnimationEvent {
+    pub entity: Entity,
+    pub clip: String,
+    pub kind: SpriteAnimationEventKind,
+}
+
+pub fn sprite_animation_system(
+    time: Res<Time>,
+    mut events: EventWriter<SpriteAnimationEvent>,
+    mut animation_events: Local<Vec<SpriteAnimationEventKind>>,
+    mut query: Query<(Entity, &mut SpriteAnimation, &mut TextureAtlasSprite)>,
+) {
+    for (entity, mut animation, mut sprite) in query.iter_mut() {
+        if animation.current_clip.is_none() {
+            continue;
+        }
+        animation_events.clear();
+        if let Some(index) = animation.tick(time.delta(), &mut animation_events) {
+            if sprite.index != index {
+                sprite.index = index;
+            }
+        }
+        for &kind in animation_events.iter() {
+            events.send(SpriteAnimationEvent {
+                entity,
+                clip: animation.current_clip.clone().unwrap_or_default(),
+                kind,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{event::Events, prelude::*};
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    /// Ticks `animation` by `delta`, returning the current frame and the sent events
+    fn tick(
+        animation: &mut SpriteAnimation,
+        delta: Duration,
+    ) -> (Option<usize>, Vec<SpriteAnimationEventKind>) {
+        let mut events = Vec::new();
+        let index = animation.tick(delta, &mut events);
+        (index.map(|index| index as usize), events)
+    }
+
+    fn walk(mode: AnimationMode) -> SpriteAnimation {
+        SpriteAnimation::new(
+            "walk",
+            SpriteAnimationClip::from_indices(0..3, ms(100)).with_mode(mode),
+        )
+    }
+
+    #[test]
+    fn loop_frames() {
+        let mut animation = walk(AnimationMode::Loop);
+        assert_eq!(tick(&mut animation, ms(0)), (Some(0), vec![]));
+        assert_eq!(tick(&mut animation, ms(99)), (Some(0), vec![]));
+        assert_eq!(tick(&mut animation, ms(1)), (Some(1), vec![]));
+        // several frames in a single tick
+        assert_eq!(
+            tick(&mut animation, ms(250)),
+            (Some(0), vec![SpriteAnimationEventKind::Looped])
+        );
+        assert_eq!(tick(&mut animation, ms(50)), (Some(1), vec![]));
+        assert_eq!(animation.current_frame(), 1);
+        assert!(!animation.is_finished());
+    }
+
+    #[test]
+    fn play_once() {
+        let mut animation = walk(AnimationMode::Once);
+        assert_eq!(tick(&mut animation, ms(200)), (Some(2), vec![]));
+        assert_eq!(
+            tick(&mut animation, ms(500)),
+            (Some(2), vec![SpriteAnimationEventKind::Finished])
+        );
+        assert!(animation.is_finished());
+        assert_eq!(tick(&mut animation, ms(500)), (Some(2), vec![]));
+
+        animation.restart();
+        assert!(!animation.is_finished());
+        assert_eq!(tick(&mut animation, ms(0)), (Some(0), vec![]));
+    }
+
+    #[test]
+    fn ping_pong() {
+        let mut animation = walk(AnimationMode::PingPong);
+        let frames = (0..6)
+            .map(|_| tick(&mut animation, ms(100)).0.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(frames, vec![1, 2, 1, 0, 1, 2]);
+
+        // it loops when it goes forward again
+        let mut animation = walk(AnimationMode::PingPong);
+        assert_eq!(tick(&mut animation, ms(400)), (Some(0), vec![]));
+        assert_eq!(
+            tick(&mut animation, ms(100)),
+            (Some(1), vec![SpriteAnimationEventKind::Looped])
+        );
+    }
+
+    #[test]
+    fn single_frame_ping_pong() {
+        let mut animation = SpriteAnimation::new(
+            "idle",
+            SpriteAnimationClip::from_indices(vec![7], ms(100)).with_mode(AnimationMode::PingPong),
+        );
+        assert_eq!(tick(&mut animation, ms(250)).0, Some(7));
+    }
+
+    #[test]
+    fn speed_and_pause() {
+        let mut animation = walk(AnimationMode::Loop);
+        animation.speed = 2.0;
+        assert_eq!(tick(&mut animation, ms(50)).0, Some(1));
+        animation.paused = true;
+        assert_eq!(tick(&mut animation, ms(500)).0, Some(1));
+        animation.paused = false;
+        animation.speed = 0.5;
+        assert_eq!(tick(&mut animation, ms(100)).0, Some(1));
+        assert_eq!(tick(&mut animation, ms(100)).0, Some(2));
+    }
+
+    #[test]
+    fn play_clips() {
+        let mut animation = walk(AnimationMode::Loop)
+            .with_clip("jump", SpriteAnimationClip::from_indices(5..8, ms(100)));
+        assert_eq!(tick(&mut animation, ms(100)).0, Some(1));
+
+        // playing the current clip doesn't restart it
+        animation.play("walk");
+        assert_eq!(tick(&mut animation, ms(0)).0, Some(1));
+
+        animation.play("jump");
+        assert_eq!(animation.current_clip(), Some("jump"));
+        assert_eq!(tick(&mut animation, ms(0)).0, Some(5));
+
+        animation.stop();
+        assert_eq!(tick(&mut animation, ms(100)).0, None);
+
+        animation.play("missing");
+        assert_eq!(tick(&mut animation, ms(100)).0, None);
+    }
+
+    #[test]
+    fn animation_system() {
+        let mut world = World::default();
+        world.insert_resource(Time::default());
+        world.insert_resource(Events::<SpriteAnimationEvent>::default());
+        let entity = world
+            .spawn()
+            .insert_bundle((
+                SpriteAnimation::new(
+                    "walk",
+                    SpriteAnimationClip::from_indices(vec![4, 5], ms(100)),
+                ),
+                TextureAtlasSprite::new(0),
+            ))
+            .id();
+        let mut stage = SystemStage::single(sprite_animation_system.system());
+        stage.run(&mut world);
+        assert_eq!(world.get::<TextureAtlasSprite>(entity).unwrap().index, 4);
+    }
+}
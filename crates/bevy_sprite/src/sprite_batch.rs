@@ -0,0 +1,601 @@
+use crate::{
+    render::uniform, Anchor, ColorMaterial, Rect, Sprite, SpriteDrawMode, SpriteNormalMap,
+    SpriteSettings, TextureAtlas, TextureAtlasSprite, FLAT_NORMAL_TEXTURE_HANDLE,
+    SPRITE_BATCH_PIPELINE_HANDLE,
+};
+use bevy_asset::{Assets, Handle, HandleUntyped};
+use bevy_core::{cast_slice, FloatOrd, Pod, Zeroable};
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    query::{With, Without},
+    reflect::ReflectComponent,
+    system::{Commands, Local, Query, Res},
+};
+use bevy_math::Vec2;
+use bevy_reflect::{Reflect, TypeUuid};
+use bevy_render::{
+    camera::RenderLayers,
+    color::Color,
+    draw::{Draw, DrawContext, OutsideFrustum, Visible},
+    pipeline::{
+        IndexFormat, InputStepMode, PipelineSpecialization, RenderPipelines, VertexAttribute,
+        VertexBufferLayout, VertexFormat,
+    },
+    prelude::Msaa,
+    render_graph::base::MainPass,
+    renderer::{
+        BindGroup, BufferId, BufferInfo, BufferUsage, RenderResourceBindings, SamplerId, TextureId,
+    },
+    texture::{Texture, SAMPLER_ASSET_INDEX, TEXTURE_ASSET_INDEX},
+};
+use bevy_transform::components::GlobalTransform;
+use std::ops::Range;
+
+/// The texture of sprites whose [`ColorMaterial`] has no texture
+pub const WHITE_TEXTURE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Texture::TYPE_UUID, 7593271394620553184);
+
+/// The order in which sprites are drawn. Sprites drawn later are drawn on top.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SpriteSortMode {
+    /// Sprites are drawn from the lowest z to the highest z
+    #[default]
+    Z,
+    /// Sprites are drawn from the lowest z to the highest z, and sprites with the same z from the
+    /// highest y to the lowest y, so that sprites lower on the screen are drawn in front of the
+    /// sprites behind them, as in most top-down games
+    YSort,
+    /// Sprites are drawn from the lowest [`SpriteLayer`] to the highest, and sprites in the same
+    /// layer from the lowest z to the highest z
+    Layer,
+}
+
+/// The layer of a sprite when sprites are sorted with [`SpriteSortMode::Layer`]. Sprites without
+/// this component are in layer `0`.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+#[reflect(Component, PartialEq)]
+pub struct SpriteLayer(pub i32);
+
+/// An entity drawing batches of sprites. These entities are spawned and updated by
+/// [`sprite_batch_system`], and are ordered among the other transparent entities by the z of
+/// their last sprite, or by their layer with [`SpriteSortMode::Layer`].
+#[derive(Component, Debug, Default, Clone)]
+pub struct SpriteBatch;
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, Pod, Zeroable)]
+struct SpriteVertex {
+    position: [f32; 3],
+    uv: [f32; 2],
+    color: [f32; 4],
+    tangent: [f32; 4],
+}
+
+fn sprite_vertex_buffer_layout() -> VertexBufferLayout {
+    VertexBufferLayout {
+        name: "SpriteVertex".into(),
+        stride: std::mem::size_of::<SpriteVertex>() as u64,
+        step_mode: InputStepMode::Vertex,
+        attributes: vec![
+            VertexAttribute {
+                name: "Vertex_Position".into(),
+                format: VertexFormat::Float32x3,
+                offset: 0,
+                shader_location: 0,
+            },
+            VertexAttribute {
+                name: "Vertex_Uv".into(),
+                format: VertexFormat::Float32x2,
+                offset: 12,
+                shader_location: 1,
+            },
+            VertexAttribute {
+                name: "Vertex_Color".into(),
+                format: VertexFormat::Float32x4,
+                offset: 20,
+                shader_location: 2,
+            },
+            VertexAttribute {
+                name: "Vertex_Tangent".into(),
+                format: VertexFormat::Float32x4,
+                offset: 36,
+                shader_location: 3,
+            },
+        ],
+    }
+}
+
+type SortKey = (i32, FloatOrd, FloatOrd);
+
+fn sort_key(
+    sort_mode: SpriteSortMode,
+    transform: &GlobalTransform,
+    layer: Option<&SpriteLayer>,
+) -> SortKey {
+    let position = transform.translation;
+    match sort_mode {
+        SpriteSortMode::Z => (0, FloatOrd(position.z), FloatOrd(0.0)),
+        SpriteSortMode::YSort => (0, FloatOrd(position.z), FloatOrd(-position.y)),
+        SpriteSortMode::Layer => (
+            layer.map_or(0, |layer| layer.0),
+            FloatOrd(position.z),
+            FloatOrd(0.0),
+        ),
+    }
+}
+
+struct QueuedSprite {
+    key: SortKey,
+    render_layers: RenderLayers,
+    texture: Handle<Texture>,
+    normal_map: Handle<Texture>,
+    /// The quads of the sprite in the quads of the [`SpriteBatchState`]
+    quads: Range<u32>,
+}
+
+impl QueuedSprite {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        key: SortKey,
+        render_layers: Option<&RenderLayers>,
+        texture: Handle<Texture>,
+        normal_map: Option<&SpriteNormalMap>,
+        transform: &GlobalTransform,
+        size: Vec2,
+        anchor: Anchor,
+        draw_mode: SpriteDrawMode,
+        uv: Rect,
+        texture_size: Vec2,
+        flip_x: bool,
+        flip_y: bool,
+        color: Color,
+        quads: &mut Vec<[SpriteVertex; 4]>,
+    ) -> Self {
+        let matrix = transform.compute_matrix();
+        let origin = anchor.center_offset(size, flip_x, flip_y) - size / 2.0;
+        let color = color.as_linear_rgba_f32();
+        // the directions of the axes of the texture, to turn the normal map to world space
+        let mut x_axis = matrix.x_axis.truncate().truncate().normalize_or_zero();
+        if flip_x {
+            x_axis = -x_axis;
+        }
+        let mut y_axis = matrix.y_axis.truncate().truncate().normalize_or_zero();
+        if flip_y {
+            y_axis = -y_axis;
+        }
+        let tangent = [x_axis.x, x_axis.y, y_axis.x, y_axis.y];
+        let vertex = |x: f32, y: f32, uv: [f32; 2]| SpriteVertex {
+            position: matrix
+                .transform_point3((origin + Vec2::new(x, y)).extend(0.0))
+                .into(),
+            uv,
+            color,
+            tangent,
+        };
+
+        let start = quads.len() as u32;
+        draw_mode.quads(size, uv, texture_size, |position, uv| {
+            // flipping mirrors the quads across the sprite, along with their texture
+            let (mut x_min, mut x_max) = (position.min.x, position.max.x);
+            let (mut left, mut right) = (uv.min.x, uv.max.x);
+            if flip_x {
+                x_min = size.x - position.max.x;
+                x_max = size.x - position.min.x;
+                std::mem::swap(&mut left, &mut right);
+            }
+            let (mut y_min, mut y_max) = (position.min.y, position.max.y);
+            let (mut top, mut bottom) = (uv.min.y, uv.max.y);
+            if flip_y {
+                y_min = size.y - position.max.y;
+                y_max = size.y - position.min.y;
+                std::mem::swap(&mut top, &mut bottom);
+            }
+            quads.push([
+                vertex(x_min, y_min, [left, bottom]),
+                vertex(x_min, y_max, [left, top]),
+                vertex(x_max, y_max, [right, top]),
+                vertex(x_max, y_min, [right, bottom]),
+            ]);
+        });
+
+        QueuedSprite {
+            key,
+            render_layers: render_layers.copied().unwrap_or_default(),
+            texture,
+            normal_map: normal_map.map_or_else(
+                || FLAT_NORMAL_TEXTURE_HANDLE.typed(),
+                |normal_map| normal_map.0.clone_weak(),
+            ),
+            quads: start..quads.len() as u32,
+        }
+    }
+}
+
+struct QueuedBatch {
+    quads: Range<u32>,
+    render_layers: RenderLayers,
+    texture: Handle<Texture>,
+    normal_map: Handle<Texture>,
+    depth: f32,
+}
+
+#[derive(Default)]
+pub struct SpriteBatchState {
+    sprites: Vec<QueuedSprite>,
+    quads: Vec<[SpriteVertex; 4]>,
+    batches: Vec<QueuedBatch>,
+    vertices: Vec<SpriteVertex>,
+    vertex_buffer: Option<BufferId>,
+    index_buffer: Option<BufferId>,
+    index_buffer_capacity: usize,
+    batch_entities: Vec<Entity>,
+}
+
+/// Sorts the visible sprites with [`SpriteSettings::sort_mode`], and draws consecutive sprites
+/// with the same texture and normal map in a single draw call, from a vertex buffer of all the
+/// sprites rebuilt every frame. Sprites with [`RenderPipelines`] are drawn on their own by their
+/// pipelines instead.
+///
+/// The sprites are lit by the 2D lights written by [`Lights2dNode`](crate::Lights2dNode).
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn sprite_batch_system(
+    mut commands: Commands,
+    mut state: Local<SpriteBatchState>,
+    mut context: DrawContext,
+    msaa: Res<Msaa>,
+    render_resource_bindings: Res<RenderResourceBindings>,
+    sprite_settings: Res<SpriteSettings>,
+    color_materials: Res<Assets<ColorMaterial>>,
+    texture_atlases: Res<Assets<TextureAtlas>>,
+    textures: Res<Assets<Texture>>,
+    sprites: Query<
+        (
+            &Sprite,
+            &Handle<ColorMaterial>,
+            &Visible,
+            &GlobalTransform,
+            Option<&SpriteLayer>,
+            Option<&RenderLayers>,
+            Option<&SpriteNormalMap>,
+        ),
+        (
+            With<MainPass>,
+            Without<OutsideFrustum>,
+            Without<RenderPipelines>,
+        ),
+    >,
+    atlas_sprites: Query<
+        (
+            &TextureAtlasSprite,
+            &Handle<TextureAtlas>,
+            &Visible,
+            &GlobalTransform,
+            Option<&SpriteLayer>,
+            Option<&RenderLayers>,
+            Option<&SpriteNormalMap>,
+        ),
+        (
+            With<MainPass>,
+            Without<OutsideFrustum>,
+            Without<RenderPipelines>,
+        ),
+    >,
+    mut batch_entities: Query<
+        (
+            &mut Draw,
+            &mut Visible,
+            &mut GlobalTransform,
+            &mut RenderLayers,
+        ),
+        (
+            With<SpriteBatch>,
+            Without<Sprite>,
+            Without<TextureAtlasSprite>,
+        ),
+    >,
+) {
+    let state = &mut *state;
+    let sort_mode = sprite_settings.sort_mode;
+
+    state.sprites.clear();
+    state.quads.clear();
+    for (sprite, material, visible, transform, layer, render_layers, normal_map) in sprites.iter() {
+        if !visible.is_visible {
+            continue;
+        }
+        let material = if let Some(material) = color_materials.get(material) {
+            material
+        } else {
+            continue;
+        };
+        let texture = material
+            .texture
+            .as_ref()
+            .map_or_else(|| WHITE_TEXTURE_HANDLE.typed(), Handle::clone_weak);
+        // the texture is only drawn once it is loaded, so its size is only missing when the sprite
+        // isn't drawn
+        let texture_size = textures
+            .get(&texture)
+            .map_or(Vec2::ONE, |texture| texture.size.as_vec3().truncate());
+        state.sprites.push(QueuedSprite::new(
+            sort_key(sort_mode, transform, layer),
+            render_layers,
+            texture,
+            normal_map,
+            transform,
+            sprite.size,
+            sprite.anchor,
+            sprite.draw_mode,
+            Rect {
+                min: Vec2::ZERO,
+                max: Vec2::ONE,
+            },
+            texture_size,
+            sprite.flip_x,
+            sprite.flip_y,
+            material.color,
+            &mut state.quads,
+        ));
+    }
+    for (sprite, atlas, visible, transform, layer, render_layers, normal_map) in
+        atlas_sprites.iter()
+    {
+        if !visible.is_visible {
+            continue;
+        }
+        let atlas = if let Some(atlas) = texture_atlases.get(atlas) {
+            atlas
+        } else {
+            continue;
+        };
+        let rect = if let Some(rect) = atlas.textures.get(sprite.index as usize) {
+            rect
+        } else {
+            continue;
+        };
+        state.sprites.push(QueuedSprite::new(
+            sort_key(sort_mode, transform, layer),
+            render_layers,
+            atlas.texture.clone_weak(),
+            normal_map,
+            transform,
+            sprite.custom_size.unwrap_or(rect.max - rect.min),
+            sprite.anchor,
+            sprite.draw_mode,
+            Rect {
+                min: rect.min / atlas.size,
+                max: rect.max / atlas.size,
+            },
+            rect.max - rect.min,
+            sprite.flip_x,
+            sprite.flip_y,
+            sprite.color,
+            &mut state.quads,
+        ));
+    }
+
+    // sprites with the same sort key are sorted by texture, so that they end up in the same batch
+    state.sprites.sort_by(|a, b| {
+        (a.key, a.render_layers, a.texture.id, a.normal_map.id).cmp(&(
+            b.key,
+            b.render_layers,
+            b.texture.id,
+            b.normal_map.id,
+        ))
+    });
+
+    state.vertices.clear();
+    state.batches.clear();
+    for sprite in state.sprites.iter() {
+        let first_quad = state.vertices.len() as u32 / 4;
+        for quad in state.quads[sprite.quads.start as usize..sprite.quads.end as usize].iter() {
+            state.vertices.extend_from_slice(quad);
+        }
+        let quads = first_quad..state.vertices.len() as u32 / 4;
+        let depth = match sort_mode {
+            SpriteSortMode::Layer => sprite.key.0 as f32,
+            SpriteSortMode::Z | SpriteSortMode::YSort => (sprite.key.1).0,
+        };
+        match state.batches.last_mut() {
+            Some(batch)
+                if batch.texture == sprite.texture
+                    && batch.normal_map == sprite.normal_map
+                    && batch.render_layers == sprite.render_layers =>
+            {
+                batch.quads.end = quads.end;
+                batch.depth = depth;
+            }
+            _ => state.batches.push(QueuedBatch {
+                quads,
+                render_layers: sprite.render_layers,
+                texture: sprite.texture.clone_weak(),
+                normal_map: sprite.normal_map.clone_weak(),
+                depth,
+            }),
+        }
+    }
+
+    let render_resource_context = &**context.render_resource_context;
+    if let Some(vertex_buffer) = state.vertex_buffer.take() {
+        render_resource_context.remove_buffer(vertex_buffer);
+    }
+    if !state.vertices.is_empty() {
+        state.vertex_buffer = Some(render_resource_context.create_buffer_with_data(
+            BufferInfo {
+                size: state.vertices.len() * std::mem::size_of::<SpriteVertex>(),
+                buffer_usage: BufferUsage::VERTEX,
+                ..Default::default()
+            },
+            cast_slice(&state.vertices),
+        ));
+    }
+    // the indices of every quad follow the same pattern, so the index buffer only changes when it
+    // grows
+    let quad_count = state.vertices.len() / 4;
+    if state.index_buffer_capacity < quad_count {
+        if let Some(index_buffer) = state.index_buffer.take() {
+            render_resource_context.remove_buffer(index_buffer);
+        }
+        let capacity = quad_count.next_power_of_two();
+        let mut indices = Vec::with_capacity(capacity * 6);
+        for quad in 0..capacity as u32 {
+            let first = quad * 4;
+            indices.extend_from_slice(&[first, first + 2, first + 1, first, first + 3, first + 2]);
+        }
+        state.index_buffer = Some(render_resource_context.create_buffer_with_data(
+            BufferInfo {
+                size: indices.len() * std::mem::size_of::<u32>(),
+                buffer_usage: BufferUsage::INDEX,
+                ..Default::default()
+            },
+            cast_slice(&indices),
+        ));
+        state.index_buffer_capacity = capacity;
+    }
+
+    // batch entities spawned last frame exist now, the ones spawned below are used from the next
+    // frame on
+    state
+        .batch_entities
+        .retain(|&entity| batch_entities.get_mut(entity).is_ok());
+    let spawned_entities = state.batch_entities.len();
+    while state.batch_entities.len() < state.batches.len() {
+        let entity = commands
+            .spawn_bundle((
+                SpriteBatch,
+                Draw::default(),
+                Visible {
+                    is_visible: false,
+                    is_transparent: true,
+                },
+                MainPass,
+                GlobalTransform::default(),
+                RenderLayers::default(),
+            ))
+            .id();
+        state.batch_entities.push(entity);
+    }
+
+    for &entity in state.batch_entities[state.batches.len().min(spawned_entities)..].iter() {
+        if let Ok((_, mut visible, _, _)) = batch_entities.get_mut(entity) {
+            if visible.is_visible {
+                visible.is_visible = false;
+            }
+        }
+    }
+    let (vertex_buffer, index_buffer) = match (state.vertex_buffer, state.index_buffer) {
+        (Some(vertex_buffer), Some(index_buffer)) if spawned_entities > 0 => {
+            (vertex_buffer, index_buffer)
+        }
+        _ => return,
+    };
+    // the lights are written by the render graph, after the first frame
+    let lights_bind_group = match render_resource_bindings.get(uniform::LIGHTS_2D) {
+        Some(lights) => BindGroup::build().add_binding(0, lights.clone()).finish(),
+        None => return,
+    };
+
+    let specialization = PipelineSpecialization {
+        sample_count: msaa.samples,
+        vertex_buffer_layout: sprite_vertex_buffer_layout(),
+        ..Default::default()
+    };
+    for (index, batch) in state.batches.iter().enumerate() {
+        let (texture, sampler, normal_map, normal_map_sampler) = match (
+            texture_resources(&context, &batch.texture),
+            texture_resources(&context, &batch.normal_map),
+        ) {
+            (Some((texture, sampler)), Some((normal_map, normal_map_sampler))) => {
+                (texture, sampler, normal_map, normal_map_sampler)
+            }
+            // the textures aren't loaded yet
+            _ => continue,
+        };
+
+        // until enough batch entities are spawned, the last one draws the remaining batches
+        let entity = state.batch_entities[index.min(spawned_entities - 1)];
+        let (mut draw, mut visible, mut transform, mut render_layers) =
+            batch_entities.get_mut(entity).unwrap();
+        visible.is_visible = true;
+        transform.translation.z = batch.depth;
+        *render_layers = batch.render_layers;
+
+        context
+            .set_pipeline(
+                &mut draw,
+                &SPRITE_BATCH_PIPELINE_HANDLE.typed(),
+                &specialization,
+            )
+            .unwrap();
+        let texture_bind_group = BindGroup::build()
+            .add_texture(0, texture)
+            .add_sampler(1, sampler)
+            .add_texture(2, normal_map)
+            .add_sampler(3, normal_map_sampler)
+            .finish();
+        context
+            .create_bind_group_resource(1, &texture_bind_group)
+            .unwrap();
+        draw.set_bind_group(1, &texture_bind_group);
+        context
+            .create_bind_group_resource(2, &lights_bind_group)
+            .unwrap();
+        draw.set_bind_group(2, &lights_bind_group);
+        draw.set_vertex_buffer(0, vertex_buffer, 0);
+        draw.set_index_buffer(index_buffer, 0, IndexFormat::Uint32);
+        draw.draw_indexed(batch.quads.start * 6..batch.quads.end * 6, 0, 0..1);
+    }
+}
+
+fn texture_resources(
+    context: &DrawContext,
+    texture: &Handle<Texture>,
+) -> Option<(TextureId, SamplerId)> {
+    let texture_id = context
+        .render_resource_context
+        .get_asset_resource(texture, TEXTURE_ASSET_INDEX)?
+        .get_texture()?;
+    let sampler_id = context
+        .render_resource_context
+        .get_asset_resource(texture, SAMPLER_ASSET_INDEX)?
+        .get_sampler()?;
+    Some((texture_id, sampler_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_math::Vec3;
+
+    /// The order in which sprites at the positions, with the layers, are drawn
+    fn draw_order(
+        sort_mode: SpriteSortMode,
+        sprites: &[(Vec3, Option<SpriteLayer>)],
+    ) -> Vec<usize> {
+        let mut order = (0..sprites.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&index| {
+            let (position, layer) = &sprites[index];
+            sort_key(
+                sort_mode,
+                &GlobalTransform::from_translation(*position),
+                layer.as_ref(),
+            )
+        });
+        order
+    }
+
+    #[test]
+    fn sort_modes() {
+        let sprites = [
+            (Vec3::new(0.0, -10.0, 1.0), None),
+            (Vec3::new(0.0, 10.0, 0.0), Some(SpriteLayer(1))),
+            (Vec3::new(0.0, -10.0, 0.0), Some(SpriteLayer(-1))),
+            (Vec3::new(0.0, 20.0, 1.0), Some(SpriteLayer(1))),
+        ];
+        // sprites with the same key keep their order
+        assert_eq!(draw_order(SpriteSortMode::Z, &sprites), [1, 2, 0, 3]);
+        assert_eq!(draw_order(SpriteSortMode::YSort, &sprites), [1, 2, 3, 0]);
+        assert_eq!(draw_order(SpriteSortMode::Layer, &sprites), [2, 0, 1, 3]);
+    }
+}
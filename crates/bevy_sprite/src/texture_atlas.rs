@@ -1,4 +1,4 @@
-use crate::Rect;
+use crate::{Anchor, Rect, SpriteDrawMode};
 use bevy_asset::Handle;
 use bevy_core::Bytes;
 use bevy_ecs::component::Component;
@@ -36,6 +36,13 @@ pub struct TextureAtlasSprite {
     pub index: u32,
     pub flip_x: bool,
     pub flip_y: bool,
+    /// The point of the sprite placed at its translation
+    pub anchor: Anchor,
+    /// The size of the sprite in world units, instead of the size of its section of the atlas in
+    /// pixels
+    pub custom_size: Option<Vec2>,
+    /// How the section of the atlas fills the size of the sprite
+    pub draw_mode: SpriteDrawMode,
 }
 
 impl RenderResource for TextureAtlasSprite {
@@ -44,7 +51,7 @@ impl RenderResource for TextureAtlasSprite {
     }
 
     fn buffer_byte_len(&self) -> Option<usize> {
-        Some(24)
+        Some(32)
     }
 
     fn write_buffer_bytes(&self, buffer: &mut [u8]) {
@@ -53,14 +60,17 @@ impl RenderResource for TextureAtlasSprite {
         self.color.write_bytes(color_buf);
 
         // Write the index buffer
-        let (index_buf, flip_buf) = rest.split_at_mut(4);
+        let (index_buf, rest) = rest.split_at_mut(4);
         self.index.write_bytes(index_buf);
 
         // First bit means flip x, second bit means flip y
+        let (flip_buf, anchor_buf) = rest.split_at_mut(4);
         flip_buf[0] = if self.flip_x { 0b01 } else { 0 } | if self.flip_y { 0b10 } else { 0 };
         flip_buf[1] = 0;
         flip_buf[2] = 0;
         flip_buf[3] = 0;
+
+        self.anchor.as_vec().write_bytes(anchor_buf);
     }
 
     fn texture(&self) -> Option<&Handle<Texture>> {
@@ -75,6 +85,9 @@ impl Default for TextureAtlasSprite {
             color: Color::WHITE,
             flip_x: false,
             flip_y: false,
+            anchor: Anchor::default(),
+            custom_size: None,
+            draw_mode: SpriteDrawMode::default(),
         }
     }
 }
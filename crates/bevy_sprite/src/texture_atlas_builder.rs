@@ -8,6 +8,10 @@ use rectangle_pack::{
     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, PackedLocation,
     RectToInsert, TargetBin,
 };
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -18,13 +22,21 @@ pub enum TextureAtlasBuilderError {
     WrongFormat,
 }
 
+/// Identifies the rect of a texture by a hash of the texture, then by the index of the texture for
+/// identical textures. Rects of the same size are placed in the order of their ids, so that the
+/// placement doesn't depend on the order the textures are added in, nor on their handles.
+type RectId = (u64, usize);
+
 #[derive(Debug)]
 /// A builder which is used to create a texture atlas from many individual
 /// sprites.
 pub struct TextureAtlasBuilder {
-    /// The grouped rects which must be placed with a key value pair of a
-    /// texture handle to an index.
-    rects_to_place: GroupedRectsToPlace<Handle<Texture>>,
+    /// The textures to place, in the order they were added.
+    textures: Vec<Handle<Texture>>,
+    /// The id of the rect of each texture in `textures`.
+    rect_ids: Vec<RectId>,
+    /// The grouped rects which must be placed.
+    rects_to_place: GroupedRectsToPlace<RectId>,
     /// The initial atlas size in pixels.
     initial_size: Vec2,
     /// The absolute maximum size of the texture atlas in pixels.
@@ -33,16 +45,24 @@ pub struct TextureAtlasBuilder {
     format: TextureFormat,
     /// Enable automatic format conversion for textures if they are not in the atlas format.
     auto_format_conversion: bool,
+    /// The empty space between textures in pixels.
+    padding: u32,
+    /// The number of pixels the edges of each texture are repeated outwards.
+    extrusion: u32,
 }
 
 impl Default for TextureAtlasBuilder {
     fn default() -> Self {
         Self {
+            textures: Vec::new(),
+            rect_ids: Vec::new(),
             rects_to_place: GroupedRectsToPlace::new(),
             initial_size: Vec2::new(256., 256.),
             max_size: Vec2::new(2048., 2048.),
             format: TextureFormat::Rgba8UnormSrgb,
             auto_format_conversion: true,
+            padding: 0,
+            extrusion: 0,
         }
     }
 }
@@ -74,35 +94,38 @@ impl TextureAtlasBuilder {
         self
     }
 
-    /// Adds a texture to be copied to the texture atlas.
-    pub fn add_texture(&mut self, texture_handle: Handle<Texture>, texture: &Texture) {
-        self.rects_to_place.push_rect(
-            texture_handle,
-            None,
-            RectToInsert::new(texture.size.width, texture.size.height, 1),
-        )
+    /// Sets the empty space between textures in pixels.
+    pub fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
     }
 
-    fn copy_texture_to_atlas(
-        atlas_texture: &mut Texture,
-        texture: &Texture,
-        packed_location: &PackedLocation,
-    ) {
-        let rect_width = packed_location.width() as usize;
-        let rect_height = packed_location.height() as usize;
-        let rect_x = packed_location.x() as usize;
-        let rect_y = packed_location.y() as usize;
-        let atlas_width = atlas_texture.size.width as usize;
-        let format_size = atlas_texture.format.pixel_size();
-
-        for (texture_y, bound_y) in (rect_y..rect_y + rect_height).enumerate() {
-            let begin = (bound_y * atlas_width + rect_x) * format_size;
-            let end = begin + rect_width * format_size;
-            let texture_begin = texture_y * rect_width * format_size;
-            let texture_end = texture_begin + rect_width * format_size;
-            atlas_texture.data[begin..end]
-                .copy_from_slice(&texture.data[texture_begin..texture_end]);
+    /// Sets the number of pixels the edges of each texture are repeated outwards, so that
+    /// sampling near the edges of a texture doesn't bleed its neighbours in.
+    pub fn extrusion(mut self, extrusion: u32) -> Self {
+        self.extrusion = extrusion;
+        self
+    }
+
+    /// Adds a texture to be copied to the texture atlas. The same textures are placed in the same
+    /// way whatever the order they are added in, and their index in the atlas is the order they
+    /// were added in.
+    pub fn add_texture(&mut self, texture_handle: Handle<Texture>, texture: &Texture) {
+        if self.textures.contains(&texture_handle) {
+            return;
         }
+        let mut hasher = DefaultHasher::new();
+        (texture.size.width, texture.size.height, texture.format).hash(&mut hasher);
+        texture.data.hash(&mut hasher);
+        let rect_id = (hasher.finish(), self.textures.len());
+        let border = 2 * self.extrusion + self.padding;
+        self.rects_to_place.push_rect(
+            rect_id,
+            None,
+            RectToInsert::new(texture.size.width + border, texture.size.height + border, 1),
+        );
+        self.textures.push(texture_handle);
+        self.rect_ids.push(rect_id);
     }
 
     fn copy_converted_texture(
@@ -111,14 +134,17 @@ impl TextureAtlasBuilder {
         texture: &Texture,
         packed_location: &PackedLocation,
     ) {
+        let extrusion = self.extrusion as usize;
+        let x = packed_location.x() as usize + extrusion;
+        let y = packed_location.y() as usize + extrusion;
         if self.format == texture.format {
-            Self::copy_texture_to_atlas(atlas_texture, texture, packed_location);
+            copy_texture_to_atlas(atlas_texture, texture, x, y, extrusion);
         } else if let Some(converted_texture) = texture.clone().convert(self.format) {
             debug!(
                 "Converting texture from '{:?}' to '{:?}'",
                 texture.format, self.format
             );
-            Self::copy_texture_to_atlas(atlas_texture, &converted_texture, packed_location);
+            copy_texture_to_atlas(atlas_texture, &converted_texture, x, y, extrusion);
         } else {
             error!(
                 "Error converting texture from '{:?}' to '{:?}', ignoring",
@@ -190,16 +216,16 @@ impl TextureAtlasBuilder {
 
         let rect_placements = rect_placements.ok_or(TextureAtlasBuilderError::NotEnoughSpace)?;
 
-        let mut texture_rects = Vec::with_capacity(rect_placements.packed_locations().len());
+        let mut texture_rects = Vec::with_capacity(self.textures.len());
         let mut texture_handles = HashMap::default();
-        for (texture_handle, (_, packed_location)) in rect_placements.packed_locations().iter() {
+        for (texture_handle, rect_id) in self.textures.iter().zip(self.rect_ids.iter()) {
+            let (_, packed_location) = &rect_placements.packed_locations()[rect_id];
             let texture = textures.get(texture_handle).unwrap();
-            let min = Vec2::new(packed_location.x() as f32, packed_location.y() as f32);
-            let max = min
-                + Vec2::new(
-                    packed_location.width() as f32,
-                    packed_location.height() as f32,
-                );
+            let min = Vec2::new(
+                (packed_location.x() + self.extrusion) as f32,
+                (packed_location.y() + self.extrusion) as f32,
+            );
+            let max = min + texture.size.as_vec3().truncate();
             texture_handles.insert(texture_handle.clone_weak(), texture_rects.len());
             texture_rects.push(Rect { min, max });
             if texture.format != self.format && !self.auto_format_conversion {
@@ -219,3 +245,100 @@ impl TextureAtlasBuilder {
         })
     }
 }
+
+/// Copies `texture` to the atlas with its top left corner at `x`, `y`, and repeats the pixels on
+/// its edges `extrusion` pixels outwards, so that filtering near the edges of the texture doesn't
+/// sample its neighbours in the atlas. The atlas must have room for the extruded pixels.
+pub(crate) fn copy_texture_to_atlas(
+    atlas_texture: &mut Texture,
+    texture: &Texture,
+    x: usize,
+    y: usize,
+    extrusion: usize,
+) {
+    let width = texture.size.width as usize;
+    let height = texture.size.height as usize;
+    if width == 0 || height == 0 {
+        return;
+    }
+    let atlas_width = atlas_texture.size.width as usize;
+    let format_size = atlas_texture.format.pixel_size();
+    let row_size = width * format_size;
+
+    for row in 0..height + 2 * extrusion {
+        let texture_y = row.saturating_sub(extrusion).min(height - 1);
+        let texture_row = &texture.data[texture_y * row_size..(texture_y + 1) * row_size];
+        let begin = ((y + row - extrusion) * atlas_width + x - extrusion) * format_size;
+        let end = begin + row_size + 2 * extrusion * format_size;
+        let (left, rest) = atlas_texture.data[begin..end].split_at_mut(extrusion * format_size);
+        let (middle, right) = rest.split_at_mut(row_size);
+        middle.copy_from_slice(texture_row);
+        for pixel in left.chunks_exact_mut(format_size) {
+            pixel.copy_from_slice(&texture_row[..format_size]);
+        }
+        for pixel in right.chunks_exact_mut(format_size) {
+            pixel.copy_from_slice(&texture_row[row_size - format_size..]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin};
+    use bevy_tasks::{IoTaskPool, TaskPool};
+
+    #[test]
+    fn placement_ignores_insertion_order() {
+        let mut app = App::new();
+        app.insert_resource(IoTaskPool(TaskPool::new()))
+            .add_plugin(AssetPlugin)
+            .add_asset::<Texture>();
+        let mut textures = app.world.get_resource_mut::<Assets<Texture>>().unwrap();
+        // textures of the same size tie in the packer
+        let sizes = [(8, 8), (8, 8), (16, 4), (8, 8), (4, 16), (16, 4)];
+        let handles = sizes
+            .iter()
+            .enumerate()
+            .map(|(index, &(width, height))| {
+                textures.add(Texture::new_fill(
+                    Extent3d::new(width, height, 1),
+                    TextureDimension::D2,
+                    &[index as u8, 0, 0, 255],
+                    TextureFormat::Rgba8UnormSrgb,
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        let mut build = |handles: &[Handle<Texture>]| {
+            let mut builder = TextureAtlasBuilder::default()
+                .initial_size(Vec2::new(16., 16.))
+                .padding(1);
+            for handle in handles {
+                let texture = textures.get(handle).unwrap();
+                builder.add_texture(handle.clone(), texture);
+            }
+            let atlas = builder.finish(&mut textures).unwrap();
+            let atlas_texture = textures.get(&atlas.texture).unwrap().data.clone();
+            (atlas, atlas_texture)
+        };
+        let (atlas, atlas_texture) = build(&handles);
+        let reversed = handles.iter().rev().cloned().collect::<Vec<_>>();
+        let (reversed_atlas, reversed_atlas_texture) = build(&reversed);
+
+        assert_eq!(atlas.size, reversed_atlas.size);
+        assert_eq!(atlas_texture, reversed_atlas_texture);
+        for (index, handle) in handles.iter().enumerate() {
+            // indices follow the insertion order, rects don't
+            assert_eq!(atlas.get_texture_index(handle), Some(index));
+            assert_eq!(
+                reversed_atlas.get_texture_index(handle),
+                Some(handles.len() - 1 - index)
+            );
+            let rect = atlas.textures[index];
+            let reversed_rect = reversed_atlas.textures[handles.len() - 1 - index];
+            assert_eq!((rect.min, rect.max), (reversed_rect.min, reversed_rect.max));
+        }
+    }
+}
@@ -0,0 +1,562 @@
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::{
+    component::Component,
+    query::Changed,
+    system::{Query, ResMut},
+};
+use bevy_math::Vec2;
+use bevy_render::{
+    color::Color,
+    mesh::{Indices, Mesh},
+    pipeline::PrimitiveTopology,
+};
+
+/// The number of segments of a full circle or ellipse
+const ELLIPSE_SEGMENTS: usize = 64;
+/// The number of segments of a bezier curve
+const CURVE_SEGMENTS: usize = 16;
+/// The longest a miter join can be, relative to the width of the stroke
+const MITER_LIMIT: f32 = 4.0;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct SubPath {
+    points: Vec<Vec2>,
+    closed: bool,
+}
+
+/// The outline of a [`Shape`], made of straight lines and bezier curves. Curves are flattened into
+/// lines when they are added.
+///
+/// A path is made of subpaths, each started by [`ShapePath::move_to`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShapePath {
+    subpaths: Vec<SubPath>,
+}
+
+impl ShapePath {
+    pub fn new() -> Self {
+        ShapePath::default()
+    }
+
+    /// A rectangle of `size`, centered on the origin
+    pub fn rectangle(size: Vec2) -> Self {
+        let half_size = size / 2.0;
+        ShapePath::polygon([
+            Vec2::new(-half_size.x, -half_size.y),
+            Vec2::new(half_size.x, -half_size.y),
+            Vec2::new(half_size.x, half_size.y),
+            Vec2::new(-half_size.x, half_size.y),
+        ])
+    }
+
+    /// A circle centered on the origin
+    pub fn circle(radius: f32) -> Self {
+        ShapePath::ellipse(Vec2::splat(radius))
+    }
+
+    /// An ellipse centered on the origin, with its axes along the x and y axes
+    pub fn ellipse(radii: Vec2) -> Self {
+        ShapePath::polygon((0..ELLIPSE_SEGMENTS).map(|segment| {
+            let angle = segment as f32 * std::f32::consts::TAU / ELLIPSE_SEGMENTS as f32;
+            Vec2::new(angle.cos(), angle.sin()) * radii
+        }))
+    }
+
+    /// A polygon with `sides` sides of the same length, centered on the origin and with a vertex
+    /// at `radius` above it
+    pub fn regular_polygon(sides: usize, radius: f32) -> Self {
+        ShapePath::polygon((0..sides).map(|side| {
+            let angle =
+                std::f32::consts::FRAC_PI_2 + side as f32 * std::f32::consts::TAU / sides as f32;
+            Vec2::new(angle.cos(), angle.sin()) * radius
+        }))
+    }
+
+    /// A closed path through `points`
+    pub fn polygon(points: impl IntoIterator<Item = Vec2>) -> Self {
+        ShapePath::polyline(points).close()
+    }
+
+    /// An open path through `points`
+    pub fn polyline(points: impl IntoIterator<Item = Vec2>) -> Self {
+        let mut points = points.into_iter();
+        let mut path = ShapePath::new();
+        if let Some(first) = points.next() {
+            path = path.move_to(first);
+        }
+        points.fold(path, |path, point| path.line_to(point))
+    }
+
+    /// Starts a new subpath at `point`
+    pub fn move_to(mut self, point: Vec2) -> Self {
+        self.subpaths.push(SubPath {
+            points: vec![point],
+            closed: false,
+        });
+        self
+    }
+
+    /// Adds a straight line from the current point to `point`
+    pub fn line_to(mut self, point: Vec2) -> Self {
+        self.current_subpath().push_point(point);
+        self
+    }
+
+    /// Adds a quadratic bezier curve from the current point to `to`
+    pub fn quadratic_bezier_to(mut self, control: Vec2, to: Vec2) -> Self {
+        let subpath = self.current_subpath();
+        let from = subpath.last_point();
+        for segment in 1..=CURVE_SEGMENTS {
+            let t = segment as f32 / CURVE_SEGMENTS as f32;
+            let u = 1.0 - t;
+            subpath.push_point(from * (u * u) + control * (2.0 * u * t) + to * (t * t));
+        }
+        self
+    }
+
+    /// Adds a cubic bezier curve from the current point to `to`
+    pub fn cubic_bezier_to(mut self, control1: Vec2, control2: Vec2, to: Vec2) -> Self {
+        let subpath = self.current_subpath();
+        let from = subpath.last_point();
+        for segment in 1..=CURVE_SEGMENTS {
+            let t = segment as f32 / CURVE_SEGMENTS as f32;
+            let u = 1.0 - t;
+            subpath.push_point(
+                from * (u * u * u)
+                    + control1 * (3.0 * u * u * t)
+                    + control2 * (3.0 * u * t * t)
+                    + to * (t * t * t),
+            );
+        }
+        self
+    }
+
+    /// Closes the current subpath with a straight line back to its first point
+    pub fn close(mut self) -> Self {
+        if let Some(subpath) = self.subpaths.last_mut() {
+            if subpath.points.len() > 1 && subpath.points.first() == subpath.points.last() {
+                subpath.points.pop();
+            }
+            subpath.closed = true;
+        }
+        self
+    }
+
+    /// The straight lines of the path, including the lines closing the closed subpaths
+    pub(crate) fn segments(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        self.subpaths.iter().flat_map(|subpath| {
+            let points = &subpath.points;
+            let closing = if subpath.closed && points.len() > 2 {
+                Some((points[points.len() - 1], points[0]))
+            } else {
+                None
+            };
+            points
+                .windows(2)
+                .map(|line| (line[0], line[1]))
+                .chain(closing)
+        })
+    }
+
+    /// The subpath lines are added to. After a subpath is closed, a new one starts at its first
+    /// point.
+    fn current_subpath(&mut self) -> &mut SubPath {
+        let start = match self.subpaths.last() {
+            Some(subpath) if !subpath.closed => None,
+            Some(subpath) => Some(subpath.points[0]),
+            None => Some(Vec2::ZERO),
+        };
+        if let Some(start) = start {
+            self.subpaths.push(SubPath {
+                points: vec![start],
+                closed: false,
+            });
+        }
+        self.subpaths.last_mut().unwrap()
+    }
+}
+
+impl SubPath {
+    fn last_point(&self) -> Vec2 {
+        *self.points.last().unwrap()
+    }
+
+    fn push_point(&mut self, point: Vec2) {
+        // repeated points would make the directions of the stroke undefined
+        if self.last_point() != point {
+            self.points.push(point);
+        }
+    }
+}
+
+/// The outline of a [`Shape`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stroke {
+    pub color: Color,
+    pub width: f32,
+}
+
+/// A 2D vector shape, tessellated into the [`Mesh`] of its entity whenever it changes.
+///
+/// Each subpath of the path is filled on its own, so shapes can't have holes, and overlapping
+/// subpaths are filled once each.
+#[derive(Component, Debug, Clone, Default)]
+pub struct Shape {
+    pub path: ShapePath,
+    /// The color inside the subpaths of the path. Open subpaths are filled as if they were closed.
+    pub fill: Option<Color>,
+    /// The outline of the subpaths of the path, drawn over the fill
+    pub stroke: Option<Stroke>,
+}
+
+impl Shape {
+    /// A shape filled with `color`
+    pub fn fill(path: ShapePath, color: Color) -> Self {
+        Shape {
+            path,
+            fill: Some(color),
+            stroke: None,
+        }
+    }
+
+    /// A shape outlined with a stroke of `color` and `width`
+    pub fn stroke(path: ShapePath, color: Color, width: f32) -> Self {
+        Shape {
+            path,
+            fill: None,
+            stroke: Some(Stroke { color, width }),
+        }
+    }
+
+    pub fn with_fill(mut self, color: Color) -> Self {
+        self.fill = Some(color);
+        self
+    }
+
+    pub fn with_stroke(mut self, color: Color, width: f32) -> Self {
+        self.stroke = Some(Stroke { color, width });
+        self
+    }
+
+    /// Tessellates the shape into a mesh with positions and colors, or returns `None` if the
+    /// shape has nothing to draw
+    pub fn tessellate(&self) -> Option<Mesh> {
+        let mut positions = Vec::new();
+        let mut colors = Vec::new();
+        let mut indices = Vec::new();
+        if let Some(color) = self.fill {
+            for subpath in self.path.subpaths.iter() {
+                fill_polygon(&subpath.points, positions.len() as u32, &mut indices);
+                positions.extend(subpath.points.iter().map(|point| [point.x, point.y, 0.0]));
+            }
+            colors.resize(positions.len(), color.as_linear_rgba_f32());
+        }
+        if let Some(stroke) = self.stroke {
+            for subpath in self.path.subpaths.iter() {
+                stroke_polyline(
+                    &subpath.points,
+                    subpath.closed,
+                    stroke.width,
+                    &mut positions,
+                    &mut indices,
+                );
+            }
+            colors.resize(positions.len(), stroke.color.as_linear_rgba_f32());
+        }
+        if indices.is_empty() {
+            return None;
+        }
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.set_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.set_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        mesh.set_indices(Some(Indices::U32(indices)));
+        Some(mesh)
+    }
+}
+
+/// Triangulates a simple polygon by ear clipping
+fn fill_polygon(points: &[Vec2], first: u32, indices: &mut Vec<u32>) {
+    if points.len() < 3 {
+        return;
+    }
+    let mut remaining = (0..points.len()).collect::<Vec<_>>();
+    // ears are found on counterclockwise polygons
+    if signed_area(points) < 0.0 {
+        remaining.reverse();
+    }
+    while remaining.len() > 3 {
+        let len = remaining.len();
+        let ear = (0..len)
+            .map(|i| {
+                (
+                    remaining[(i + len - 1) % len],
+                    remaining[i],
+                    remaining[(i + 1) % len],
+                )
+            })
+            .position(|(a, b, c)| is_ear(points, &remaining, a, b, c));
+        let i = match ear {
+            Some(i) => i,
+            // self-intersecting polygons can run out of ears, the rest is filled as a fan
+            None => break,
+        };
+        let (a, b, c) = (
+            remaining[(i + len - 1) % len],
+            remaining[i],
+            remaining[(i + 1) % len],
+        );
+        indices.extend_from_slice(&[first + a as u32, first + b as u32, first + c as u32]);
+        remaining.remove(i);
+    }
+    for i in 1..remaining.len() - 1 {
+        indices.extend_from_slice(&[
+            first + remaining[0] as u32,
+            first + remaining[i] as u32,
+            first + remaining[i + 1] as u32,
+        ]);
+    }
+}
+
+fn signed_area(points: &[Vec2]) -> f32 {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a.perp_dot(*b))
+        .sum::<f32>()
+        / 2.0
+}
+
+fn is_ear(points: &[Vec2], remaining: &[usize], a: usize, b: usize, c: usize) -> bool {
+    let (pa, pb, pc) = (points[a], points[b], points[c]);
+    // reflex and degenerate corners aren't ears
+    if (pb - pa).perp_dot(pc - pb) <= 0.0 {
+        return false;
+    }
+    remaining.iter().all(|&p| {
+        p == a || p == b || p == c || {
+            let p = points[p];
+            (pb - pa).perp_dot(p - pa) < 0.0
+                || (pc - pb).perp_dot(p - pb) < 0.0
+                || (pa - pc).perp_dot(p - pc) < 0.0
+        }
+    })
+}
+
+/// Adds a strip of `width` along `points`, with miter joins and butt caps
+fn stroke_polyline(
+    points: &[Vec2],
+    closed: bool,
+    width: f32,
+    positions: &mut Vec<[f32; 3]>,
+    indices: &mut Vec<u32>,
+) {
+    let len = points.len();
+    if len < 2 {
+        return;
+    }
+    let closed = closed && len > 2;
+    let half_width = width / 2.0;
+    let first = positions.len() as u32;
+    for (i, &point) in points.iter().enumerate() {
+        let previous = match i {
+            0 if closed => Some(points[len - 1]),
+            0 => None,
+            _ => Some(points[i - 1]),
+        };
+        let next = match points.get(i + 1) {
+            Some(&next) => Some(next),
+            None if closed => Some(points[0]),
+            None => None,
+        };
+        let normal_in = previous.map(|previous| (point - previous).normalize().perp());
+        let normal_out = next.map(|next| (next - point).normalize().perp());
+        let offset = match (normal_in, normal_out) {
+            (Some(normal_in), Some(normal_out)) => {
+                let miter = (normal_in + normal_out).normalize_or_zero();
+                if miter == Vec2::ZERO {
+                    // the path turns back on itself
+                    normal_in * half_width
+                } else {
+                    miter * (half_width / miter.dot(normal_in).max(1.0 / MITER_LIMIT))
+                }
+            }
+            (Some(normal), None) | (None, Some(normal)) => normal * half_width,
+            (None, None) => Vec2::ZERO,
+        };
+        let (left, right) = (point + offset, point - offset);
+        positions.push([left.x, left.y, 0.0]);
+        positions.push([right.x, right.y, 0.0]);
+    }
+
+    let len = len as u32;
+    let segments = if closed { len } else { len - 1 };
+    for segment in 0..segments {
+        let start = first + segment * 2;
+        let end = first + (segment + 1) % len * 2;
+        indices.extend_from_slice(&[start, start + 1, end + 1, start, end + 1, end]);
+    }
+}
+
+/// Tessellates the [`Shape`]s that changed into the [`Mesh`] of their entity
+pub fn shape_mesh_system(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut query: Query<(&Shape, &mut Handle<Mesh>), Changed<Shape>>,
+) {
+    for (shape, mut mesh) in query.iter_mut() {
+        *mesh = match shape.tessellate() {
+            Some(tessellated) => meshes.add(tessellated),
+            None => Handle::default(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_render::mesh::VertexAttributeValues;
+
+    /// The positions, colors and indices of the tessellated shape
+    fn tessellate(shape: &Shape) -> (Vec<Vec2>, Vec<[f32; 4]>, Vec<u32>) {
+        let mesh = shape.tessellate().unwrap();
+        let positions = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => positions
+                .iter()
+                .map(|position| Vec2::new(position[0], position[1]))
+                .collect(),
+            _ => panic!("positions aren't Float32x3"),
+        };
+        let colors = match mesh.attribute(Mesh::ATTRIBUTE_COLOR) {
+            Some(VertexAttributeValues::Float32x4(colors)) => colors.clone(),
+            _ => panic!("colors aren't Float32x4"),
+        };
+        let indices = match mesh.indices() {
+            Some(Indices::U32(indices)) => indices.clone(),
+            _ => panic!("indices aren't U32"),
+        };
+        (positions, colors, indices)
+    }
+
+    /// The total area of the triangles, which is the area of the filled shape when no triangles
+    /// overlap
+    fn triangles_area(positions: &[Vec2], indices: &[u32]) -> f32 {
+        indices
+            .chunks(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|i| positions[triangle[i] as usize]);
+                (b - a).perp_dot(c - a).abs() / 2.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn fill_rectangle() {
+        let shape = Shape::fill(ShapePath::rectangle(Vec2::new(4.0, 2.0)), Color::RED);
+        let (positions, colors, indices) = tessellate(&shape);
+        assert_eq!(positions.len(), 4);
+        assert_eq!(indices.len(), 6);
+        assert_eq!(triangles_area(&positions, &indices), 8.0);
+        assert!(colors
+            .iter()
+            .all(|color| *color == Color::RED.as_linear_rgba_f32()));
+    }
+
+    #[test]
+    fn fill_concave_polygon() {
+        // an L shape, in clockwise order
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(0.0, 2.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(2.0, 0.0),
+        ];
+        let shape = Shape::fill(ShapePath::polygon(points), Color::RED);
+        let (positions, _, indices) = tessellate(&shape);
+        assert_eq!(positions.len(), 6);
+        assert_eq!(indices.len(), 4 * 3);
+        assert_eq!(triangles_area(&positions, &indices), 3.0);
+    }
+
+    #[test]
+    fn fill_circle() {
+        let shape = Shape::fill(ShapePath::circle(1.0), Color::RED);
+        let (positions, _, indices) = tessellate(&shape);
+        assert_eq!(positions.len(), ELLIPSE_SEGMENTS);
+        assert_eq!(indices.len(), (ELLIPSE_SEGMENTS - 2) * 3);
+    }
+
+    #[test]
+    fn stroke_closed_path() {
+        let shape = Shape::stroke(ShapePath::rectangle(Vec2::splat(2.0)), Color::BLUE, 2.0);
+        let (positions, _, indices) = tessellate(&shape);
+        // two vertices per point, and two triangles per line including the closing line
+        assert_eq!(positions.len(), 8);
+        assert_eq!(indices.len(), 4 * 6);
+        // miter joins on the corners
+        assert!(positions[0].abs_diff_eq(Vec2::ZERO, 1e-6));
+        assert!(positions[1].abs_diff_eq(Vec2::new(-2.0, -2.0), 1e-6));
+        assert!(positions[4].abs_diff_eq(Vec2::ZERO, 1e-6));
+        assert!(positions[5].abs_diff_eq(Vec2::new(2.0, 2.0), 1e-6));
+    }
+
+    #[test]
+    fn stroke_open_path() {
+        let path = ShapePath::polyline([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(2.0, 2.0),
+        ]);
+        let shape = Shape::stroke(path, Color::BLUE, 1.0);
+        let (positions, _, indices) = tessellate(&shape);
+        assert_eq!(positions.len(), 6);
+        assert_eq!(indices.len(), 2 * 6);
+        // butt caps across the ends of the path
+        assert!(positions[0].abs_diff_eq(Vec2::new(0.0, 0.5), 1e-6));
+        assert!(positions[1].abs_diff_eq(Vec2::new(0.0, -0.5), 1e-6));
+        assert!(positions[4].abs_diff_eq(Vec2::new(1.5, 2.0), 1e-6));
+        assert!(positions[5].abs_diff_eq(Vec2::new(2.5, 2.0), 1e-6));
+    }
+
+    #[test]
+    fn fill_and_stroke() {
+        let shape =
+            Shape::fill(ShapePath::rectangle(Vec2::ONE), Color::RED).with_stroke(Color::BLUE, 0.1);
+        let (positions, colors, indices) = tessellate(&shape);
+        assert_eq!(positions.len(), 4 + 8);
+        assert_eq!(indices.len(), 6 + 24);
+        // the stroke is drawn over the fill
+        assert_eq!(colors[3], Color::RED.as_linear_rgba_f32());
+        assert_eq!(colors[4], Color::BLUE.as_linear_rgba_f32());
+        assert!(indices[..6].iter().all(|&index| index < 4));
+        assert!(indices[6..].iter().all(|&index| index >= 4));
+    }
+
+    #[test]
+    fn nothing_to_draw() {
+        assert!(Shape::default().tessellate().is_none());
+        let line = ShapePath::polyline([Vec2::ZERO, Vec2::X]);
+        assert!(Shape::fill(line.clone(), Color::RED).tessellate().is_none());
+        assert!(Shape::stroke(line, Color::RED, 1.0).tessellate().is_some());
+    }
+
+    #[test]
+    fn path_segments() {
+        let path = ShapePath::new()
+            .move_to(Vec2::ZERO)
+            .quadratic_bezier_to(Vec2::new(1.0, 1.0), Vec2::new(2.0, 0.0))
+            .line_to(Vec2::ZERO)
+            .close()
+            .line_to(Vec2::new(0.0, -1.0));
+        // the point closing the subpath is dropped, and a new subpath starts at its first point
+        assert_eq!(path.subpaths.len(), 2);
+        assert_eq!(path.subpaths[0].points.len(), 1 + CURVE_SEGMENTS);
+        assert_eq!(path.subpaths[0].points[CURVE_SEGMENTS], Vec2::new(2.0, 0.0));
+        assert_eq!(path.segments().count(), CURVE_SEGMENTS + 1 + 1);
+        assert_eq!(
+            path.segments().last(),
+            Some((Vec2::ZERO, Vec2::new(0.0, -1.0)))
+        );
+    }
+}
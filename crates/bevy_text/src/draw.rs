@@ -1,4 +1,4 @@
-use crate::{PositionedGlyph, TextSection};
+use crate::{PositionedGlyph, SdfTextUniform, TextEffects, TextSection, SDF_TEXT_PIPELINE_HANDLE};
 use bevy_math::{Mat4, Vec3};
 use bevy_render::pipeline::IndexFormat;
 use bevy_render::{
@@ -9,7 +9,7 @@ use bevy_render::{
     prelude::Msaa,
     renderer::{BindGroup, RenderResourceBindings, RenderResourceId},
 };
-use bevy_sprite::TextureAtlasSprite;
+use bevy_sprite::{Anchor, TextureAtlasSprite};
 use bevy_transform::prelude::GlobalTransform;
 use bevy_utils::tracing::error;
 
@@ -22,13 +22,26 @@ pub struct DrawableText<'a> {
     pub msaa: &'a Msaa,
     pub font_quad_vertex_layout: &'a VertexBufferLayout,
     pub alignment_offset: Vec3,
+    /// The outline and glow of the glyphs drawn from distance fields
+    pub effects: TextEffects,
 }
 
-impl<'a> Drawable for DrawableText<'a> {
-    fn draw(&mut self, draw: &mut Draw, context: &mut DrawContext) -> Result<(), DrawError> {
+impl<'a> DrawableText<'a> {
+    /// Sets the pipeline of glyphs drawn from bitmaps or from distance fields
+    fn set_pipeline(
+        &mut self,
+        draw: &mut Draw,
+        context: &mut DrawContext,
+        sdf: bool,
+    ) -> Result<(), DrawError> {
+        let pipeline = if sdf {
+            SDF_TEXT_PIPELINE_HANDLE
+        } else {
+            bevy_sprite::SPRITE_SHEET_PIPELINE_HANDLE
+        };
         context.set_pipeline(
             draw,
-            &bevy_sprite::SPRITE_SHEET_PIPELINE_HANDLE.typed(),
+            &pipeline.typed(),
             &PipelineSpecialization {
                 sample_count: self.msaa.samples,
                 vertex_buffer_layout: self.font_quad_vertex_layout.clone(),
@@ -36,6 +49,19 @@ impl<'a> Drawable for DrawableText<'a> {
             },
         )?;
 
+        // set global bindings
+        context.set_bind_groups_from_bindings(draw, &mut [self.render_resource_bindings])
+    }
+}
+
+impl<'a> Drawable for DrawableText<'a> {
+    fn draw(&mut self, draw: &mut Draw, context: &mut DrawContext) -> Result<(), DrawError> {
+        let mut sdf = self
+            .text_glyphs
+            .first()
+            .is_some_and(|glyph| glyph.sdf.is_some());
+        self.set_pipeline(draw, context, sdf)?;
+
         let render_resource_context = &**context.render_resource_context;
 
         if let Some(RenderResourceId::Buffer(vertex_attribute_buffer_id)) = render_resource_context
@@ -64,33 +90,51 @@ impl<'a> Drawable for DrawableText<'a> {
             }
         }
 
-        // set global bindings
-        context.set_bind_groups_from_bindings(draw, &mut [self.render_resource_bindings])?;
-
         for tv in self.text_glyphs {
-            context.set_asset_bind_groups(draw, &tv.atlas_info.texture_atlas)?;
+            if tv.sdf.is_some() != sdf {
+                sdf = !sdf;
+                self.set_pipeline(draw, context, sdf)?;
+            }
+            match context.set_asset_bind_groups(draw, &tv.atlas_info.texture_atlas) {
+                // the font atlas of the glyph was evicted, the text is laid out again next frame
+                Err(DrawError::MissingAssetRenderResources) => continue,
+                result => result?,
+            }
 
             let sprite = TextureAtlasSprite {
                 index: tv.atlas_info.glyph_index,
                 color: self.sections[tv.section_index].style.color,
                 flip_x: false,
                 flip_y: false,
+                anchor: Anchor::Center,
+                ..Default::default()
             };
 
+            // distance fields are scaled from the font size they were generated at
+            let glyph_scale = tv.sdf.map_or(1.0, |sdf| sdf.scale);
             let transform = Mat4::from_rotation_translation(
                 self.global_transform.rotation,
                 self.global_transform.translation,
             ) * Mat4::from_scale(self.global_transform.scale / self.scale_factor)
                 * Mat4::from_translation(
                     self.alignment_offset * self.scale_factor + tv.position.extend(0.),
-                );
+                )
+                * Mat4::from_scale(Vec3::new(glyph_scale, glyph_scale, 1.0));
 
             let transform_buffer = context.get_uniform_buffer(&transform).unwrap();
             let sprite_buffer = context.get_uniform_buffer(&sprite).unwrap();
-            let sprite_bind_group = BindGroup::build()
+            let mut sprite_bind_group = BindGroup::build()
                 .add_binding(0, transform_buffer)
-                .add_binding(1, sprite_buffer)
-                .finish();
+                .add_binding(1, sprite_buffer);
+            if let Some(glyph_sdf) = tv.sdf {
+                let sdf_text = SdfTextUniform {
+                    effects: self.effects,
+                    distance_scale: 2.0 * glyph_sdf.spread * glyph_sdf.scale / self.scale_factor,
+                };
+                let sdf_text_buffer = context.get_uniform_buffer(&sdf_text).unwrap();
+                sprite_bind_group = sprite_bind_group.add_binding(2, sdf_text_buffer);
+            }
+            let sprite_bind_group = sprite_bind_group.finish();
             context.create_bind_group_resource(2, &sprite_bind_group)?;
             draw.set_bind_group(2, &sprite_bind_group);
             draw.draw_indexed(indices.clone(), 0, 0..1);
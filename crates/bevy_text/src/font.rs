@@ -1,3 +1,4 @@
+use crate::FontSdf;
 use ab_glyph::{FontArc, FontVec, InvalidFont, OutlinedGlyph};
 use bevy_reflect::TypeUuid;
 use bevy_render::texture::{Extent3d, Texture, TextureDimension, TextureFormat};
@@ -6,13 +7,15 @@ use bevy_render::texture::{Extent3d, Texture, TextureDimension, TextureFormat};
 #[uuid = "97059ac6-c9ba-4da9-95b6-bed82c3ce198"]
 pub struct Font {
     pub font: FontArc,
+    /// Draws the glyphs from signed distance fields instead of bitmaps when set
+    pub sdf: Option<FontSdf>,
 }
 
 impl Font {
     pub fn try_from_bytes(font_data: Vec<u8>) -> Result<Self, InvalidFont> {
         let font = FontVec::try_from_vec(font_data)?;
         let font = FontArc::new(font);
-        Ok(Font { font })
+        Ok(Font { font, sdf: None })
     }
 
     pub fn get_outlined_glyph_texture(outlined_glyph: OutlinedGlyph) -> Texture {
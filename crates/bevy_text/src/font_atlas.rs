@@ -40,6 +40,7 @@ pub struct FontAtlas {
     pub dynamic_texture_atlas_builder: DynamicTextureAtlasBuilder,
     pub glyph_to_atlas_index: HashMap<(GlyphId, SubpixelOffset), u32>,
     pub texture_atlas: Handle<TextureAtlas>,
+    pub(crate) last_used: u64,
 }
 
 impl FontAtlas {
@@ -59,6 +60,7 @@ impl FontAtlas {
             texture_atlas: texture_atlases.add(texture_atlas),
             glyph_to_atlas_index: HashMap::default(),
             dynamic_texture_atlas_builder: DynamicTextureAtlasBuilder::new(size, 1),
+            last_used: 0,
         }
     }
 
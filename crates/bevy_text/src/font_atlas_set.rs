@@ -10,10 +10,34 @@ use bevy_utils::HashMap;
 
 type FontSizeKey = FloatOrd;
 
+/// Limits on the texture memory the glyphs of each font are rasterized in
+#[derive(Debug, Clone)]
+pub struct FontAtlasSettings {
+    /// The size in pixels of new font atlases
+    pub page_size: Vec2,
+    /// The size in pixels font atlases grow to when they are full, before another atlas is added
+    pub max_page_size: Vec2,
+    /// The most atlases of each font, across every font size. Adding another evicts the least
+    /// recently used atlas of the font, so it should be more than the atlases of the text shown
+    /// at once.
+    pub max_pages: usize,
+}
+
+impl Default for FontAtlasSettings {
+    fn default() -> Self {
+        Self {
+            page_size: Vec2::new(512.0, 512.0),
+            max_page_size: Vec2::new(1024.0, 1024.0),
+            max_pages: 16,
+        }
+    }
+}
+
 #[derive(TypeUuid)]
 #[uuid = "73ba778b-b6b5-4f45-982d-d21b6b86ace2"]
 pub struct FontAtlasSet {
     font_atlases: HashMap<FontSizeKey, Vec<FontAtlas>>,
+    uses: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -26,6 +50,7 @@ impl Default for FontAtlasSet {
     fn default() -> Self {
         FontAtlasSet {
             font_atlases: HashMap::with_capacity_and_hasher(1, Default::default()),
+            uses: 0,
         }
     }
 }
@@ -45,58 +70,130 @@ impl FontAtlasSet {
             })
     }
 
+    /// Rasterizes a glyph into the atlases of its font size. The atlases grow up to
+    /// [`FontAtlasSettings::max_page_size`] before a new atlas is added, and the least recently
+    /// used atlas of the font is evicted when the font already has
+    /// [`FontAtlasSettings::max_pages`] atlases.
     pub fn add_glyph_to_atlas(
         &mut self,
         texture_atlases: &mut Assets<TextureAtlas>,
         textures: &mut Assets<Texture>,
+        font_atlas_settings: &FontAtlasSettings,
         outlined_glyph: OutlinedGlyph,
     ) -> Result<GlyphAtlasInfo, TextError> {
         let glyph = outlined_glyph.glyph();
         let glyph_id = glyph.id;
         let glyph_position = glyph.position;
         let font_size = glyph.scale.y;
-        let font_atlases = self
+        let glyph_texture = Font::get_outlined_glyph_texture(outlined_glyph);
+        self.add_glyph_texture_to_atlas(
+            texture_atlases,
+            textures,
+            font_atlas_settings,
+            font_size,
+            glyph_id,
+            glyph_position,
+            &glyph_texture,
+        )
+    }
+
+    /// Adds a glyph that was already rasterized to the atlases of a font size, like
+    /// [`Self::add_glyph_to_atlas`]. New atlases are sampled with the sampler of the glyph texture.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_glyph_texture_to_atlas(
+        &mut self,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        textures: &mut Assets<Texture>,
+        font_atlas_settings: &FontAtlasSettings,
+        font_size: f32,
+        glyph_id: GlyphId,
+        glyph_position: Point,
+        glyph_texture: &Texture,
+    ) -> Result<GlyphAtlasInfo, TextError> {
+        let added = self
             .font_atlases
-            .entry(FloatOrd(font_size))
-            .or_insert_with(|| {
-                vec![FontAtlas::new(
-                    textures,
-                    texture_atlases,
-                    Vec2::new(512.0, 512.0),
-                )]
+            .get_mut(&FloatOrd(font_size))
+            .is_some_and(|font_atlases| {
+                font_atlases.iter_mut().any(|atlas| {
+                    atlas.add_glyph(
+                        textures,
+                        texture_atlases,
+                        glyph_id,
+                        glyph_position.into(),
+                        glyph_texture,
+                    )
+                })
             });
-        let glyph_texture = Font::get_outlined_glyph_texture(outlined_glyph);
-        let add_char_to_font_atlas = |atlas: &mut FontAtlas| -> bool {
-            atlas.add_glyph(
+        if !added {
+            let mut font_atlas =
+                FontAtlas::new(textures, texture_atlases, font_atlas_settings.page_size);
+            font_atlas.dynamic_texture_atlas_builder.max_size = font_atlas_settings.max_page_size;
+            let atlas_texture = &texture_atlases
+                .get(&font_atlas.texture_atlas)
+                .unwrap()
+                .texture;
+            textures.get_mut(atlas_texture).unwrap().sampler = glyph_texture.sampler;
+            if !font_atlas.add_glyph(
                 textures,
                 texture_atlases,
                 glyph_id,
                 glyph_position.into(),
-                &glyph_texture,
-            )
-        };
-        if !font_atlases.iter_mut().any(add_char_to_font_atlas) {
-            font_atlases.push(FontAtlas::new(
-                textures,
-                texture_atlases,
-                Vec2::new(512.0, 512.0),
-            ));
-            if !font_atlases.last_mut().unwrap().add_glyph(
-                textures,
-                texture_atlases,
-                glyph_id,
-                glyph_position.into(),
-                &glyph_texture,
+                glyph_texture,
             ) {
+                remove_font_atlas(font_atlas, texture_atlases, textures);
                 return Err(TextError::FailedToAddGlyph(glyph_id));
             }
+            if self.len() >= font_atlas_settings.max_pages {
+                self.evict_least_recently_used(texture_atlases, textures);
+            }
+            self.font_atlases
+                .entry(FloatOrd(font_size))
+                .or_default()
+                .push(font_atlas);
         }
 
         Ok(self
-            .get_glyph_atlas_info(font_size, glyph_id, glyph_position)
+            .use_glyph_atlas_info(font_size, glyph_id, glyph_position)
             .unwrap())
     }
 
+    /// The number of atlases of the font, across every font size
+    pub fn len(&self) -> usize {
+        self.font_atlases.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.font_atlases.is_empty()
+    }
+
+    /// Removes the atlas that went the longest without glyphs being looked up in it or added to
+    /// it. Text with glyphs in the atlas is laid out again when the removal of its
+    /// [`TextureAtlas`] is noticed.
+    fn evict_least_recently_used(
+        &mut self,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        textures: &mut Assets<Texture>,
+    ) {
+        let least_recently_used = self
+            .font_atlases
+            .iter()
+            .flat_map(|(font_size, font_atlases)| {
+                font_atlases
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, atlas)| (atlas.last_used, *font_size, index))
+            })
+            .min();
+        if let Some((_, font_size, index)) = least_recently_used {
+            let font_atlases = self.font_atlases.get_mut(&font_size).unwrap();
+            let font_atlas = font_atlases.remove(index);
+            if font_atlases.is_empty() {
+                self.font_atlases.remove(&font_size);
+            }
+            remove_font_atlas(font_atlas, texture_atlases, textures);
+        }
+    }
+
     pub fn get_glyph_atlas_info(
         &self,
         font_size: f32,
@@ -119,4 +216,128 @@ impl FontAtlasSet {
                     })
             })
     }
+
+    /// Like [`Self::get_glyph_atlas_info`], and marks the atlas of the glyph as used, so that
+    /// atlases that weren't used since are evicted first
+    pub fn use_glyph_atlas_info(
+        &mut self,
+        font_size: f32,
+        glyph_id: GlyphId,
+        position: Point,
+    ) -> Option<GlyphAtlasInfo> {
+        self.uses += 1;
+        let uses = self.uses;
+        self.font_atlases
+            .get_mut(&FloatOrd(font_size))?
+            .iter_mut()
+            .find_map(|atlas| {
+                let glyph_index = atlas.get_glyph_index(glyph_id, position.into())?;
+                atlas.last_used = uses;
+                Some(GlyphAtlasInfo {
+                    texture_atlas: atlas.texture_atlas.clone_weak(),
+                    glyph_index,
+                })
+            })
+    }
+}
+
+fn remove_font_atlas(
+    font_atlas: FontAtlas,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    textures: &mut Assets<Texture>,
+) {
+    if let Some(texture_atlas) = texture_atlases.remove(&font_atlas.texture_atlas) {
+        textures.remove(&texture_atlas.texture);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ab_glyph::point;
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin};
+    use bevy_render::texture::{Extent3d, TextureDimension, TextureFormat};
+    use bevy_tasks::{IoTaskPool, TaskPool};
+
+    /// Adds a glyph that takes 16x16 pixels with the padding of font atlases, to pages of 16x16
+    /// pixels that grow to 32x32 pixels, with at most 2 pages. Returns the page of the glyph.
+    fn add_test_glyph(
+        font_atlas_set: &mut FontAtlasSet,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        textures: &mut Assets<Texture>,
+        font_size: f32,
+        glyph_id: u16,
+    ) -> Handle<TextureAtlas> {
+        let settings = FontAtlasSettings {
+            page_size: Vec2::new(16.0, 16.0),
+            max_page_size: Vec2::new(32.0, 32.0),
+            max_pages: 2,
+        };
+        let glyph_texture = Texture::new_fill(
+            Extent3d::new(15, 15, 1),
+            TextureDimension::D2,
+            &[255, 255, 255, 255],
+            TextureFormat::Rgba8UnormSrgb,
+        );
+        font_atlas_set
+            .add_glyph_texture_to_atlas(
+                texture_atlases,
+                textures,
+                &settings,
+                font_size,
+                GlyphId(glyph_id),
+                point(0.0, 0.0),
+                &glyph_texture,
+            )
+            .unwrap()
+            .texture_atlas
+    }
+
+    #[test]
+    fn grow_and_evict_atlases() {
+        let mut app = App::new();
+        app.insert_resource(IoTaskPool(TaskPool::new()))
+            .add_plugin(AssetPlugin)
+            .add_asset::<Texture>()
+            .add_asset::<TextureAtlas>();
+        let mut textures = app.world.remove_resource::<Assets<Texture>>().unwrap();
+        let mut texture_atlases = app.world.remove_resource::<Assets<TextureAtlas>>().unwrap();
+        let mut font_atlas_set = FontAtlasSet::default();
+        let mut add_glyph = |font_atlas_set: &mut FontAtlasSet, font_size, glyph_id| {
+            add_test_glyph(
+                font_atlas_set,
+                &mut texture_atlases,
+                &mut textures,
+                font_size,
+                glyph_id,
+            )
+        };
+
+        // the first page grows to hold 4 glyphs before a second page is added
+        let first_page = add_glyph(&mut font_atlas_set, 10.0, 0);
+        for glyph_id in 1..4 {
+            assert_eq!(add_glyph(&mut font_atlas_set, 10.0, glyph_id), first_page);
+        }
+        assert_eq!(font_atlas_set.len(), 1);
+        let second_page = add_glyph(&mut font_atlas_set, 10.0, 4);
+        assert_ne!(second_page, first_page);
+        assert_eq!(font_atlas_set.len(), 2);
+
+        // looking a glyph up makes its page the most recently used one, so adding a page for
+        // another font size evicts the second page
+        font_atlas_set.use_glyph_atlas_info(10.0, GlyphId(0), point(0.0, 0.0));
+        add_glyph(&mut font_atlas_set, 20.0, 0);
+        assert_eq!(font_atlas_set.len(), 2);
+        assert!(font_atlas_set.has_glyph(GlyphId(0), point(0.0, 0.0), 10.0));
+        assert!(!font_atlas_set.has_glyph(GlyphId(4), point(0.0, 0.0), 10.0));
+        assert!(font_atlas_set.has_glyph(GlyphId(0), point(0.0, 0.0), 20.0));
+
+        let first_page = texture_atlases.get(&first_page).unwrap();
+        assert_eq!(first_page.size, Vec2::new(32.0, 32.0));
+        assert_eq!(textures.get(&first_page.texture).unwrap().size.width, 32);
+        assert!(texture_atlases.get(&second_page).is_none());
+        assert_eq!(texture_atlases.len(), 2);
+        assert_eq!(textures.len(), 2);
+    }
 }
@@ -1,4 +1,4 @@
-use crate::Font;
+use crate::{Font, FontSdf};
 use anyhow::Result;
 use bevy_asset::{AssetLoader, LoadContext, LoadedAsset};
 use bevy_utils::BoxedFuture;
@@ -14,6 +14,14 @@ impl AssetLoader for FontLoader {
     ) -> BoxedFuture<'a, Result<()>> {
         Box::pin(async move {
             let font = Font::try_from_bytes(bytes.into())?;
+            let sdf = FontSdf::default().with_glyphs(&font.font, ' '..='~');
+            load_context.set_labeled_asset(
+                "sdf",
+                LoadedAsset::new(Font {
+                    font: font.font.clone(),
+                    sdf: Some(sdf),
+                }),
+            );
             load_context.set_default_asset(LoadedAsset::new(font));
             Ok(())
         })
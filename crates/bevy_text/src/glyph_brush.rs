@@ -1,4 +1,4 @@
-use ab_glyph::{Font as _, FontArc, Glyph, ScaleFont as _};
+use ab_glyph::{point, Font as _, FontArc, Glyph, ScaleFont as _};
 use bevy_asset::{Assets, Handle};
 use bevy_math::{Size, Vec2};
 use bevy_render::prelude::Texture;
@@ -7,7 +7,10 @@ use glyph_brush_layout::{
     FontId, GlyphPositioner, Layout, SectionGeometry, SectionGlyph, SectionText, ToSectionText,
 };
 
-use crate::{error::TextError, Font, FontAtlasSet, GlyphAtlasInfo, TextAlignment};
+use crate::{
+    error::TextError, Font, FontAtlasSet, FontAtlasSettings, GlyphAtlasInfo, GlyphSdf,
+    TextAlignment,
+};
 
 pub struct GlyphBrush {
     fonts: Vec<FontArc>,
@@ -48,6 +51,7 @@ impl GlyphBrush {
         glyphs: Vec<SectionGlyph>,
         sections: &[SectionText],
         font_atlas_set_storage: &mut Assets<FontAtlasSet>,
+        font_atlas_settings: &FontAtlasSettings,
         fonts: &Assets<Font>,
         texture_atlases: &mut Assets<TextureAtlas>,
         textures: &mut Assets<Texture>,
@@ -94,35 +98,84 @@ impl GlyphBrush {
             let glyph_position = glyph.position;
             let adjust = GlyphPlacementAdjuster::new(&mut glyph);
             let section_data = sections_data[sg.section_index];
-            if let Some(outlined_glyph) = section_data.1.font.outline_glyph(glyph) {
-                let bounds = outlined_glyph.px_bounds();
-                let handle_font_atlas: Handle<FontAtlasSet> = section_data.0.as_weak();
-                let font_atlas_set = font_atlas_set_storage
-                    .get_or_insert_with(handle_font_atlas, FontAtlasSet::default);
-
+            let font = section_data.1;
+            let handle_font_atlas: Handle<FontAtlasSet> = section_data.0.as_weak();
+            let font_atlas_set =
+                font_atlas_set_storage.get_or_insert_with(handle_font_atlas, FontAtlasSet::default);
+
+            // the glyph in the atlas, the top left corner of its texture in the layout, and the
+            // distance field it is drawn from
+            let (atlas_info, min, sdf) = if let Some(font_sdf) = &font.sdf {
+                // one distance field is drawn at every font size, rasterized at the origin
+                let sdf_glyph = match font
+                    .font
+                    .outline_glyph(glyph_id.with_scale(font_sdf.font_size))
+                {
+                    Some(sdf_glyph) => sdf_glyph,
+                    None => continue,
+                };
+                let sdf_bounds = sdf_glyph.px_bounds();
                 let atlas_info = font_atlas_set
-                    .get_glyph_atlas_info(section_data.2, glyph_id, glyph_position)
+                    .use_glyph_atlas_info(font_sdf.font_size, glyph_id, point(0.0, 0.0))
                     .map(Ok)
                     .unwrap_or_else(|| {
-                        font_atlas_set.add_glyph_to_atlas(texture_atlases, textures, outlined_glyph)
+                        font_atlas_set.add_glyph_texture_to_atlas(
+                            texture_atlases,
+                            textures,
+                            font_atlas_settings,
+                            font_sdf.font_size,
+                            glyph_id,
+                            point(0.0, 0.0),
+                            &font_sdf.glyph_texture(sdf_glyph),
+                        )
                     })?;
 
-                let texture_atlas = texture_atlases.get(&atlas_info.texture_atlas).unwrap();
-                let glyph_rect = texture_atlas.textures[atlas_info.glyph_index as usize];
-                let size = Vec2::new(glyph_rect.width(), glyph_rect.height());
-
-                let x = bounds.min.x + size.x / 2.0 - min_x;
-                let y = max_y - bounds.max.y + size.y / 2.0;
-                let position = adjust.position(Vec2::new(x, y));
-
-                positioned_glyphs.push(PositionedGlyph {
-                    position,
-                    size,
-                    atlas_info,
-                    section_index: sg.section_index,
-                    byte_index,
-                });
-            }
+                let scale = section_data.2 / font_sdf.font_size;
+                let padding = font_sdf.padding() as f32;
+                let min = Vec2::new(
+                    glyph.position.x + (sdf_bounds.min.x - padding) * scale,
+                    glyph.position.y + (sdf_bounds.min.y - padding) * scale,
+                );
+                let sdf = GlyphSdf {
+                    scale,
+                    spread: font_sdf.spread,
+                };
+                (atlas_info, min, Some(sdf))
+            } else if let Some(outlined_glyph) = font.font.outline_glyph(glyph) {
+                let bounds = outlined_glyph.px_bounds();
+                let atlas_info = font_atlas_set
+                    .use_glyph_atlas_info(section_data.2, glyph_id, glyph_position)
+                    .map(Ok)
+                    .unwrap_or_else(|| {
+                        font_atlas_set.add_glyph_to_atlas(
+                            texture_atlases,
+                            textures,
+                            font_atlas_settings,
+                            outlined_glyph,
+                        )
+                    })?;
+                (atlas_info, Vec2::new(bounds.min.x, bounds.min.y), None)
+            } else {
+                continue;
+            };
+
+            let texture_atlas = texture_atlases.get(&atlas_info.texture_atlas).unwrap();
+            let glyph_rect = texture_atlas.textures[atlas_info.glyph_index as usize];
+            let scale = sdf.map_or(1.0, |sdf| sdf.scale);
+            let size = Vec2::new(glyph_rect.width(), glyph_rect.height()) * scale;
+
+            let x = min.x + size.x / 2.0 - min_x;
+            let y = max_y - min.y - size.y / 2.0;
+            let position = adjust.position(Vec2::new(x, y));
+
+            positioned_glyphs.push(PositionedGlyph {
+                position,
+                size,
+                atlas_info,
+                sdf,
+                section_index: sg.section_index,
+                byte_index,
+            });
         }
         Ok(positioned_glyphs)
     }
@@ -141,6 +194,9 @@ pub struct PositionedGlyph {
     pub position: Vec2,
     pub size: Vec2,
     pub atlas_info: GlyphAtlasInfo,
+    /// The distance field the glyph is drawn from, for the glyphs of fonts with a
+    /// [`FontSdf`](crate::FontSdf)
+    pub sdf: Option<GlyphSdf>,
     pub section_index: usize,
     pub byte_index: usize,
 }
@@ -6,6 +6,8 @@ mod font_atlas_set;
 mod font_loader;
 mod glyph_brush;
 mod pipeline;
+mod render;
+mod sdf;
 mod text;
 mod text2d;
 
@@ -17,20 +19,25 @@ pub use font_atlas_set::*;
 pub use font_loader::*;
 pub use glyph_brush::*;
 pub use pipeline::*;
+pub use render::*;
+pub use sdf::*;
 pub use text::*;
 pub use text2d::*;
 
 pub mod prelude {
     #[doc(hidden)]
-    pub use crate::{Font, Text, Text2dBundle, TextAlignment, TextError, TextSection, TextStyle};
+    pub use crate::{
+        Font, Text, Text2dBounds, Text2dBundle, TextAlignment, TextEffects, TextError, TextSection,
+        TextStyle,
+    };
     #[doc(hidden)]
     pub use glyph_brush_layout::{HorizontalAlign, VerticalAlign};
 }
 
 use bevy_app::prelude::*;
-use bevy_asset::AddAsset;
+use bevy_asset::{AddAsset, Assets};
 use bevy_ecs::entity::Entity;
-use bevy_render::RenderStage;
+use bevy_render::{pipeline::PipelineDescriptor, shader::Shader, RenderStage};
 
 pub type DefaultTextPipeline = TextPipeline<Entity>;
 
@@ -43,7 +50,15 @@ impl Plugin for TextPlugin {
             .add_asset::<FontAtlasSet>()
             .init_asset_loader::<FontLoader>()
             .init_resource::<DefaultTextPipeline>()
+            .init_resource::<FontAtlasSettings>()
             .add_system_to_stage(CoreStage::PostUpdate, text2d_system)
             .add_system_to_stage(RenderStage::Draw, text2d::draw_text2d_system);
+
+        let world_cell = app.world.cell();
+        let mut pipelines = world_cell
+            .get_resource_mut::<Assets<PipelineDescriptor>>()
+            .unwrap();
+        let mut shaders = world_cell.get_resource_mut::<Assets<Shader>>().unwrap();
+        render::add_text_pipelines(&mut pipelines, &mut shaders);
     }
 }
@@ -10,8 +10,8 @@ use bevy_utils::HashMap;
 use glyph_brush_layout::{FontId, SectionText};
 
 use crate::{
-    error::TextError, glyph_brush::GlyphBrush, scale_value, Font, FontAtlasSet, PositionedGlyph,
-    TextAlignment, TextSection,
+    error::TextError, glyph_brush::GlyphBrush, scale_value, Font, FontAtlasSet, FontAtlasSettings,
+    PositionedGlyph, TextAlignment, TextSection,
 };
 
 pub struct TextPipeline<ID> {
@@ -33,6 +33,17 @@ impl<ID> Default for TextPipeline<ID> {
 pub struct TextLayoutInfo {
     pub glyphs: Vec<PositionedGlyph>,
     pub size: Size,
+    /// The scale factor the glyphs were laid out and rasterized with
+    pub scale_factor: f64,
+}
+
+impl TextLayoutInfo {
+    /// Whether any of the glyphs is in one of the given texture atlases
+    pub fn has_glyphs_in(&self, texture_atlases: &[HandleId]) -> bool {
+        self.glyphs
+            .iter()
+            .any(|glyph| texture_atlases.contains(&glyph.atlas_info.texture_atlas.id))
+    }
 }
 
 impl<ID: Hash + Eq> TextPipeline<ID> {
@@ -58,6 +69,7 @@ impl<ID: Hash + Eq> TextPipeline<ID> {
         text_alignment: TextAlignment,
         bounds: Size,
         font_atlas_set_storage: &mut Assets<FontAtlasSet>,
+        font_atlas_settings: &FontAtlasSettings,
         texture_atlases: &mut Assets<TextureAtlas>,
         textures: &mut Assets<Texture>,
     ) -> Result<(), TextError> {
@@ -93,6 +105,7 @@ impl<ID: Hash + Eq> TextPipeline<ID> {
                 TextLayoutInfo {
                     glyphs: Vec::new(),
                     size: Size::new(0., 0.),
+                    scale_factor,
                 },
             );
             return Ok(());
@@ -118,12 +131,20 @@ impl<ID: Hash + Eq> TextPipeline<ID> {
             section_glyphs,
             &sections,
             font_atlas_set_storage,
+            font_atlas_settings,
             fonts,
             texture_atlases,
             textures,
         )?;
 
-        self.glyph_map.insert(id, TextLayoutInfo { glyphs, size });
+        self.glyph_map.insert(
+            id,
+            TextLayoutInfo {
+                glyphs,
+                size,
+                scale_factor,
+            },
+        );
 
         Ok(())
     }
@@ -0,0 +1,45 @@
+#version 450
+
+layout(location = 0) in vec2 v_Uv;
+layout(location = 1) in vec4 v_Color;
+
+layout(location = 0) out vec4 o_Target;
+
+layout(set = 1, binding = 2) uniform texture2D TextureAtlas_texture;
+layout(set = 1, binding = 3) uniform sampler TextureAtlas_texture_sampler;
+
+layout(set = 2, binding = 2) uniform SdfText {
+    vec4 OutlineColor;
+    vec4 GlowColor;
+    float DistanceScale;
+    float OutlineWidth;
+    float GlowWidth;
+};
+
+void main() {
+    // distance to the outline of the glyph in the units of the font size, positive inside
+    float distance = (texture(
+        sampler2D(TextureAtlas_texture, TextureAtlas_texture_sampler),
+        v_Uv).a - 0.5) * DistanceScale;
+    // the distance covered by a pixel on screen, to antialias at any scale and rotation
+    float pixel = max(fwidth(distance), 0.0001);
+
+    vec4 color = v_Color;
+    float coverage = clamp(distance / pixel + 0.5, 0.0, 1.0);
+    if (OutlineWidth > 0.0) {
+        color = mix(OutlineColor, v_Color, coverage);
+        coverage = clamp((distance + OutlineWidth) / pixel + 0.5, 0.0, 1.0);
+    }
+    color.a *= coverage;
+
+    if (GlowWidth > 0.0) {
+        float glow_distance = -distance - max(OutlineWidth, 0.0);
+        float glow = GlowColor.a * clamp(1.0 - glow_distance / GlowWidth, 0.0, 1.0);
+        // the glyph over its glow
+        float alpha = color.a + glow * (1.0 - color.a);
+        color.rgb = (color.rgb * color.a + GlowColor.rgb * glow * (1.0 - color.a))
+            / max(alpha, 0.0001);
+        color.a = alpha;
+    }
+    o_Target = color;
+}
@@ -0,0 +1,53 @@
+#version 450
+
+layout(location = 0) in vec3 Vertex_Position;
+layout(location = 1) in vec3 Vertex_Normal;
+layout(location = 2) in vec2 Vertex_Uv;
+
+layout(location = 0) out vec2 v_Uv;
+layout(location = 1) out vec4 v_Color;
+
+layout(set = 0, binding = 0) uniform CameraViewProj {
+    mat4 ViewProj;
+};
+
+layout(set = 1, binding = 0) uniform TextureAtlas_size {
+    vec2 AtlasSize;
+};
+
+struct Rect {
+    vec2 begin;
+    vec2 end;
+};
+
+layout(set = 1, binding = 1) buffer TextureAtlas_textures {
+    Rect[] Textures;
+};
+
+layout(set = 2, binding = 0) uniform Transform {
+    mat4 SpriteTransform;
+};
+
+layout(set = 2, binding = 1) uniform TextureAtlasSprite {
+    vec4 color;
+    uint index;
+    uint flip;
+    vec2 anchor;
+};
+
+void main() {
+    Rect glyph_rect = Textures[index];
+    vec2 glyph_dimensions = glyph_rect.end - glyph_rect.begin;
+    vec3 vertex_position = vec3(Vertex_Position.xy * glyph_dimensions, 0.0);
+
+    vec2 atlas_positions[4] = vec2[](
+        vec2(glyph_rect.begin.x, glyph_rect.end.y),
+        glyph_rect.begin,
+        vec2(glyph_rect.end.x, glyph_rect.begin.y),
+        glyph_rect.end
+    );
+
+    v_Uv = atlas_positions[gl_VertexIndex] / AtlasSize;
+    v_Color = color;
+    gl_Position = ViewProj * SpriteTransform * vec4(vertex_position, 1.0);
+}
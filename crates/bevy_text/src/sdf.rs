@@ -0,0 +1,371 @@
+use ab_glyph::{Font as _, FontArc, GlyphId, OutlinedGlyph};
+use bevy_asset::Handle;
+use bevy_core::Bytes;
+use bevy_ecs::component::Component;
+use bevy_render::{
+    color::Color,
+    renderer::{RenderResource, RenderResourceType},
+    texture::{Extent3d, FilterMode, Texture, TextureDimension, TextureFormat},
+};
+use bevy_utils::HashMap;
+
+/// Stands in for the squared distance to pixels that aren't in the grid
+const INF: f32 = 1e20;
+
+/// Draws the glyphs of a [`Font`](crate::Font) from signed distance fields instead of bitmaps.
+///
+/// The distance field of a glyph is generated once at [`FontSdf::font_size`], and stays sharp
+/// when the text is drawn at any font size, scale or rotation. Outlines and glows can be added to
+/// the text with [`TextEffects`].
+///
+/// Fonts loaded with the `sdf` label, like `"fonts/FiraSans-Bold.ttf#sdf"`, are drawn from
+/// distance fields, generated for the printable ASCII characters when the font is loaded.
+#[derive(Debug, Clone)]
+pub struct FontSdf {
+    /// The font size in pixels the distance fields are generated at
+    pub font_size: f32,
+    /// The distance in pixels at [`Self::font_size`] the distance fields reach on both sides of
+    /// the outlines of the glyphs. Outlines and glows wider than it are cut off.
+    pub spread: f32,
+    glyphs: HashMap<GlyphId, Texture>,
+}
+
+impl Default for FontSdf {
+    fn default() -> Self {
+        FontSdf::new(48.0, 8.0)
+    }
+}
+
+impl FontSdf {
+    pub fn new(font_size: f32, spread: f32) -> Self {
+        Self {
+            font_size,
+            spread,
+            glyphs: Default::default(),
+        }
+    }
+
+    /// Generates the distance fields of the glyphs of the given characters now, instead of when
+    /// they are first drawn
+    pub fn with_glyphs(mut self, font: &FontArc, chars: impl IntoIterator<Item = char>) -> Self {
+        for character in chars {
+            let glyph_id = font.glyph_id(character);
+            if let Some(outlined_glyph) = font.outline_glyph(glyph_id.with_scale(self.font_size)) {
+                let texture = self.generate(outlined_glyph);
+                self.glyphs.insert(glyph_id, texture);
+            }
+        }
+        self
+    }
+
+    /// The distance field of a glyph outlined at [`Self::font_size`]
+    pub fn glyph_texture(&self, outlined_glyph: OutlinedGlyph) -> Texture {
+        match self.glyphs.get(&outlined_glyph.glyph().id) {
+            Some(texture) => texture.clone(),
+            None => self.generate(outlined_glyph),
+        }
+    }
+
+    /// The number of pixels the distance fields extend past the bounds of the glyphs
+    pub fn padding(&self) -> u32 {
+        self.spread.ceil() as u32
+    }
+
+    /// Generates the distance field of a glyph from its coverage, as the distance to the nearest
+    /// pixel outside of the glyph minus the distance to the nearest pixel inside of it. Partly
+    /// covered pixels are placed by their coverage between the two, as in Mapbox's TinySDF.
+    ///
+    /// The distance is stored in the alpha channel, from 0.0 at `spread` outside of the outline to
+    /// 1.0 at `spread` inside of it.
+    fn generate(&self, outlined_glyph: OutlinedGlyph) -> Texture {
+        let bounds = outlined_glyph.px_bounds();
+        let padding = self.padding() as usize;
+        let width = bounds.width() as usize + 2 * padding;
+        let height = bounds.height() as usize + 2 * padding;
+
+        // squared distances to the nearest pixel inside and outside of the glyph
+        let mut outside = vec![INF; width * height];
+        let mut inside = vec![0.0; width * height];
+        outlined_glyph.draw(|x, y, coverage| {
+            let index = (y as usize + padding) * width + x as usize + padding;
+            if coverage >= 1.0 {
+                outside[index] = 0.0;
+                inside[index] = INF;
+            } else if coverage > 0.0 {
+                outside[index] = (0.5 - coverage).max(0.0).powi(2);
+                inside[index] = (coverage - 0.5).max(0.0).powi(2);
+            }
+        });
+        distance_transform(&mut outside, width, height);
+        distance_transform(&mut inside, width, height);
+
+        let data = outside
+            .iter()
+            .zip(inside.iter())
+            .flat_map(|(outside, inside)| {
+                let distance = outside.sqrt() - inside.sqrt();
+                let alpha = (0.5 - distance / (2.0 * self.spread)).clamp(0.0, 1.0);
+                [255, 255, 255, (alpha * 255.0).round() as u8]
+            })
+            .collect();
+        let mut texture = Texture::new(
+            Extent3d::new(width as u32, height as u32, 1),
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        );
+        texture.sampler.mag_filter = FilterMode::Linear;
+        texture
+    }
+}
+
+/// Replaces each squared distance of a grid with the smallest squared distance of a pixel plus the
+/// squared distance to it, along the columns then the rows of the grid
+fn distance_transform(grid: &mut [f32], width: usize, height: usize) {
+    let len = width.max(height);
+    let mut samples = vec![0.0; len];
+    let mut distances = vec![0.0; len];
+    let mut parabolas = vec![0; len];
+    let mut boundaries = vec![0.0; len + 1];
+    for x in 0..width {
+        for (y, sample) in samples[..height].iter_mut().enumerate() {
+            *sample = grid[y * width + x];
+        }
+        distance_transform_1d(
+            &samples[..height],
+            &mut distances[..height],
+            &mut parabolas,
+            &mut boundaries,
+        );
+        for (y, distance) in distances[..height].iter().enumerate() {
+            grid[y * width + x] = *distance;
+        }
+    }
+    for row in grid.chunks_exact_mut(width) {
+        samples[..width].copy_from_slice(row);
+        distance_transform_1d(
+            &samples[..width],
+            &mut distances[..width],
+            &mut parabolas,
+            &mut boundaries,
+        );
+        row.copy_from_slice(&distances[..width]);
+    }
+}
+
+/// The squared distance transform of a row of samples, as the lower envelope of the parabolas
+/// rooted at each sample, from "Distance Transforms of Sampled Functions" by Felzenszwalb and
+/// Huttenlocher
+fn distance_transform_1d(
+    samples: &[f32],
+    distances: &mut [f32],
+    parabolas: &mut [usize],
+    boundaries: &mut [f32],
+) {
+    let intersection = |q: usize, r: usize| {
+        ((samples[q] + (q * q) as f32) - (samples[r] + (r * r) as f32)) / (2 * (q - r)) as f32
+    };
+    let mut k = 0;
+    parabolas[0] = 0;
+    boundaries[0] = -INF;
+    boundaries[1] = INF;
+    for q in 1..samples.len() {
+        let mut s = intersection(q, parabolas[k]);
+        while s <= boundaries[k] {
+            k -= 1;
+            s = intersection(q, parabolas[k]);
+        }
+        k += 1;
+        parabolas[k] = q;
+        boundaries[k] = s;
+        boundaries[k + 1] = INF;
+    }
+
+    k = 0;
+    for (q, distance) in distances.iter_mut().enumerate() {
+        while boundaries[k + 1] < q as f32 {
+            k += 1;
+        }
+        let offset = q as f32 - parabolas[k] as f32;
+        *distance = offset * offset + samples[parabolas[k]];
+    }
+}
+
+/// The distance field a [`PositionedGlyph`](crate::PositionedGlyph) is drawn from
+#[derive(Debug, Clone, Copy)]
+pub struct GlyphSdf {
+    /// The scale from the distance field in the atlas to the glyph in the layout
+    pub scale: f32,
+    /// The [`FontSdf::spread`] of the distance field
+    pub spread: f32,
+}
+
+/// The outline and glow of text drawn from signed distance fields. It is ignored by the text of
+/// fonts without a [`FontSdf`].
+#[derive(Component, Debug, Clone, Copy)]
+pub struct TextEffects {
+    /// The width of the outline around the glyphs, in the units of the font size
+    pub outline_width: f32,
+    pub outline_color: Color,
+    /// The distance outside of the outline the glow fades out over, in the units of the font size
+    pub glow_width: f32,
+    pub glow_color: Color,
+}
+
+impl Default for TextEffects {
+    fn default() -> Self {
+        Self {
+            outline_width: 0.0,
+            outline_color: Color::BLACK,
+            glow_width: 0.0,
+            glow_color: Color::BLACK,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SdfTextUniform {
+    pub effects: TextEffects,
+    /// The distance in the units of the font size between the alpha 0.0 and 1.0 of the distance
+    /// field
+    pub distance_scale: f32,
+}
+
+impl RenderResource for SdfTextUniform {
+    fn resource_type(&self) -> Option<RenderResourceType> {
+        Some(RenderResourceType::Buffer)
+    }
+
+    fn buffer_byte_len(&self) -> Option<usize> {
+        Some(48)
+    }
+
+    fn write_buffer_bytes(&self, buffer: &mut [u8]) {
+        let (outline_color_buf, rest) = buffer.split_at_mut(16);
+        self.effects.outline_color.write_bytes(outline_color_buf);
+        let (glow_color_buf, rest) = rest.split_at_mut(16);
+        self.effects.glow_color.write_bytes(glow_color_buf);
+        [
+            self.distance_scale,
+            self.effects.outline_width,
+            self.effects.glow_width,
+            0.0,
+        ]
+        .write_bytes(rest);
+    }
+
+    fn texture(&self) -> Option<&Handle<Texture>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ab_glyph::FontRef;
+    use bevy_render::shader::{glsl_to_spirv, ShaderStage};
+
+    fn font() -> FontArc {
+        let font =
+            FontRef::try_from_slice(include_bytes!("../../../assets/fonts/FiraMono-Medium.ttf"))
+                .unwrap();
+        FontArc::new(font)
+    }
+
+    #[test]
+    fn squared_distances() {
+        // the distances to two pixels inside of a 5x3 grid
+        let mut grid = vec![INF; 15];
+        grid[0] = 0.0;
+        grid[9] = 0.0;
+        distance_transform(&mut grid, 5, 3);
+        #[rustfmt::skip]
+        assert_eq!(grid, vec![
+            0.0, 1.0, 4.0, 2.0, 1.0,
+            1.0, 2.0, 4.0, 1.0, 0.0,
+            4.0, 5.0, 5.0, 2.0, 1.0,
+        ]);
+    }
+
+    #[test]
+    fn glyph_distance_field() {
+        let font = font();
+        let sdf = FontSdf::new(32.0, 4.0);
+        let glyph = font.glyph_id('O').with_scale(sdf.font_size);
+        let outlined_glyph = font.outline_glyph(glyph).unwrap();
+        let bounds = outlined_glyph.px_bounds();
+        let texture = sdf.glyph_texture(outlined_glyph);
+
+        // padded by the spread on every side
+        let width = bounds.width() as u32 + 8;
+        let height = bounds.height() as u32 + 8;
+        assert_eq!(texture.size, Extent3d::new(width, height, 1));
+        let alpha = |x: u32, y: u32| texture.data[((y * width + x) * 4 + 3) as usize];
+        // far outside of the glyph in the corners, and outside in the hole of the O
+        assert_eq!(alpha(0, 0), 0);
+        assert_eq!(alpha(width - 1, height - 1), 0);
+        assert!(alpha(width / 2, height / 2) < 128);
+        // inside of the stroke of the O, on its left side
+        let stroke = (0..width / 2).map(|x| alpha(x, height / 2)).max().unwrap();
+        assert!(stroke > 128);
+        // and increasing toward it from the outside
+        let row = (0..width / 4)
+            .map(|x| alpha(x, height / 2))
+            .collect::<Vec<_>>();
+        assert!(row.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn pregenerated_glyphs() {
+        let font = font();
+        let sdf = FontSdf::new(32.0, 4.0).with_glyphs(&font, 'a'..='c');
+        assert_eq!(sdf.glyphs.len(), 3);
+        let glyph = font.glyph_id('b').with_scale(sdf.font_size);
+        let outlined_glyph = font.outline_glyph(glyph).unwrap();
+        assert_eq!(
+            sdf.glyph_texture(outlined_glyph).data,
+            sdf.glyphs[&font.glyph_id('b')].data
+        );
+        // glyphs without an outline are skipped
+        let sdf = sdf.with_glyphs(&font, Some(' '));
+        assert_eq!(sdf.glyphs.len(), 3);
+    }
+
+    #[test]
+    fn sdf_text_uniform() {
+        let uniform = SdfTextUniform {
+            effects: TextEffects {
+                outline_width: 0.1,
+                outline_color: Color::rgba_linear(1.0, 0.0, 0.0, 1.0),
+                glow_width: 0.2,
+                glow_color: Color::rgba_linear(0.0, 0.0, 1.0, 0.5),
+            },
+            distance_scale: 0.25,
+        };
+        let mut buffer = vec![0; uniform.buffer_byte_len().unwrap()];
+        uniform.write_buffer_bytes(&mut buffer);
+        let floats = buffer
+            .chunks(4)
+            .map(|float| f32::from_le_bytes([float[0], float[1], float[2], float[3]]))
+            .collect::<Vec<_>>();
+        assert_eq!(&floats[0..4], &[1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(&floats[4..8], &[0.0, 0.0, 1.0, 0.5]);
+        assert_eq!(&floats[8..12], &[0.25, 0.1, 0.2, 0.0]);
+    }
+
+    #[test]
+    fn sdf_text_shaders_compile() {
+        glsl_to_spirv(
+            include_str!("render/sdf_text.vert"),
+            ShaderStage::Vertex,
+            None,
+        )
+        .unwrap();
+        glsl_to_spirv(
+            include_str!("render/sdf_text.frag"),
+            ShaderStage::Fragment,
+            None,
+        )
+        .unwrap();
+    }
+}
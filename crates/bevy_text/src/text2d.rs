@@ -1,16 +1,19 @@
-use bevy_asset::Assets;
+use bevy_asset::{AssetEvent, Assets};
 use bevy_ecs::{
     bundle::Bundle,
+    component::Component,
     entity::Entity,
-    query::{Changed, QueryState, With, Without},
+    event::EventReader,
+    query::{Changed, Or, QueryState, With, Without},
     system::{Local, Query, QuerySet, Res, ResMut},
 };
 use bevy_math::{Size, Vec3};
 use bevy_render::{
+    camera::{ActiveCameras, Camera, OrthographicProjection},
     draw::{DrawContext, Drawable, OutsideFrustum},
     mesh::Mesh,
     prelude::{Draw, Msaa, Texture, Visible},
-    render_graph::base::MainPass,
+    render_graph::base::{camera::CAMERA_2D, MainPass},
     renderer::RenderResourceBindings,
 };
 use bevy_sprite::{TextureAtlas, QUAD_HANDLE};
@@ -18,7 +21,34 @@ use bevy_transform::prelude::{GlobalTransform, Transform};
 use bevy_window::Windows;
 use glyph_brush_layout::{HorizontalAlign, VerticalAlign};
 
-use crate::{DefaultTextPipeline, DrawableText, Font, FontAtlasSet, Text, Text2dSize, TextError};
+use crate::{
+    DefaultTextPipeline, DrawableText, Font, FontAtlasSet, FontAtlasSettings, Text, Text2dSize,
+    TextEffects, TextError,
+};
+
+/// The largest font size in pixels that 2D text is rasterized at when it is zoomed in. Larger
+/// text is drawn from glyphs rasterized at this size.
+pub const MAX_TEXT2D_RASTERIZED_FONT_SIZE: f32 = 256.0;
+
+/// The number of steps between each doubling of the scale 2D text is rasterized at. Text between
+/// two steps is drawn from glyphs rasterized at the nearest step, so that zooming doesn't
+/// rasterize the glyphs again every frame.
+const TEXT2D_SCALE_STEPS_PER_OCTAVE: f64 = 8.0;
+
+/// The box 2D text is laid out in, in the units of its transform. Text wraps at the width of the
+/// bounds.
+#[derive(Component, Copy, Clone, Debug)]
+pub struct Text2dBounds {
+    pub size: Size,
+}
+
+impl Default for Text2dBounds {
+    fn default() -> Self {
+        Self {
+            size: Size::new(f32::MAX, f32::MAX),
+        }
+    }
+}
 
 /// The bundle of components needed to draw text in a 2D scene via a 2D `OrthographicCameraBundle`.
 /// [Example usage.](https://github.com/bevyengine/bevy/blob/latest/examples/2d/text2d.rs)
@@ -31,6 +61,7 @@ pub struct Text2dBundle {
     pub global_transform: GlobalTransform,
     pub main_pass: MainPass,
     pub text_2d_size: Text2dSize,
+    pub text_2d_bounds: Text2dBounds,
 }
 
 impl Default for Text2dBundle {
@@ -50,19 +81,20 @@ impl Default for Text2dBundle {
             text_2d_size: Text2dSize {
                 size: Size::default(),
             },
+            text_2d_bounds: Default::default(),
         }
     }
 }
 
 /// System for drawing text in a 2D scene via a 2D `OrthographicCameraBundle`. Included in the
-/// default `TextPlugin`. Position is determined by the `Transform`'s translation, though scale and
-/// rotation are ignored.
+/// default `TextPlugin`. The text block is placed so that the point picked by its
+/// [`TextAlignment`](crate::TextAlignment) is at the `Transform`'s translation, and is rotated and
+/// scaled around it.
 #[allow(clippy::type_complexity)]
 pub fn draw_text2d_system(
     mut context: DrawContext,
     msaa: Res<Msaa>,
     meshes: Res<Assets<Mesh>>,
-    windows: Res<Windows>,
     mut render_resource_bindings: ResMut<RenderResourceBindings>,
     text_pipeline: Res<DefaultTextPipeline>,
     mut query: Query<
@@ -73,6 +105,7 @@ pub fn draw_text2d_system(
             &Text,
             &GlobalTransform,
             &Text2dSize,
+            Option<&TextEffects>,
         ),
         (With<MainPass>, Without<OutsideFrustum>),
     >,
@@ -80,13 +113,9 @@ pub fn draw_text2d_system(
     let font_quad = meshes.get(&QUAD_HANDLE).unwrap();
     let font_quad_vertex_layout = font_quad.get_vertex_buffer_layout();
 
-    let scale_factor = if let Some(window) = windows.get_primary() {
-        window.scale_factor() as f32
-    } else {
-        1.
-    };
-
-    for (entity, mut draw, visible, text, global_transform, calculated_size) in query.iter_mut() {
+    for (entity, mut draw, visible, text, global_transform, calculated_size, effects) in
+        query.iter_mut()
+    {
         if !visible.is_visible {
             continue;
         }
@@ -107,12 +136,13 @@ pub fn draw_text2d_system(
             let mut drawable_text = DrawableText {
                 render_resource_bindings: &mut render_resource_bindings,
                 global_transform: *global_transform,
-                scale_factor,
+                scale_factor: text_glyphs.scale_factor as f32,
                 msaa: &msaa,
                 text_glyphs: &text_glyphs.glyphs,
                 font_quad_vertex_layout: &font_quad_vertex_layout,
                 sections: &text.sections,
                 alignment_offset,
+                effects: effects.copied().unwrap_or_default(),
             };
 
             drawable_text.draw(&mut draw, &mut context).unwrap();
@@ -125,19 +155,34 @@ pub struct QueuedText2d {
     entities: Vec<Entity>,
 }
 
-/// Updates the TextGlyphs with the new computed glyphs from the layout
+/// Updates the TextGlyphs with the new computed glyphs from the layout. Text is laid out again
+/// when it changes, and when the scale it is drawn at by the 2D camera changes, so that its glyphs
+/// are rasterized at the size they are drawn at.
 #[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn text2d_system(
     mut queued_text: Local<QueuedText2d>,
     mut textures: ResMut<Assets<Texture>>,
     fonts: Res<Assets<Font>>,
     windows: Res<Windows>,
+    active_cameras: Res<ActiveCameras>,
+    cameras: Query<(&Camera, &OrthographicProjection, &GlobalTransform)>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
     mut font_atlas_set_storage: ResMut<Assets<FontAtlasSet>>,
+    font_atlas_settings: Res<FontAtlasSettings>,
+    mut texture_atlas_events: EventReader<AssetEvent<TextureAtlas>>,
     mut text_pipeline: ResMut<DefaultTextPipeline>,
     mut text_queries: QuerySet<(
-        QueryState<Entity, (With<MainPass>, Changed<Text>)>,
-        QueryState<(&Text, &mut Text2dSize), With<MainPass>>,
+        QueryState<Entity, (With<MainPass>, Or<(Changed<Text>, Changed<Text2dBounds>)>)>,
+        QueryState<(Entity, &Text, &GlobalTransform), With<MainPass>>,
+        QueryState<
+            (
+                &Text,
+                Option<&Text2dBounds>,
+                &GlobalTransform,
+                &mut Text2dSize,
+            ),
+            With<MainPass>,
+        >,
     )>,
 ) {
     // Adds all entities where the text or the style has changed to the local queue
@@ -145,29 +190,72 @@ pub fn text2d_system(
         queued_text.entities.push(entity);
     }
 
-    if queued_text.entities.is_empty() {
-        return;
-    }
-
-    let scale_factor = if let Some(window) = windows.get_primary() {
+    let window_scale_factor = if let Some(window) = windows.get_primary() {
         window.scale_factor()
     } else {
         1.
     };
+    // the number of physical pixels per world unit of the 2D camera
+    let pixels_per_unit = active_cameras
+        .get(CAMERA_2D)
+        .and_then(|active_camera| active_camera.entity)
+        .and_then(|entity| cameras.get(entity).ok())
+        .and_then(|(camera, projection, transform)| {
+            let window = windows.get(camera.window)?;
+            let units_per_pixel = projection.world_units_per_pixel(window.physical_width() as f32)
+                * transform.scale.x.abs();
+            (units_per_pixel > 0.0).then(|| 1.0 / units_per_pixel as f64)
+        })
+        .unwrap_or(window_scale_factor);
+    let text_scale_factor = |text: &Text, transform: &GlobalTransform| {
+        text2d_scale_factor(text, transform, pixels_per_unit, window_scale_factor)
+    };
+
+    let removed_texture_atlases = texture_atlas_events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Removed { handle } => Some(handle.id),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    // Adds all entities drawn at another scale than the one they were laid out at, or with glyphs
+    // in an evicted font atlas, to the queue
+    #[allow(clippy::float_cmp)]
+    for (entity, text, transform) in text_queries.q1().iter() {
+        if let Some(text_layout_info) = text_pipeline.get_glyphs(&entity) {
+            if (text_layout_info.scale_factor != text_scale_factor(text, transform)
+                || text_layout_info.has_glyphs_in(&removed_texture_atlases))
+                && !queued_text.entities.contains(&entity)
+            {
+                queued_text.entities.push(entity);
+            }
+        }
+    }
+
+    if queued_text.entities.is_empty() {
+        return;
+    }
 
     // Computes all text in the local queue
     let mut new_queue = Vec::new();
-    let mut query = text_queries.q1();
+    let mut query = text_queries.q2();
     for entity in queued_text.entities.drain(..) {
-        if let Ok((text, mut calculated_size)) = query.get_mut(entity) {
+        if let Ok((text, bounds, transform, mut calculated_size)) = query.get_mut(entity) {
+            let scale_factor = text_scale_factor(text, transform);
+            let bounds = bounds.copied().unwrap_or_default();
             match text_pipeline.queue_text(
                 entity,
                 &fonts,
                 &text.sections,
                 scale_factor,
                 text.alignment,
-                Size::new(f32::MAX, f32::MAX),
+                Size::new(
+                    scale_value(bounds.size.width, scale_factor).min(f32::MAX),
+                    scale_value(bounds.size.height, scale_factor).min(f32::MAX),
+                ),
                 &mut *font_atlas_set_storage,
+                &font_atlas_settings,
                 &mut *texture_atlases,
                 &mut *textures,
             ) {
@@ -195,6 +283,150 @@ pub fn text2d_system(
     queued_text.entities = new_queue;
 }
 
+/// The scale 2D text is laid out and rasterized at, from the number of physical pixels per world
+/// unit of the camera drawing it and the scale of the text. The scale is rounded to steps from
+/// the scale factor of the window, so that text drawn by an unscaled camera isn't resampled.
+fn text2d_scale_factor(
+    text: &Text,
+    transform: &GlobalTransform,
+    pixels_per_unit: f64,
+    window_scale_factor: f64,
+) -> f64 {
+    let text_scale = transform.scale.x.abs().max(transform.scale.y.abs()) as f64;
+    let scale_factor = pixels_per_unit * text_scale;
+    if !scale_factor.is_normal() {
+        return window_scale_factor;
+    }
+    let steps =
+        ((scale_factor / window_scale_factor).log2() * TEXT2D_SCALE_STEPS_PER_OCTAVE).round();
+    let scale_factor = window_scale_factor * (steps / TEXT2D_SCALE_STEPS_PER_OCTAVE).exp2();
+
+    let max_font_size = text
+        .sections
+        .iter()
+        .map(|section| section.style.font_size)
+        .fold(0.0, f32::max);
+    if max_font_size > 0.0 {
+        scale_factor.min((MAX_TEXT2D_RASTERIZED_FONT_SIZE / max_font_size) as f64)
+    } else {
+        scale_factor
+    }
+}
+
 pub fn scale_value(value: f32, factor: f64) -> f32 {
     (value as f64 * factor) as f32
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TextStyle;
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin};
+    use bevy_ecs::entity::Entity;
+    use bevy_render::color::Color;
+    use bevy_tasks::{IoTaskPool, TaskPool};
+
+    fn text_app() -> App {
+        let mut app = App::new();
+        app.insert_resource(IoTaskPool(TaskPool::new()))
+            .add_plugin(AssetPlugin)
+            .add_asset::<Font>()
+            .add_asset::<FontAtlasSet>()
+            .add_asset::<Texture>()
+            .add_asset::<TextureAtlas>()
+            .init_resource::<DefaultTextPipeline>()
+            .init_resource::<FontAtlasSettings>()
+            .init_resource::<Windows>()
+            .init_resource::<ActiveCameras>()
+            .add_system(text2d_system);
+        app
+    }
+
+    fn spawn_text(app: &mut App, bounds: Text2dBounds) -> Entity {
+        let font = Font::try_from_bytes(
+            include_bytes!("../../../assets/fonts/FiraMono-Medium.ttf").to_vec(),
+        )
+        .unwrap();
+        let font = app
+            .world
+            .get_resource_mut::<Assets<Font>>()
+            .unwrap()
+            .add(font);
+        let style = TextStyle {
+            font,
+            font_size: 20.0,
+            color: Color::WHITE,
+        };
+        app.world
+            .spawn()
+            .insert_bundle(Text2dBundle {
+                text: Text::with_section("one two three four", style, Default::default()),
+                text_2d_bounds: bounds,
+                ..Default::default()
+            })
+            .id()
+    }
+
+    fn size(app: &App, entity: Entity) -> Size {
+        app.world.get::<Text2dSize>(entity).unwrap().size
+    }
+
+    #[test]
+    fn bounds_wrapping() {
+        let mut app = text_app();
+        let unbounded = spawn_text(&mut app, Text2dBounds::default());
+        let bounds = Text2dBounds {
+            size: Size::new(80.0, f32::MAX),
+        };
+        let bounded = spawn_text(&mut app, bounds);
+        app.update();
+
+        let line = size(&app, unbounded);
+        let wrapped = size(&app, bounded);
+        assert!(line.width > 80.0);
+        assert!(wrapped.width < 80.01);
+        // "one two", "three" and "four" on three lines of monospace glyphs
+        assert!((wrapped.height - 3.0 * line.height).abs() < 0.01);
+
+        // the text is laid out again when its bounds change
+        app.world
+            .get_mut::<Text2dBounds>(bounded)
+            .unwrap()
+            .size
+            .width = f32::MAX;
+        app.update();
+        assert_eq!(size(&app, bounded), line);
+    }
+
+    #[test]
+    fn scale_factor_steps() {
+        let text = |font_size| {
+            Text::with_section(
+                "text",
+                TextStyle {
+                    font_size,
+                    ..Default::default()
+                },
+                Default::default(),
+            )
+        };
+        let unscaled = GlobalTransform::identity();
+        assert_eq!(text2d_scale_factor(&text(20.0), &unscaled, 1.0, 1.0), 1.0);
+        assert_eq!(text2d_scale_factor(&text(20.0), &unscaled, 2.0, 1.0), 2.0);
+        // rounded to the nearest of 8 steps per doubling
+        assert_eq!(text2d_scale_factor(&text(20.0), &unscaled, 1.02, 1.0), 1.0);
+        let step = text2d_scale_factor(&text(20.0), &unscaled, 1.07, 1.0);
+        assert!((step - 2f64.powf(1.0 / 8.0)).abs() < 1e-9);
+        // the steps start from the scale factor of the window
+        assert_eq!(text2d_scale_factor(&text(20.0), &unscaled, 1.5, 1.5), 1.5);
+
+        // the scale of the text and of the camera add up
+        let scaled = GlobalTransform::from_scale(Vec3::new(-2.0, 1.0, 1.0));
+        assert_eq!(text2d_scale_factor(&text(20.0), &scaled, 2.0, 1.0), 4.0);
+        // large text is rasterized at most at the largest font size
+        assert_eq!(text2d_scale_factor(&text(64.0), &unscaled, 8.0, 1.0), 4.0);
+        let flattened = GlobalTransform::from_scale(Vec3::ZERO);
+        assert_eq!(text2d_scale_factor(&text(20.0), &flattened, 1.0, 1.5), 1.5);
+    }
+}
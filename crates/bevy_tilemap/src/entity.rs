@@ -0,0 +1,200 @@
+use crate::{MapCollider, MapLayerContent, MapTile, TileMap, TileSet};
+use bevy_asset::{AssetEvent, Assets, Handle};
+use bevy_core::Name;
+use bevy_ecs::{
+    bundle::Bundle,
+    component::Component,
+    entity::Entity,
+    event::EventReader,
+    query::{Changed, With},
+    system::{Commands, Local, Query, Res},
+};
+use bevy_math::Quat;
+use bevy_render::{color::Color, draw::Visible};
+use bevy_sprite::{entity::SpriteSheetBundle, TextureAtlasSprite};
+use bevy_transform::{
+    hierarchy::{BuildChildren, ChildBuilder, DespawnRecursiveExt},
+    prelude::{Children, GlobalTransform, Transform},
+};
+use bevy_utils::HashSet;
+
+/// A component bundle for a [`TileMap`]. Once the map is loaded, an entity with a [`TileMapLayer`]
+/// is spawned as a child for each layer of the map, with the tiles, objects and IntGrid cells of
+/// the layer as its children.
+#[derive(Bundle, Default)]
+pub struct TileMapBundle {
+    pub tile_map: Handle<TileMap>,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+}
+
+/// A layer of a spawned [`TileMap`]. Layers are one unit apart on the z axis, from the bottom one
+/// to the top one.
+#[derive(Component, Debug, Clone)]
+pub struct TileMapLayer {
+    /// The index of the layer in [`TileMap::layers`]
+    pub index: usize,
+    pub name: String,
+}
+
+/// Spawns the layers of [`TileMap`]s when they are loaded, and respawns them when the map asset
+/// changes
+pub fn tile_map_spawn_system(
+    mut commands: Commands,
+    mut pending_maps: Local<HashSet<Entity>>,
+    mut tile_map_events: EventReader<AssetEvent<TileMap>>,
+    tile_maps: Res<Assets<TileMap>>,
+    changed_maps: Query<Entity, Changed<Handle<TileMap>>>,
+    maps: Query<(Entity, &Handle<TileMap>, Option<&Children>)>,
+    layers: Query<(), With<TileMapLayer>>,
+) {
+    pending_maps.extend(changed_maps.iter());
+    for event in tile_map_events.iter() {
+        if let AssetEvent::Modified { handle } = event {
+            pending_maps.extend(
+                maps.iter()
+                    .filter(|(_, map_handle, _)| *map_handle == handle)
+                    .map(|(entity, _, _)| entity),
+            );
+        }
+    }
+
+    pending_maps.retain(|&entity| {
+        let (_, handle, children) = match maps.get(entity) {
+            Ok(map) => map,
+            // the entity was despawned or its map removed
+            Err(_) => return false,
+        };
+        let tile_map = match tile_maps.get(handle) {
+            Some(tile_map) => tile_map,
+            None => return true,
+        };
+
+        for &child in children.into_iter().flat_map(|children| children.iter()) {
+            if layers.get(child).is_ok() {
+                commands.entity(child).despawn_recursive();
+            }
+        }
+        commands
+            .entity(entity)
+            .with_children(|parent| spawn_layers(parent, tile_map));
+        false
+    });
+}
+
+fn spawn_layers(parent: &mut ChildBuilder, tile_map: &TileMap) {
+    for (index, layer) in tile_map.layers.iter().enumerate() {
+        let mut layer_entity = parent.spawn_bundle((
+            TileMapLayer {
+                index,
+                name: layer.name.clone(),
+            },
+            Name::new(layer.name.clone()),
+            layer.properties.clone(),
+            Transform::from_xyz(layer.offset.x, layer.offset.y, index as f32),
+            GlobalTransform::default(),
+        ));
+        let color = Color::rgba(1.0, 1.0, 1.0, layer.opacity);
+        layer_entity.with_children(|parent| match &layer.content {
+            MapLayerContent::Tiles(tiles) => {
+                for tile in tiles {
+                    let tileset = &tile_map.tilesets[tile.tileset];
+                    let transform = Transform::from_translation(tile.position.extend(0.0));
+                    parent
+                        .spawn_bundle(tile_sprite_bundle(tile, tileset, color, transform))
+                        .insert_bundle((tile.clone(), layer_visible(layer.visible)))
+                        .with_children(|parent| spawn_tile_colliders(parent, tile, tileset));
+                }
+            }
+            MapLayerContent::Objects(objects) => {
+                for object in objects {
+                    let mut transform = Transform {
+                        translation: object.position.extend(0.0),
+                        rotation: Quat::from_rotation_z(object.rotation),
+                        ..Default::default()
+                    };
+                    let mut object_entity = parent.spawn();
+                    if let Some(tile) = &object.tile {
+                        let tileset = &tile_map.tilesets[tile.tileset];
+                        // tile objects can be scaled in the editor
+                        if let Some(MapCollider::Rectangle { size }) = &object.shape {
+                            transform.scale = (*size / tileset.tile_size).extend(1.0);
+                        }
+                        object_entity
+                            .insert_bundle(tile_sprite_bundle(tile, tileset, color, transform))
+                            .insert(layer_visible(layer.visible));
+                    } else {
+                        object_entity.insert_bundle((transform, GlobalTransform::default()));
+                    }
+                    object_entity.insert_bundle((object.clone(), Name::new(object.name.clone())));
+                    if let Some(shape) = &object.shape {
+                        object_entity.insert(shape.clone());
+                    }
+                }
+            }
+            MapLayerContent::IntGrid { cell_size, cells } => {
+                for cell in cells {
+                    parent.spawn_bundle((
+                        cell.clone(),
+                        MapCollider::Rectangle { size: *cell_size },
+                        Transform::from_translation(cell.position.extend(0.0)),
+                        GlobalTransform::default(),
+                    ));
+                }
+            }
+        });
+    }
+}
+
+fn tile_sprite_bundle(
+    tile: &MapTile,
+    tileset: &TileSet,
+    color: Color,
+    mut transform: Transform,
+) -> SpriteSheetBundle {
+    let mut sprite = TextureAtlasSprite {
+        color,
+        index: tile.index,
+        flip_x: tile.flip_x,
+        flip_y: tile.flip_y,
+        ..Default::default()
+    };
+    // a diagonal flip is a horizontal flip followed by a quarter turn, and the quarter turn
+    // swaps the axes of the other flips
+    if tile.flip_diagonal {
+        sprite.flip_x = !tile.flip_y;
+        sprite.flip_y = tile.flip_x;
+        transform.rotation *= Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+    }
+    SpriteSheetBundle {
+        sprite,
+        texture_atlas: tileset.atlas.clone(),
+        transform,
+        ..Default::default()
+    }
+}
+
+fn layer_visible(visible: bool) -> Visible {
+    Visible {
+        is_visible: visible,
+        is_transparent: true,
+    }
+}
+
+fn spawn_tile_colliders(parent: &mut ChildBuilder, tile: &MapTile, tileset: &TileSet) {
+    let colliders = match tileset.tile_colliders.get(&tile.index) {
+        Some(colliders) => colliders,
+        None => return,
+    };
+    for collider in colliders {
+        parent.spawn_bundle((
+            collider.shape.clone(),
+            Transform {
+                translation: collider.position.extend(0.0),
+                rotation: Quat::from_rotation_z(collider.rotation),
+                ..Default::default()
+            },
+            GlobalTransform::default(),
+        ));
+    }
+}
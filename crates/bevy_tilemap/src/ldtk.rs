@@ -0,0 +1,612 @@
+use crate::{
+    load_tileset_atlas, resolve_path, IntGridCell, MapCollider, MapLayer, MapLayerContent,
+    MapObject, MapProperties, MapProperty, MapTile, TileMap, TileSet, TileSetImage,
+};
+use anyhow::Result;
+use bevy_asset::{AssetIoError, AssetLoader, BoxedFuture, Handle, LoadContext, LoadedAsset};
+use bevy_math::{UVec2, Vec2};
+use bevy_reflect::TypeUuid;
+use bevy_render::color::Color;
+use bevy_sprite::TextureAtlas;
+use bevy_utils::HashMap;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
+use thiserror::Error;
+
+/// The levels of an [LDtk](https://ldtk.io/) project. Each level is also a labeled [`TileMap`]
+/// asset, like `world.ldtk#Level0`.
+#[derive(Debug, TypeUuid)]
+#[uuid = "8f0c6e1a-3b47-4d5e-a2c9-61d7e4b0f3a8"]
+pub struct LdtkProject {
+    pub levels: Vec<Handle<TileMap>>,
+    /// The levels by identifier
+    pub named_levels: HashMap<String, Handle<TileMap>>,
+}
+
+/// An error that occurs when loading an LDtk project
+#[derive(Error, Debug)]
+pub enum LdtkError {
+    #[error("invalid LDtk file: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("level `{0}` has no layers")]
+    MissingLayers(String),
+    #[error("failed to load an external level: {0}")]
+    AssetIoError(#[from] AssetIoError),
+}
+
+/// Loads [LDtk](https://ldtk.io/) `.ldtk` projects into [`LdtkProject`] assets, with a
+/// [`TileMap`] for each level. Levels saved in separate files are supported.
+#[derive(Default)]
+pub struct LdtkLoader;
+
+impl AssetLoader for LdtkLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<()>> {
+        Box::pin(async move { Ok(load_ldtk(bytes, load_context).await?) })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ldtk"]
+    }
+}
+
+#[derive(Deserialize)]
+struct Project {
+    defs: Definitions,
+    levels: Vec<Level>,
+}
+
+#[derive(Deserialize)]
+struct Definitions {
+    tilesets: Vec<TilesetDefinition>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TilesetDefinition {
+    uid: i64,
+    identifier: String,
+    rel_path: Option<String>,
+    px_wid: u32,
+    px_hei: u32,
+    tile_grid_size: u32,
+    #[serde(default)]
+    spacing: u32,
+    #[serde(default)]
+    padding: u32,
+    #[serde(default)]
+    custom_data: Vec<TileCustomData>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TileCustomData {
+    tile_id: u32,
+    data: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Level {
+    identifier: String,
+    px_hei: u32,
+    px_wid: u32,
+    #[serde(default)]
+    field_instances: Vec<FieldInstance>,
+    layer_instances: Option<Vec<LayerInstance>>,
+    external_rel_path: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LayerInstance {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    #[serde(rename = "__type")]
+    layer_type: String,
+    #[serde(rename = "__cWid")]
+    c_wid: u32,
+    #[serde(rename = "__gridSize")]
+    grid_size: u32,
+    #[serde(rename = "__opacity")]
+    opacity: f32,
+    #[serde(rename = "__pxTotalOffsetX")]
+    px_total_offset_x: i32,
+    #[serde(rename = "__pxTotalOffsetY")]
+    px_total_offset_y: i32,
+    #[serde(rename = "__tilesetDefUid")]
+    tileset_def_uid: Option<i64>,
+    visible: bool,
+    #[serde(default)]
+    int_grid_csv: Vec<i32>,
+    #[serde(default)]
+    grid_tiles: Vec<TileInstance>,
+    #[serde(default)]
+    auto_layer_tiles: Vec<TileInstance>,
+    #[serde(default)]
+    entity_instances: Vec<EntityInstance>,
+}
+
+#[derive(Deserialize)]
+struct TileInstance {
+    px: [i32; 2],
+    src: [u32; 2],
+    /// Bit 0 is the horizontal flip, bit 1 the vertical flip
+    f: u8,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EntityInstance {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    #[serde(rename = "__pivot")]
+    pivot: [f32; 2],
+    #[serde(rename = "__tile")]
+    tile: Option<TileRect>,
+    iid: Option<String>,
+    px: [f32; 2],
+    width: f32,
+    height: f32,
+    #[serde(default)]
+    field_instances: Vec<FieldInstance>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TileRect {
+    tileset_uid: i64,
+    x: u32,
+    y: u32,
+}
+
+#[derive(Deserialize)]
+struct FieldInstance {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    #[serde(rename = "__type")]
+    field_type: String,
+    #[serde(rename = "__value")]
+    value: Value,
+}
+
+async fn load_ldtk<'a, 'b>(
+    bytes: &'a [u8],
+    load_context: &'a mut LoadContext<'b>,
+) -> Result<(), LdtkError> {
+    let project: Project = serde_json::from_slice(bytes)?;
+    let directory = load_context
+        .path()
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .to_path_buf();
+
+    let mut tilesets = Vec::new();
+    for definition in &project.defs.tilesets {
+        // tilesets without an image are used for the internal icons of the editor
+        if let Some(rel_path) = &definition.rel_path {
+            let atlas = load_tileset_atlas(
+                load_context,
+                &format!("Tileset{}", definition.uid),
+                &TileSetImage {
+                    path: &resolve_path(&directory, rel_path),
+                    size: Vec2::new(definition.px_wid as f32, definition.px_hei as f32),
+                    tile_size: Vec2::splat(definition.tile_grid_size as f32),
+                    margin: definition.padding as f32,
+                    spacing: definition.spacing as f32,
+                    columns: tileset_columns(definition.px_wid, definition),
+                    tile_count: tileset_columns(definition.px_wid, definition)
+                        * tileset_columns(definition.px_hei, definition),
+                },
+            );
+            tilesets.push((definition, read_tileset(definition, atlas)));
+        }
+    }
+
+    let mut levels = Vec::new();
+    let mut named_levels = HashMap::default();
+    for (index, level) in project.levels.iter().enumerate() {
+        let external_level;
+        let level = match &level.external_rel_path {
+            Some(rel_path) => {
+                let path = resolve_path(&directory, rel_path);
+                external_level =
+                    serde_json::from_slice::<Level>(&load_context.read_asset_bytes(&path).await?)?;
+                &external_level
+            }
+            None => level,
+        };
+        let tile_map = read_level(level, &tilesets)?;
+        let handle =
+            load_context.set_labeled_asset(&format!("Level{}", index), LoadedAsset::new(tile_map));
+        named_levels.insert(level.identifier.clone(), handle.clone());
+        levels.push(handle);
+    }
+
+    load_context.set_default_asset(LoadedAsset::new(LdtkProject {
+        levels,
+        named_levels,
+    }));
+    Ok(())
+}
+
+/// The number of tiles along an axis of a tileset image of `size` pixels
+fn tileset_columns(size: u32, definition: &TilesetDefinition) -> u32 {
+    let size = size.saturating_sub(definition.padding * 2) + definition.spacing;
+    size / (definition.tile_grid_size + definition.spacing).max(1)
+}
+
+/// The index in the tileset atlas of the tile at the `x`, `y` pixel position in the image
+fn tile_index(definition: &TilesetDefinition, x: u32, y: u32) -> u32 {
+    let stride = (definition.tile_grid_size + definition.spacing).max(1);
+    let column = x.saturating_sub(definition.padding) / stride;
+    let row = y.saturating_sub(definition.padding) / stride;
+    row * tileset_columns(definition.px_wid, definition) + column
+}
+
+fn read_tileset(definition: &TilesetDefinition, atlas: Handle<TextureAtlas>) -> TileSet {
+    let tile_properties = definition
+        .custom_data
+        .iter()
+        .map(|custom_data| {
+            let mut properties = MapProperties::default();
+            properties.insert("data", MapProperty::String(custom_data.data.clone()));
+            (custom_data.tile_id, properties)
+        })
+        .collect();
+    TileSet {
+        name: definition.identifier.clone(),
+        atlas,
+        tile_size: Vec2::splat(definition.tile_grid_size as f32),
+        tile_properties,
+        tile_colliders: Default::default(),
+    }
+}
+
+fn read_level(
+    level: &Level,
+    tilesets: &[(&TilesetDefinition, TileSet)],
+) -> Result<TileMap, LdtkError> {
+    let height = level.px_hei as f32;
+    let layer_instances = level
+        .layer_instances
+        .as_ref()
+        .ok_or_else(|| LdtkError::MissingLayers(level.identifier.clone()))?;
+    let find_tileset = |uid: i64| {
+        tilesets
+            .iter()
+            .position(|(definition, _)| definition.uid == uid)
+    };
+
+    let mut layers = Vec::new();
+    // LDtk lists the layers from the top one to the bottom one
+    for instance in layer_instances.iter().rev() {
+        let layer = |content: MapLayerContent| MapLayer {
+            name: instance.identifier.clone(),
+            offset: Vec2::new(
+                instance.px_total_offset_x as f32,
+                -instance.px_total_offset_y as f32,
+            ),
+            opacity: instance.opacity,
+            visible: instance.visible,
+            properties: MapProperties::default(),
+            content,
+        };
+        let grid_size = instance.grid_size as f32;
+
+        if instance.layer_type == "IntGrid" {
+            let cells = instance
+                .int_grid_csv
+                .iter()
+                .enumerate()
+                .filter(|&(_, &value)| value != 0)
+                .map(|(i, &value)| {
+                    let grid_position =
+                        UVec2::new(i as u32 % instance.c_wid, i as u32 / instance.c_wid);
+                    IntGridCell {
+                        grid_position,
+                        position: Vec2::new(
+                            (grid_position.x as f32 + 0.5) * grid_size,
+                            height - (grid_position.y as f32 + 0.5) * grid_size,
+                        ),
+                        value,
+                    }
+                })
+                .collect();
+            layers.push(layer(MapLayerContent::IntGrid {
+                cell_size: Vec2::splat(grid_size),
+                cells,
+            }));
+        }
+
+        if let Some(tileset) = instance.tileset_def_uid.and_then(find_tileset) {
+            let definition = tilesets[tileset].0;
+            let tiles = instance
+                .grid_tiles
+                .iter()
+                .chain(instance.auto_layer_tiles.iter())
+                .map(|tile| MapTile {
+                    grid_position: UVec2::new(
+                        tile.px[0].max(0) as u32 / instance.grid_size.max(1),
+                        tile.px[1].max(0) as u32 / instance.grid_size.max(1),
+                    ),
+                    position: Vec2::new(
+                        tile.px[0] as f32 + grid_size / 2.0,
+                        height - tile.px[1] as f32 - grid_size / 2.0,
+                    ),
+                    tileset,
+                    index: tile_index(definition, tile.src[0], tile.src[1]),
+                    flip_x: tile.f & 1 != 0,
+                    flip_y: tile.f & 2 != 0,
+                    flip_diagonal: false,
+                })
+                .collect::<Vec<_>>();
+            if !tiles.is_empty() {
+                layers.push(layer(MapLayerContent::Tiles(tiles)));
+            }
+        }
+
+        if instance.layer_type == "Entities" {
+            let objects = instance
+                .entity_instances
+                .iter()
+                .map(|entity| read_entity(entity, height, tilesets, &find_tileset))
+                .collect();
+            layers.push(layer(MapLayerContent::Objects(objects)));
+        }
+    }
+
+    Ok(TileMap {
+        size: Vec2::new(level.px_wid as f32, height),
+        tilesets: tilesets
+            .iter()
+            .map(|(_, tileset)| tileset.clone())
+            .collect(),
+        layers,
+        properties: read_fields(&level.field_instances),
+    })
+}
+
+fn read_entity(
+    entity: &EntityInstance,
+    height: f32,
+    tilesets: &[(&TilesetDefinition, TileSet)],
+    find_tileset: &impl Fn(i64) -> Option<usize>,
+) -> MapObject {
+    let size = Vec2::new(entity.width, entity.height);
+    // `px` is the position of the pivot of the entity, from its top left corner
+    let center = Vec2::new(
+        entity.px[0] + (0.5 - entity.pivot[0]) * size.x,
+        entity.px[1] + (0.5 - entity.pivot[1]) * size.y,
+    );
+    let tile = entity.tile.as_ref().and_then(|rect| {
+        let tileset = find_tileset(rect.tileset_uid)?;
+        Some(MapTile {
+            grid_position: UVec2::ZERO,
+            position: Vec2::ZERO,
+            tileset,
+            index: tile_index(tilesets[tileset].0, rect.x, rect.y),
+            flip_x: false,
+            flip_y: false,
+            flip_diagonal: false,
+        })
+    });
+    let mut properties = read_fields(&entity.field_instances);
+    if let Some(iid) = &entity.iid {
+        properties.insert("iid", MapProperty::String(iid.clone()));
+    }
+
+    MapObject {
+        id: 0,
+        name: entity.identifier.clone(),
+        class: entity.identifier.clone(),
+        position: Vec2::new(center.x, height - center.y),
+        rotation: 0.0,
+        shape: Some(MapCollider::Rectangle { size }),
+        tile,
+        properties,
+    }
+}
+
+fn read_fields(fields: &[FieldInstance]) -> MapProperties {
+    let mut properties = MapProperties::default();
+    for field in fields {
+        if let Some(property) = field_property(&field.field_type, &field.value) {
+            properties.insert(field.identifier.clone(), property);
+        }
+    }
+    properties
+}
+
+/// Converts the value of a field. Null values and tile fields are skipped.
+fn field_property(field_type: &str, value: &Value) -> Option<MapProperty> {
+    if let Some(item_type) = field_type
+        .strip_prefix("Array<")
+        .and_then(|item_type| item_type.strip_suffix('>'))
+    {
+        return Some(MapProperty::List(
+            value
+                .as_array()?
+                .iter()
+                .filter_map(|item| field_property(item_type, item))
+                .collect(),
+        ));
+    }
+    let property = match field_type {
+        "Int" => MapProperty::Int(value.as_i64()?),
+        "Float" => MapProperty::Float(value.as_f64()?),
+        "Bool" => MapProperty::Bool(value.as_bool()?),
+        "FilePath" => MapProperty::File(value.as_str()?.to_string()),
+        "Color" => MapProperty::Color(Color::hex(value.as_str()?.trim_start_matches('#')).ok()?),
+        // points are in grid cells, from the top left corner of the level
+        "Point" => MapProperty::Point(Vec2::new(
+            value.get("cx")?.as_f64()? as f32,
+            value.get("cy")?.as_f64()? as f32,
+        )),
+        "EntityRef" => MapProperty::String(value.get("entityIid")?.as_str()?.to_string()),
+        // strings, multiline strings and enums
+        _ => MapProperty::String(value.as_str()?.to_string()),
+    };
+    Some(property)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::load_asset;
+    use bevy_asset::Assets;
+
+    const PROJECT: &str = r##"{
+  "jsonVersion": "1.1.3",
+  "defs": {
+    "tilesets": [
+      {
+        "uid": 1, "identifier": "Internal_Icons", "relPath": null, "pxWid": 16, "pxHei": 16,
+        "tileGridSize": 16
+      },
+      {
+        "uid": 7, "identifier": "Cavern", "relPath": "../images/cavern.png",
+        "pxWid": 40, "pxHei": 24, "tileGridSize": 8, "spacing": 0, "padding": 0,
+        "customData": [{ "tileId": 6, "data": "lava" }]
+      }
+    ]
+  },
+  "levels": [
+    {
+      "identifier": "Entrance", "pxWid": 24, "pxHei": 16,
+      "fieldInstances": [{ "__identifier": "dark", "__type": "Bool", "__value": true }],
+      "layerInstances": [
+        {
+          "__identifier": "Entities", "__type": "Entities", "__cWid": 3, "__gridSize": 8,
+          "__opacity": 1, "__pxTotalOffsetX": 0, "__pxTotalOffsetY": 0,
+          "__tilesetDefUid": null, "visible": true,
+          "entityInstances": [
+            {
+              "__identifier": "Door", "__pivot": [0.5, 1],
+              "__tile": { "tilesetUid": 7, "x": 8, "y": 8, "w": 8, "h": 8 },
+              "iid": "door-1", "px": [12, 16], "width": 8, "height": 16,
+              "fieldInstances": [
+                {
+                  "__identifier": "target", "__type": "EntityRef",
+                  "__value": { "entityIid": "door-2" }
+                },
+                { "__identifier": "keys", "__type": "Array<Int>", "__value": [1, 2] },
+                { "__identifier": "label", "__type": "String", "__value": null }
+              ]
+            }
+          ]
+        },
+        {
+          "__identifier": "Walls", "__type": "IntGrid", "__cWid": 3, "__gridSize": 8,
+          "__opacity": 0.5, "__pxTotalOffsetX": 2, "__pxTotalOffsetY": 4,
+          "__tilesetDefUid": 7, "visible": true,
+          "intGridCsv": [1, 0, 0, 0, 0, 2],
+          "autoLayerTiles": [{ "px": [0, 0], "src": [8, 8], "f": 1 }]
+        }
+      ]
+    },
+    {
+      "identifier": "Depths", "pxWid": 8, "pxHei": 8, "layerInstances": null,
+      "externalRelPath": "project/Depths.ldtkl"
+    }
+  ]
+}"##;
+
+    const EXTERNAL_LEVEL: &str =
+        r#"{ "identifier": "Depths", "pxWid": 8, "pxHei": 8, "layerInstances": [] }"#;
+
+    #[test]
+    fn load_ldtk_project() {
+        let (app, handle) = load_asset::<LdtkProject>(
+            &[
+                ("levels/project.ldtk", PROJECT),
+                ("levels/project/Depths.ldtkl", EXTERNAL_LEVEL),
+            ],
+            "levels/project.ldtk",
+        );
+        let projects = app.world.get_resource::<Assets<LdtkProject>>().unwrap();
+        let tile_maps = app.world.get_resource::<Assets<TileMap>>().unwrap();
+        let project = projects.get(&handle).unwrap();
+        assert_eq!(project.levels.len(), 2);
+        assert_eq!(project.named_levels["Entrance"], project.levels[0]);
+
+        let entrance = tile_maps.get(&project.levels[0]).unwrap();
+        assert_eq!(entrance.size, Vec2::new(24.0, 16.0));
+        assert_eq!(entrance.properties.get_bool("dark"), Some(true));
+        // tilesets without an image are skipped
+        assert_eq!(entrance.tilesets.len(), 1);
+        let cavern = &entrance.tilesets[0];
+        assert_eq!(cavern.name, "Cavern");
+        assert_eq!(cavern.tile_properties[&6].get_str("data"), Some("lava"));
+
+        // layers are listed from the bottom one, and IntGrid layers with a tileset have both
+        assert_eq!(entrance.layers.len(), 3);
+        let walls = &entrance.layers[0];
+        assert_eq!(walls.name, "Walls");
+        assert_eq!(walls.offset, Vec2::new(2.0, -4.0));
+        assert_eq!(walls.opacity, 0.5);
+        match &walls.content {
+            MapLayerContent::IntGrid { cell_size, cells } => {
+                assert_eq!(*cell_size, Vec2::splat(8.0));
+                assert_eq!(
+                    cells,
+                    &vec![
+                        IntGridCell {
+                            grid_position: UVec2::new(0, 0),
+                            position: Vec2::new(4.0, 12.0),
+                            value: 1,
+                        },
+                        IntGridCell {
+                            grid_position: UVec2::new(2, 1),
+                            position: Vec2::new(20.0, 4.0),
+                            value: 2,
+                        },
+                    ]
+                );
+            }
+            content => panic!("unexpected layer content {:?}", content),
+        }
+        match &entrance.layers[1].content {
+            MapLayerContent::Tiles(tiles) => assert_eq!(
+                tiles,
+                &vec![MapTile {
+                    grid_position: UVec2::ZERO,
+                    position: Vec2::new(4.0, 12.0),
+                    tileset: 0,
+                    index: 6,
+                    flip_x: true,
+                    flip_y: false,
+                    flip_diagonal: false,
+                }]
+            ),
+            content => panic!("unexpected layer content {:?}", content),
+        }
+        let door = match &entrance.layers[2].content {
+            MapLayerContent::Objects(objects) => &objects[0],
+            content => panic!("unexpected layer content {:?}", content),
+        };
+        assert_eq!(door.class, "Door");
+        // the pivot is at the bottom center of the door
+        assert_eq!(door.position, Vec2::new(12.0, 8.0));
+        assert_eq!(door.tile.as_ref().map(|tile| tile.index), Some(6));
+        assert_eq!(door.properties.get_str("iid"), Some("door-1"));
+        assert_eq!(door.properties.get_str("target"), Some("door-2"));
+        assert_eq!(
+            door.properties.get("keys"),
+            Some(&MapProperty::List(vec![
+                MapProperty::Int(1),
+                MapProperty::Int(2)
+            ]))
+        );
+        assert!(door.properties.get("label").is_none());
+
+        // levels saved in separate files are relative to the project
+        let depths = tile_maps.get(&project.named_levels["Depths"]).unwrap();
+        assert_eq!(depths.size, Vec2::new(8.0, 8.0));
+        assert!(depths.layers.is_empty());
+    }
+}
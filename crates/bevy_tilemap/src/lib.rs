@@ -0,0 +1,40 @@
+mod entity;
+mod ldtk;
+mod map;
+mod properties;
+#[cfg(test)]
+mod test_utils;
+mod tiled;
+
+pub use entity::*;
+pub use ldtk::*;
+pub use map::*;
+pub use properties::*;
+pub use tiled::*;
+
+pub mod prelude {
+    #[doc(hidden)]
+    pub use crate::{
+        entity::{TileMapBundle, TileMapLayer},
+        map::{IntGridCell, MapCollider, MapObject, MapTile, TileMap},
+        properties::{MapProperties, MapProperty},
+    };
+}
+
+use bevy_app::prelude::*;
+use bevy_asset::AddAsset;
+
+/// Adds support for loading [Tiled](https://www.mapeditor.org/) and [LDtk](https://ldtk.io/)
+/// maps, and for spawning them with a [`TileMapBundle`]
+#[derive(Default)]
+pub struct TileMapPlugin;
+
+impl Plugin for TileMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<TileMap>()
+            .add_asset::<LdtkProject>()
+            .init_asset_loader::<TiledLoader>()
+            .init_asset_loader::<LdtkLoader>()
+            .add_system_to_stage(CoreStage::PreUpdate, tile_map_spawn_system);
+    }
+}
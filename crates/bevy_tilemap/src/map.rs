@@ -0,0 +1,186 @@
+use crate::MapProperties;
+use bevy_asset::{AssetPath, Handle, LoadContext, LoadedAsset};
+use bevy_ecs::component::Component;
+use bevy_math::{UVec2, Vec2};
+use bevy_reflect::TypeUuid;
+use bevy_sprite::{Rect, TextureAtlas};
+use bevy_utils::HashMap;
+use std::path::{Component as PathComponent, Path, PathBuf};
+
+/// A 2D map loaded from a [Tiled](https://www.mapeditor.org/) `.tmx` file or from a level of an
+/// [LDtk](https://ldtk.io/) `.ldtk` project.
+///
+/// Positions are in pixels, relative to the bottom left corner of the map, with y pointing up.
+#[derive(Debug, TypeUuid)]
+#[uuid = "2e3b7d8f-5d2a-4a8e-9c1f-7b6a0f9d4c21"]
+pub struct TileMap {
+    /// The size of the map in pixels
+    pub size: Vec2,
+    pub tilesets: Vec<TileSet>,
+    /// The layers of the map, from the bottom one to the top one
+    pub layers: Vec<MapLayer>,
+    pub properties: MapProperties,
+}
+
+/// A set of tiles cut from a single image
+#[derive(Debug, Clone)]
+pub struct TileSet {
+    pub name: String,
+    pub atlas: Handle<TextureAtlas>,
+    pub tile_size: Vec2,
+    /// Custom properties of the tiles that have some, by index in the atlas
+    pub tile_properties: HashMap<u32, MapProperties>,
+    /// Collision shapes of the tiles that have some, by index in the atlas. The shapes are not
+    /// mirrored for flipped tiles.
+    pub tile_colliders: HashMap<u32, Vec<TileCollider>>,
+}
+
+/// A collision shape of a tile, positioned relative to the center of the tile
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileCollider {
+    pub position: Vec2,
+    /// Counter-clockwise rotation in radians
+    pub rotation: f32,
+    pub shape: MapCollider,
+}
+
+#[derive(Debug, Clone)]
+pub struct MapLayer {
+    pub name: String,
+    /// The offset of the layer content, in pixels
+    pub offset: Vec2,
+    pub opacity: f32,
+    pub visible: bool,
+    pub properties: MapProperties,
+    pub content: MapLayerContent,
+}
+
+#[derive(Debug, Clone)]
+pub enum MapLayerContent {
+    Tiles(Vec<MapTile>),
+    Objects(Vec<MapObject>),
+    /// The non-zero cells of an LDtk IntGrid layer
+    IntGrid {
+        cell_size: Vec2,
+        cells: Vec<IntGridCell>,
+    },
+}
+
+/// A tile of a map layer
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct MapTile {
+    /// The cell of the tile in the layer grid, from the top left corner
+    pub grid_position: UVec2,
+    /// The center of the tile sprite, in pixels
+    pub position: Vec2,
+    /// The index of the tileset in [`TileMap::tilesets`]
+    pub tileset: usize,
+    /// The index of the tile in the tileset atlas
+    pub index: u32,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    /// Whether the tile is flipped along its top left to bottom right diagonal, before the
+    /// other flips. Tiled uses this to rotate tiles by 90 degrees.
+    pub flip_diagonal: bool,
+}
+
+/// An object of an object layer (Tiled) or an entity of an entity layer (LDtk)
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct MapObject {
+    pub id: u64,
+    pub name: String,
+    /// The class of the object in Tiled, or the identifier of the entity in LDtk
+    pub class: String,
+    /// The position of the object in pixels. This is the center of rectangles, ellipses and
+    /// tiles, and the origin of points, polygons and polylines.
+    pub position: Vec2,
+    /// Counter-clockwise rotation in radians
+    pub rotation: f32,
+    pub shape: Option<MapCollider>,
+    /// The tile drawn by the object, if any. Its position is relative to the object.
+    pub tile: Option<MapTile>,
+    pub properties: MapProperties,
+}
+
+/// The collision shape of an object or a tile, centered on its entity
+#[derive(Component, Debug, Clone, PartialEq)]
+pub enum MapCollider {
+    Rectangle {
+        size: Vec2,
+    },
+    Ellipse {
+        size: Vec2,
+    },
+    /// A closed shape, relative to the entity position
+    Polygon {
+        points: Vec<Vec2>,
+    },
+    /// An open line, relative to the entity position
+    Polyline {
+        points: Vec<Vec2>,
+    },
+    Point,
+}
+
+/// The layout of the tiles of a tileset image
+pub(crate) struct TileSetImage<'a> {
+    /// The path of the image, relative to the asset folder
+    pub path: &'a Path,
+    pub size: Vec2,
+    pub tile_size: Vec2,
+    /// The space around the tiles at the borders of the image
+    pub margin: f32,
+    /// The space between tiles
+    pub spacing: f32,
+    pub columns: u32,
+    pub tile_count: u32,
+}
+
+/// Adds the atlas of a tileset image as a labeled asset, with the image as a dependency
+pub(crate) fn load_tileset_atlas(
+    load_context: &mut LoadContext,
+    label: &str,
+    image: &TileSetImage,
+) -> Handle<TextureAtlas> {
+    let image_path = AssetPath::new(image.path.to_path_buf(), None);
+    let mut atlas =
+        TextureAtlas::new_empty(load_context.get_handle(image_path.clone()), image.size);
+    for index in 0..image.tile_count {
+        let column = (index % image.columns.max(1)) as f32;
+        let row = (index / image.columns.max(1)) as f32;
+        let min = Vec2::new(
+            image.margin + column * (image.tile_size.x + image.spacing),
+            image.margin + row * (image.tile_size.y + image.spacing),
+        );
+        atlas.add_texture(Rect {
+            min,
+            max: min + image.tile_size,
+        });
+    }
+    load_context.set_labeled_asset(label, LoadedAsset::new(atlas).with_dependency(image_path))
+}
+
+/// Resolves a path relative to `directory`, removing the `.` and `..` components
+pub(crate) fn resolve_path(directory: &Path, relative: &str) -> PathBuf {
+    let mut path = PathBuf::new();
+    for component in directory.join(relative).components() {
+        match component {
+            PathComponent::CurDir => {}
+            PathComponent::ParentDir => {
+                path.pop();
+            }
+            component => path.push(component),
+        }
+    }
+    path
+}
+
+/// A non-zero cell of an LDtk IntGrid layer
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct IntGridCell {
+    /// The cell in the layer grid, from the top left corner
+    pub grid_position: UVec2,
+    /// The center of the cell, in pixels
+    pub position: Vec2,
+    pub value: i32,
+}
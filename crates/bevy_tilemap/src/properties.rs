@@ -0,0 +1,88 @@
+use bevy_ecs::component::Component;
+use bevy_math::Vec2;
+use bevy_render::color::Color;
+use bevy_utils::HashMap;
+
+/// A typed custom property set in the map editor
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapProperty {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Color(Color),
+    /// A path to a file, relative to the map file
+    File(String),
+    /// A reference to another object of the map, by id
+    Object(u64),
+    Point(Vec2),
+    List(Vec<MapProperty>),
+}
+
+/// The custom properties of a map, layer, tile or object, by name
+#[derive(Component, Debug, Default, Clone, PartialEq)]
+pub struct MapProperties {
+    properties: HashMap<String, MapProperty>,
+}
+
+impl MapProperties {
+    pub fn get(&self, name: &str) -> Option<&MapProperty> {
+        self.properties.get(name)
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        match self.get(name)? {
+            MapProperty::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the property as an integer. Float properties are not converted.
+    pub fn get_int(&self, name: &str) -> Option<i64> {
+        match self.get(name)? {
+            MapProperty::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the property as a float. Integer properties are converted.
+    pub fn get_float(&self, name: &str) -> Option<f64> {
+        match self.get(name)? {
+            MapProperty::Float(value) => Some(*value),
+            MapProperty::Int(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        match self.get(name)? {
+            MapProperty::String(value) | MapProperty::File(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_color(&self, name: &str) -> Option<Color> {
+        match self.get(name)? {
+            MapProperty::Color(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, property: MapProperty) {
+        self.properties.insert(name.into(), property);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &MapProperty)> {
+        self.properties
+            .iter()
+            .map(|(name, property)| (name.as_str(), property))
+    }
+
+    pub fn len(&self) -> usize {
+        self.properties.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{LdtkLoader, LdtkProject, TileMap, TiledLoader};
+use bevy_app::App;
+use bevy_asset::{
+    AddAsset, Asset, AssetPlugin, AssetServer, AssetServerSettings, Assets, Handle, LoadState,
+};
+use bevy_sprite::TextureAtlas;
+use bevy_tasks::{IoTaskPool, TaskPool};
+use std::time::Duration;
+
+/// Writes the files, given by path, to a temporary asset folder and loads the asset at `path`
+/// with the map loaders. The images of the tilesets aren't loaded.
+pub(crate) fn load_asset<T: Asset>(files: &[(&str, &str)], path: &str) -> (App, Handle<T>) {
+    let asset_folder = tempfile::tempdir().unwrap();
+    for (file_path, content) in files {
+        let file_path = asset_folder.path().join(file_path);
+        std::fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        std::fs::write(file_path, content).unwrap();
+    }
+
+    let mut app = App::new();
+    app.insert_resource(IoTaskPool(TaskPool::new()))
+        .insert_resource(AssetServerSettings {
+            asset_folder: asset_folder.path().to_string_lossy().to_string(),
+        })
+        .add_plugin(AssetPlugin)
+        .add_asset::<TextureAtlas>()
+        .add_asset::<TileMap>()
+        .add_asset::<LdtkProject>()
+        .init_asset_loader::<TiledLoader>()
+        .init_asset_loader::<LdtkLoader>();
+    let handle: Handle<T> = app.world.get_resource::<AssetServer>().unwrap().load(path);
+
+    for _ in 0..1000 {
+        app.update();
+        if app
+            .world
+            .get_resource::<Assets<T>>()
+            .unwrap()
+            .contains(&handle)
+        {
+            return (app, handle);
+        }
+        let asset_server = app.world.get_resource::<AssetServer>().unwrap();
+        if asset_server.get_load_state(&handle) == LoadState::Failed {
+            panic!("failed to load {}", path);
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    panic!("{} wasn't loaded in time", path);
+}
@@ -0,0 +1,797 @@
+use crate::{
+    load_tileset_atlas, resolve_path, MapCollider, MapLayer, MapLayerContent, MapObject,
+    MapProperties, MapProperty, MapTile, TileCollider, TileMap, TileSet, TileSetImage,
+};
+use anyhow::Result;
+use bevy_asset::{AssetIoError, AssetLoader, BoxedFuture, LoadContext, LoadedAsset};
+use bevy_log::warn;
+use bevy_math::{Mat2, UVec2, Vec2};
+use bevy_render::color::Color;
+use bevy_utils::HashMap;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use roxmltree::{Document, Node};
+use std::{io::Read, path::Path, str::FromStr};
+use thiserror::Error;
+
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+const TILE_ID_MASK: u32 = 0x1FFF_FFFF;
+
+/// An error that occurs when loading a Tiled map
+#[derive(Error, Debug)]
+pub enum TiledError {
+    #[error("invalid UTF-8: {0}")]
+    Utf8(#[from] std::str::Utf8Error),
+    #[error("invalid XML: {0}")]
+    Xml(#[from] roxmltree::Error),
+    #[error("missing attribute `{attribute}` on `<{element}>`")]
+    MissingAttribute {
+        element: String,
+        attribute: &'static str,
+    },
+    #[error("missing element `<{0}>`")]
+    MissingElement(&'static str),
+    #[error("invalid value `{value}` for `{name}`")]
+    InvalidValue { name: String, value: String },
+    #[error("unsupported map orientation `{0}`, only orthogonal maps are supported")]
+    UnsupportedOrientation(String),
+    #[error("infinite maps are not supported")]
+    InfiniteMap,
+    #[error("tileset `{0}` is a collection of images, which is not supported")]
+    ImageCollection(String),
+    #[error("unsupported tile layer encoding `{0}`")]
+    UnsupportedEncoding(String),
+    #[error("failed to decode base64 tile layer data: {0}")]
+    Base64Decode(#[from] base64::DecodeError),
+    #[error("failed to decompress tile layer data: {0}")]
+    Decompress(#[from] std::io::Error),
+    #[error("failed to load an external tileset: {0}")]
+    AssetIoError(#[from] AssetIoError),
+}
+
+/// Loads [Tiled](https://www.mapeditor.org/) `.tmx` maps into [`TileMap`] assets. Only orthogonal,
+/// finite maps are supported. Tilesets can be embedded in the map or external `.tsx` files.
+#[derive(Default)]
+pub struct TiledLoader;
+
+impl AssetLoader for TiledLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<()>> {
+        Box::pin(async move { Ok(load_tiled(bytes, load_context).await?) })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tmx"]
+    }
+}
+
+async fn load_tiled<'a, 'b>(
+    bytes: &'a [u8],
+    load_context: &'a mut LoadContext<'b>,
+) -> Result<(), TiledError> {
+    let text = std::str::from_utf8(bytes)?;
+    let directory = load_context
+        .path()
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .to_path_buf();
+
+    // the document is not kept across awaits
+    let sources = {
+        let document = Document::parse(text)?;
+        document
+            .root_element()
+            .children()
+            .filter(|node| node.has_tag_name("tileset"))
+            .filter_map(|node| node.attribute("source"))
+            .map(|source| source.to_string())
+            .collect::<Vec<_>>()
+    };
+    let mut external_tilesets = HashMap::default();
+    for source in sources {
+        let path = resolve_path(&directory, &source);
+        let bytes = load_context.read_asset_bytes(&path).await?;
+        external_tilesets.insert(source, bytes);
+    }
+
+    let document = Document::parse(text)?;
+    let tile_map = read_map(
+        document.root_element(),
+        &directory,
+        &external_tilesets,
+        load_context,
+    )?;
+    load_context.set_default_asset(LoadedAsset::new(tile_map));
+    Ok(())
+}
+
+/// The data needed to place the tiles and objects of a map
+struct MapContext {
+    /// The first global tile id of each tileset
+    first_gids: Vec<u32>,
+    tile_sizes: Vec<Vec2>,
+    tile_size: Vec2,
+    rows: u32,
+    height: f32,
+}
+
+impl MapContext {
+    /// Returns the tile of a global tile id, positioned at the origin
+    fn tile(&self, gid: u32) -> Option<MapTile> {
+        let id = gid & TILE_ID_MASK;
+        if id == 0 {
+            return None;
+        }
+        let tileset = self.first_gids.iter().rposition(|&first| first <= id)?;
+        Some(MapTile {
+            grid_position: UVec2::ZERO,
+            position: Vec2::ZERO,
+            tileset,
+            index: id - self.first_gids[tileset],
+            flip_x: gid & FLIPPED_HORIZONTALLY != 0,
+            flip_y: gid & FLIPPED_VERTICALLY != 0,
+            flip_diagonal: gid & FLIPPED_DIAGONALLY != 0,
+        })
+    }
+}
+
+fn read_map(
+    map: Node,
+    directory: &Path,
+    external_tilesets: &HashMap<String, Vec<u8>>,
+    load_context: &mut LoadContext,
+) -> Result<TileMap, TiledError> {
+    let orientation = map.attribute("orientation").unwrap_or("orthogonal");
+    if orientation != "orthogonal" {
+        return Err(TiledError::UnsupportedOrientation(orientation.to_string()));
+    }
+    if map.attribute("infinite") == Some("1") {
+        return Err(TiledError::InfiniteMap);
+    }
+    let columns: u32 = parse_attribute(map, "width")?;
+    let rows: u32 = parse_attribute(map, "height")?;
+    let tile_size = Vec2::new(
+        parse_attribute(map, "tilewidth")?,
+        parse_attribute(map, "tileheight")?,
+    );
+    let size = Vec2::new(columns as f32, rows as f32) * tile_size;
+
+    let mut tilesets = Vec::new();
+    let mut first_gids: Vec<u32> = Vec::new();
+    for node in map.children().filter(|node| node.has_tag_name("tileset")) {
+        first_gids.push(parse_attribute(node, "firstgid")?);
+        let tileset = match node.attribute("source") {
+            Some(source) => {
+                let path = resolve_path(directory, source);
+                let text = std::str::from_utf8(&external_tilesets[source])?;
+                let document = Document::parse(text)?;
+                let directory = path.parent().unwrap_or_else(|| Path::new(""));
+                read_tileset(
+                    document.root_element(),
+                    directory,
+                    tilesets.len(),
+                    load_context,
+                )?
+            }
+            None => read_tileset(node, directory, tilesets.len(), load_context)?,
+        };
+        tilesets.push(tileset);
+    }
+
+    let context = MapContext {
+        first_gids,
+        tile_sizes: tilesets.iter().map(|tileset| tileset.tile_size).collect(),
+        tile_size,
+        rows,
+        height: size.y,
+    };
+    let mut layers = Vec::new();
+    read_layers(map, Vec2::ZERO, 1.0, true, &context, &mut layers)?;
+
+    Ok(TileMap {
+        size,
+        tilesets,
+        layers,
+        properties: read_child_properties(map)?,
+    })
+}
+
+fn read_tileset(
+    node: Node,
+    directory: &Path,
+    index: usize,
+    load_context: &mut LoadContext,
+) -> Result<TileSet, TiledError> {
+    let name = node.attribute("name").unwrap_or_default().to_string();
+    let tile_size = Vec2::new(
+        parse_attribute(node, "tilewidth")?,
+        parse_attribute(node, "tileheight")?,
+    );
+    let image = node
+        .children()
+        .find(|child| child.has_tag_name("image"))
+        .ok_or_else(|| TiledError::ImageCollection(name.clone()))?;
+    let image_path = resolve_path(directory, attribute(image, "source")?);
+    let atlas = load_tileset_atlas(
+        load_context,
+        &format!("Tileset{}", index),
+        &TileSetImage {
+            path: &image_path,
+            size: Vec2::new(
+                parse_attribute(image, "width")?,
+                parse_attribute(image, "height")?,
+            ),
+            tile_size,
+            margin: parse_attribute_or(node, "margin", 0.0)?,
+            spacing: parse_attribute_or(node, "spacing", 0.0)?,
+            columns: parse_attribute(node, "columns")?,
+            tile_count: parse_attribute(node, "tilecount")?,
+        },
+    );
+
+    let mut tile_properties = HashMap::default();
+    let mut tile_colliders = HashMap::default();
+    // objects of tiles are in the space of the tile, and don't reference other tiles
+    let tile_context = MapContext {
+        first_gids: Vec::new(),
+        tile_sizes: Vec::new(),
+        tile_size,
+        rows: 1,
+        height: tile_size.y,
+    };
+    for tile in node.children().filter(|child| child.has_tag_name("tile")) {
+        let id: u32 = parse_attribute(tile, "id")?;
+        let properties = read_child_properties(tile)?;
+        if !properties.is_empty() {
+            tile_properties.insert(id, properties);
+        }
+        let colliders = tile
+            .children()
+            .filter(|child| child.has_tag_name("objectgroup"))
+            .flat_map(|group| {
+                group
+                    .children()
+                    .filter(|child| child.has_tag_name("object"))
+            })
+            .filter_map(|object| match read_object(object, &tile_context) {
+                Ok(MapObject {
+                    position,
+                    rotation,
+                    shape: Some(shape),
+                    ..
+                }) => Some(Ok(TileCollider {
+                    position: position - tile_size / 2.0,
+                    rotation,
+                    shape,
+                })),
+                Ok(_) => None,
+                Err(err) => Some(Err(err)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !colliders.is_empty() {
+            tile_colliders.insert(id, colliders);
+        }
+    }
+
+    Ok(TileSet {
+        name,
+        atlas,
+        tile_size,
+        tile_properties,
+        tile_colliders,
+    })
+}
+
+fn read_layers(
+    parent: Node,
+    offset: Vec2,
+    opacity: f32,
+    visible: bool,
+    context: &MapContext,
+    layers: &mut Vec<MapLayer>,
+) -> Result<(), TiledError> {
+    for node in parent.children().filter(|node| node.is_element()) {
+        // offsets are in pixels, with y pointing down
+        let offset = offset
+            + Vec2::new(
+                parse_attribute_or(node, "offsetx", 0.0)?,
+                -parse_attribute_or::<f32>(node, "offsety", 0.0)?,
+            );
+        let opacity = opacity * parse_attribute_or(node, "opacity", 1.0)?;
+        let visible = visible && node.attribute("visible") != Some("0");
+        let content = match node.tag_name().name() {
+            "layer" => MapLayerContent::Tiles(read_tiles(node, context)?),
+            "objectgroup" => MapLayerContent::Objects(
+                node.children()
+                    .filter(|child| child.has_tag_name("object"))
+                    .map(|object| read_object(object, context))
+                    .collect::<Result<_, _>>()?,
+            ),
+            "group" => {
+                read_layers(node, offset, opacity, visible, context, layers)?;
+                continue;
+            }
+            "imagelayer" => {
+                warn!("Tiled image layers are not supported");
+                continue;
+            }
+            _ => continue,
+        };
+        layers.push(MapLayer {
+            name: node.attribute("name").unwrap_or_default().to_string(),
+            offset,
+            opacity,
+            visible,
+            properties: read_child_properties(node)?,
+            content,
+        });
+    }
+    Ok(())
+}
+
+fn read_tiles(layer: Node, context: &MapContext) -> Result<Vec<MapTile>, TiledError> {
+    let width: u32 = parse_attribute(layer, "width")?;
+    let data = layer
+        .children()
+        .find(|child| child.has_tag_name("data"))
+        .ok_or(TiledError::MissingElement("data"))?;
+    if data.children().any(|child| child.has_tag_name("chunk")) {
+        return Err(TiledError::InfiniteMap);
+    }
+
+    let text = data.text().unwrap_or_default().trim();
+    let gids: Vec<u32> = match (data.attribute("encoding"), data.attribute("compression")) {
+        (Some("csv"), _) => text
+            .split(',')
+            .map(str::trim)
+            .filter(|gid| !gid.is_empty())
+            .map(|gid| parse_value("gid", gid))
+            .collect::<Result<_, _>>()?,
+        (Some("base64"), compression) => {
+            let bytes = base64::decode(text)?;
+            let bytes = match compression {
+                None => bytes,
+                Some("zlib") => {
+                    let mut decoded = Vec::new();
+                    ZlibDecoder::new(&bytes[..]).read_to_end(&mut decoded)?;
+                    decoded
+                }
+                Some("gzip") => {
+                    let mut decoded = Vec::new();
+                    GzDecoder::new(&bytes[..]).read_to_end(&mut decoded)?;
+                    decoded
+                }
+                Some(compression) => {
+                    return Err(TiledError::UnsupportedEncoding(format!(
+                        "base64 {}",
+                        compression
+                    )))
+                }
+            };
+            bytes
+                .chunks_exact(4)
+                .map(|gid| u32::from_le_bytes([gid[0], gid[1], gid[2], gid[3]]))
+                .collect()
+        }
+        (None, _) => data
+            .children()
+            .filter(|child| child.has_tag_name("tile"))
+            .map(|tile| parse_attribute_or(tile, "gid", 0))
+            .collect::<Result<_, _>>()?,
+        (Some(encoding), _) => return Err(TiledError::UnsupportedEncoding(encoding.to_string())),
+    };
+
+    let mut tiles = Vec::new();
+    for (i, gid) in gids.into_iter().enumerate() {
+        let (column, row) = (i as u32 % width, i as u32 / width);
+        if row >= context.rows {
+            break;
+        }
+        if let Some(mut tile) = context.tile(gid) {
+            // tiles larger than the map grid stick out at the top and right of their cell
+            let tile_size = context.tile_sizes[tile.tileset];
+            tile.grid_position = UVec2::new(column, row);
+            tile.position = Vec2::new(
+                column as f32 * context.tile_size.x,
+                (context.rows - row - 1) as f32 * context.tile_size.y,
+            ) + tile_size / 2.0;
+            tiles.push(tile);
+        }
+    }
+    Ok(tiles)
+}
+
+fn read_object(object: Node, context: &MapContext) -> Result<MapObject, TiledError> {
+    let size = Vec2::new(
+        parse_attribute_or(object, "width", 0.0)?,
+        parse_attribute_or(object, "height", 0.0)?,
+    );
+    // Tiled rotates clockwise in degrees, around the position of the object
+    let rotation = -parse_attribute_or::<f32>(object, "rotation", 0.0)?.to_radians();
+    let origin = Vec2::new(
+        parse_attribute_or(object, "x", 0.0)?,
+        context.height - parse_attribute_or(object, "y", 0.0)?,
+    );
+    let rotate = Mat2::from_angle(rotation);
+    let child = |name: &str| object.children().find(|child| child.has_tag_name(name));
+
+    let mut tile = None;
+    let (position, shape) = if let Some(gid) = object.attribute("gid") {
+        tile = context.tile(parse_value("gid", gid)?);
+        // tile objects are anchored at their bottom left corner
+        (
+            origin + rotate * (size / 2.0),
+            Some(MapCollider::Rectangle { size }),
+        )
+    } else if child("point").is_some() {
+        (origin, Some(MapCollider::Point))
+    } else if let Some(polygon) = child("polygon") {
+        let points = read_points(polygon)?;
+        (origin, Some(MapCollider::Polygon { points }))
+    } else if let Some(polyline) = child("polyline") {
+        let points = read_points(polyline)?;
+        (origin, Some(MapCollider::Polyline { points }))
+    } else {
+        let center = origin + rotate * Vec2::new(size.x / 2.0, -size.y / 2.0);
+        if child("ellipse").is_some() {
+            (center, Some(MapCollider::Ellipse { size }))
+        } else if child("text").is_some() {
+            (center, None)
+        } else {
+            (center, Some(MapCollider::Rectangle { size }))
+        }
+    };
+
+    Ok(MapObject {
+        id: parse_attribute_or(object, "id", 0)?,
+        name: object.attribute("name").unwrap_or_default().to_string(),
+        // the class was called type before Tiled 1.9
+        class: object
+            .attribute("class")
+            .or_else(|| object.attribute("type"))
+            .unwrap_or_default()
+            .to_string(),
+        position,
+        rotation,
+        shape,
+        tile,
+        properties: read_child_properties(object)?,
+    })
+}
+
+fn read_points(node: Node) -> Result<Vec<Vec2>, TiledError> {
+    attribute(node, "points")?
+        .split_whitespace()
+        .map(|point| {
+            let mut coordinates = point.split(',');
+            match (coordinates.next(), coordinates.next()) {
+                (Some(x), Some(y)) => Ok(Vec2::new(
+                    parse_value("points", x)?,
+                    -parse_value::<f32>("points", y)?,
+                )),
+                _ => Err(TiledError::InvalidValue {
+                    name: "points".to_string(),
+                    value: point.to_string(),
+                }),
+            }
+        })
+        .collect()
+}
+
+fn read_child_properties(node: Node) -> Result<MapProperties, TiledError> {
+    let mut properties = MapProperties::default();
+    let nodes = node
+        .children()
+        .filter(|child| child.has_tag_name("properties"))
+        .flat_map(|child| {
+            child
+                .children()
+                .filter(|child| child.has_tag_name("property"))
+        });
+    for property in nodes {
+        let name = attribute(property, "name")?;
+        // multiline strings are stored as text
+        let value = property
+            .attribute("value")
+            .or_else(|| property.text())
+            .unwrap_or_default();
+        let value = match property.attribute("type").unwrap_or("string") {
+            "bool" => MapProperty::Bool(value == "true"),
+            "int" => MapProperty::Int(parse_value(name, value)?),
+            "float" => MapProperty::Float(parse_value(name, value)?),
+            "color" => MapProperty::Color(parse_color(name, value)?),
+            "file" => MapProperty::File(value.to_string()),
+            "object" => MapProperty::Object(parse_value(name, value)?),
+            "class" => {
+                warn!(
+                    "Tiled class properties are not supported, skipping `{}`",
+                    name
+                );
+                continue;
+            }
+            _ => MapProperty::String(value.to_string()),
+        };
+        properties.insert(name, value);
+    }
+    Ok(properties)
+}
+
+/// Parses a Tiled color, in the `#AARRGGBB` or `#RRGGBB` format
+fn parse_color(name: &str, value: &str) -> Result<Color, TiledError> {
+    let hex = value.trim_start_matches('#');
+    let color = match hex.len() {
+        // an unset color
+        0 => Ok(Color::NONE),
+        8 => Color::hex(format!("{}{}", &hex[2..], &hex[..2])),
+        _ => Color::hex(hex),
+    };
+    color.map_err(|_| TiledError::InvalidValue {
+        name: name.to_string(),
+        value: value.to_string(),
+    })
+}
+
+fn attribute<'a>(node: Node<'a, '_>, name: &'static str) -> Result<&'a str, TiledError> {
+    node.attribute(name)
+        .ok_or_else(|| TiledError::MissingAttribute {
+            element: node.tag_name().name().to_string(),
+            attribute: name,
+        })
+}
+
+fn parse_attribute<T: FromStr>(node: Node, name: &'static str) -> Result<T, TiledError> {
+    parse_value(name, attribute(node, name)?)
+}
+
+fn parse_attribute_or<T: FromStr>(
+    node: Node,
+    name: &'static str,
+    default: T,
+) -> Result<T, TiledError> {
+    match node.attribute(name) {
+        Some(value) => parse_value(name, value),
+        None => Ok(default),
+    }
+}
+
+fn parse_value<T: FromStr>(name: &str, value: &str) -> Result<T, TiledError> {
+    value.trim().parse().map_err(|_| TiledError::InvalidValue {
+        name: name.to_string(),
+        value: value.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::load_asset;
+    use bevy_asset::{AssetServer, Assets};
+    use bevy_sprite::TextureAtlas;
+
+    const MAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" width="3" height="2" tilewidth="16" tileheight="16"
+     infinite="0">
+ <properties>
+  <property name="music" type="file" value="forest.ogg"/>
+  <property name="gravity" type="float" value="9.5"/>
+ </properties>
+ <tileset firstgid="1" name="terrain" tilewidth="16" tileheight="16" tilecount="4" columns="2">
+  <image source="terrain.png" width="32" height="32"/>
+  <tile id="1">
+   <properties>
+    <property name="solid" type="bool" value="true"/>
+   </properties>
+   <objectgroup>
+    <object id="1" x="0" y="0" width="16" height="8"/>
+   </objectgroup>
+  </tile>
+ </tileset>
+ <tileset firstgid="5" source="tilesets/props.tsx"/>
+ <layer id="1" name="ground" width="3" height="2">
+  <data encoding="csv">
+1,2,0,
+2147483650,5,0
+</data>
+ </layer>
+ <group id="2" name="decor" offsetx="4" offsety="2" opacity="0.5">
+  <objectgroup id="3" name="spawns" offsety="2" opacity="0.5" visible="0">
+   <object id="7" name="player" type="spawn" x="8" y="16" width="16" height="8"/>
+   <object id="8" x="4" y="4">
+    <point/>
+   </object>
+   <object id="9" x="0" y="32">
+    <polygon points="0,0 16,0 16,-16"/>
+   </object>
+   <object id="10" gid="6" x="16" y="32" width="16" height="16"/>
+  </objectgroup>
+ </group>
+</map>
+"#;
+
+    const PROPS_TILESET: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<tileset name="props" tilewidth="16" tileheight="32" tilecount="2" columns="2" margin="1"
+         spacing="2">
+ <image source="../images/props.png" width="36" height="34"/>
+</tileset>
+"#;
+
+    fn tile(grid_position: UVec2, position: Vec2, tileset: usize, index: u32) -> MapTile {
+        MapTile {
+            grid_position,
+            position,
+            tileset,
+            index,
+            flip_x: false,
+            flip_y: false,
+            flip_diagonal: false,
+        }
+    }
+
+    #[test]
+    fn load_tiled_map() {
+        let (app, handle) = load_asset::<TileMap>(
+            &[
+                ("maps/map.tmx", MAP),
+                ("maps/tilesets/props.tsx", PROPS_TILESET),
+            ],
+            "maps/map.tmx",
+        );
+        let tile_maps = app.world.get_resource::<Assets<TileMap>>().unwrap();
+        let map = tile_maps.get(&handle).unwrap();
+        assert_eq!(map.size, Vec2::new(48.0, 32.0));
+        assert_eq!(
+            map.properties.get("music"),
+            Some(&MapProperty::File("forest.ogg".to_string()))
+        );
+        assert_eq!(map.properties.get_float("gravity"), Some(9.5));
+
+        assert_eq!(map.tilesets.len(), 2);
+        let terrain = &map.tilesets[0];
+        assert_eq!(terrain.name, "terrain");
+        assert_eq!(terrain.tile_properties[&1].get_bool("solid"), Some(true));
+        // the collider covers the top half of the tile
+        assert_eq!(
+            terrain.tile_colliders[&1],
+            vec![TileCollider {
+                position: Vec2::new(0.0, 4.0),
+                rotation: 0.0,
+                shape: MapCollider::Rectangle {
+                    size: Vec2::new(16.0, 8.0)
+                },
+            }]
+        );
+        // external tilesets are relative to the map, and their images to the tileset
+        let props = &map.tilesets[1];
+        assert_eq!(props.name, "props");
+        assert_eq!(props.tile_size, Vec2::new(16.0, 32.0));
+        let atlases = app.world.get_resource::<Assets<TextureAtlas>>().unwrap();
+        let atlas = atlases.get(&props.atlas).unwrap();
+        let asset_server = app.world.get_resource::<AssetServer>().unwrap();
+        assert_eq!(
+            atlas.texture,
+            asset_server.get_handle("maps/images/props.png")
+        );
+        assert_eq!(atlas.textures.len(), 2);
+        assert_eq!(atlas.textures[1].min, Vec2::new(19.0, 1.0));
+        assert_eq!(atlas.textures[1].max, Vec2::new(35.0, 33.0));
+
+        // the group is flattened into its layers
+        assert_eq!(map.layers.len(), 2);
+        let ground = &map.layers[0];
+        assert_eq!(ground.name, "ground");
+        let tiles = match &ground.content {
+            MapLayerContent::Tiles(tiles) => tiles,
+            content => panic!("unexpected layer content {:?}", content),
+        };
+        assert_eq!(
+            tiles,
+            &vec![
+                tile(UVec2::new(0, 0), Vec2::new(8.0, 24.0), 0, 0),
+                tile(UVec2::new(1, 0), Vec2::new(24.0, 24.0), 0, 1),
+                MapTile {
+                    flip_x: true,
+                    ..tile(UVec2::new(0, 1), Vec2::new(8.0, 8.0), 0, 1)
+                },
+                // tiles taller than the grid stick out at the top of their cell
+                tile(UVec2::new(1, 1), Vec2::new(24.0, 16.0), 1, 0),
+            ]
+        );
+
+        let spawns = &map.layers[1];
+        assert_eq!(spawns.name, "spawns");
+        assert_eq!(spawns.offset, Vec2::new(4.0, -4.0));
+        assert_eq!(spawns.opacity, 0.25);
+        assert!(!spawns.visible);
+        let objects = match &spawns.content {
+            MapLayerContent::Objects(objects) => objects,
+            content => panic!("unexpected layer content {:?}", content),
+        };
+        assert_eq!(objects.len(), 4);
+        assert_eq!(objects[0].id, 7);
+        assert_eq!(objects[0].name, "player");
+        assert_eq!(objects[0].class, "spawn");
+        assert_eq!(objects[0].position, Vec2::new(16.0, 12.0));
+        assert_eq!(
+            objects[0].shape,
+            Some(MapCollider::Rectangle {
+                size: Vec2::new(16.0, 8.0)
+            })
+        );
+        assert_eq!(objects[1].position, Vec2::new(4.0, 28.0));
+        assert_eq!(objects[1].shape, Some(MapCollider::Point));
+        assert_eq!(objects[2].position, Vec2::ZERO);
+        assert_eq!(
+            objects[2].shape,
+            Some(MapCollider::Polygon {
+                points: vec![Vec2::ZERO, Vec2::new(16.0, 0.0), Vec2::new(16.0, 16.0)]
+            })
+        );
+        // tile objects are anchored at their bottom left corner
+        assert_eq!(objects[3].position, Vec2::new(24.0, 8.0));
+        assert_eq!(objects[3].tile, Some(tile(UVec2::ZERO, Vec2::ZERO, 1, 1)));
+    }
+
+    fn layer_tiles(layer: &str) -> Result<Vec<MapTile>, TiledError> {
+        let document = Document::parse(layer).unwrap();
+        let context = MapContext {
+            first_gids: vec![1],
+            tile_sizes: vec![Vec2::splat(16.0)],
+            tile_size: Vec2::splat(16.0),
+            rows: 1,
+            height: 16.0,
+        };
+        read_tiles(document.root_element(), &context)
+    }
+
+    #[test]
+    fn read_encoded_tiles() {
+        let gids = [1u32, 0, 3 | FLIPPED_VERTICALLY];
+        let bytes = gids
+            .iter()
+            .flat_map(|gid| gid.to_le_bytes())
+            .collect::<Vec<_>>();
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, &bytes).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        for data in [
+            format!(
+                r#"<data encoding="base64">{}</data>"#,
+                base64::encode(&bytes)
+            ),
+            format!(
+                r#"<data encoding="base64" compression="zlib">{}</data>"#,
+                base64::encode(&compressed)
+            ),
+            r#"<data><tile gid="1"/><tile/><tile gid="1073741827"/></data>"#.to_string(),
+        ] {
+            let tiles = layer_tiles(&format!(r#"<layer width="3">{}</layer>"#, data)).unwrap();
+            assert_eq!(
+                tiles,
+                vec![
+                    tile(UVec2::new(0, 0), Vec2::new(8.0, 8.0), 0, 0),
+                    MapTile {
+                        flip_y: true,
+                        ..tile(UVec2::new(2, 0), Vec2::new(40.0, 8.0), 0, 2)
+                    },
+                ]
+            );
+        }
+
+        assert!(matches!(
+            layer_tiles(r#"<layer width="3"><data encoding="csv"><chunk/></data></layer>"#),
+            Err(TiledError::InfiniteMap)
+        ));
+        assert!(matches!(
+            layer_tiles(
+                r#"<layer width="3"><data encoding="base64" compression="zstd">AA==</data></layer>"#
+            ),
+            Err(TiledError::UnsupportedEncoding(_))
+        ));
+    }
+}
@@ -2,8 +2,10 @@ mod children;
 mod global_transform;
 mod parent;
 mod transform;
+mod transform_interpolation;
 
 pub use children::Children;
 pub use global_transform::*;
 pub use parent::{Parent, PreviousParent};
 pub use transform::*;
+pub use transform_interpolation::*;
@@ -205,6 +205,17 @@ impl Transform {
         self.rotation *= rotation;
     }
 
+    /// Interpolates between `self` and `transform` component by component, returning `self` for
+    /// `s = 0.0` and `transform` for `s = 1.0`. The rotation is spherically interpolated.
+    #[inline]
+    pub fn lerp(&self, transform: Transform, s: f32) -> Self {
+        Transform {
+            translation: self.translation.lerp(transform.translation, s),
+            rotation: self.rotation.slerp(transform.rotation, s),
+            scale: self.scale.lerp(transform.scale, s),
+        }
+    }
+
     /// Multiplies `self` with `transform` component by component, returning the
     /// resulting [`Transform`]
     #[inline]
@@ -0,0 +1,31 @@
+use super::Transform;
+use bevy_ecs::{component::Component, reflect::ReflectComponent};
+use bevy_reflect::Reflect;
+
+/// Smooths the movement of an entity whose [`Transform`] is simulated in
+/// `CoreStage::FixedUpdate`.
+///
+/// As the fixed update stage doesn't run in lockstep with the frames, an entity moved there
+/// would visibly stutter. With this component the [`Transform`] of the entity is set to a blend of
+/// its last two fixed update states for each frame, and restored before the next fixed update, so
+/// the simulation isn't affected.
+///
+/// The [`Transform`] of an interpolated entity should only be changed in the fixed update stage,
+/// changes made in other stages are overwritten.
+#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct TransformInterpolation {
+    #[reflect(ignore)]
+    pub(crate) previous: Option<Transform>,
+    #[reflect(ignore)]
+    pub(crate) current: Option<Transform>,
+}
+
+impl TransformInterpolation {
+    /// Stops interpolating from the previous state. Call this in the fixed update stage after
+    /// teleporting the entity.
+    pub fn reset(&mut self) {
+        self.previous = None;
+        self.current = None;
+    }
+}
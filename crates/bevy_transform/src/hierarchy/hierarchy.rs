@@ -11,6 +11,11 @@ pub struct DespawnRecursive {
     entity: Entity,
 }
 
+#[derive(Debug)]
+pub struct DespawnDescendants {
+    entity: Entity,
+}
+
 pub fn despawn_with_children_recursive(world: &mut World, entity: Entity) {
     // first, make the entity's own parent forget about it
     if let Some(parent) = world.get::<Parent>(entity).map(|parent| parent.0) {
@@ -23,7 +28,19 @@ pub fn despawn_with_children_recursive(world: &mut World, entity: Entity) {
     despawn_with_children_recursive_inner(world, entity);
 }
 
-// Should only be called by `despawn_with_children_recursive`!
+/// Despawns the children of `entity` and all of their descendants, keeping `entity` itself.
+pub fn despawn_children_recursive(world: &mut World, entity: Entity) {
+    let children = world
+        .get_entity_mut(entity)
+        .and_then(|mut entity| entity.remove::<Children>());
+    if let Some(children) = children {
+        for e in children.0 {
+            despawn_with_children_recursive_inner(world, e);
+        }
+    }
+}
+
+// Should only be called by `despawn_with_children_recursive` and `despawn_children_recursive`!
 fn despawn_with_children_recursive_inner(world: &mut World, entity: Entity) {
     if let Some(mut children) = world.get_mut::<Children>(entity) {
         for e in std::mem::take(&mut children.0) {
@@ -42,9 +59,19 @@ impl Command for DespawnRecursive {
     }
 }
 
+impl Command for DespawnDescendants {
+    fn write(self, world: &mut World) {
+        despawn_children_recursive(world, self.entity);
+    }
+}
+
 pub trait DespawnRecursiveExt {
     /// Despawns the provided entity and its children.
     fn despawn_recursive(self);
+
+    /// Despawns the children of the provided entity and all of their descendants, keeping the
+    /// entity itself.
+    fn despawn_descendants(&mut self) -> &mut Self;
 }
 
 impl<'w, 's, 'a> DespawnRecursiveExt for EntityCommands<'w, 's, 'a> {
@@ -53,6 +80,12 @@ impl<'w, 's, 'a> DespawnRecursiveExt for EntityCommands<'w, 's, 'a> {
         let entity = self.id();
         self.commands().add(DespawnRecursive { entity });
     }
+
+    fn despawn_descendants(&mut self) -> &mut Self {
+        let entity = self.id();
+        self.commands().add(DespawnDescendants { entity });
+        self
+    }
 }
 
 impl<'w> DespawnRecursiveExt for EntityMut<'w> {
@@ -65,6 +98,16 @@ impl<'w> DespawnRecursiveExt for EntityMut<'w> {
             despawn_with_children_recursive(self.world_mut(), entity);
         }
     }
+
+    fn despawn_descendants(&mut self) -> &mut Self {
+        let entity = self.id();
+        // SAFE: the location of the entity is updated right after the children are despawned
+        unsafe {
+            despawn_children_recursive(self.world_mut(), entity);
+            self.update_location();
+        }
+        self
+    }
 }
 
 #[cfg(test)]
@@ -76,7 +119,10 @@ mod tests {
     };
 
     use super::DespawnRecursiveExt;
-    use crate::{components::Children, hierarchy::BuildChildren};
+    use crate::{
+        components::Children,
+        hierarchy::{BuildChildren, BuildWorldChildren},
+    };
 
     #[derive(Component, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Debug)]
     struct Idx(u32);
@@ -160,4 +206,20 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn despawn_descendants() {
+        let mut world = World::default();
+        let parent = world.spawn().insert(Idx(0)).id();
+        let child = world.spawn().insert(Idx(1)).id();
+        let grandchild = world.spawn().insert(Idx(2)).id();
+        world.entity_mut(parent).push_children(&[child]);
+        world.entity_mut(child).push_children(&[grandchild]);
+
+        world.entity_mut(parent).despawn_descendants();
+        assert!(world.get_entity(parent).is_some());
+        assert!(world.get::<Children>(parent).is_none());
+        assert!(world.get_entity(grandchild).is_none());
+        assert_eq!(world.query::<&Idx>().iter(&world).count(), 1);
+    }
 }
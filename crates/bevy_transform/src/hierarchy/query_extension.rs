@@ -0,0 +1,412 @@
+use crate::components::{Children, Parent};
+use bevy_ecs::{
+    archetype::{Archetype, ArchetypeComponentId},
+    component::{Component, ComponentId},
+    entity::Entity,
+    query::{Access, Fetch, FetchState, FilterFetch, FilteredAccess, ReadOnlyFetch, WorldQuery},
+    storage::{Table, Tables},
+    system::Query,
+    world::World,
+};
+use std::{collections::VecDeque, marker::PhantomData};
+
+/// Walks the hierarchy with a [`Query`] of [`Parent`] or of [`Children`].
+///
+/// Entities can be filtered by their ancestors with [`WithAncestor`], or by combining
+/// [`iter_ancestors`] with a second query for more complex conditions:
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_transform::prelude::*;
+/// #[derive(Component)]
+/// struct Player;
+///
+/// fn player_parts(
+///     parts: Query<Entity, With<Parent>>,
+///     parents: Query<&Parent>,
+///     players: Query<(), With<Player>>,
+/// ) {
+///     for part in parts.iter() {
+///         if parents
+///             .iter_ancestors(part)
+///             .any(|ancestor| players.get(ancestor).is_ok())
+///         {
+///             // `part` is part of a player, at any depth
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`iter_ancestors`]: HierarchyQueryExt::iter_ancestors
+pub trait HierarchyQueryExt<'w, 's, Q: WorldQuery, F: WorldQuery>
+where
+    F::Fetch: FilterFetch,
+{
+    /// Iterates over the ancestors of `entity`, from its parent up to the root of its hierarchy.
+    fn iter_ancestors(&'s self, entity: Entity) -> AncestorIter<'w, 's, Q, F>
+    where
+        Q::Fetch: ReadOnlyFetch + Fetch<'w, 's, Item = &'w Parent>;
+
+    /// Iterates over the descendants of `entity`, breadth first.
+    fn iter_descendants(&'s self, entity: Entity) -> DescendantIter<'w, 's, Q, F>
+    where
+        Q::Fetch: ReadOnlyFetch + Fetch<'w, 's, Item = &'w Children>;
+}
+
+impl<'w, 's, Q: WorldQuery, F: WorldQuery> HierarchyQueryExt<'w, 's, Q, F> for Query<'w, 's, Q, F>
+where
+    F::Fetch: FilterFetch,
+{
+    fn iter_ancestors(&'s self, entity: Entity) -> AncestorIter<'w, 's, Q, F>
+    where
+        Q::Fetch: ReadOnlyFetch + Fetch<'w, 's, Item = &'w Parent>,
+    {
+        AncestorIter::new(self, entity)
+    }
+
+    fn iter_descendants(&'s self, entity: Entity) -> DescendantIter<'w, 's, Q, F>
+    where
+        Q::Fetch: ReadOnlyFetch + Fetch<'w, 's, Item = &'w Children>,
+    {
+        DescendantIter::new(self, entity)
+    }
+}
+
+/// An [`Iterator`] over the ancestors of an entity, see [`HierarchyQueryExt::iter_ancestors`].
+pub struct AncestorIter<'w, 's, Q: WorldQuery, F: WorldQuery>
+where
+    F::Fetch: FilterFetch,
+{
+    parent_query: &'s Query<'w, 's, Q, F>,
+    next: Option<Entity>,
+}
+
+impl<'w, 's, Q: WorldQuery, F: WorldQuery> AncestorIter<'w, 's, Q, F>
+where
+    F::Fetch: FilterFetch,
+    Q::Fetch: ReadOnlyFetch + Fetch<'w, 's, Item = &'w Parent>,
+{
+    pub fn new(parent_query: &'s Query<'w, 's, Q, F>, entity: Entity) -> Self {
+        AncestorIter {
+            parent_query,
+            next: parent_query.get(entity).ok().map(|parent| parent.0),
+        }
+    }
+}
+
+impl<'w, 's, Q: WorldQuery, F: WorldQuery> Iterator for AncestorIter<'w, 's, Q, F>
+where
+    F::Fetch: FilterFetch,
+    Q::Fetch: ReadOnlyFetch + Fetch<'w, 's, Item = &'w Parent>,
+{
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ancestor = self.next?;
+        self.next = self.parent_query.get(ancestor).ok().map(|parent| parent.0);
+        Some(ancestor)
+    }
+}
+
+/// An [`Iterator`] over the descendants of an entity, see
+/// [`HierarchyQueryExt::iter_descendants`].
+pub struct DescendantIter<'w, 's, Q: WorldQuery, F: WorldQuery>
+where
+    F::Fetch: FilterFetch,
+{
+    children_query: &'s Query<'w, 's, Q, F>,
+    queue: VecDeque<Entity>,
+}
+
+impl<'w, 's, Q: WorldQuery, F: WorldQuery> DescendantIter<'w, 's, Q, F>
+where
+    F::Fetch: FilterFetch,
+    Q::Fetch: ReadOnlyFetch + Fetch<'w, 's, Item = &'w Children>,
+{
+    pub fn new(children_query: &'s Query<'w, 's, Q, F>, entity: Entity) -> Self {
+        DescendantIter {
+            children_query,
+            queue: children_query
+                .get(entity)
+                .map(|children| children.iter().copied().collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl<'w, 's, Q: WorldQuery, F: WorldQuery> Iterator for DescendantIter<'w, 's, Q, F>
+where
+    F::Fetch: FilterFetch,
+    Q::Fetch: ReadOnlyFetch + Fetch<'w, 's, Item = &'w Children>,
+{
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let descendant = self.queue.pop_front()?;
+        if let Ok(children) = self.children_query.get(descendant) {
+            self.queue.extend(children.iter().copied());
+        }
+        Some(descendant)
+    }
+}
+
+/// Filter that selects entities with an ancestor that has a component `T`, at any depth.
+///
+/// It reads the [`Parent`] and `T` components of the ancestors, so it conflicts with queries
+/// writing them in the same system.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_transform::prelude::*;
+/// #[derive(Component)]
+/// struct Player;
+///
+/// fn player_parts(parts: Query<Entity, WithAncestor<Player>>) {
+///     for part in parts.iter() {
+///         // `part` is part of a player, at any depth
+///     }
+/// }
+/// # player_parts.system();
+/// ```
+pub struct WithAncestor<T>(PhantomData<T>);
+
+impl<T: Component> WorldQuery for WithAncestor<T> {
+    type Fetch = WithAncestorFetch<T>;
+    type State = WithAncestorState<T>;
+}
+
+/// The [`FetchState`] of [`WithAncestor`].
+pub struct WithAncestorState<T> {
+    parent_id: ComponentId,
+    component_id: ComponentId,
+    marker: PhantomData<T>,
+}
+
+// SAFETY: component access and archetype component access are updated to reflect that Parent and
+// T are read on any entity
+unsafe impl<T: Component> FetchState for WithAncestorState<T> {
+    fn init(world: &mut World) -> Self {
+        Self {
+            parent_id: world.init_component::<Parent>(),
+            component_id: world.init_component::<T>(),
+            marker: PhantomData,
+        }
+    }
+
+    fn update_component_access(&self, access: &mut FilteredAccess<ComponentId>) {
+        for component_id in [self.parent_id, self.component_id] {
+            if access.access().has_write(component_id) {
+                panic!(
+                    "WithAncestor<{}> conflicts with a previous access in this query. Shared \
+                    access cannot coincide with exclusive access.",
+                    std::any::type_name::<T>()
+                );
+            }
+            // the components are read on the ancestors, so filters on the entities of the query
+            // don't make this access disjoint from writes
+            access.add_unfiltered_read(component_id);
+        }
+        access.add_with(self.parent_id);
+    }
+
+    fn update_archetype_component_access(
+        &self,
+        _archetype: &Archetype,
+        _access: &mut Access<ArchetypeComponentId>,
+    ) {
+    }
+
+    fn update_other_archetype_component_access(
+        &self,
+        archetype: &Archetype,
+        access: &mut Access<ArchetypeComponentId>,
+    ) {
+        for component_id in [self.parent_id, self.component_id] {
+            if let Some(archetype_component_id) = archetype.get_archetype_component_id(component_id)
+            {
+                access.add_read(archetype_component_id);
+            }
+        }
+    }
+
+    fn matches_archetype(&self, archetype: &Archetype) -> bool {
+        archetype.contains(self.parent_id)
+    }
+
+    fn matches_table(&self, table: &Table) -> bool {
+        table.has_column(self.parent_id)
+    }
+}
+
+/// The [`Fetch`] of [`WithAncestor`].
+pub struct WithAncestorFetch<T> {
+    world: *const World,
+    entities: *const Entity,
+    marker: PhantomData<T>,
+}
+
+/// SAFETY: access is read only
+unsafe impl<T> ReadOnlyFetch for WithAncestorFetch<T> {}
+
+impl<T: Component> WithAncestorFetch<T> {
+    /// # Safety
+    /// `world` must be the world the fetch was initialized with, and be valid
+    unsafe fn has_ancestor(&self, entity: Entity) -> bool {
+        let world = &*self.world;
+        let mut next = world.get::<Parent>(entity).map(|parent| parent.0);
+        while let Some(ancestor) = next.and_then(|ancestor| world.get_entity(ancestor)) {
+            if ancestor.contains::<T>() {
+                return true;
+            }
+            next = ancestor.get::<Parent>().map(|parent| parent.0);
+        }
+        false
+    }
+}
+
+impl<'w, 's, T: Component> Fetch<'w, 's> for WithAncestorFetch<T> {
+    type Item = bool;
+    type State = WithAncestorState<T>;
+
+    unsafe fn init(
+        world: &World,
+        _state: &Self::State,
+        _last_change_tick: u32,
+        _change_tick: u32,
+    ) -> Self {
+        Self {
+            world,
+            entities: std::ptr::null::<Entity>(),
+            marker: PhantomData,
+        }
+    }
+
+    const IS_DENSE: bool = false;
+
+    #[inline]
+    unsafe fn set_archetype(
+        &mut self,
+        _state: &Self::State,
+        archetype: &Archetype,
+        _tables: &Tables,
+    ) {
+        self.entities = archetype.entities().as_ptr();
+    }
+
+    #[inline]
+    unsafe fn set_table(&mut self, _state: &Self::State, table: &Table) {
+        self.entities = table.entities().as_ptr();
+    }
+
+    #[inline]
+    unsafe fn archetype_fetch(&mut self, archetype_index: usize) -> bool {
+        self.has_ancestor(*self.entities.add(archetype_index))
+    }
+
+    #[inline]
+    unsafe fn table_fetch(&mut self, table_row: usize) -> bool {
+        self.has_ancestor(*self.entities.add(table_row))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HierarchyQueryExt, WithAncestor};
+    use crate::{
+        components::{Children, Parent},
+        hierarchy::BuildWorldChildren,
+    };
+    use bevy_ecs::{
+        component::Component,
+        entity::Entity,
+        query::Without,
+        system::{IntoSystem, Query, System, SystemState},
+        world::World,
+    };
+
+    #[derive(Component)]
+    struct Player;
+
+    #[test]
+    fn ancestors_and_descendants() {
+        let mut world = World::default();
+        let [root, child, grandchild, other_child] = [(); 4].map(|_| world.spawn().id());
+        world.entity_mut(root).push_children(&[child, other_child]);
+        world.entity_mut(child).push_children(&[grandchild]);
+
+        let mut system_state = SystemState::<(Query<&Parent>, Query<&Children>)>::new(&mut world);
+        let (parents, children) = system_state.get(&world);
+
+        assert_eq!(
+            parents.iter_ancestors(grandchild).collect::<Vec<_>>(),
+            vec![child, root]
+        );
+        assert_eq!(parents.iter_ancestors(root).next(), None);
+        assert_eq!(
+            children.iter_descendants(root).collect::<Vec<_>>(),
+            vec![child, other_child, grandchild]
+        );
+        assert_eq!(children.iter_descendants(grandchild).next(), None);
+    }
+
+    #[test]
+    fn with_ancestor() {
+        let mut world = World::default();
+        let player = world.spawn().insert(Player).id();
+        let [child, grandchild, other_root, other_child] = [(); 4].map(|_| world.spawn().id());
+        world.entity_mut(player).push_children(&[child]);
+        world.entity_mut(child).push_children(&[grandchild]);
+        world.entity_mut(other_root).push_children(&[other_child]);
+
+        let mut query = world.query_filtered::<Entity, WithAncestor<Player>>();
+        let mut parts = query.iter(&world).collect::<Vec<_>>();
+        parts.sort();
+        assert_eq!(parts, vec![child, grandchild]);
+
+        // the root of the other hierarchy becomes a player part
+        world.entity_mut(child).push_children(&[other_root]);
+        let mut parts = query.iter(&world).collect::<Vec<_>>();
+        parts.sort();
+        assert_eq!(parts, vec![child, grandchild, other_root, other_child]);
+    }
+
+    #[test]
+    fn with_ancestor_conflicts_with_ancestor_writes() {
+        fn read_parts(_parts: Query<Entity, WithAncestor<Player>>) {}
+        fn write_players(_players: Query<&mut Player, Without<Parent>>) {}
+
+        let mut world = World::default();
+        let player = world.spawn().insert(Player).id();
+        let child = world.spawn().id();
+        world.entity_mut(player).push_children(&[child]);
+
+        let mut read_parts = read_parts.system();
+        let mut write_players = write_players.system();
+        read_parts.initialize(&mut world);
+        write_players.initialize(&mut world);
+        for archetype in world.archetypes().iter() {
+            read_parts.new_archetype(archetype);
+            write_players.new_archetype(archetype);
+        }
+        // the players are read as ancestors, though they don't match the filter
+        assert!(!read_parts
+            .archetype_component_access()
+            .is_compatible(write_players.archetype_component_access()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn with_ancestor_conflicts_in_system() {
+        #[derive(Component)]
+        struct Part;
+
+        fn player_parts(
+            _parts: Query<&Part, WithAncestor<Player>>,
+            _players: Query<&mut Player, Without<Parent>>,
+        ) {
+        }
+
+        let mut world = World::default();
+        player_parts.system().initialize(&mut world);
+    }
+}
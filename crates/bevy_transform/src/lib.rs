@@ -1,5 +1,6 @@
 pub mod components;
 pub mod hierarchy;
+pub mod transform_interpolation_system;
 pub mod transform_propagate_system;
 
 pub mod prelude {
@@ -8,8 +9,14 @@ pub mod prelude {
 }
 
 use bevy_app::prelude::*;
-use bevy_ecs::schedule::{ParallelSystemDescriptorCoercion, SystemLabel};
-use prelude::{parent_update_system, Children, GlobalTransform, Parent, PreviousParent, Transform};
+use bevy_ecs::{
+    schedule::{ExclusiveSystemDescriptorCoercion, ParallelSystemDescriptorCoercion, SystemLabel},
+    system::IntoExclusiveSystem,
+};
+use prelude::{
+    parent_update_system, Children, GlobalTransform, Parent, PreviousParent, Transform,
+    TransformInterpolation,
+};
 
 #[derive(Default)]
 pub struct TransformPlugin;
@@ -18,6 +25,7 @@ pub struct TransformPlugin;
 pub enum TransformSystem {
     TransformPropagate,
     ParentUpdate,
+    TransformInterpolation,
 }
 
 impl Plugin for TransformPlugin {
@@ -27,6 +35,7 @@ impl Plugin for TransformPlugin {
             .register_type::<PreviousParent>()
             .register_type::<Transform>()
             .register_type::<GlobalTransform>()
+            .register_type::<TransformInterpolation>()
             // add transform systems to startup so the first update is "correct"
             .add_startup_system_to_stage(
                 StartupStage::PostStartup,
@@ -38,6 +47,25 @@ impl Plugin for TransformPlugin {
                     .label(TransformSystem::TransformPropagate)
                     .after(TransformSystem::ParentUpdate),
             )
+            // the fixed update states are recorded around all of the stage's systems
+            .add_system_to_stage(
+                CoreStage::FixedUpdate,
+                transform_interpolation_system::restore_fixed_transform_system
+                    .exclusive_system()
+                    .at_start(),
+            )
+            .add_system_to_stage(
+                CoreStage::FixedUpdate,
+                transform_interpolation_system::record_fixed_transform_system
+                    .exclusive_system()
+                    .at_end(),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                transform_interpolation_system::transform_interpolation_system
+                    .label(TransformSystem::TransformInterpolation)
+                    .before(TransformSystem::TransformPropagate),
+            )
             .add_system_to_stage(
                 CoreStage::PostUpdate,
                 parent_update_system.label(TransformSystem::ParentUpdate),
@@ -0,0 +1,134 @@
+use crate::components::{Transform, TransformInterpolation};
+use bevy_core::{FixedTimesteps, FIXED_UPDATE};
+use bevy_ecs::system::{Query, Res};
+
+/// Restores the simulated [`Transform`] of interpolated entities, and remembers it as the state
+/// to interpolate from. This runs at the start of each fixed update.
+pub fn restore_fixed_transform_system(
+    mut query: Query<(&mut Transform, &mut TransformInterpolation)>,
+) {
+    for (mut transform, mut interpolation) in query.iter_mut() {
+        if let Some(current) = interpolation.current {
+            *transform = current;
+        }
+        interpolation.previous = Some(*transform);
+    }
+}
+
+/// Remembers the simulated [`Transform`] of interpolated entities as the state to interpolate to.
+/// This runs at the end of each fixed update.
+pub fn record_fixed_transform_system(mut query: Query<(&Transform, &mut TransformInterpolation)>) {
+    for (transform, mut interpolation) in query.iter_mut() {
+        interpolation.current = Some(*transform);
+    }
+}
+
+/// Blends the last two fixed update states of interpolated entities, based on the time elapsed
+/// since the last fixed update.
+pub fn transform_interpolation_system(
+    fixed_timesteps: Option<Res<FixedTimesteps>>,
+    mut query: Query<(&mut Transform, &TransformInterpolation)>,
+) {
+    let fixed_update = fixed_timesteps
+        .as_ref()
+        .and_then(|fixed_timesteps| fixed_timesteps.get(FIXED_UPDATE));
+    let overstep = match fixed_update {
+        Some(fixed_update) => fixed_update.overstep_percentage() as f32,
+        None => return,
+    };
+    for (mut transform, interpolation) in query.iter_mut() {
+        if let (Some(previous), Some(current)) = (interpolation.previous, interpolation.current) {
+            *transform = previous.lerp(current, overstep.min(1.0));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy_core::FixedTimestep;
+    use bevy_ecs::{
+        schedule::{ParallelSystemDescriptorCoercion, Stage, SystemStage},
+        system::System,
+        world::World,
+    };
+
+    fn set_overstep(world: &mut World, overstep: f64) {
+        let mut fixed_timesteps = world.get_resource_mut::<FixedTimesteps>().unwrap();
+        let fixed_update = fixed_timesteps.get_mut(FIXED_UPDATE).unwrap();
+        fixed_update.accumulator = overstep * fixed_update.step;
+    }
+
+    #[test]
+    fn restores_simulated_transform() {
+        let mut world = World::default();
+        let entity = world
+            .spawn()
+            .insert_bundle((
+                Transform::from_xyz(1.0, 0.0, 0.0),
+                TransformInterpolation::default(),
+            ))
+            .id();
+
+        let mut fixed_update = SystemStage::single_threaded();
+        fixed_update.add_system(restore_fixed_transform_system.label("restore"));
+        fixed_update.add_system(
+            (|mut query: Query<&mut Transform>| {
+                for mut transform in query.iter_mut() {
+                    transform.translation.x += 1.0;
+                }
+            })
+            .label("simulate")
+            .after("restore"),
+        );
+        fixed_update.add_system(record_fixed_transform_system.after("simulate"));
+
+        fixed_update.run(&mut world);
+        let interpolation = *world.get::<TransformInterpolation>(entity).unwrap();
+        assert_eq!(
+            interpolation.previous,
+            Some(Transform::from_xyz(1.0, 0.0, 0.0))
+        );
+        assert_eq!(
+            interpolation.current,
+            Some(Transform::from_xyz(2.0, 0.0, 0.0))
+        );
+
+        // without a fixed update, nothing is interpolated
+        let mut update = SystemStage::single_threaded();
+        update.add_system(transform_interpolation_system);
+        update.run(&mut world);
+        assert_eq!(
+            *world.get::<Transform>(entity).unwrap(),
+            Transform::from_xyz(2.0, 0.0, 0.0)
+        );
+
+        world.insert_resource(FixedTimesteps::default());
+        FixedTimestep::step(0.5)
+            .with_label(FIXED_UPDATE)
+            .initialize(&mut world);
+        set_overstep(&mut world, 0.5);
+        update.run(&mut world);
+        assert_eq!(
+            *world.get::<Transform>(entity).unwrap(),
+            Transform::from_xyz(1.5, 0.0, 0.0)
+        );
+
+        // the time left can exceed a step when the fixed update falls behind
+        set_overstep(&mut world, 1.5);
+        update.run(&mut world);
+        assert_eq!(
+            *world.get::<Transform>(entity).unwrap(),
+            Transform::from_xyz(2.0, 0.0, 0.0)
+        );
+
+        // a blended transform is replaced by the simulated one in the next fixed update
+        set_overstep(&mut world, 0.5);
+        update.run(&mut world);
+        fixed_update.run(&mut world);
+        assert_eq!(
+            *world.get::<Transform>(entity).unwrap(),
+            Transform::from_xyz(3.0, 0.0, 0.0)
+        );
+    }
+}
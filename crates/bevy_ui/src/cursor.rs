@@ -0,0 +1,238 @@
+use crate::{entity::ImageBundle, FocusPolicy, PositionType, Style, Val};
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    query::With,
+    system::{Commands, Local, Query, Res, ResMut},
+};
+use bevy_math::{Rect, Size, Vec2};
+use bevy_render::{draw::Visible, texture::Texture};
+use bevy_sprite::ColorMaterial;
+use bevy_transform::prelude::Transform;
+use bevy_window::{CursorGrabMode, Windows};
+
+/// The z of the cursor image node, in front of the other UI nodes
+const CURSOR_IMAGE_Z: f32 = 999.0;
+
+/// A custom image drawn in place of the system cursor over the primary window, for stylized
+/// cursors.
+///
+/// Windows can only show the icons of the system cursor (see
+/// [`Window::set_cursor_icon`](bevy_window::Window::set_cursor_icon)), so the image is drawn by
+/// the UI in front of the other nodes, and the system cursor is hidden while an image is set. It
+/// needs a `UiCameraBundle`. The image isn't drawn while the cursor is locked.
+#[derive(Debug, Clone, Default)]
+pub struct CursorImage {
+    /// `None` shows the system cursor
+    pub texture: Option<Handle<Texture>>,
+    /// The point of the image at the cursor position, in pixels from its top left corner
+    pub hotspot: Vec2,
+    /// The size the image is drawn at, in logical pixels. Defaults to the size of the texture
+    pub size: Option<Vec2>,
+}
+
+impl CursorImage {
+    pub fn new(texture: Handle<Texture>, hotspot: Vec2) -> Self {
+        CursorImage {
+            texture: Some(texture),
+            hotspot,
+            size: None,
+        }
+    }
+}
+
+/// Marks the UI node drawing the [`CursorImage`]
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct CursorImageNode;
+
+/// Moves the node drawing the [`CursorImage`] to the cursor position, spawning it when an image
+/// is set and despawning it when the image is removed.
+#[allow(clippy::too_many_arguments)]
+pub fn cursor_image_system(
+    mut commands: Commands,
+    cursor_image: Res<CursorImage>,
+    mut windows: ResMut<Windows>,
+    textures: Res<Assets<Texture>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut node_query: Query<
+        (Entity, &mut Style, &mut Visible, &mut Handle<ColorMaterial>),
+        With<CursorImageNode>,
+    >,
+    mut shown_texture: Local<Option<Handle<Texture>>>,
+    mut hid_system_cursor: Local<bool>,
+) {
+    let window = match windows.get_primary_mut() {
+        Some(window) => window,
+        None => return,
+    };
+
+    let texture = match &cursor_image.texture {
+        Some(texture) => texture,
+        None => {
+            if *hid_system_cursor {
+                window.set_cursor_visibility(true);
+                *hid_system_cursor = false;
+            }
+            for (entity, ..) in node_query.iter_mut() {
+                commands.entity(entity).despawn();
+            }
+            *shown_texture = None;
+            return;
+        }
+    };
+    if !*hid_system_cursor {
+        window.set_cursor_visibility(false);
+        *hid_system_cursor = true;
+    }
+
+    let (_, mut style, mut visible, mut material) = match node_query.iter_mut().next() {
+        Some(node) => node,
+        None => {
+            commands
+                .spawn_bundle(ImageBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        ..Default::default()
+                    },
+                    visible: Visible {
+                        is_visible: false,
+                        is_transparent: true,
+                    },
+                    transform: Transform::from_xyz(0.0, 0.0, CURSOR_IMAGE_Z),
+                    ..Default::default()
+                })
+                .insert(CursorImageNode)
+                .insert(FocusPolicy::Pass);
+            *shown_texture = None;
+            return;
+        }
+    };
+
+    if shown_texture.as_ref() != Some(texture) {
+        *material = materials.add(texture.clone().into());
+        *shown_texture = Some(texture.clone());
+    }
+
+    let size = cursor_image.size.or_else(|| {
+        textures
+            .get(texture)
+            .map(|texture| Vec2::new(texture.size.width as f32, texture.size.height as f32))
+    });
+    let position = window
+        .cursor_position()
+        .filter(|_| window.cursor_grab_mode() != CursorGrabMode::Locked);
+    match (position, size) {
+        (Some(position), Some(size)) => {
+            // the cursor position and the UI have their origin at the bottom left
+            let node_position = Rect {
+                left: Val::Px(position.x - cursor_image.hotspot.x),
+                bottom: Val::Px(position.y - size.y + cursor_image.hotspot.y),
+                ..Default::default()
+            };
+            let node_size = Size::new(Val::Px(size.x), Val::Px(size.y));
+            // Update only if the style has changed to avoid needless layout calculations
+            if style.position != node_position || style.size != node_size {
+                style.position = node_position;
+                style.size = node_size;
+            }
+            if !visible.is_visible {
+                visible.is_visible = true;
+            }
+        }
+        _ => {
+            if visible.is_visible {
+                visible.is_visible = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin};
+    use bevy_render::texture::{Extent3d, TextureDimension, TextureFormat};
+    use bevy_tasks::{IoTaskPool, TaskPool};
+    use bevy_window::{Window, WindowDescriptor, WindowId};
+
+    fn cursor_app() -> App {
+        let mut windows = Windows::default();
+        windows.add(Window::new(
+            WindowId::primary(),
+            &WindowDescriptor::default(),
+            800,
+            600,
+            1.0,
+            None,
+        ));
+        let mut app = App::new();
+        app.insert_resource(IoTaskPool(TaskPool::new()))
+            .add_plugin(AssetPlugin)
+            .add_asset::<Texture>()
+            .add_asset::<ColorMaterial>()
+            .insert_resource(windows)
+            .init_resource::<CursorImage>()
+            .add_system(cursor_image_system);
+        app
+    }
+
+    fn window(app: &mut App) -> &mut Window {
+        app.world
+            .get_resource_mut::<Windows>()
+            .unwrap()
+            .into_inner()
+            .get_primary_mut()
+            .unwrap()
+    }
+
+    fn cursor_node(app: &mut App) -> Option<(Style, bool)> {
+        app.world
+            .query_filtered::<(&Style, &Visible), With<CursorImageNode>>()
+            .iter(&app.world)
+            .next()
+            .map(|(style, visible)| (style.clone(), visible.is_visible))
+    }
+
+    #[test]
+    fn cursor_image() {
+        let mut app = cursor_app();
+        let texture = app
+            .world
+            .get_resource_mut::<Assets<Texture>>()
+            .unwrap()
+            .add(Texture::new_fill(
+                Extent3d::new(16, 16, 1),
+                TextureDimension::D2,
+                &[255, 255, 255, 255],
+                TextureFormat::Rgba8UnormSrgb,
+            ));
+        app.update();
+        assert!(cursor_node(&mut app).is_none());
+        assert!(window(&mut app).cursor_visible());
+
+        app.insert_resource(CursorImage::new(texture, Vec2::new(2.0, 3.0)));
+        window(&mut app).update_cursor_position_from_backend(Some(Vec2::new(100.0, 200.0)));
+        // the node is spawned on the first frame, and follows the cursor from the next one
+        app.update();
+        assert!(!window(&mut app).cursor_visible());
+        assert!(!cursor_node(&mut app).unwrap().1);
+        app.update();
+        let (style, visible) = cursor_node(&mut app).unwrap();
+        assert!(visible);
+        assert_eq!(style.position.left, Val::Px(98.0));
+        assert_eq!(style.position.bottom, Val::Px(187.0));
+        assert_eq!(style.size, Size::new(Val::Px(16.0), Val::Px(16.0)));
+
+        // hidden while the cursor is locked
+        window(&mut app).set_cursor_grab_mode(CursorGrabMode::Locked);
+        app.update();
+        assert!(!cursor_node(&mut app).unwrap().1);
+
+        app.insert_resource(CursorImage::default());
+        app.update();
+        assert!(cursor_node(&mut app).is_none());
+        assert!(window(&mut app).cursor_visible());
+    }
+}
@@ -16,6 +16,7 @@ use bevy_render::{
 use bevy_sprite::{ColorMaterial, QUAD_HANDLE};
 use bevy_text::Text;
 use bevy_transform::prelude::{GlobalTransform, Transform};
+use bevy_window::WindowId;
 
 /// If you add this to an entity, it should be the *only* bundle on it from bevy_ui.
 /// This bundle will mark the entity as transparent to the UI layout system, meaning the
@@ -203,3 +204,11 @@ impl Default for UiCameraBundle {
         }
     }
 }
+
+impl UiCameraBundle {
+    /// Draws the UI of a window instead of the primary window, see [`UiWindow`](crate::UiWindow).
+    pub fn for_window(mut self, window_id: WindowId) -> Self {
+        self.camera.set_window(window_id);
+        self
+    }
+}
@@ -1,6 +1,6 @@
 mod convert;
 
-use crate::{CalculatedSize, ControlNode, Node, Style};
+use crate::{CalculatedSize, ControlNode, Node, Style, UiWindow};
 use bevy_app::EventReader;
 use bevy_ecs::{
     entity::Entity,
@@ -195,6 +195,18 @@ impl FlexSurface {
             .unwrap();
     }
 
+    /// Removes the root nodes of the windows that were closed.
+    pub fn remove_closed_windows(&mut self, windows: &Windows) {
+        let stretch = &mut self.stretch;
+        self.window_nodes.retain(|window_id, node| {
+            let is_open = windows.get(*window_id).is_some();
+            if !is_open {
+                stretch.remove(*node);
+            }
+            is_open
+        });
+    }
+
     pub fn set_window_children(
         &mut self,
         window_id: WindowId,
@@ -243,7 +255,7 @@ pub fn flex_node_system(
     windows: Res<Windows>,
     mut scale_factor_events: EventReader<WindowScaleFactorChanged>,
     mut flex_surface: ResMut<FlexSurface>,
-    root_node_query: Query<Entity, (With<Node>, Without<Parent>)>,
+    root_node_query: Query<(Entity, Option<&UiWindow>), (With<Node>, Without<Parent>)>,
     node_query: Query<(Entity, &Style, Option<&CalculatedSize>), (With<Node>, Changed<Style>)>,
     full_node_query: Query<(Entity, &Style, Option<&CalculatedSize>), With<Node>>,
     changed_size_query: Query<
@@ -257,6 +269,7 @@ pub fn flex_node_system(
     mut node_transform_query: Query<(Entity, &mut Node, &mut Transform, Option<&Parent>)>,
 ) {
     // update window root nodes
+    flex_surface.remove_closed_windows(&windows);
     for window in windows.iter() {
         flex_surface.update_window(window);
     }
@@ -302,9 +315,18 @@ pub fn flex_node_system(
 
     // TODO: handle removed nodes
 
-    // update window children (for now assuming all Nodes live in the primary window)
-    if let Some(primary_window) = windows.get_primary() {
-        flex_surface.set_window_children(primary_window.id(), root_node_query.iter());
+    // update window children, root nodes without a UiWindow live in the primary window
+    for window in windows.iter() {
+        let window_id = window.id();
+        flex_surface.set_window_children(
+            window_id,
+            root_node_query
+                .iter()
+                .filter(|(_, ui_window)| {
+                    ui_window.map_or(WindowId::primary(), |ui_window| ui_window.0) == window_id
+                })
+                .map(|(entity, _)| entity),
+        );
     }
 
     // update children
@@ -1,13 +1,15 @@
-use crate::Node;
+use crate::{window::node_windows, Node, UiWindow};
 use bevy_core::FloatOrd;
 use bevy_ecs::{
     component::Component,
     entity::Entity,
+    query::{With, Without},
     system::{Local, Query, Res},
 };
 use bevy_input::{mouse::MouseButton, touch::Touches, Input};
-use bevy_transform::components::GlobalTransform;
-use bevy_window::Windows;
+use bevy_transform::components::{Children, GlobalTransform, Parent};
+use bevy_utils::HashMap;
+use bevy_window::{WindowId, Windows};
 use smallvec::SmallVec;
 
 #[derive(Component, Copy, Clone, Eq, PartialEq, Debug)]
@@ -53,15 +55,17 @@ pub fn ui_focus_system(
         Option<&mut Interaction>,
         Option<&FocusPolicy>,
     )>,
+    root_node_query: Query<(Entity, &UiWindow), (With<Node>, Without<Parent>)>,
+    children_query: Query<&Children>,
 ) {
-    let cursor_position = if let Some(cursor_position) = windows
-        .get_primary()
-        .and_then(|window| window.cursor_position())
-    {
-        cursor_position
-    } else {
+    let cursor_positions = windows
+        .iter()
+        .filter_map(|window| Some((window.id(), window.cursor_position()?)))
+        .collect::<HashMap<_, _>>();
+    if cursor_positions.is_empty() {
         return;
-    };
+    }
+    let node_windows = node_windows(root_node_query.iter(), &children_query);
 
     // reset entities that were both clicked and released in the last frame
     for entity in state.entities_to_reset.drain(..) {
@@ -95,10 +99,18 @@ pub fn ui_focus_system(
                 let extents = node.size / 2.0;
                 let min = ui_position - extents;
                 let max = ui_position + extents;
-                // if the current cursor position is within the bounds of the node, consider it for
-                // clicking
-                if (min.x..max.x).contains(&cursor_position.x)
-                    && (min.y..max.y).contains(&cursor_position.y)
+                let window = node_windows
+                    .get(&entity)
+                    .copied()
+                    .unwrap_or_else(WindowId::primary);
+                // if the cursor position in the window of the node is within the bounds of the
+                // node, consider it for clicking
+                if cursor_positions
+                    .get(&window)
+                    .is_some_and(|cursor_position| {
+                        (min.x..max.x).contains(&cursor_position.x)
+                            && (min.y..max.y).contains(&cursor_position.y)
+                    })
                 {
                     Some((entity, focus_policy, interaction, FloatOrd(position.z)))
                 } else {
@@ -1,31 +1,37 @@
 mod anchors;
+mod cursor;
 mod flex;
 mod focus;
 mod margins;
 mod render;
 mod ui_node;
+mod window;
 
 pub mod entity;
 pub mod update;
 pub mod widget;
 
 pub use anchors::*;
+pub use cursor::*;
 pub use flex::*;
 pub use focus::*;
 pub use margins::*;
 pub use render::*;
 pub use ui_node::*;
+pub use window::*;
 
 pub mod prelude {
     #[doc(hidden)]
-    pub use crate::{entity::*, ui_node::*, widget::Button, Anchors, Interaction, Margins};
+    pub use crate::{
+        entity::*, ui_node::*, widget::Button, Anchors, CursorImage, Interaction, Margins, UiWindow,
+    };
 }
 
 use bevy_app::prelude::*;
 use bevy_ecs::schedule::{ParallelSystemDescriptorCoercion, SystemLabel};
 use bevy_input::InputSystem;
 use bevy_math::{Rect, Size};
-use bevy_render::RenderStage;
+use bevy_render::{RenderStage, RenderSystem};
 use bevy_transform::TransformSystem;
 use update::ui_z_system;
 
@@ -42,6 +48,7 @@ pub enum UiSystem {
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<FlexSurface>()
+            .init_resource::<CursorImage>()
             .register_type::<AlignContent>()
             .register_type::<AlignItems>()
             .register_type::<AlignSelf>()
@@ -70,6 +77,10 @@ impl Plugin for UiPlugin {
                 CoreStage::PostUpdate,
                 widget::image_node_system.before(UiSystem::Flex),
             )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                cursor_image_system.before(UiSystem::Flex),
+            )
             .add_system_to_stage(
                 CoreStage::PostUpdate,
                 flex_node_system
@@ -82,6 +93,14 @@ impl Plugin for UiPlugin {
                     .after(UiSystem::Flex)
                     .before(TransformSystem::TransformPropagate),
             )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                ui_camera_window_system.after(RenderSystem::VisibleEntities),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                ui_window_graph_system.after(RenderSystem::WindowGraph),
+            )
             .add_system_to_stage(RenderStage::Draw, widget::draw_text_system);
 
         crate::render::add_ui_graph(&mut app.world);
@@ -1,6 +1,10 @@
 use crate::Node;
+use bevy_app::EventReader;
 use bevy_asset::{Assets, HandleUntyped};
-use bevy_ecs::world::World;
+use bevy_ecs::{
+    system::{Res, ResMut},
+    world::World,
+};
 use bevy_reflect::TypeUuid;
 use bevy_render::{
     camera::ActiveCameras,
@@ -16,6 +20,7 @@ use bevy_render::{
     shader::{Shader, ShaderStage, ShaderStages},
     texture::TextureFormat,
 };
+use bevy_window::{WindowClosed, WindowCreated, WindowId};
 
 pub const UI_PIPELINE_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 3234320022263993878);
@@ -89,6 +94,23 @@ pub(crate) fn add_ui_graph(world: &mut World) {
 
     pipelines.set_untracked(UI_PIPELINE_HANDLE, build_ui_pipeline(&mut shaders));
 
+    graph.add_system_node(node::NODE, RenderResourcesNode::<Node>::new(true));
+    add_window_ui_graph(&mut graph, &mut active_cameras, &msaa, WindowId::primary());
+}
+
+/// Adds the pass drawing the UI camera of a window over its main pass, and makes the camera
+/// active. The nodes and the camera are named with [`base::window_node_name`] and
+/// [`base::window_camera_name`], the graph of the window has to be added first.
+pub fn add_window_ui_graph(
+    graph: &mut RenderGraph,
+    active_cameras: &mut ActiveCameras,
+    msaa: &Msaa,
+    window_id: WindowId,
+) {
+    let ui_pass = base::window_node_name(node::UI_PASS, window_id);
+    let camera_node = base::window_node_name(node::CAMERA_UI, window_id);
+    let camera = base::window_camera_name(camera::CAMERA_UI, window_id);
+
     let mut ui_pass_node = PassNode::<&Node>::new(PassDescriptor {
         color_attachments: vec![msaa.color_attachment(
             TextureAttachment::Input("color_attachment".to_string()),
@@ -109,14 +131,14 @@ pub(crate) fn add_ui_graph(world: &mut World) {
         sample_count: msaa.samples,
     });
 
-    ui_pass_node.add_camera(camera::CAMERA_UI);
-    graph.add_node(node::UI_PASS, ui_pass_node);
+    ui_pass_node.add_camera(&camera);
+    graph.add_node(ui_pass.clone(), ui_pass_node);
 
     graph
         .add_slot_edge(
-            base::node::PRIMARY_SWAP_CHAIN,
+            base::window_node_name(base::node::PRIMARY_SWAP_CHAIN, window_id),
             WindowSwapChainNode::OUT_TEXTURE,
-            node::UI_PASS,
+            ui_pass.clone(),
             if msaa.samples > 1 {
                 "color_resolve_target"
             } else {
@@ -127,9 +149,9 @@ pub(crate) fn add_ui_graph(world: &mut World) {
 
     graph
         .add_slot_edge(
-            base::node::MAIN_DEPTH_TEXTURE,
+            base::window_node_name(base::node::MAIN_DEPTH_TEXTURE, window_id),
             WindowTextureNode::OUT_TEXTURE,
-            node::UI_PASS,
+            ui_pass.clone(),
             "depth",
         )
         .unwrap();
@@ -137,9 +159,9 @@ pub(crate) fn add_ui_graph(world: &mut World) {
     if msaa.samples > 1 {
         graph
             .add_slot_edge(
-                base::node::MAIN_SAMPLED_COLOR_ATTACHMENT,
+                base::window_node_name(base::node::MAIN_SAMPLED_COLOR_ATTACHMENT, window_id),
                 WindowSwapChainNode::OUT_TEXTURE,
-                node::UI_PASS,
+                ui_pass.clone(),
                 "color_attachment",
             )
             .unwrap();
@@ -147,13 +169,50 @@ pub(crate) fn add_ui_graph(world: &mut World) {
 
     // ensure ui pass runs after main pass
     graph
-        .add_node_edge(base::node::MAIN_PASS, node::UI_PASS)
+        .add_node_edge(
+            base::window_node_name(base::node::MAIN_PASS, window_id),
+            ui_pass.clone(),
+        )
         .unwrap();
 
     // setup ui camera
-    graph.add_system_node(node::CAMERA_UI, CameraNode::new(camera::CAMERA_UI));
-    graph.add_node_edge(node::CAMERA_UI, node::UI_PASS).unwrap();
-    graph.add_system_node(node::NODE, RenderResourcesNode::<Node>::new(true));
-    graph.add_node_edge(node::NODE, node::UI_PASS).unwrap();
-    active_cameras.add(camera::CAMERA_UI);
+    graph.add_system_node(camera_node.clone(), CameraNode::new(camera.clone()));
+    graph.add_node_edge(camera_node, ui_pass.clone()).unwrap();
+    graph.add_node_edge(node::NODE, ui_pass).unwrap();
+    active_cameras.add(&camera);
+}
+
+/// Removes the nodes added by [`add_window_ui_graph`] and the UI camera of the window.
+pub fn remove_window_ui_graph(
+    graph: &mut RenderGraph,
+    active_cameras: &mut ActiveCameras,
+    window_id: WindowId,
+) {
+    for node in &[node::UI_PASS, node::CAMERA_UI] {
+        let _ = graph.remove_node(base::window_node_name(node, window_id));
+    }
+    active_cameras.remove(&base::window_camera_name(camera::CAMERA_UI, window_id));
+}
+
+/// Adds the UI pass of the secondary windows when they are created, and removes it when they are
+/// closed.
+pub fn ui_window_graph_system(
+    mut graph: ResMut<RenderGraph>,
+    mut active_cameras: ResMut<ActiveCameras>,
+    msaa: Res<Msaa>,
+    mut window_created_events: EventReader<WindowCreated>,
+    mut window_closed_events: EventReader<WindowClosed>,
+) {
+    for event in window_created_events.iter() {
+        let main_pass = base::window_node_name(base::node::MAIN_PASS, event.id);
+        // windows without a graph, like the windows of an app without the base graph, aren't drawn
+        if !event.id.is_primary() && graph.get_node_id(main_pass).is_ok() {
+            add_window_ui_graph(&mut graph, &mut active_cameras, &msaa, event.id);
+        }
+    }
+    for event in window_closed_events.iter() {
+        if !event.id.is_primary() {
+            remove_window_ui_graph(&mut graph, &mut active_cameras, event.id);
+        }
+    }
 }
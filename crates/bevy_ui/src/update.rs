@@ -1,4 +1,4 @@
-use super::Node;
+use super::{CursorImageNode, Node};
 use bevy_ecs::{
     entity::Entity,
     query::{With, Without},
@@ -8,8 +8,11 @@ use bevy_transform::prelude::{Children, Parent, Transform};
 
 pub const UI_Z_STEP: f32 = 0.001;
 
+/// Orders the UI nodes by z, the node drawing the [`CursorImage`](crate::CursorImage) keeps its z
+/// to stay in front of the others.
+#[allow(clippy::type_complexity)]
 pub fn ui_z_system(
-    root_node_query: Query<Entity, (With<Node>, Without<Parent>)>,
+    root_node_query: Query<Entity, (With<Node>, Without<Parent>, Without<CursorImageNode>)>,
     mut node_query: Query<&mut Transform, With<Node>>,
     children_query: Query<&Children>,
 ) {
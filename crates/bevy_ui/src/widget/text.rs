@@ -1,7 +1,8 @@
 use crate::{CalculatedSize, Node, Style, Val};
-use bevy_asset::Assets;
+use bevy_asset::{AssetEvent, Assets};
 use bevy_ecs::{
     entity::Entity,
+    event::EventReader,
     query::{Changed, Or, QueryState, With, Without},
     system::{Local, Query, QuerySet, Res, ResMut},
 };
@@ -14,7 +15,10 @@ use bevy_render::{
     texture::Texture,
 };
 use bevy_sprite::{TextureAtlas, QUAD_HANDLE};
-use bevy_text::{DefaultTextPipeline, DrawableText, Font, FontAtlasSet, Text, TextError};
+use bevy_text::{
+    DefaultTextPipeline, DrawableText, Font, FontAtlasSet, FontAtlasSettings, Text, TextEffects,
+    TextError,
+};
 use bevy_transform::prelude::GlobalTransform;
 use bevy_window::Windows;
 
@@ -51,6 +55,8 @@ pub fn text_system(
     windows: Res<Windows>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
     mut font_atlas_set_storage: ResMut<Assets<FontAtlasSet>>,
+    font_atlas_settings: Res<FontAtlasSettings>,
+    mut texture_atlas_events: EventReader<AssetEvent<TextureAtlas>>,
     mut text_pipeline: ResMut<DefaultTextPipeline>,
     mut text_queries: QuerySet<(
         QueryState<Entity, Or<(Changed<Text>, Changed<Style>)>>,
@@ -80,6 +86,26 @@ pub fn text_system(
         *last_scale_factor = scale_factor;
     }
 
+    // Adds all entities with glyphs in an evicted font atlas to the queue
+    let removed_texture_atlases = texture_atlas_events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Removed { handle } => Some(handle.id),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if !removed_texture_atlases.is_empty() {
+        for entity in text_queries.q1().iter() {
+            if text_pipeline
+                .get_glyphs(&entity)
+                .is_some_and(|info| info.has_glyphs_in(&removed_texture_atlases))
+                && !queued_text.entities.contains(&entity)
+            {
+                queued_text.entities.push(entity);
+            }
+        }
+    }
+
     if queued_text.entities.is_empty() {
         return;
     }
@@ -112,6 +138,7 @@ pub fn text_system(
                 text.alignment,
                 node_size,
                 &mut *font_atlas_set_storage,
+                &font_atlas_settings,
                 &mut *texture_atlases,
                 &mut *textures,
             ) {
@@ -148,7 +175,15 @@ pub fn draw_text_system(
     mut render_resource_bindings: ResMut<RenderResourceBindings>,
     text_pipeline: Res<DefaultTextPipeline>,
     mut query: Query<
-        (Entity, &mut Draw, &Visible, &Text, &Node, &GlobalTransform),
+        (
+            Entity,
+            &mut Draw,
+            &Visible,
+            &Text,
+            &Node,
+            &GlobalTransform,
+            Option<&TextEffects>,
+        ),
         Without<OutsideFrustum>,
     >,
 ) {
@@ -161,7 +196,7 @@ pub fn draw_text_system(
     let font_quad = meshes.get(&QUAD_HANDLE).unwrap();
     let vertex_buffer_layout = font_quad.get_vertex_buffer_layout();
 
-    for (entity, mut draw, visible, text, node, global_transform) in query.iter_mut() {
+    for (entity, mut draw, visible, text, node, global_transform, effects) in query.iter_mut() {
         if !visible.is_visible {
             continue;
         }
@@ -176,6 +211,7 @@ pub fn draw_text_system(
                 font_quad_vertex_layout: &vertex_buffer_layout,
                 sections: &text.sections,
                 alignment_offset: (node.size / -2.0).extend(0.0),
+                effects: effects.copied().unwrap_or_default(),
             };
 
             drawable_text.draw(&mut draw, &mut context).unwrap();
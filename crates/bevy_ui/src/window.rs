@@ -0,0 +1,67 @@
+use crate::{camera::CAMERA_UI, Node};
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    query::{With, Without},
+    system::Query,
+};
+use bevy_render::{
+    camera::{Camera, VisibleEntities},
+    render_graph::base::window_camera_name,
+};
+use bevy_transform::prelude::{Children, Parent};
+use bevy_utils::HashMap;
+use bevy_window::WindowId;
+
+/// The window a root UI node and its children are laid out in, and drawn to by the UI camera of
+/// that window (see [`UiCameraBundle::for_window`](crate::entity::UiCameraBundle::for_window)).
+/// Root nodes without this component are in the primary window.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UiWindow(pub WindowId);
+
+/// The windows of the nodes that aren't in the primary window.
+pub(crate) fn node_windows<'a>(
+    roots: impl Iterator<Item = (Entity, &'a UiWindow)>,
+    children_query: &Query<&Children>,
+) -> HashMap<Entity, WindowId> {
+    let mut node_windows = HashMap::default();
+    for (root, window) in roots {
+        if window.0.is_primary() {
+            continue;
+        }
+        let mut entities = vec![root];
+        while let Some(entity) = entities.pop() {
+            node_windows.insert(entity, window.0);
+            if let Ok(children) = children_query.get(entity) {
+                entities.extend(children.iter().cloned());
+            }
+        }
+    }
+    node_windows
+}
+
+/// Only keeps the nodes of its window in the visible entities of the UI camera of a window.
+#[allow(clippy::type_complexity)]
+pub fn ui_camera_window_system(
+    root_node_query: Query<(Entity, &UiWindow), (With<Node>, Without<Parent>)>,
+    children_query: Query<&Children>,
+    mut camera_query: Query<(&Camera, &mut VisibleEntities)>,
+) {
+    let node_windows = node_windows(root_node_query.iter(), &children_query);
+    for (camera, mut visible_entities) in camera_query.iter_mut() {
+        if node_windows.is_empty() && camera.window.is_primary() {
+            continue;
+        }
+        let ui_camera_name = window_camera_name(CAMERA_UI, camera.window);
+        if camera.name.as_deref() != Some(ui_camera_name.as_str()) {
+            continue;
+        }
+        visible_entities.value.retain(|visible_entity| {
+            let window = node_windows
+                .get(&visible_entity.entity)
+                .copied()
+                .unwrap_or_else(WindowId::primary);
+            window == camera.window
+        });
+    }
+}
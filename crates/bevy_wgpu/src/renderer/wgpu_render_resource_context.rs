@@ -11,7 +11,10 @@ use bevy_render::{
         RenderResourceContext, RenderResourceId, SamplerId, TextureId,
     },
     shader::{glsl_to_spirv, Shader, ShaderError, ShaderSource},
-    texture::{Extent3d, SamplerDescriptor, TextureDescriptor},
+    texture::{
+        Extent3d, SamplerDescriptor, TextureDescriptor, TextureDimension, TextureFormat,
+        TextureUsage,
+    },
 };
 use bevy_utils::tracing::trace;
 use bevy_window::{Window, WindowId};
@@ -48,6 +51,20 @@ impl WgpuRenderResourceContext {
         window_surfaces.insert(window_id, surface);
     }
 
+    /// Removes the surface, the swap chain and the offscreen texture of a closed window.
+    pub fn remove_window(&self, window_id: WindowId) {
+        self.resources.window_swap_chains.write().remove(&window_id);
+        self.resources.window_surfaces.write().remove(&window_id);
+        let offscreen_texture = self
+            .resources
+            .offscreen_window_textures
+            .write()
+            .remove(&window_id);
+        if let Some(offscreen_texture) = offscreen_texture {
+            self.remove_texture(offscreen_texture);
+        }
+    }
+
     pub fn copy_buffer_to_buffer(
         &self,
         command_encoder: &mut wgpu::CommandEncoder,
@@ -230,6 +247,41 @@ impl WgpuRenderResourceContext {
         bind_group_layouts.insert(descriptor.id, bind_group_layout);
     }
 
+    /// Creates (or recreates, after a resize) the texture a window without a surface renders
+    /// into.
+    fn create_offscreen_window_texture(&self, window: &Window) -> TextureId {
+        let texture = self.create_texture(TextureDescriptor {
+            size: Extent3d::new(
+                window.physical_width().max(1),
+                window.physical_height().max(1),
+                1,
+            ),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::default(),
+            usage: TextureUsage::OUTPUT_ATTACHMENT | TextureUsage::COPY_SRC,
+        });
+        let old_texture = self
+            .resources
+            .offscreen_window_textures
+            .write()
+            .insert(window.id(), texture);
+        if let Some(old_texture) = old_texture {
+            self.remove_texture(old_texture);
+        }
+        texture
+    }
+
+    /// Returns the texture a window without a surface renders into, if it was rendered to yet.
+    pub fn get_offscreen_window_texture(&self, window_id: WindowId) -> Option<TextureId> {
+        self.resources
+            .offscreen_window_textures
+            .read()
+            .get(&window_id)
+            .cloned()
+    }
+
     fn try_next_swap_chain_texture(&self, window_id: bevy_window::WindowId) -> Option<TextureId> {
         let mut window_swap_chains = self.resources.window_swap_chains.write();
         let mut swap_chain_outputs = self.resources.swap_chain_frames.write();
@@ -360,12 +412,17 @@ impl RenderResourceContext for WgpuRenderResourceContext {
 
     fn create_swap_chain(&self, window: &Window) {
         let surfaces = self.resources.window_surfaces.read();
+        let surface = if let Some(surface) = surfaces.get(&window.id()) {
+            surface
+        } else {
+            // windows without a surface render into an offscreen texture instead
+            drop(surfaces);
+            self.create_offscreen_window_texture(window);
+            return;
+        };
         let mut window_swap_chains = self.resources.window_swap_chains.write();
 
         let swap_chain_descriptor: wgpu::SwapChainDescriptor = window.wgpu_into();
-        let surface = surfaces
-            .get(&window.id())
-            .expect("No surface found for window.");
         let swap_chain = self
             .device
             .create_swap_chain(surface, &swap_chain_descriptor);
@@ -374,6 +431,21 @@ impl RenderResourceContext for WgpuRenderResourceContext {
     }
 
     fn next_swap_chain_texture(&self, window: &bevy_window::Window) -> TextureId {
+        if !self
+            .resources
+            .window_surfaces
+            .read()
+            .contains_key(&window.id())
+        {
+            let texture = self
+                .resources
+                .offscreen_window_textures
+                .read()
+                .get(&window.id())
+                .cloned();
+            return texture.unwrap_or_else(|| self.create_offscreen_window_texture(window));
+        }
+
         if let Some(texture_id) = self.try_next_swap_chain_texture(window.id()) {
             texture_id
         } else {
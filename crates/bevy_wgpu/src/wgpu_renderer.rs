@@ -7,9 +7,12 @@ use bevy_app::{Events, ManualEventReader};
 use bevy_ecs::world::{Mut, World};
 use bevy_render::{
     render_graph::{DependentNodeStager, RenderGraph, RenderGraphStager},
-    renderer::RenderResourceContext,
+    renderer::{BufferInfo, BufferMapMode, BufferUsage, RenderResourceContext},
+    screenshot::{ScreenshotCaptured, Screenshots},
+    texture::{Texture, TextureDimension},
 };
-use bevy_window::{WindowCreated, WindowResized, Windows};
+use bevy_utils::tracing::warn;
+use bevy_window::{WindowClosed, WindowCreated, WindowResized, Windows};
 use std::{ops::Deref, sync::Arc};
 
 pub struct WgpuRenderer {
@@ -18,6 +21,7 @@ pub struct WgpuRenderer {
     pub queue: wgpu::Queue,
     pub window_resized_event_reader: ManualEventReader<WindowResized>,
     pub window_created_event_reader: ManualEventReader<WindowCreated>,
+    pub window_closed_event_reader: ManualEventReader<WindowClosed>,
     pub initialized: bool,
 }
 
@@ -74,6 +78,7 @@ impl WgpuRenderer {
             queue,
             window_resized_event_reader: Default::default(),
             window_created_event_reader: Default::default(),
+            window_closed_event_reader: Default::default(),
             initialized: false,
         }
     }
@@ -95,17 +100,30 @@ impl WgpuRenderer {
             let window = windows
                 .get(window_created_event.id)
                 .expect("Received window created event for non-existent window.");
+            // windows without a native window (like headless windows) render offscreen
             #[cfg(feature = "bevy_winit")]
-            {
-                let winit_windows = world.get_resource::<bevy_winit::WinitWindows>().unwrap();
-                let winit_window = winit_windows.get_window(window.id()).unwrap();
-                // SAFE: The raw window handle created from a `winit::Window` is always valid.
-                let surface = unsafe { self.instance.create_surface(winit_window.deref()) };
-                render_resource_context.set_window_surface(window.id(), surface);
+            if let Some(winit_windows) = world.get_resource::<bevy_winit::WinitWindows>() {
+                if let Some(winit_window) = winit_windows.get_window(window.id()) {
+                    // SAFE: The raw window handle created from a `winit::Window` is always valid.
+                    let surface = unsafe { self.instance.create_surface(winit_window.deref()) };
+                    render_resource_context.set_window_surface(window.id(), surface);
+                }
             }
         }
     }
 
+    pub fn handle_window_closed_events(&mut self, world: &mut World) {
+        let render_resource_context = world
+            .get_resource::<Box<dyn RenderResourceContext>>()
+            .unwrap()
+            .downcast_ref::<WgpuRenderResourceContext>()
+            .unwrap();
+        let window_closed_events = world.get_resource::<Events<WindowClosed>>().unwrap();
+        for window_closed_event in self.window_closed_event_reader.iter(window_closed_events) {
+            render_resource_context.remove_window(window_closed_event.id);
+        }
+    }
+
     pub fn run_graph(&mut self, world: &mut World) {
         world.resource_scope(|world, mut render_graph: Mut<RenderGraph>| {
             render_graph.prepare(world);
@@ -122,9 +140,90 @@ impl WgpuRenderer {
         })
     }
 
+    /// Reads back the frames requested through [Screenshots] and sends them as
+    /// [ScreenshotCaptured] events.
+    pub fn capture_screenshots(&mut self, world: &mut World) {
+        let world = world.cell();
+        let mut screenshots = if let Some(screenshots) = world.get_resource_mut::<Screenshots>() {
+            screenshots
+        } else {
+            return;
+        };
+        let render_resource_context = world
+            .get_resource::<Box<dyn RenderResourceContext>>()
+            .unwrap();
+        let render_resource_context = render_resource_context
+            .downcast_ref::<WgpuRenderResourceContext>()
+            .unwrap();
+        let mut screenshot_captured_events = world
+            .get_resource_mut::<Events<ScreenshotCaptured>>()
+            .unwrap();
+
+        for request in screenshots.drain_requests() {
+            let texture = if let Some(texture) =
+                render_resource_context.get_offscreen_window_texture(request.window)
+            {
+                texture
+            } else {
+                warn!(
+                    "Can't capture a screenshot of window {}. Only windows rendered offscreen can be captured.",
+                    request.window
+                );
+                continue;
+            };
+            let descriptor = render_resource_context.resources.texture_descriptors.read()[&texture];
+            let size = descriptor.size;
+            let pixel_size = descriptor.format.pixel_size();
+            let row_size = size.width as usize * pixel_size;
+            let padded_row_size = render_resource_context.get_aligned_texture_size(row_size);
+
+            let buffer = render_resource_context.create_buffer(BufferInfo {
+                size: padded_row_size * size.height as usize,
+                buffer_usage: BufferUsage::COPY_DST | BufferUsage::MAP_READ,
+                mapped_at_creation: false,
+            });
+            let mut command_encoder = self
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            render_resource_context.copy_texture_to_buffer(
+                &mut command_encoder,
+                texture,
+                [0, 0, 0],
+                0,
+                buffer,
+                0,
+                padded_row_size as u32,
+                size,
+            );
+            self.queue.submit(std::iter::once(command_encoder.finish()));
+
+            render_resource_context.map_buffer(buffer, BufferMapMode::Read);
+            let data = {
+                let buffers = render_resource_context.resources.buffers.read();
+                let mapped = buffers[&buffer].slice(..).get_mapped_range();
+                // strip the padding wgpu requires at the end of each row
+                mapped
+                    .chunks(padded_row_size)
+                    .flat_map(|row| &row[..row_size])
+                    .cloned()
+                    .collect::<Vec<u8>>()
+            };
+            render_resource_context.unmap_buffer(buffer);
+            render_resource_context.remove_buffer(buffer);
+
+            screenshot_captured_events.send(ScreenshotCaptured {
+                window: request.window,
+                path: request.path,
+                texture: Texture::new(size, TextureDimension::D2, data, descriptor.format),
+            });
+        }
+    }
+
     pub fn update(&mut self, world: &mut World) {
         self.handle_window_created_events(world);
+        self.handle_window_closed_events(world);
         self.run_graph(world);
+        self.capture_screenshots(world);
 
         let render_resource_context = world
             .get_resource::<Box<dyn RenderResourceContext>>()
@@ -0,0 +1,180 @@
+use thiserror::Error;
+
+/// An image on the clipboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardImage {
+    pub width: u32,
+    pub height: u32,
+    /// RGBA pixels with 8 bits per channel, row by row from the top left corner
+    pub rgba: Vec<u8>,
+}
+
+#[derive(Error, Debug)]
+pub enum ClipboardError {
+    #[error("The clipboard doesn't contain content of the requested type")]
+    Empty,
+    #[error("The clipboard backend doesn't support this type of content")]
+    Unsupported,
+    #[error("Failed to access the clipboard: {0}")]
+    Backend(String),
+}
+
+/// The platform clipboard used by the [`Clipboard`] resource.
+pub trait ClipboardBackend: Send + Sync + 'static {
+    fn get_text(&self) -> Result<String, ClipboardError>;
+    fn set_text(&mut self, text: String) -> Result<(), ClipboardError>;
+
+    fn get_image(&self) -> Result<ClipboardImage, ClipboardError> {
+        Err(ClipboardError::Unsupported)
+    }
+
+    fn set_image(&mut self, _image: ClipboardImage) -> Result<(), ClipboardError> {
+        Err(ClipboardError::Unsupported)
+    }
+}
+
+/// A clipboard shared by the app only, used when the windowing backend doesn't provide access
+/// to the clipboard of the platform, like on the web or in headless apps.
+#[derive(Debug, Default)]
+pub struct LocalClipboard {
+    text: Option<String>,
+    image: Option<ClipboardImage>,
+}
+
+impl ClipboardBackend for LocalClipboard {
+    fn get_text(&self) -> Result<String, ClipboardError> {
+        self.text.clone().ok_or(ClipboardError::Empty)
+    }
+
+    fn set_text(&mut self, text: String) -> Result<(), ClipboardError> {
+        self.text = Some(text);
+        self.image = None;
+        Ok(())
+    }
+
+    fn get_image(&self) -> Result<ClipboardImage, ClipboardError> {
+        self.image.clone().ok_or(ClipboardError::Empty)
+    }
+
+    fn set_image(&mut self, image: ClipboardImage) -> Result<(), ClipboardError> {
+        self.image = Some(image);
+        self.text = None;
+        Ok(())
+    }
+}
+
+/// Reads and writes the clipboard, for copy and paste in text inputs and editors.
+///
+/// The windowing backend replaces the [`ClipboardBackend`] with the clipboard of the platform
+/// when it has access to it, otherwise a [`LocalClipboard`] is used.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_window::Clipboard;
+/// fn copy_score(mut clipboard: ResMut<Clipboard>) {
+///     if let Err(err) = clipboard.set_text("Score: 42") {
+///         eprintln!("{}", err);
+///     }
+/// }
+/// # copy_score.system();
+/// ```
+pub struct Clipboard {
+    backend: Box<dyn ClipboardBackend>,
+}
+
+impl Clipboard {
+    pub fn new(backend: impl ClipboardBackend) -> Self {
+        Clipboard {
+            backend: Box::new(backend),
+        }
+    }
+
+    pub fn get_text(&self) -> Result<String, ClipboardError> {
+        self.backend.get_text()
+    }
+
+    pub fn set_text(&mut self, text: impl Into<String>) -> Result<(), ClipboardError> {
+        self.backend.set_text(text.into())
+    }
+
+    pub fn get_image(&self) -> Result<ClipboardImage, ClipboardError> {
+        self.backend.get_image()
+    }
+
+    pub fn set_image(&mut self, image: ClipboardImage) -> Result<(), ClipboardError> {
+        self.backend.set_image(image)
+    }
+}
+
+impl Default for Clipboard {
+    fn default() -> Self {
+        Clipboard::new(LocalClipboard::default())
+    }
+}
+
+impl std::fmt::Debug for Clipboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Clipboard").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_clipboard() {
+        let mut clipboard = Clipboard::default();
+        assert!(matches!(clipboard.get_text(), Err(ClipboardError::Empty)));
+        assert!(matches!(clipboard.get_image(), Err(ClipboardError::Empty)));
+
+        clipboard.set_text("Score: 42").unwrap();
+        assert_eq!(clipboard.get_text().unwrap(), "Score: 42");
+
+        // the content of the clipboard is either text or an image
+        let image = ClipboardImage {
+            width: 1,
+            height: 1,
+            rgba: vec![255, 0, 0, 255],
+        };
+        clipboard.set_image(image.clone()).unwrap();
+        assert_eq!(clipboard.get_image().unwrap(), image);
+        assert!(matches!(clipboard.get_text(), Err(ClipboardError::Empty)));
+        clipboard.set_text("Score: 43").unwrap();
+        assert!(matches!(clipboard.get_image(), Err(ClipboardError::Empty)));
+    }
+
+    #[test]
+    fn text_only_backend() {
+        #[derive(Default)]
+        struct TextClipboard(String);
+
+        impl ClipboardBackend for TextClipboard {
+            fn get_text(&self) -> Result<String, ClipboardError> {
+                Ok(self.0.clone())
+            }
+
+            fn set_text(&mut self, text: String) -> Result<(), ClipboardError> {
+                self.0 = text;
+                Ok(())
+            }
+        }
+
+        let mut clipboard = Clipboard::new(TextClipboard::default());
+        clipboard.set_text("text").unwrap();
+        assert_eq!(clipboard.get_text().unwrap(), "text");
+        let image = ClipboardImage {
+            width: 0,
+            height: 0,
+            rgba: vec![],
+        };
+        assert!(matches!(
+            clipboard.set_image(image),
+            Err(ClipboardError::Unsupported)
+        ));
+        assert!(matches!(
+            clipboard.get_image(),
+            Err(ClipboardError::Unsupported)
+        ));
+    }
+}
@@ -32,6 +32,13 @@ pub struct WindowCreated {
     pub id: WindowId,
 }
 
+/// An event that is sent whenever a window is closed, after it was removed from
+/// [`Windows`](crate::Windows).
+#[derive(Debug, Clone)]
+pub struct WindowClosed {
+    pub id: WindowId,
+}
+
 /// An event that is sent whenever a close was requested for a window. For example: when the "close"
 /// button is pressed on a window.
 #[derive(Debug, Clone)]
@@ -82,14 +89,41 @@ pub struct WindowBackendScaleFactorChanged {
     pub scale_factor: f64,
 }
 
-/// Events related to files being dragged and dropped on a window.
+/// Events related to files being dragged and dropped on a window. When several files are dragged,
+/// an event is sent for each of them.
+///
+/// The cursor position is the last one known by the window, in logical pixels from its bottom
+/// left corner, as most platforms don't report the movements of the cursor during a drag.
+///
+/// Drag and drop is disabled on Windows, where it conflicts with the audio backend.
 #[derive(Debug, Clone)]
 pub enum FileDragAndDrop {
-    DroppedFile { id: WindowId, path_buf: PathBuf },
-
-    HoveredFile { id: WindowId, path_buf: PathBuf },
+    DroppedFile {
+        id: WindowId,
+        path_buf: PathBuf,
+        cursor_position: Option<Vec2>,
+    },
+
+    HoveredFile {
+        id: WindowId,
+        path_buf: PathBuf,
+        cursor_position: Option<Vec2>,
+    },
+
+    HoveredFileCancelled {
+        id: WindowId,
+    },
+}
 
-    HoveredFileCancelled { id: WindowId },
+impl FileDragAndDrop {
+    /// The window the file is dragged on
+    pub fn window_id(&self) -> WindowId {
+        match self {
+            FileDragAndDrop::DroppedFile { id, .. }
+            | FileDragAndDrop::HoveredFile { id, .. }
+            | FileDragAndDrop::HoveredFileCancelled { id } => *id,
+        }
+    }
 }
 
 /// An event that is sent when a window is repositioned in physical pixels.
@@ -98,3 +132,29 @@ pub struct WindowMoved {
     pub id: WindowId,
     pub position: IVec2,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_drag_and_drop_window() {
+        let window = WindowId::new();
+        let events = [
+            FileDragAndDrop::HoveredFile {
+                id: window,
+                path_buf: PathBuf::from("image.png"),
+                cursor_position: Some(Vec2::new(10.0, 20.0)),
+            },
+            FileDragAndDrop::HoveredFileCancelled { id: window },
+            FileDragAndDrop::DroppedFile {
+                id: window,
+                path_buf: PathBuf::from("image.png"),
+                cursor_position: None,
+            },
+        ];
+        for event in events.iter() {
+            assert_eq!(event.window_id(), window);
+        }
+    }
+}
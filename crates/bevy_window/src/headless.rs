@@ -0,0 +1,101 @@
+use crate::{CloseWindow, CreateWindow, Window, WindowClosed, WindowCreated, Windows};
+use bevy_app::{prelude::*, EventReader, EventWriter};
+use bevy_ecs::system::ResMut;
+
+/// Creates windows without a native counterpart. This can replace a windowing backend like
+/// `WinitPlugin` to render offscreen, for example on CI machines or servers without a display.
+///
+/// The size of a headless window is taken from its [WindowDescriptor](crate::WindowDescriptor)
+/// and never changes. The app needs a runner that doesn't depend on an event loop, like the one
+/// added by `ScheduleRunnerPlugin`.
+#[derive(Debug, Default)]
+pub struct HeadlessWindowPlugin;
+
+impl Plugin for HeadlessWindowPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(CoreStage::PreUpdate, create_headless_windows_system)
+            .add_system_to_stage(CoreStage::PreUpdate, close_headless_windows_system);
+    }
+}
+
+pub fn create_headless_windows_system(
+    mut windows: ResMut<Windows>,
+    mut create_window_events: EventReader<CreateWindow>,
+    mut window_created_events: EventWriter<WindowCreated>,
+) {
+    for create_window_event in create_window_events.iter() {
+        let descriptor = &create_window_event.descriptor;
+        let scale_factor = descriptor.scale_factor_override.unwrap_or(1.0);
+        windows.add(Window::new(
+            create_window_event.id,
+            descriptor,
+            (descriptor.width as f64 * scale_factor) as u32,
+            (descriptor.height as f64 * scale_factor) as u32,
+            scale_factor,
+            None,
+        ));
+        window_created_events.send(WindowCreated {
+            id: create_window_event.id,
+        });
+    }
+}
+
+pub fn close_headless_windows_system(
+    mut windows: ResMut<Windows>,
+    mut close_window_events: EventReader<CloseWindow>,
+    mut window_closed_events: EventWriter<WindowClosed>,
+) {
+    for close_window_event in close_window_events.iter() {
+        if windows.remove(close_window_event.id).is_some() {
+            window_closed_events.send(WindowClosed {
+                id: close_window_event.id,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{WindowDescriptor, WindowId, WindowPlugin};
+    use bevy_app::Events;
+
+    #[test]
+    fn create_and_close_headless_windows() {
+        let mut app = App::new();
+        app.insert_resource(WindowDescriptor {
+            width: 320.0,
+            height: 240.0,
+            scale_factor_override: Some(2.0),
+            ..Default::default()
+        })
+        .add_plugin(WindowPlugin {
+            exit_on_close: false,
+            ..Default::default()
+        })
+        .add_plugin(HeadlessWindowPlugin);
+        app.update();
+
+        let windows = app.world.get_resource::<Windows>().unwrap();
+        let window = windows.get_primary().unwrap();
+        assert_eq!(window.physical_width(), 640);
+        assert_eq!(window.physical_height(), 480);
+        assert_eq!(window.width(), 320.0);
+        assert_eq!(window.scale_factor(), 2.0);
+        let created = app.world.get_resource::<Events<WindowCreated>>().unwrap();
+        assert_eq!(created.get_reader().iter(created).count(), 1);
+
+        app.world
+            .get_resource_mut::<Events<CloseWindow>>()
+            .unwrap()
+            .send(CloseWindow {
+                id: WindowId::primary(),
+            });
+        app.update();
+
+        let windows = app.world.get_resource::<Windows>().unwrap();
+        assert!(windows.get_primary().is_none());
+        let closed = app.world.get_resource::<Events<WindowClosed>>().unwrap();
+        assert_eq!(closed.get_reader().iter(closed).count(), 1);
+    }
+}
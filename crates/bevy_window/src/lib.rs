@@ -1,9 +1,15 @@
+mod clipboard;
 mod event;
+mod headless;
+mod monitor;
 mod system;
 mod window;
 mod windows;
 
+pub use clipboard::*;
 pub use event::*;
+pub use headless::*;
+pub use monitor::*;
 pub use system::*;
 pub use window::*;
 pub use windows::*;
@@ -11,8 +17,9 @@ pub use windows::*;
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        CursorEntered, CursorLeft, CursorMoved, FileDragAndDrop, ReceivedCharacter, Window,
-        WindowDescriptor, WindowMoved, Windows,
+        Clipboard, CursorEntered, CursorGrabMode, CursorIcon, CursorLeft, CursorMoved,
+        FileDragAndDrop, MonitorSelection, Monitors, ReceivedCharacter, UserAttentionType, Window,
+        WindowDescriptor, WindowIcon, WindowMoved, Windows,
     };
 }
 
@@ -39,6 +46,7 @@ impl Plugin for WindowPlugin {
             .add_event::<WindowCreated>()
             .add_event::<WindowCloseRequested>()
             .add_event::<CloseWindow>()
+            .add_event::<WindowClosed>()
             .add_event::<CursorMoved>()
             .add_event::<CursorEntered>()
             .add_event::<CursorLeft>()
@@ -48,7 +56,9 @@ impl Plugin for WindowPlugin {
             .add_event::<WindowBackendScaleFactorChanged>()
             .add_event::<FileDragAndDrop>()
             .add_event::<WindowMoved>()
-            .init_resource::<Windows>();
+            .init_resource::<Windows>()
+            .init_resource::<Clipboard>()
+            .init_resource::<Monitors>();
 
         if self.add_primary_window {
             let window_descriptor = app
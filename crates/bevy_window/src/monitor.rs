@@ -0,0 +1,168 @@
+use bevy_math::{IVec2, UVec2};
+
+/// A video mode of a monitor, used by [`WindowMode::ExclusiveFullscreen`](crate::WindowMode)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VideoMode {
+    /// The resolution, in physical pixels
+    pub physical_size: UVec2,
+    pub bit_depth: u16,
+    /// In hertz
+    pub refresh_rate: u16,
+}
+
+/// A monitor connected to the system, see [`Monitors`].
+#[derive(Debug, Clone)]
+pub struct Monitor {
+    pub name: Option<String>,
+    /// The position of the top left corner of the monitor on the desktop, in physical pixels
+    pub position: IVec2,
+    /// The current resolution, in physical pixels
+    pub physical_size: UVec2,
+    pub scale_factor: f64,
+    pub is_primary: bool,
+    pub video_modes: Vec<VideoMode>,
+}
+
+impl Monitor {
+    /// The video mode with the highest resolution and refresh rate.
+    pub fn best_video_mode(&self) -> Option<VideoMode> {
+        self.video_modes.iter().copied().max_by_key(|mode| {
+            (
+                mode.physical_size.x,
+                mode.physical_size.y,
+                mode.refresh_rate,
+                mode.bit_depth,
+            )
+        })
+    }
+}
+
+/// Which monitor a window is shown on, see [`Window::set_monitor`](crate::Window::set_monitor)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MonitorSelection {
+    /// The monitor the window is currently on, the primary monitor for new windows
+    #[default]
+    Current,
+    Primary,
+    /// The monitor at this index in [`Monitors`]
+    Index(usize),
+}
+
+/// The monitors connected to the system, with their video modes, for the display settings of a
+/// game. The list is filled by the windowing backend at startup, and refreshed after
+/// [`request_refresh`](Monitors::request_refresh) is called, as monitors can be plugged in while
+/// the app is running.
+#[derive(Debug)]
+pub struct Monitors {
+    monitors: Vec<Monitor>,
+    refresh_requested: bool,
+}
+
+impl Default for Monitors {
+    fn default() -> Self {
+        Monitors {
+            monitors: Vec::new(),
+            refresh_requested: true,
+        }
+    }
+}
+
+impl Monitors {
+    pub fn get(&self, index: usize) -> Option<&Monitor> {
+        self.monitors.get(index)
+    }
+
+    pub fn primary(&self) -> Option<&Monitor> {
+        self.monitors.iter().find(|monitor| monitor.is_primary)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Monitor> {
+        self.monitors.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.monitors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.monitors.is_empty()
+    }
+
+    /// Requests the windowing backend to update the list of monitors before the next frame.
+    pub fn request_refresh(&mut self) {
+        self.refresh_requested = true;
+    }
+
+    #[allow(missing_docs)]
+    #[inline]
+    pub fn is_refresh_requested(&self) -> bool {
+        self.refresh_requested
+    }
+
+    #[allow(missing_docs)]
+    #[inline]
+    pub fn update_from_backend(&mut self, monitors: Vec<Monitor>) {
+        self.monitors = monitors;
+        self.refresh_requested = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video_mode(width: u32, height: u32, refresh_rate: u16) -> VideoMode {
+        VideoMode {
+            physical_size: UVec2::new(width, height),
+            bit_depth: 32,
+            refresh_rate,
+        }
+    }
+
+    fn monitor(name: &str, is_primary: bool, video_modes: Vec<VideoMode>) -> Monitor {
+        Monitor {
+            name: Some(name.to_string()),
+            position: IVec2::ZERO,
+            physical_size: UVec2::new(1920, 1080),
+            scale_factor: 1.0,
+            is_primary,
+            video_modes,
+        }
+    }
+
+    #[test]
+    fn best_video_mode() {
+        assert_eq!(monitor("DP-2", false, vec![]).best_video_mode(), None);
+        let monitor = monitor(
+            "DP-1",
+            true,
+            vec![
+                video_mode(1280, 720, 144),
+                video_mode(1920, 1080, 60),
+                video_mode(1920, 1080, 144),
+                video_mode(1920, 1080, 120),
+            ],
+        );
+        assert_eq!(monitor.best_video_mode(), Some(video_mode(1920, 1080, 144)));
+    }
+
+    #[test]
+    fn update_monitors() {
+        let mut monitors = Monitors::default();
+        assert!(monitors.is_empty());
+        assert!(monitors.is_refresh_requested());
+
+        monitors.update_from_backend(vec![
+            monitor("DP-1", false, vec![]),
+            monitor("HDMI-1", true, vec![]),
+        ]);
+        assert!(!monitors.is_refresh_requested());
+        assert_eq!(monitors.len(), 2);
+        assert_eq!(monitors.get(0).unwrap().name.as_deref(), Some("DP-1"));
+        assert_eq!(monitors.primary().unwrap().name.as_deref(), Some("HDMI-1"));
+        assert!(monitors.get(2).is_none());
+
+        monitors.request_refresh();
+        assert!(monitors.is_refresh_requested());
+    }
+}
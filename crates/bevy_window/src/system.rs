@@ -1,11 +1,18 @@
-use crate::WindowCloseRequested;
+use crate::{CloseWindow, WindowCloseRequested};
 use bevy_app::{AppExit, EventReader, EventWriter};
 
+/// Exits the app when the close of the primary window is requested, and closes the other
+/// windows when their close is requested.
 pub fn exit_on_window_close_system(
     mut app_exit_events: EventWriter<AppExit>,
+    mut close_window_events: EventWriter<CloseWindow>,
     mut window_close_requested_events: EventReader<WindowCloseRequested>,
 ) {
-    if window_close_requested_events.iter().next().is_some() {
-        app_exit_events.send(AppExit);
+    for event in window_close_requested_events.iter() {
+        if event.id.is_primary() {
+            app_exit_events.send(AppExit);
+        } else {
+            close_window_events.send(CloseWindow { id: event.id });
+        }
     }
 }
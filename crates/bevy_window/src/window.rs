@@ -1,3 +1,4 @@
+use crate::{MonitorSelection, VideoMode};
 use bevy_math::{IVec2, Vec2};
 use bevy_utils::{tracing::warn, Uuid};
 
@@ -120,11 +121,17 @@ pub struct Window {
     vsync: bool,
     resizable: bool,
     decorations: bool,
+    transparent: bool,
+    always_on_top: bool,
+    click_through: bool,
     cursor_visible: bool,
-    cursor_locked: bool,
+    cursor_grab_mode: CursorGrabMode,
+    cursor_icon: CursorIcon,
     cursor_position: Option<Vec2>,
+    icon: Option<WindowIcon>,
     focused: bool,
     mode: WindowMode,
+    monitor: MonitorSelection,
     #[cfg(target_arch = "wasm32")]
     pub canvas: Option<String>,
     command_queue: Vec<WindowCommand>,
@@ -135,6 +142,7 @@ pub enum WindowCommand {
     SetWindowMode {
         mode: WindowMode,
         resolution: (u32, u32),
+        monitor: MonitorSelection,
     },
     SetTitle {
         title: String,
@@ -155,8 +163,17 @@ pub enum WindowCommand {
     SetDecorations {
         decorations: bool,
     },
-    SetCursorLockMode {
-        locked: bool,
+    SetAlwaysOnTop {
+        always_on_top: bool,
+    },
+    SetClickThrough {
+        click_through: bool,
+    },
+    SetCursorGrabMode {
+        grab_mode: CursorGrabMode,
+    },
+    SetCursorIcon {
+        icon: CursorIcon,
     },
     SetCursorVisibility {
         visible: bool,
@@ -176,6 +193,12 @@ pub enum WindowCommand {
     SetResizeConstraints {
         resize_constraints: WindowResizeConstraints,
     },
+    SetIcon {
+        icon: Option<WindowIcon>,
+    },
+    RequestUserAttention {
+        request_type: Option<UserAttentionType>,
+    },
 }
 
 /// Defines the way a window is displayed
@@ -183,11 +206,108 @@ pub enum WindowCommand {
 /// defines whether a videomode is chosen that best fits the width and height
 /// in the Window structure, or if these are ignored.
 /// E.g. when use_size is set to false the best video mode possible is chosen.
+/// The fullscreen modes are on the monitor of [`Window::monitor`].
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum WindowMode {
     Windowed,
     BorderlessFullscreen,
-    Fullscreen { use_size: bool },
+    Fullscreen {
+        use_size: bool,
+    },
+    /// Exclusive fullscreen with a video mode of the monitor, see
+    /// [`Monitor::video_modes`](crate::Monitor::video_modes). The closest video mode is used if
+    /// the monitor doesn't support this one.
+    ExclusiveFullscreen {
+        video_mode: VideoMode,
+    },
+}
+
+/// Defines how the cursor is held by a window
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CursorGrabMode {
+    /// The cursor moves freely in and out of the window
+    #[default]
+    None,
+    /// The cursor can't leave the window
+    Confined,
+    /// The cursor stays at the center of the window, for camera controls like the ones of first
+    /// person games. [`CursorMoved`](crate::CursorMoved) events aren't sent while the cursor is
+    /// locked, the movements of the mouse are still sent as `MouseMotion` events.
+    Locked,
+}
+
+/// The icon of the system cursor when it is over a window
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CursorIcon {
+    #[default]
+    Default,
+    Crosshair,
+    Hand,
+    Arrow,
+    Move,
+    Text,
+    Wait,
+    Help,
+    Progress,
+    NotAllowed,
+    ContextMenu,
+    Cell,
+    VerticalText,
+    Alias,
+    Copy,
+    NoDrop,
+    Grab,
+    Grabbing,
+    AllScroll,
+    ZoomIn,
+    ZoomOut,
+    EResize,
+    NResize,
+    NeResize,
+    NwResize,
+    SResize,
+    SeResize,
+    SwResize,
+    WResize,
+    EwResize,
+    NsResize,
+    NeswResize,
+    NwseResize,
+    ColResize,
+    RowResize,
+}
+
+/// The icon of a window, shown in its title bar and in the taskbar (or dock) of the desktop
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowIcon {
+    /// RGBA pixels with 8 bits per channel, row by row from the top left corner
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl WindowIcon {
+    pub fn new(rgba: Vec<u8>, width: u32, height: u32) -> Self {
+        debug_assert_eq!(
+            rgba.len(),
+            (width * height * 4) as usize,
+            "Icon data and size have to match",
+        );
+        WindowIcon {
+            rgba,
+            width,
+            height,
+        }
+    }
+}
+
+/// How a window requests the attention of the user, see [`Window::request_attention`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UserAttentionType {
+    /// Flashes the taskbar button (or bounces the dock icon) until the window is focused
+    Critical,
+    /// Flashes the taskbar button (or bounces the dock icon) once
+    Informational,
 }
 
 impl Window {
@@ -213,11 +333,17 @@ impl Window {
             vsync: window_descriptor.vsync,
             resizable: window_descriptor.resizable,
             decorations: window_descriptor.decorations,
+            transparent: window_descriptor.transparent,
+            always_on_top: window_descriptor.always_on_top,
+            click_through: window_descriptor.click_through,
             cursor_visible: window_descriptor.cursor_visible,
-            cursor_locked: window_descriptor.cursor_locked,
+            cursor_grab_mode: window_descriptor.cursor_grab_mode,
+            cursor_icon: CursorIcon::Default,
             cursor_position: None,
+            icon: window_descriptor.icon.clone(),
             focused: true,
             mode: window_descriptor.mode,
+            monitor: window_descriptor.monitor,
             #[cfg(target_arch = "wasm32")]
             canvas: window_descriptor.canvas.clone(),
             command_queue: Vec::new(),
@@ -443,15 +569,77 @@ impl Window {
             .push(WindowCommand::SetDecorations { decorations });
     }
 
+    /// Whether the framebuffer of the window has an alpha channel, set at its creation with
+    /// [`WindowDescriptor::transparent`].
+    #[inline]
+    pub fn transparent(&self) -> bool {
+        self.transparent
+    }
+
+    #[inline]
+    pub fn always_on_top(&self) -> bool {
+        self.always_on_top
+    }
+
+    /// Keeps the window above the other windows of the desktop.
+    pub fn set_always_on_top(&mut self, always_on_top: bool) {
+        self.always_on_top = always_on_top;
+        self.command_queue
+            .push(WindowCommand::SetAlwaysOnTop { always_on_top });
+    }
+
+    #[inline]
+    pub fn click_through(&self) -> bool {
+        self.click_through
+    }
+
+    /// Lets the mouse go through the window to the windows below it, for overlays that don't
+    /// take input. The window doesn't receive mouse events while it is click-through.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - Only supported on **Windows** and **macOS**.
+    pub fn set_click_through(&mut self, click_through: bool) {
+        self.click_through = click_through;
+        self.command_queue
+            .push(WindowCommand::SetClickThrough { click_through });
+    }
+
+    /// Whether the cursor is [`Locked`](CursorGrabMode::Locked) or
+    /// [`Confined`](CursorGrabMode::Confined) to the window.
     #[inline]
     pub fn cursor_locked(&self) -> bool {
-        self.cursor_locked
+        self.cursor_grab_mode != CursorGrabMode::None
     }
 
+    /// Locks the cursor with [`CursorGrabMode::Locked`], or frees it.
     pub fn set_cursor_lock_mode(&mut self, lock_mode: bool) {
-        self.cursor_locked = lock_mode;
+        self.set_cursor_grab_mode(match lock_mode {
+            true => CursorGrabMode::Locked,
+            false => CursorGrabMode::None,
+        });
+    }
+
+    #[inline]
+    pub fn cursor_grab_mode(&self) -> CursorGrabMode {
+        self.cursor_grab_mode
+    }
+
+    pub fn set_cursor_grab_mode(&mut self, grab_mode: CursorGrabMode) {
+        self.cursor_grab_mode = grab_mode;
+        self.command_queue
+            .push(WindowCommand::SetCursorGrabMode { grab_mode });
+    }
+
+    #[inline]
+    pub fn cursor_icon(&self) -> CursorIcon {
+        self.cursor_icon
+    }
+
+    pub fn set_cursor_icon(&mut self, icon: CursorIcon) {
+        self.cursor_icon = icon;
         self.command_queue
-            .push(WindowCommand::SetCursorLockMode { locked: lock_mode });
+            .push(WindowCommand::SetCursorIcon { icon });
     }
 
     #[inline]
@@ -499,9 +687,59 @@ impl Window {
         self.command_queue.push(WindowCommand::SetWindowMode {
             mode,
             resolution: (self.physical_width, self.physical_height),
+            monitor: self.monitor,
+        });
+    }
+
+    #[inline]
+    pub fn monitor(&self) -> MonitorSelection {
+        self.monitor
+    }
+
+    /// Moves the window to `monitor`, in its current [`WindowMode`].
+    pub fn set_monitor(&mut self, monitor: MonitorSelection) {
+        self.monitor = monitor;
+        self.command_queue.push(WindowCommand::SetWindowMode {
+            mode: self.mode,
+            resolution: (self.physical_width, self.physical_height),
+            monitor,
         });
     }
 
+    /// Sets the mode of the window and the monitor it is shown on at once, like when applying
+    /// the display settings of a game.
+    pub fn set_mode_on_monitor(&mut self, mode: WindowMode, monitor: MonitorSelection) {
+        self.monitor = monitor;
+        self.set_mode(mode);
+    }
+
+    #[inline]
+    pub fn icon(&self) -> Option<&WindowIcon> {
+        self.icon.as_ref()
+    }
+
+    /// Sets the icon of the window, `None` restores the default icon. An icon can be made from a
+    /// `Texture` with `bevy_render`'s `WindowIcons`.
+    pub fn set_icon(&mut self, icon: Option<WindowIcon>) {
+        self.icon = icon.clone();
+        self.command_queue.push(WindowCommand::SetIcon { icon });
+    }
+
+    /// Requests the attention of the user, by flashing the taskbar button of the window or
+    /// bouncing its dock icon. It does nothing if the window is focused, and the request is
+    /// canceled once the window gets focused.
+    pub fn request_attention(&mut self, request_type: UserAttentionType) {
+        self.command_queue
+            .push(WindowCommand::RequestUserAttention {
+                request_type: Some(request_type),
+            });
+    }
+
+    pub fn cancel_attention_request(&mut self) {
+        self.command_queue
+            .push(WindowCommand::RequestUserAttention { request_type: None });
+    }
+
     #[inline]
     pub fn drain_commands(&mut self) -> impl Iterator<Item = WindowCommand> + '_ {
         self.command_queue.drain(..)
@@ -523,9 +761,21 @@ pub struct WindowDescriptor {
     pub vsync: bool,
     pub resizable: bool,
     pub decorations: bool,
+    /// Gives the framebuffer of the window an alpha channel, so that the desktop shows through
+    /// the parts of the window drawn with a transparent [`ClearColor`] and transparent materials.
+    /// It can't be changed once the window is created.
+    ///
+    /// [`ClearColor`]: https://docs.rs/bevy/*/bevy/render/pass/struct.ClearColor.html
+    pub transparent: bool,
+    /// See [`Window::set_always_on_top`]
+    pub always_on_top: bool,
+    /// See [`Window::set_click_through`]
+    pub click_through: bool,
     pub cursor_visible: bool,
-    pub cursor_locked: bool,
+    pub cursor_grab_mode: CursorGrabMode,
+    pub icon: Option<WindowIcon>,
     pub mode: WindowMode,
+    pub monitor: MonitorSelection,
     #[cfg(target_arch = "wasm32")]
     pub canvas: Option<String>,
 }
@@ -541,11 +791,163 @@ impl Default for WindowDescriptor {
             vsync: true,
             resizable: true,
             decorations: true,
-            cursor_locked: false,
+            transparent: false,
+            always_on_top: false,
+            click_through: false,
+            cursor_grab_mode: CursorGrabMode::None,
             cursor_visible: true,
+            icon: None,
             mode: WindowMode::Windowed,
+            monitor: MonitorSelection::Current,
             #[cfg(target_arch = "wasm32")]
             canvas: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_math::UVec2;
+
+    fn window(descriptor: &WindowDescriptor) -> Window {
+        Window::new(WindowId::primary(), descriptor, 800, 600, 1.0, None)
+    }
+
+    #[test]
+    fn cursor_grab_mode() {
+        let mut window = window(&WindowDescriptor {
+            cursor_grab_mode: CursorGrabMode::Confined,
+            ..Default::default()
+        });
+        assert_eq!(window.cursor_grab_mode(), CursorGrabMode::Confined);
+        assert!(window.cursor_locked());
+
+        window.set_cursor_lock_mode(false);
+        assert_eq!(window.cursor_grab_mode(), CursorGrabMode::None);
+        assert!(!window.cursor_locked());
+        window.set_cursor_lock_mode(true);
+        assert_eq!(window.cursor_grab_mode(), CursorGrabMode::Locked);
+        assert!(window.cursor_locked());
+
+        assert!(matches!(
+            window.drain_commands().collect::<Vec<_>>()[..],
+            [
+                WindowCommand::SetCursorGrabMode {
+                    grab_mode: CursorGrabMode::None
+                },
+                WindowCommand::SetCursorGrabMode {
+                    grab_mode: CursorGrabMode::Locked
+                },
+            ]
+        ));
+    }
+
+    #[test]
+    fn cursor_icon() {
+        let mut window = window(&WindowDescriptor::default());
+        assert_eq!(window.cursor_icon(), CursorIcon::Default);
+        window.set_cursor_icon(CursorIcon::Grab);
+        assert_eq!(window.cursor_icon(), CursorIcon::Grab);
+        assert!(matches!(
+            window.drain_commands().collect::<Vec<_>>()[..],
+            [WindowCommand::SetCursorIcon {
+                icon: CursorIcon::Grab
+            }]
+        ));
+        assert_eq!(window.drain_commands().count(), 0);
+    }
+
+    #[test]
+    fn icon_and_attention() {
+        let icon = WindowIcon::new(vec![255; 16], 2, 2);
+        let mut window = window(&WindowDescriptor {
+            icon: Some(icon.clone()),
+            ..Default::default()
+        });
+        assert_eq!(window.icon(), Some(&icon));
+
+        window.set_icon(None);
+        assert_eq!(window.icon(), None);
+        window.request_attention(UserAttentionType::Critical);
+        window.cancel_attention_request();
+        assert!(matches!(
+            window.drain_commands().collect::<Vec<_>>()[..],
+            [
+                WindowCommand::SetIcon { icon: None },
+                WindowCommand::RequestUserAttention {
+                    request_type: Some(UserAttentionType::Critical)
+                },
+                WindowCommand::RequestUserAttention { request_type: None },
+            ]
+        ));
+    }
+
+    #[test]
+    fn window_monitor() {
+        let mut window = window(&WindowDescriptor {
+            monitor: MonitorSelection::Index(1),
+            ..Default::default()
+        });
+        assert_eq!(window.monitor(), MonitorSelection::Index(1));
+
+        window.set_monitor(MonitorSelection::Primary);
+        let video_mode = VideoMode {
+            physical_size: UVec2::new(1280, 720),
+            bit_depth: 32,
+            refresh_rate: 60,
+        };
+        window.set_mode_on_monitor(
+            WindowMode::ExclusiveFullscreen { video_mode },
+            MonitorSelection::Index(0),
+        );
+        assert_eq!(window.monitor(), MonitorSelection::Index(0));
+        assert_eq!(
+            window.mode(),
+            WindowMode::ExclusiveFullscreen { video_mode }
+        );
+        assert!(matches!(
+            window.drain_commands().collect::<Vec<_>>()[..],
+            [
+                WindowCommand::SetWindowMode {
+                    mode: WindowMode::Windowed,
+                    resolution: (800, 600),
+                    monitor: MonitorSelection::Primary,
+                },
+                WindowCommand::SetWindowMode {
+                    mode: WindowMode::ExclusiveFullscreen { .. },
+                    monitor: MonitorSelection::Index(0),
+                    ..
+                },
+            ]
+        ));
+    }
+
+    #[test]
+    fn overlay_options() {
+        let mut window = window(&WindowDescriptor {
+            transparent: true,
+            always_on_top: true,
+            ..Default::default()
+        });
+        assert!(window.transparent());
+        assert!(window.always_on_top());
+        assert!(!window.click_through());
+
+        window.set_always_on_top(false);
+        window.set_click_through(true);
+        assert!(!window.always_on_top());
+        assert!(window.click_through());
+        assert!(matches!(
+            window.drain_commands().collect::<Vec<_>>()[..],
+            [
+                WindowCommand::SetAlwaysOnTop {
+                    always_on_top: false
+                },
+                WindowCommand::SetClickThrough {
+                    click_through: true
+                },
+            ]
+        ));
+    }
+}
@@ -19,6 +19,17 @@ impl Windows {
         self.windows.get_mut(&id)
     }
 
+    /// Removes a window without closing it, see [`CloseWindow`](crate::CloseWindow) to close a
+    /// window.
+    pub fn remove(&mut self, id: WindowId) -> Option<Window> {
+        self.windows.remove(&id)
+    }
+
+    /// The window that receives the keyboard input, if any.
+    pub fn get_focused(&self) -> Option<&Window> {
+        self.windows.values().find(|window| window.is_focused())
+    }
+
     pub fn get_primary(&self) -> Option<&Window> {
         self.get(WindowId::primary())
     }
@@ -0,0 +1,61 @@
+use bevy_window::{ClipboardBackend, ClipboardError, ClipboardImage};
+use std::{borrow::Cow, cell::RefCell};
+
+thread_local! {
+    // the clipboard is opened lazily on each thread that uses it, and kept open as the content
+    // set on some platforms is lost once every handle to the clipboard is dropped
+    static CLIPBOARD: RefCell<Option<arboard::Clipboard>> = const { RefCell::new(None) };
+}
+
+fn with_clipboard<T>(
+    f: impl FnOnce(&mut arboard::Clipboard) -> Result<T, arboard::Error>,
+) -> Result<T, ClipboardError> {
+    CLIPBOARD.with(|clipboard| {
+        let mut clipboard = clipboard.borrow_mut();
+        if clipboard.is_none() {
+            *clipboard = Some(arboard::Clipboard::new().map_err(convert_error)?);
+        }
+        f(clipboard.as_mut().unwrap()).map_err(convert_error)
+    })
+}
+
+fn convert_error(err: arboard::Error) -> ClipboardError {
+    match err {
+        arboard::Error::ContentNotAvailable => ClipboardError::Empty,
+        arboard::Error::ClipboardNotSupported => ClipboardError::Unsupported,
+        err => ClipboardError::Backend(err.to_string()),
+    }
+}
+
+/// The clipboard of the desktop
+#[derive(Debug, Default)]
+pub struct SystemClipboard;
+
+impl ClipboardBackend for SystemClipboard {
+    fn get_text(&self) -> Result<String, ClipboardError> {
+        with_clipboard(|clipboard| clipboard.get_text())
+    }
+
+    fn set_text(&mut self, text: String) -> Result<(), ClipboardError> {
+        with_clipboard(|clipboard| clipboard.set_text(text))
+    }
+
+    fn get_image(&self) -> Result<ClipboardImage, ClipboardError> {
+        let image = with_clipboard(|clipboard| clipboard.get_image())?;
+        Ok(ClipboardImage {
+            width: image.width as u32,
+            height: image.height as u32,
+            rgba: image.bytes.into_owned(),
+        })
+    }
+
+    fn set_image(&mut self, image: ClipboardImage) -> Result<(), ClipboardError> {
+        with_clipboard(|clipboard| {
+            clipboard.set_image(arboard::ImageData {
+                width: image.width as usize,
+                height: image.height as usize,
+                bytes: Cow::Owned(image.rgba),
+            })
+        })
+    }
+}
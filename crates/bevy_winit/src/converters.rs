@@ -1,10 +1,12 @@
 use bevy_input::{
     keyboard::{KeyCode, KeyboardInput},
     mouse::MouseButton,
+    pen::{PenButtons, PenInput, PenPhase, PenTilt},
     touch::{ForceTouch, TouchInput, TouchPhase},
     ElementState,
 };
-use bevy_math::Vec2;
+use bevy_math::{IVec2, UVec2, Vec2};
+use bevy_window::{CursorIcon, Monitor, UserAttentionType, VideoMode};
 
 pub fn convert_keyboard_input(keyboard_input: &winit::event::KeyboardInput) -> KeyboardInput {
     KeyboardInput {
@@ -58,6 +60,39 @@ pub fn convert_touch_input(
     }
 }
 
+/// The pen input of a touch made with a stylus, which winit only tells apart from fingers on iOS
+/// by the altitude of the stylus.
+pub fn convert_pen_input(
+    touch_input: winit::event::Touch,
+    location: winit::dpi::LogicalPosition<f32>,
+) -> Option<PenInput> {
+    let (force, max_possible_force, altitude_angle) = match touch_input.force {
+        Some(winit::event::Force::Calibrated {
+            force,
+            max_possible_force,
+            altitude_angle: Some(altitude_angle),
+        }) => (force, max_possible_force, altitude_angle),
+        _ => return None,
+    };
+    Some(PenInput {
+        phase: match touch_input.phase {
+            winit::event::TouchPhase::Started => PenPhase::Started,
+            winit::event::TouchPhase::Moved => PenPhase::Moved,
+            winit::event::TouchPhase::Ended => PenPhase::Ended,
+            winit::event::TouchPhase::Cancelled => PenPhase::Cancelled,
+        },
+        position: Vec2::new(location.x, location.y),
+        pressure: Some((force / max_possible_force).min(1.0) as f32),
+        tilt: Some(PenTilt {
+            altitude: altitude_angle as f32,
+            azimuth: None,
+        }),
+        buttons: PenButtons::default(),
+        eraser: false,
+        id: touch_input.id,
+    })
+}
+
 pub fn convert_virtual_key_code(virtual_key_code: winit::event::VirtualKeyCode) -> KeyCode {
     match virtual_key_code {
         winit::event::VirtualKeyCode::Key1 => KeyCode::Key1,
@@ -225,3 +260,75 @@ pub fn convert_virtual_key_code(virtual_key_code: winit::event::VirtualKeyCode)
         winit::event::VirtualKeyCode::Cut => KeyCode::Cut,
     }
 }
+
+pub fn convert_monitor(
+    monitor: &winit::monitor::MonitorHandle,
+    primary_monitor: Option<&winit::monitor::MonitorHandle>,
+) -> Monitor {
+    let position = monitor.position();
+    let size = monitor.size();
+    Monitor {
+        name: monitor.name(),
+        position: IVec2::new(position.x, position.y),
+        physical_size: UVec2::new(size.width, size.height),
+        scale_factor: monitor.scale_factor(),
+        is_primary: Some(monitor) == primary_monitor,
+        video_modes: monitor
+            .video_modes()
+            .map(|video_mode| VideoMode {
+                physical_size: UVec2::new(video_mode.size().width, video_mode.size().height),
+                bit_depth: video_mode.bit_depth(),
+                refresh_rate: video_mode.refresh_rate(),
+            })
+            .collect(),
+    }
+}
+
+pub fn convert_user_attention_type(
+    request_type: UserAttentionType,
+) -> winit::window::UserAttentionType {
+    match request_type {
+        UserAttentionType::Critical => winit::window::UserAttentionType::Critical,
+        UserAttentionType::Informational => winit::window::UserAttentionType::Informational,
+    }
+}
+
+pub fn convert_cursor_icon(cursor_icon: CursorIcon) -> winit::window::CursorIcon {
+    match cursor_icon {
+        CursorIcon::Default => winit::window::CursorIcon::Default,
+        CursorIcon::Crosshair => winit::window::CursorIcon::Crosshair,
+        CursorIcon::Hand => winit::window::CursorIcon::Hand,
+        CursorIcon::Arrow => winit::window::CursorIcon::Arrow,
+        CursorIcon::Move => winit::window::CursorIcon::Move,
+        CursorIcon::Text => winit::window::CursorIcon::Text,
+        CursorIcon::Wait => winit::window::CursorIcon::Wait,
+        CursorIcon::Help => winit::window::CursorIcon::Help,
+        CursorIcon::Progress => winit::window::CursorIcon::Progress,
+        CursorIcon::NotAllowed => winit::window::CursorIcon::NotAllowed,
+        CursorIcon::ContextMenu => winit::window::CursorIcon::ContextMenu,
+        CursorIcon::Cell => winit::window::CursorIcon::Cell,
+        CursorIcon::VerticalText => winit::window::CursorIcon::VerticalText,
+        CursorIcon::Alias => winit::window::CursorIcon::Alias,
+        CursorIcon::Copy => winit::window::CursorIcon::Copy,
+        CursorIcon::NoDrop => winit::window::CursorIcon::NoDrop,
+        CursorIcon::Grab => winit::window::CursorIcon::Grab,
+        CursorIcon::Grabbing => winit::window::CursorIcon::Grabbing,
+        CursorIcon::AllScroll => winit::window::CursorIcon::AllScroll,
+        CursorIcon::ZoomIn => winit::window::CursorIcon::ZoomIn,
+        CursorIcon::ZoomOut => winit::window::CursorIcon::ZoomOut,
+        CursorIcon::EResize => winit::window::CursorIcon::EResize,
+        CursorIcon::NResize => winit::window::CursorIcon::NResize,
+        CursorIcon::NeResize => winit::window::CursorIcon::NeResize,
+        CursorIcon::NwResize => winit::window::CursorIcon::NwResize,
+        CursorIcon::SResize => winit::window::CursorIcon::SResize,
+        CursorIcon::SeResize => winit::window::CursorIcon::SeResize,
+        CursorIcon::SwResize => winit::window::CursorIcon::SwResize,
+        CursorIcon::WResize => winit::window::CursorIcon::WResize,
+        CursorIcon::EwResize => winit::window::CursorIcon::EwResize,
+        CursorIcon::NsResize => winit::window::CursorIcon::NsResize,
+        CursorIcon::NeswResize => winit::window::CursorIcon::NeswResize,
+        CursorIcon::NwseResize => winit::window::CursorIcon::NwseResize,
+        CursorIcon::ColResize => winit::window::CursorIcon::ColResize,
+        CursorIcon::RowResize => winit::window::CursorIcon::RowResize,
+    }
+}
@@ -1,3 +1,5 @@
+#[cfg(not(target_arch = "wasm32"))]
+mod clipboard;
 mod converters;
 mod winit_config;
 mod winit_windows;
@@ -5,8 +7,11 @@ mod winit_windows;
 use bevy_input::{
     keyboard::KeyboardInput,
     mouse::{MouseButtonInput, MouseMotion, MouseScrollUnit, MouseWheel},
+    pen::{PenInput, PenPhase},
     touch::TouchInput,
 };
+#[cfg(not(target_arch = "wasm32"))]
+pub use clipboard::*;
 pub use winit_config::*;
 pub use winit_windows::*;
 
@@ -15,9 +20,10 @@ use bevy_ecs::{system::IntoExclusiveSystem, world::World};
 use bevy_math::{ivec2, Vec2};
 use bevy_utils::tracing::{error, trace, warn};
 use bevy_window::{
-    CreateWindow, CursorEntered, CursorLeft, CursorMoved, FileDragAndDrop, ReceivedCharacter,
-    WindowBackendScaleFactorChanged, WindowCloseRequested, WindowCreated, WindowFocused,
-    WindowMoved, WindowResized, WindowScaleFactorChanged, Windows,
+    CloseWindow, CreateWindow, CursorEntered, CursorGrabMode, CursorLeft, CursorMoved,
+    FileDragAndDrop, Monitors, ReceivedCharacter, WindowBackendScaleFactorChanged,
+    WindowCloseRequested, WindowClosed, WindowCreated, WindowFocused, WindowMoved, WindowResized,
+    WindowScaleFactorChanged, Windows,
 };
 use winit::{
     dpi::PhysicalPosition,
@@ -43,6 +49,8 @@ impl Plugin for WinitPlugin {
         app.init_resource::<WinitWindows>()
             .set_runner(winit_runner)
             .add_system_to_stage(CoreStage::PostUpdate, change_window.exclusive_system());
+        #[cfg(not(target_arch = "wasm32"))]
+        app.insert_resource(bevy_window::Clipboard::new(SystemClipboard));
     }
 }
 
@@ -58,23 +66,26 @@ fn change_window(world: &mut World) {
                 bevy_window::WindowCommand::SetWindowMode {
                     mode,
                     resolution: (width, height),
+                    monitor,
                 } => {
                     let window = winit_windows.get_window(id).unwrap();
-                    match mode {
-                        bevy_window::WindowMode::BorderlessFullscreen => {
-                            window.set_fullscreen(Some(winit::window::Fullscreen::Borderless(None)))
+                    let current_monitor = window.current_monitor();
+                    let target_monitor = select_monitor(
+                        monitor,
+                        current_monitor.clone(),
+                        window.primary_monitor(),
+                        window.available_monitors(),
+                    );
+                    if mode == bevy_window::WindowMode::Windowed {
+                        window.set_fullscreen(None);
+                        // move the window to the top left corner of the other monitor
+                        if let Some(target_monitor) = target_monitor {
+                            if Some(&target_monitor) != current_monitor.as_ref() {
+                                window.set_outer_position(target_monitor.position());
+                            }
                         }
-                        bevy_window::WindowMode::Fullscreen { use_size } => window.set_fullscreen(
-                            Some(winit::window::Fullscreen::Exclusive(match use_size {
-                                true => get_fitting_videomode(
-                                    &window.current_monitor().unwrap(),
-                                    width,
-                                    height,
-                                ),
-                                false => get_best_videomode(&window.current_monitor().unwrap()),
-                            })),
-                        ),
-                        bevy_window::WindowMode::Windowed => window.set_fullscreen(None),
+                    } else {
+                        window.set_fullscreen(get_fullscreen(mode, target_monitor, width, height));
                     }
                 }
                 bevy_window::WindowCommand::SetTitle { title } => {
@@ -106,11 +117,26 @@ fn change_window(world: &mut World) {
                     let window = winit_windows.get_window(id).unwrap();
                     window.set_decorations(decorations);
                 }
-                bevy_window::WindowCommand::SetCursorLockMode { locked } => {
+                bevy_window::WindowCommand::SetAlwaysOnTop { always_on_top } => {
+                    let window = winit_windows.get_window(id).unwrap();
+                    window.set_always_on_top(always_on_top);
+                }
+                bevy_window::WindowCommand::SetClickThrough { click_through } => {
+                    let window = winit_windows.get_window(id).unwrap();
+                    set_click_through(window, click_through);
+                }
+                bevy_window::WindowCommand::SetCursorGrabMode { grab_mode } => {
                     let window = winit_windows.get_window(id).unwrap();
                     window
-                        .set_cursor_grab(locked)
+                        .set_cursor_grab(grab_mode != CursorGrabMode::None)
                         .unwrap_or_else(|e| error!("Unable to un/grab cursor: {}", e));
+                    if grab_mode == CursorGrabMode::Locked {
+                        center_cursor(window);
+                    }
+                }
+                bevy_window::WindowCommand::SetCursorIcon { icon } => {
+                    let window = winit_windows.get_window(id).unwrap();
+                    window.set_cursor_icon(converters::convert_cursor_icon(icon));
                 }
                 bevy_window::WindowCommand::SetCursorVisibility { visible } => {
                     let window = winit_windows.get_window(id).unwrap();
@@ -158,11 +184,29 @@ fn change_window(world: &mut World) {
                         window.set_max_inner_size(Some(max_inner_size));
                     }
                 }
+                bevy_window::WindowCommand::SetIcon { icon } => {
+                    let window = winit_windows.get_window(id).unwrap();
+                    set_window_icon(window, icon.as_ref());
+                }
+                bevy_window::WindowCommand::RequestUserAttention { request_type } => {
+                    let window = winit_windows.get_window(id).unwrap();
+                    window.request_user_attention(
+                        request_type.map(converters::convert_user_attention_type),
+                    );
+                }
             }
         }
     }
 }
 
+/// Moves the cursor to the center of the window, to keep a locked cursor in place.
+fn center_cursor(window: &winit::window::Window) {
+    let size = window.inner_size();
+    let center = PhysicalPosition::new(size.width / 2, size.height / 2);
+    // not every platform supports moving the cursor, the cursor then stays confined
+    let _ = window.set_cursor_position(center);
+}
+
 fn run<F>(event_loop: EventLoop<()>, event_handler: F) -> !
 where
     F: 'static + FnMut(Event<'_, ()>, &EventLoopWindowTarget<()>, &mut ControlFlow),
@@ -223,6 +267,7 @@ pub fn winit_runner_any_thread(app: App) {
 
 pub fn winit_runner_with(mut app: App, mut event_loop: EventLoop<()>) {
     let mut create_window_event_reader = ManualEventReader::<CreateWindow>::default();
+    let mut close_window_event_reader = ManualEventReader::<CloseWindow>::default();
     let mut app_exit_event_reader = ManualEventReader::<AppExit>::default();
     app.world.insert_non_send(event_loop.create_proxy());
 
@@ -312,12 +357,23 @@ pub fn winit_runner_with(mut app: App, mut event_loop: EventLoop<()>) {
                         let y_position = inner_size.height - position.y;
 
                         let position = Vec2::new(position.x, y_position);
-                        window.update_cursor_position_from_backend(Some(position));
 
-                        cursor_moved_events.send(CursorMoved {
-                            id: window_id,
-                            position,
-                        });
+                        // winit can only confine the cursor, a locked cursor is moved back to the
+                        // center of the window, its movements are only sent as `MouseMotion`
+                        if window.cursor_grab_mode() == CursorGrabMode::Locked {
+                            let center = Vec2::new(inner_size.width, inner_size.height) / 2.0;
+                            window.update_cursor_position_from_backend(Some(center));
+                            if position.distance_squared(center) > 1.0 {
+                                center_cursor(winit_window);
+                            }
+                        } else {
+                            window.update_cursor_position_from_backend(Some(position));
+
+                            cursor_moved_events.send(CursorMoved {
+                                id: window_id,
+                                position,
+                            });
+                        }
                     }
                     WindowEvent::CursorEntered { .. } => {
                         let mut cursor_entered_events =
@@ -373,6 +429,20 @@ pub fn winit_runner_with(mut app: App, mut event_loop: EventLoop<()>) {
                             location.y = window_height - location.y;
                         }
                         touch_input_events.send(converters::convert_touch_input(touch, location));
+
+                        if let Some(pen_input) = converters::convert_pen_input(touch, location) {
+                            let mut pen_input_events =
+                                world.get_resource_mut::<Events<PenInput>>().unwrap();
+                            pen_input_events.send(pen_input);
+                            // winit doesn't report pens hovering the screen, a lifted pen is out
+                            // of range
+                            if pen_input.phase == PenPhase::Ended {
+                                pen_input_events.send(PenInput {
+                                    phase: PenPhase::Left,
+                                    ..pen_input
+                                });
+                            }
+                        }
                     }
                     WindowEvent::ReceivedCharacter(c) => {
                         let mut char_input_events = world
@@ -452,6 +522,7 @@ pub fn winit_runner_with(mut app: App, mut event_loop: EventLoop<()>) {
                         events.send(FileDragAndDrop::DroppedFile {
                             id: window_id,
                             path_buf,
+                            cursor_position: window.cursor_position(),
                         });
                     }
                     WindowEvent::HoveredFile(path_buf) => {
@@ -460,6 +531,7 @@ pub fn winit_runner_with(mut app: App, mut event_loop: EventLoop<()>) {
                         events.send(FileDragAndDrop::HoveredFile {
                             id: window_id,
                             path_buf,
+                            cursor_position: window.cursor_position(),
                         });
                     }
                     WindowEvent::HoveredFileCancelled => {
@@ -496,11 +568,13 @@ pub fn winit_runner_with(mut app: App, mut event_loop: EventLoop<()>) {
                 active = true;
             }
             event::Event::MainEventsCleared => {
+                update_monitors(&mut app.world, event_loop);
                 handle_create_window_events(
                     &mut app.world,
                     event_loop,
                     &mut create_window_event_reader,
                 );
+                handle_close_window_events(&mut app.world, &mut close_window_event_reader);
                 if active {
                     app.update();
                 }
@@ -515,6 +589,23 @@ pub fn winit_runner_with(mut app: App, mut event_loop: EventLoop<()>) {
     }
 }
 
+fn update_monitors(world: &mut World, event_loop: &EventLoopWindowTarget<()>) {
+    let mut monitors = match world.get_resource_mut::<Monitors>() {
+        Some(monitors) => monitors,
+        None => return,
+    };
+    if !monitors.is_refresh_requested() {
+        return;
+    }
+    let primary_monitor = event_loop.primary_monitor();
+    monitors.update_from_backend(
+        event_loop
+            .available_monitors()
+            .map(|monitor| converters::convert_monitor(&monitor, primary_monitor.as_ref()))
+            .collect(),
+    );
+}
+
 fn handle_create_window_events(
     world: &mut World,
     event_loop: &EventLoopWindowTarget<()>,
@@ -537,3 +628,23 @@ fn handle_create_window_events(
         });
     }
 }
+
+fn handle_close_window_events(
+    world: &mut World,
+    close_window_event_reader: &mut ManualEventReader<CloseWindow>,
+) {
+    let world = world.cell();
+    let mut winit_windows = world.get_resource_mut::<WinitWindows>().unwrap();
+    let mut windows = world.get_resource_mut::<Windows>().unwrap();
+    let close_window_events = world.get_resource::<Events<CloseWindow>>().unwrap();
+    let mut window_closed_events = world.get_resource_mut::<Events<WindowClosed>>().unwrap();
+    for close_window_event in close_window_event_reader.iter(&close_window_events) {
+        // dropping the winit window closes it
+        if winit_windows.remove_window(close_window_event.id).is_some() {
+            windows.remove(close_window_event.id);
+            window_closed_events.send(WindowClosed {
+                id: close_window_event.id,
+            });
+        }
+    }
+}
@@ -1,7 +1,11 @@
 use bevy_math::IVec2;
-use bevy_utils::HashMap;
-use bevy_window::{Window, WindowDescriptor, WindowId, WindowMode};
+use bevy_utils::{tracing::warn, HashMap};
+use bevy_window::{
+    CursorGrabMode, MonitorSelection, VideoMode, Window, WindowDescriptor, WindowIcon, WindowId,
+    WindowMode,
+};
 use winit::dpi::LogicalSize;
+use winit::monitor::MonitorHandle;
 
 #[derive(Debug, Default)]
 pub struct WinitWindows {
@@ -27,20 +31,23 @@ impl WinitWindows {
         let mut winit_window_builder = winit::window::WindowBuilder::new();
 
         winit_window_builder = match window_descriptor.mode {
-            WindowMode::BorderlessFullscreen => winit_window_builder.with_fullscreen(Some(
-                winit::window::Fullscreen::Borderless(event_loop.primary_monitor()),
-            )),
-            WindowMode::Fullscreen { use_size } => winit_window_builder.with_fullscreen(Some(
-                winit::window::Fullscreen::Exclusive(match use_size {
-                    true => get_fitting_videomode(
-                        &event_loop.primary_monitor().unwrap(),
-                        window_descriptor.width as u32,
-                        window_descriptor.height as u32,
-                    ),
-                    false => get_best_videomode(&event_loop.primary_monitor().unwrap()),
-                }),
-            )),
-            _ => {
+            WindowMode::BorderlessFullscreen
+            | WindowMode::Fullscreen { .. }
+            | WindowMode::ExclusiveFullscreen { .. } => {
+                let monitor = select_monitor(
+                    window_descriptor.monitor,
+                    None,
+                    event_loop.primary_monitor(),
+                    event_loop.available_monitors(),
+                );
+                winit_window_builder.with_fullscreen(get_fullscreen(
+                    window_descriptor.mode,
+                    monitor,
+                    window_descriptor.width as u32,
+                    window_descriptor.height as u32,
+                ))
+            }
+            WindowMode::Windowed => {
                 let WindowDescriptor {
                     width,
                     height,
@@ -57,7 +64,9 @@ impl WinitWindows {
                 }
             }
             .with_resizable(window_descriptor.resizable)
-            .with_decorations(window_descriptor.decorations),
+            .with_decorations(window_descriptor.decorations)
+            .with_transparent(window_descriptor.transparent)
+            .with_always_on_top(window_descriptor.always_on_top),
         };
 
         let constraints = window_descriptor.resize_constraints.check_constraints();
@@ -104,7 +113,8 @@ impl WinitWindows {
 
         let winit_window = winit_window_builder.build(event_loop).unwrap();
 
-        match winit_window.set_cursor_grab(window_descriptor.cursor_locked) {
+        let cursor_grabbed = window_descriptor.cursor_grab_mode != CursorGrabMode::None;
+        match winit_window.set_cursor_grab(cursor_grabbed) {
             Ok(_) => {}
             Err(winit::error::ExternalError::NotSupported(_)) => {}
             Err(err) => Err(err).unwrap(),
@@ -112,6 +122,14 @@ impl WinitWindows {
 
         winit_window.set_cursor_visible(window_descriptor.cursor_visible);
 
+        if window_descriptor.click_through {
+            set_click_through(&winit_window, true);
+        }
+
+        if let Some(icon) = &window_descriptor.icon {
+            set_window_icon(&winit_window, Some(icon));
+        }
+
         self.window_id_to_winit.insert(window_id, winit_window.id());
         self.winit_to_window_id.insert(winit_window.id(), window_id);
 
@@ -157,6 +175,12 @@ impl WinitWindows {
     pub fn get_window_id(&self, id: winit::window::WindowId) -> Option<WindowId> {
         self.winit_to_window_id.get(&id).cloned()
     }
+
+    pub fn remove_window(&mut self, id: WindowId) -> Option<winit::window::Window> {
+        let winit_id = self.window_id_to_winit.remove(&id)?;
+        self.winit_to_window_id.remove(&winit_id);
+        self.windows.remove(&winit_id)
+    }
 }
 pub fn get_fitting_videomode(
     monitor: &winit::monitor::MonitorHandle,
@@ -188,6 +212,71 @@ pub fn get_fitting_videomode(
     modes.first().unwrap().clone()
 }
 
+/// The video mode of `monitor` matching `video_mode`, or the closest one.
+pub fn get_videomode(monitor: &MonitorHandle, video_mode: VideoMode) -> winit::monitor::VideoMode {
+    monitor
+        .video_modes()
+        .find(|mode| {
+            mode.size().width == video_mode.physical_size.x
+                && mode.size().height == video_mode.physical_size.y
+                && mode.refresh_rate() == video_mode.refresh_rate
+                && mode.bit_depth() == video_mode.bit_depth
+        })
+        .unwrap_or_else(|| {
+            get_fitting_videomode(
+                monitor,
+                video_mode.physical_size.x,
+                video_mode.physical_size.y,
+            )
+        })
+}
+
+/// The monitor chosen by `selection`, falling back to the current then the primary monitor.
+pub(crate) fn select_monitor(
+    selection: MonitorSelection,
+    current_monitor: Option<MonitorHandle>,
+    primary_monitor: Option<MonitorHandle>,
+    mut available_monitors: impl Iterator<Item = MonitorHandle>,
+) -> Option<MonitorHandle> {
+    let monitor = match selection {
+        MonitorSelection::Current => None,
+        MonitorSelection::Primary => primary_monitor.clone(),
+        MonitorSelection::Index(index) => available_monitors.nth(index),
+    };
+    monitor.or(current_monitor).or(primary_monitor)
+}
+
+/// The winit fullscreen mode of `mode` on `monitor`. `width` and `height` are the physical size
+/// of the window, used by [`WindowMode::Fullscreen`] with `use_size`.
+pub(crate) fn get_fullscreen(
+    mode: WindowMode,
+    monitor: Option<MonitorHandle>,
+    width: u32,
+    height: u32,
+) -> Option<winit::window::Fullscreen> {
+    use winit::window::Fullscreen;
+
+    let monitor = match (mode, monitor) {
+        (WindowMode::Windowed, _) => return None,
+        (_, Some(monitor)) => monitor,
+        // exclusive fullscreen needs a monitor, the window is made borderless fullscreen on the
+        // current monitor without one
+        (_, None) => return Some(Fullscreen::Borderless(None)),
+    };
+    Some(match mode {
+        WindowMode::Fullscreen { use_size: true } => {
+            Fullscreen::Exclusive(get_fitting_videomode(&monitor, width, height))
+        }
+        WindowMode::Fullscreen { use_size: false } => {
+            Fullscreen::Exclusive(get_best_videomode(&monitor))
+        }
+        WindowMode::ExclusiveFullscreen { video_mode } => {
+            Fullscreen::Exclusive(get_videomode(&monitor, video_mode))
+        }
+        _ => Fullscreen::Borderless(Some(monitor)),
+    })
+}
+
 pub fn get_best_videomode(monitor: &winit::monitor::MonitorHandle) -> winit::monitor::VideoMode {
     let mut modes = monitor.video_modes().collect::<Vec<_>>();
     modes.sort_by(|a, b| {
@@ -209,3 +298,76 @@ pub fn get_best_videomode(monitor: &winit::monitor::MonitorHandle) -> winit::mon
 unsafe impl Send for WinitWindows {}
 #[cfg(target_arch = "wasm32")]
 unsafe impl Sync for WinitWindows {}
+
+/// Sets the icon of the window, and of its taskbar button on Windows where they are separate.
+pub(crate) fn set_window_icon(window: &winit::window::Window, icon: Option<&WindowIcon>) {
+    let icon = match icon {
+        Some(icon) => {
+            match winit::window::Icon::from_rgba(icon.rgba.clone(), icon.width, icon.height) {
+                Ok(icon) => Some(icon),
+                Err(err) => {
+                    warn!("Failed to set the window icon: {}", err);
+                    return;
+                }
+            }
+        }
+        None => None,
+    };
+
+    #[cfg(target_os = "windows")]
+    {
+        use winit::platform::windows::WindowExtWindows;
+        window.set_taskbar_icon(icon.clone());
+    }
+
+    window.set_window_icon(icon);
+}
+
+/// Lets the mouse go through the window to the windows below it. winit doesn't support it, so it
+/// is done with the native window.
+pub(crate) fn set_click_through(window: &winit::window::Window, click_through: bool) {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use winapi::um::winuser::{
+            GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE,
+            LWA_ALPHA, WS_EX_LAYERED, WS_EX_TRANSPARENT,
+        };
+        use winit::platform::windows::WindowExtWindows;
+
+        let hwnd = window.hwnd() as winapi::shared::windef::HWND;
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as u32;
+        // a transparent window is only skipped by hit testing if it is layered, which hides it
+        // until the opacity of the layer is set
+        let style = if click_through {
+            style | WS_EX_LAYERED | WS_EX_TRANSPARENT
+        } else {
+            style & !WS_EX_TRANSPARENT
+        };
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style as _);
+        if click_through {
+            SetLayeredWindowAttributes(hwnd, 0, 255, LWA_ALPHA);
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe {
+        use objc::{msg_send, runtime::Object, sel, sel_impl};
+        use winit::platform::macos::WindowExtMacOS;
+
+        let ns_window = window.ns_window() as *mut Object;
+        let ignores_mouse_events = if click_through {
+            objc::runtime::YES
+        } else {
+            objc::runtime::NO
+        };
+        let () = msg_send![ns_window, setIgnoresMouseEvents: ignores_mouse_events];
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let _ = window;
+        if click_through {
+            warn!("Click-through windows aren't supported on this platform");
+        }
+    }
+}
@@ -19,6 +19,7 @@ fn main() {
         .insert_resource(SpriteSettings {
             // NOTE: this is an experimental feature that doesn't work in all cases
             frustum_culling_enabled: true,
+            ..Default::default()
         })
         .add_plugins(DefaultPlugins)
         .add_startup_system(setup)
@@ -1,4 +1,4 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, sprite::Anchor};
 
 fn main() {
     App::new()
@@ -21,6 +21,8 @@ fn setup(
             flip_x: true,
             // And don't flip it upside-down ( the default )
             flip_y: false,
+            // Place the bottom of the logo at the origin
+            anchor: Anchor::BottomCenter,
             ..Default::default()
         },
         ..Default::default()
@@ -1,27 +1,12 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::Duration};
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .add_startup_system(setup)
-        .add_system(animate_sprite_system)
         .run();
 }
 
-fn animate_sprite_system(
-    time: Res<Time>,
-    texture_atlases: Res<Assets<TextureAtlas>>,
-    mut query: Query<(&mut Timer, &mut TextureAtlasSprite, &Handle<TextureAtlas>)>,
-) {
-    for (mut timer, mut sprite, texture_atlas_handle) in query.iter_mut() {
-        timer.tick(time.delta());
-        if timer.finished() {
-            let texture_atlas = texture_atlases.get(texture_atlas_handle).unwrap();
-            sprite.index = ((sprite.index as usize + 1) % texture_atlas.textures.len()) as u32;
-        }
-    }
-}
-
 fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -37,5 +22,9 @@ fn setup(
             transform: Transform::from_scale(Vec3::splat(6.0)),
             ..Default::default()
         })
-        .insert(Timer::from_seconds(0.1, true));
+        // the SpriteAnimation sets the index of the TextureAtlasSprite to the current frame
+        .insert(SpriteAnimation::new(
+            "run",
+            SpriteAnimationClip::from_indices(0..7, Duration::from_secs_f32(0.1)),
+        ));
 }
@@ -47,10 +47,50 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     // Demonstrate changing scale
     commands
         .spawn_bundle(Text2dBundle {
-            text: Text::with_section("scale", text_style, text_alignment),
+            text: Text::with_section("scale", text_style.clone(), text_alignment),
             ..Default::default()
         })
         .insert(AnimateScale);
+    // Demonstrate text drawn from distance fields, with an outline and a glow
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                "distance field",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf#sdf"),
+                    ..text_style.clone()
+                },
+                text_alignment,
+            ),
+            transform: Transform::from_xyz(0.0, 200.0, 0.0),
+            ..Default::default()
+        })
+        .insert(TextEffects {
+            outline_width: 3.0,
+            outline_color: Color::BLACK,
+            glow_width: 6.0,
+            glow_color: Color::rgba(1.0, 0.5, 0.0, 0.8),
+        })
+        .insert(AnimateRotation);
+    // Demonstrate text wrapping in a box
+    commands.spawn_bundle(Text2dBundle {
+        text: Text::with_section(
+            "this text wraps in the box",
+            TextStyle {
+                font_size: 30.0,
+                ..text_style
+            },
+            TextAlignment {
+                vertical: VerticalAlign::Top,
+                horizontal: HorizontalAlign::Left,
+            },
+        ),
+        text_2d_bounds: Text2dBounds {
+            size: Size::new(200.0, 200.0),
+        },
+        transform: Transform::from_xyz(-100.0, -150.0, 0.0),
+        ..Default::default()
+    });
 }
 
 fn animate_translation(
@@ -76,8 +116,8 @@ fn animate_scale(
     time: Res<Time>,
     mut query: Query<&mut Transform, (With<Text>, With<AnimateScale>)>,
 ) {
-    // Consider changing font-size instead of scaling the transform. Scaling a Text2D will scale the
-    // rendered quad, resulting in a pixellated look.
+    // The glyphs are rasterized again at the size they are drawn at as the scale changes, so the
+    // text stays sharp.
     for mut transform in query.iter_mut() {
         transform.translation = Vec3::new(400.0, 0.0, 0.0);
         transform.scale = Vec3::splat((time.seconds_since_startup().sin() as f32 + 1.1) * 2.0);
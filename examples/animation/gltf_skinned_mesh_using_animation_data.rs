@@ -5,6 +5,7 @@ use bevy::{
     },
     pbr::AmbientLight,
     prelude::*,
+    tasks::ComputeTaskPool,
     utils::HashMap,
 };
 
@@ -18,17 +19,25 @@ fn main() {
             ..Default::default()
         })
         .add_startup_system(setup.system())
+        // The controllers inserted by activate_animations are applied at this point, so the
+        // systems after it can use them in the same frame.
+        .add_flush_point_to_stage(CoreStage::Update, "animations_activated")
         // This general-purpose system adds AnimationControllers to entities that request
         // them for a given Gltf.
-        .add_system(activate_animations.system())
+        .add_system(activate_animations.system().before("animations_activated"))
         // This example system updates the GltfAnimationController to set animation times
         // and weights. The GltfAnimationController can be used with any Gltf animation
         // data to drive and blend multiple animations.
-        .add_system(update_animation_controllers.system())
+        .add_system(
+            update_animation_controllers
+                .system()
+                .label("update_controllers")
+                .after("animations_activated"),
+        )
         // This general-purpose system takes the times and weights specified by
         // GltfAnimationControllers and updates entities that automatically receive
         // GltfAnimTargetInfo from the Gltf animation loader when they are spawned.
-        .add_system(update_gltf_animations.system())
+        .add_system(update_gltf_animations.system().after("update_controllers"))
         .run();
 }
 
@@ -144,7 +153,11 @@ enum GltfAnimOutputSample {
     MorphTargetWeights(f32), // TODO: I think this should actually be Vec<f32>
 }
 
+/// The number of animation targets evaluated by each task of update_gltf_animations.
+const ANIMATION_TARGET_BATCH_SIZE: usize = 16;
+
 fn update_gltf_animations(
+    pool: Res<ComputeTaskPool>,
     gltf_assets: Res<Assets<Gltf>>,
     anim_assets: Res<Assets<GltfAnimation>>,
     query_evaluators: Query<&GltfAnimationController>,
@@ -160,116 +173,123 @@ fn update_gltf_animations(
         }
     }
 
-    for (target_info, mut xfm) in query_targets.iter_mut() {
-        let gltf_handle = &target_info.gltf;
-        let gltf = gltf_assets.get(gltf_handle);
-        if gltf.is_none() {
-            continue;
-        }
-        let gltf = gltf.unwrap();
+    // Every target (joint) samples and blends its channels independently of the others, so the
+    // targets are evaluated in parallel.
+    let eval_data = &eval_data;
+    query_targets.par_for_each_mut(
+        &pool,
+        ANIMATION_TARGET_BATCH_SIZE,
+        |(target_info, mut xfm)| {
+            let gltf_handle = &target_info.gltf;
+            let gltf = gltf_assets.get(gltf_handle);
+            if gltf.is_none() {
+                return;
+            }
+            let gltf = gltf.unwrap();
 
-        let anim_idcs = &target_info.animation_indices;
-        let chan_idcs = &target_info.channel_indices;
+            let anim_idcs = &target_info.animation_indices;
+            let chan_idcs = &target_info.channel_indices;
 
-        let anim_handles = &gltf.animations;
+            let anim_handles = &gltf.animations;
 
-        let mut accum_pos = Vec::<(Vec3, f32)>::with_capacity(anim_handles.len());
-        let mut accum_rot = Vec::<(Quat, f32)>::with_capacity(anim_handles.len());
-        let mut accum_scale = Vec::<(Vec3, f32)>::with_capacity(anim_handles.len());
+            let mut accum_pos = Vec::<(Vec3, f32)>::with_capacity(anim_handles.len());
+            let mut accum_rot = Vec::<(Quat, f32)>::with_capacity(anim_handles.len());
+            let mut accum_scale = Vec::<(Vec3, f32)>::with_capacity(anim_handles.len());
 
-        // Get each channel, its time, and its blend weight.
-        let node_animations: Vec<_> = anim_idcs
-            .iter()
-            .zip(chan_idcs)
-            .filter_map(|(anim_idx, chan_idx)| {
-                let anim_handle = &anim_handles[*anim_idx];
-                if let Some(anim) = &anim_assets.get(anim_handle.clone()) {
-                    let channel = &anim.channels[*chan_idx];
+            // Get each channel, its time, and its blend weight.
+            let node_animations: Vec<_> = anim_idcs
+                .iter()
+                .zip(chan_idcs)
+                .filter_map(|(anim_idx, chan_idx)| {
+                    let anim_handle = &anim_handles[*anim_idx];
+                    if let Some(anim) = &anim_assets.get(anim_handle.clone()) {
+                        let channel = &anim.channels[*chan_idx];
 
-                    let input_vals = eval_data.get(&anim_handle.clone());
-                    if input_vals.is_none() {
-                        None
+                        let input_vals = eval_data.get(&anim_handle.clone());
+                        if input_vals.is_none() {
+                            None
+                        } else {
+                            let (input_time, input_weight) = input_vals.unwrap();
+                            Some((channel, input_time, input_weight))
+                        }
                     } else {
-                        let (input_time, input_weight) = input_vals.unwrap();
-                        Some((channel, input_time, input_weight))
+                        None
                     }
-                } else {
-                    None
+                })
+                .collect();
+
+            // Accumulate weighted animated properties.
+            for (channel, input_time, input_weight) in node_animations {
+                if *input_weight == 0. {
+                    continue;
                 }
-            })
-            .collect();
 
-        // Accumulate weighted animated properties.
-        for (channel, input_time, input_weight) in node_animations {
-            if *input_weight == 0. {
-                continue;
-            }
+                let output_sample = sample_animation_value(&channel.sampler, *input_time);
 
-            let output_sample = sample_animation_value(&channel.sampler, *input_time);
+                match (&channel.target.path, output_sample) {
+                    (GltfAnimTargetProperty::Position, GltfAnimOutputSample::Position(pos)) => {
+                        accum_pos.push((pos, *input_weight))
+                    }
+                    (GltfAnimTargetProperty::Rotation, GltfAnimOutputSample::Rotation(rot)) => {
+                        accum_rot.push((rot, *input_weight))
+                    }
+                    (GltfAnimTargetProperty::Scale, GltfAnimOutputSample::Scale(scale)) => {
+                        accum_scale.push((scale, *input_weight))
+                    }
+                    (
+                        GltfAnimTargetProperty::MorphTargetWeights,
+                        GltfAnimOutputSample::MorphTargetWeights(_weights),
+                    ) => todo!("Morph target weights NYI."),
+                    (_, _) => panic!("Mismatch between target property and sampler output type."),
+                }
+            }
 
-            match (&channel.target.path, output_sample) {
-                (GltfAnimTargetProperty::Position, GltfAnimOutputSample::Position(pos)) => {
-                    accum_pos.push((pos, *input_weight))
+            // Compute blends and assign transform values.
+            let translation = {
+                if accum_pos.len() > 0 {
+                    let (pos_sum, weight_sum) = accum_pos
+                        .iter()
+                        .fold((Vec3::ZERO, 0.), |(acc_pos, acc_w), (pos, w)| {
+                            (acc_pos + (*pos * *w), acc_w + w)
+                        });
+                    Some(pos_sum / weight_sum)
+                } else {
+                    None
                 }
-                (GltfAnimTargetProperty::Rotation, GltfAnimOutputSample::Rotation(rot)) => {
-                    accum_rot.push((rot, *input_weight))
+            };
+            let rotation = {
+                if accum_rot.len() > 0 {
+                    Some(accum_rot.iter().fold(Quat::IDENTITY, |acc_rot, (rot, w)| {
+                        Quat::lerp(Quat::IDENTITY, *rot, *w) * acc_rot
+                    }))
+                } else {
+                    None
                 }
-                (GltfAnimTargetProperty::Scale, GltfAnimOutputSample::Scale(scale)) => {
-                    accum_scale.push((scale, *input_weight))
+            };
+            let scale = {
+                if accum_scale.len() > 0 {
+                    let (scale_sum, weight_sum) = accum_scale
+                        .iter()
+                        .fold((Vec3::ZERO, 0.), |(acc_scale, acc_w), (scale, w)| {
+                            (acc_scale + (*scale * *w), acc_w + w)
+                        });
+                    Some(scale_sum / weight_sum)
+                } else {
+                    None
                 }
-                (
-                    GltfAnimTargetProperty::MorphTargetWeights,
-                    GltfAnimOutputSample::MorphTargetWeights(_weights),
-                ) => todo!("Morph target weights NYI."),
-                (_, _) => panic!("Mismatch between target property and sampler output type."),
-            }
-        }
+            };
 
-        // Compute blends and assign transform values.
-        let translation = {
-            if accum_pos.len() > 0 {
-                let (pos_sum, weight_sum) = accum_pos
-                    .iter()
-                    .fold((Vec3::ZERO, 0.), |(acc_pos, acc_w), (pos, w)| {
-                        (acc_pos + (*pos * *w), acc_w + w)
-                    });
-                Some(pos_sum / weight_sum)
-            } else {
-                None
+            if let Some(t) = translation {
+                xfm.translation = t;
             }
-        };
-        let rotation = {
-            if accum_rot.len() > 0 {
-                Some(accum_rot.iter().fold(Quat::IDENTITY, |acc_rot, (rot, w)| {
-                    Quat::lerp(Quat::IDENTITY, *rot, *w) * acc_rot
-                }))
-            } else {
-                None
+            if let Some(r) = rotation {
+                xfm.rotation = r;
             }
-        };
-        let scale = {
-            if accum_scale.len() > 0 {
-                let (scale_sum, weight_sum) = accum_scale
-                    .iter()
-                    .fold((Vec3::ZERO, 0.), |(acc_scale, acc_w), (scale, w)| {
-                        (acc_scale + (*scale * *w), acc_w + w)
-                    });
-                Some(scale_sum / weight_sum)
-            } else {
-                None
+            if let Some(s) = scale {
+                xfm.scale = s;
             }
-        };
-
-        if let Some(t) = translation {
-            xfm.translation = t;
-        }
-        if let Some(r) = rotation {
-            xfm.rotation = r;
-        }
-        if let Some(s) = scale {
-            xfm.scale = s;
-        }
-    }
+        },
+    );
 }
 
 fn sample_animation_value(sampler: &GltfAnimSampler, time: f32) -> GltfAnimOutputSample {
@@ -9,6 +9,15 @@ fn main() {
 
 fn file_drag_and_drop_system(mut events: EventReader<FileDragAndDrop>) {
     for event in events.iter() {
-        info!("{:?}", event);
+        if let FileDragAndDrop::DroppedFile {
+            path_buf,
+            cursor_position,
+            ..
+        } = event
+        {
+            info!("Dropped {:?} at {:?}", path_buf, cursor_position);
+        } else {
+            info!("{:?}", event);
+        }
     }
 }
@@ -0,0 +1,88 @@
+use bevy::{
+    app::{AppExit, ScheduleRunnerPlugin, ScheduleRunnerSettings},
+    prelude::*,
+    render::{
+        camera::Camera,
+        screenshot::{ScreenshotCaptured, Screenshots},
+    },
+    utils::Duration,
+    window::HeadlessWindowPlugin,
+    winit::WinitPlugin,
+};
+
+/// This example renders a scene without opening a window, saves a screenshot of it and exits.
+/// The [HeadlessWindowPlugin] replaces the [WinitPlugin], so the window is rendered offscreen.
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .insert_resource(WindowDescriptor {
+            width: 640.,
+            height: 360.,
+            ..Default::default()
+        })
+        .insert_resource(ScheduleRunnerSettings::run_loop(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .add_plugins_with(DefaultPlugins, |group| group.disable::<WinitPlugin>())
+        .add_plugin(HeadlessWindowPlugin)
+        .add_plugin(ScheduleRunnerPlugin)
+        .add_startup_system(setup)
+        .add_system(take_screenshot)
+        .add_system(exit_after_screenshot)
+        .run();
+}
+
+/// set up a simple 3D scene
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // plane
+    commands.spawn_bundle(PbrBundle {
+        mesh: meshes.add(Mesh::from(shape::Plane { size: 5.0 })),
+        material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
+        ..Default::default()
+    });
+    // cube
+    commands.spawn_bundle(PbrBundle {
+        mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
+        material: materials.add(Color::rgb(0.8, 0.7, 0.6).into()),
+        transform: Transform::from_xyz(0.0, 0.5, 0.0),
+        ..Default::default()
+    });
+    // light
+    commands.spawn_bundle(PointLightBundle {
+        transform: Transform::from_xyz(4.0, 8.0, 4.0),
+        ..Default::default()
+    });
+    // camera
+    commands.spawn_bundle(PerspectiveCameraBundle {
+        transform: Transform::from_xyz(-2.0, 2.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..Default::default()
+    });
+}
+
+/// requests a screenshot once the scene had a few frames to get uploaded to the GPU
+fn take_screenshot(
+    mut frame: Local<u32>,
+    mut screenshots: ResMut<Screenshots>,
+    cameras: Query<&Camera>,
+) {
+    *frame += 1;
+    if *frame != 3 {
+        return;
+    }
+    for camera in cameras.iter() {
+        screenshots.save_to_file(camera, "headless_rendering.png");
+    }
+}
+
+fn exit_after_screenshot(
+    mut screenshot_captured_events: EventReader<ScreenshotCaptured>,
+    mut app_exit_events: EventWriter<AppExit>,
+) {
+    if screenshot_captured_events.iter().next().is_some() {
+        app_exit_events.send(AppExit);
+    }
+}
@@ -3,16 +3,15 @@
 use bevy::prelude::*;
 
 fn main() {
-    // Information regarding removed `Component`s is discarded at the end of each frame, so you need
-    // to react to the removal before the frame is over.
+    // Information regarding removed `Component`s is kept for two frames, so a system that runs
+    // every frame sees every removal, in whichever stage it runs.
     //
-    // Also, `Components` are removed via a `Command`. `Command`s are applied after a stage has
-    // finished executing. So you need to react to the removal at some stage after the
-    // `Component` is removed.
+    // `Components` are removed via a `Command`. `Command`s are applied after a stage has finished
+    // executing, so reacting to the removal in a later stage sees it in the same frame, while
+    // reacting in an earlier stage sees it in the next frame.
     //
-    // With these constraints in mind we make sure to place the system that removes a `Component` on
-    // the `CoreStage::Update' stage, and the system that reacts on the removal on the
-    // `CoreStage::PostUpdate` stage.
+    // Here the system that removes a `Component` runs in the `CoreStage::Update' stage, and the
+    // system that reacts on the removal in the `CoreStage::PostUpdate` stage.
     App::new()
         .add_plugins(DefaultPlugins)
         .add_startup_system(setup)
@@ -57,7 +56,7 @@ fn remove_component(
 
 fn react_on_removal(
     mut materials: ResMut<Assets<ColorMaterial>>,
-    removed: RemovedComponents<MyComponent>,
+    mut removed: RemovedComponents<MyComponent>,
     query: Query<(Entity, &Handle<ColorMaterial>)>,
 ) {
     // Note: usually this isn't how you would handle a `Query`. In this example it makes things
@@ -65,7 +64,7 @@ fn react_on_removal(
     let (query_entity, material) = query.iter().next().unwrap();
 
     // `RemovedComponents<T>::iter()` returns an interator with the `Entity`s that had their
-    // `Component` `T` (in this case `MyComponent`) removed at some point earlier during the frame.
+    // `Component` `T` (in this case `MyComponent`) removed since this system last ran.
     for entity in removed.iter() {
         // We compare the `Entity` that had its `MyComponent` `Component` removed with the `Entity`
         // in the current `Query`. If they match all red is removed from the material.
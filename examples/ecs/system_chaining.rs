@@ -1,10 +1,12 @@
 use anyhow::Result;
-use bevy::prelude::*;
+use bevy::{ecs::system::adapter, prelude::*};
 
 fn main() {
     App::new()
         .insert_resource(Message("42".to_string()))
-        .add_system(parse_message_system.chain(handler_system))
+        .add_system(parse_message_system.pipe(handler_system))
+        // the adapters handle errors in common ways, here by logging them
+        .add_system(check_message_system.pipe(adapter::error))
         .run();
 }
 
@@ -24,3 +26,10 @@ fn handler_system(In(result): In<Result<usize>>) {
         Err(err) => println!("encountered an error: {:?}", err),
     }
 }
+
+// This system doesn't produce a value, only an error when the message is empty. Piped into
+// `adapter::error`, the error is logged instead of being handled by hand.
+fn check_message_system(message: Res<Message>) -> Result<()> {
+    anyhow::ensure!(!message.0.is_empty(), "the message is empty");
+    Ok(())
+}
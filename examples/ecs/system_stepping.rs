@@ -0,0 +1,59 @@
+use bevy::{
+    ecs::schedule::Stepping,
+    input::{system::stepping_keyboard_system, InputSystem},
+    prelude::*,
+};
+
+/// This example shows how to pause the systems of a stage and run them one at a time, which helps
+/// finding system ordering bugs. Each system is logged as it runs.
+///
+/// Press "F8" to toggle stepping, "F9" to run the next system and "F10" to run the rest of the
+/// stage.
+fn main() {
+    let mut stepping = Stepping::default();
+    stepping.add_stage(CoreStage::Update).enable();
+
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .insert_resource(stepping)
+        // the stepping controls must run in a stage that isn't stepped
+        .add_system_to_stage(
+            CoreStage::PreUpdate,
+            stepping_keyboard_system.after(InputSystem),
+        )
+        .add_startup_system(setup)
+        .add_system(spawn_velocity.label("spawn"))
+        .add_system(apply_velocity.label("apply").after("spawn"))
+        .add_system(print_positions.after("apply"))
+        .run();
+}
+
+#[derive(Component)]
+struct Position(Vec2);
+
+#[derive(Component)]
+struct Velocity(Vec2);
+
+fn setup(mut commands: Commands) {
+    commands.spawn().insert(Position(Vec2::ZERO));
+}
+
+fn spawn_velocity(mut commands: Commands, query: Query<Entity, Without<Velocity>>) {
+    for entity in query.iter() {
+        commands
+            .entity(entity)
+            .insert(Velocity(Vec2::new(1.0, 0.5)));
+    }
+}
+
+fn apply_velocity(mut query: Query<(&mut Position, &Velocity)>) {
+    for (mut position, velocity) in query.iter_mut() {
+        position.0 += velocity.0;
+    }
+}
+
+fn print_positions(query: Query<&Position>) {
+    for position in query.iter() {
+        info!("position: {}", position.0);
+    }
+}
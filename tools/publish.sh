@@ -25,6 +25,7 @@ crates=(
     bevy_gltf
     bevy_scene
     bevy_sprite
+    bevy_tilemap
     bevy_text
     bevy_ui
     bevy_winit